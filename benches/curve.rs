@@ -1,7 +1,7 @@
 extern crate num_bigint;
 use crate::num_bigint::BigInt;
 
-use criterion::{criterion_group, criterion_main, Benchmark, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 
 use redox_ecc::ellipticcurve::EllipticCurve;
 use redox_ecc::instances::{GetCurve, P256, P384, P521};
@@ -12,12 +12,12 @@ fn arith(c: &mut Criterion) {
         let mut g0 = ec.get_generator();
         let mut g1 = g0.clone();
         let k = ec.new_scalar(BigInt::from(-1));
-        c.bench(
-            format!("{}/ec", id).as_str(),
-            Benchmark::new("add", move |b| b.iter(|| g0 = &g0 + &g0))
-                .with_function("mul", move |b| b.iter(|| g1 = &k * &g1))
-                .sample_size(10),
-        );
+
+        let mut group = c.benchmark_group(format!("{}/ec", id));
+        group.sample_size(10);
+        group.bench_function("add", |b| b.iter(|| g0 = &g0 + &g0));
+        group.bench_function("mul", |b| b.iter(|| g1 = &k * &g1));
+        group.finish();
     }
 }
 
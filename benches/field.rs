@@ -1,4 +1,4 @@
-use criterion::{criterion_group, criterion_main, Benchmark, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 
 use redox_ecc::ellipticcurve::EllipticCurve;
 use redox_ecc::instances::{GetCurve, P256, P384, P521};
@@ -14,13 +14,12 @@ fn arith(c: &mut Criterion) {
         let y0 = f.from(15i64);
         let y1 = f.from(15i64);
 
-        c.bench(
-            format!("{}/fp", id).as_str(),
-            Benchmark::new("add", move |b| b.iter(|| x0 = &x0 + &y0))
-                .with_function("mul", move |b| b.iter(|| x1 = &x1 * &y1))
-                .with_function("inv", move |b| b.iter(|| x2 = 1u32 / &x2))
-                .sample_size(10),
-        );
+        let mut group = c.benchmark_group(format!("{}/fp", id));
+        group.sample_size(10);
+        group.bench_function("add", |b| b.iter(|| x0 = &x0 + &y0));
+        group.bench_function("mul", |b| b.iter(|| x1 = &x1 * &y1));
+        group.bench_function("inv", |b| b.iter(|| x2 = 1u32 / &x2));
+        group.finish();
     }
 }
 
@@ -0,0 +1,215 @@
+//! ecc-tool performs operations on the curves of the crate from the command
+//! line, reading and writing points, scalars, and signatures in hex, which
+//! helps to compare the crate with other implementations.
+//!
+//! ```text
+//!  cargo run --example ecc-tool -- curves
+//!  cargo run --example ecc-tool -- mul P256 03
+//! ```
+
+use num_bigint::{BigInt, Sign};
+use rand_core::OsRng;
+use sha2::{Sha256, Sha384, Sha512};
+
+use std::error::Error;
+
+use redox_ecc::ecdsa;
+use redox_ecc::eddsa::{self, Algorithm};
+use redox_ecc::ellipticcurve::{Decode, EllipticCurve, Encode, PointFormat};
+use redox_ecc::field::Field;
+use redox_ecc::instances::{
+    EdCurveID, GetCurve, MtCurveID, WeCurveID, BLS12381G1, BN254, CURVE25519, CURVE448,
+    EDWARDS25519, EDWARDS448, JUBJUB, P256, P384, P521, SECP256K1, SM2, W25519,
+};
+use redox_ecc::keypair::KeyPair;
+
+const USAGE: &str = "usage: ecc-tool <command> [<args>]
+
+commands:
+  curves                                  lists the curves
+  keygen <curve>                          generates a secret scalar and its public point
+  decode <curve> <point>                  decodes a point and encodes it in every format
+  mul <curve> <scalar> [<point>]          multiplies a point, by default the generator
+  add <curve> <point> <point>             adds two points
+  sign <curve> <secret> <msg>             signs with ECDSA or, on edwards25519 and
+                                          edwards448, with EdDSA from a seed
+  verify <curve> <public> <msg> <sig>     verifies a signature
+
+Arguments other than the curve are in hex; scalars are big-endian.";
+
+#[derive(Clone, Copy)]
+enum Id {
+    Weierstrass(WeCurveID),
+    Edwards(EdCurveID),
+    Montgomery(MtCurveID),
+}
+
+const CURVES: [Id; 13] = [
+    Id::Weierstrass(P256),
+    Id::Weierstrass(P384),
+    Id::Weierstrass(P521),
+    Id::Weierstrass(SECP256K1),
+    Id::Weierstrass(SM2),
+    Id::Weierstrass(BLS12381G1),
+    Id::Weierstrass(BN254),
+    Id::Weierstrass(W25519),
+    Id::Edwards(EDWARDS25519),
+    Id::Edwards(EDWARDS448),
+    Id::Edwards(JUBJUB),
+    Id::Montgomery(CURVE25519),
+    Id::Montgomery(CURVE448),
+];
+
+impl std::fmt::Display for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Id::Weierstrass(id) => write!(f, "{}", id),
+            Id::Edwards(id) => write!(f, "{}", id),
+            Id::Montgomery(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+fn find(name: &str) -> Result<Id, Box<dyn Error>> {
+    CURVES
+        .iter()
+        .find(|id| id.to_string().eq_ignore_ascii_case(name))
+        .copied()
+        .ok_or_else(|| format!("unknown curve {}, see ecc-tool curves", name).into())
+}
+
+fn scalar<E: EllipticCurve>(e: &E, s: &str) -> Result<E::Scalar, Box<dyn Error>> {
+    Ok(e.new_scalar(BigInt::from_bytes_be(Sign::Plus, &hex::decode(s)?)))
+}
+
+fn point<E>(e: &E, s: &str) -> Result<E::Point, Box<dyn Error>>
+where
+    E: EllipticCurve + Decode<Deser = <E as EllipticCurve>::Point>,
+{
+    Ok(e.point_from_hex(s)?)
+}
+
+fn print_point<P: Encode>(p: &P) {
+    println!(
+        "compressed:   {}",
+        hex::encode(p.encode(PointFormat::Compressed))
+    );
+    println!(
+        "uncompressed: {}",
+        hex::encode(p.encode(PointFormat::Uncompressed))
+    );
+}
+
+/// Runs the commands that work on any curve.
+fn run<E>(e: &E, cmd: &str, args: &[String]) -> Result<(), Box<dyn Error>>
+where
+    E: EllipticCurve + Decode<Deser = <E as EllipticCurve>::Point>,
+{
+    match (cmd, args) {
+        ("keygen", []) => {
+            let kp = KeyPair::generate(e, &mut OsRng);
+            println!("secret: {}", hex::encode(kp.to_bytes().expose_secret()));
+            print_point(kp.public());
+        }
+        ("decode", [p]) => {
+            let p = point(e, p)?;
+            println!("{:?}", p);
+            print_point(&p);
+        }
+        ("mul", [k]) => print_point(&(e.get_generator() * &scalar(e, k)?)),
+        ("mul", [k, p]) => print_point(&(point(e, p)? * &scalar(e, k)?)),
+        ("add", [p, q]) => print_point(&(point(e, p)? + point(e, q)?)),
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+/// Signs and verifies with ECDSA, where the hash function is SHA-256,
+/// SHA-384, or SHA-512, whichever matches the size of the field.
+fn ecdsa(id: WeCurveID, cmd: &str, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let e = id.get();
+    let size = e.get_field().size_bytes();
+    match (cmd, args) {
+        ("sign", [d, msg]) => {
+            let sk = ecdsa::SigningKey::from_bytes(&e, &hex::decode(d)?)?;
+            let (msg, nonce) = (hex::decode(msg)?, ecdsa::Nonce::Deterministic);
+            let sig = match size {
+                0..=32 => sk.sign_with::<Sha256, _>(&msg, nonce, &mut OsRng),
+                33..=48 => sk.sign_with::<Sha384, _>(&msg, nonce, &mut OsRng),
+                _ => sk.sign_with::<Sha512, _>(&msg, nonce, &mut OsRng),
+            };
+            println!("signature: {}", hex::encode(sig.to_bytes()));
+            println!("der:       {}", hex::encode(sig.to_der()));
+        }
+        ("verify", [q, msg, sig]) => {
+            let vk = ecdsa::VerifyingKey::from_bytes(&e, &hex::decode(q)?)?;
+            let (msg, sig) = (hex::decode(msg)?, hex::decode(sig)?);
+            let sig = ecdsa::Signature::from_bytes(&e, &sig)
+                .or_else(|_| ecdsa::Signature::from_der(&e, &sig))?;
+            match size {
+                0..=32 => vk.verify::<Sha256>(&msg, &sig)?,
+                33..=48 => vk.verify::<Sha384>(&msg, &sig)?,
+                _ => vk.verify::<Sha512>(&msg, &sig)?,
+            };
+            println!("valid signature");
+        }
+        _ => return run(&e, cmd, args),
+    }
+    Ok(())
+}
+
+fn eddsa(alg: Algorithm, cmd: &str, args: &[String]) -> Result<(), Box<dyn Error>> {
+    match (cmd, args) {
+        ("sign", [seed, msg]) => {
+            let sk = eddsa::SigningKey::from_seed(alg, &hex::decode(seed)?)?;
+            let sig = sk.sign(&hex::decode(msg)?);
+            println!("public:    {}", hex::encode(sk.verifying_key().to_bytes()));
+            println!("signature: {}", hex::encode(sig.to_bytes()));
+        }
+        ("verify", [pk, msg, sig]) => {
+            let vk = eddsa::VerifyingKey::from_bytes(alg, &hex::decode(pk)?)?;
+            let sig = eddsa::Signature::from_bytes(alg, &hex::decode(sig)?)?;
+            vk.verify(&hex::decode(msg)?, &sig)?;
+            println!("valid signature");
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
+
+fn cli(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (cmd, curve, args) = match args {
+        [cmd] if cmd == "curves" => {
+            for id in CURVES.iter() {
+                let model = match id {
+                    Id::Weierstrass(_) => "Weierstrass",
+                    Id::Edwards(_) => "twisted Edwards",
+                    Id::Montgomery(_) => "Montgomery",
+                };
+                println!("{:<12} {}", id, model);
+            }
+            return Ok(());
+        }
+        [cmd, curve, args @ ..] => (cmd.as_str(), find(curve)?, args),
+        _ => return Err(USAGE.into()),
+    };
+    match (curve, cmd) {
+        (Id::Weierstrass(id), _) => ecdsa(id, cmd, args),
+        (Id::Edwards(id), "sign") | (Id::Edwards(id), "verify") if id == EDWARDS25519 => {
+            eddsa(Algorithm::Ed25519, cmd, args)
+        }
+        (Id::Edwards(id), "sign") | (Id::Edwards(id), "verify") if id == EDWARDS448 => {
+            eddsa(Algorithm::Ed448, cmd, args)
+        }
+        (Id::Edwards(id), _) => run(&id.get(), cmd, args),
+        (Id::Montgomery(id), _) => run(&id.get(), cmd, args),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(err) = cli(&args) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,12 @@
+//! Decodes points of all the named curves. Decoders only accept canonical
+//! encodings, so every point decoded must encode back to the input.
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use redox_ecc::fuzzing::Encoding;
+
+fuzz_target!(|input: Encoding| {
+    match input.curve.reencode(&input.bytes) {
+        Ok(out) => assert_eq!(out, input.bytes, "{}", input.curve),
+        Err(_) => assert!(!input.valid, "{}", input.curve),
+    }
+});
@@ -0,0 +1,19 @@
+//! Checks that the constant-time decompression of Montgomery points agrees
+//! with `decode` on compressed encodings and rejects everything else.
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use redox_ecc::ellipticcurve::{Decode, EllipticCurve};
+use redox_ecc::field::Field;
+use redox_ecc::fuzzing::{AnyCurve, Encoding};
+use redox_ecc::instances::GetCurve;
+
+fuzz_target!(|input: Encoding| {
+    if let AnyCurve::Montgomery(id) = input.curve {
+        let e = id.get();
+        let buf = &input.bytes;
+        let compressed = buf.len() == e.get_field().size_bytes() + 1 && buf[0] != 0;
+        let want = e.decode(buf).ok().filter(|_| compressed);
+        let got = e.decode_ct(buf).ok();
+        assert!(want == got, "{}", id);
+    }
+});
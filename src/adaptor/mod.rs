@@ -0,0 +1,245 @@
+//! This is documentation for the `adaptor` module.
+//!
+//! The adaptor module implements Schnorr adaptor signatures over any
+//! prime-order group, such as secp256k1 or ristretto255.
+//!
+//! A pre-signature is a signature locked to an adaptor point `T = [t]G`:
+//! anyone can check that it is valid for `T`, but only someone knowing the
+//! secret `t` can adapt it into a complete signature. Conversely, the secret
+//! `t` is extracted from the pre-signature and the complete signature, which
+//! makes atomic swaps and discreet log contracts possible.
+//!
+//! Signatures are pairs `(R, s)` with `[s]G = R + [c]P`, where the
+//! challenge `c` hashes `R`, the public key `P` and the message to a scalar
+//! of the group. In particular, they are not BIP-340 signatures.
+//!
+//! # Swapping a Signature for a Secret
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::adaptor::Adaptor;
+//!  use redox_ecc::group::{PrimeGroup, Ristretto255};
+//!
+//!  let adaptor = Adaptor::new(Ristretto255::new());
+//!  let g = adaptor.group();
+//!  let (x, t) = (g.new_scalar(5.into()), g.new_scalar(7.into()));
+//!  let (pk, ta) = (g.generator() * &x, g.generator() * &t);
+//!  let pre = adaptor.pre_sign(&x, b"message", &ta, &mut OsRng);
+//!  assert!(adaptor.pre_verify(&pk, b"message", &ta, &pre).is_ok());
+//!  let sig = adaptor.adapt(&pre, &t);
+//!  assert!(adaptor.verify(&pk, b"message", &sig).is_ok());
+//!  let secret = adaptor.extract(&pre, &sig);
+//!  assert_eq!(g.encode_scalar(&secret), g.encode_scalar(&t));
+//! ```
+
+use num_bigint::BigInt;
+use rand_core::{CryptoRng, RngCore};
+
+use std::io::Error;
+
+use crate::group::PrimeGroup;
+use crate::h2c::Dst;
+
+/// Signature is a Schnorr signature `(R, s)`.
+pub struct Signature<G: PrimeGroup> {
+    pub r: G::Elt,
+    pub s: G::Scalar,
+}
+
+/// PreSignature is a signature locked to an adaptor point. It holds the
+/// nonce `R` of the complete signature, which already includes the adaptor
+/// point, and the scalar `s'` such that `s = s' + t`.
+pub struct PreSignature<G: PrimeGroup> {
+    pub r: G::Elt,
+    pub s: G::Scalar,
+}
+
+macro_rules! impl_encoding {
+    ($name:ident, $doc:literal) => {
+        impl<G: PrimeGroup> $name<G> {
+            #[doc = $doc]
+            pub fn from_bytes(g: &G, buf: &[u8]) -> Result<$name<G>, Error> {
+                let n = scalar_size(g);
+                if buf.len() < n {
+                    return Err(Error::other(ERR_SIG_LEN));
+                }
+                let (enc_r, enc_s) = buf.split_at(buf.len() - n);
+                let r = g.decode(enc_r)?;
+                let s = g.decode_scalar(enc_s)?;
+                Ok($name { r, s })
+            }
+            pub fn to_bytes(&self, g: &G) -> Vec<u8> {
+                let mut out = g.encode(&self.r);
+                out.append(&mut g.encode_scalar(&self.s));
+                out
+            }
+        }
+
+        impl<G: PrimeGroup> Clone for $name<G> {
+            fn clone(&self) -> Self {
+                $name {
+                    r: self.r.clone(),
+                    s: self.s.clone(),
+                }
+            }
+        }
+    };
+}
+
+impl_encoding!(
+    Signature,
+    "Parses a signature encoded as an element followed by a scalar of `g`."
+);
+impl_encoding!(
+    PreSignature,
+    "Parses a pre-signature encoded as an element followed by a scalar of `g`."
+);
+
+/// Adaptor is the adaptor signature scheme over a prime-order group.
+#[derive(Clone)]
+pub struct Adaptor<G: PrimeGroup> {
+    g: G,
+}
+
+impl<G: PrimeGroup> Adaptor<G> {
+    pub fn new(g: G) -> Adaptor<G> {
+        Adaptor { g }
+    }
+    pub fn group(&self) -> &G {
+        &self.g
+    }
+    /// Signs `msg` with the secret key `x`.
+    pub fn sign<R: RngCore + CryptoRng>(
+        &self,
+        x: &G::Scalar,
+        msg: &[u8],
+        rng: &mut R,
+    ) -> Signature<G> {
+        let pre = self.pre_sign(x, msg, &self.g.identity(), rng);
+        Signature { r: pre.r, s: pre.s }
+    }
+    /// Verifies a signature of `msg` under the public key `pk`.
+    pub fn verify(&self, pk: &G::Elt, msg: &[u8], sig: &Signature<G>) -> Result<(), Error> {
+        let c = self.challenge(&sig.r, pk, msg);
+        if self.g.generator() * &sig.s != sig.r.clone() + pk.clone() * c {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        Ok(())
+    }
+    /// Produces a pre-signature of `msg` with the secret key `x`, locked to
+    /// the adaptor point `t`.
+    pub fn pre_sign<R: RngCore + CryptoRng>(
+        &self,
+        x: &G::Scalar,
+        msg: &[u8],
+        t: &G::Elt,
+        rng: &mut R,
+    ) -> PreSignature<G> {
+        let g = &self.g;
+        let pk = g.generator() * x;
+        let (k, r) = loop {
+            let k = self.g.random_scalar(rng);
+            let r = g.generator() * &k + t.clone();
+            if r != g.identity() {
+                break (k, r);
+            }
+        };
+        let c = self.challenge(&r, &pk, msg);
+        let s = k + c * x;
+        PreSignature { r, s }
+    }
+    /// Verifies that `pre` is a pre-signature of `msg` under the public key
+    /// `pk` locked to the adaptor point `t`.
+    pub fn pre_verify(
+        &self,
+        pk: &G::Elt,
+        msg: &[u8],
+        t: &G::Elt,
+        pre: &PreSignature<G>,
+    ) -> Result<(), Error> {
+        let c = self.challenge(&pre.r, pk, msg);
+        if self.g.generator() * &pre.s + t.clone() != pre.r.clone() + pk.clone() * c {
+            return Err(Error::other(ERR_PRESIG_INVALID));
+        }
+        Ok(())
+    }
+    /// Completes a pre-signature with the secret `t` of its adaptor point.
+    pub fn adapt(&self, pre: &PreSignature<G>, t: &G::Scalar) -> Signature<G> {
+        Signature {
+            r: pre.r.clone(),
+            s: pre.s.clone() + t,
+        }
+    }
+    /// Returns the secret of the adaptor point from a pre-signature and the
+    /// signature completed from it.
+    pub fn extract(&self, pre: &PreSignature<G>, sig: &Signature<G>) -> G::Scalar {
+        sig.s.clone() - &pre.s
+    }
+    fn challenge(&self, r: &G::Elt, pk: &G::Elt, msg: &[u8]) -> G::Scalar {
+        let g = &self.g;
+        let dst = Dst::new(CHALLENGE_DST).unwrap();
+        let input = [g.encode(r), g.encode(pk), msg.to_vec()].concat();
+        g.hash_to_scalar(&input, &dst)
+    }
+}
+
+fn scalar_size<G: PrimeGroup>(g: &G) -> usize {
+    g.encode_scalar(&g.new_scalar(BigInt::from(0))).len()
+}
+
+const CHALLENGE_DST: &[u8] = b"REDOX-ECC-ADAPTOR-V01";
+const ERR_SIG_LEN: &str = "signature is too short";
+const ERR_SIG_INVALID: &str = "invalid signature";
+const ERR_PRESIG_INVALID: &str = "invalid pre-signature";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand_core::OsRng;
+
+    use super::{Adaptor, PreSignature, Signature};
+    use crate::group::{PrimeGroup, Ristretto255, WeGroup};
+    use crate::h2c::SECP256K1_XMDSHA256_SSWU_RO_;
+
+    fn check_adaptor<G: PrimeGroup>(g: G) {
+        let adaptor = Adaptor::new(g);
+        let g = adaptor.group();
+        let k = |v: i64| g.new_scalar(BigInt::from(v));
+        let (x, t) = (k(0x1234), k(0x5678));
+        let (pk, ta) = (g.generator() * &x, g.generator() * &t);
+
+        let sig = adaptor.sign(&x, b"message", &mut OsRng);
+        assert!(adaptor.verify(&pk, b"message", &sig).is_ok());
+        assert!(adaptor.verify(&pk, b"other", &sig).is_err());
+
+        let pre = adaptor.pre_sign(&x, b"message", &ta, &mut OsRng);
+        let pre = PreSignature::from_bytes(g, &pre.to_bytes(g)).unwrap();
+        assert!(adaptor.pre_verify(&pk, b"message", &ta, &pre).is_ok());
+        assert!(adaptor.pre_verify(&pk, b"other", &ta, &pre).is_err());
+        assert!(adaptor.pre_verify(&pk, b"message", &pk, &pre).is_err());
+
+        // a pre-signature is not a valid signature by itself
+        let locked = Signature {
+            r: pre.r.clone(),
+            s: pre.s.clone(),
+        };
+        assert!(adaptor.verify(&pk, b"message", &locked).is_err());
+
+        let sig = adaptor.adapt(&pre, &t);
+        let sig = Signature::from_bytes(g, &sig.to_bytes(g)).unwrap();
+        assert!(adaptor.verify(&pk, b"message", &sig).is_ok());
+        assert!(adaptor
+            .verify(&pk, b"message", &adaptor.adapt(&pre, &x))
+            .is_err());
+        let secret = adaptor.extract(&pre, &sig);
+        assert_eq!(g.encode_scalar(&secret), g.encode_scalar(&t));
+
+        assert!(Signature::from_bytes(g, &[0u8; 4]).is_err());
+    }
+
+    #[test]
+    fn adaptor() {
+        check_adaptor(WeGroup::new(SECP256K1_XMDSHA256_SSWU_RO_));
+        check_adaptor(Ristretto255::new());
+    }
+}
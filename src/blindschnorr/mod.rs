@@ -0,0 +1,249 @@
+//! This is documentation for the `blindschnorr` module.
+//!
+//! The blindschnorr module implements blind Schnorr signatures over
+//! secp256k1, whose unblinded signatures are BIP-340 signatures. A user
+//! obtains a signature of a message from the signer, while the signer learns
+//! neither the message nor the resulting signature, as needed for issuing
+//! privacy-preserving tokens.
+//!
+//! The protocol has three moves: the signer sends a commitment to its
+//! nonce, the user answers with a blinded challenge, and the signer returns
+//! a blind signature, which the user unblinds.
+//!
+//! **Attention** The signer must not run several sessions concurrently: a
+//! user that opens many sessions at once can forge an additional signature
+//! with the ROS attack. Secret nonces cannot be cloned, and signing consumes
+//! them, so a nonce cannot be used for two sessions by accident.
+//!
+//! # Issuing a Signature
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::blindschnorr::{SignerNonce, User};
+//!  use redox_ecc::schnorr::SigningKey;
+//!
+//!  let sk = SigningKey::random(&mut OsRng);
+//!  let vk = sk.verifying_key();
+//!  let (nonce, commitment) = SignerNonce::generate(&mut OsRng);
+//!  let (user, challenge) = User::new(vk, &commitment, b"token", &mut OsRng).unwrap();
+//!  let blind_sig = nonce.sign(&sk, &challenge);
+//!  let sig = user.unblind(&blind_sig).unwrap();
+//!  assert!(vk.verify(b"token", &sig).is_ok());
+//! ```
+
+use num_bigint::{BigInt, BigUint, Sign};
+use rand_core::{CryptoRng, RngCore};
+
+use std::io::Error;
+
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode, PointFormat};
+use crate::instances::{GetCurve, SECP256K1};
+use crate::ops::Serialize;
+use crate::schnorr::{challenge, has_even_y, x_only, Signature, SigningKey, VerifyingKey};
+use crate::weierstrass::{Curve, Point, Scalar};
+
+/// SignerNonce is the secret nonce of the signer for one session. It is
+/// consumed when signing.
+pub struct SignerNonce {
+    k: Scalar,
+}
+
+impl SignerNonce {
+    /// Samples a nonce uniformly at random. It returns the secret nonce and
+    /// the commitment to send to the user.
+    pub fn generate<R: RngCore + CryptoRng>(rng: &mut R) -> (SignerNonce, Commitment) {
+        let e = SECP256K1.get();
+        let k = e.random_scalar(rng);
+        let r = e.get_generator() * &k;
+        (
+            SignerNonce { k },
+            Commitment(r.encode(PointFormat::Compressed)),
+        )
+    }
+    /// Answers the blinded challenge of the user with the key `sk`, which
+    /// consumes the nonce.
+    pub fn sign(self, sk: &SigningKey, challenge: &BlindChallenge) -> BlindSignature {
+        let e = SECP256K1.get();
+        let d = e.new_scalar(BigInt::from_bytes_be(Sign::Plus, &sk.to_bytes()));
+        // the secret key is negated if its public point has odd y-coordinate
+        let d = if has_even_y(&(e.get_generator() * &d)) {
+            d
+        } else {
+            -d
+        };
+        BlindSignature(self.k + &challenge.0 * d)
+    }
+}
+
+/// Commitment is the public nonce `R` of the signer, which is sent to the
+/// user in the first move.
+#[derive(Clone, PartialEq)]
+pub struct Commitment(Vec<u8>);
+
+impl Commitment {
+    /// Parses a point in compressed form.
+    pub fn from_bytes(buf: &[u8]) -> Result<Commitment, Error> {
+        cpoint(&SECP256K1.get(), buf)?;
+        Ok(Commitment(buf.to_vec()))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+}
+
+/// BlindChallenge is the challenge sent by the user in the second move,
+/// which hides the message being signed.
+#[derive(Clone, PartialEq)]
+pub struct BlindChallenge(Scalar);
+
+impl BlindChallenge {
+    /// Parses a 32-byte challenge. It fails if it is not less than the order
+    /// of secp256k1.
+    pub fn from_bytes(buf: &[u8]) -> Result<BlindChallenge, Error> {
+        Ok(BlindChallenge(scalar(buf)?))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes_be()
+    }
+}
+
+/// BlindSignature is the answer of the signer in the third move.
+#[derive(Clone, PartialEq)]
+pub struct BlindSignature(Scalar);
+
+impl BlindSignature {
+    /// Parses a 32-byte blind signature. It fails if it is not less than the
+    /// order of secp256k1.
+    pub fn from_bytes(buf: &[u8]) -> Result<BlindSignature, Error> {
+        Ok(BlindSignature(scalar(buf)?))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes_be()
+    }
+}
+
+/// User is the state of the party that obtains a signature, which holds
+/// the blinding factors of one session.
+pub struct User {
+    vk: VerifyingKey,
+    r: Point,
+    c: Scalar,
+    alpha: Scalar,
+    sig_r: Vec<u8>,
+}
+
+impl User {
+    /// Blinds the commitment of the signer and the message `msg`. It returns
+    /// the state of the user and the challenge to send to the signer. It
+    /// fails if the commitment is not a valid point.
+    pub fn new<R: RngCore + CryptoRng>(
+        vk: &VerifyingKey,
+        commitment: &Commitment,
+        msg: &[u8],
+        rng: &mut R,
+    ) -> Result<(User, BlindChallenge), Error> {
+        let e = SECP256K1.get();
+        let r = cpoint(&e, &commitment.0)?;
+        let p = vk.as_point();
+        // the blinding factors are resampled until the nonce of the final
+        // signature has even y-coordinate, as required by BIP-340
+        let (alpha, beta, r_blind) = loop {
+            let (alpha, beta) = (e.random_scalar(rng), e.random_scalar(rng));
+            let r_blind = &r + e.get_generator() * &alpha + p * &beta;
+            if !r_blind.is_identity() && has_even_y(&r_blind) {
+                break (alpha, beta, r_blind);
+            }
+        };
+        let sig_r = x_only(&r_blind);
+        let c = challenge(&e, &sig_r, &vk.to_bytes(), msg) + beta;
+        let user = User {
+            vk: vk.clone(),
+            r,
+            c: c.clone(),
+            alpha,
+            sig_r,
+        };
+        Ok((user, BlindChallenge(c)))
+    }
+    /// Verifies the blind signature of the signer and unblinds it into a
+    /// BIP-340 signature of the message.
+    pub fn unblind(self, blind_sig: &BlindSignature) -> Result<Signature, Error> {
+        let e = SECP256K1.get();
+        let s = &blind_sig.0;
+        if e.get_generator() * s != &self.r + self.vk.as_point() * &self.c {
+            return Err(Error::other(ERR_BLIND_SIG));
+        }
+        let mut out = self.sig_r;
+        out.append(&mut (self.alpha + s).to_bytes_be());
+        Signature::from_bytes(&out)
+    }
+}
+
+/// Parses a point in compressed form.
+fn cpoint(e: &Curve, buf: &[u8]) -> Result<Point, Error> {
+    if buf.len() != POINT_SIZE || (buf[0] != 0x02 && buf[0] != 0x03) {
+        return Err(Error::other(ERR_POINT));
+    }
+    e.decode(buf).map_err(|_| Error::other(ERR_POINT))
+}
+
+fn scalar(buf: &[u8]) -> Result<Scalar, Error> {
+    let e = SECP256K1.get();
+    let s = BigUint::from_bytes_be(buf);
+    if buf.len() != SCALAR_SIZE || s >= e.get_order() {
+        return Err(Error::other(ERR_SCALAR));
+    }
+    Ok(e.new_scalar(s.into()))
+}
+
+const SCALAR_SIZE: usize = 32;
+const POINT_SIZE: usize = 33;
+const ERR_POINT: &str = "point must be encoded in compressed form";
+const ERR_SCALAR: &str = "scalar must be a 32-byte integer less than the order";
+const ERR_BLIND_SIG: &str = "invalid blind signature";
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use super::{BlindChallenge, BlindSignature, Commitment, SignerNonce, User};
+    use crate::schnorr::SigningKey;
+
+    #[test]
+    fn issuance() {
+        for _ in 0..4 {
+            let sk = SigningKey::random(&mut OsRng);
+            let vk = sk.verifying_key();
+            let (nonce, commitment) = SignerNonce::generate(&mut OsRng);
+            let commitment = Commitment::from_bytes(&commitment.to_bytes()).unwrap();
+            let (user, challenge) = User::new(vk, &commitment, b"token", &mut OsRng).unwrap();
+            let challenge = BlindChallenge::from_bytes(&challenge.to_bytes()).unwrap();
+            let blind_sig = nonce.sign(&sk, &challenge);
+            let blind_sig = BlindSignature::from_bytes(&blind_sig.to_bytes()).unwrap();
+            let sig = user.unblind(&blind_sig).unwrap();
+            assert!(vk.verify(b"token", &sig).is_ok());
+            assert!(vk.verify(b"other", &sig).is_err());
+
+            // the signer sees neither the nonce nor the challenge of the
+            // final signature
+            let sig = sig.to_bytes();
+            assert_ne!(sig[..32], commitment.to_bytes()[1..]);
+            assert_ne!(challenge.to_bytes(), blind_sig.to_bytes());
+        }
+    }
+
+    #[test]
+    fn invalid_blind_signature() {
+        let sk = SigningKey::random(&mut OsRng);
+        let (nonce, commitment) = SignerNonce::generate(&mut OsRng);
+        let (user, challenge) =
+            User::new(sk.verifying_key(), &commitment, b"token", &mut OsRng).unwrap();
+        let blind_sig = nonce.sign(&SigningKey::random(&mut OsRng), &challenge);
+        assert!(user.unblind(&blind_sig).is_err());
+
+        assert!(Commitment::from_bytes(&[0x04; 33]).is_err());
+        assert!(Commitment::from_bytes(&[0x02; 32]).is_err());
+        assert!(BlindChallenge::from_bytes(&[0xff; 32]).is_err());
+        assert!(BlindSignature::from_bytes(&[0x01; 31]).is_err());
+    }
+}
@@ -0,0 +1,369 @@
+//! This is documentation for the `minsig` module.
+//!
+//! The minsig module implements the BLS signatures of
+//! draft-irtf-cfrg-bls-signature over BLS12-381 in the variant with minimal
+//! signature size: signatures are points of G1 (48 bytes) and verifying keys
+//! are points of G2 (96 bytes). It is otherwise identical to the variant of
+//! the parent module, and a secret key derived from the same keying material
+//! is the same in both.
+//!
+//! # Aggregating signatures
+//!
+//! ```
+//!  use redox_ecc::bls::minsig::Ciphersuite;
+//!  use redox_ecc::bls::Scheme;
+//!
+//!  let cs = Ciphersuite::new(Scheme::ProofOfPossession);
+//!  let sk1 = cs.key_gen(&[1u8; 32]).unwrap();
+//!  let sk2 = cs.key_gen(&[2u8; 32]).unwrap();
+//!  let (vk1, vk2) = (sk1.verifying_key(), sk2.verifying_key());
+//!  assert!(cs.pop_verify(vk1, &cs.pop_prove(&sk1).unwrap()).is_ok());
+//!
+//!  let sig = cs.aggregate(&[cs.sign(&sk1, b"msg"), cs.sign(&sk2, b"msg")]).unwrap();
+//!  assert!(cs.fast_aggregate_verify(&[vk1.clone(), vk2.clone()], b"msg", &sig).is_ok());
+//! ```
+
+use num_bigint::BigInt;
+use rand_core::{CryptoRng, RngCore};
+
+use std::io::Error;
+
+use crate::bls::{
+    derive_secret, parse_secret, Scheme, ERR_DISTINCT, ERR_EMPTY, ERR_KEY_IDENTITY, ERR_KEY_LEN,
+    ERR_MSG_COUNT, ERR_SCHEME, ERR_SIG_INVALID, ERR_SIG_LEN, SK_SIZE,
+};
+use crate::ellipticcurve::{Decode, EcPoint, Encode, PointFormat};
+use crate::h2c::{Dst, Encoding, HashToCurve, BLS12381G1_XMDSHA256_SSWU_RO_, RO};
+use crate::ops::Serialize;
+use crate::pairing::{Bls12381, G2Point, PairingEngine};
+use crate::weierstrass::{Curve, Point, Scalar};
+
+/// Signature is a point of G1, encoded in the compressed format of Zcash.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Signature(Point);
+
+impl Signature {
+    /// Parses a 48-byte signature. It fails for points outside of G1.
+    pub fn from_bytes(buf: &[u8]) -> Result<Signature, Error> {
+        let e = Bls12381::new();
+        if buf.len() != SIG_SIZE {
+            return Err(Error::other(ERR_SIG_LEN));
+        }
+        Ok(Signature(e.decode_g1(buf)?))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Bls12381::new().encode_g1(&self.0, PointFormat::Compressed)
+    }
+    pub fn as_point(&self) -> &Point {
+        &self.0
+    }
+}
+
+/// SigningKey is a BLS secret key, i.e., a non-zero scalar modulo `r`,
+/// together with its verifying key.
+#[derive(Clone)]
+pub struct SigningKey {
+    d: Scalar,
+    vk: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Parses a 32-byte secret key. It fails if the secret is zero or is not
+    /// less than `r`.
+    pub fn from_bytes(buf: &[u8]) -> Result<SigningKey, Error> {
+        let e = Bls12381::new();
+        let d = parse_secret(&e, buf)?;
+        Ok(SigningKey::new(&e, d))
+    }
+    /// Returns a signing key sampled uniformly at random.
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> SigningKey {
+        let mut buf = [0u8; SK_SIZE];
+        loop {
+            rng.fill_bytes(&mut buf);
+            if let Ok(sk) = SigningKey::from_bytes(&buf) {
+                return sk;
+            }
+        }
+    }
+    fn new(e: &Bls12381, d: BigInt) -> SigningKey {
+        let d = e.new_scalar(d);
+        let q = e.g2_generator() * &d;
+        let enc = q.encode(PointFormat::Compressed);
+        let vk = VerifyingKey { q, enc };
+        SigningKey { d, vk }
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.d.to_bytes_be()
+    }
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.vk
+    }
+}
+
+/// VerifyingKey is a point of G2 other than the identity, encoded in the
+/// compressed format of Zcash.
+#[derive(Clone, PartialEq, Eq)]
+pub struct VerifyingKey {
+    q: G2Point,
+    enc: Vec<u8>,
+}
+
+impl VerifyingKey {
+    /// Parses a 96-byte verifying key. It fails for the identity and for
+    /// points outside of G2, which is the `KeyValidate` procedure of the
+    /// draft.
+    pub fn from_bytes(buf: &[u8]) -> Result<VerifyingKey, Error> {
+        let e = Bls12381::new();
+        if buf.len() != VK_SIZE {
+            return Err(Error::other(ERR_KEY_LEN));
+        }
+        let q = e.g2().decode(buf)?;
+        if q.is_zero() {
+            return Err(Error::other(ERR_KEY_IDENTITY));
+        }
+        Ok(VerifyingKey {
+            q,
+            enc: buf.to_vec(),
+        })
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.enc.clone()
+    }
+    pub fn as_point(&self) -> &G2Point {
+        &self.q
+    }
+}
+
+/// Ciphersuite is a BLS signature ciphersuite over BLS12-381 with
+/// signatures in G1, e.g., `BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_`.
+pub struct Ciphersuite {
+    scheme: Scheme,
+    e: Bls12381,
+    h: Encoding<Curve, RO>,
+    h_pop: Encoding<Curve, RO>,
+}
+
+impl Ciphersuite {
+    pub fn new(scheme: Scheme) -> Ciphersuite {
+        let suite = &BLS12381G1_XMDSHA256_SSWU_RO_;
+        let dst = match scheme {
+            Scheme::Basic => DST_BASIC,
+            Scheme::ProofOfPossession => DST_POP,
+        };
+        Ciphersuite {
+            scheme,
+            e: Bls12381::new(),
+            h: suite.get(&Dst::new(dst).unwrap()),
+            h_pop: suite.get(&Dst::new(DST_POP_PROOF).unwrap()),
+        }
+    }
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+    /// Derives a signing key from at least 32 bytes of secret keying
+    /// material `ikm`.
+    pub fn key_gen(&self, ikm: &[u8]) -> Result<SigningKey, Error> {
+        let d = derive_secret(&self.e, ikm)?;
+        Ok(SigningKey::new(&self.e, d))
+    }
+    /// Signs `msg`.
+    pub fn sign(&self, sk: &SigningKey, msg: &[u8]) -> Signature {
+        Signature(self.h.hash(msg) * &sk.d)
+    }
+    /// Verifies a signature of `msg` under `vk`.
+    pub fn verify(&self, vk: &VerifyingKey, msg: &[u8], sig: &Signature) -> Result<(), Error> {
+        self.aggregate_verify(std::slice::from_ref(vk), &[msg], sig)
+    }
+    /// Returns the aggregate of one or more signatures.
+    pub fn aggregate(&self, sigs: &[Signature]) -> Result<Signature, Error> {
+        let (first, rest) = sigs.split_first().ok_or_else(|| Error::other(ERR_EMPTY))?;
+        Ok(Signature(
+            rest.iter().fold(first.0.clone(), |acc, s| acc + &s.0),
+        ))
+    }
+    /// Verifies an aggregate signature, where `msgs[i]` was signed under
+    /// `vks[i]`. Under `Scheme::Basic`, it fails if the messages are not
+    /// distinct.
+    pub fn aggregate_verify(
+        &self,
+        vks: &[VerifyingKey],
+        msgs: &[&[u8]],
+        sig: &Signature,
+    ) -> Result<(), Error> {
+        if vks.is_empty() {
+            return Err(Error::other(ERR_EMPTY));
+        }
+        if vks.len() != msgs.len() {
+            return Err(Error::other(ERR_MSG_COUNT));
+        }
+        if self.scheme == Scheme::Basic {
+            let mut sorted = msgs.to_vec();
+            sorted.sort_unstable();
+            sorted.dedup();
+            if sorted.len() != msgs.len() {
+                return Err(Error::other(ERR_DISTINCT));
+            }
+        }
+        let mut pairs: Vec<(Point, G2Point)> = vks
+            .iter()
+            .zip(msgs)
+            .map(|(vk, msg)| (self.h.hash(msg), vk.q.clone()))
+            .collect();
+        pairs.push((-sig.0.clone(), self.e.g2_generator()));
+        self.check(&pairs)
+    }
+    /// Returns a proof of possession of `sk`, which is a signature of its
+    /// verifying key under a separate tag.
+    pub fn pop_prove(&self, sk: &SigningKey) -> Result<Signature, Error> {
+        self.require_pop()?;
+        Ok(Signature(self.h_pop.hash(&sk.vk.enc) * &sk.d))
+    }
+    /// Verifies a proof of possession of the secret key of `vk`.
+    pub fn pop_verify(&self, vk: &VerifyingKey, proof: &Signature) -> Result<(), Error> {
+        self.require_pop()?;
+        let p = self.h_pop.hash(&vk.enc);
+        self.check(&[(p, vk.q.clone()), (-proof.0.clone(), self.e.g2_generator())])
+    }
+    /// Verifies an aggregate signature of the same message `msg` by all of
+    /// `vks`, which costs two pairings regardless of the number of signers.
+    /// It is only available under `Scheme::ProofOfPossession`, and the
+    /// proofs of possession of `vks` must have been checked beforehand.
+    pub fn fast_aggregate_verify(
+        &self,
+        vks: &[VerifyingKey],
+        msg: &[u8],
+        sig: &Signature,
+    ) -> Result<(), Error> {
+        self.require_pop()?;
+        let (first, rest) = vks.split_first().ok_or_else(|| Error::other(ERR_EMPTY))?;
+        let vk = rest.iter().fold(first.q.clone(), |acc, vk| acc + &vk.q);
+        self.check(&[
+            (self.h.hash(msg), vk),
+            (-sig.0.clone(), self.e.g2_generator()),
+        ])
+    }
+    fn require_pop(&self) -> Result<(), Error> {
+        if self.scheme != Scheme::ProofOfPossession {
+            return Err(Error::other(ERR_SCHEME));
+        }
+        Ok(())
+    }
+    fn check(&self, pairs: &[(Point, G2Point)]) -> Result<(), Error> {
+        if !self.e.multi_pairing(pairs).is_identity() {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        Ok(())
+    }
+}
+
+const VK_SIZE: usize = 96;
+const SIG_SIZE: usize = 48;
+const DST_BASIC: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+const DST_POP: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+const DST_POP_PROOF: &[u8] = b"BLS_POP_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use crate::ellipticcurve::EcPoint;
+    use crate::h2c::{Dst, HashToCurve, BLS12381G1_XMDSHA256_SSWU_RO_};
+    use crate::pairing::{Bls12381, PairingEngine};
+
+    use super::{Ciphersuite, Scheme, Signature, SigningKey, VerifyingKey, DST_POP};
+
+    const SK: &str = "0d7359d57963ab8fbbde1852dcf553fedbc31f464d80ee7d40ae683122b45070";
+
+    #[test]
+    fn sign_verify() {
+        // the keying material of the first test vector of EIP-2333 yields
+        // the same secret key as in the variant with keys in G1
+        let ikm = hex::decode("c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04").unwrap();
+        let e = Bls12381::new();
+
+        let basic = Ciphersuite::new(Scheme::Basic);
+        let sk = basic.key_gen(&ikm).unwrap();
+        assert_eq!(hex::encode(sk.to_bytes()), SK);
+        let vk = sk.verifying_key();
+        assert_eq!(vk.to_bytes().len(), 96);
+        assert!(vk.as_point() == &(e.g2_generator() * &sk.d));
+        let vk = VerifyingKey::from_bytes(&vk.to_bytes()).unwrap();
+        assert!(&vk == sk.verifying_key());
+
+        let sig = basic.sign(&sk, b"abc");
+        assert_eq!(sig.to_bytes().len(), 48);
+        let sig = Signature::from_bytes(&sig.to_bytes()).unwrap();
+        assert!(basic.verify(&vk, b"abc", &sig).is_ok());
+        assert!(basic.verify(&vk, b"abd", &sig).is_err());
+        assert!(basic.pop_prove(&sk).is_err());
+
+        // signatures hash with the G1 suite under the POP tag
+        let pop = Ciphersuite::new(Scheme::ProofOfPossession);
+        let sig = pop.sign(&sk, b"abc");
+        let h = BLS12381G1_XMDSHA256_SSWU_RO_.get(&Dst::new(DST_POP).unwrap());
+        assert!(sig.as_point() == &(h.hash(b"abc") * &sk.d));
+        assert!(pop.verify(&vk, b"abc", &sig).is_ok());
+        assert!(basic.verify(&vk, b"abc", &sig).is_err());
+
+        let proof = pop.pop_prove(&sk).unwrap();
+        let proof = Signature::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(pop.pop_verify(&vk, &proof).is_ok());
+        assert!(pop.pop_verify(&vk, &sig).is_err());
+    }
+
+    #[test]
+    fn aggregate() {
+        let basic = Ciphersuite::new(Scheme::Basic);
+        let sk1 = SigningKey::from_bytes(&hex::decode(SK).unwrap()).unwrap();
+        let sk2 = SigningKey::random(&mut OsRng);
+        let vks = [sk1.verifying_key().clone(), sk2.verifying_key().clone()];
+        let sig = basic
+            .aggregate(&[basic.sign(&sk1, b"one"), basic.sign(&sk2, b"two")])
+            .unwrap();
+        let sig = Signature::from_bytes(&sig.to_bytes()).unwrap();
+        assert!(basic
+            .aggregate_verify(&vks, &[b"one", b"two"], &sig)
+            .is_ok());
+        assert!(basic
+            .aggregate_verify(&vks, &[b"two", b"one"], &sig)
+            .is_err());
+        assert!(basic.aggregate_verify(&vks[..1], &[b"one"], &sig).is_err());
+        assert!(basic.aggregate(&[]).is_err());
+
+        // the basic scheme rejects repeated messages
+        let sig = basic
+            .aggregate(&[basic.sign(&sk1, b"one"), basic.sign(&sk2, b"one")])
+            .unwrap();
+        assert!(basic
+            .aggregate_verify(&vks, &[b"one", b"one"], &sig)
+            .is_err());
+        assert!(basic.fast_aggregate_verify(&vks, b"one", &sig).is_err());
+
+        let pop = Ciphersuite::new(Scheme::ProofOfPossession);
+        let sig = pop
+            .aggregate(&[pop.sign(&sk1, b"one"), pop.sign(&sk2, b"one")])
+            .unwrap();
+        assert!(pop.fast_aggregate_verify(&vks, b"one", &sig).is_ok());
+        assert!(pop.aggregate_verify(&vks, &[b"one", b"one"], &sig).is_ok());
+        assert!(pop.fast_aggregate_verify(&vks[1..], b"one", &sig).is_err());
+        assert!(pop.fast_aggregate_verify(&[], b"one", &sig).is_err());
+    }
+
+    #[test]
+    fn invalid_encodings() {
+        assert!(SigningKey::from_bytes(&[0u8; 32]).is_err());
+        assert!(SigningKey::from_bytes(&[0xffu8; 32]).is_err());
+        let mut inf = vec![0u8; 96];
+        inf[0] = 0xc0;
+        assert!(VerifyingKey::from_bytes(&inf).is_err());
+        assert!(VerifyingKey::from_bytes(&[]).is_err());
+        let sk = SigningKey::random(&mut OsRng);
+        let vk = sk.verifying_key().to_bytes();
+        assert!(VerifyingKey::from_bytes(&vk[..48]).is_err());
+        let mut inf = vec![0u8; 48];
+        inf[0] = 0xc0;
+        let sig = Signature::from_bytes(&inf).unwrap();
+        assert!(sig.as_point().is_zero());
+        assert!(Signature::from_bytes(&[inf.clone(), inf].concat()).is_err());
+    }
+}
@@ -0,0 +1,422 @@
+//! This is documentation for the `bls` module.
+//!
+//! The bls module implements the BLS signatures of
+//! draft-irtf-cfrg-bls-signature over BLS12-381, in the variant with
+//! minimal public key size: verifying keys are points of G1 (48 bytes) and
+//! signatures are points of G2 (96 bytes). The [`minsig`] module implements
+//! the variant with minimal signature size, which swaps both groups.
+//!
+//! Signatures of many signers are aggregated into a single one. Since an
+//! adversary could otherwise choose its key as a function of the others
+//! (rogue key attack), every ciphersuite fixes one of two defenses:
+//!
+//! - `Scheme::Basic` only verifies aggregates of distinct messages.
+//! - `Scheme::ProofOfPossession` requires each verifying key to come with a
+//!   proof of possession of its secret key, which must be checked once,
+//!   e.g., when the key is registered. In exchange, signatures of the same
+//!   message are verified with a single pairing product.
+//!
+//! # Aggregating signatures
+//!
+//! ```
+//!  use redox_ecc::bls::{Ciphersuite, Scheme};
+//!
+//!  let cs = Ciphersuite::new(Scheme::ProofOfPossession);
+//!  let sk1 = cs.key_gen(&[1u8; 32]).unwrap();
+//!  let sk2 = cs.key_gen(&[2u8; 32]).unwrap();
+//!  let (vk1, vk2) = (sk1.verifying_key(), sk2.verifying_key());
+//!  assert!(cs.pop_verify(vk1, &cs.pop_prove(&sk1).unwrap()).is_ok());
+//!
+//!  let sig = cs.aggregate(&[cs.sign(&sk1, b"msg"), cs.sign(&sk2, b"msg")]).unwrap();
+//!  assert!(cs.fast_aggregate_verify(&[vk1.clone(), vk2.clone()], b"msg", &sig).is_ok());
+//! ```
+
+use hkdf::Hkdf;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use std::io::Error;
+
+use crate::ellipticcurve::{Decode, EcPoint, Encode, PointFormat};
+use crate::h2c::{Dst, Encoding, HashToCurve, BLS12381G2_XMDSHA256_SSWU_RO_, RO};
+use crate::ops::Serialize;
+use crate::pairing::{Bls12381, G2Curve, G2Point, PairingEngine};
+use crate::weierstrass::{Point, Scalar};
+
+pub mod minsig;
+
+/// Scheme is the defense of a ciphersuite against rogue key attacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scheme {
+    /// Aggregate signatures are only valid for distinct messages.
+    Basic,
+    /// Verifying keys must come with a proof of possession.
+    ProofOfPossession,
+}
+
+impl Scheme {
+    fn dst(&self) -> &'static [u8] {
+        match self {
+            Scheme::Basic => DST_BASIC,
+            Scheme::ProofOfPossession => DST_POP,
+        }
+    }
+}
+
+/// Signature is a point of G2, encoded in the compressed format of Zcash.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Signature(G2Point);
+
+impl Signature {
+    /// Parses a 96-byte signature. It fails for points outside of G2.
+    pub fn from_bytes(buf: &[u8]) -> Result<Signature, Error> {
+        let e = Bls12381::new();
+        if buf.len() != SIG_SIZE {
+            return Err(Error::other(ERR_SIG_LEN));
+        }
+        Ok(Signature(e.g2().decode(buf)?))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.encode(PointFormat::Compressed)
+    }
+    pub fn as_point(&self) -> &G2Point {
+        &self.0
+    }
+}
+
+/// SigningKey is a BLS secret key, i.e., a non-zero scalar modulo `r`,
+/// together with its verifying key.
+#[derive(Clone)]
+pub struct SigningKey {
+    d: Scalar,
+    vk: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Parses a 32-byte secret key. It fails if the secret is zero or is not
+    /// less than `r`.
+    pub fn from_bytes(buf: &[u8]) -> Result<SigningKey, Error> {
+        let e = Bls12381::new();
+        let d = parse_secret(&e, buf)?;
+        Ok(SigningKey::new(&e, d))
+    }
+    /// Returns a signing key sampled uniformly at random.
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> SigningKey {
+        let mut buf = [0u8; SK_SIZE];
+        loop {
+            rng.fill_bytes(&mut buf);
+            if let Ok(sk) = SigningKey::from_bytes(&buf) {
+                return sk;
+            }
+        }
+    }
+    fn new(e: &Bls12381, d: BigInt) -> SigningKey {
+        let d = e.new_scalar(d);
+        let p = e.g1_generator() * &d;
+        let enc = e.encode_g1(&p, PointFormat::Compressed);
+        let vk = VerifyingKey { p, enc };
+        SigningKey { d, vk }
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.d.to_bytes_be()
+    }
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.vk
+    }
+}
+
+/// VerifyingKey is a point of G1 other than the identity, encoded in the
+/// compressed format of Zcash.
+#[derive(Clone, PartialEq, Eq)]
+pub struct VerifyingKey {
+    p: Point,
+    enc: Vec<u8>,
+}
+
+impl VerifyingKey {
+    /// Parses a 48-byte verifying key. It fails for the identity and for
+    /// points outside of G1, which is the `KeyValidate` procedure of the
+    /// draft.
+    pub fn from_bytes(buf: &[u8]) -> Result<VerifyingKey, Error> {
+        let e = Bls12381::new();
+        if buf.len() != VK_SIZE {
+            return Err(Error::other(ERR_KEY_LEN));
+        }
+        let p = e.decode_g1(buf)?;
+        if p.is_zero() {
+            return Err(Error::other(ERR_KEY_IDENTITY));
+        }
+        Ok(VerifyingKey {
+            p,
+            enc: buf.to_vec(),
+        })
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.enc.clone()
+    }
+    pub fn as_point(&self) -> &Point {
+        &self.p
+    }
+}
+
+/// Ciphersuite is a BLS signature ciphersuite over BLS12-381 with
+/// signatures in G2, e.g., `BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_`.
+pub struct Ciphersuite {
+    scheme: Scheme,
+    e: Bls12381,
+    h: Encoding<G2Curve, RO>,
+    h_pop: Encoding<G2Curve, RO>,
+}
+
+impl Ciphersuite {
+    pub fn new(scheme: Scheme) -> Ciphersuite {
+        let suite = &BLS12381G2_XMDSHA256_SSWU_RO_;
+        Ciphersuite {
+            scheme,
+            e: Bls12381::new(),
+            h: suite.get(&Dst::new(scheme.dst()).unwrap()),
+            h_pop: suite.get(&Dst::new(DST_POP_PROOF).unwrap()),
+        }
+    }
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+    /// Derives a signing key from at least 32 bytes of secret keying
+    /// material `ikm`.
+    pub fn key_gen(&self, ikm: &[u8]) -> Result<SigningKey, Error> {
+        let d = derive_secret(&self.e, ikm)?;
+        Ok(SigningKey::new(&self.e, d))
+    }
+    /// Signs `msg`.
+    pub fn sign(&self, sk: &SigningKey, msg: &[u8]) -> Signature {
+        Signature(self.h.hash(msg) * &sk.d)
+    }
+    /// Verifies a signature of `msg` under `vk`.
+    pub fn verify(&self, vk: &VerifyingKey, msg: &[u8], sig: &Signature) -> Result<(), Error> {
+        self.aggregate_verify(std::slice::from_ref(vk), &[msg], sig)
+    }
+    /// Returns the aggregate of one or more signatures.
+    pub fn aggregate(&self, sigs: &[Signature]) -> Result<Signature, Error> {
+        let (first, rest) = sigs.split_first().ok_or_else(|| Error::other(ERR_EMPTY))?;
+        Ok(Signature(
+            rest.iter().fold(first.0.clone(), |acc, s| acc + &s.0),
+        ))
+    }
+    /// Verifies an aggregate signature, where `msgs[i]` was signed under
+    /// `vks[i]`. Under `Scheme::Basic`, it fails if the messages are not
+    /// distinct.
+    pub fn aggregate_verify(
+        &self,
+        vks: &[VerifyingKey],
+        msgs: &[&[u8]],
+        sig: &Signature,
+    ) -> Result<(), Error> {
+        if vks.is_empty() {
+            return Err(Error::other(ERR_EMPTY));
+        }
+        if vks.len() != msgs.len() {
+            return Err(Error::other(ERR_MSG_COUNT));
+        }
+        if self.scheme == Scheme::Basic {
+            let mut sorted = msgs.to_vec();
+            sorted.sort_unstable();
+            sorted.dedup();
+            if sorted.len() != msgs.len() {
+                return Err(Error::other(ERR_DISTINCT));
+            }
+        }
+        let mut pairs: Vec<(Point, G2Point)> = vks
+            .iter()
+            .zip(msgs)
+            .map(|(vk, msg)| (vk.p.clone(), self.h.hash(msg)))
+            .collect();
+        pairs.push((-self.e.g1_generator(), sig.0.clone()));
+        self.check(&pairs)
+    }
+    /// Returns a proof of possession of `sk`, which is a signature of its
+    /// verifying key under a separate tag.
+    pub fn pop_prove(&self, sk: &SigningKey) -> Result<Signature, Error> {
+        self.require_pop()?;
+        Ok(Signature(self.h_pop.hash(&sk.vk.enc) * &sk.d))
+    }
+    /// Verifies a proof of possession of the secret key of `vk`.
+    pub fn pop_verify(&self, vk: &VerifyingKey, proof: &Signature) -> Result<(), Error> {
+        self.require_pop()?;
+        let q = self.h_pop.hash(&vk.enc);
+        self.check(&[(vk.p.clone(), q), (-self.e.g1_generator(), proof.0.clone())])
+    }
+    /// Verifies an aggregate signature of the same message `msg` by all of
+    /// `vks`, which costs two pairings regardless of the number of signers.
+    /// It is only available under `Scheme::ProofOfPossession`, and the
+    /// proofs of possession of `vks` must have been checked beforehand.
+    pub fn fast_aggregate_verify(
+        &self,
+        vks: &[VerifyingKey],
+        msg: &[u8],
+        sig: &Signature,
+    ) -> Result<(), Error> {
+        self.require_pop()?;
+        let (first, rest) = vks.split_first().ok_or_else(|| Error::other(ERR_EMPTY))?;
+        let vk = rest.iter().fold(first.p.clone(), |acc, vk| acc + &vk.p);
+        self.check(&[
+            (vk, self.h.hash(msg)),
+            (-self.e.g1_generator(), sig.0.clone()),
+        ])
+    }
+    fn require_pop(&self) -> Result<(), Error> {
+        if self.scheme != Scheme::ProofOfPossession {
+            return Err(Error::other(ERR_SCHEME));
+        }
+        Ok(())
+    }
+    fn check(&self, pairs: &[(Point, G2Point)]) -> Result<(), Error> {
+        if !self.e.multi_pairing(pairs).is_identity() {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        Ok(())
+    }
+}
+
+/// Parses a 32-byte secret key, which must be non-zero and less than `r`.
+fn parse_secret(e: &Bls12381, buf: &[u8]) -> Result<BigInt, Error> {
+    let d = BigUint::from_bytes_be(buf);
+    if buf.len() != SK_SIZE || d.is_zero() || d >= e.order() {
+        return Err(Error::other(ERR_KEY_RANGE));
+    }
+    Ok(d.into())
+}
+
+/// Derives a secret key from the keying material `ikm`. Both variants share
+/// this derivation, so the same `ikm` yields the same secret in each.
+// Reference: draft-irtf-cfrg-bls-signature-05, Section 2.3.
+fn derive_secret(e: &Bls12381, ikm: &[u8]) -> Result<BigInt, Error> {
+    if ikm.len() < SK_SIZE {
+        return Err(Error::other(ERR_IKM_LEN));
+    }
+    let r = BigInt::from_biguint(Sign::Plus, e.order());
+    let mut ikm = ikm.to_vec();
+    ikm.push(0);
+    let mut salt = KEYGEN_SALT.to_vec();
+    loop {
+        salt = Sha256::digest(&salt).to_vec();
+        let mut okm = [0u8; KEYGEN_LEN];
+        Hkdf::<Sha256>::new(Some(&salt), &ikm)
+            .expand(&(KEYGEN_LEN as u16).to_be_bytes(), &mut okm)
+            .unwrap();
+        let d = BigInt::from_bytes_be(Sign::Plus, &okm) % &r;
+        if !d.is_zero() {
+            return Ok(d);
+        }
+    }
+}
+
+const SK_SIZE: usize = 32;
+const VK_SIZE: usize = 48;
+const SIG_SIZE: usize = 96;
+const KEYGEN_LEN: usize = 48;
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+const DST_BASIC: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+const DST_POP: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+const DST_POP_PROOF: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+const ERR_SIG_LEN: &str = "wrong length of signature";
+const ERR_SIG_INVALID: &str = "invalid signature";
+const ERR_KEY_LEN: &str = "wrong length of verifying key";
+const ERR_KEY_RANGE: &str = "secret key is out of range";
+const ERR_KEY_IDENTITY: &str = "verifying key is the identity";
+const ERR_IKM_LEN: &str = "keying material must have at least 32 bytes";
+const ERR_EMPTY: &str = "nothing to aggregate";
+const ERR_MSG_COUNT: &str = "number of keys and messages differ";
+const ERR_DISTINCT: &str = "messages of an aggregate signature must be distinct";
+const ERR_SCHEME: &str = "only available with proofs of possession";
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use super::{Ciphersuite, Scheme, Signature, SigningKey, VerifyingKey};
+
+    const SK: &str = "0d7359d57963ab8fbbde1852dcf553fedbc31f464d80ee7d40ae683122b45070";
+
+    #[test]
+    fn vectors() {
+        // the secret key is the first test vector of EIP-2333, and the rest
+        // were computed with the bls12_381 crate
+        let ikm = hex::decode("c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04").unwrap();
+        let vk = "a2c975348667926acf12f3eecb005044e08a7a9b7d95f30bd281b55445107367a2e5d0558be7943c8bd13f9a1a7036fb";
+        let sig_basic = "81efa43161daa04bbdc75246e6927da06e07b9e71fe4eec9679eb8678f2f82661c314c701a2c2e1d180e6184518756631725e060cf82670743c18fbfaffddd0072b6cf716b9b26d951d197f258236c73cdd545cf536d579878ae8167325270a0";
+        let sig_pop = "839cf6b613d5d5dd4fedfd3ef47b8bbf781f6c35ec6c0b416f0067b8812e36319eabbc8e72b0803ea6516d0da7bbb3ce0f18b611d2f53403aa29e5e68879ea378a10656b02bc93459389d68f4b9462782603894a563a15fefbd9b907625a2f1f";
+        let proof = "8a4728673af25888751078e6023f1092378e219d4405efacab14a5935502e6bb45e4149d6dea817861485baa65fe1c54047ee1024c9f8d8d0215e35197bff3a9586486199dd11e3d4563b20a3b1b8eeb68c84e980d1b729d1b66280fd59e2494";
+
+        let basic = Ciphersuite::new(Scheme::Basic);
+        let sk = basic.key_gen(&ikm).unwrap();
+        assert_eq!(hex::encode(sk.to_bytes()), SK);
+        assert_eq!(hex::encode(sk.verifying_key().to_bytes()), vk);
+        let sig = basic.sign(&sk, b"abc");
+        assert_eq!(hex::encode(sig.to_bytes()), sig_basic);
+        assert!(basic.verify(sk.verifying_key(), b"abc", &sig).is_ok());
+        assert!(basic.verify(sk.verifying_key(), b"abd", &sig).is_err());
+        assert!(basic.pop_prove(&sk).is_err());
+
+        let pop = Ciphersuite::new(Scheme::ProofOfPossession);
+        let sig = pop.sign(&sk, b"abc");
+        assert_eq!(hex::encode(sig.to_bytes()), sig_pop);
+        assert_eq!(hex::encode(pop.pop_prove(&sk).unwrap().to_bytes()), proof);
+        let vk = VerifyingKey::from_bytes(&hex::decode(vk).unwrap()).unwrap();
+        let proof = Signature::from_bytes(&hex::decode(proof).unwrap()).unwrap();
+        assert!(pop.pop_verify(&vk, &proof).is_ok());
+        assert!(pop.pop_verify(&vk, &sig).is_err());
+    }
+
+    #[test]
+    fn aggregate() {
+        let basic = Ciphersuite::new(Scheme::Basic);
+        let sk1 = SigningKey::from_bytes(&hex::decode(SK).unwrap()).unwrap();
+        let sk2 = SigningKey::random(&mut OsRng);
+        let vks = [sk1.verifying_key().clone(), sk2.verifying_key().clone()];
+        let sig = basic
+            .aggregate(&[basic.sign(&sk1, b"one"), basic.sign(&sk2, b"two")])
+            .unwrap();
+        let sig = Signature::from_bytes(&sig.to_bytes()).unwrap();
+        assert!(basic
+            .aggregate_verify(&vks, &[b"one", b"two"], &sig)
+            .is_ok());
+        assert!(basic
+            .aggregate_verify(&vks, &[b"two", b"one"], &sig)
+            .is_err());
+        assert!(basic.aggregate_verify(&vks[..1], &[b"one"], &sig).is_err());
+        assert!(basic.aggregate(&[]).is_err());
+
+        // the basic scheme rejects repeated messages
+        let sig = basic
+            .aggregate(&[basic.sign(&sk1, b"one"), basic.sign(&sk2, b"one")])
+            .unwrap();
+        assert!(basic
+            .aggregate_verify(&vks, &[b"one", b"one"], &sig)
+            .is_err());
+        assert!(basic.fast_aggregate_verify(&vks, b"one", &sig).is_err());
+
+        let pop = Ciphersuite::new(Scheme::ProofOfPossession);
+        let sig = pop
+            .aggregate(&[pop.sign(&sk1, b"one"), pop.sign(&sk2, b"one")])
+            .unwrap();
+        assert!(pop.fast_aggregate_verify(&vks, b"one", &sig).is_ok());
+        assert!(pop.aggregate_verify(&vks, &[b"one", b"one"], &sig).is_ok());
+        assert!(pop.fast_aggregate_verify(&vks[1..], b"one", &sig).is_err());
+        assert!(pop.fast_aggregate_verify(&[], b"one", &sig).is_err());
+    }
+
+    #[test]
+    fn invalid_encodings() {
+        assert!(SigningKey::from_bytes(&[0u8; 32]).is_err());
+        assert!(SigningKey::from_bytes(&[0xffu8; 32]).is_err());
+        let mut inf = vec![0u8; 48];
+        inf[0] = 0xc0;
+        assert!(VerifyingKey::from_bytes(&inf).is_err());
+        let mut inf = vec![0u8; 96];
+        inf[0] = 0xc0;
+        assert!(Signature::from_bytes(&inf).is_ok());
+        assert!(Signature::from_bytes(&inf[..48]).is_err());
+    }
+}
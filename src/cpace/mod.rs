@@ -0,0 +1,330 @@
+//! This is documentation for the `cpace` module.
+//!
+//! The cpace module implements CPace, the balanced password-authenticated
+//! key exchange specified in draft-irtf-cfrg-cpace, in its
+//! initiator-responder setting.
+//!
+//! Both parties derive a secret generator from the password, the channel
+//! identifier and the session identifier, and then run a Diffie-Hellman
+//! exchange with it. Only parties that used the same password agree on the
+//! resulting session key. Each party can attach associated data to its
+//! message, which is authenticated by the key.
+//!
+//! # Exchanging a Key
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::cpace::{ristretto255_sha512, Initiator, Responder};
+//!
+//!  let cs = ristretto255_sha512();
+//!  let (prs, ci, sid) = (b"password", b"channel", b"session");
+//!  let (alice, msg_a) = Initiator::start(&cs, prs, ci, sid, b"ADa", &mut OsRng);
+//!  let (msg_b, key_b) = Responder::respond(&cs, prs, ci, sid, b"ADb", &msg_a, &mut OsRng).unwrap();
+//!  let key_a = alice.finish(&msg_b).unwrap();
+//!  assert_eq!(key_a.isk, key_b.isk);
+//! ```
+
+use digest::Digest;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Sha256, Sha512};
+
+use std::io::Error;
+use std::marker::PhantomData;
+
+use crate::ellipticcurve::{EcPoint, Encode, PointFormat};
+use crate::group::{PrimeGroup, Ristretto255, WeGroup};
+use crate::h2c::{Dst, EncodeToCurve, P256_XMDSHA256_SSWU_NU_, P256_XMDSHA256_SSWU_RO_};
+
+type ScalarMultVfy<G> = fn(&G, &<G as PrimeGroup>::Scalar, &[u8]) -> Result<Vec<u8>, Error>;
+
+/// Ciphersuite is a group together with the hash function and the group
+/// operations that CPace specifies for it. The generator string is padded
+/// up to `s_in_bytes`, the input block size of the hash function.
+pub struct Ciphersuite<G: PrimeGroup, H> {
+    g: G,
+    dsi: &'static [u8],
+    s_in_bytes: usize,
+    /// Maps the generator string to the secret generator.
+    map: fn(&Self, &[u8]) -> G::Elt,
+    /// Encodes an element of the group for the messages of the protocol.
+    encode: fn(&G, &G::Elt) -> Vec<u8>,
+    /// Decodes the element of the peer, multiplies it by the secret scalar,
+    /// and returns the encoding of the shared secret.
+    scalar_mult_vfy: ScalarMultVfy<G>,
+    _h: PhantomData<H>,
+}
+
+/// Returns the ristretto255 ciphersuite with SHA-512.
+pub fn ristretto255_sha512() -> Ciphersuite<Ristretto255, Sha512> {
+    Ciphersuite {
+        g: Ristretto255::new(),
+        dsi: b"CPaceRistretto255",
+        s_in_bytes: 128,
+        map: |cs, gen_str| cs.g.from_uniform_bytes(&Sha512::digest(gen_str)),
+        encode: |g, p| g.encode(p),
+        scalar_mult_vfy: |g, y, buf| {
+            let p = g.decode(buf)? * y;
+            if p == g.identity() {
+                return Err(Error::other(ERR_IDENTITY));
+            }
+            Ok(g.encode(&p))
+        },
+        _h: PhantomData,
+    }
+}
+
+/// Returns the P-256 ciphersuite with SHA-256, whose generators are
+/// obtained with the `P256_XMD:SHA-256_SSWU_NU_` encoding. Elements are
+/// encoded in uncompressed form, and the shared secret is the
+/// x-coordinate.
+pub fn p256_sha256() -> Ciphersuite<WeGroup, Sha256> {
+    Ciphersuite {
+        g: WeGroup::new(P256_XMDSHA256_SSWU_RO_),
+        dsi: b"CPaceP256_XMD:SHA-256_SSWU_NU_",
+        s_in_bytes: 64,
+        map: |cs, gen_str| {
+            let dst = Dst::new(&[cs.dsi, b"_DST"].concat()).unwrap();
+            P256_XMDSHA256_SSWU_NU_.get(&dst).encode(gen_str)
+        },
+        encode: |_, p| p.encode(PointFormat::Uncompressed),
+        scalar_mult_vfy: |g, y, buf| {
+            if buf.first() != Some(&0x04) {
+                return Err(Error::other(ERR_ENCODING));
+            }
+            let p = g.decode(buf)? * y;
+            if p.is_identity() {
+                return Err(Error::other(ERR_IDENTITY));
+            }
+            Ok(p.encode(PointFormat::Compressed)[1..].to_vec())
+        },
+        _h: PhantomData,
+    }
+}
+
+impl<G: PrimeGroup, H: Digest> Ciphersuite<G, H> {
+    pub fn group(&self) -> &G {
+        &self.g
+    }
+    /// Returns the secret generator derived from the password-related
+    /// string `prs`, the channel identifier `ci` and the session identifier
+    /// `sid`.
+    // based on draft-irtf-cfrg-cpace, Section 7
+    pub fn calculate_generator(&self, prs: &[u8], ci: &[u8], sid: &[u8]) -> G::Elt {
+        let used = 1 + prepend_len(prs).len() + prepend_len(self.dsi).len();
+        let zpad = vec![0u8; self.s_in_bytes.saturating_sub(used)];
+        let gen_str = lv_cat(&[self.dsi, prs, &zpad, ci, sid]);
+        (self.map)(self, &gen_str)
+    }
+    /// Derives the intermediate session key from the shared secret `k` and
+    /// the messages of both parties.
+    fn isk(&self, sid: &[u8], k: &[u8], msg_a: &[u8], msg_b: &[u8]) -> SessionKey {
+        let label = [self.dsi, b"_ISK"].concat();
+        let isk = H::new()
+            .chain_update(lv_cat(&[&label, sid, k]))
+            .chain_update(msg_a)
+            .chain_update(msg_b)
+            .finalize()
+            .to_vec();
+        SessionKey { isk }
+    }
+}
+
+/// SessionKey is the intermediate session key output by CPace, which must
+/// be confirmed or expanded before use.
+#[derive(Clone, PartialEq)]
+pub struct SessionKey {
+    pub isk: Vec<u8>,
+}
+
+/// Initiator is the state of the party that sends the first message.
+pub struct Initiator<'a, G: PrimeGroup, H> {
+    cs: &'a Ciphersuite<G, H>,
+    sid: Vec<u8>,
+    y: G::Scalar,
+    msg: Vec<u8>,
+}
+
+impl<'a, G: PrimeGroup, H: Digest> Initiator<'a, G, H> {
+    /// Starts an exchange. It returns the state of the initiator, and the
+    /// message to send to the responder, which carries the associated data
+    /// `ad`. The ephemeral secret is sampled from `rng`.
+    pub fn start<R: RngCore + CryptoRng>(
+        cs: &'a Ciphersuite<G, H>,
+        prs: &[u8],
+        ci: &[u8],
+        sid: &[u8],
+        ad: &[u8],
+        rng: &mut R,
+    ) -> (Initiator<'a, G, H>, Vec<u8>) {
+        let g = cs.calculate_generator(prs, ci, sid);
+        let y = cs.g.random_scalar(rng);
+        let msg = lv_cat(&[&(cs.encode)(&cs.g, &(g * &y)), ad]);
+        let st = Initiator {
+            cs,
+            sid: sid.to_vec(),
+            y,
+            msg: msg.clone(),
+        };
+        (st, msg)
+    }
+    /// Finishes the exchange with the message of the responder. It fails if
+    /// the message is malformed or contains an invalid element.
+    pub fn finish(self, msg_b: &[u8]) -> Result<SessionKey, Error> {
+        let (yb, _) = parse_message(msg_b)?;
+        let k = (self.cs.scalar_mult_vfy)(&self.cs.g, &self.y, &yb)?;
+        Ok(self.cs.isk(&self.sid, &k, &self.msg, msg_b))
+    }
+}
+
+/// Responder is the party that answers the message of the initiator.
+pub struct Responder;
+
+impl Responder {
+    /// Answers the message of the initiator. It returns the message to send
+    /// back, which carries the associated data `ad`, and the session key.
+    /// The ephemeral secret is sampled from `rng`. It fails if the message
+    /// of the initiator is malformed or contains an invalid element.
+    pub fn respond<G: PrimeGroup, H: Digest, R: RngCore + CryptoRng>(
+        cs: &Ciphersuite<G, H>,
+        prs: &[u8],
+        ci: &[u8],
+        sid: &[u8],
+        ad: &[u8],
+        msg_a: &[u8],
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, SessionKey), Error> {
+        let (ya, _) = parse_message(msg_a)?;
+        let g = cs.calculate_generator(prs, ci, sid);
+        let y = cs.g.random_scalar(rng);
+        let k = (cs.scalar_mult_vfy)(&cs.g, &y, &ya)?;
+        let msg_b = lv_cat(&[&(cs.encode)(&cs.g, &(g * &y)), ad]);
+        let key = cs.isk(sid, &k, msg_a, &msg_b);
+        Ok((msg_b, key))
+    }
+}
+
+/// Returns the associated data of a message of the protocol.
+pub fn associated_data(msg: &[u8]) -> Result<Vec<u8>, Error> {
+    let (_, ad) = parse_message(msg)?;
+    Ok(ad)
+}
+
+/// Returns `data` prefixed by its length encoded in LEB128.
+fn prepend_len(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + 2);
+    let mut len = data.len();
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+    out.extend_from_slice(data);
+    out
+}
+
+fn lv_cat(parts: &[&[u8]]) -> Vec<u8> {
+    parts.iter().flat_map(|p| prepend_len(p)).collect()
+}
+
+/// Splits `buf` into its first length-prefixed field and the rest.
+fn read_field(buf: &[u8]) -> Result<(&[u8], &[u8]), Error> {
+    let mut len = 0usize;
+    for (i, &byte) in buf.iter().enumerate().take(MAX_LEB128_BYTES) {
+        len |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            let rest = &buf[i + 1..];
+            if rest.len() < len {
+                break;
+            }
+            return Ok(rest.split_at(len));
+        }
+    }
+    Err(Error::other(ERR_ENCODING))
+}
+
+/// Returns the element and the associated data of a message.
+fn parse_message(msg: &[u8]) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let (y, rest) = read_field(msg)?;
+    let (ad, rest) = read_field(rest)?;
+    if !rest.is_empty() {
+        return Err(Error::other(ERR_ENCODING));
+    }
+    Ok((y.to_vec(), ad.to_vec()))
+}
+
+const MAX_LEB128_BYTES: usize = 4;
+const ERR_ENCODING: &str = "invalid encoding of message";
+const ERR_IDENTITY: &str = "shared secret is the identity";
+
+#[cfg(test)]
+mod tests {
+    use digest::Digest;
+    use rand_core::OsRng;
+
+    use super::{associated_data, lv_cat, p256_sha256, prepend_len, ristretto255_sha512};
+    use super::{Ciphersuite, Initiator, Responder};
+    use crate::group::PrimeGroup;
+
+    fn check_exchange<G: PrimeGroup, H: Digest>(cs: &Ciphersuite<G, H>, elt_len: usize) {
+        let (ci, sid) = (b"channel".as_ref(), b"session".as_ref());
+        let (alice, msg_a) = Initiator::start(cs, b"password", ci, sid, b"ADa", &mut OsRng);
+        let (msg_b, key_b) =
+            Responder::respond(cs, b"password", ci, sid, b"ADb", &msg_a, &mut OsRng).unwrap();
+        assert_eq!(msg_a.len(), 1 + elt_len + 4);
+        assert_eq!(associated_data(&msg_b).unwrap(), b"ADb");
+        assert!(alice.finish(&msg_b).unwrap() == key_b);
+        assert_eq!(key_b.isk.len(), <H as Digest>::output_size());
+
+        // a wrong password or session identifier gives unrelated keys
+        let (alice, msg_a) = Initiator::start(cs, b"password", ci, sid, b"", &mut OsRng);
+        let (msg_b, key_b) =
+            Responder::respond(cs, b"passw0rd", ci, sid, b"", &msg_a, &mut OsRng).unwrap();
+        assert!(alice.finish(&msg_b).unwrap() != key_b);
+        let (alice, msg_a) = Initiator::start(cs, b"password", ci, sid, b"", &mut OsRng);
+        let (msg_b, key_b) =
+            Responder::respond(cs, b"password", ci, b"other", b"", &msg_a, &mut OsRng).unwrap();
+        assert!(alice.finish(&msg_b).unwrap() != key_b);
+
+        // tampering with the associated data changes the key
+        let (alice, msg_a) = Initiator::start(cs, b"password", ci, sid, b"ADa", &mut OsRng);
+        let (msg_b, key_b) =
+            Responder::respond(cs, b"password", ci, sid, b"ADb", &msg_a, &mut OsRng).unwrap();
+        let mut forged = msg_b[..msg_b.len() - 3].to_vec();
+        forged.extend_from_slice(b"ADc");
+        assert!(alice.finish(&forged).unwrap() != key_b);
+
+        // malformed messages and the identity are rejected
+        let g = cs.group();
+        let identity = lv_cat(&[&g.encode(&g.identity()), b""]);
+        assert!(Responder::respond(cs, b"password", ci, sid, b"", &identity, &mut OsRng).is_err());
+        assert!(
+            Responder::respond(cs, b"password", ci, sid, b"", &msg_a[..10], &mut OsRng).is_err()
+        );
+        let mut trailing = msg_a.clone();
+        trailing.push(0);
+        assert!(Responder::respond(cs, b"password", ci, sid, b"", &trailing, &mut OsRng).is_err());
+        let mut invalid = msg_a;
+        invalid[1] ^= 0xff;
+        invalid[elt_len] ^= 0xff;
+        assert!(Responder::respond(cs, b"password", ci, sid, b"", &invalid, &mut OsRng).is_err());
+    }
+
+    #[test]
+    fn exchange() {
+        check_exchange(&ristretto255_sha512(), 32);
+        check_exchange(&p256_sha256(), 65);
+    }
+
+    #[test]
+    fn encoding() {
+        assert_eq!(prepend_len(b""), [0]);
+        assert_eq!(prepend_len(&[7; 127])[..1], [127]);
+        assert_eq!(prepend_len(&[7; 128])[..2], [0x80, 0x01]);
+        assert_eq!(prepend_len(&[7; 300])[..2], [0xac, 0x02]);
+        assert_eq!(lv_cat(&[b"a", b"bc"]), b"\x01a\x02bc");
+    }
+}
@@ -0,0 +1,198 @@
+//! This is documentation for the `differential` module.
+//!
+//! The differential module compares the crate against independent
+//! implementations on random inputs: `p256` and `k256` for scalar
+//! multiplication, ECDH, and ECDSA, `ed25519-dalek` for Ed25519, and
+//! `x25519-dalek` for X25519. Known-answer tests only cover a handful of
+//! inputs, whereas a wrong carry or reduction may show up on a few scalars
+//! in a billion; every run here draws fresh inputs. The tests need the
+//! `differential-tests` feature:
+//!
+//! ```text
+//!  cargo test --release --features differential-tests differential
+//! ```
+
+use num_bigint::{BigInt, Sign};
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+
+use std::convert::TryInto;
+
+use crate::ecdh::DiffieHellman;
+use crate::ecdsa::{Nonce, Signature, SigningKey, VerifyingKey};
+use crate::eddsa::{self, Algorithm};
+use crate::ellipticcurve::{Decode, EllipticCurve, Encode, PointFormat};
+use crate::instances::{GetCurve, P256, SECP256K1};
+use crate::weierstrass;
+use crate::xdh::x25519;
+
+/// Number of random inputs of each test.
+const ROUNDS: usize = 16;
+
+fn random_bytes(n: usize) -> Vec<u8> {
+    let mut buf = vec![0u8; n];
+    OsRng.fill_bytes(&mut buf);
+    buf
+}
+
+fn scalar(e: &weierstrass::Curve, d: &[u8]) -> weierstrass::Scalar {
+    e.new_scalar(BigInt::from_bytes_be(Sign::Plus, d))
+}
+
+/// Returns a message of random length.
+fn random_msg() -> Vec<u8> {
+    random_bytes(OsRng.next_u32() as usize % 100)
+}
+
+/// Defines the tests for a curve supported by a crate of the RustCrypto
+/// `elliptic-curve` family, whose APIs are identical. Signatures of `k256`
+/// always have low `s` values whereas those of `p256` do not, so
+/// signatures are normalized before being compared or passed to them.
+macro_rules! rust_crypto_tests {
+    ($name:ident, $id:expr, $rc:ident) => {
+        mod $name {
+            use ::$rc::ecdsa::signature::{Signer, Verifier};
+            use ::$rc::elliptic_curve::sec1::ToEncodedPoint;
+            use ::$rc::{ecdh, ecdsa, ProjectivePoint, SecretKey};
+
+            use super::*;
+
+            /// Returns a secret key of both crates, which is a random
+            /// integer in [1, r).
+            fn keys() -> (Vec<u8>, SecretKey) {
+                loop {
+                    let d = random_bytes(32);
+                    if let Ok(sk) = SecretKey::from_slice(&d) {
+                        return (d, sk);
+                    }
+                }
+            }
+
+            #[test]
+            fn scalar_mult() {
+                let e = $id.get();
+                for _ in 0..ROUNDS {
+                    let (d, sk) = keys();
+                    let k = scalar(&e, &d);
+                    let p = e.get_generator() * &k;
+                    let want = sk.public_key().to_encoded_point(false);
+                    assert_eq!(
+                        p.encode(PointFormat::Uncompressed),
+                        want.as_bytes(),
+                        "[k]G for k={:02x?}",
+                        d
+                    );
+
+                    // a point other than the generator
+                    let (d2, sk2) = keys();
+                    let k2 = scalar(&e, &d2);
+                    let q = p * &k2;
+                    let want = ProjectivePoint::from(*sk.public_key().as_affine())
+                        * *sk2.to_nonzero_scalar();
+                    let want = want.to_affine().to_encoded_point(true);
+                    assert_eq!(
+                        q.encode(PointFormat::Compressed),
+                        want.as_bytes(),
+                        "[k2]([k]G) for k={:02x?}",
+                        d
+                    );
+                }
+            }
+
+            #[test]
+            fn ecdh() {
+                let e = $id.get();
+                for _ in 0..ROUNDS {
+                    let (d, sk) = keys();
+                    let (_, peer) = keys();
+                    let pk = peer.public_key().to_encoded_point(false);
+                    let k = scalar(&e, &d);
+                    let p = e.decode(pk.as_bytes()).unwrap();
+                    let got = e.diffie_hellman(&k, &p).unwrap();
+                    let want =
+                        ecdh::diffie_hellman(sk.to_nonzero_scalar(), peer.public_key().as_affine());
+                    assert_eq!(got.as_bytes(), &want.raw_secret_bytes()[..]);
+                }
+            }
+
+            #[test]
+            fn ecdsa() {
+                let e = $id.get();
+                for _ in 0..ROUNDS {
+                    let (d, _) = keys();
+                    let msg = random_msg();
+                    let sk = SigningKey::from_bytes(&e, &d).unwrap();
+                    let rc_sk = ecdsa::SigningKey::from_slice(&d).unwrap();
+                    let vk = VerifyingKey::from_bytes(
+                        &e,
+                        &sk.verifying_key().to_bytes(PointFormat::Uncompressed),
+                    );
+                    let vk = vk.unwrap();
+                    let rc_vk = rc_sk.verifying_key();
+
+                    // deterministic signatures are equal
+                    let sig = sk.sign_with::<Sha256, _>(&msg, Nonce::Deterministic, &mut OsRng);
+                    let rc_sig: ecdsa::Signature = rc_sk.sign(&msg);
+                    let rc_sig = rc_sig.normalize_s().unwrap_or(rc_sig);
+                    let sig_bytes = sig.normalize_s().to_bytes();
+                    assert_eq!(sig_bytes, &rc_sig.to_bytes()[..], "msg={:02x?}", msg);
+
+                    // random signatures are accepted by the other crate
+                    let sig = sk
+                        .sign::<Sha256, _>(&msg, &mut OsRng)
+                        .normalize_s()
+                        .to_bytes();
+                    let sig = ecdsa::Signature::from_slice(&sig).unwrap();
+                    assert!(rc_vk.verify(&msg, &sig).is_ok(), "msg={:02x?}", msg);
+                    let sig = Signature::from_bytes(&e, &rc_sig.to_bytes()).unwrap();
+                    assert!(vk.verify::<Sha256>(&msg, &sig).is_ok(), "msg={:02x?}", msg);
+                }
+            }
+        }
+    };
+}
+
+rust_crypto_tests!(with_p256, P256, p256);
+rust_crypto_tests!(with_k256, SECP256K1, k256);
+
+#[test]
+fn ed25519() {
+    use ed25519_dalek::{Signer, Verifier};
+    for _ in 0..ROUNDS {
+        let seed: [u8; 32] = random_bytes(32).try_into().unwrap();
+        let msg = random_msg();
+        let sk = eddsa::SigningKey::from_seed(Algorithm::Ed25519, &seed).unwrap();
+        let dalek_sk = ed25519_dalek::SigningKey::from_bytes(&seed);
+        let pk = sk.verifying_key().to_bytes();
+        assert_eq!(
+            pk,
+            dalek_sk.verifying_key().to_bytes(),
+            "seed={:02x?}",
+            seed
+        );
+
+        let sig = sk.sign(&msg);
+        let dalek_sig = dalek_sk.sign(&msg);
+        assert_eq!(sig.to_bytes(), dalek_sig.to_bytes(), "msg={:02x?}", msg);
+        assert!(dalek_sk.verifying_key().verify(&msg, &dalek_sig).is_ok());
+        let sig = eddsa::Signature::from_bytes(Algorithm::Ed25519, &dalek_sig.to_bytes());
+        assert!(sk.verifying_key().verify(&msg, &sig.unwrap()).is_ok());
+    }
+}
+
+#[test]
+fn x25519_dalek() {
+    let mut base = [0u8; 32];
+    base[0] = 9;
+    for _ in 0..ROUNDS {
+        let k: [u8; 32] = random_bytes(32).try_into().unwrap();
+        let u: [u8; 32] = random_bytes(32).try_into().unwrap();
+        assert_eq!(
+            x25519(&k, &base),
+            x25519_dalek::x25519(k, base),
+            "k={:02x?}",
+            k
+        );
+        assert_eq!(x25519(&k, &u), x25519_dalek::x25519(k, u), "u={:02x?}", u);
+    }
+}
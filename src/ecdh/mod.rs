@@ -0,0 +1,445 @@
+//! This is documentation for the `ecdh` module.
+//!
+//! The ecdh module implements the Elliptic Curve Diffie-Hellman key
+//! agreement over Weierstrass curves.
+//!
+//! Public keys are exchanged as x-coordinates only, which halves their size
+//! with respect to uncompressed points. The shared secret is computed with
+//! a Montgomery ladder on x-coordinates, so the y-coordinate of the peer is
+//! never needed.
+//!
+//! # Validation of Public Keys
+//!
+//! The public key of the peer is validated before it is multiplied by the
+//! secret scalar, and `PeerValidation` describes the checks that are
+//! enforced for a given curve. Points must lie on the curve, which stops
+//! invalid-curve attacks, and x-only keys must not be the x-coordinate of
+//! a point of the quadratic twist. On curves with cofactor larger than one,
+//! points outside the subgroup of prime order are rejected too, so a peer
+//! cannot learn the secret scalar modulo the cofactor. The X25519 and X448
+//! functions of the `xdh` module enforce different checks, see
+//! `xdh::XDH_PEER_VALIDATION`.
+//!
+//! # Key Agreement
+//!
+//! ```
+//!  use redox_ecc::ecdh::{diffie_hellman_x, public_key_x};
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use sha2::Sha256;
+//!
+//!  let ec = P256.get();
+//!  let (a, b) = (ec.new_scalar(7.into()), ec.new_scalar(11.into()));
+//!  let (pk_a, pk_b) = (public_key_x(&ec, &a), public_key_x(&ec, &b));
+//!  let k_a = diffie_hellman_x(&ec, &a, &pk_b).unwrap();
+//!  let k_b = diffie_hellman_x(&ec, &b, &pk_a).unwrap();
+//!  assert_eq!(k_a.as_bytes(), k_b.as_bytes());
+//!  let key = k_a.extract_and_expand::<Sha256>(b"", b"example", 32).unwrap();
+//! ```
+
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use hkdf::SimpleHkdf;
+use num_bigint::BigInt;
+use num_traits::identities::One;
+
+use std::io::Error;
+
+use crate::edwards;
+use crate::ellipticcurve::{EcPoint, EcScalar, EllipticCurve, Encode, PointFormat};
+use crate::field::{CMov, Field, Sqrt};
+use crate::montgomery;
+use crate::ops::Serialize;
+use crate::primefield::FpElt;
+use crate::weierstrass::{self, Curve, Scalar};
+
+/// SharedSecret is the output of a Diffie-Hellman key agreement, i.e., the
+/// x-coordinate of the shared point encoded in big-endian order.
+///
+/// The raw bytes are not uniformly distributed, so they must not be used as
+/// a key directly; `extract_and_expand` derives keys from them.
+#[derive(Clone, PartialEq)]
+pub struct SharedSecret(Vec<u8>);
+
+impl SharedSecret {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+    /// Derives `len` bytes of key material from the shared secret using
+    /// HKDF with the digest `D`, where an empty `salt` stands for no salt.
+    /// It fails if `len` is larger than 255 times the digest size.
+    // based on RFC 5869, Section 2
+    pub fn extract_and_expand<D>(
+        &self,
+        salt: &[u8],
+        info: &[u8],
+        len: usize,
+    ) -> Result<Vec<u8>, Error>
+    where
+        D: Digest + BlockSizeUser + Clone,
+    {
+        let salt = if salt.is_empty() { None } else { Some(salt) };
+        let mut out = vec![0u8; len];
+        SimpleHkdf::<D>::new(salt, &self.0)
+            .expand(info, &mut out)
+            .map_err(|_| Error::other(ERR_KDF_LEN))?;
+        Ok(out)
+    }
+}
+
+/// PeerValidation lists the checks applied to the public key of the peer
+/// before it is used in a key agreement. Each field is true if the check is
+/// enforced.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct PeerValidation {
+    /// The key is a point of the curve and not of another curve defined
+    /// over the same field.
+    pub on_curve: bool,
+    /// The key is in the subgroup of prime order. It is only enforced for
+    /// curves with cofactor larger than one.
+    pub in_subgroup: bool,
+    /// The x-only key is not the x-coordinate of a point of the quadratic
+    /// twist of the curve.
+    pub not_on_twist: bool,
+    /// The shared point is not of small order, so the shared secret is not
+    /// a value known in advance.
+    pub not_small_order: bool,
+}
+
+/// DiffieHellman computes shared secrets between a scalar and a point of a
+/// curve, so protocols such as ECIES can be written once for all curves.
+pub trait DiffieHellman: EllipticCurve {
+    /// Returns the shared secret derived from `[k]P`. For Weierstrass and
+    /// Montgomery curves it is the x-coordinate, as in SEC 1, Section 3.3.1,
+    /// and for Edwards curves it is the encoding of the point. It fails if
+    /// `P` does not pass the checks of `peer_validation`.
+    fn diffie_hellman(&self, k: &Self::Scalar, p: &Self::Point) -> Result<SharedSecret, Error>;
+    /// Returns the checks enforced by `diffie_hellman` on the public key of
+    /// the peer, which depend on the cofactor of the curve.
+    fn peer_validation(&self) -> PeerValidation {
+        PeerValidation {
+            on_curve: true,
+            in_subgroup: !self.get_cofactor().is_one(),
+            not_on_twist: true,
+            not_small_order: true,
+        }
+    }
+}
+
+impl DiffieHellman for weierstrass::Curve {
+    fn diffie_hellman(&self, k: &Scalar, p: &weierstrass::Point) -> Result<SharedSecret, Error> {
+        validate_peer(self, p)?;
+        let q = shared_point(p * k)?;
+        Ok(SharedSecret(
+            q.encode(PointFormat::Compressed)[1..].to_vec(),
+        ))
+    }
+}
+
+impl DiffieHellman for montgomery::Curve {
+    fn diffie_hellman(
+        &self,
+        k: &montgomery::Scalar,
+        p: &montgomery::Point,
+    ) -> Result<SharedSecret, Error> {
+        validate_peer(self, p)?;
+        let q = shared_point(p * k)?;
+        Ok(SharedSecret(
+            q.encode(PointFormat::Compressed)[1..].to_vec(),
+        ))
+    }
+}
+
+impl DiffieHellman for edwards::Curve {
+    fn diffie_hellman(
+        &self,
+        k: &edwards::Scalar,
+        p: &edwards::Point,
+    ) -> Result<SharedSecret, Error> {
+        validate_peer(self, p)?;
+        let q = shared_point(p * k)?;
+        Ok(SharedSecret(q.encode(PointFormat::Compressed)))
+    }
+}
+
+/// Applies the checks of `e.peer_validation()` that concern the point `p`;
+/// the shared point is checked by `shared_point`.
+fn validate_peer<E>(e: &E, p: &E::Point) -> Result<(), Error>
+where
+    E: DiffieHellman,
+    E::Point: Clone,
+{
+    if !e.is_on_curve(p) {
+        return Err(Error::other(ERR_PEER_KEY));
+    }
+    if e.peer_validation().in_subgroup && !in_subgroup(e, p) {
+        return Err(Error::other(ERR_SUBGROUP));
+    }
+    Ok(())
+}
+
+/// Determines whether `[r]P` is the identity, where `r` is the order of the
+/// curve. Scalars are reduced modulo `r`, so `[r]P` is computed as
+/// `[r-1]P + P`.
+fn in_subgroup<E>(e: &E, p: &E::Point) -> bool
+where
+    E: EllipticCurve,
+    E::Point: Clone,
+{
+    let r_minus_1 = e.new_scalar(BigInt::from(-1));
+    p.clone() * &r_minus_1 == -p.clone()
+}
+
+fn shared_point<S: EcScalar, P: EcPoint<S>>(q: P) -> Result<P, Error> {
+    if q.is_small_order() {
+        return Err(Error::other(ERR_IDENTITY));
+    }
+    Ok(q)
+}
+
+/// Returns the x-coordinate of `[k]G`, which is the public key for the
+/// secret `k`, encoded in big-endian order with as many bytes as the field.
+pub fn public_key_x(e: &Curve, k: &Scalar) -> Vec<u8> {
+    let gx = e.get_generator().x().expect(ERR_ZERO_KEY);
+    let x = ladder_x(e, k, &gx).expect(ERR_ZERO_KEY);
+    x.to_bytes_be()
+}
+
+/// Computes the shared secret between the secret `k` and the x-only public
+/// key of the peer. It fails if `peer` does not pass the checks of
+/// `e.peer_validation()`, i.e., if it is not the x-coordinate of a point of
+/// `e` in the subgroup of prime order, or if the shared point is the
+/// identity.
+pub fn diffie_hellman_x(e: &Curve, k: &Scalar, peer: &[u8]) -> Result<SharedSecret, Error> {
+    let f = e.get_field();
+    let x = f
+        .from_canonical_be(peer)
+        .map_err(|_| Error::other(ERR_PEER_KEY))?;
+    // the x-coordinate of a point of the quadratic twist also has a valid
+    // encoding, those points must be rejected
+    let yy = &x * &x * &x + &e.a * &x + &e.b;
+    if !yy.is_square() {
+        return Err(Error::other(ERR_PEER_KEY));
+    }
+    // both points with this x-coordinate are in the subgroup or neither is
+    if e.peer_validation().in_subgroup && !in_subgroup(e, &e.new_point(x.clone(), yy.sqrt())) {
+        return Err(Error::other(ERR_SUBGROUP));
+    }
+    match ladder_x(e, k, &x) {
+        Some(x) => Ok(SharedSecret(x.to_bytes_be())),
+        None => Err(Error::other(ERR_IDENTITY)),
+    }
+}
+
+/// Returns the x-coordinate of `[k]P` where `x` is the x-coordinate of `P`,
+/// or `None` if the result is the identity. The ladder keeps `R1 - R0 = P`
+/// and uses the projective formulas of Brier and Joye for the differential
+/// addition in additive form, which work even if `x` is zero.
+fn ladder_x(e: &Curve, k: &Scalar, x: &FpElt) -> Option<FpElt> {
+    let f = e.get_field();
+    let (mut x0, mut z0) = (f.one(), f.zero());
+    let (mut x1, mut z1) = (x.clone(), f.one());
+    let mut swap = false;
+    for byte in k.to_bytes_be() {
+        for i in (0..8).rev() {
+            let bit = (byte >> i) & 1 == 1;
+            swap ^= bit;
+            cswap(&mut x0, &mut x1, swap);
+            cswap(&mut z0, &mut z1, swap);
+            swap = bit;
+            let (xa, za) = xadd(e, x, (&x0, &z0), (&x1, &z1));
+            let (xd, zd) = xdbl(e, (&x0, &z0));
+            x1 = xa;
+            z1 = za;
+            x0 = xd;
+            z0 = zd;
+        }
+    }
+    cswap(&mut x0, &mut x1, swap);
+    cswap(&mut z0, &mut z1, swap);
+    if z0 == f.zero() {
+        return None;
+    }
+    Some(x0 / z0)
+}
+
+/// Returns `[2]P` given `P = (X:Z)`.
+fn xdbl(e: &Curve, (x, z): (&FpElt, &FpElt)) -> (FpElt, FpElt) {
+    let xx = x * x;
+    let zz = z * z;
+    let azz = &e.a * &zz;
+    let t0 = &xx - &azz;
+    let x2 = &t0 * &t0 - double(&double(&double(&(x * &zz * z * &e.b))));
+    let z2 = double(&double(&(z * (x * (xx + azz) + &e.b * zz * z))));
+    (x2, z2)
+}
+
+/// Returns `P + Q` given `P = (X1:Z1)`, `Q = (X2:Z2)` and the affine
+/// x-coordinate `xd` of `P - Q`, which must not be the identity.
+fn xadd(
+    e: &Curve,
+    xd: &FpElt,
+    (x1, z1): (&FpElt, &FpElt),
+    (x2, z2): (&FpElt, &FpElt),
+) -> (FpElt, FpElt) {
+    let x1z2 = x1 * z2;
+    let x2z1 = x2 * z1;
+    let z1z2 = z1 * z2;
+    let t0 = &x1z2 - &x2z1;
+    let d = &t0 * &t0;
+    let n = double(&((&x1z2 + &x2z1) * (x1 * x2 + &e.a * &z1z2)))
+        + double(&double(&(&e.b * &z1z2 * &z1z2)));
+    (n - xd * &d, d)
+}
+
+fn double(a: &FpElt) -> FpElt {
+    a + a
+}
+
+fn cswap(a: &mut FpElt, b: &mut FpElt, swap: bool) {
+    let t = FpElt::cmov(a, b, swap);
+    *b = FpElt::cmov(b, a, swap);
+    *a = t;
+}
+
+const ERR_PEER_KEY: &str = "public key must be the x-coordinate of a point of the curve";
+const ERR_SUBGROUP: &str = "public key must be in the subgroup of prime order";
+const ERR_IDENTITY: &str = "shared point is of small order";
+const ERR_ZERO_KEY: &str = "secret key must not be zero";
+const ERR_KDF_LEN: &str = "too many bytes requested from the key derivation function";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, Sign};
+    use sha2::{Sha256, Sha512};
+
+    use super::{diffie_hellman_x, public_key_x, DiffieHellman, SharedSecret};
+    use crate::ellipticcurve::{EllipticCurve, Encode, PointFormat};
+    use crate::field::{Field, Sqrt};
+    use crate::instances::{GetCurve, BLS12381G1, CURVE25519, EDWARDS25519, P256, P384, SECP256K1};
+
+    #[test]
+    fn cavs_vector() {
+        // ECC CDH Primitive Test Vectors for P-256, COUNT = 0, from NIST CAVS 14.1.
+        let e = P256.get();
+        let peer = "700c48f77f56584c5cc632ca65640db91b6bacce3a4df6b42ce7cc838833d287";
+        let d = "7d7dc5f71eb29ddaf80d6214632eeae03d9058af1fb6d22ed80badb62bc1a534";
+        let z = "46fc62106420ff012e54a434fbdd2d25ccc5852060561e68040dd7778997bd7b";
+        let d = e.new_scalar(BigInt::from_bytes_be(Sign::Plus, &hex::decode(d).unwrap()));
+        let got = diffie_hellman_x(&e, &d, &hex::decode(peer).unwrap()).unwrap();
+        assert_eq!(hex::encode(got.as_bytes()), z);
+    }
+
+    #[test]
+    fn ladder() {
+        for &id in [P256, P384, SECP256K1].iter() {
+            let e = id.get();
+            let g = e.get_generator();
+            for k in [1i64, 2, 3, 0x1234_5678_9abc, -1].iter() {
+                let k = e.new_scalar(BigInt::from(*k));
+                let want = (&g * &k).encode(PointFormat::Compressed)[1..].to_vec();
+                assert_eq!(public_key_x(&e, &k), want, "curve: {} k: {}", id, k);
+            }
+            let a = e.new_scalar(BigInt::from(0x0bad_cafe));
+            let b = e.new_scalar(BigInt::from(0x00de_caf0));
+            let k_a = diffie_hellman_x(&e, &a, &public_key_x(&e, &b)).unwrap();
+            let k_b = diffie_hellman_x(&e, &b, &public_key_x(&e, &a)).unwrap();
+            assert!(k_a == k_b);
+        }
+    }
+
+    #[test]
+    fn invalid_peer() {
+        let e = P256.get();
+        let k = e.new_scalar(BigInt::from(5));
+        let (_, p) = e.get_field().get_modulus().to_bytes_be();
+        assert!(diffie_hellman_x(&e, &k, &p).is_err());
+        // x = 1 is not the x-coordinate of a point of P-256
+        let mut x = vec![0u8; 32];
+        x[31] = 1;
+        assert!(diffie_hellman_x(&e, &k, &x).is_err());
+        assert!(diffie_hellman_x(&e, &k, &x[1..]).is_err());
+        let zero = e.new_scalar(BigInt::from(0));
+        let pk = public_key_x(&e, &k);
+        assert!(diffie_hellman_x(&e, &zero, &pk).is_err());
+    }
+
+    #[test]
+    fn subgroup() {
+        let e = P256.get();
+        assert!(!e.peer_validation().in_subgroup);
+        let k = e.new_scalar(BigInt::from(5));
+        assert!(e.diffie_hellman(&k, &e.get_generator()).is_ok());
+
+        // adding a point of order two leaves the subgroup of prime order
+        let e = EDWARDS25519.get();
+        let f = e.get_field();
+        assert!(e.peer_validation().in_subgroup);
+        let k = e.new_scalar(BigInt::from(5));
+        let g = e.get_generator();
+        let t2 = e.new_point(f.zero(), -f.one());
+        assert!(e.diffie_hellman(&k, &g).is_ok());
+        assert!(e.diffie_hellman(&k, &(&g + &t2)).is_err());
+
+        let e = CURVE25519.get();
+        let f = e.get_field();
+        assert!(e.peer_validation().in_subgroup);
+        let k = e.new_scalar(BigInt::from(5));
+        let g = e.get_generator();
+        let t2 = e.new_point(f.zero(), f.zero());
+        assert!(e.diffie_hellman(&k, &g).is_ok());
+        assert!(e.diffie_hellman(&k, &(&g + &t2)).is_err());
+
+        // points of BLS12-381 found by increasing x are outside of G1
+        let e = BLS12381G1.get();
+        let f = e.get_field();
+        assert!(e.peer_validation().in_subgroup);
+        let k = e.new_scalar(BigInt::from(5));
+        let g = e.get_generator();
+        let mut x = f.zero();
+        while !(&x * &x * &x + &e.b).is_square() {
+            x += f.one();
+        }
+        let y = (&x * &x * &x + &e.b).sqrt();
+        let p = e.new_point(x, y);
+        assert!(e.diffie_hellman(&k, &g).is_ok());
+        assert!(e.diffie_hellman(&k, &p).is_err());
+        let pk = public_key_x(&e, &k);
+        assert!(diffie_hellman_x(&e, &k, &pk).is_ok());
+        assert!(diffie_hellman_x(&e, &k, &p.encode(PointFormat::Compressed)[1..]).is_err());
+    }
+
+    #[test]
+    fn extract_and_expand() {
+        // Test case 1 taken from RFC 5869, Appendix A.1.
+        let ikm = SharedSecret(vec![0x0b; 22]);
+        let salt = hex::decode("000102030405060708090a0b0c").unwrap();
+        let info = hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap();
+        let okm = ikm.extract_and_expand::<Sha256>(&salt, &info, 42).unwrap();
+        assert_eq!(
+            hex::encode(okm),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+        assert!(ikm.extract_and_expand::<Sha256>(&[], &[], 255 * 32).is_ok());
+        assert!(ikm
+            .extract_and_expand::<Sha256>(&[], &[], 255 * 32 + 1)
+            .is_err());
+
+        let e = P256.get();
+        let (a, b) = (e.new_scalar(3.into()), e.new_scalar(4.into()));
+        let k_a = diffie_hellman_x(&e, &a, &public_key_x(&e, &b)).unwrap();
+        let k_b = diffie_hellman_x(&e, &b, &public_key_x(&e, &a)).unwrap();
+        let key_a = k_a
+            .extract_and_expand::<Sha512>(b"salt", b"key", 32)
+            .unwrap();
+        let key_b = k_b
+            .extract_and_expand::<Sha512>(b"salt", b"key", 32)
+            .unwrap();
+        assert_eq!(key_a, key_b);
+        assert_ne!(
+            key_a,
+            k_a.extract_and_expand::<Sha512>(b"salt", b"iv", 32)
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! DER encoding of the ASN.1 structure `SEQUENCE { r INTEGER, s INTEGER }`
+//! following ITU-T X.690.
+
+use num_bigint::BigUint;
+
+use std::io::Error;
+
+/// Returns the DER encoding of the pair of non-negative integers `r` and `s`
+/// given as big-endian octet-strings.
+pub(super) fn encode(r: &[u8], s: &[u8]) -> Vec<u8> {
+    let mut body = integer(r);
+    body.append(&mut integer(s));
+    let mut out = vec![TAG_SEQUENCE];
+    out.append(&mut length(body.len()));
+    out.append(&mut body);
+    out
+}
+
+/// Parses the DER encoding of a pair of non-negative integers. Only the
+/// canonical encoding is accepted.
+pub(super) fn decode(buf: &[u8]) -> Result<(BigUint, BigUint), Error> {
+    let (body, rest) = read(buf, TAG_SEQUENCE)?;
+    if !rest.is_empty() {
+        return Err(Error::other(ERR_DER));
+    }
+    let (r, body) = read(body, TAG_INTEGER)?;
+    let (s, rest) = read(body, TAG_INTEGER)?;
+    if !rest.is_empty() {
+        return Err(Error::other(ERR_DER));
+    }
+    Ok((uint(r)?, uint(s)?))
+}
+
+fn integer(x: &[u8]) -> Vec<u8> {
+    // strip leading zeros, but keep one byte for the zero integer
+    let first = x.iter().position(|&b| b != 0).unwrap_or(x.len() - 1);
+    let x = &x[first..];
+    let mut v = vec![TAG_INTEGER];
+    // prepend a zero byte, otherwise the integer is read as negative
+    let pad = x[0] & 0x80 != 0;
+    v.append(&mut length(x.len() + pad as usize));
+    if pad {
+        v.push(0);
+    }
+    v.extend_from_slice(x);
+    v
+}
+
+fn length(len: usize) -> Vec<u8> {
+    // signatures never exceed 255 bytes
+    if len < 0x80 {
+        vec![len as u8]
+    } else {
+        vec![0x81, len as u8]
+    }
+}
+
+fn read(buf: &[u8], tag: u8) -> Result<(&[u8], &[u8]), Error> {
+    if buf.len() < 2 || buf[0] != tag {
+        return Err(Error::other(ERR_DER));
+    }
+    let (len, hdr) = match buf[1] {
+        l if l < 0x80 => (l as usize, 2),
+        0x81 if buf.len() > 2 && buf[2] >= 0x80 => (buf[2] as usize, 3),
+        _ => return Err(Error::other(ERR_DER)),
+    };
+    if buf.len() - hdr < len {
+        return Err(Error::other(ERR_DER));
+    }
+    Ok((&buf[hdr..hdr + len], &buf[hdr + len..]))
+}
+
+fn uint(x: &[u8]) -> Result<BigUint, Error> {
+    match x {
+        [] => Err(Error::other(ERR_DER)),
+        [b, ..] if b & 0x80 != 0 => Err(Error::other(ERR_DER)),
+        [0, b, ..] if b & 0x80 == 0 => Err(Error::other(ERR_DER)),
+        _ => Ok(BigUint::from_bytes_be(x)),
+    }
+}
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_SEQUENCE: u8 = 0x30;
+const ERR_DER: &str = "invalid DER encoding";
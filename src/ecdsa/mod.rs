@@ -0,0 +1,601 @@
+//! This is documentation for the `ecdsa` module.
+//!
+//! The ecdsa module implements the Elliptic Curve Digital Signature Algorithm
+//! (ECDSA) over Weierstrass curves as specified in FIPS 186-5 and SEC 1.
+//!
+//! # Signing and Verifying
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::ecdsa::SigningKey;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use sha2::Sha256;
+//!
+//!  let ec = P256.get();
+//!  let sk = SigningKey::random(&ec, &mut OsRng);
+//!  let sig = sk.sign::<Sha256, _>(b"message", &mut OsRng);
+//!  assert!(sk.verifying_key().verify::<Sha256>(b"message", &sig).is_ok());
+//! ```
+
+mod der;
+mod rfc6979;
+
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
+
+use std::io::Error;
+
+use crate::ecdsa::rfc6979::HmacDrbg;
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode, PointFormat};
+use crate::ops::Serialize;
+use crate::weierstrass::{Curve, Point, Scalar};
+
+/// Signature is an ECDSA signature, i.e., a pair of integers modulo the
+/// order of the curve.
+#[derive(Clone, PartialEq)]
+pub struct Signature {
+    pub r: Scalar,
+    pub s: Scalar,
+}
+
+impl Signature {
+    /// Returns the fixed-size encoding `r || s`, where both integers are
+    /// encoded in big-endian order with as many bytes as the order of the
+    /// curve.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.r.to_bytes_be();
+        out.append(&mut self.s.to_bytes_be());
+        out
+    }
+    /// Parses the fixed-size encoding of a signature. It fails if either
+    /// integer is zero or is not less than the order of `e`.
+    pub fn from_bytes(e: &Curve, buf: &[u8]) -> Result<Signature, Error> {
+        let n = scalar_size(e);
+        if buf.len() != 2 * n {
+            return Err(Error::other(ERR_SIG_LEN));
+        }
+        let r = BigUint::from_bytes_be(&buf[..n]);
+        let s = BigUint::from_bytes_be(&buf[n..]);
+        Signature::from_integers(e, r, s)
+    }
+    /// Returns the DER encoding of the ASN.1 structure
+    /// `SEQUENCE { r INTEGER, s INTEGER }`, as used by X.509 and TLS.
+    pub fn to_der(&self) -> Vec<u8> {
+        der::encode(&self.r.to_bytes_be(), &self.s.to_bytes_be())
+    }
+    /// Parses the DER encoding of a signature. Besides the checks done by
+    /// `from_bytes`, it fails if the encoding is not canonical.
+    pub fn from_der(e: &Curve, buf: &[u8]) -> Result<Signature, Error> {
+        let (r, s) = der::decode(buf)?;
+        Signature::from_integers(e, r, s)
+    }
+    /// Determines whether `s` is greater than half the order of the curve.
+    /// Since `(r, s)` and `(r, -s)` are both valid signatures, consensus
+    /// rules such as Bitcoin's BIP-146 only accept the low one.
+    pub fn is_high_s(&self) -> bool {
+        // both encodings have the same length, so they compare as integers
+        self.s.to_bytes_be() > (-&self.s).to_bytes_be()
+    }
+    /// Returns the signature with low `s`, which is `(r, -s)` if `s` is high
+    /// and a copy of the signature otherwise.
+    pub fn normalize_s(&self) -> Signature {
+        if self.is_high_s() {
+            Signature {
+                r: self.r.clone(),
+                s: -&self.s,
+            }
+        } else {
+            self.clone()
+        }
+    }
+    fn from_integers(e: &Curve, r: BigUint, s: BigUint) -> Result<Signature, Error> {
+        let n = e.get_order();
+        let valid = |x: &BigUint| !x.is_zero() && x < &n;
+        if !valid(&r) || !valid(&s) {
+            return Err(Error::other(ERR_SIG_RANGE));
+        }
+        Ok(Signature {
+            r: e.new_scalar(r.into()),
+            s: e.new_scalar(s.into()),
+        })
+    }
+}
+
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "r: {}\ns: {}", self.r, self.s)
+    }
+}
+
+/// Nonce selects how the nonce of a signature is generated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Nonce {
+    /// The nonce is sampled uniformly at random.
+    Random,
+    /// The nonce is derived from the secret key and the message digest as
+    /// specified in RFC 6979, Section 3.2.
+    Deterministic,
+    /// The nonce is derived as in `Deterministic`, but fresh randomness is
+    /// mixed in as the additional data of RFC 6979, Section 3.6. Nonces
+    /// remain secret if either the randomness or the derivation is sound.
+    Hedged,
+}
+
+/// SignaturePolicy determines which signatures are accepted by the
+/// verification. Rejecting high `s` values removes signature malleability.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SignaturePolicy {
+    AcceptAll,
+    RejectHighS,
+}
+
+/// SigningKey is an ECDSA private key together with its public key.
+#[derive(Clone)]
+pub struct SigningKey {
+    d: Scalar,
+    vk: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Returns the signing key whose secret is `d`, which must not be zero.
+    pub fn new(e: &Curve, d: Scalar) -> Result<SigningKey, Error> {
+        if is_zero(e, &d) {
+            return Err(Error::other(ERR_KEY_RANGE));
+        }
+        let q = e.get_generator() * &d;
+        let vk = VerifyingKey { e: e.clone(), q };
+        Ok(SigningKey { d, vk })
+    }
+    /// Returns a signing key sampled uniformly at random.
+    pub fn random<R: RngCore + CryptoRng>(e: &Curve, rng: &mut R) -> SigningKey {
+        SigningKey::new(e, e.random_scalar(rng)).unwrap()
+    }
+    /// Parses a secret encoded in big-endian order with as many bytes as the
+    /// order of the curve. It fails if the secret is zero or is not less
+    /// than the order of `e`.
+    pub fn from_bytes(e: &Curve, buf: &[u8]) -> Result<SigningKey, Error> {
+        let d = BigUint::from_bytes_be(buf);
+        if buf.len() != scalar_size(e) || d >= e.get_order() {
+            return Err(Error::other(ERR_KEY_RANGE));
+        }
+        SigningKey::new(e, e.new_scalar(d.into()))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.d.to_bytes_be()
+    }
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.vk
+    }
+    /// Signs the digest of `msg` computed with the hash function `D`.
+    pub fn sign<D: Digest, R: RngCore + CryptoRng>(&self, msg: &[u8], rng: &mut R) -> Signature {
+        self.sign_prehash(&D::digest(msg), rng)
+    }
+    /// Signs a message digest using a nonce sampled uniformly at random.
+    pub fn sign_prehash<R: RngCore + CryptoRng>(&self, digest: &[u8], rng: &mut R) -> Signature {
+        loop {
+            let k = self.vk.e.random_scalar(rng);
+            if let Ok(sig) = self.sign_prehash_with_nonce(digest, &k) {
+                return sig;
+            }
+        }
+    }
+    /// Signs the digest of `msg` computed with the hash function `D`. The
+    /// nonce is generated as selected by `nonce`, where derived nonces use
+    /// HMAC instantiated with `D`; `rng` is not used by deterministic
+    /// nonces.
+    pub fn sign_with<D, R>(&self, msg: &[u8], nonce: Nonce, rng: &mut R) -> Signature
+    where
+        D: Digest + BlockSizeUser,
+        R: RngCore + CryptoRng,
+    {
+        self.sign_prehash_with::<D, R>(&D::digest(msg), nonce, rng)
+    }
+    /// Signs a message digest generating the nonce as selected by `nonce`,
+    /// where derived nonces use HMAC instantiated with `D`.
+    pub fn sign_prehash_with<D, R>(&self, digest: &[u8], nonce: Nonce, rng: &mut R) -> Signature
+    where
+        D: Digest + BlockSizeUser,
+        R: RngCore + CryptoRng,
+    {
+        let e = &self.vk.e;
+        let extra = match nonce {
+            Nonce::Random => return self.sign_prehash(digest, rng),
+            Nonce::Deterministic => Vec::new(),
+            Nonce::Hedged => {
+                let mut buf = vec![0u8; scalar_size(e)];
+                rng.fill_bytes(&mut buf);
+                buf
+            }
+        };
+        let mut drbg = HmacDrbg::<D>::new(e, &self.d, digest, &extra);
+        loop {
+            if let Ok(sig) = self.sign_prehash_with_nonce(digest, &drbg.next_k()) {
+                return sig;
+            }
+        }
+    }
+    /// Signs a message digest using the nonce `k`. It fails if either half
+    /// of the signature is zero, in which case another nonce must be used.
+    ///
+    /// **Attention** Revealing `k`, or using it for signing two different
+    /// messages, reveals the secret key.
+    pub fn sign_prehash_with_nonce(&self, digest: &[u8], k: &Scalar) -> Result<Signature, Error> {
+        let e = &self.vk.e;
+        let z = e.new_scalar(bits2int(e, digest));
+        let r = e.new_scalar(x_coordinate(&(e.get_generator() * k)));
+        let s = (z + &r * &self.d) / k;
+        if is_zero(e, &r) || is_zero(e, &s) {
+            return Err(Error::other(ERR_NONCE));
+        }
+        Ok(Signature { r, s })
+    }
+}
+
+/// VerifyingKey is an ECDSA public key.
+#[derive(Clone)]
+pub struct VerifyingKey {
+    e: Curve,
+    q: Point,
+}
+
+impl VerifyingKey {
+    /// Returns the verifying key for the point `q` of `e`. It fails if `q`
+    /// is the identity.
+    pub fn new(e: &Curve, q: Point) -> Result<VerifyingKey, Error> {
+        if q.is_identity() || !e.is_on_curve(&q) {
+            return Err(Error::other(ERR_KEY_POINT));
+        }
+        Ok(VerifyingKey { e: e.clone(), q })
+    }
+    /// Parses a verifying key encoded as a point of `e`.
+    pub fn from_bytes(e: &Curve, buf: &[u8]) -> Result<VerifyingKey, Error> {
+        VerifyingKey::new(e, e.decode(buf)?)
+    }
+    pub fn to_bytes(&self, format: PointFormat) -> Vec<u8> {
+        self.q.encode(format)
+    }
+    pub fn as_point(&self) -> &Point {
+        &self.q
+    }
+    /// Verifies a signature of the digest of `msg` computed with the hash
+    /// function `D`.
+    pub fn verify<D: Digest>(&self, msg: &[u8], sig: &Signature) -> Result<(), Error> {
+        self.verify_prehash(&D::digest(msg), sig)
+    }
+    /// Verifies a signature of the digest of `msg` computed with the hash
+    /// function `D` under the given policy.
+    pub fn verify_with<D: Digest>(
+        &self,
+        msg: &[u8],
+        sig: &Signature,
+        policy: SignaturePolicy,
+    ) -> Result<(), Error> {
+        self.verify_prehash_with(&D::digest(msg), sig, policy)
+    }
+    /// Verifies a signature of a message digest under the given policy.
+    pub fn verify_prehash_with(
+        &self,
+        digest: &[u8],
+        sig: &Signature,
+        policy: SignaturePolicy,
+    ) -> Result<(), Error> {
+        if policy == SignaturePolicy::RejectHighS && sig.is_high_s() {
+            return Err(Error::other(ERR_SIG_HIGH_S));
+        }
+        self.verify_prehash(digest, sig)
+    }
+    /// Verifies a signature of a message digest.
+    pub fn verify_prehash(&self, digest: &[u8], sig: &Signature) -> Result<(), Error> {
+        let e = &self.e;
+        if is_zero(e, &sig.r) || is_zero(e, &sig.s) {
+            return Err(Error::other(ERR_SIG_RANGE));
+        }
+        let z = e.new_scalar(bits2int(e, digest));
+        let w = 1u32 / &sig.s;
+        let p = e.get_generator() * (z * &w) + &self.q * (&sig.r * w);
+        if p.is_identity() || e.new_scalar(x_coordinate(&p)) != sig.r {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        Ok(())
+    }
+}
+
+/// Converts an octet-string into an integer keeping at most as many
+/// leftmost bits as the order of `e`, as prescribed by SEC 1, Section 4.1.3.
+/// The result is not reduced.
+fn bits2int(e: &Curve, digest: &[u8]) -> BigInt {
+    let qlen = e.get_order().bits();
+    let blen = 8 * digest.len();
+    let z = BigInt::from_bytes_be(Sign::Plus, digest);
+    if blen > qlen {
+        z >> (blen - qlen)
+    } else {
+        z
+    }
+}
+
+fn x_coordinate(p: &Point) -> BigInt {
+    p.x().map_or_else(BigInt::zero, |x| {
+        BigInt::from_bytes_be(Sign::Plus, &x.to_bytes_be())
+    })
+}
+
+fn is_zero(e: &Curve, k: &Scalar) -> bool {
+    *k == e.new_scalar(BigInt::zero())
+}
+
+fn scalar_size(e: &Curve) -> usize {
+    e.get_order().bits().div_ceil(8)
+}
+
+const ERR_SIG_LEN: &str = "wrong length of signature";
+const ERR_SIG_RANGE: &str = "signature integers must be in [1, n-1]";
+const ERR_SIG_INVALID: &str = "invalid signature";
+const ERR_SIG_HIGH_S: &str = "signature has a high s value";
+const ERR_KEY_RANGE: &str = "secret key must be in [1, n-1]";
+const ERR_KEY_POINT: &str = "public key must be a point of the curve other than the identity";
+const ERR_NONCE: &str = "nonce produces an invalid signature";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, Sign};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::{OsRng, SeedableRng};
+    use sha2::{Sha256, Sha384, Sha512};
+
+    use super::{bits2int, Nonce, Signature, SignaturePolicy, SigningKey, VerifyingKey};
+    use crate::ellipticcurve::{EllipticCurve, PointFormat};
+    use crate::instances::{GetCurve, P256, P384, P521, SECP256K1};
+    use crate::ops::Serialize;
+
+    // Test vectors taken from the FIPS 186-4 ECDSA test vectors, SigGen.txt,
+    // where `m` is the digest of the message.
+    struct Vector {
+        d: &'static str,
+        qx: &'static str,
+        qy: &'static str,
+        k: &'static str,
+        m: &'static str,
+        r: &'static str,
+        s: &'static str,
+    }
+
+    #[test]
+    fn cavp_vectors() {
+        let vectors = [
+            (
+                P256,
+                Vector {
+                    d: "519b423d715f8b581f4fa8ee59f4771a5b44c8130b4e3eacca54a56dda72b464",
+                    qx: "1ccbe91c075fc7f4f033bfa248db8fccd3565de94bbfb12f3c59ff46c271bf83",
+                    qy: "ce4014c68811f9a21a1fdb2c0e6113e06db7ca93b7404e78dc7ccd5ca89a4ca9",
+                    k: "94a1bbb14b906a61a280f245f9e93c7f3b4a6247824f5d33b9670787642a68de",
+                    m: "44acf6b7e36c1342c2c5897204fe09504e1e2efb1a900377dbc4e7a6a133ec56",
+                    r: "f3ac8061b514795b8843e3d6629527ed2afd6b1f6a555a7acabb5e6f79c8c2ac",
+                    s: "8bf77819ca05a6b2786c76262bf7371cef97b218e96f175a3ccdda2acc058903",
+                },
+            ),
+            (
+                P256,
+                Vector {
+                    d: "0f56db78ca460b055c500064824bed999a25aaf48ebb519ac201537b85479813",
+                    qx: "e266ddfdc12668db30d4ca3e8f7749432c416044f2d2b8c10bf3d4012aeffa8a",
+                    qy: "bfa86404a2e9ffe67d47c587ef7a97a7f456b863b4d02cfc6928973ab5b1cb39",
+                    k: "6d3e71882c3b83b156bb14e0ab184aa9fb728068d3ae9fac421187ae0b2f34c6",
+                    m: "9b2db89cb0e8fa3cc7608b4d6cc1dec0114e0b9ff4080bea12b134f489ab2bbc",
+                    r: "976d3a4e9d23326dc0baa9fa560b7c4e53f42864f508483a6473b6a11079b2db",
+                    s: "1b766e9ceb71ba6c01dcd46e0af462cd4cfa652ae5017d4555b8eeefe36e1932",
+                },
+            ),
+            (
+                P384,
+                Vector {
+                    d: "201b432d8df14324182d6261db3e4b3f46a8284482d52e370da41e6cbdf45ec2952f5db7ccbce3bc29449f4fb080ac97",
+                    qx: "c2b47944fb5de342d03285880177ca5f7d0f2fcad7678cce4229d6e1932fcac11bfc3c3e97d942a3c56bf34123013dbf",
+                    qy: "37257906a8223866eda0743c519616a76a758ae58aee81c5fd35fbf3a855b7754a36d4a0672df95d6c44a81cf7620c2d",
+                    k: "dcedabf85978e090f733c6e16646fa34df9ded6e5ce28c6676a00f58a25283db8885e16ce5bf97f917c81e1f25c9c771",
+                    m: "31a452d6164d904bb5724c878280231eae705c29ce9d4bc7d58e020e1085f17eebcc1a38f0ed0bf2b344d81fbd896825",
+                    r: "50835a9251bad008106177ef004b091a1e4235cd0da84fff54542b0ed755c1d6f251609d14ecf18f9e1ddfe69b946e32",
+                    s: "0475f3d30c6463b646e8d3bf2455830314611cbde404be518b14464fdb195fdcc92eb222e61f426a4a592c00a6a89721",
+                },
+            ),
+        ];
+        for (id, v) in vectors.iter() {
+            let e = id.get();
+            let sk = SigningKey::from_bytes(&e, &hex::decode(v.d).unwrap()).unwrap();
+            let q = hex::decode(format!("04{}{}", v.qx, v.qy)).unwrap();
+            let vk = VerifyingKey::from_bytes(&e, &q).unwrap();
+            assert!(
+                sk.verifying_key().as_point() == vk.as_point(),
+                "public key for {}",
+                id
+            );
+            let m = hex::decode(v.m).unwrap();
+            let k = e.new_scalar(BigInt::parse_bytes(v.k.as_bytes(), 16).unwrap());
+            let sig = sk.sign_prehash_with_nonce(&m, &k).unwrap();
+            assert_eq!(hex::encode(sig.r.to_bytes_be()), v.r, "r for {}", id);
+            assert_eq!(hex::encode(sig.s.to_bytes_be()), v.s, "s for {}", id);
+            assert!(vk.verify_prehash(&m, &sig).is_ok(), "verify for {}", id);
+            let mut m = m;
+            m[0] ^= 1;
+            assert!(vk.verify_prehash(&m, &sig).is_err(), "forgery for {}", id);
+        }
+    }
+
+    // Test vectors taken from RFC 6979, Appendix A.2.5 and A.2.6.
+    #[test]
+    fn rfc6979_vectors() {
+        let sk256 = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+        let sk384 = "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d896d5724e4c70a825f872c9ea60d2edf5";
+        let sign = |id: &_, sk: &str, msg: &[u8], hash: &str| {
+            let e = GetCurve::get(id);
+            let sk = SigningKey::from_bytes(&e, &hex::decode(sk).unwrap()).unwrap();
+            let sig = match hash {
+                "SHA-256" => sk.sign_with::<Sha256, _>(msg, Nonce::Deterministic, &mut OsRng),
+                "SHA-384" => sk.sign_with::<Sha384, _>(msg, Nonce::Deterministic, &mut OsRng),
+                _ => sk.sign_with::<Sha512, _>(msg, Nonce::Deterministic, &mut OsRng),
+            };
+            hex::encode(sig.to_bytes())
+        };
+        for (id, sk, msg, hash, r, s) in [
+            (
+                P256,
+                sk256,
+                "sample",
+                "SHA-256",
+                "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+                "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+            ),
+            (
+                P256,
+                sk256,
+                "test",
+                "SHA-256",
+                "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367",
+                "019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+            ),
+            (
+                P256,
+                sk256,
+                "sample",
+                "SHA-512",
+                "8496a60b5e9b47c825488827e0495b0e3fa109ec4568fd3f8d1097678eb97f00",
+                "2362ab1adbe2b8adf9cb9edab740ea6049c028114f2460f96554f61fae3302fe",
+            ),
+            (
+                P384,
+                sk384,
+                "sample",
+                "SHA-384",
+                "94edbb92a5ecb8aad4736e56c691916b3f88140666ce9fa73d64c4ea95ad133c81a648152e44acf96e36dd1e80fabe46",
+                "99ef4aeb15f178cea1fe40db2603138f130e740a19624526203b6351d0a3a94fa329c145786e679e7b82c71a38628ac8",
+            ),
+        ]
+        .iter()
+        {
+            let got = sign(id, sk, msg.as_bytes(), hash);
+            assert_eq!(got, format!("{}{}", r, s), "{} {} {:?}", id, hash, msg);
+        }
+    }
+
+    #[test]
+    fn hedged_nonces() {
+        let e = P256.get();
+        let sk = SigningKey::random(&e, &mut OsRng);
+        let vk = sk.verifying_key();
+        let sig0 = sk.sign_with::<Sha256, _>(b"abc", Nonce::Hedged, &mut OsRng);
+        let sig1 = sk.sign_with::<Sha256, _>(b"abc", Nonce::Hedged, &mut OsRng);
+        assert!(sig0 != sig1);
+        assert!(vk.verify::<Sha256>(b"abc", &sig0).is_ok());
+        assert!(vk.verify::<Sha256>(b"abc", &sig1).is_ok());
+        let sig0 = sk.sign_with::<Sha256, _>(b"abc", Nonce::Deterministic, &mut OsRng);
+        let sig1 = sk.sign_with::<Sha256, _>(b"abc", Nonce::Deterministic, &mut OsRng);
+        assert!(sig0 == sig1);
+    }
+
+    #[test]
+    fn seeded_rng() {
+        let e = P256.get();
+        let (mut rng0, mut rng1) = (ChaCha20Rng::seed_from_u64(7), ChaCha20Rng::seed_from_u64(7));
+        let sk0 = SigningKey::random(&e, &mut rng0);
+        let sk1 = SigningKey::random(&e, &mut rng1);
+        assert_eq!(sk0.to_bytes(), sk1.to_bytes());
+        let sig0 = sk0.sign::<Sha256, _>(b"abc", &mut rng0);
+        let sig1 = sk1.sign::<Sha256, _>(b"abc", &mut rng1);
+        assert!(sig0 == sig1);
+        let sig2 = sk0.sign::<Sha256, _>(b"abc", &mut rng0);
+        assert!(sig0 != sig2);
+    }
+
+    #[test]
+    fn sign_verify() {
+        for &id in [P256, P384, P521, SECP256K1].iter() {
+            let e = id.get();
+            let sk = SigningKey::random(&e, &mut OsRng);
+            let vk = sk.verifying_key();
+            let sig = sk.sign::<Sha256, _>(b"abc", &mut OsRng);
+            assert!(vk.verify::<Sha256>(b"abc", &sig).is_ok(), "{}", id);
+            assert!(vk.verify::<Sha256>(b"abd", &sig).is_err(), "{}", id);
+            let sk = SigningKey::from_bytes(&e, &sk.to_bytes()).unwrap();
+            let vk = VerifyingKey::from_bytes(&e, &vk.to_bytes(PointFormat::Compressed)).unwrap();
+            assert!(vk.verify::<Sha256>(b"abc", &sig).is_ok(), "{}", id);
+            assert!(sk
+                .sign_prehash_with_nonce(b"abc", &e.new_scalar(0.into()))
+                .is_err());
+        }
+        let e = P256.get();
+        assert!(SigningKey::from_bytes(&e, &[0u8; 32]).is_err());
+        assert!(SigningKey::from_bytes(&e, &[0xffu8; 32]).is_err());
+        assert!(VerifyingKey::from_bytes(&e, &[0u8]).is_err());
+    }
+
+    #[test]
+    fn signature_encoding() {
+        for &id in [P256, P384, P521].iter() {
+            let e = id.get();
+            let sig = SigningKey::random(&e, &mut OsRng).sign::<Sha512, _>(b"abc", &mut OsRng);
+            let buf = sig.to_bytes();
+            assert!(Signature::from_bytes(&e, &buf).unwrap() == sig, "{}", id);
+            let der = sig.to_der();
+            assert!(Signature::from_der(&e, &der).unwrap() == sig, "{}", id);
+            let mut long = der.clone();
+            long.push(0);
+            assert!(Signature::from_der(&e, &long).is_err(), "{}", id);
+            assert!(Signature::from_der(&e, &der[..der.len() - 1]).is_err());
+            assert!(Signature::from_bytes(&e, &vec![0u8; buf.len()]).is_err());
+        }
+        let e = P256.get();
+        let one = e.new_scalar(1.into());
+        let sig = Signature {
+            r: one.clone(),
+            s: one,
+        };
+        assert_eq!(
+            sig.to_der(),
+            [0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01]
+        );
+        let non_minimal = [0x30, 0x07, 0x02, 0x02, 0x00, 0x01, 0x02, 0x01, 0x01];
+        assert!(Signature::from_der(&e, &non_minimal).is_err());
+        let negative = [0x30, 0x06, 0x02, 0x01, 0x81, 0x02, 0x01, 0x01];
+        assert!(Signature::from_der(&e, &negative).is_err());
+    }
+
+    #[test]
+    fn low_s() {
+        let e = P256.get();
+        let sk = SigningKey::from_bytes(
+            &e,
+            &hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721")
+                .unwrap(),
+        )
+        .unwrap();
+        let vk = sk.verifying_key();
+        let high = sk.sign_with::<Sha256, _>(b"sample", Nonce::Deterministic, &mut OsRng);
+        let low = high.normalize_s();
+        assert!(high.is_high_s() && !low.is_high_s());
+        assert!(low.normalize_s() == low);
+        assert_eq!(
+            hex::encode(low.s.to_bytes_be()),
+            "0834e36ad29a83bf2bc9385e491d6099c8fdf9d1ed67aa7ea5f51f93782857a9"
+        );
+        let policy = SignaturePolicy::RejectHighS;
+        assert!(vk.verify::<Sha256>(b"sample", &high).is_ok());
+        assert!(vk.verify::<Sha256>(b"sample", &low).is_ok());
+        assert!(vk.verify_with::<Sha256>(b"sample", &high, policy).is_err());
+        assert!(vk.verify_with::<Sha256>(b"sample", &low, policy).is_ok());
+    }
+
+    #[test]
+    fn truncation() {
+        let digest = [0xa5u8; 64];
+        let e = P256.get();
+        let want = BigInt::from_bytes_be(Sign::Plus, &digest[..32]);
+        assert_eq!(bits2int(&e, &digest), want);
+        let e = P521.get();
+        let want = BigInt::from_bytes_be(Sign::Plus, &digest);
+        assert_eq!(bits2int(&e, &digest), want);
+    }
+}
@@ -0,0 +1,83 @@
+//! Deterministic generation of ECDSA nonces as specified in RFC 6979.
+
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use hmac::{Mac, SimpleHmac};
+use num_bigint::BigInt;
+use num_traits::identities::Zero;
+
+use std::marker::PhantomData;
+
+use crate::ecdsa::bits2int;
+use crate::ellipticcurve::EllipticCurve;
+use crate::ops::Serialize;
+use crate::weierstrass::{Curve, Scalar};
+
+/// HmacDrbg is the HMAC_DRBG instance of RFC 6979, Section 3.2, which
+/// outputs the sequence of candidate nonces for signing a message digest.
+pub(super) struct HmacDrbg<D> {
+    e: Curve,
+    k: Vec<u8>,
+    v: Vec<u8>,
+    first: bool,
+    _d: PhantomData<D>,
+}
+
+impl<D> HmacDrbg<D>
+where
+    D: Digest + BlockSizeUser,
+{
+    /// Seeds the generator with the secret key `x` and the message digest.
+    /// The `extra` data, if not empty, is the additional input of
+    /// RFC 6979, Section 3.6.
+    pub(super) fn new(e: &Curve, x: &Scalar, digest: &[u8], extra: &[u8]) -> HmacDrbg<D> {
+        let hlen = <D as Digest>::output_size();
+        let x = x.to_bytes_be();
+        let h = e.new_scalar(bits2int(e, digest)).to_bytes_be();
+        let v = vec![0x01u8; hlen];
+        let k = vec![0x00u8; hlen];
+        let k = hmac::<D>(&k, &[&v, &[0x00], &x, &h, extra]);
+        let v = hmac::<D>(&k, &[&v]);
+        let k = hmac::<D>(&k, &[&v, &[0x01], &x, &h, extra]);
+        let v = hmac::<D>(&k, &[&v]);
+        HmacDrbg {
+            e: e.clone(),
+            k,
+            v,
+            first: true,
+            _d: PhantomData,
+        }
+    }
+    /// Returns the next candidate nonce, which is in the range [1, n-1].
+    pub(super) fn next_k(&mut self) -> Scalar {
+        let n = BigInt::from(self.e.get_order());
+        let qlen = n.bits();
+        loop {
+            if !self.first {
+                self.k = hmac::<D>(&self.k, &[&self.v, &[0x00]]);
+                self.v = hmac::<D>(&self.k, &[&self.v]);
+            }
+            self.first = false;
+            let mut t = Vec::new();
+            while 8 * t.len() < qlen {
+                self.v = hmac::<D>(&self.k, &[&self.v]);
+                t.extend_from_slice(&self.v);
+            }
+            let k = bits2int(&self.e, &t);
+            if !k.is_zero() && k < n {
+                return self.e.new_scalar(k);
+            }
+        }
+    }
+}
+
+fn hmac<D>(key: &[u8], parts: &[&[u8]]) -> Vec<u8>
+where
+    D: Digest + BlockSizeUser,
+{
+    let mut mac = <SimpleHmac<D> as Mac>::new_from_slice(key).unwrap();
+    for p in parts {
+        mac.update(p);
+    }
+    mac.finalize().into_bytes().to_vec()
+}
@@ -0,0 +1,287 @@
+//! This is documentation for the `ecies` module.
+//!
+//! The ecies module implements the Elliptic Curve Integrated Encryption
+//! Scheme (ECIES) of SEC 1, Section 5.1, where the symmetric encryption
+//! and the MAC are replaced by an authenticated encryption scheme (AEAD).
+//!
+//! A ciphertext is the encoding of an ephemeral public key `R = [r]G`
+//! followed by the AEAD ciphertext. The AEAD key and nonce are derived from
+//! the Diffie-Hellman shared secret of `r` and the public key of the
+//! recipient.
+//!
+//! # Encrypting and Decrypting
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::ecies;
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!
+//!  let ec = P256.get();
+//!  let sk = ec.new_scalar(1234.into());
+//!  let pk = ec.get_generator() * &sk;
+//!  let ecies = ecies::sec1(ec);
+//!  let ct = ecies.encrypt(&pk, b"message", b"info", &mut OsRng).unwrap();
+//!  assert_eq!(ecies.decrypt(&sk, &ct, b"info").unwrap(), b"message");
+//! ```
+
+use aead::generic_array::typenum::Unsigned;
+use aead::{Aead, AeadCore, KeyInit, Nonce};
+use aes_gcm::Aes128Gcm;
+use digest::core_api::BlockSizeUser;
+use digest::Digest;
+use hkdf::SimpleHkdf;
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha256;
+
+use std::io::Error;
+use std::marker::PhantomData;
+
+use crate::ecdh::DiffieHellman;
+use crate::ellipticcurve::{Decode, EllipticCurve, Encode, PointFormat};
+use crate::weierstrass;
+
+/// Kdf is a key derivation function that expands a shared secret into
+/// key material.
+pub trait Kdf {
+    /// Derives `len` bytes from the shared secret `z` and the shared
+    /// information `info`.
+    fn derive(&self, z: &[u8], info: &[u8], len: usize) -> Vec<u8>;
+}
+
+/// X963Kdf is the key derivation function of ANSI X9.63, which is the one
+/// specified in SEC 1, Section 3.6.1.
+pub struct X963Kdf<D>(PhantomData<D>);
+
+impl<D> X963Kdf<D> {
+    pub fn new() -> X963Kdf<D> {
+        X963Kdf(PhantomData)
+    }
+}
+
+impl<D> Default for X963Kdf<D> {
+    fn default() -> Self {
+        X963Kdf::new()
+    }
+}
+
+impl<D: Digest> Kdf for X963Kdf<D> {
+    fn derive(&self, z: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+        let mut out = Vec::with_capacity(len);
+        let mut counter = 1u32;
+        while out.len() < len {
+            let h = D::new()
+                .chain_update(z)
+                .chain_update(counter.to_be_bytes())
+                .chain_update(info)
+                .finalize();
+            out.extend_from_slice(&h);
+            counter += 1;
+        }
+        out.truncate(len);
+        out
+    }
+}
+
+/// HkdfKdf is the HKDF of RFC 5869 with an empty salt, where the shared
+/// information is used as the `info` parameter.
+pub struct HkdfKdf<D>(PhantomData<D>);
+
+impl<D> HkdfKdf<D> {
+    pub fn new() -> HkdfKdf<D> {
+        HkdfKdf(PhantomData)
+    }
+}
+
+impl<D> Default for HkdfKdf<D> {
+    fn default() -> Self {
+        HkdfKdf::new()
+    }
+}
+
+impl<D> Kdf for HkdfKdf<D>
+where
+    D: Digest + BlockSizeUser + Clone,
+{
+    fn derive(&self, z: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+        let mut out = vec![0u8; len];
+        SimpleHkdf::<D>::new(None, z)
+            .expand(info, &mut out)
+            .expect(ERR_KDF_LEN);
+        out
+    }
+}
+
+/// Ecies is an instance of ECIES over the curve `E`, which derives keys with
+/// `K` and encrypts with the AEAD `A`.
+pub struct Ecies<E, K, A> {
+    e: E,
+    kdf: K,
+    format: PointFormat,
+    _a: PhantomData<A>,
+}
+
+impl<E, K, A> Ecies<E, K, A>
+where
+    E: DiffieHellman + Decode<Deser = <E as EllipticCurve>::Point>,
+    K: Kdf,
+    A: KeyInit + Aead,
+{
+    /// Returns an ECIES instance, where ephemeral public keys are encoded
+    /// in the given `format`.
+    pub fn new(e: E, kdf: K, format: PointFormat) -> Ecies<E, K, A> {
+        Ecies {
+            e,
+            kdf,
+            format,
+            _a: PhantomData,
+        }
+    }
+    /// Encrypts `msg` to the public key `pk`. The shared information `info`
+    /// is bound to the derived key, so the same value must be used for
+    /// decryption. The ephemeral secret key is sampled from `rng`. It fails
+    /// if `pk` is of small order.
+    // based on SEC 1 v2, Section 5.1.3
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        &self,
+        pk: &E::Point,
+        msg: &[u8],
+        info: &[u8],
+        rng: &mut R,
+    ) -> Result<Vec<u8>, Error> {
+        let r = self.e.random_scalar(rng);
+        let z = self.e.diffie_hellman(&r, pk)?;
+        let mut out = (self.e.get_generator() * &r).encode(self.format);
+        let (cipher, nonce) = self.cipher(z.as_bytes(), info);
+        let mut ct = cipher
+            .encrypt(&nonce, msg)
+            .map_err(|_| Error::other(ERR_AEAD))?;
+        out.append(&mut ct);
+        Ok(out)
+    }
+    /// Decrypts the ciphertext `ct` with the secret key `sk`. It fails if
+    /// the ephemeral public key is invalid or if the ciphertext is not
+    /// authentic.
+    // based on SEC 1 v2, Section 5.1.4
+    pub fn decrypt(&self, sk: &E::Scalar, ct: &[u8], info: &[u8]) -> Result<Vec<u8>, Error> {
+        let n = self.e.get_generator().encode(self.format).len();
+        if ct.len() < n {
+            return Err(Error::other(ERR_CT_LEN));
+        }
+        let (enc_r, ct) = ct.split_at(n);
+        let r = self.e.decode(enc_r)?;
+        let z = self.e.diffie_hellman(sk, &r)?;
+        let (cipher, nonce) = self.cipher(z.as_bytes(), info);
+        cipher
+            .decrypt(&nonce, ct)
+            .map_err(|_| Error::other(ERR_AEAD))
+    }
+    /// Derives the AEAD key followed by the nonce. The nonce can be fixed
+    /// as every key is used only once.
+    fn cipher(&self, z: &[u8], info: &[u8]) -> (A, Nonce<A>) {
+        let klen = A::key_size();
+        let nlen = <A as AeadCore>::NonceSize::USIZE;
+        let keys = self.kdf.derive(z, info, klen + nlen);
+        let cipher = A::new_from_slice(&keys[..klen]).unwrap();
+        let nonce = Nonce::<A>::clone_from_slice(&keys[klen..]);
+        (cipher, nonce)
+    }
+}
+
+/// Sec1 is the ECIES profile returned by `sec1`.
+pub type Sec1 = Ecies<weierstrass::Curve, X963Kdf<Sha256>, Aes128Gcm>;
+
+/// Returns the SEC 1 profile of ECIES over the Weierstrass curve `e`, which
+/// uses uncompressed ephemeral public keys, the X9.63 key derivation
+/// function with SHA-256, and AES-128-GCM.
+pub fn sec1(e: weierstrass::Curve) -> Sec1 {
+    Ecies::new(e, X963Kdf::new(), PointFormat::Uncompressed)
+}
+
+const ERR_CT_LEN: &str = "ciphertext is too short";
+const ERR_AEAD: &str = "ciphertext is not authentic";
+const ERR_KDF_LEN: &str = "too many bytes requested from the key derivation function";
+
+#[cfg(test)]
+mod tests {
+    use chacha20poly1305::ChaCha20Poly1305;
+    use rand_core::OsRng;
+    use sha2::{Sha256, Sha512};
+
+    use super::{sec1, Ecies, HkdfKdf, Kdf, X963Kdf};
+    use crate::ellipticcurve::{EllipticCurve, Encode, PointFormat};
+    use crate::instances::{GetCurve, CURVE25519, EDWARDS25519, P384};
+
+    #[test]
+    fn x963_kdf() {
+        // Test vectors taken from NIST CAVS 12.0, ANSI X9.63 KDF with SHA-256.
+        let kdf = X963Kdf::<Sha256>::new();
+        let z = hex::decode("96c05619d56c328ab95fe84b18264b08725b85e33fd34f08").unwrap();
+        let got = kdf.derive(&z, &[], 16);
+        assert_eq!(hex::encode(got), "443024c3dae66b95e6f5670601558f71");
+        let z = hex::decode("22518b10e70f2a3f243810ae3254139efbee04aa57c7af7d").unwrap();
+        let info = hex::decode("75eef81aa3041e33b80971203d2c0c52").unwrap();
+        let got = kdf.derive(&z, &info, 64);
+        assert_eq!(
+            hex::encode(got),
+            "c498af77161cc59f2962b9a713e2b215152d139766ce34a776df11866a69bf2e\
+             52a13d9c7c6fc878c50c5ea0bc7b00e0da2447cfd874f6cf92f30d0097111485"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let e = P384.get();
+        let sk = e.new_scalar(0x5eed.into());
+        let pk = e.get_generator() * &sk;
+        let ecies = sec1(e);
+        let ct = ecies.encrypt(&pk, b"message", b"info", &mut OsRng).unwrap();
+        assert_eq!(ct.len(), 97 + 7 + 16);
+        assert_eq!(ecies.decrypt(&sk, &ct, b"info").unwrap(), b"message");
+        assert!(ecies.decrypt(&sk, &ct, b"other").is_err());
+        let mut bad = ct.clone();
+        *bad.last_mut().unwrap() ^= 1;
+        assert!(ecies.decrypt(&sk, &bad, b"info").is_err());
+        assert!(ecies.decrypt(&sk, &ct[..40], b"info").is_err());
+
+        let e = EDWARDS25519.get();
+        let sk = e.new_scalar(0x5eed.into());
+        let pk = e.get_generator() * &sk;
+        let ecies = Ecies::<_, _, ChaCha20Poly1305>::new(
+            e,
+            HkdfKdf::<Sha512>::new(),
+            PointFormat::Compressed,
+        );
+        let ct = ecies.encrypt(&pk, b"message", b"", &mut OsRng).unwrap();
+        assert_eq!(ecies.decrypt(&sk, &ct, b"").unwrap(), b"message");
+
+        let e = CURVE25519.get();
+        let sk = e.new_scalar(0x5eed.into());
+        let pk = e.get_generator() * &sk;
+        let ecies = Ecies::<_, _, ChaCha20Poly1305>::new(
+            e,
+            X963Kdf::<Sha256>::new(),
+            PointFormat::Compressed,
+        );
+        let ct = ecies.encrypt(&pk, b"message", b"", &mut OsRng).unwrap();
+        assert_eq!(ecies.decrypt(&sk, &ct, b"").unwrap(), b"message");
+    }
+
+    #[test]
+    fn small_order_keys() {
+        let e = EDWARDS25519.get();
+        let sk = e.new_scalar(0x5eed.into());
+        let pk = e.get_generator() * &sk;
+        let id = e.identity();
+        let ecies = Ecies::<_, _, ChaCha20Poly1305>::new(
+            e,
+            HkdfKdf::<Sha512>::new(),
+            PointFormat::Compressed,
+        );
+        assert!(ecies.encrypt(&id, b"message", b"", &mut OsRng).is_err());
+        // replaces the ephemeral public key with the identity
+        let mut ct = ecies.encrypt(&pk, b"message", b"", &mut OsRng).unwrap();
+        ct[..32].copy_from_slice(&id.encode(PointFormat::Compressed));
+        assert!(ecies.decrypt(&sk, &ct, b"").is_err());
+    }
+}
@@ -0,0 +1,671 @@
+//! This is documentation for the `eddsa` module.
+//!
+//! The eddsa module implements the Edwards-curve Digital Signature Algorithm
+//! (EdDSA) as specified in RFC 8032, namely Ed25519, its variant with
+//! contexts Ed25519ctx, and Ed448. Keys, signatures, and points are handled
+//! in the encodings of RFC 8032.
+//!
+//! # Signing and Verifying
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::eddsa::{Algorithm, SigningKey};
+//!
+//!  let sk = SigningKey::random(Algorithm::Ed448, &mut OsRng);
+//!  let sig = sk.sign(b"message");
+//!  assert!(sk.verifying_key().verify(b"message", &sig).is_ok());
+//! ```
+
+use digest::{Digest, ExtendableOutput, Update, XofReader};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha512;
+use sha3::Shake256;
+
+use std::io::Error;
+
+use crate::edwards::{Curve, Point, Scalar};
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode, PointFormat};
+use crate::field::Field;
+use crate::group::msm::msm;
+use crate::instances::{GetCurve, EDWARDS25519, EDWARDS448};
+use crate::ops::{batch_chunk_len, map_batch, Serialize};
+
+/// Algorithm identifies the instance of EdDSA.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Algorithm {
+    /// Ed25519 over edwards25519 with SHA-512. Signing with a non-empty
+    /// context selects Ed25519ctx.
+    Ed25519,
+    /// Ed448 over edwards448 with SHAKE256. The context, possibly empty, is
+    /// always part of the hash.
+    Ed448,
+}
+
+impl Algorithm {
+    fn curve(&self) -> Curve {
+        match self {
+            Algorithm::Ed25519 => EDWARDS25519.get(),
+            Algorithm::Ed448 => EDWARDS448.get(),
+        }
+    }
+    /// Returns the length in bytes of seeds, and of encoded points and
+    /// integers.
+    fn size(&self) -> usize {
+        match self {
+            Algorithm::Ed25519 => 32,
+            Algorithm::Ed448 => 57,
+        }
+    }
+    /// Returns the hash of the concatenation of `parts`, which is SHA-512
+    /// for Ed25519 and SHAKE256 with 114 bytes of output for Ed448.
+    fn digest(&self, parts: &[&[u8]]) -> Vec<u8> {
+        match self {
+            Algorithm::Ed25519 => {
+                let mut h = Sha512::new();
+                for p in parts {
+                    Digest::update(&mut h, p);
+                }
+                h.finalize().to_vec()
+            }
+            Algorithm::Ed448 => {
+                let mut h = Shake256::default();
+                for p in parts {
+                    h.update(p);
+                }
+                let mut out = vec![0u8; 2 * self.size()];
+                h.finalize_xof().read(&mut out);
+                out
+            }
+        }
+    }
+    /// Returns the hash of the concatenation of `parts` prefixed by the
+    /// domain separation string of RFC 8032, i.e., `dom2` for Ed25519ctx
+    /// and `dom4` for Ed448. Pure Ed25519 has no prefix.
+    fn hash(&self, ctx: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+        let len = [0u8, ctx.len() as u8];
+        let dom: Vec<&[u8]> = match self {
+            Algorithm::Ed25519 if ctx.is_empty() => vec![],
+            Algorithm::Ed25519 => vec![DOM2, &len, ctx],
+            Algorithm::Ed448 => vec![DOM4, &len, ctx],
+        };
+        self.digest(&[&dom[..], parts].concat())
+    }
+    /// Returns the secret integer obtained from the lower half of the hash
+    /// of the seed, as prescribed by RFC 8032, Sections 5.1.5 and 5.2.5.
+    fn clamp(&self, buf: &[u8]) -> BigInt {
+        let mut buf = buf.to_vec();
+        let last = buf.len() - 1;
+        match self {
+            Algorithm::Ed25519 => {
+                buf[0] &= 0xf8;
+                buf[last] &= 0x7f;
+                buf[last] |= 0x40;
+            }
+            Algorithm::Ed448 => {
+                buf[0] &= 0xfc;
+                buf[last] = 0;
+                buf[last - 1] |= 0x80;
+            }
+        }
+        BigInt::from_bytes_le(Sign::Plus, &buf)
+    }
+    fn hash_to_scalar(&self, e: &Curve, ctx: &[u8], parts: &[&[u8]]) -> Scalar {
+        let h = self.hash(ctx, parts);
+        e.new_scalar(BigInt::from_bytes_le(Sign::Plus, &h))
+    }
+}
+
+/// SignaturePolicy determines which signatures are accepted by the
+/// verification, since ecosystems disagree on the rules of RFC 8032 that
+/// are left optional.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SignaturePolicy {
+    /// Encodings must be canonical, and the verification equation
+    /// `[S]B = R + [k]A` must hold exactly.
+    Strict,
+    /// Encodings must be canonical, and the verification equation must hold
+    /// after multiplying by the cofactor, i.e., `[8][S]B = [8]R + [8][k]A`
+    /// for Ed25519. This is the equation satisfied by batch verification.
+    Cofactored,
+    /// Non-canonical encodings of `A` and `R` are accepted and the cofactored
+    /// equation is used, as specified by ZIP-215 for consensus in Zcash.
+    Zip215,
+}
+
+/// Signature is an EdDSA signature, i.e., the encoding of the point `R`
+/// followed by the encoding of the integer `S`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Signature(Vec<u8>);
+
+impl Signature {
+    /// Parses a signature of the given algorithm. Only its length is checked
+    /// here; the remaining checks are part of the verification.
+    pub fn from_bytes(alg: Algorithm, buf: &[u8]) -> Result<Signature, Error> {
+        if buf.len() != 2 * alg.size() {
+            return Err(Error::other(ERR_SIG_LEN));
+        }
+        Ok(Signature(buf.to_vec()))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+}
+
+/// SigningKey is an EdDSA private key, which is a seed, together with the
+/// values derived from it.
+#[derive(Clone)]
+pub struct SigningKey {
+    seed: Vec<u8>,
+    s: Scalar,
+    prefix: Vec<u8>,
+    vk: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Returns the signing key derived from a seed as specified in RFC 8032,
+    /// Sections 5.1.5 and 5.2.5. Seeds have 32 bytes for Ed25519 and 57
+    /// bytes for Ed448.
+    pub fn from_seed(alg: Algorithm, seed: &[u8]) -> Result<SigningKey, Error> {
+        let size = alg.size();
+        if seed.len() != size {
+            return Err(Error::other(ERR_SEED_LEN));
+        }
+        let e = alg.curve();
+        let h = alg.digest(&[seed]);
+        let (lower, prefix) = h.split_at(size);
+        let s = e.new_scalar(alg.clamp(lower));
+        let a = e.get_generator() * &s;
+        let vk = VerifyingKey {
+            alg,
+            enc: a.encode(PointFormat::Compressed),
+            a,
+            e,
+        };
+        Ok(SigningKey {
+            seed: seed.to_vec(),
+            s,
+            prefix: prefix.to_vec(),
+            vk,
+        })
+    }
+    /// Returns a signing key derived from a seed sampled at random.
+    pub fn random<R: RngCore + CryptoRng>(alg: Algorithm, rng: &mut R) -> SigningKey {
+        let mut seed = vec![0u8; alg.size()];
+        rng.fill_bytes(&mut seed);
+        SigningKey::from_seed(alg, &seed).unwrap()
+    }
+    /// Returns the seed of the key.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.seed.clone()
+    }
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.vk
+    }
+    /// Signs `msg` with an empty context.
+    pub fn sign(&self, msg: &[u8]) -> Signature {
+        self.sign_with_context(msg, &[]).unwrap()
+    }
+    /// Signs `msg` under the context `ctx`, which has at most 255 bytes.
+    // based on https://www.rfc-editor.org/rfc/rfc8032#section-5.1.6
+    pub fn sign_with_context(&self, msg: &[u8], ctx: &[u8]) -> Result<Signature, Error> {
+        if ctx.len() > MAX_CONTEXT_LENGTH {
+            return Err(Error::other(ERR_CONTEXT_LEN));
+        }
+        let alg = self.vk.alg;
+        let e = &self.vk.e;
+        let r = alg.hash_to_scalar(e, ctx, &[&self.prefix, msg]);
+        let enc_r = (e.get_generator() * &r).encode(PointFormat::Compressed);
+        let k = alg.hash_to_scalar(e, ctx, &[&enc_r, &self.vk.enc, msg]);
+        let s = r + k * &self.s;
+        let mut enc_s = s.to_bytes_le();
+        enc_s.resize(alg.size(), 0u8);
+        let mut sig = enc_r;
+        sig.append(&mut enc_s);
+        Ok(Signature(sig))
+    }
+}
+
+/// VerifyingKey is an EdDSA public key.
+#[derive(Clone)]
+pub struct VerifyingKey {
+    alg: Algorithm,
+    e: Curve,
+    a: Point,
+    enc: Vec<u8>,
+}
+
+impl VerifyingKey {
+    /// Parses a public key encoded as a point of the curve of `alg`.
+    /// Non-canonical encodings are accepted here, but the verification
+    /// rejects them unless the policy is `SignaturePolicy::Zip215`.
+    pub fn from_bytes(alg: Algorithm, buf: &[u8]) -> Result<VerifyingKey, Error> {
+        if buf.len() != alg.size() {
+            return Err(Error::other(ERR_KEY_LEN));
+        }
+        let e = alg.curve();
+        let a = decode_point(&e, buf)?;
+        Ok(VerifyingKey {
+            alg,
+            enc: buf.to_vec(),
+            a,
+            e,
+        })
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.enc.clone()
+    }
+    pub fn as_point(&self) -> &Point {
+        &self.a
+    }
+    /// Verifies a signature of `msg` made with an empty context.
+    pub fn verify(&self, msg: &[u8], sig: &Signature) -> Result<(), Error> {
+        self.verify_with_context(msg, &[], sig)
+    }
+    /// Verifies a signature of `msg` made under the context `ctx` with the
+    /// strict policy.
+    pub fn verify_with_context(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        sig: &Signature,
+    ) -> Result<(), Error> {
+        self.verify_with(msg, ctx, sig, SignaturePolicy::Strict)
+    }
+    /// Verifies a signature of `msg` made under the context `ctx` with the
+    /// given policy.
+    // based on https://www.rfc-editor.org/rfc/rfc8032#section-5.1.7
+    pub fn verify_with(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        sig: &Signature,
+        policy: SignaturePolicy,
+    ) -> Result<(), Error> {
+        let (r, s, k) = self.parse(msg, ctx, sig, policy)?;
+        let diff = self.e.get_generator() * s - r - &self.a * k;
+        let valid = match policy {
+            SignaturePolicy::Strict => diff.is_identity(),
+            SignaturePolicy::Cofactored | SignaturePolicy::Zip215 => diff.is_small_order(),
+        };
+        if !valid {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        Ok(())
+    }
+    /// Returns the point `R`, the integer `S`, and the challenge `k` of a
+    /// signature after checking its encoding according to `policy`.
+    fn parse(
+        &self,
+        msg: &[u8],
+        ctx: &[u8],
+        sig: &Signature,
+        policy: SignaturePolicy,
+    ) -> Result<(Point, Scalar, Scalar), Error> {
+        let alg = self.alg;
+        let e = &self.e;
+        if ctx.len() > MAX_CONTEXT_LENGTH {
+            return Err(Error::other(ERR_CONTEXT_LEN));
+        }
+        if sig.0.len() != 2 * alg.size() {
+            return Err(Error::other(ERR_SIG_LEN));
+        }
+        let (enc_r, enc_s) = sig.0.split_at(alg.size());
+        let r = decode_point(e, enc_r)?;
+        let canonical = r.encode(PointFormat::Compressed) == enc_r
+            && self.a.encode(PointFormat::Compressed) == self.enc;
+        if policy != SignaturePolicy::Zip215 && !canonical {
+            return Err(Error::other(ERR_NON_CANONICAL));
+        }
+        let s = BigUint::from_bytes_le(enc_s);
+        if s >= e.get_order() {
+            return Err(Error::other(ERR_SIG_RANGE));
+        }
+        let s = e.new_scalar(s.into());
+        let k = alg.hash_to_scalar(e, ctx, &[enc_r, &self.enc, msg]);
+        Ok((r, s, k))
+    }
+}
+
+/// Verifies a batch of signatures, each given as a message, a signature
+/// made with an empty context, and the public key of the signer. All keys
+/// must be of the same algorithm.
+///
+/// The signatures are checked at once using a random linear combination of
+/// the verification equations, which costs a single multi-scalar
+/// multiplication. It succeeds if and only if every signature is valid
+/// under `SignaturePolicy::Cofactored`, except with negligible probability;
+/// a failure does not tell which signatures are invalid. The coefficients
+/// of the combination are drawn from `rng`, and must be unpredictable to
+/// the signers.
+///
+/// With the `parallel` feature, the signatures are parsed and the
+/// multiplication is computed on several threads. The coefficients are
+/// still drawn in the order of `items`, and the first error in that order
+/// is reported, so the outcome does not depend on the feature.
+pub fn verify_batch<R: RngCore + CryptoRng>(
+    items: &[(&[u8], &Signature, &VerifyingKey)],
+    rng: &mut R,
+) -> Result<(), Error> {
+    let (_, _, vk) = match items.first() {
+        Some(item) => item,
+        None => return Ok(()),
+    };
+    let e = &vk.e;
+    let parsed = map_batch(items, |(msg, sig, item_vk)| {
+        if item_vk.alg != vk.alg {
+            return Err(Error::other(ERR_BATCH_ALG));
+        }
+        item_vk.parse(msg, &[], sig, SignaturePolicy::Cofactored)
+    });
+    let mut points = vec![e.get_generator()];
+    let mut scalars = vec![e.new_scalar(BigInt::zero())];
+    let mut buf = [0u8; BATCH_COEFFICIENT_BYTES];
+    for ((_, _, vk), item) in items.iter().zip(parsed) {
+        let (r, s, k) = item?;
+        rng.fill_bytes(&mut buf);
+        let z = e.new_scalar(BigInt::from_bytes_le(Sign::Plus, &buf));
+        scalars[0] = &scalars[0] - &z * s;
+        points.push(r);
+        scalars.push(z.clone());
+        points.push(vk.a.clone());
+        scalars.push(z * k);
+    }
+    let len = batch_chunk_len(points.len());
+    let chunks: Vec<_> = points.chunks(len).zip(scalars.chunks(len)).collect();
+    let sum: Point = map_batch(&chunks, |(p, k)| msm(&e.identity(), p, k))
+        .into_iter()
+        .sum();
+    if !sum.is_small_order() {
+        return Err(Error::other(ERR_SIG_INVALID));
+    }
+    Ok(())
+}
+
+/// Returns the point encoded in `buf`, also accepting the non-canonical
+/// encodings, i.e., those with `y` not reduced modulo `p`, and those of
+/// points with `x = 0` whose sign bit is set.
+fn decode_point(e: &Curve, buf: &[u8]) -> Result<Point, Error> {
+    let last = buf.len() - 1;
+    let sign = buf[last] & 0x80;
+    let mut y = buf.to_vec();
+    y[last] &= 0x7f;
+    let y = BigInt::from_bytes_le(Sign::Plus, &y) % e.get_field().get_modulus();
+    let (_, mut enc) = y.to_bytes_le();
+    enc.resize(buf.len(), 0u8);
+    enc[last] |= sign;
+    e.decode(&enc).or_else(|err| {
+        if sign == 0 {
+            return Err(err);
+        }
+        enc[last] &= 0x7f;
+        e.decode(&enc)
+    })
+}
+
+const DOM2: &[u8] = b"SigEd25519 no Ed25519 collisions";
+const DOM4: &[u8] = b"SigEd448";
+const MAX_CONTEXT_LENGTH: usize = 255;
+const BATCH_COEFFICIENT_BYTES: usize = 16;
+const ERR_SIG_LEN: &str = "wrong length of signature";
+const ERR_SIG_RANGE: &str = "signature integer must be less than the order";
+const ERR_SIG_INVALID: &str = "invalid signature";
+const ERR_NON_CANONICAL: &str = "non-canonical encoding of point";
+const ERR_BATCH_ALG: &str = "all keys of a batch must be of the same algorithm";
+const ERR_SEED_LEN: &str = "wrong length of seed";
+const ERR_KEY_LEN: &str = "wrong length of public key";
+const ERR_CONTEXT_LEN: &str = "context must have at most 255 bytes";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand_core::OsRng;
+
+    use super::{verify_batch, Algorithm, Signature, SignaturePolicy, SigningKey, VerifyingKey};
+    use crate::edwards::Scalar;
+    use crate::ellipticcurve::{EllipticCurve, Encode, PointFormat};
+    use crate::field::Field;
+    use crate::ops::Serialize;
+
+    // Test vectors taken from RFC 8032, Sections 7.1, 7.2, and 7.4.
+    struct Vector {
+        alg: Algorithm,
+        ctx: &'static str,
+        sk: &'static str,
+        pk: &'static str,
+        msg: &'static str,
+        sig: &'static str,
+    }
+
+    #[test]
+    fn rfc8032_vectors() {
+        let vectors = [
+            Vector {
+                alg: Algorithm::Ed25519,
+                ctx: "",
+                sk: "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+                pk: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+                msg: "",
+                sig: "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+            },
+            Vector {
+                alg: Algorithm::Ed25519,
+                ctx: "",
+                sk: "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+                pk: "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+                msg: "72",
+                sig: "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+            },
+            Vector {
+                alg: Algorithm::Ed25519,
+                ctx: "",
+                sk: "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+                pk: "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+                msg: "af82",
+                sig: "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+            },
+            Vector {
+                alg: Algorithm::Ed25519,
+                ctx: "",
+                sk: "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42",
+                pk: "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf",
+                msg: "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+                sig: "dc2a4459e7369633a52b1bf277839a00201009a3efbf3ecb69bea2186c26b58909351fc9ac90b3ecfdfbc7c66431e0303dca179c138ac17ad9bef1177331a704",
+            },
+            Vector {
+                alg: Algorithm::Ed25519,
+                ctx: "666f6f",
+                sk: "0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6",
+                pk: "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292",
+                msg: "f726936d19c800494e3fdaff20b276a8",
+                sig: "55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d",
+            },
+            Vector {
+                alg: Algorithm::Ed448,
+                ctx: "",
+                sk: "6c82a562cb808d10d632be89c8513ebf6c929f34ddfa8c9f63c9960ef6e348a3528c8a3fcc2f044e39a3fc5b94492f8f032e7549a20098f95b",
+                pk: "5fd7449b59b461fd2ce787ec616ad46a1da1342485a70e1f8a0ea75d80e96778edf124769b46c7061bd6783df1e50f6cd1fa1abeafe8256180",
+                msg: "",
+                sig: "533a37f6bbe457251f023c0d88f976ae2dfb504a843e34d2074fd823d41a591f2b233f034f628281f2fd7a22ddd47d7828c59bd0a21bfd3980ff0d2028d4b18a9df63e006c5d1c2d345b925d8dc00b4104852db99ac5c7cdda8530a113a0f4dbb61149f05a7363268c71d95808ff2e652600",
+            },
+            Vector {
+                alg: Algorithm::Ed448,
+                ctx: "",
+                sk: "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463afbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+                pk: "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c0866aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+                msg: "03",
+                sig: "26b8f91727bd62897af15e41eb43c377efb9c610d48f2335cb0bd0087810f4352541b143c4b981b7e18f62de8ccdf633fc1bf037ab7cd779805e0dbcc0aae1cbcee1afb2e027df36bc04dcecbf154336c19f0af7e0a6472905e799f1953d2a0ff3348ab21aa4adafd1d234441cf807c03a00",
+            },
+            Vector {
+                alg: Algorithm::Ed448,
+                ctx: "666f6f",
+                sk: "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463afbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+                pk: "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c0866aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+                msg: "03",
+                sig: "d4f8f6131770dd46f40867d6fd5d5055de43541f8c5e35abbcd001b32a89f7d2151f7647f11d8ca2ae279fb842d607217fce6e042f6815ea000c85741de5c8da1144a6a1aba7f96de42505d7a7298524fda538fccbbb754f578c1cad10d54d0d5428407e85dcbc98a49155c13764e66c3c00",
+            },
+        ];
+        for v in vectors.iter() {
+            let sk = SigningKey::from_seed(v.alg, &hex::decode(v.sk).unwrap()).unwrap();
+            let msg = hex::decode(v.msg).unwrap();
+            let ctx = hex::decode(v.ctx).unwrap();
+            assert_eq!(hex::encode(sk.verifying_key().to_bytes()), v.pk);
+            let sig = sk.sign_with_context(&msg, &ctx).unwrap();
+            assert_eq!(hex::encode(sig.to_bytes()), v.sig);
+            let vk = VerifyingKey::from_bytes(v.alg, &hex::decode(v.pk).unwrap()).unwrap();
+            let sig = Signature::from_bytes(v.alg, &hex::decode(v.sig).unwrap()).unwrap();
+            assert!(vk.verify_with_context(&msg, &ctx, &sig).is_ok());
+            assert!(vk.verify_with_context(b"forgery", &ctx, &sig).is_err());
+            assert!(vk.verify_with_context(&msg, b"bar", &sig).is_err());
+        }
+    }
+
+    // The remaining vectors of RFC 8032, Sections 7.1 and 7.4, have long
+    // messages, so they are kept in a file.
+    #[test]
+    fn rfc8032_long_vectors() {
+        let data: serde_json::Value =
+            serde_json::from_str(include_str!("vectors/rfc8032.json")).unwrap();
+        for v in data["vectors"].as_array().unwrap() {
+            let field = |name: &str| hex::decode(v[name].as_str().unwrap()).unwrap();
+            let alg = match v["algorithm"].as_str().unwrap() {
+                "Ed25519" => Algorithm::Ed25519,
+                _ => Algorithm::Ed448,
+            };
+            let name = v["name"].as_str().unwrap();
+            let (msg, ctx) = (field("message"), field("context"));
+            let sk = SigningKey::from_seed(alg, &field("secretKey")).unwrap();
+            assert_eq!(
+                sk.verifying_key().to_bytes(),
+                field("publicKey"),
+                "{}",
+                name
+            );
+            let sig = sk.sign_with_context(&msg, &ctx).unwrap();
+            assert_eq!(sig.to_bytes(), field("signature"), "{}", name);
+            assert!(sk
+                .verifying_key()
+                .verify_with_context(&msg, &ctx, &sig)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn malformed_signatures() {
+        let alg = Algorithm::Ed25519;
+        let sk = SigningKey::random(alg, &mut OsRng);
+        let vk = sk.verifying_key();
+        let sig = sk.sign(b"abc").to_bytes();
+        assert!(Signature::from_bytes(alg, &sig[1..]).is_err());
+        assert!(sk.sign_with_context(b"abc", &[0u8; 256]).is_err());
+        // S + L is rejected, although it passes the verification equation
+        let order = hex::decode("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010")
+            .unwrap();
+        let mut malleated = sig.clone();
+        let mut carry = 0u16;
+        for i in 0..32 {
+            let sum = malleated[32 + i] as u16 + order[i] as u16 + carry;
+            malleated[32 + i] = sum as u8;
+            carry = sum >> 8;
+        }
+        let malleated = Signature::from_bytes(alg, &malleated).unwrap();
+        assert!(vk.verify(b"abc", &malleated).is_err());
+        // R is not a point of the curve
+        let mut invalid = sig;
+        invalid[..32].copy_from_slice(&[0xffu8; 32]);
+        let invalid = Signature::from_bytes(alg, &invalid).unwrap();
+        assert!(vk.verify(b"abc", &invalid).is_err());
+    }
+
+    #[test]
+    fn malformed_keys() {
+        for &alg in [Algorithm::Ed25519, Algorithm::Ed448].iter() {
+            let pk = SigningKey::random(alg, &mut OsRng)
+                .verifying_key()
+                .to_bytes();
+            assert!(VerifyingKey::from_bytes(alg, &pk).is_ok());
+            assert!(VerifyingKey::from_bytes(alg, &[]).is_err());
+            assert!(VerifyingKey::from_bytes(alg, &pk[1..]).is_err());
+            let mut long = pk.clone();
+            long.extend_from_slice(&pk);
+            assert!(VerifyingKey::from_bytes(alg, &long).is_err());
+        }
+    }
+
+    #[test]
+    fn policies() {
+        let alg = Algorithm::Ed25519;
+        let sk = SigningKey::random(alg, &mut OsRng);
+        let vk = sk.verifying_key();
+        let e = &vk.e;
+        let f = e.get_field();
+        let sign = |enc_r: Vec<u8>, r: &Scalar| {
+            let k = alg.hash_to_scalar(e, &[], &[&enc_r, &vk.enc, b"abc"]);
+            let mut sig = enc_r;
+            sig.append(&mut (r + k * &sk.s).to_bytes_le());
+            Signature::from_bytes(alg, &sig).unwrap()
+        };
+        let check = |sig: &Signature, want: [bool; 3]| {
+            let got = [
+                SignaturePolicy::Strict,
+                SignaturePolicy::Cofactored,
+                SignaturePolicy::Zip215,
+            ]
+            .iter()
+            .map(|&p| vk.verify_with(b"abc", &[], sig, p).is_ok())
+            .collect::<Vec<_>>();
+            assert_eq!(got, want);
+        };
+        // R has a component of small order
+        let r0 = e.new_scalar(BigInt::from(7));
+        let t2 = e.new_point(f.zero(), -f.one());
+        let r = e.get_generator() * &r0 + t2;
+        check(
+            &sign(r.encode(PointFormat::Compressed), &r0),
+            [false, true, true],
+        );
+        // R is the identity encoded with y = p + 1
+        let zero = e.new_scalar(BigInt::from(0));
+        let id = e.identity();
+        let mut enc = f.get_modulus().to_bytes_le().1;
+        enc[0] += 1;
+        check(&sign(enc, &zero), [false, false, true]);
+        // R is the identity encoded with the sign bit set
+        let mut enc = id.encode(PointFormat::Compressed);
+        enc[31] |= 0x80;
+        check(&sign(enc, &zero), [false, false, true]);
+        check(&sk.sign(b"abc"), [true, true, true]);
+    }
+
+    #[test]
+    fn batch() {
+        let alg = Algorithm::Ed25519;
+        let keys: Vec<_> = (0..3)
+            .map(|_| SigningKey::random(alg, &mut OsRng))
+            .collect();
+        let msgs: Vec<Vec<u8>> = (0..8u8).map(|i| vec![i; i as usize]).collect();
+        let sigs: Vec<_> = msgs
+            .iter()
+            .enumerate()
+            .map(|(i, m)| keys[i % 3].sign(m))
+            .collect();
+        let mut items: Vec<_> = msgs
+            .iter()
+            .zip(sigs.iter())
+            .enumerate()
+            .map(|(i, (m, s))| (&m[..], s, keys[i % 3].verifying_key()))
+            .collect();
+        assert!(verify_batch(&items, &mut OsRng).is_ok());
+        assert!(verify_batch(&[], &mut OsRng).is_ok());
+        items[5].0 = b"forgery";
+        assert!(verify_batch(&items, &mut OsRng).is_err());
+        items[5].0 = &msgs[5];
+        items[2].2 = keys[0].verifying_key();
+        assert!(verify_batch(&items, &mut OsRng).is_err());
+        let other = SigningKey::random(Algorithm::Ed448, &mut OsRng);
+        let sig = other.sign(b"abc");
+        items[2] = (b"abc", &sig, other.verifying_key());
+        assert!(verify_batch(&items, &mut OsRng).is_err());
+    }
+}
@@ -2,39 +2,82 @@
 //!
 //! The curve module is meant to be used for bar.
 
-use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
+use num_bigint::{BigInt, BigUint, ToBigInt};
 use num_traits::identities::Zero;
 
-use std::io::{Error, ErrorKind};
-use std::str::FromStr;
+use std::io::Error;
+use std::sync::Arc;
 
-use crate::do_if_eq;
 use crate::edwards::point::{Point, ProyCoordinates};
 use crate::edwards::scalar::Scalar;
-use crate::ellipticcurve::{Decode, EllipticCurve};
+use crate::ellipticcurve::{Decode, EllipticCurve, PointFormat};
 use crate::field::{Field, Sgn0, Sqrt};
+use crate::integer::Integer;
 use crate::ops::FromFactory;
 use crate::primefield::{Fp, FpElt};
+use crate::{do_if_eq, uncounted};
 
 /// This is an elliptic curve defined in the twisted Edwards model and defined by the equation:
 /// ax^2+y^2=1+dx^2y^2.
 ///
-#[derive(Clone, PartialEq)]
-pub struct Curve {
-    pub(super) f: Fp,
-    pub(super) a: FpElt,
-    pub(super) d: FpElt,
-    pub(super) r: BigUint,
-    pub(super) gx: FpElt,
-    pub(super) gy: FpElt,
-    pub(super) h: BigUint,
+#[derive(Clone)]
+pub struct Curve(pub(crate) Arc<CurveInner>);
+
+/// CurveInner holds the parameters of a curve, which are shared by its points
+/// instead of being copied into each of them.
+pub struct CurveInner {
+    pub(crate) name: &'static str,
+    pub(crate) f: Fp,
+    pub(crate) a: FpElt,
+    pub(crate) d: FpElt,
+    pub(crate) r: BigUint,
+    pub(crate) gx: FpElt,
+    pub(crate) gy: FpElt,
+    pub(crate) h: BigUint,
+    // constants computed once for the formulas and the decoder
+    pub(crate) zero: FpElt,
+    pub(crate) one: FpElt,
+}
+
+impl std::ops::Deref for Curve {
+    type Target = CurveInner;
+    fn deref(&self) -> &CurveInner {
+        &self.0
+    }
+}
+
+/// Curves are compared by their parameters, regardless of their names.
+impl PartialEq for Curve {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+            || (self.f == other.f
+                && self.a == other.a
+                && self.d == other.d
+                && self.r == other.r
+                && self.gx == other.gx
+                && self.gy == other.gy
+                && self.h == other.h)
+    }
 }
 
 impl Curve {
+    /// Returns the name given in the parameters of the curve, which is empty
+    /// for curves derived from other curves.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// Returns the coefficient `a` of the equation `ax^2+y^2=1+dx^2y^2`.
+    pub fn a(&self) -> &FpElt {
+        &self.a
+    }
+    /// Returns the coefficient `d` of the equation `ax^2+y^2=1+dx^2y^2`.
+    pub fn d(&self) -> &FpElt {
+        &self.d
+    }
     pub(crate) fn new_proy_point(&self, c: ProyCoordinates) -> Point {
         let e = self.clone();
         let pt = Point { e, c };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        do_if_eq!(uncounted!(self.is_on_curve(&pt)), pt, ERR_ECC_NEW)
     }
 }
 
@@ -44,29 +87,27 @@ impl EllipticCurve for Curve {
     type Point = Point;
     fn new_point(&self, x: <Self::F as Field>::Elt, y: <Self::F as Field>::Elt) -> Self::Point {
         let e = self.clone();
-        let f = e.get_field();
         let pt = Point {
             c: ProyCoordinates {
                 t: &x * &y,
                 x,
                 y,
-                z: f.one(),
+                z: self.one.clone(),
             },
             e,
         };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        do_if_eq!(uncounted!(self.is_on_curve(&pt)), pt, ERR_ECC_NEW)
     }
 
     fn new_scalar(&self, k: BigInt) -> Self::Scalar {
         Scalar::new(k, &self.r)
     }
     fn identity(&self) -> Self::Point {
-        let f = &self.f;
         self.new_proy_point(ProyCoordinates {
-            x: f.zero(),
-            y: f.one(),
-            t: f.zero(),
-            z: f.one(),
+            x: self.zero.clone(),
+            y: self.one.clone(),
+            t: self.zero.clone(),
+            z: self.one.clone(),
         })
     }
     fn is_on_curve(&self, p: &Self::Point) -> bool {
@@ -97,61 +138,67 @@ impl EllipticCurve for Curve {
             x: self.gx.clone(),
             y: self.gy.clone(),
             t: &self.gx * &self.gy,
-            z: self.f.one(),
+            z: self.one.clone(),
         })
     }
 }
 
 impl Decode for Curve {
     type Deser = Point;
-    // based on https://tools.ietf.org/html/rfc8032#section-5.2.3
-    fn decode(&self, buf: &[u8]) -> Result<Self::Deser, Error> {
+    // based on https://tools.ietf.org/html/rfc8032#section-5.2.3, whose
+    // encodings are always compressed
+    fn decode_with_format(&self, buf: &[u8]) -> Result<(Self::Deser, PointFormat), Error> {
         let modulus = self.get_field().get_modulus();
-        let size = (modulus.bits() + 1 + 7) / 8;
+        let size = (modulus.bits() + 1).div_ceil(8);
         // step 1
         if buf.len() != size {
-            return Err(Error::new(ErrorKind::Other, "Wrong input buffer size."));
+            return Err(Error::other("Wrong input buffer size."));
         }
         let last_byte = size - 1;
         let x_0 = (buf[last_byte] >> 7) & 0x01;
         let mut y_bytes = buf.to_vec();
         y_bytes[last_byte] &= &127; // clear msb
-        let y_zz = BigInt::from_bytes_le(Sign::Plus, &y_bytes);
-        if y_zz >= modulus {
-            return Err(Error::new(ErrorKind::Other, "Invalid y value chosen"));
+
+        // the encoding has one byte more than the field when its bit length
+        // is a multiple of 8, as in Ed448, and that byte must be zero
+        let (y_bytes, pad) = y_bytes.split_at(self.f.size_bytes());
+        if pad.iter().any(|&b| b != 0) {
+            return Err(Error::other("Invalid y value chosen"));
         }
-        let y = self.f.elt(y_zz);
+        let y = self.f.from_canonical_le(y_bytes)?;
 
         // step 2
         let yy = &y * &y;
-        let minus_one = -self.f.one();
-        let u = &yy + &minus_one;
+        let u = &yy - &self.one;
         let v = (&self.d * &yy) - &self.a;
         let u_inv_v = u / v;
+        if !u_inv_v.is_square() {
+            return Err(Error::other("Failed decoding on square root"));
+        }
         let x_sqrt = u_inv_v.sqrt();
 
         // step 4 (step 3 is unnecessary)
-        if x_sqrt == self.f.zero() && x_0 == 0x01 {
-            return Err(Error::new(
-                ErrorKind::Other,
-                "Failed decoding on square root",
-            ));
+        if x_sqrt.is_zero() && x_0 == 0x01 {
+            return Err(Error::other("Failed decoding on square root"));
         }
         let tag = ((x_sqrt.sgn0() >> 1) & 0x01) as u8;
         let mut x = x_sqrt;
         if tag != x_0 {
             x = -x;
         }
-        Ok(self.new_point(x, y))
+        Ok((self.new_point(x, y), PointFormat::Compressed))
     }
 }
 
 impl std::fmt::Display for Curve {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if !self.name.is_empty() {
+            write!(f, "{}: ", self.name)?;
+        }
         write!(
             f,
-            "Twisted Edwards Curve ax^2+y^2=1+dx^2y^2\na: {}\nd: {}",
-            self.a, self.d,
+            "Twisted Edwards Curve ax^2+y^2=1+dx^2y^2 over {}\na: {}\nd: {}",
+            self.f, self.a, self.d,
         )
     }
 }
@@ -159,27 +206,30 @@ impl std::fmt::Display for Curve {
 #[derive(PartialEq, Eq)]
 pub struct Params {
     pub name: &'static str,
-    pub p: &'static str,
-    pub a: &'static str,
-    pub d: &'static str,
-    pub r: &'static str,
-    pub h: &'static str,
-    pub gx: &'static str,
-    pub gy: &'static str,
+    pub p: Integer,
+    pub a: Integer,
+    pub d: Integer,
+    pub r: Integer,
+    pub h: Integer,
+    pub gx: Integer,
+    pub gy: Integer,
 }
 
 impl<'a> std::convert::From<&'a Params> for Curve {
     fn from(params: &'a Params) -> Curve {
-        let f = Fp::new(BigUint::from_str(params.p).unwrap());
-        Curve {
+        let f = Fp::new(params.p.to_biguint());
+        Curve(Arc::new(CurveInner {
+            name: params.name,
             a: f.from(params.a),
             d: f.from(params.d),
-            r: BigUint::from_str(params.r).unwrap(),
-            h: BigUint::from_str(params.h).unwrap(),
+            r: params.r.to_biguint(),
+            h: params.h.to_biguint(),
             gx: f.from(params.gx),
             gy: f.from(params.gy),
+            zero: f.zero(),
+            one: f.one(),
             f,
-        }
+        }))
     }
 }
 
@@ -188,18 +238,23 @@ const ERR_ECC_NEW: &str = "not valid point";
 // tests for ser/deser
 #[cfg(test)]
 mod tests {
-    use crate::ellipticcurve::{Decode, EllipticCurve, Encode};
+    use num_bigint::BigInt;
+    use num_traits::identities::{One, Zero};
+    use rand_core::OsRng;
+
+    use crate::edwards::{Point, Scalar};
+    use crate::ellipticcurve::{Decode, DecodePolicy, EcPoint, EllipticCurve, Encode, PointFormat};
     use crate::field::Field;
-    use crate::instances::{GetCurve, EDWARDS25519, EDWARDS448};
+    use crate::instances::{GetCurve, EDWARDS25519, EDWARDS448, JUBJUB};
 
     #[test]
     fn point_serialization() {
-        for &id in [EDWARDS25519, EDWARDS448].iter() {
+        for &id in [EDWARDS25519, EDWARDS448, JUBJUB].iter() {
             let ec = id.get();
             let modulus = ec.get_field().get_modulus();
             let gen = ec.get_generator();
-            let ser = gen.encode(false); // compression does not exist
-            assert_eq!(ser.len(), (modulus.bits() + 1 + 7) / 8);
+            let ser = gen.encode(PointFormat::Uncompressed); // compression does not exist
+            assert_eq!(ser.len(), (modulus.bits() + 1).div_ceil(8));
             let deser = ec.decode(&ser).unwrap();
             assert!(
                 ec.is_on_curve(&deser),
@@ -209,4 +264,193 @@ mod tests {
             assert!(gen == deser, "decompressed point equality check for {}", id);
         }
     }
+
+    #[test]
+    fn zero_scalar() {
+        for &id in [EDWARDS25519, EDWARDS448].iter() {
+            let ec = id.get();
+            let k = ec.new_scalar(BigInt::from(0));
+            let p = &ec.get_generator() * &k;
+            assert!(p.is_zero(), "[0]G must be the identity for {}", id);
+        }
+    }
+
+    #[test]
+    fn identity_serialization() {
+        for &id in [EDWARDS25519, EDWARDS448, JUBJUB].iter() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let inf = ec.identity();
+            let t2 = ec.new_point(f.zero(), -f.one());
+            assert!(inf.is_identity(), "identity check for {}", id);
+            assert!(!t2.is_identity(), "point of order two of {}", id);
+            let ser = inf.encode(PointFormat::Uncompressed);
+            let mut want = vec![0u8; ser.len()];
+            want[0] = 1;
+            assert_eq!(ser, want, "identity encoding for {}", id);
+            let deser = ec.decode(&ser).unwrap();
+            assert!(deser.is_identity(), "identity round-trip for {}", id);
+        }
+    }
+
+    #[test]
+    fn non_canonical_encodings() {
+        for &id in [EDWARDS25519, EDWARDS448, JUBJUB].iter() {
+            let ec = id.get();
+            let ser = ec.get_generator().encode(PointFormat::Uncompressed);
+            let mut long = ser.clone();
+            long.push(0);
+            assert!(ec.decode(&long).is_err(), "over-long encoding for {}", id);
+            // y = p, with the sign bit of x either clear or set
+            let mut bad_y = vec![0u8; ser.len()];
+            let p = ec.get_field().get_modulus().to_bytes_le().1;
+            bad_y[..p.len()].copy_from_slice(&p);
+            assert!(ec.decode(&bad_y).is_err(), "y >= p for {}", id);
+            *bad_y.last_mut().unwrap() |= 0x80;
+            assert!(ec.decode(&bad_y).is_err(), "y >= p for {}", id);
+        }
+        // the extra byte of Ed448 encodings only holds the sign of x
+        let ec = EDWARDS448.get();
+        let mut ser = ec.get_generator().encode(PointFormat::Uncompressed);
+        *ser.last_mut().unwrap() |= 0x01;
+        assert!(ec.decode(&ser).is_err());
+    }
+
+    #[test]
+    fn small_order() {
+        for &id in [EDWARDS25519, EDWARDS448, JUBJUB].iter() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let gen = ec.get_generator();
+            let t2 = ec.new_point(f.zero(), -f.one());
+            assert!(!gen.is_small_order(), "generator of {}", id);
+            assert!(t2.is_small_order(), "point of order two of {}", id);
+            assert!(ec.identity().is_small_order(), "identity of {}", id);
+            assert!(
+                !(gen.clone() + &t2).is_small_order(),
+                "mixed point of {}",
+                id
+            );
+            let policy = DecodePolicy::RejectSmallOrder;
+            assert!(ec
+                .decode_with(&gen.encode(PointFormat::Uncompressed), policy)
+                .is_ok());
+            assert!(ec
+                .decode_with(&t2.encode(PointFormat::Uncompressed), policy)
+                .is_err());
+            assert!(ec
+                .decode_with(&ec.identity().encode(PointFormat::Uncompressed), policy)
+                .is_err());
+            assert!(ec
+                .decode_with(
+                    &t2.encode(PointFormat::Uncompressed),
+                    DecodePolicy::AcceptAll
+                )
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn scalar_blinding() {
+        for &id in [EDWARDS25519, JUBJUB].iter() {
+            let ec = id.get();
+            let g = ec.get_generator();
+            let k = ec.new_scalar(BigInt::from(0x1234_5678u64));
+            let want = &g * &k;
+            assert!(g.mul_blinded(&k, &mut OsRng) == want, "{}", id);
+            assert!(g.mul_blinded(&k, &mut OsRng) == want, "{}", id);
+        }
+    }
+
+    #[test]
+    fn randomized_representation() {
+        for &id in [EDWARDS25519, JUBJUB].iter() {
+            let ec = id.get();
+            let g = ec.get_generator();
+            let mut p = g.clone();
+            p.randomize_representation(&mut OsRng);
+            assert!(p == g, "{}", id);
+            assert!(
+                p.encode(PointFormat::Compressed) == g.encode(PointFormat::Compressed),
+                "{}",
+                id
+            );
+            let mut o = ec.identity();
+            o.randomize_representation(&mut OsRng);
+            assert!(o.is_identity(), "{}", id);
+        }
+    }
+
+    #[test]
+    fn assign_ops() {
+        let ec = EDWARDS25519.get();
+        let g = ec.get_generator();
+        let (k, l) = (
+            ec.new_scalar(BigInt::from(5)),
+            ec.new_scalar(BigInt::from(7)),
+        );
+        let mut p = ec.identity();
+        p += &g;
+        p += g.clone();
+        p -= &g;
+        p *= &k;
+        assert!(p == &g * &k);
+        p *= l.clone();
+        p -= g.clone();
+        assert!(p == &g * &(&k * &l) - &g);
+
+        let mut s = k.clone();
+        s += &l;
+        s *= &l;
+        s -= k.clone();
+        s /= &l;
+        assert!(&g * &s == &g * &((&(&k + &l) * &l - &k) / &l));
+    }
+
+    #[test]
+    fn iter_sums() {
+        let ec = EDWARDS25519.get();
+        let g = ec.get_generator();
+        let k: Vec<_> = (1..5).map(|i| ec.new_scalar(BigInt::from(i))).collect();
+        let points: Vec<_> = k.iter().map(|k| &g * k).collect();
+        let sum: Point = points.iter().sum();
+        assert!(sum == &g * &ec.new_scalar(BigInt::from(10)));
+        assert!(points.clone().into_iter().sum::<Point>() == sum);
+        assert!(ec.sum(points) == sum);
+        assert!(ec.sum(Vec::new()).is_identity());
+
+        let s: Scalar = k.iter().sum();
+        assert!(&g * &s == sum);
+        let p: Scalar = k.into_iter().product();
+        assert!(&g * &p == &g * &ec.new_scalar(BigInt::from(24)));
+    }
+
+    #[test]
+    fn formatting() {
+        let ec = EDWARDS25519.get();
+        let g = ec.get_generator();
+        let enc = "5866666666666666666666666666666666666666666666666666666666666666";
+        assert_eq!(g.to_string(), enc);
+        let dbg = format!("{:?}", &g + &g - &g);
+        assert!(dbg.starts_with("Point { curve: \"edwards25519\", x: 0x2169"));
+        assert!(dbg
+            .ends_with("y: 0x6666666666666666666666666666666666666666666666666666666666666658 }"));
+        let o = ec.identity();
+        assert_eq!(
+            format!("{:?}", o),
+            "Point { curve: \"edwards25519\", identity }"
+        );
+    }
+
+    #[test]
+    fn affine_coordinates() {
+        let ec = EDWARDS25519.get();
+        let g = ec.get_generator();
+        let p = &g + &g - &g;
+        let gy = "6666666666666666666666666666666666666666666666666666666666666658";
+        assert_eq!(format!("{:x}", p.y()), gy);
+        assert!(p.x() == g.x());
+        let (x, y) = ec.identity().to_affine();
+        assert!(x.is_zero() && y.is_one());
+    }
 }
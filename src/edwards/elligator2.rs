@@ -1,39 +1,83 @@
+use rand_core::{CryptoRng, RngCore};
+
+use std::io::Error;
+use std::sync::Arc;
+
 use crate::edwards::Curve as TeCurve;
-use crate::ellipticcurve::{EllipticCurve, MapToCurve, RationalMap};
-use crate::field::Field;
+use crate::edwards::{Point as TePoint, ProyCoordinates as TeProyCoordinates};
+use crate::ellipticcurve::{EcPoint, EllipticCurve, MapToCurve, RationalMap};
+use crate::field::{Field, Sqrt};
 use crate::montgomery::Curve as MtCurve;
+use crate::montgomery::CurveInner as MtCurveInner;
 use crate::montgomery::Ell2 as MtEll2;
+use crate::montgomery::{Point as MtPoint, ProyCoordinates as MtProyCoordinates};
+use crate::ops::FromFactory;
 use crate::primefield::FpElt;
 
+/// Ell2 is the Elligator 2 map to a curve in twisted Edwards model as
+/// specified in RFC 9380, Section 6.8.2. Points are obtained by mapping to
+/// an equivalent Montgomery curve, and then pulling them back through a
+/// rational map.
 pub struct Ell2 {
     ratmap: Box<dyn RationalMap<E0 = TeCurve, E1 = MtCurve> + 'static>,
-    map_to_curve: Box<dyn MapToCurve<E = MtCurve> + 'static>,
+    map_to_curve: MtEll2,
 }
 
 impl Ell2 {
+    /// Returns the Elligator 2 map for the curve `e`. If no rational map is
+    /// given, the canonical birational map to a Montgomery curve is used.
     pub fn new(
         e: TeCurve,
         z: FpElt,
         ratmap: Option<Box<dyn RationalMap<E0 = TeCurve, E1 = MtCurve>>>,
     ) -> Ell2 {
-        let (map_to_curve, ratmap) = match ratmap {
-            None => {
-                // If no ratmap is provided, it must use the cannonical birational map.
-                todo!()
-            }
+        let ratmap = match ratmap {
+            // If no ratmap is provided, it must use the cannonical birational map.
+            None => Box::new(Canonical::new(e)),
             Some(r) => {
                 if r.domain() != e {
                     panic!("Domain of rational map is incompatible with curve")
                 }
-                let mt_curve = r.codomain();
-                (Box::new(MtEll2::new(mt_curve, z)), r)
+                r
             }
         };
+        let map_to_curve = MtEll2::new(ratmap.codomain(), z);
         Ell2 {
             map_to_curve,
             ratmap,
         }
     }
+    /// Returns a field element `u` such that `map(u) = p`, or `None` if `p`
+    /// is not in the image of the map. Preimages are found through the
+    /// inverse map of the Montgomery curve, so they only exist when the
+    /// rational map is birational, e.g., not for the 4-isogeny used for
+    /// edwards448.
+    pub fn inverse(&self, p: &TePoint) -> Option<FpElt> {
+        self.map_to_curve
+            .inverse(&self.ratmap.push(p.clone()))
+            .filter(|u| self.map(u) == *p)
+    }
+    /// Returns an Elligator squared encoding of `p` that is
+    /// indistinguishable from a random string, as given by
+    /// `montgomery::Ell2::encode_uniform` on the image of `p` through the
+    /// rational map. It returns `None` if the rational map does not invert
+    /// on `p`, e.g., for the 4-isogeny used for edwards448.
+    pub fn encode_uniform<R: RngCore + CryptoRng>(
+        &self,
+        p: &TePoint,
+        rng: &mut R,
+    ) -> Option<Vec<u8>> {
+        let q = self.ratmap.push(p.clone());
+        if self.ratmap.pull(q.clone()) != *p {
+            return None;
+        }
+        Some(self.map_to_curve.encode_uniform(&q, rng))
+    }
+    /// Returns the point encoded by `encode_uniform`.
+    pub fn decode_uniform(&self, bytes: &[u8]) -> Result<TePoint, Error> {
+        let q = self.map_to_curve.decode_uniform(bytes)?;
+        Ok(self.ratmap.pull(q))
+    }
 }
 
 impl MapToCurve for Ell2 {
@@ -45,3 +89,174 @@ impl MapToCurve for Ell2 {
         self.ratmap.pull(self.map_to_curve.map(u))
     }
 }
+
+/// Canonical is the birational map from ax^2+y^2=1+dx^2y^2 to the
+/// Montgomery curve y^2=x^3+Ax^2+x, where A=2(a+d)/(a-d), given by
+/// (x,y) -> ((1+y)/(1-y), c(1+y)/((1-y)x)) and c=sqrt(4/(a-d)).
+/// It panics if 4/(a-d) is not a square, e.g., for edwards448.
+struct Canonical {
+    e0: TeCurve,
+    e1: MtCurve,
+    c: FpElt,
+}
+
+impl Canonical {
+    fn new(e0: TeCurve) -> Canonical {
+        let f = e0.get_field();
+        let a_minus_d = &e0.a - &e0.d;
+        let inv = 1u32 / &a_minus_d;
+        let b: FpElt = f.from(4) * &inv;
+        if !b.is_square() {
+            panic!("wrong input parameters")
+        }
+        let c = b.sqrt();
+        let a = f.from(2) * (&e0.a + &e0.d) * &inv;
+        let s = MtCurve::find_s(&f, &a);
+        let g = e0.get_generator();
+        let (x, y) = (&g.c.x, &g.c.y);
+        let one = f.one();
+        let gx = (&one + y) / (&one - y);
+        let gy = &c * &gx / x;
+        let a24 = (&a - f.from(2)) / f.from(4);
+        let e1 = MtCurve(Arc::new(MtCurveInner {
+            name: "",
+            f: f.clone(),
+            a,
+            b: f.one(),
+            s,
+            r: e0.r.clone(),
+            gx,
+            gy,
+            h: e0.h.clone(),
+            zero: f.zero(),
+            one: f.one(),
+            inv_b: f.one(),
+            a24,
+        }));
+        Canonical { e0, e1, c }
+    }
+}
+
+impl RationalMap for Canonical {
+    type E0 = TeCurve;
+    type E1 = MtCurve;
+
+    fn domain(&self) -> Self::E0 {
+        self.e0.clone()
+    }
+    fn codomain(&self) -> Self::E1 {
+        self.e1.clone()
+    }
+    fn push(&self, p: TePoint) -> MtPoint {
+        if p.is_zero() {
+            self.e1.identity()
+        } else {
+            let (x, y, z) = (&p.c.x, &p.c.y, &p.c.z);
+            let t0 = z + y;
+            let xx = x * &t0;
+            let yy = &self.c * z * t0;
+            let zz = x * (z - y);
+            self.e1.new_proy_point(MtProyCoordinates {
+                x: xx,
+                y: yy,
+                z: zz,
+            })
+        }
+    }
+    fn pull(&self, p: MtPoint) -> TePoint {
+        if p.is_zero() {
+            self.e0.identity()
+        } else if p.is_two_torsion() {
+            let f = self.e0.get_field();
+            self.e0.new_proy_point(TeProyCoordinates {
+                x: f.zero(),
+                y: -f.one(),
+                t: f.zero(),
+                z: f.one(),
+            })
+        } else {
+            let (x, y, z) = (&p.c.x, &p.c.y, &p.c.z);
+            let add = x + z;
+            let sub = x - z;
+            let xx = &self.c * x * &add;
+            let yy = y * &sub;
+            let tt = &self.c * x * sub;
+            let zz = y * add;
+            self.e0.new_proy_point(TeProyCoordinates {
+                x: xx,
+                y: yy,
+                t: tt,
+                z: zz,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand_core::SeedableRng;
+
+    use super::{Canonical, Ell2};
+    use crate::ellipticcurve::{EcPoint, EllipticCurve, MapToCurve, RationalMap};
+    use crate::instances::{edwards25519_to_curve25519, GetCurve, EDWARDS25519};
+    use crate::ops::FromFactory;
+
+    #[test]
+    fn canonical_map() {
+        let e0 = EDWARDS25519.get();
+        let ratmap = Canonical::new(e0.clone());
+        let e1 = ratmap.codomain();
+        let g0 = e0.get_generator();
+        let g1 = ratmap.push(g0.clone());
+        assert!(g1 == e1.get_generator(), "generator mapping");
+        assert!(ratmap.pull(g1.clone()) == g0, "pull must invert push");
+
+        let k = BigInt::from(1234567);
+        let lhs = ratmap.push(&g0 * &e0.new_scalar(k.clone()));
+        let rhs = &g1 * &e1.new_scalar(k);
+        assert!(lhs == rhs, "map must be a group homomorphism");
+        assert!(ratmap.pull(e1.identity()).is_zero());
+
+        let f = e0.get_field();
+        let map = Ell2::new(e0.clone(), f.from(2), None);
+        for u in 0..8 {
+            let p = map.map(&f.from(u));
+            assert!(e0.is_on_curve(&p), "point must be on curve for u={}", u);
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        let e = EDWARDS25519.get();
+        let f = e.get_field();
+        let ratmap = Box::new(edwards25519_to_curve25519());
+        let ell2 = Ell2::new(e.clone(), f.from(2), Some(ratmap));
+        for i in 1..16 {
+            let u = f.from(1000 * i);
+            let p = ell2.map(&u);
+            let v = ell2.inverse(&p).expect("point must have a preimage");
+            assert!(v == u || v == -&u, "preimage of map(u) must be u or -u");
+        }
+    }
+
+    #[test]
+    fn elligator_squared() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(733);
+        let e = EDWARDS25519.get();
+        let f = e.get_field();
+        let ell2 = Ell2::new(
+            e.clone(),
+            f.from(2),
+            Some(Box::new(edwards25519_to_curve25519())),
+        );
+        let g = e.get_generator();
+        for i in 0..8 {
+            let p = &g * &e.new_scalar(BigInt::from(i));
+            let enc = ell2
+                .encode_uniform(&p, &mut rng)
+                .expect("map is birational");
+            assert!(ell2.decode_uniform(&enc).unwrap() == p);
+        }
+    }
+}
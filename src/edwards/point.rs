@@ -1,17 +1,19 @@
 use impl_ops::impl_op_ex;
 use num_bigint::ToBigInt;
 use num_traits::identities::{One, Zero};
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
 
 use std::ops;
 
-use crate::do_if_eq;
 use crate::edwards::curve::Curve;
 use crate::edwards::scalar::Scalar;
-use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode};
+use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode, PointFormat};
 use crate::field::{Field, Sgn0};
-use crate::ops::ScMulRef;
 use crate::ops::Serialize;
+use crate::ops::{blinding_mask, ScMulRef};
 use crate::primefield::FpElt;
+use crate::{count_op, do_if_eq, impl_iter_fold};
 
 #[derive(Clone)]
 pub struct ProyCoordinates {
@@ -29,12 +31,16 @@ pub struct Point {
 impl ScMulRef<Scalar> for Point {}
 impl EcPoint<Scalar> for Point {
     fn is_zero(&self) -> bool {
-        self.c.x.is_zero() && !self.c.y.is_zero() && self.c.t.is_zero() && !self.c.z.is_zero()
+        self.c.x.is_zero() && self.c.t.is_zero() && !self.c.z.is_zero() && self.c.y == self.c.z
+    }
+    fn is_small_order(&self) -> bool {
+        let h = self.e.new_scalar(self.e.get_cofactor());
+        (self * h).is_zero()
     }
 }
 impl Encode for Point {
     // based on https://tools.ietf.org/html/rfc8032#section-5.2.2
-    fn encode(&self, _: bool) -> Vec<u8> {
+    fn encode(&self, _: PointFormat) -> Vec<u8> {
         let mut p_normal = self.clone();
         p_normal.normalize();
         let coords = p_normal.c;
@@ -44,7 +50,7 @@ impl Encode for Point {
         let x_0 = (((x.sgn0() >> 1) & 0x01) << 7) as u8;
         let mut enc = y.to_bytes_le();
         let p = self.e.f.get_modulus();
-        let size = (p.bits() + 1 + 7) / 8;
+        let size = (p.bits() + 1).div_ceil(8);
         enc.resize(size, 0u8);
         let last = enc.len() - 1;
         enc[last] |= x_0;
@@ -60,6 +66,21 @@ impl Point {
         self.c.t = &self.c.x * &self.c.y;
         self.c.z.set_one();
     }
+    /// Returns the affine x-coordinate. Every point of a twisted Edwards
+    /// curve is affine, including the identity (0, 1).
+    pub fn x(&self) -> FpElt {
+        self.to_affine().0
+    }
+    /// Returns the affine y-coordinate.
+    pub fn y(&self) -> FpElt {
+        self.to_affine().1
+    }
+    /// Returns the affine coordinates (x, y).
+    pub fn to_affine(&self) -> (FpElt, FpElt) {
+        let mut p = self.clone();
+        p.normalize();
+        (p.c.x, p.c.y)
+    }
     fn core_neg(&self) -> Point {
         self.e.new_proy_point(ProyCoordinates {
             x: -&self.c.x,
@@ -69,34 +90,80 @@ impl Point {
         })
     }
     fn core_add(&self, p: &Point) -> Point {
+        if std::ptr::eq(self, p) {
+            count_op!(dbl);
+        } else {
+            count_op!(add);
+        }
         let (x1, y1, t1, z1) = (&self.c.x, &self.c.y, &self.c.t, &self.c.z);
         let (x2, y2, t2, z2) = (&p.c.x, &p.c.y, &p.c.t, &p.c.z);
         let (a_ec, d_ec) = (&self.e.a, &self.e.d);
-        let aa = x1 * x2; // A = X1 * X2
+        let mut aa = x1 * x2; // A = X1 * X2
         let bb = y1 * y2; // B = Y1 * Y2
-        let cc = d_ec * t1 * t2; // C = d*T1 * T2
-        let dd = z1 * z2; // D = Z1 * Z2
-        let ee = (x1 + y1) * (x2 + y2) - &aa - &bb; // E = (X1 + Y1 ) * (X2 + Y2 ) - A - B
+        let mut cc = d_ec * t1;
+        cc *= t2; // C = d*T1 * T2
+        let dd = if z2.is_one() {
+            // D = Z1 * Z2 is free if either operand is normalized
+            z1.clone()
+        } else if z1.is_one() {
+            z2.clone()
+        } else {
+            z1 * z2
+        };
+        let mut ee = x1 + y1;
+        ee *= &(x2 + y2);
+        ee -= &aa;
+        ee -= &bb; // E = (X1 + Y1 ) * (X2 + Y2 ) - A - B
         let ff = &dd - &cc; // F = D - C
-        let gg = dd + cc; // G = D + C
-        let hh = bb - aa * a_ec; // H = B - a*A
+        let mut gg = dd;
+        gg += &cc; // G = D + C
+        aa *= a_ec;
+        let hh = bb - aa; // H = B - a*A
         let x3 = &ee * &ff; // X3 = E * F
         let y3 = &gg * &hh; // Y3 = G * H
-        let t3 = ee * hh; // T3 = E * H
-        let z3 = ff * gg; // Z3 = F * G
+        ee *= &hh; // T3 = E * H
+        gg *= &ff; // Z3 = F * G
         self.e.new_proy_point(ProyCoordinates {
             x: x3,
             y: y3,
-            t: t3,
-            z: z3,
+            t: ee,
+            z: gg,
         })
     }
+    /// Multiplies the projective coordinates `X`, `Y`, `T`, and `Z` by a random
+    /// non-zero field element, which changes the representation of the
+    /// point but not the point itself. Randomizing the input of a secret
+    /// scalar multiplication blunts template attacks, since the attacker
+    /// can no longer predict the intermediate values.
+    pub fn randomize_representation<R: RngCore + CryptoRng>(&mut self, rng: &mut R) {
+        let l = self.e.f.random_nonzero(rng);
+        self.c.x = &self.c.x * &l;
+        self.c.y = &self.c.y * &l;
+        self.c.t = &self.c.t * &l;
+        self.c.z = &self.c.z * &l;
+    }
+    /// Returns `[k]p` computed as `[k + m*n]p`, where `n` is the order of
+    /// the curve and `m` is a random 64-bit mask drawn from `rng` on every
+    /// call. Since the bits processed by the multiplication change on every
+    /// call, this hardens it against differential power analysis. The
+    /// representation of `p` is also randomized beforehand.
+    pub fn mul_blinded<R: RngCore + CryptoRng>(&self, k: &Scalar, rng: &mut R) -> Point {
+        let r = self.e.r.to_bigint().unwrap();
+        let n = (&self.e.r * &self.e.h).to_bigint().unwrap();
+        let blinded = Scalar {
+            k: &k.k + blinding_mask(&n, rng),
+            r: k.r.clone(),
+        };
+        let mut p = self.clone();
+        p.randomize_representation(rng);
+        do_if_eq!(r == k.r, p.core_mul(&blinded), ERR_MUL_OP)
+    }
     fn core_mul(&self, k: &Scalar) -> Point {
         let mut q = self.e.identity();
         for ki in k.iter_lr() {
             q = &q + &q;
             if ki {
-                q = q + self;
+                q += self;
             }
         }
         q
@@ -106,6 +173,7 @@ impl Point {
 impl Eq for Point {}
 
 impl PartialEq for Point {
+    /// Compares the points in variable time.
     fn eq(&self, other: &Self) -> bool {
         let x1z2 = &self.c.x * &other.c.z;
         let z1x2 = &self.c.z * &other.c.x;
@@ -117,6 +185,20 @@ impl PartialEq for Point {
     }
 }
 
+impl ConstantTimeEq for Point {
+    /// Compares the points without short-circuiting on their coordinates.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let x1z2 = &self.c.x * &other.c.z;
+        let z1x2 = &self.c.z * &other.c.x;
+        let y1z2 = &self.c.y * &other.c.z;
+        let z1y2 = &self.c.z * &other.c.y;
+        let t1z2 = &self.c.t * &other.c.z;
+        let z1t2 = &self.c.z * &other.c.t;
+        let same_curve = Choice::from((self.e == other.e) as u8);
+        same_curve & x1z2.ct_eq(&z1x2) & y1z2.ct_eq(&z1y2) & t1z2.ct_eq(&z1t2)
+    }
+}
+
 impl_op_ex!(+|a: &Point , b: &Point | -> Point  {
     do_if_eq!(a.e == b.e, a.core_add(b), ERR_ADD_OP)
 });
@@ -126,16 +208,36 @@ impl_op_ex!(*|a: &Point, b: &Scalar| -> Point {
     let r = a.e.r.to_bigint().unwrap();
     do_if_eq!(r == b.r, a.core_mul(b), ERR_MUL_OP)
 });
+impl_op_ex!(+=|a: &mut Point, b: &Point| { *a = &*a + b });
+impl_op_ex!(-=|a: &mut Point, b: &Point| { *a = &*a - b });
+impl_op_ex!(*=|a: &mut Point, b: &Scalar| { *a = &*a * b });
+impl_iter_fold!(Sum, sum, Point, +);
 
 const ERR_MUL_OP: &str = "Scalar don't match with point";
 const ERR_ADD_OP: &str = "points of different curves";
 
+/// Displays the compressed encoding of the point in hex.
 impl std::fmt::Display for Point {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "\nx: {}\ny: {}\nt: {}\nz: {}",
-            self.c.x, self.c.y, self.c.t, self.c.z
-        )
+        self.encode(PointFormat::Compressed)
+            .iter()
+            .try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+/// Shows the name of the curve and the affine coordinates of the point in
+/// hex.
+impl std::fmt::Debug for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_identity() {
+            return write!(f, "Point {{ curve: {:?}, identity }}", self.e.name);
+        }
+        let mut p = self.clone();
+        p.normalize();
+        f.debug_struct("Point")
+            .field("curve", &self.e.name)
+            .field("x", &format_args!("{:#x}", p.c.x))
+            .field("y", &format_args!("{:#x}", p.c.y))
+            .finish()
     }
 }
@@ -2,22 +2,25 @@
 //!
 //! The scalar module is meant to be used for bar.
 
+use digest::Digest;
 use impl_ops::impl_op_ex;
 use num_bigint::{BigInt, BigUint, ToBigInt};
 use num_integer::Integer;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
 
 use std::ops;
 use std::ops::{Div, Mul};
 
-use crate::do_if_eq;
 use crate::edwards::point::Point;
 use crate::ellipticcurve::EcScalar;
-use crate::ops::Serialize;
+use crate::ops::{hash_wide, wipe, Serialize, WIDE_REDUCTION_BYTES};
+use crate::{do_if_eq, impl_iter_fold};
 
 #[derive(Clone)]
 pub struct Scalar {
-    pub(super) k: BigInt,
-    pub(super) r: BigInt,
+    pub(crate) k: BigInt,
+    pub(crate) r: BigInt,
 }
 
 impl Scalar {
@@ -26,13 +29,32 @@ impl Scalar {
         let k = k.mod_floor(&r);
         Scalar { k, r }
     }
+    /// Returns the scalar obtained by hashing `msg` with `D`, reduced
+    /// modulo `r`. The digest is extended to 128 bits more than the size of
+    /// `r` before it is reduced, so the bias of the output is negligible.
+    pub fn from_hash<D: Digest>(msg: &[u8], r: &BigUint) -> Self {
+        let len = r.bits().div_ceil(8) + WIDE_REDUCTION_BYTES;
+        Scalar::new(hash_wide::<D>(msg, len), r)
+    }
 }
 
 impl EcScalar for Scalar {}
+impl ConstantTimeEq for Scalar {
+    /// Compares the canonical encodings of the scalars in constant time.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let same_order = Choice::from((self.r == other.r) as u8);
+        same_order & self.to_bytes_be().ct_eq(&other.to_bytes_be())
+    }
+}
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        wipe(&mut self.k);
+    }
+}
 impl Serialize for Scalar {
     /// serializes the field element into big-endian bytes
     fn to_bytes_be(&self) -> Vec<u8> {
-        let field_len = (self.r.bits() + 7) / 8;
+        let field_len = self.r.bits().div_ceil(8);
         let mut bytes = self.k.to_biguint().unwrap().to_bytes_be();
         let mut out = vec![0; field_len - bytes.len()];
         if !out.is_empty() {
@@ -84,8 +106,14 @@ impl_op_ex!(/|a: &Scalar, b: &Scalar| -> Scalar {
     }
 });
 impl_op_ex!(-|a: &Scalar| -> Scalar { a.red(-&a.k) });
-
-impl<'a> Div<&'a Scalar> for u32 {
+impl_op_ex!(+=|a: &mut Scalar, b: &Scalar| { *a = &*a + b });
+impl_op_ex!(-=|a: &mut Scalar, b: &Scalar| { *a = &*a - b });
+impl_op_ex!(*=|a: &mut Scalar, b: &Scalar| { *a = &*a * b });
+impl_op_ex!(/=|a: &mut Scalar, b: &Scalar| { *a = &*a / b });
+impl_iter_fold!(Sum, sum, Scalar, +);
+impl_iter_fold!(Product, product, Scalar, *);
+
+impl Div<&Scalar> for u32 {
     type Output = Scalar;
     #[inline]
     fn div(self, other: &Scalar) -> Self::Output {
@@ -93,7 +121,7 @@ impl<'a> Div<&'a Scalar> for u32 {
     }
 }
 
-impl<'a, 'b> Mul<&'b Point> for &'a Scalar {
+impl<'b> Mul<&'b Point> for &Scalar {
     type Output = Point;
     #[inline]
     fn mul(self, other: &'b Point) -> Self::Output {
@@ -144,7 +172,7 @@ impl std::iter::Iterator for Iterino {
 impl Scalar {
     pub fn iter_lr(&self) -> impl std::iter::Iterator<Item = bool> {
         let l = self.k.bits();
-        let i = l - 1usize;
+        let (i, _) = l.overflowing_sub(1usize);
         let (_, v) = self.k.to_u32_digits();
         let is_lr = true;
         Iterino { l, i, v, is_lr }
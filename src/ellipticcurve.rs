@@ -2,34 +2,77 @@
 //!
 //! The ellipticcurve module is meant to be used for bar.
 
-use num_bigint::{BigInt, BigUint};
+use num_bigint::{BigInt, BigUint, Sign};
+use rand_core::{CryptoRng, RngCore};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
 
-use std::fmt::Display;
+use std::fmt::{Debug, Display};
 
 use crate::field::Field;
-use crate::ops::{AddRef, DivRef, MulRef, NegRef, ScMulRef, Serialize, SubRef};
-/// EcScalar models the behaviour of a scalar to multiply points.
-pub trait EcScalar: Display + AddRef + SubRef + MulRef + DivRef + NegRef + Serialize {}
+use crate::ops::{AddRef, DivRef, Endianness, MulRef, NegRef, ScMulRef, Serialize, SubRef};
+/// EcScalar models the behaviour of a scalar to multiply points. Scalars
+/// are often secret, so they can be zeroized and compared in constant time.
+pub trait EcScalar:
+    Display + AddRef + SubRef + MulRef + DivRef + NegRef + Serialize + Zeroize + ConstantTimeEq
+{
+}
 
-/// EcPoint models the behaviour of a point on an elliptic curve.
-pub trait EcPoint<T>: Display + AddRef + SubRef + NegRef + ScMulRef<T> + Encode + Eq
+/// EcPoint models the behaviour of a point on an elliptic curve. Comparing
+/// points with `==` takes variable time, so secret points must be compared
+/// with `ct_eq`.
+pub trait EcPoint<T>:
+    Debug + Display + AddRef + SubRef + NegRef + ScMulRef<T> + Encode + Eq + ConstantTimeEq
 where
     T: EcScalar,
 {
     fn is_zero(&self) -> bool;
+    /// Determines whether the point is the identity element of the group,
+    /// i.e., the point at infinity for Weierstrass and Montgomery curves,
+    /// and the point (0,1) for Edwards curves.
+    fn is_identity(&self) -> bool {
+        self.is_zero()
+    }
+    /// Determines whether the order of the point divides the cofactor of the
+    /// curve. The identity is a point of small order.
+    fn is_small_order(&self) -> bool;
+}
+
+/// PointFormat is the octet-string representation of a point, as defined
+/// in ANSI X9.62 and SEC 1, Section 2.3.3.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointFormat {
+    /// Tag 0x04 followed by both coordinates.
+    Uncompressed,
+    /// Tag 0x02 or 0x03, carrying the sign of y, followed by x.
+    Compressed,
+    /// Tag 0x06 or 0x07, carrying the sign of y, followed by both
+    /// coordinates. It is still emitted by some legacy devices.
+    Hybrid,
 }
 
 /// Encode provides functionality for encoding elliptic curve points as
 /// octet-strings
+///
+/// The identity of Weierstrass and Montgomery curves is the point at
+/// infinity, which has no affine coordinates; it is encoded as a single zero
+/// byte, regardless of the format. The identity of Edwards curves is an
+/// affine point and is encoded as any other point. Encodings without a
+/// hybrid form write hybrid points uncompressed.
 pub trait Encode {
-    fn encode(&self, compress: bool) -> Vec<u8>;
+    fn encode(&self, format: PointFormat) -> Vec<u8>;
 }
 
 /// Decode provides functionality for decoding octet-strings into
 /// elliptic curve points
 pub trait Decode {
     type Deser;
-    fn decode(&self, _: &[u8]) -> Result<Self::Deser, std::io::Error>;
+    fn decode(&self, buf: &[u8]) -> Result<Self::Deser, std::io::Error> {
+        self.decode_with_format(buf).map(|(p, _)| p)
+    }
+    /// Decodes a point and reports the format in which it was encoded. The
+    /// single zero byte of the point at infinity is reported as compressed.
+    fn decode_with_format(&self, _: &[u8]) -> Result<(Self::Deser, PointFormat), std::io::Error>;
 }
 
 /// Curve trait allows to implement elliptic curve operations.
@@ -40,11 +83,120 @@ pub trait EllipticCurve: Decode {
     fn identity(&self) -> Self::Point;
     fn new_point(&self, x: <Self::F as Field>::Elt, y: <Self::F as Field>::Elt) -> Self::Point;
     fn new_scalar(&self, _: BigInt) -> Self::Scalar;
+    /// Returns the scalar whose unsigned encoding in the given byte order
+    /// is `buf`, reduced modulo the order of the curve.
+    fn new_scalar_from_bytes(&self, buf: &[u8], endianness: Endianness) -> Self::Scalar {
+        let k = match endianness {
+            Endianness::Big => BigInt::from_bytes_be(Sign::Plus, buf),
+            Endianness::Little => BigInt::from_bytes_le(Sign::Plus, buf),
+        };
+        self.new_scalar(k)
+    }
     fn get_generator(&self) -> Self::Point;
     fn is_on_curve(&self, _: &Self::Point) -> bool;
     fn get_order(&self) -> BigUint;
     fn get_cofactor(&self) -> BigInt;
     fn get_field(&self) -> Self::F;
+    /// Adds the points of `iter`. Unlike `Iterator::sum`, which takes the
+    /// curve from the first point, it returns the identity if there are no
+    /// points.
+    fn sum<I: IntoIterator<Item = Self::Point>>(&self, iter: I) -> Self::Point {
+        iter.into_iter().fold(self.identity(), |acc, p| acc + p)
+    }
+    /// Returns a scalar sampled uniformly at random in `[1, r-1]`. It
+    /// reduces 64 more bits than the size of `r`, so the bias of the output
+    /// is negligible.
+    fn random_scalar<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Self::Scalar {
+        let mut buf = vec![0u8; self.get_order().bits().div_ceil(8) + 8];
+        loop {
+            rng.fill_bytes(&mut buf);
+            let k = self.new_scalar_from_bytes(&buf, Endianness::Big);
+            if k.to_bytes_be().iter().any(|&b| b != 0) {
+                buf.zeroize();
+                return k;
+            }
+        }
+    }
+    /// Returns a point sampled uniformly at random among the points of order
+    /// `r`, computed as `[k]G` for a random non-zero scalar `k`. Protocols
+    /// that need a point whose discrete logarithm is unknown should use
+    /// `HashToCurve::random_point` instead.
+    fn random_point<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Self::Point {
+        self.get_generator() * &self.random_scalar(rng)
+    }
+    /// Decodes a point and applies `policy` to the result.
+    fn decode_with(&self, buf: &[u8], policy: DecodePolicy) -> Result<Self::Point, std::io::Error>
+    where
+        Self: Decode<Deser = Self::Point>,
+    {
+        let p = self.decode(buf)?;
+        if policy == DecodePolicy::RejectSmallOrder && p.is_small_order() {
+            return Err(std::io::Error::other("Point of small order"));
+        }
+        Ok(p)
+    }
+    /// Decodes a point from the hex string of its encoding, such as the
+    /// compressed or uncompressed SEC1 encodings found in test vectors.
+    fn point_from_hex(&self, s: &str) -> Result<Self::Point, std::io::Error>
+    where
+        Self: Decode<Deser = Self::Point>,
+    {
+        let buf = decode_hex(s).ok_or_else(|| std::io::Error::other(ERR_HEX))?;
+        self.decode(&buf)
+    }
+    /// Checks that the generator lies on the curve and has the order of the
+    /// curve, that scalar multiplication agrees with repeated addition, and
+    /// that points survive an encoding round-trip. It takes a few scalar
+    /// multiplications, so it can be run at startup to detect miscompiled
+    /// arithmetic or corrupted parameters.
+    fn self_test(&self) -> Result<(), std::io::Error>
+    where
+        Self: Decode<Deser = Self::Point>,
+    {
+        let g = self.get_generator();
+        if g.is_identity() || !self.is_on_curve(&g) {
+            return Err(std::io::Error::other(ERR_SELF_TEST_GENERATOR));
+        }
+        // [r]G = O if and only if [r-1]G = -G
+        let r1 = self.new_scalar(BigInt::from(self.get_order()) - 1u32);
+        if !(self.get_generator() * &r1 + g).is_identity() {
+            return Err(std::io::Error::other(ERR_SELF_TEST_ORDER));
+        }
+        let g3 = self.get_generator() + self.get_generator() + self.get_generator();
+        if self.get_generator() * &self.new_scalar(BigInt::from(3u32)) != g3 {
+            return Err(std::io::Error::other(ERR_SELF_TEST_ARITH));
+        }
+        for p in [self.get_generator(), g3].iter() {
+            for &format in POINT_FORMATS.iter() {
+                match self.decode(&p.encode(format)) {
+                    Ok(q) if q == *p => {}
+                    _ => return Err(std::io::Error::other(ERR_SELF_TEST_CODEC)),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// DecodePolicy selects the points accepted by `EllipticCurve::decode_with`.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum DecodePolicy {
+    /// Accepts any point on the curve.
+    AcceptAll,
+    /// Rejects points whose order divides the cofactor, including the
+    /// identity. This is the check needed to reject all-zero shared secrets
+    /// in Diffie-Hellman and small-order keys in EdDSA.
+    RejectSmallOrder,
 }
 
 /// Rational map between two elliptic curves.
@@ -75,3 +227,23 @@ pub trait MapToCurve {
         _: &<<Self::E as EllipticCurve>::F as Field>::Elt,
     ) -> <Self::E as EllipticCurve>::Point;
 }
+
+/// ClearCofactor maps points of an elliptic curve E to its subgroup of
+/// prime order.
+pub trait ClearCofactor {
+    type E: EllipticCurve;
+    fn clear(&self, _: <Self::E as EllipticCurve>::Point) -> <Self::E as EllipticCurve>::Point;
+}
+
+/// All the point formats, for iterating over them in tests.
+pub(crate) const POINT_FORMATS: [PointFormat; 3] = [
+    PointFormat::Uncompressed,
+    PointFormat::Compressed,
+    PointFormat::Hybrid,
+];
+
+const ERR_HEX: &str = "string must have an even number of hex digits";
+const ERR_SELF_TEST_GENERATOR: &str = "self-test: generator is not a point of the curve";
+const ERR_SELF_TEST_ORDER: &str = "self-test: generator does not have the order of the curve";
+const ERR_SELF_TEST_ARITH: &str = "self-test: scalar multiplication differs from addition";
+const ERR_SELF_TEST_CODEC: &str = "self-test: point does not survive encoding";
@@ -4,6 +4,8 @@
 
 use num_bigint::BigInt;
 use num_traits::identities::{One, Zero};
+use subtle::ConstantTimeEq;
+
 use std::fmt::Display;
 use std::ops::BitXor;
 
@@ -32,8 +34,22 @@ pub trait CMov: Clone {
     }
 }
 
+/// FieldElement models an element of a field. Comparing elements with `==`
+/// takes variable time, so secret elements must be compared with `ct_eq`,
+/// which compares their canonical encodings without short-circuiting.
 pub trait FieldElement:
-    Display + Eq + Zero + One + AddRef + SubRef + MulRef + DivRef + BitXor<u32> + Serialize + Clone
+    Display
+    + Eq
+    + ConstantTimeEq
+    + Zero
+    + One
+    + AddRef
+    + SubRef
+    + MulRef
+    + DivRef
+    + BitXor<u32>
+    + Serialize
+    + Clone
 {
 }
 
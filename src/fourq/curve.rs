@@ -0,0 +1,290 @@
+//! This is documentation for the `curve` module.
+//!
+//! The curve module defines FourQ and decodes its points.
+
+use num_bigint::{BigInt, BigUint, ToBigInt};
+use num_traits::identities::Zero;
+
+use std::io::Error;
+use std::sync::Arc;
+
+use crate::edwards::Scalar;
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, PointFormat};
+use crate::field::{Field, Sgn0, Sqrt};
+use crate::fourq::glv::Endomorphisms;
+use crate::fourq::point::{Point, ProyCoordinates};
+use crate::int;
+use crate::integer::Integer;
+use crate::ops::FromFactory;
+use crate::quadraticfield::{Fp2, Fp2Elt};
+use crate::{do_if_eq, uncounted};
+
+/// Curve is FourQ, the twisted Edwards curve defined by the equation
+/// -x^2+y^2=1+dx^2y^2 over GF(p^2), where `p = 2^127-1`.
+///
+/// Points are encoded in 32 bytes holding the coordinate `y` as
+/// `n0 || n1`, where each coefficient is in little-endian order, and the
+/// most significant bit of the last byte holds the sign of `x`.
+#[derive(Clone)]
+pub struct Curve(pub(crate) Arc<CurveInner>);
+
+/// CurveInner holds the parameters of the curve, which are shared by its
+/// points instead of being copied into each of them.
+pub struct CurveInner {
+    pub(crate) f: Fp2,
+    pub(crate) d: Fp2Elt,
+    pub(crate) r: BigUint,
+    pub(crate) h: BigUint,
+    pub(crate) gx: Fp2Elt,
+    pub(crate) gy: Fp2Elt,
+    // constants computed once for the formulas and the decoder
+    pub(crate) zero: Fp2Elt,
+    pub(crate) one: Fp2Elt,
+    pub(crate) endo: Endomorphisms,
+}
+
+impl std::ops::Deref for Curve {
+    type Target = CurveInner;
+    fn deref(&self) -> &CurveInner {
+        &self.0
+    }
+}
+
+/// Curves are compared by their parameters.
+impl PartialEq for Curve {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+            || (self.f == other.f
+                && self.d == other.d
+                && self.r == other.r
+                && self.gx == other.gx
+                && self.gy == other.gy
+                && self.h == other.h)
+    }
+}
+
+impl Curve {
+    pub fn new() -> Curve {
+        let f = Fp2::new(P.to_biguint());
+        let d = f.from(D);
+        let endo = Endomorphisms::new(&f, &d);
+        Curve(Arc::new(CurveInner {
+            r: R.to_biguint(),
+            h: H.to_biguint(),
+            gx: f.from(GX),
+            gy: f.from(GY),
+            zero: f.zero(),
+            one: f.one(),
+            endo,
+            d,
+            f,
+        }))
+    }
+    /// Returns the coefficient `d` of the equation `-x^2+y^2=1+dx^2y^2`.
+    pub fn d(&self) -> &Fp2Elt {
+        &self.d
+    }
+    pub(crate) fn new_proy_point(&self, c: ProyCoordinates) -> Point {
+        let e = self.clone();
+        let pt = Point { e, c };
+        do_if_eq!(uncounted!(self.is_on_curve(&pt)), pt, ERR_ECC_NEW)
+    }
+    /// Determines whether `p` belongs to the subgroup of order `N`, by
+    /// multiplying it by `N`.
+    pub fn is_in_subgroup(&self, p: &Point) -> bool {
+        p.mul_uint(&self.r).is_zero()
+    }
+}
+
+impl Default for Curve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EllipticCurve for Curve {
+    type F = Fp2;
+    type Scalar = Scalar;
+    type Point = Point;
+    fn new_point(&self, x: Fp2Elt, y: Fp2Elt) -> Self::Point {
+        self.new_proy_point(ProyCoordinates {
+            t: &x * &y,
+            x,
+            y,
+            z: self.one.clone(),
+        })
+    }
+    fn new_scalar(&self, k: BigInt) -> Self::Scalar {
+        Scalar::new(k, &self.r)
+    }
+    fn identity(&self) -> Self::Point {
+        self.new_proy_point(ProyCoordinates {
+            x: self.zero.clone(),
+            y: self.one.clone(),
+            t: self.zero.clone(),
+            z: self.one.clone(),
+        })
+    }
+    fn is_on_curve(&self, p: &Self::Point) -> bool {
+        let p = &p.c;
+        let x2 = &p.x ^ 2u32;
+        let y2 = &p.y ^ 2u32;
+        let t2 = &p.t ^ 2u32;
+        let z2 = &p.z ^ 2u32;
+        let l1 = y2 - x2;
+        let r1 = t2 * &self.d + z2;
+        let l2 = &p.x * &p.y;
+        let r2 = &p.t * &p.z;
+        let e1 = l1 - r1;
+        let e2 = l2 - r2;
+        e1.is_zero() && e2.is_zero()
+    }
+    fn get_order(&self) -> BigUint {
+        self.r.clone()
+    }
+    fn get_cofactor(&self) -> BigInt {
+        self.h.to_bigint().unwrap()
+    }
+    fn get_field(&self) -> Self::F {
+        self.f.clone()
+    }
+    fn get_generator(&self) -> Self::Point {
+        self.new_point(self.gx.clone(), self.gy.clone())
+    }
+}
+
+impl Decode for Curve {
+    type Deser = Point;
+    /// Decodes a point as described in `Curve`, whose encodings are always
+    /// compressed. It fails for points outside the subgroup of order `N`.
+    fn decode_with_format(&self, buf: &[u8]) -> Result<(Self::Deser, PointFormat), Error> {
+        if buf.len() != SIZE_BYTES {
+            return Err(Error::other(ERR_SIZE));
+        }
+        let x_0 = (buf[SIZE_BYTES - 1] >> 7) & 0x01;
+        let mut y_bytes = buf.to_vec();
+        y_bytes[SIZE_BYTES - 1] &= 0x7f;
+        let base = self.f.get_base();
+        let n0 = base.from_canonical_le(&y_bytes[..SIZE_BYTES / 2])?;
+        let n1 = base.from_canonical_le(&y_bytes[SIZE_BYTES / 2..])?;
+        let y = self.f.new_elt(n0, n1);
+
+        // x^2 = (y^2-1)/(dy^2+1)
+        let yy = &y * &y;
+        let u = &yy - &self.one;
+        let v = (&self.d * &yy) + &self.one;
+        let u_inv_v = u / v;
+        // is_square fails for zero, which is the x of the identity
+        if !u_inv_v.is_zero() && !u_inv_v.is_square() {
+            return Err(Error::other(ERR_SQRT));
+        }
+        let x_sqrt = u_inv_v.sqrt();
+        if x_sqrt.is_zero() && x_0 == 0x01 {
+            return Err(Error::other(ERR_SQRT));
+        }
+        let tag = ((x_sqrt.sgn0() >> 1) & 0x01) as u8;
+        let x = if tag != x_0 { -x_sqrt } else { x_sqrt };
+        let p = self.new_point(x, y);
+        if !self.is_in_subgroup(&p) {
+            return Err(Error::other(ERR_SUBGROUP));
+        }
+        Ok((p, PointFormat::Compressed))
+    }
+}
+
+impl std::fmt::Display for Curve {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "FourQ: Twisted Edwards Curve -x^2+y^2=1+dx^2y^2 over {}\nd: {}",
+            self.f, self.d,
+        )
+    }
+}
+
+pub(crate) const SIZE_BYTES: usize = 32;
+
+static P: Integer = int!("0x7fffffffffffffffffffffffffffffff");
+static D: [Integer; 2] = int!(
+    "4205857648805777768770",
+    "125317048443780598345676279555970305165"
+);
+static R: Integer = int!("0x29cbc14e5e0a72f05397829cbc14e5dfbd004dfe0f79992fb2540ec7768ce7");
+static H: Integer = int!("392");
+static GX: [Integer; 2] = int!(
+    "0x1a3472237c2fb305286592ad7b3833aa",
+    "0x1e1f553f2878aa9c96869fb360ac77f6"
+);
+static GY: [Integer; 2] = int!(
+    "0x0e3fee9ba120785ab924a2462bcbb287",
+    "0x6e1c4af8630e024249a7c344844c8b5c"
+);
+
+const ERR_ECC_NEW: &str = "not valid point";
+const ERR_SIZE: &str = "wrong size of encoded point";
+const ERR_SQRT: &str = "Failed decoding on square root";
+const ERR_SUBGROUP: &str = "point is not in the subgroup of order N";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, Sign};
+    use rand_core::{OsRng, RngCore};
+
+    use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode, PointFormat};
+    use crate::field::Field;
+    use crate::fourq::Curve;
+
+    #[test]
+    fn self_test() {
+        let e = Curve::new();
+        assert!(e.self_test().is_ok());
+    }
+
+    #[test]
+    fn point_serialization() {
+        let e = Curve::new();
+        let g = e.get_generator();
+        let mut buf = [0u8; 40];
+        OsRng.fill_bytes(&mut buf);
+        let k = BigInt::from_bytes_be(Sign::Plus, &buf);
+        for k in [BigInt::from(0), BigInt::from(1), BigInt::from(2), k].iter() {
+            let p = &g * &e.new_scalar(k.clone());
+            let ser = p.encode(PointFormat::Compressed);
+            assert_eq!(ser.len(), 32);
+            let deser = e.decode(&ser).unwrap();
+            assert!(p == deser, "round-trip of [{}]G", k);
+        }
+    }
+
+    #[test]
+    fn invalid_encodings() {
+        let e = Curve::new();
+        let f = e.get_field();
+        let ser = e.get_generator().encode(PointFormat::Compressed);
+        assert!(e.decode(&ser[1..]).is_err(), "short encoding");
+        // n0 = p is not canonical
+        let mut bad = ser.clone();
+        bad[..16].copy_from_slice(&[0xff; 16]);
+        bad[15] = 0x7f;
+        assert!(e.decode(&bad).is_err(), "y >= p");
+        // y = 1 is the identity, whose x is zero and has no sign
+        let mut one = vec![0u8; 32];
+        one[0] = 1;
+        assert!(e.decode(&one).unwrap().is_zero());
+        one[31] |= 0x80;
+        assert!(e.decode(&one).is_err(), "negative zero");
+        // (0, -1) has order two
+        let t2 = e.new_point(f.zero(), -f.one());
+        assert!(t2.is_small_order());
+        assert!(!e.get_generator().is_small_order());
+        assert!(
+            e.decode(&t2.encode(PointFormat::Compressed)).is_err(),
+            "point of order two"
+        );
+        let mixed = e.get_generator() + &t2;
+        assert!(
+            e.decode(&mixed.encode(PointFormat::Compressed)).is_err(),
+            "mixed point"
+        );
+    }
+}
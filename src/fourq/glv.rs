@@ -0,0 +1,485 @@
+//! This is documentation for the `glv` module.
+//!
+//! The glv module implements the endomorphisms `phi` and `psi` of FourQ,
+//! and the scalar multiplication that splits the scalar into four parts
+//! using them.
+//!
+//! Both endomorphisms are computed on the short Weierstrass model of FourQ.
+//! They go through the 2-isogenous curve, which has complex multiplication
+//! by the maximal order of Q(sqrt(-10)), so `phi` and `psi` are the
+//! compositions `tau_dual o delta o tau` where `tau` is the 2-isogeny to
+//! that curve, `tau_dual` is its dual, and `delta` is either a 5-isogeny
+//! or a 2-isogeny to the conjugate curve followed by the Frobenius map.
+// Reference: "FourQ: four-dimensional decompositions on a Q-curve over the
+// Mersenne prime" by Costello-Longa. [Sec.3 and Sec.4]
+// (eprint.iacr.org/2015/565).
+
+use num_bigint::{BigInt, ToBigInt};
+use num_integer::Integer as _;
+use num_traits::identities::Zero;
+use num_traits::ToPrimitive;
+
+use crate::edwards::Scalar;
+use crate::ellipticcurve::{EcPoint, EllipticCurve};
+use crate::field::Field;
+use crate::fourq::curve::Curve;
+use crate::fourq::point::{Point, ProyCoordinates};
+use crate::int;
+use crate::integer::Integer;
+use crate::ops::FromFactory;
+use crate::quadraticfield::{Fp2, Fp2Elt};
+
+/// Endomorphisms holds the constants of the maps that compute `phi` and
+/// `psi`.
+pub(crate) struct Endomorphisms {
+    // the Weierstrass model is y^2 = x^3 + a4*x + a6, with x = (u + kappa)/b
+    // and y = v/b for the point (u, v) of the Montgomery model
+    kappa: Fp2Elt,
+    b: Fp2Elt,
+    tau: Iso2,
+    tau_dual: Iso2,
+    delta_psi: Iso2,
+    delta_phi: Iso5,
+}
+
+impl Endomorphisms {
+    pub(crate) fn new(f: &Fp2, d: &Fp2Elt) -> Self {
+        let one = f.one();
+        let a_minus_d = -&one - d;
+        let a_plus_d = d - &one;
+        let two: Fp2Elt = f.from(2u8);
+        let three: Fp2Elt = f.from(3u8);
+        let four: Fp2Elt = f.from(4u8);
+        let kappa = two * a_plus_d / (three * &a_minus_d);
+        let b = four / a_minus_d;
+        let ud: Fp2Elt = f.from(UD);
+        let uh: Fp2Elt = f.from(UH);
+        let u5: Fp2Elt = f.from(U5);
+        let poly = |c: &[[Integer; 2]]| c.iter().map(|&c| f.from(c)).collect();
+        Endomorphisms {
+            kappa,
+            b,
+            tau: Iso2 {
+                x1: f.from(X0),
+                t: f.from(T0),
+                u2: one.clone(),
+                u3: one,
+            },
+            tau_dual: Iso2 {
+                x1: f.from(XD),
+                t: f.from(TD),
+                u2: &ud * &ud,
+                u3: &ud * &ud * &ud,
+            },
+            delta_psi: Iso2 {
+                x1: f.from(XH),
+                t: f.from(TH),
+                u2: &uh * &uh,
+                u3: &uh * &uh * &uh,
+            },
+            delta_phi: Iso5 {
+                h: poly(&H5),
+                n: poly(&N5),
+                m: poly(&M5),
+                u2: &u5 * &u5,
+                u3: &u5 * &u5 * &u5,
+            },
+        }
+    }
+}
+
+/// WeCoordinates are the projective coordinates `(x: y: z)` of a point on
+/// a short Weierstrass curve.
+struct WeCoordinates {
+    x: Fp2Elt,
+    y: Fp2Elt,
+    z: Fp2Elt,
+}
+
+impl WeCoordinates {
+    fn conjugate(&self) -> WeCoordinates {
+        WeCoordinates {
+            x: self.x.conjugate(),
+            y: self.y.conjugate(),
+            z: self.z.conjugate(),
+        }
+    }
+}
+
+/// Iso2 is the 2-isogeny with kernel `(x1, 0)` given by Velu's formulas,
+/// followed by the isomorphism `(x, y) -> (u^2*x, u^3*y)`. It maps `(x, y)`
+/// to `(u^2*(x + t/(x-x1)), u^3*y*(1 - t/(x-x1)^2))`.
+struct Iso2 {
+    x1: Fp2Elt,
+    t: Fp2Elt,
+    u2: Fp2Elt,
+    u3: Fp2Elt,
+}
+
+impl Iso2 {
+    fn eval(&self, p: &WeCoordinates) -> WeCoordinates {
+        let w = &p.x - &self.x1 * &p.z;
+        let tzz = &self.t * &p.z * &p.z;
+        let ww = &w * &w;
+        WeCoordinates {
+            x: &self.u2 * (&p.x * &w + &tzz) * &w,
+            y: &self.u3 * &p.y * (&ww - &tzz),
+            z: &p.z * ww,
+        }
+    }
+}
+
+/// Iso5 is a 5-isogeny followed by the isomorphism `(x, y) -> (u^2*x,
+/// u^3*y)`. It maps `(x, y)` to `(u^2*n(x)/h(x)^2, u^3*y*m(x)/h(x)^3)`,
+/// where the polynomials are monic and their coefficients are listed from
+/// the lowest degree.
+struct Iso5 {
+    h: Vec<Fp2Elt>,
+    n: Vec<Fp2Elt>,
+    m: Vec<Fp2Elt>,
+    u2: Fp2Elt,
+    u3: Fp2Elt,
+}
+
+impl Iso5 {
+    fn eval(&self, p: &WeCoordinates) -> WeCoordinates {
+        let hh = homogenize(&self.h, &p.x, &p.z);
+        let hhh = &hh * &hh * &hh;
+        WeCoordinates {
+            x: &self.u2 * homogenize(&self.n, &p.x, &p.z) * &hh,
+            y: &self.u3 * &p.y * homogenize(&self.m, &p.x, &p.z),
+            z: &p.z * hhh,
+        }
+    }
+}
+
+/// Evaluates the polynomial `c` at `x/z`, multiplied by `z^deg(c)`.
+fn homogenize(c: &[Fp2Elt], x: &Fp2Elt, z: &Fp2Elt) -> Fp2Elt {
+    let (last, rest) = c.split_last().unwrap();
+    let mut acc = last.clone();
+    let mut zi = z.clone();
+    for ci in rest.iter().rev() {
+        acc = acc * x + ci * &zi;
+        zi = zi * z;
+    }
+    acc
+}
+
+impl Curve {
+    /// Returns the integers `[a0, a1, a2, a3]` of at most 62 bits such that
+    /// `k = a0 + a1*lambda_phi + a2*lambda_psi + a3*lambda_phi*lambda_psi`
+    /// modulo `N`, where `phi` and `psi` act on the subgroup of order `N` as
+    /// multiplications by `lambda_phi` and `lambda_psi`.
+    ///
+    /// The vector `(k, 0, 0, 0)` is rounded to the closest vector of a
+    /// reduced basis of the lattice of decompositions of zero, and their
+    /// difference is returned.
+    pub fn decompose(&self, k: &Scalar) -> [i64; 4] {
+        let n = self.r.to_bigint().unwrap();
+        let two_n = &n << 1usize;
+        let mut a = [k.k.clone(), BigInt::zero(), BigInt::zero(), BigInt::zero()];
+        for (&l, row) in ELL.iter().zip(BASIS.iter()) {
+            let c = (((&k.k * BigInt::from(l)) << 1usize) + &n).div_floor(&two_n);
+            for (aj, &bj) in a.iter_mut().zip(row.iter()) {
+                *aj -= &c * bj;
+            }
+        }
+        let mut out = [0i64; 4];
+        for (o, aj) in out.iter_mut().zip(a.iter()) {
+            *o = aj.to_i64().unwrap();
+        }
+        out
+    }
+}
+
+impl Point {
+    /// Returns `phi(p)`, which is `[lambda_phi]p` for points of odd order.
+    pub fn phi(&self) -> Point {
+        let endo = &self.e.endo;
+        self.apply(|p| endo.delta_phi.eval(p).conjugate())
+    }
+    /// Returns `psi(p)`, which is `[lambda_psi]p` for points of odd order.
+    pub fn psi(&self) -> Point {
+        let endo = &self.e.endo;
+        self.apply(|p| endo.delta_psi.eval(p).conjugate())
+    }
+    /// Returns `tau_dual(delta(tau(p)))`. The maps are not defined at the
+    /// points of order two, so the identity is handled apart.
+    fn apply<F>(&self, delta: F) -> Point
+    where
+        F: Fn(&WeCoordinates) -> WeCoordinates,
+    {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let endo = &self.e.endo;
+        // (x, y) -> (u, v) = ((1+y)/(1-y), u/x) -> ((u + kappa)/b, v/b)
+        let (x, y, z) = (&self.c.x, &self.c.y, &self.c.z);
+        let s = z + y;
+        let t = z - y;
+        let w = WeCoordinates {
+            x: (&s + &endo.kappa * &t) * x,
+            y: &s * z,
+            z: &endo.b * t * x,
+        };
+        let w = endo.tau_dual.eval(&delta(&endo.tau.eval(&w)));
+        // (x, y) -> (u, v) = (b*x - kappa, b*y) -> (u/v, (u-1)/(u+1))
+        let u = &endo.b * &w.x - &endo.kappa * &w.z;
+        let v = &endo.b * &w.y;
+        let u_plus_z = &u + &w.z;
+        let u_minus_z = &u - &w.z;
+        self.e.new_proy_point(ProyCoordinates {
+            x: &u * &u_plus_z,
+            y: &v * &u_minus_z,
+            t: u * u_minus_z,
+            z: v * u_plus_z,
+        })
+    }
+    /// Returns `[k]p` using the decomposition of `k`, which takes at most
+    /// 62 doublings. It assumes that `p` belongs to the subgroup of order
+    /// `N`. This function runs in non-constant time.
+    pub(crate) fn mul_glv(&self, k: &Scalar) -> Point {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let a = self.e.decompose(k);
+        let phi = self.phi();
+        let psi = self.psi();
+        let psi_phi = phi.psi();
+        let mut table = vec![self.e.identity()];
+        let mut bits = 0u64;
+        for (j, (q, &aj)) in [self, &phi, &psi, &psi_phi]
+            .iter()
+            .zip(a.iter())
+            .enumerate()
+        {
+            let q = if aj < 0 { -*q } else { (*q).clone() };
+            for i in 0..1 << j {
+                let sum = &table[i] + &q;
+                table.push(sum);
+            }
+            bits |= aj.unsigned_abs();
+        }
+        let mut r = self.e.identity();
+        for i in (0..64 - bits.leading_zeros()).rev() {
+            r = &r + &r;
+            let index = a.iter().enumerate().fold(0, |acc, (j, aj)| {
+                acc | (((aj.unsigned_abs() >> i) & 1) << j)
+            });
+            if index != 0 {
+                r += &table[index as usize];
+            }
+        }
+        r
+    }
+}
+
+// Rows of the reduced basis of the lattice {a: sum(a_i*lambda_i) = 0 mod N}.
+static BASIS: [[i64; 4]; 4] = [
+    [
+        325243871469523147,
+        1397215820276968864,
+        -523086274270593807,
+        -1197648757382171810,
+    ],
+    [
+        1178067483741901388,
+        1197895137395561295,
+        2813273774154039696,
+        332693898162458252,
+    ],
+    [
+        1979292295481763424,
+        -2243082165883190970,
+        1616364156812036341,
+        -1283177498622799119,
+    ],
+    [
+        -2506872036284618516,
+        646545662724814536,
+        2243082165883190970,
+        -1444813914304002753,
+    ],
+];
+// First column of N times the inverse of the basis.
+static ELL: [Integer; 4] = [
+    int!("0x85dfbcb01d8623dfb5b0630a18fdbeb19f1a666cbf1891"),
+    int!("0x6a2160739e1ec8a219888d089ce975e30434ba87270566"),
+    int!("0x5ba9307527759ee03143c8280519680f6527fe9a10808d"),
+    int!("-0xa7f0adbc5bdd28fb66d4fd236f56ff9802249d2351f17d"),
+];
+
+// tau: E -> E', where E' is the 2-isogenous curve with CM by Z[sqrt(-10)]
+static X0: [Integer; 2] = int!(
+    "0x40000000000000260000000000000035",
+    "0x250bdd40bbb9502a1deb036c2854acc2"
+);
+static T0: [Integer; 2] = int!(
+    "0x08000000000047d18000000000006591",
+    "0x4a7567c094ab81a24d7d9193f3b1931e"
+);
+// tau_dual: E' -> E
+static XD: [Integer; 2] = int!(
+    "0x7fffffffffffffb3ffffffffffffff94",
+    "0x35e8457e888d5fabc429f927af56a67b"
+);
+static TD: [Integer; 2] = int!(
+    "0x7fffffffffffff1bfffffffffffffebd",
+    "0x21b8d07b99a81f034c7deb770e03f372"
+);
+static UD: [Integer; 2] = int!("0x40000000000000000000000000000000", "0");
+// delta_psi: E' -> E'^p, the conjugate curve
+static XH: [Integer; 2] = int!(
+    "0x7fffffffffffffb3ffffffffffffff93",
+    "0x35e8457e888d5fabc429f927af56a67b"
+);
+static TH: [Integer; 2] = int!(
+    "0x00000000000000e40000000000000143",
+    "0x5e472f846657e0fcb3821488f1fc0c8d"
+);
+static UH: [Integer; 2] = int!(
+    "0x6659378b0ebeb4831d37355f3af39d30",
+    "0x7ffffffffffffffcfffffffffffffffa"
+);
+// delta_phi: E' -> E'^p
+static U5: [Integer; 2] = int!(
+    "0x7ffffffffffffffdfffffffffffffffc",
+    "0x2a77d2b26c496642fb9eb9302b743a2e"
+);
+static H5: [[Integer; 2]; 3] = [
+    int!(
+        "0x0000000000009e28000000000000dfab",
+        "0x0ab5262ba5c8b15e16b16a43dc12c5dc"
+    ),
+    int!(
+        "0x00000000000000aa00000000000000f0",
+        "0x1f529f860316cbe544e251582b5d0ef0"
+    ),
+    int!("1", "0"),
+];
+static N5: [[Integer; 2]; 6] = [
+    int!(
+        "0x000007b9220cf02000000aec169bf570",
+        "0x46670511d37f0c2a152a8065bb0fdd0a"
+    ),
+    int!(
+        "0x0000000fd74f5088000000166712fb8d",
+        "0x79666ee1735fe26efcc6db76120e3c2c"
+    ),
+    int!(
+        "0x000000000cb9cd1c0000000011ff3a00",
+        "0x1f16b0b0553389a3d3c58d757474452b"
+    ),
+    int!(
+        "0x000000000005532000000000000787c6",
+        "0x0d521ae0b0ae7376c4a4f168f060a91c"
+    ),
+    int!(
+        "0x000000000000015400000000000001e0",
+        "0x3ea53f0c062d97ca89c4a2b056ba1de0"
+    ),
+    int!("1", "0"),
+];
+static M5: [[Integer; 2]; 7] = [
+    int!(
+        "0x7ffd4d4f58ea06dffffc2f377de1e126",
+        "0x7fdc385e09b5d4246c73187738d1abbf"
+    ),
+    int!(
+        "0x7ffffe8332cab2a5fffffde57718aebf",
+        "0x207fcafbf0a722b2f2d70187686ebfc1"
+    ),
+    int!(
+        "0x000000084da05cf80000000bbe16f737",
+        "0x46072b3758002f729d259ee9d6cd6db8"
+    ),
+    int!(
+        "0x000000000d23dd540000000012953910",
+        "0x23460b9709a67ece4e3f4076b4b1ec08"
+    ),
+    int!(
+        "0x000000000007c12800000000000af771",
+        "0x06343577159597311c1327106823a202"
+    ),
+    int!(
+        "0x00000000000001fe00000000000002d0",
+        "0x5df7de92094463afcea6f40882172cd0"
+    ),
+    int!("1", "0"),
+];
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
+    use num_integer::Integer;
+    use rand_core::{OsRng, RngCore};
+
+    use crate::ellipticcurve::{EcPoint, EllipticCurve};
+    use crate::fourq::Curve;
+
+    const LAMBDA_PHI: &str =
+        "67797525826014020946098518280353850192055058191032750194272939173883793589";
+    const LAMBDA_PSI: &str =
+        "51966879809160380004441108472126034758529891648482438273138739294936496035";
+
+    fn random_scalars(e: &Curve) -> Vec<BigInt> {
+        let n = e.get_order().to_bigint().unwrap();
+        let mut ks = vec![BigInt::from(0), BigInt::from(1), &n - 1u32];
+        for _ in 0..32 {
+            let mut buf = [0u8; 40];
+            OsRng.fill_bytes(&mut buf);
+            ks.push(BigInt::from_bytes_be(Sign::Plus, &buf).mod_floor(&n));
+        }
+        ks
+    }
+
+    #[test]
+    fn endomorphisms() {
+        let e = Curve::new();
+        let lambda_phi = BigUint::parse_bytes(LAMBDA_PHI.as_bytes(), 10).unwrap();
+        let lambda_psi = BigUint::parse_bytes(LAMBDA_PSI.as_bytes(), 10).unwrap();
+        let g = e.get_generator();
+        let p = g.mul_uint(&BigUint::from(0x1234_5678u32));
+        for q in [g, p].iter() {
+            assert!(q.phi() == q.mul_uint(&lambda_phi));
+            assert!(q.psi() == q.mul_uint(&lambda_psi));
+        }
+        assert!(e.identity().phi().is_zero());
+        assert!(e.identity().psi().is_zero());
+    }
+
+    #[test]
+    fn decomposition() {
+        let e = Curve::new();
+        let n = e.get_order().to_bigint().unwrap();
+        let lambda_phi = BigInt::parse_bytes(LAMBDA_PHI.as_bytes(), 10).unwrap();
+        let lambda_psi = BigInt::parse_bytes(LAMBDA_PSI.as_bytes(), 10).unwrap();
+        let lambdas = [
+            BigInt::from(1),
+            lambda_phi.clone(),
+            lambda_psi.clone(),
+            lambda_phi * lambda_psi,
+        ];
+        for k in random_scalars(&e) {
+            let a = e.decompose(&e.new_scalar(k.clone()));
+            let sum = a
+                .iter()
+                .zip(lambdas.iter())
+                .fold(BigInt::from(0), |acc, (&ai, l)| acc + l * ai);
+            assert_eq!(sum.mod_floor(&n), k);
+            assert!(a.iter().all(|ai| ai.unsigned_abs() < 1 << 62), "{:?}", a);
+        }
+    }
+
+    #[test]
+    fn scalar_mult() {
+        let e = Curve::new();
+        let g = e.get_generator();
+        for k in random_scalars(&e) {
+            let want = g.mul_uint(&k.to_biguint().unwrap());
+            assert!(&g * &e.new_scalar(k) == want);
+        }
+        let k = e.new_scalar(BigInt::from(5));
+        assert!((&e.identity() * &k).is_zero());
+    }
+}
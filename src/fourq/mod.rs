@@ -0,0 +1,41 @@
+//! This is documentation for the `fourq` module.
+//!
+//! The fourq module implements FourQ, the twisted Edwards curve
+//! `-x^2+y^2=1+dx^2y^2` defined over GF(p^2) with `p = 2^127-1`. Its points
+//! form a group of order `392*N`, where `N` is a prime of 246 bits.
+//!
+//! FourQ has two endomorphisms `phi` and `psi` that are cheap to evaluate
+//! and act on the subgroup of order `N` as multiplications by the scalars
+//! `lambda_phi` and `lambda_psi`. So a scalar `k` is split into four
+//! integers of at most 62 bits, such that
+//! `k = a0 + a1*lambda_phi + a2*lambda_psi + a3*lambda_phi*lambda_psi mod N`,
+//! and `[k]P` is computed with 62 doublings shared by `P`, `phi(P)`,
+//! `psi(P)` and `psi(phi(P))`, instead of 246 doublings.
+//!
+//! Multiplying by a scalar takes the point to be in the subgroup of order
+//! `N`, which holds for multiples of the generator and for decoded points,
+//! since the decoder rejects any other point.
+//!
+//! # Multiplying a Point
+//!
+//! ```
+//!  use num_bigint::BigInt;
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::fourq::Curve;
+//!
+//!  let e = Curve::new();
+//!  let g = e.get_generator();
+//!  let k = e.new_scalar(BigInt::from(1_000_000u32));
+//!  let p = g.clone() * &k;
+//!  let k1 = e.new_scalar(BigInt::from(1_000_001u32));
+//!  assert!(p + &g == g * &k1);
+//! ```
+// Reference: "FourQ: four-dimensional decompositions on a Q-curve over the
+// Mersenne prime" by Costello-Longa (eprint.iacr.org/2015/565).
+
+mod curve;
+mod glv;
+mod point;
+
+pub use crate::fourq::curve::Curve;
+pub use crate::fourq::point::{Point, ProyCoordinates};
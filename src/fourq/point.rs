@@ -0,0 +1,197 @@
+use impl_ops::impl_op_ex;
+use num_bigint::{BigUint, ToBigInt};
+use num_traits::identities::{One, Zero};
+use subtle::{Choice, ConstantTimeEq};
+
+use std::ops;
+
+use crate::edwards::Scalar;
+use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode, PointFormat};
+use crate::field::Sgn0;
+use crate::fourq::curve::{Curve, SIZE_BYTES};
+use crate::ops::{ScMulRef, Serialize};
+use crate::quadraticfield::Fp2Elt;
+use crate::{count_op, do_if_eq, impl_iter_fold};
+
+/// ProyCoordinates are the extended coordinates `(X: Y: T: Z)` of a point,
+/// whose affine coordinates are `(X/Z, Y/Z)` and where `T = XY/Z`.
+#[derive(Clone)]
+pub struct ProyCoordinates {
+    pub x: Fp2Elt,
+    pub y: Fp2Elt,
+    pub t: Fp2Elt,
+    pub z: Fp2Elt,
+}
+
+/// Point is a point of FourQ.
+#[derive(Clone)]
+pub struct Point {
+    pub(crate) e: Curve,
+    pub(crate) c: ProyCoordinates,
+}
+
+impl ScMulRef<Scalar> for Point {}
+impl EcPoint<Scalar> for Point {
+    fn is_zero(&self) -> bool {
+        self.c.x.is_zero() && self.c.t.is_zero() && !self.c.z.is_zero() && self.c.y == self.c.z
+    }
+    fn is_small_order(&self) -> bool {
+        self.mul_uint(&self.e.h).is_zero()
+    }
+}
+
+impl Encode for Point {
+    /// Encodes a point as described in `Curve`, which is the only format.
+    fn encode(&self, _: PointFormat) -> Vec<u8> {
+        let (x, y) = self.to_affine();
+        let x_0 = (((x.sgn0() >> 1) & 0x01) << 7) as u8;
+        let mut enc = y.c0().to_bytes_le();
+        enc.append(&mut y.c1().to_bytes_le());
+        enc[SIZE_BYTES - 1] |= x_0;
+        enc
+    }
+}
+
+impl Point {
+    pub fn normalize(&mut self) {
+        let inv_z = 1u32 / &self.c.z;
+        self.c.x = &self.c.x * &inv_z;
+        self.c.y = &self.c.y * &inv_z;
+        self.c.t = &self.c.x * &self.c.y;
+        self.c.z.set_one();
+    }
+    /// Returns the affine x-coordinate. Every point of FourQ is affine,
+    /// including the identity (0, 1).
+    pub fn x(&self) -> Fp2Elt {
+        self.to_affine().0
+    }
+    /// Returns the affine y-coordinate.
+    pub fn y(&self) -> Fp2Elt {
+        self.to_affine().1
+    }
+    /// Returns the affine coordinates (x, y).
+    pub fn to_affine(&self) -> (Fp2Elt, Fp2Elt) {
+        let mut p = self.clone();
+        p.normalize();
+        (p.c.x, p.c.y)
+    }
+    fn core_neg(&self) -> Point {
+        self.e.new_proy_point(ProyCoordinates {
+            x: -&self.c.x,
+            y: self.c.y.clone(),
+            t: -&self.c.t,
+            z: self.c.z.clone(),
+        })
+    }
+    /// core_add implements the unified addition of twisted Edwards curves
+    /// in extended coordinates, for `a = -1`.
+    fn core_add(&self, p: &Point) -> Point {
+        if std::ptr::eq(self, p) {
+            count_op!(dbl);
+        } else {
+            count_op!(add);
+        }
+        let (x1, y1, t1, z1) = (&self.c.x, &self.c.y, &self.c.t, &self.c.z);
+        let (x2, y2, t2, z2) = (&p.c.x, &p.c.y, &p.c.t, &p.c.z);
+        let aa = x1 * x2; // A = X1 * X2
+        let bb = y1 * y2; // B = Y1 * Y2
+        let cc = &self.e.d * t1 * t2; // C = d*T1 * T2
+        let dd = if z2.is_one() {
+            // D = Z1 * Z2 is free if either operand is normalized
+            z1.clone()
+        } else if z1.is_one() {
+            z2.clone()
+        } else {
+            z1 * z2
+        };
+        let ee = (x1 + y1) * (x2 + y2) - &aa - &bb; // E = (X1 + Y1 ) * (X2 + Y2 ) - A - B
+        let ff = &dd - &cc; // F = D - C
+        let gg = dd + &cc; // G = D + C
+        let hh = bb + aa; // H = B - a*A
+        self.e.new_proy_point(ProyCoordinates {
+            x: &ee * &ff, // X3 = E * F
+            y: &gg * &hh, // Y3 = G * H
+            t: ee * hh,   // T3 = E * H
+            z: ff * gg,   // Z3 = F * G
+        })
+    }
+    /// Multiplies the point by an integer that is not reduced modulo `N`,
+    /// such as the cofactor or the order itself.
+    pub(crate) fn mul_uint(&self, k: &BigUint) -> Point {
+        let digits = k.to_u32_digits();
+        let mut q = self.e.identity();
+        for i in (0..k.bits()).rev() {
+            q = &q + &q;
+            if (digits[i / 32] >> (i % 32)) & 1 == 1 {
+                q += self;
+            }
+        }
+        q
+    }
+}
+
+impl Eq for Point {}
+
+impl PartialEq for Point {
+    /// Compares the points in variable time.
+    fn eq(&self, other: &Self) -> bool {
+        let x1z2 = &self.c.x * &other.c.z;
+        let z1x2 = &self.c.z * &other.c.x;
+        let y1z2 = &self.c.y * &other.c.z;
+        let z1y2 = &self.c.z * &other.c.y;
+        self.e == other.e && x1z2 == z1x2 && y1z2 == z1y2
+    }
+}
+
+impl ConstantTimeEq for Point {
+    /// Compares the points without short-circuiting on their coordinates.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let x1z2 = &self.c.x * &other.c.z;
+        let z1x2 = &self.c.z * &other.c.x;
+        let y1z2 = &self.c.y * &other.c.z;
+        let z1y2 = &self.c.z * &other.c.y;
+        let same_curve = Choice::from((self.e == other.e) as u8);
+        same_curve & x1z2.ct_eq(&z1x2) & y1z2.ct_eq(&z1y2)
+    }
+}
+
+impl_op_ex!(+|a: &Point, b: &Point| -> Point {
+    do_if_eq!(a.e == b.e, a.core_add(b), ERR_ADD_OP)
+});
+impl_op_ex!(-|a: &Point, b: &Point| -> Point { a + (-b) });
+impl_op_ex!(-|a: &Point| -> Point { a.core_neg() });
+impl_op_ex!(*|a: &Point, b: &Scalar| -> Point {
+    let r = a.e.r.to_bigint().unwrap();
+    do_if_eq!(r == b.r, a.mul_glv(b), ERR_MUL_OP)
+});
+impl_op_ex!(+=|a: &mut Point, b: &Point| { *a = &*a + b });
+impl_op_ex!(-=|a: &mut Point, b: &Point| { *a = &*a - b });
+impl_op_ex!(*=|a: &mut Point, b: &Scalar| { *a = &*a * b });
+impl_iter_fold!(Sum, sum, Point, +);
+
+const ERR_MUL_OP: &str = "Scalar don't match with point";
+const ERR_ADD_OP: &str = "points of different curves";
+
+/// Displays the encoding of the point in hex.
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.encode(PointFormat::Compressed)
+            .iter()
+            .try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+/// Shows the affine coordinates of the point in hex.
+impl std::fmt::Debug for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_identity() {
+            return write!(f, "Point {{ curve: \"FourQ\", identity }}");
+        }
+        let (x, y) = self.to_affine();
+        f.debug_struct("Point")
+            .field("curve", &"FourQ")
+            .field("x", &format_args!("{:#x}", x))
+            .field("y", &format_args!("{:#x}", y))
+            .finish()
+    }
+}
@@ -0,0 +1,291 @@
+//! This is documentation for the `fuzzing` module.
+//!
+//! The fuzzing module implements `arbitrary::Arbitrary` for the inputs of
+//! the crate, so fuzzers such as `cargo fuzz` can turn raw bytes into
+//! structured inputs. It is only available with the `arbitrary` feature.
+//!
+//! Values live in a field or a curve chosen at run time, so each input
+//! carries the curve it belongs to. The targets in the `fuzz` directory
+//! use these inputs to fuzz the point decoders:
+//!
+//! ```text
+//!  cargo fuzz run decode
+//! ```
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use num_bigint::{BigInt, Sign};
+
+use std::io::Error;
+
+use crate::edwards;
+use crate::ellipticcurve::{Decode, EllipticCurve, Encode, PointFormat, POINT_FORMATS};
+use crate::field::Field;
+use crate::instances::{
+    EdCurveID, GetCurve, MtCurveID, WeCurveID, BLS12381G1, BN254, CURVE25519, CURVE448,
+    EDWARDS25519, EDWARDS448, JUBJUB, P256, P384, P521, SECP256K1, SM2, W25519,
+};
+use crate::montgomery;
+use crate::primefield::{Fp, FpElt};
+use crate::weierstrass;
+
+const WEIERSTRASS: [WeCurveID; 8] = [P256, P384, P521, SECP256K1, SM2, BLS12381G1, BN254, W25519];
+const EDWARDS: [EdCurveID; 3] = [EDWARDS25519, EDWARDS448, JUBJUB];
+const MONTGOMERY: [MtCurveID; 2] = [CURVE25519, CURVE448];
+
+impl<'a> Arbitrary<'a> for WeCurveID {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&WEIERSTRASS).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for EdCurveID {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&EDWARDS).copied()
+    }
+}
+
+impl<'a> Arbitrary<'a> for MtCurveID {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&MONTGOMERY).copied()
+    }
+}
+
+/// AnyCurve is one of the named curves of the crate.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum AnyCurve {
+    Weierstrass(WeCurveID),
+    Edwards(EdCurveID),
+    Montgomery(MtCurveID),
+}
+
+impl<'a> Arbitrary<'a> for AnyCurve {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0..=2)? {
+            0 => AnyCurve::Weierstrass(WeCurveID::arbitrary(u)?),
+            1 => AnyCurve::Edwards(EdCurveID::arbitrary(u)?),
+            _ => AnyCurve::Montgomery(MtCurveID::arbitrary(u)?),
+        })
+    }
+}
+
+impl AnyCurve {
+    pub fn get_field(&self) -> Fp {
+        match self {
+            AnyCurve::Weierstrass(id) => id.get().get_field(),
+            AnyCurve::Edwards(id) => id.get().get_field(),
+            AnyCurve::Montgomery(id) => id.get().get_field(),
+        }
+    }
+    /// Returns the encoding of `[k]G` in the given `format`, if the model
+    /// supports it.
+    pub fn encode_multiple(&self, k: BigInt, format: PointFormat) -> Vec<u8> {
+        match self {
+            AnyCurve::Weierstrass(id) => multiple(&id.get(), k).encode(format),
+            AnyCurve::Edwards(id) => multiple(&id.get(), k).encode(format),
+            AnyCurve::Montgomery(id) => multiple(&id.get(), k).encode(format),
+        }
+    }
+    /// Decodes a point and encodes it again in the same format. Decoders
+    /// only accept canonical encodings, so the output must equal `buf`.
+    pub fn reencode(&self, buf: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            AnyCurve::Weierstrass(id) => reencode(&id.get(), buf),
+            AnyCurve::Edwards(id) => reencode(&id.get(), buf),
+            AnyCurve::Montgomery(id) => reencode(&id.get(), buf),
+        }
+    }
+}
+
+impl std::fmt::Debug for AnyCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl std::fmt::Display for AnyCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AnyCurve::Weierstrass(id) => write!(f, "{}", id),
+            AnyCurve::Edwards(id) => write!(f, "{}", id),
+            AnyCurve::Montgomery(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+fn multiple<E: EllipticCurve>(e: &E, k: BigInt) -> E::Point {
+    e.get_generator() * &e.new_scalar(k)
+}
+
+fn reencode<E>(e: &E, buf: &[u8]) -> Result<Vec<u8>, Error>
+where
+    E: EllipticCurve + Decode<Deser = <E as EllipticCurve>::Point>,
+{
+    let (p, format) = e.decode_with_format(buf)?;
+    Ok(p.encode(format))
+}
+
+/// Returns an integer of up to `bytes` bytes, which is small with some
+/// probability, since small values often hit corner cases.
+fn integer(u: &mut Unstructured, bytes: usize) -> Result<BigInt> {
+    if u.ratio(1, 4)? {
+        return Ok(BigInt::from(u.int_in_range(-2i8..=2)?));
+    }
+    let len = u.int_in_range(0..=bytes)?;
+    Ok(BigInt::from_bytes_be(Sign::Plus, u.bytes(len)?))
+}
+
+/// FieldElement is an element of the base field of a curve.
+#[derive(Clone)]
+pub struct FieldElement {
+    pub curve: AnyCurve,
+    pub elt: FpElt,
+}
+
+impl<'a> Arbitrary<'a> for FieldElement {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let curve = AnyCurve::arbitrary(u)?;
+        let f = curve.get_field();
+        let elt = f.elt(integer(u, f.size_bytes() + 8)?);
+        Ok(FieldElement { curve, elt })
+    }
+}
+
+/// Scalar is a scalar of a curve of any of the models.
+#[derive(Clone)]
+pub enum Scalar {
+    Weierstrass(WeCurveID, weierstrass::Scalar),
+    Edwards(EdCurveID, edwards::Scalar),
+    Montgomery(MtCurveID, montgomery::Scalar),
+}
+
+impl<'a> Arbitrary<'a> for Scalar {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match AnyCurve::arbitrary(u)? {
+            AnyCurve::Weierstrass(id) => {
+                let k = integer(u, 72)?;
+                Scalar::Weierstrass(id, id.get().new_scalar(k))
+            }
+            AnyCurve::Edwards(id) => {
+                let k = integer(u, 72)?;
+                Scalar::Edwards(id, id.get().new_scalar(k))
+            }
+            AnyCurve::Montgomery(id) => {
+                let k = integer(u, 72)?;
+                Scalar::Montgomery(id, id.get().new_scalar(k))
+            }
+        })
+    }
+}
+
+/// Point is a point of a curve of any of the models, which is a multiple of
+/// the generator.
+#[derive(Clone)]
+pub enum Point {
+    Weierstrass(WeCurveID, weierstrass::Point),
+    Edwards(EdCurveID, edwards::Point),
+    Montgomery(MtCurveID, montgomery::Point),
+}
+
+impl<'a> Arbitrary<'a> for Point {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match Scalar::arbitrary(u)? {
+            Scalar::Weierstrass(id, k) => Point::Weierstrass(id, id.get().get_generator() * k),
+            Scalar::Edwards(id, k) => Point::Edwards(id, id.get().get_generator() * k),
+            Scalar::Montgomery(id, k) => Point::Montgomery(id, id.get().get_generator() * k),
+        })
+    }
+}
+
+/// Encoding is an input for the point decoder of a curve. It is either the
+/// encoding of a valid point, an encoding corrupted in a way decoders must
+/// detect, or arbitrary bytes.
+#[derive(Clone, Debug)]
+pub struct Encoding {
+    pub curve: AnyCurve,
+    pub bytes: Vec<u8>,
+    /// Whether the encoding is known to be valid.
+    pub valid: bool,
+}
+
+impl<'a> Arbitrary<'a> for Encoding {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let curve = AnyCurve::arbitrary(u)?;
+        let size = curve.get_field().size_bytes();
+        let k = integer(u, size + 8)?;
+        let mut bytes = curve.encode_multiple(k, *u.choose(&POINT_FORMATS)?);
+        let valid = match u.int_in_range(0..=5)? {
+            0 => true,
+            1 => {
+                // a coordinate equal to the modulus
+                let p = curve.get_field().get_modulus();
+                match curve {
+                    AnyCurve::Edwards(_) => {
+                        let p = p.to_bytes_le().1;
+                        bytes[..p.len()].copy_from_slice(&p)
+                    }
+                    _ if bytes.len() > 1 => bytes[1..size + 1].copy_from_slice(&p.to_bytes_be().1),
+                    _ => bytes[0] = 0x04,
+                }
+                false
+            }
+            2 => {
+                if bool::arbitrary(u)? {
+                    bytes.pop();
+                } else {
+                    bytes.push(u8::arbitrary(u)?);
+                }
+                false
+            }
+            3 => {
+                // a flipped bit yields a different point or no point at all
+                let i = u.choose_index(bytes.len())?;
+                bytes[i] ^= 1 << u.int_in_range(0..=7)?;
+                let valid = curve.reencode(&bytes).is_ok();
+                return Ok(Encoding {
+                    curve,
+                    bytes,
+                    valid,
+                });
+            }
+            _ => {
+                let bytes = Vec::<u8>::arbitrary(u)?;
+                let valid = curve.reencode(&bytes).is_ok();
+                return Ok(Encoding {
+                    curve,
+                    bytes,
+                    valid,
+                });
+            }
+        };
+        Ok(Encoding {
+            curve,
+            bytes,
+            valid,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+    use rand_core::{OsRng, RngCore};
+
+    use super::{Encoding, FieldElement, Point, Scalar};
+
+    #[test]
+    fn encodings() {
+        let mut buf = vec![0u8; 4096];
+        for _ in 0..64 {
+            OsRng.fill_bytes(&mut buf);
+            let mut u = Unstructured::new(&buf);
+            let enc = Encoding::arbitrary(&mut u).unwrap();
+            match enc.curve.reencode(&enc.bytes) {
+                Ok(out) => assert_eq!(out, enc.bytes, "{}", enc.curve),
+                Err(_) => assert!(!enc.valid, "{}", enc.curve),
+            }
+            assert!(FieldElement::arbitrary(&mut u).is_ok());
+            assert!(Scalar::arbitrary(&mut u).is_ok());
+            assert!(Point::arbitrary(&mut u).is_ok());
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use impl_ops::impl_op_ex;
+use num_bigint::{BigInt, BigUint};
+use sha3::Shake256;
+
+use std::io::Error;
+use std::ops;
+
+use crate::edwards::{Curve, Point, ProyCoordinates, Scalar};
+use crate::ellipticcurve::EllipticCurve;
+use crate::field::{Field, Sgn0, Sqrt};
+use crate::group::{ct_abs, decode_integer, sqrt_ratio, PrimeGroup, ERR_SCALAR};
+use crate::h2c::{Dst, Expander, ExpanderXof};
+use crate::instances::{GetCurve, EDWARDS448};
+use crate::ops::{Deserialize, Endianness, FromFactory, ScMulRef, Serialize};
+use crate::primefield::FpElt;
+use crate::{do_if_eq, impl_iter_fold};
+
+/// DecafElt is an element of Decaf448. It is represented by any of the
+/// points of edwards448 in its equivalence class.
+#[derive(Clone)]
+pub struct DecafElt(Point);
+
+/// Decaf448 is the prime-order group built on top of edwards448 as
+/// specified in RFC 9496, Section 5.
+#[derive(Clone)]
+pub struct Decaf448 {
+    e: Curve,
+    minus_one: FpElt,
+    sqrt_minus_d: FpElt,
+    invsqrt_minus_d: FpElt,
+    one_minus_d: FpElt,
+    one_minus_two_d: FpElt,
+}
+
+impl Decaf448 {
+    pub fn new() -> Decaf448 {
+        let e = EDWARDS448.get();
+        let f = e.get_field();
+        let sqrt_minus_d = ct_abs((-&e.d).sqrt());
+        let invsqrt_minus_d = 1u32 / &sqrt_minus_d;
+        let one_minus_d = f.one() - &e.d;
+        let one_minus_two_d = &one_minus_d - &e.d;
+        Decaf448 {
+            minus_one: -f.one(),
+            sqrt_minus_d,
+            invsqrt_minus_d,
+            one_minus_d,
+            one_minus_two_d,
+            e,
+        }
+    }
+    /// Returns the element derived from 112 uniformly random bytes, which is
+    /// the element derivation function of RFC 9496, Section 5.3.4.
+    pub fn from_uniform_bytes(&self, buf: &[u8]) -> DecafElt {
+        do_if_eq!(
+            buf.len() == 2 * SIZE_BYTES,
+            {
+                let p0 = self.map(&buf[..SIZE_BYTES]);
+                let p1 = self.map(&buf[SIZE_BYTES..]);
+                DecafElt(p0 + p1)
+            },
+            ERR_UNIFORM_LEN
+        )
+    }
+    fn map(&self, buf: &[u8]) -> Point {
+        let f = self.e.get_field();
+        let d = &self.e.d;
+        let t = f.from_bytes_le(buf).unwrap();
+        let one = f.one();
+        let r = -(&t ^ 2u32);
+        let u0 = d * (&r - &one);
+        let u1 = (&u0 + &one) * (&u0 - &r);
+        let rhs = (&r + &one) * u1;
+        let (was_square, v) = sqrt_ratio(&self.one_minus_two_d, &rhs, &self.minus_one);
+        let (v_prime, sgn) = if was_square {
+            (v, one.clone())
+        } else {
+            (t * v, self.minus_one.clone())
+        };
+        let s = &v_prime * (&r + &one);
+        let s2 = &s ^ 2u32;
+        let w0 = f.from(2u32) * ct_abs(s.clone());
+        let w1 = &s2 + &one;
+        let w2 = s2 - &one;
+        let w3 = v_prime * s * (r - one) * &self.one_minus_two_d + sgn;
+        self.e.new_proy_point(ProyCoordinates {
+            x: &w0 * &w3,
+            y: &w2 * &w1,
+            t: w0 * w2,
+            z: w1 * w3,
+        })
+    }
+}
+
+impl Default for Decaf448 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrimeGroup for Decaf448 {
+    type Elt = DecafElt;
+    type Scalar = Scalar;
+    fn identity(&self) -> Self::Elt {
+        DecafElt(self.e.identity())
+    }
+    /// Returns the generator of Decaf448, which is the class of twice the
+    /// generator of edwards448.
+    fn generator(&self) -> Self::Elt {
+        let g = self.e.get_generator();
+        DecafElt(&g + &g)
+    }
+    fn order(&self) -> BigUint {
+        self.e.get_order()
+    }
+    fn new_scalar(&self, k: BigInt) -> Self::Scalar {
+        self.e.new_scalar(k)
+    }
+    // based on https://www.rfc-editor.org/rfc/rfc9496#section-5.3.2
+    fn encode(&self, p: &Self::Elt) -> Vec<u8> {
+        let f = self.e.get_field();
+        let (x0, z0, t0) = (&p.0.c.x, &p.0.c.z, &p.0.c.t);
+        let u1 = (x0 + t0) * (x0 - t0);
+        let (_, invsqrt) = sqrt_ratio(
+            &f.one(),
+            &(&u1 * &self.one_minus_d * (x0 ^ 2u32)),
+            &self.minus_one,
+        );
+        let ratio = ct_abs(&invsqrt * u1 * &self.sqrt_minus_d);
+        let u2 = &self.invsqrt_minus_d * ratio * z0 - t0;
+        let s = ct_abs(&self.one_minus_d * invsqrt * x0 * u2);
+        s.to_bytes_le()
+    }
+    // based on https://www.rfc-editor.org/rfc/rfc9496#section-5.3.1
+    fn decode(&self, buf: &[u8]) -> Result<Self::Elt, Error> {
+        let f = self.e.get_field();
+        let s = f.from_canonical_le(buf)?;
+        if s.sgn0() < 0 {
+            return Err(Error::other("Non-canonical encoding."));
+        }
+        let one = f.one();
+        let ss = &s ^ 2u32;
+        let u1 = &one + &ss;
+        let u2 = (&u1 ^ 2u32) - f.from(4u32) * &self.e.d * &ss;
+        let (was_square, invsqrt) = sqrt_ratio(&one, &(&u2 * (&u1 ^ 2u32)), &self.minus_one);
+        if !was_square {
+            return Err(Error::other("Invalid encoding."));
+        }
+        let u3 = ct_abs(f.from(2u32) * s * &invsqrt * &u1 * &self.sqrt_minus_d);
+        let x = u3 * &invsqrt * u2 * &self.invsqrt_minus_d;
+        let y = (one - ss) * invsqrt * u1;
+        Ok(DecafElt(self.e.new_point(x, y)))
+    }
+    fn encode_scalar(&self, k: &Self::Scalar) -> Vec<u8> {
+        k.to_bytes_le()
+    }
+    fn decode_scalar(&self, buf: &[u8]) -> Result<Self::Scalar, Error> {
+        let order = self.e.get_order();
+        let len = order.bits().div_ceil(8);
+        match decode_integer(buf, len, &order, true) {
+            Some(k) => Ok(self.e.new_scalar(k)),
+            None => Err(Error::other(ERR_SCALAR)),
+        }
+    }
+    fn hash_to_group(&self, msg: &[u8], dst: &Dst) -> Self::Elt {
+        let exp = ExpanderXof::<Shake256>::new(dst, SECURITY_BITS);
+        self.from_uniform_bytes(&exp.expand(msg, 2 * SIZE_BYTES))
+    }
+    /// Returns the scalar obtained by reducing 64 bytes in little-endian
+    /// order, as done by the Decaf448 ciphersuite of RFC 9497.
+    fn hash_to_scalar(&self, msg: &[u8], dst: &Dst) -> Self::Scalar {
+        let exp = ExpanderXof::<Shake256>::new(dst, SECURITY_BITS);
+        let bytes = exp.expand(msg, WIDE_SCALAR_BYTES);
+        self.e.new_scalar_from_bytes(&bytes, Endianness::Little)
+    }
+}
+
+impl ScMulRef<Scalar> for DecafElt {}
+
+impl Eq for DecafElt {}
+
+impl PartialEq for DecafElt {
+    fn eq(&self, other: &Self) -> bool {
+        let (x1, y1) = (&self.0.c.x, &self.0.c.y);
+        let (x2, y2) = (&other.0.c.x, &other.0.c.y);
+        self.0.e == other.0.e && x1 * y2 == y1 * x2
+    }
+}
+
+impl_op_ex!(+|a: &DecafElt, b: &DecafElt| -> DecafElt { DecafElt(&a.0 + &b.0) });
+impl_op_ex!(-|a: &DecafElt, b: &DecafElt| -> DecafElt { DecafElt(&a.0 - &b.0) });
+impl_op_ex!(-|a: &DecafElt| -> DecafElt { DecafElt(-&a.0) });
+impl_op_ex!(*|a: &DecafElt, b: &Scalar| -> DecafElt { DecafElt(&a.0 * b) });
+impl_op_ex!(+=|a: &mut DecafElt, b: &DecafElt| { a.0 += &b.0 });
+impl_op_ex!(-=|a: &mut DecafElt, b: &DecafElt| { a.0 -= &b.0 });
+impl_op_ex!(*=|a: &mut DecafElt, b: &Scalar| { a.0 *= b });
+impl_iter_fold!(Sum, sum, DecafElt, +);
+
+impl std::fmt::Display for DecafElt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+const SIZE_BYTES: usize = 56;
+const WIDE_SCALAR_BYTES: usize = 64;
+const SECURITY_BITS: usize = 224;
+const ERR_UNIFORM_LEN: &str = "input must have 112 bytes";
@@ -0,0 +1,258 @@
+//! This is documentation for the `group` module.
+//!
+//! The group module provides prime-order groups, so that protocols can be
+//! written once and instantiated with any of them. Prime-order Weierstrass
+//! curves are groups on their own, whereas Ristretto255 and Decaf448 are
+//! prime-order quotients of edwards25519 and edwards448, respectively.
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
+use subtle::Choice;
+use zeroize::Zeroize;
+
+use crate::do_if_eq;
+use crate::ellipticcurve::EcScalar;
+use crate::field::Sqrt;
+use crate::h2c::Dst;
+use crate::ops::{AddRef, NegRef, ScMulRef, Serialize, SubRef};
+use crate::primefield::FpElt;
+
+mod decaf;
+pub(crate) mod msm;
+mod ristretto;
+mod weier;
+
+pub use crate::group::decaf::{Decaf448, DecafElt};
+pub use crate::group::ristretto::{Ristretto255, RistrettoElt};
+pub use crate::group::weier::WeGroup;
+
+/// PrimeGroup models a cyclic group of prime order together with the
+/// operations that protocols usually need: serialization of elements,
+/// hashing to the group, and multi-scalar multiplication.
+pub trait PrimeGroup {
+    type Elt: Clone + Eq + AddRef + SubRef + NegRef + ScMulRef<Self::Scalar>;
+    type Scalar: EcScalar + Clone;
+    fn identity(&self) -> Self::Elt;
+    fn generator(&self) -> Self::Elt;
+    fn order(&self) -> BigUint;
+    fn new_scalar(&self, _: BigInt) -> Self::Scalar;
+    /// Returns the canonical encoding of an element.
+    fn encode(&self, _: &Self::Elt) -> Vec<u8>;
+    /// Returns the element encoded by `buf`, or an error if `buf` is not a
+    /// canonical encoding of an element.
+    fn decode(&self, buf: &[u8]) -> Result<Self::Elt, std::io::Error>;
+    /// Returns the canonical encoding of a scalar.
+    fn encode_scalar(&self, _: &Self::Scalar) -> Vec<u8>;
+    /// Returns the scalar encoded by `buf`, or an error if `buf` is not a
+    /// canonical encoding of a scalar.
+    fn decode_scalar(&self, buf: &[u8]) -> Result<Self::Scalar, std::io::Error>;
+    /// Returns an element derived from `msg` whose discrete logarithm is
+    /// unknown, using `dst` as the domain separation tag.
+    fn hash_to_group(&self, msg: &[u8], dst: &Dst) -> Self::Elt;
+    /// Returns a uniformly distributed scalar derived from `msg`, using
+    /// `dst` as the domain separation tag.
+    fn hash_to_scalar(&self, msg: &[u8], dst: &Dst) -> Self::Scalar;
+    /// Returns a scalar sampled uniformly at random in `[1, n-1]`, where `n`
+    /// is the order of the group. Its bias is negligible, as 64 more bits
+    /// than the size of `n` are reduced.
+    fn random_scalar<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Self::Scalar {
+        let mut buf = vec![0u8; self.order().bits().div_ceil(8) + 8];
+        loop {
+            rng.fill_bytes(&mut buf);
+            let k = self.new_scalar(BigInt::from_bytes_be(Sign::Plus, &buf));
+            if self.encode_scalar(&k).iter().any(|&b| b != 0) {
+                buf.zeroize();
+                return k;
+            }
+        }
+    }
+    /// Returns the sum of `k_i * p_i`. Sums of up to 16 terms are computed
+    /// with the interleaved method of Strauss, and larger ones with the
+    /// bucket method of Pippenger.
+    fn multi_scalar_mul(&self, p: &[Self::Elt], k: &[Self::Scalar]) -> Self::Elt {
+        do_if_eq!(
+            p.len() == k.len(),
+            msm::msm(&self.identity(), p, k),
+            ERR_MSM_LEN
+        )
+    }
+}
+
+/// Returns `x` or `-x`, whichever is non-negative, i.e., even. The parity
+/// is read from the encoding of `x` and the negation is applied with
+/// `conditional_negate`, so that secret values are not branched on.
+fn ct_abs(mut x: FpElt) -> FpElt {
+    let bytes = x.to_bytes_be();
+    let is_odd = Choice::from(bytes[bytes.len() - 1] & 1);
+    x.conditional_negate(is_odd);
+    x
+}
+
+/// Returns `(true, sqrt(u/v))` if `u/v` is square, and
+/// `(false, sqrt(qnr*u/v))` otherwise, where `qnr` is a fixed non-square.
+/// The root returned is always non-negative. This is the `SQRT_RATIO_M1`
+/// function of RFC 9496.
+fn sqrt_ratio(u: &FpElt, v: &FpElt, qnr: &FpElt) -> (bool, FpElt) {
+    if u.is_zero() {
+        return (true, u.clone());
+    }
+    if v.is_zero() {
+        return (false, v.clone());
+    }
+    let ratio = u / v;
+    if ratio.is_square() {
+        (true, ct_abs(ratio.sqrt()))
+    } else {
+        (false, ct_abs((qnr * ratio).sqrt()))
+    }
+}
+
+/// Returns the integer encoded by `buf` if it has exactly `len` bytes and is
+/// smaller than `order`.
+fn decode_integer(buf: &[u8], len: usize, order: &BigUint, le: bool) -> Option<BigInt> {
+    let k = if le {
+        BigUint::from_bytes_le(buf)
+    } else {
+        BigUint::from_bytes_be(buf)
+    };
+    if buf.len() != len || &k >= order {
+        return None;
+    }
+    Some(k.into())
+}
+
+const ERR_SCALAR: &str = "scalar must be encoded with the size of the order and be smaller than it";
+const ERR_MSM_LEN: &str = "number of points and scalars must be equal";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::{ct_abs, Decaf448, PrimeGroup, Ristretto255, WeGroup};
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::field::Sgn0;
+    use crate::h2c::{Dst, P256_XMDSHA256_SSWU_RO_, SECP256K1_XMDSHA256_SSWU_RO_};
+    use crate::instances::{GetCurve, EDWARDS25519};
+    use crate::ops::FromFactory;
+    use crate::primefield::FpElt;
+
+    fn check_group<G: PrimeGroup>(g: &G, name: &str) {
+        let gen = g.generator();
+        let r = g.new_scalar(BigInt::from(g.order()));
+        assert!(
+            gen.clone() * r == g.identity(),
+            "order of generator for {}",
+            name
+        );
+        let mut p = g.identity();
+        for _ in 0..4 {
+            let q = g.decode(&g.encode(&p)).unwrap();
+            assert!(p == q, "encoding roundtrip for {}", name);
+            p = p + &gen;
+        }
+        let k = [
+            g.new_scalar(BigInt::from(3)),
+            g.new_scalar(BigInt::from(-5)),
+        ];
+        let pts = [gen.clone() + &gen, -gen.clone()];
+        let want = gen.clone() * g.new_scalar(BigInt::from(11));
+        assert!(g.multi_scalar_mul(&pts, &k) == want, "msm for {}", name);
+        let dst = Dst::new(b"QUUX-V01-CS02").unwrap();
+        let h0 = g.hash_to_group(b"abc", &dst);
+        let h1 = g.hash_to_group(b"abc", &dst);
+        let h2 = g.hash_to_group(b"abd", &dst);
+        assert!(h0 == h1 && h0 != h2, "hash to group for {}", name);
+        assert!(h0 != g.identity(), "hash to group for {}", name);
+        let k0 = g.hash_to_scalar(b"abc", &dst);
+        let k1 = g.hash_to_scalar(b"abd", &dst);
+        let enc = g.encode_scalar(&k1);
+        assert!(
+            g.decode_scalar(&enc).is_ok(),
+            "scalar encoding for {}",
+            name
+        );
+        assert!(
+            g.decode_scalar(&enc[1..]).is_err(),
+            "scalar encoding for {}",
+            name
+        );
+        let high = vec![0xffu8; enc.len()];
+        assert!(
+            g.decode_scalar(&high).is_err(),
+            "scalar encoding for {}",
+            name
+        );
+        assert!(
+            gen.clone() * k0 != gen.clone() * k1,
+            "hash to scalar for {}",
+            name
+        );
+    }
+
+    #[test]
+    fn absolute_value() {
+        let f = EDWARDS25519.get().get_field();
+        for i in [0, 1, 2, -1, -2, 12345].iter() {
+            let x: FpElt = f.from(*i);
+            let abs = ct_abs(x.clone());
+            assert!(abs.sgn0() > 0, "abs({}) must be non-negative", i);
+            assert!(abs == x || abs == -x, "abs({}) must be +-{}", i, i);
+        }
+    }
+
+    #[test]
+    fn prime_groups() {
+        check_group(&WeGroup::new(P256_XMDSHA256_SSWU_RO_), "P256");
+        check_group(&WeGroup::new(SECP256K1_XMDSHA256_SSWU_RO_), "secp256k1");
+        check_group(&Ristretto255::new(), "ristretto255");
+        check_group(&Decaf448::new(), "decaf448");
+    }
+
+    // Test vectors taken from RFC 9496, Appendix A.
+    #[test]
+    fn ristretto255() {
+        let g = Ristretto255::new();
+        let multiples = [
+            "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+            "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919",
+            "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259",
+        ];
+        let mut p = g.identity();
+        assert_eq!(g.encode(&p), vec![0u8; 32]);
+        for want in multiples.iter() {
+            p += g.generator();
+            assert_eq!(hex::encode(g.encode(&p)), *want);
+        }
+        let uniform = [
+            ("5d1be09e3d0c82fc538112490e35701979d99e06ca3e2b5b54bffe8b4dc772c14d98b696a1bbfb5ca32c436cc61c16563790306c79eaca7705668b47dffe5bb6",
+             "3066f82a1a747d45120d1740f14358531a8f04bbffe6a819f86dfe50f44a0a46"),
+            ("f116b34b8f17ceb56e8732a60d913dd10cce47a6d53bee9204be8b44f6678b270102a56902e2488c46120e9276cfe54638286b9e4b3cdb470b542d46c2068d38",
+             "f26e5b6f7d362d2d2a94c5d0e7602cb4773c95a2e5c31a64f133189fa76ed61b"),
+        ];
+        for (input, want) in uniform.iter() {
+            let p = g.from_uniform_bytes(&hex::decode(input).unwrap());
+            assert_eq!(hex::encode(g.encode(&p)), *want);
+        }
+        let bad = "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f";
+        assert!(g.decode(&hex::decode(bad).unwrap()).is_err());
+    }
+
+    #[test]
+    fn decaf448() {
+        let g = Decaf448::new();
+        let multiples = [
+            "6666666666666666666666666666666666666666666666666666666633333333333333333333333333333333333333333333333333333333",
+            "c898eb4f87f97c564c6fd61fc7e49689314a1f818ec85eeb3bd5514ac816d38778f69ef347a89fca817e66defdedce178c7cc709b2116e75",
+            "a0c09bf2ba7208fda0f4bfe3d0f5b29a543012306d43831b5adc6fe7f8596fa308763db15468323b11cf6e4aeb8c18fe44678f44545a69bc",
+        ];
+        let mut p = g.identity();
+        assert_eq!(g.encode(&p), vec![0u8; 56]);
+        for want in multiples.iter() {
+            p += g.generator();
+            assert_eq!(hex::encode(g.encode(&p)), *want);
+        }
+        assert!(g.decode(&[1u8; 56]).is_err());
+        assert!(g.decode(&[2u8; 56]).is_err());
+    }
+}
@@ -0,0 +1,219 @@
+//! This is documentation for the `msm` module.
+//!
+//! The msm module computes multi-scalar multiplications, i.e., sums of
+//! products `k_i * p_i`, in any group whose scalars can be serialized, such
+//! as a `PrimeGroup` or the points of an elliptic curve. Sums of a few terms, as in
+//! verification equations, are computed with the interleaved method of
+//! Strauss over wNAF representations of the scalars, which shares the
+//! doublings among all the terms. Larger sums are computed with the bucket
+//! method of Pippenger, which also shares most of the additions.
+
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::cast::ToPrimitive;
+use num_traits::identities::Zero;
+
+use crate::ops::{AddRef, Serialize, SubRef};
+
+/// Returns the sum of `k[i] * p[i]`, where `zero` is the identity of the
+/// group, using `strauss` for up to `STRAUSS_MAX_TERMS` terms and
+/// `pippenger` otherwise.
+pub(crate) fn msm<E, S>(zero: &E, p: &[E], k: &[S]) -> E
+where
+    E: Clone + AddRef + SubRef,
+    S: Serialize,
+{
+    if p.len() <= STRAUSS_MAX_TERMS {
+        strauss(zero, p, k)
+    } else {
+        pippenger(zero, p, k)
+    }
+}
+
+/// Returns the sum of `k[i] * p[i]` with the interleaved method of Strauss.
+/// Each scalar is written in width-`WNAF_WIDTH` NAF, and a table with the
+/// odd multiples of its point is computed, so that the sum takes one
+/// doubling per bit plus one addition per non-zero digit.
+pub(crate) fn strauss<E, S>(zero: &E, p: &[E], k: &[S]) -> E
+where
+    E: Clone + AddRef + SubRef,
+    S: Serialize,
+{
+    let digits: Vec<Vec<i8>> = k.iter().map(|k| wnaf(&to_int(k), WNAF_WIDTH)).collect();
+    let tables: Vec<Vec<E>> = p.iter().map(odd_multiples).collect();
+    let len = digits.iter().map(Vec::len).max().unwrap_or(0);
+    let mut acc = zero.clone();
+    for i in (0..len).rev() {
+        acc = double(acc);
+        for (d, t) in digits.iter().zip(tables.iter()) {
+            match d.get(i) {
+                Some(&di) if di > 0 => acc = acc + &t[(di / 2) as usize],
+                Some(&di) if di < 0 => acc = acc - &t[(-di / 2) as usize],
+                _ => {}
+            }
+        }
+    }
+    acc
+}
+
+/// Returns the sum of `k[i] * p[i]` with the bucket method of Pippenger.
+/// The scalars are split into windows of `c` bits; for each window, the
+/// points are added into the bucket of their digit, and the buckets are
+/// combined with a running sum.
+pub(crate) fn pippenger<E, S>(zero: &E, p: &[E], k: &[S]) -> E
+where
+    E: Clone + AddRef + SubRef,
+    S: Serialize,
+{
+    let c = window_len(p.len());
+    let ks: Vec<Vec<u8>> = k.iter().map(|k| k.to_bytes_be()).collect();
+    let bits = 8 * ks.iter().map(Vec::len).max().unwrap_or(0);
+    let windows = bits.div_ceil(c);
+    let mut acc = zero.clone();
+    for w in (0..windows).rev() {
+        if w + 1 != windows {
+            for _ in 0..c {
+                acc = double(acc);
+            }
+        }
+        let mut buckets: Vec<Option<E>> = vec![None; (1 << c) - 1];
+        for (pi, ki) in p.iter().zip(ks.iter()) {
+            let d = window(ki, w * c, c);
+            if d > 0 {
+                buckets[d - 1] = Some(match buckets[d - 1].take() {
+                    Some(b) => b + pi,
+                    None => pi.clone(),
+                });
+            }
+        }
+        // sum_j j * B_j = B_m + (B_m + B_m-1) + ... + (B_m + ... + B_1)
+        let mut running = zero.clone();
+        let mut sum = zero.clone();
+        for b in buckets.into_iter().rev() {
+            if let Some(b) = b {
+                running = running + b;
+            }
+            sum = sum + &running;
+        }
+        acc = acc + sum;
+    }
+    acc
+}
+
+fn double<T: Clone + std::ops::Add<T, Output = T>>(p: T) -> T {
+    p.clone() + p
+}
+
+/// Returns the scalar as a non-negative integer.
+fn to_int<S: Serialize>(k: &S) -> BigInt {
+    BigInt::from_bytes_be(Sign::Plus, &k.to_bytes_be())
+}
+
+/// Returns `[p, 3p, 5p, ..., (2^(WNAF_WIDTH-1)-1)p]`.
+fn odd_multiples<E: Clone + for<'a> std::ops::Add<&'a E, Output = E>>(p: &E) -> Vec<E> {
+    let p2 = p.clone() + p;
+    let mut t = vec![p.clone()];
+    for j in 1..1 << (WNAF_WIDTH - 2) {
+        let next = t[j - 1].clone() + &p2;
+        t.push(next);
+    }
+    t
+}
+
+/// Returns the width-`w` non-adjacent form of `k` >= 0, least significant
+/// digit first. Every non-zero digit is odd and less than `2^(w-1)` in
+/// absolute value, and is followed by at least `w-1` zeros.
+fn wnaf(k: &BigInt, w: usize) -> Vec<i8> {
+    let m = BigInt::from(1 << w);
+    let half = 1 << (w - 1);
+    let mut k = k.clone();
+    let mut out = Vec::new();
+    while !k.is_zero() {
+        let mut d = 0i32;
+        if k.is_odd() {
+            d = k.mod_floor(&m).to_i32().unwrap();
+            if d >= half {
+                d -= 1 << w;
+            }
+            k -= BigInt::from(d);
+        }
+        out.push(d as i8);
+        k >>= 1usize;
+    }
+    out
+}
+
+/// Returns the `c` bits of the big-endian integer `k` starting at bit
+/// `start`, counted from the least significant bit.
+fn window(k: &[u8], start: usize, c: usize) -> usize {
+    (0..c)
+        .map(|j| start + j)
+        .filter(|&i| i < 8 * k.len())
+        .map(|i| ((k[k.len() - 1 - i / 8] >> (i % 8)) & 1) as usize)
+        .enumerate()
+        .fold(0, |acc, (j, b)| acc | (b << j))
+}
+
+/// Returns the number of bits of the windows of `pippenger` for `n` terms,
+/// which grows with the logarithm of `n`.
+fn window_len(n: usize) -> usize {
+    let log_n = (usize::BITS - n.leading_zeros()) as usize;
+    (2 * log_n / 3).max(2)
+}
+
+const STRAUSS_MAX_TERMS: usize = 16;
+const WNAF_WIDTH: usize = 5;
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::{msm, pippenger, strauss, window, wnaf, WNAF_WIDTH};
+    use crate::group::{PrimeGroup, Ristretto255, WeGroup};
+    use crate::h2c::P256_XMDSHA256_SSWU_RO_;
+
+    #[test]
+    fn wnaf_digits() {
+        for k in [0u64, 1, 7, 255, 0xdead_beef, u64::MAX].iter() {
+            let d = wnaf(&BigInt::from(*k), WNAF_WIDTH);
+            let sum = d
+                .iter()
+                .rev()
+                .fold(BigInt::from(0), |acc, &di| 2 * acc + BigInt::from(di));
+            assert_eq!(sum, BigInt::from(*k));
+            assert!(d.iter().all(|&di| di % 2 != 0 || di == 0));
+            assert!(d.iter().all(|&di| di.abs() < 1 << (WNAF_WIDTH - 1)));
+        }
+        assert_eq!(window(&[0b1010_0110, 0b1100_0011], 6, 4), 0b1011);
+    }
+
+    fn check_msm<G: PrimeGroup>(g: &G) {
+        let gen = g.generator();
+        for n in [0usize, 1, 2, 5, 16, 17, 40].iter() {
+            let p: Vec<_> = (0..*n)
+                .map(|i| gen.clone() * g.new_scalar(BigInt::from(i + 2)))
+                .collect();
+            let k: Vec<_> = (0..*n)
+                .map(|i| g.new_scalar(BigInt::from(-3) - BigInt::from(1_000_003 * i)))
+                .collect();
+            let want = p
+                .iter()
+                .zip(k.iter())
+                .fold(g.identity(), |acc, (pi, ki)| acc + pi.clone() * ki);
+            let zero = g.identity();
+            assert!(strauss(&zero, &p, &k) == want, "strauss with {} terms", n);
+            assert!(
+                pippenger(&zero, &p, &k) == want,
+                "pippenger with {} terms",
+                n
+            );
+            assert!(msm(&zero, &p, &k) == want, "msm with {} terms", n);
+        }
+    }
+
+    #[test]
+    fn strauss_and_pippenger() {
+        check_msm(&WeGroup::new(P256_XMDSHA256_SSWU_RO_));
+        check_msm(&Ristretto255::new());
+    }
+}
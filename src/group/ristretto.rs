@@ -0,0 +1,222 @@
+use impl_ops::impl_op_ex;
+use num_bigint::{BigInt, BigUint};
+use num_traits::identities::Zero;
+use sha2::Sha512;
+
+use std::io::Error;
+use std::ops;
+
+use crate::edwards::{Curve, Point, ProyCoordinates, Scalar};
+use crate::ellipticcurve::EllipticCurve;
+use crate::field::{Field, Sgn0};
+use crate::group::{ct_abs, decode_integer, sqrt_ratio, PrimeGroup, ERR_SCALAR};
+use crate::h2c::{Dst, Expander, ExpanderXmd};
+use crate::instances::{GetCurve, EDWARDS25519};
+use crate::ops::{Deserialize, Endianness, FromFactory, ScMulRef, Serialize};
+use crate::primefield::FpElt;
+use crate::{do_if_eq, impl_iter_fold};
+
+/// RistrettoElt is an element of Ristretto255. It is represented by any of
+/// the points of edwards25519 in its equivalence class.
+#[derive(Clone)]
+pub struct RistrettoElt(Point);
+
+/// Ristretto255 is the prime-order group built on top of edwards25519 as
+/// specified in RFC 9496, Section 4.
+#[derive(Clone)]
+pub struct Ristretto255 {
+    e: Curve,
+    sqrt_m1: FpElt,
+    sqrt_ad_minus_one: FpElt,
+    invsqrt_a_minus_d: FpElt,
+    one_minus_d_sq: FpElt,
+    d_minus_one_sq: FpElt,
+}
+
+impl Ristretto255 {
+    pub fn new() -> Ristretto255 {
+        let e = EDWARDS25519.get();
+        let f = e.get_field();
+        let one = f.one();
+        let one_minus_d_sq = &one - (&e.d ^ 2u32);
+        let d_minus_one_sq = (&e.d - &one) ^ 2u32;
+        Ristretto255 {
+            sqrt_m1: f.from(SQRT_M1),
+            sqrt_ad_minus_one: f.from(SQRT_AD_MINUS_ONE),
+            invsqrt_a_minus_d: f.from(INVSQRT_A_MINUS_D),
+            one_minus_d_sq,
+            d_minus_one_sq,
+            e,
+        }
+    }
+    /// Returns the element derived from 64 uniformly random bytes, which is
+    /// the element derivation function of RFC 9496, Section 4.3.4.
+    pub fn from_uniform_bytes(&self, buf: &[u8]) -> RistrettoElt {
+        do_if_eq!(
+            buf.len() == 2 * SIZE_BYTES,
+            {
+                let p0 = self.map(&buf[..SIZE_BYTES]);
+                let p1 = self.map(&buf[SIZE_BYTES..]);
+                RistrettoElt(p0 + p1)
+            },
+            ERR_UNIFORM_LEN
+        )
+    }
+    fn map(&self, buf: &[u8]) -> Point {
+        let f = self.e.get_field();
+        let d = &self.e.d;
+        let mut bytes = buf.to_vec();
+        bytes[SIZE_BYTES - 1] &= 0x7f;
+        let t = f.from_bytes_le(&bytes).unwrap();
+        let one = f.one();
+        let r = &self.sqrt_m1 * (&t ^ 2u32);
+        let u = (&r + &one) * &self.one_minus_d_sq;
+        let v = (-&one - &r * d) * (&r + d);
+        let (was_square, s) = sqrt_ratio(&u, &v, &self.sqrt_m1);
+        let (s, c) = if was_square {
+            (s, -one.clone())
+        } else {
+            (-ct_abs(s * t), r.clone())
+        };
+        let n = c * (&r - &one) * &self.d_minus_one_sq - &v;
+        let s2 = &s ^ 2u32;
+        let w0 = f.from(2u32) * &s * &v;
+        let w1 = n * &self.sqrt_ad_minus_one;
+        let w2 = &one - &s2;
+        let w3 = one + s2;
+        self.e.new_proy_point(ProyCoordinates {
+            x: &w0 * &w3,
+            y: &w2 * &w1,
+            t: w0 * w2,
+            z: w1 * w3,
+        })
+    }
+}
+
+impl Default for Ristretto255 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrimeGroup for Ristretto255 {
+    type Elt = RistrettoElt;
+    type Scalar = Scalar;
+    fn identity(&self) -> Self::Elt {
+        RistrettoElt(self.e.identity())
+    }
+    fn generator(&self) -> Self::Elt {
+        RistrettoElt(self.e.get_generator())
+    }
+    fn order(&self) -> BigUint {
+        self.e.get_order()
+    }
+    fn new_scalar(&self, k: BigInt) -> Self::Scalar {
+        self.e.new_scalar(k)
+    }
+    // based on https://www.rfc-editor.org/rfc/rfc9496#section-4.3.2
+    fn encode(&self, p: &Self::Elt) -> Vec<u8> {
+        let f = self.e.get_field();
+        let (x0, y0, z0, t0) = (&p.0.c.x, &p.0.c.y, &p.0.c.z, &p.0.c.t);
+        let u1 = (z0 + y0) * (z0 - y0);
+        let u2 = x0 * y0;
+        let (_, invsqrt) = sqrt_ratio(&f.one(), &(&u1 * (&u2 ^ 2u32)), &self.sqrt_m1);
+        let den1 = &invsqrt * u1;
+        let den2 = invsqrt * u2;
+        let z_inv = &den1 * &den2 * t0;
+        let rotate = (t0 * &z_inv).sgn0() < 0;
+        let (x, y, den_inv) = if rotate {
+            let ix0 = x0 * &self.sqrt_m1;
+            let iy0 = y0 * &self.sqrt_m1;
+            (iy0, ix0, den1 * &self.invsqrt_a_minus_d)
+        } else {
+            (x0.clone(), y0.clone(), den2)
+        };
+        let y = if (x * z_inv).sgn0() < 0 { -y } else { y };
+        let s = ct_abs(den_inv * (z0 - y));
+        s.to_bytes_le()
+    }
+    // based on https://www.rfc-editor.org/rfc/rfc9496#section-4.3.1
+    fn decode(&self, buf: &[u8]) -> Result<Self::Elt, Error> {
+        let f = self.e.get_field();
+        let s = f.from_canonical_le(buf)?;
+        if s.sgn0() < 0 {
+            return Err(Error::other("Non-canonical encoding."));
+        }
+        let one = f.one();
+        let ss = &s ^ 2u32;
+        let u1 = &one - &ss;
+        let u2 = one + ss;
+        let u2_sqr = &u2 ^ 2u32;
+        let v = -(&self.e.d * (&u1 ^ 2u32)) - &u2_sqr;
+        let (was_square, invsqrt) = sqrt_ratio(&f.one(), &(&v * u2_sqr), &self.sqrt_m1);
+        let den_x = &invsqrt * u2;
+        let den_y = invsqrt * &den_x * v;
+        let x = ct_abs(f.from(2u32) * s * den_x);
+        let y = u1 * den_y;
+        let t = &x * &y;
+        if !was_square || t.sgn0() < 0 || y.is_zero() {
+            return Err(Error::other("Invalid encoding."));
+        }
+        Ok(RistrettoElt(self.e.new_point(x, y)))
+    }
+    fn encode_scalar(&self, k: &Self::Scalar) -> Vec<u8> {
+        k.to_bytes_le()
+    }
+    fn decode_scalar(&self, buf: &[u8]) -> Result<Self::Scalar, Error> {
+        let order = self.e.get_order();
+        let len = order.bits().div_ceil(8);
+        match decode_integer(buf, len, &order, true) {
+            Some(k) => Ok(self.e.new_scalar(k)),
+            None => Err(Error::other(ERR_SCALAR)),
+        }
+    }
+    fn hash_to_group(&self, msg: &[u8], dst: &Dst) -> Self::Elt {
+        let exp = ExpanderXmd::<Sha512>::new(dst);
+        self.from_uniform_bytes(&exp.expand(msg, 2 * SIZE_BYTES))
+    }
+    /// Returns the scalar obtained by reducing 64 bytes in little-endian
+    /// order, as done by the Ristretto255 ciphersuite of RFC 9497.
+    fn hash_to_scalar(&self, msg: &[u8], dst: &Dst) -> Self::Scalar {
+        let exp = ExpanderXmd::<Sha512>::new(dst);
+        let bytes = exp.expand(msg, WIDE_SCALAR_BYTES);
+        self.e.new_scalar_from_bytes(&bytes, Endianness::Little)
+    }
+}
+
+impl ScMulRef<Scalar> for RistrettoElt {}
+
+impl Eq for RistrettoElt {}
+
+impl PartialEq for RistrettoElt {
+    fn eq(&self, other: &Self) -> bool {
+        let (x1, y1) = (&self.0.c.x, &self.0.c.y);
+        let (x2, y2) = (&other.0.c.x, &other.0.c.y);
+        self.0.e == other.0.e && (x1 * y2 == y1 * x2 || y1 * y2 == x1 * x2)
+    }
+}
+
+impl_op_ex!(+|a: &RistrettoElt, b: &RistrettoElt| -> RistrettoElt { RistrettoElt(&a.0 + &b.0) });
+impl_op_ex!(-|a: &RistrettoElt, b: &RistrettoElt| -> RistrettoElt { RistrettoElt(&a.0 - &b.0) });
+impl_op_ex!(-|a: &RistrettoElt| -> RistrettoElt { RistrettoElt(-&a.0) });
+impl_op_ex!(*|a: &RistrettoElt, b: &Scalar| -> RistrettoElt { RistrettoElt(&a.0 * b) });
+impl_op_ex!(+=|a: &mut RistrettoElt, b: &RistrettoElt| { a.0 += &b.0 });
+impl_op_ex!(-=|a: &mut RistrettoElt, b: &RistrettoElt| { a.0 -= &b.0 });
+impl_op_ex!(*=|a: &mut RistrettoElt, b: &Scalar| { a.0 *= b });
+impl_iter_fold!(Sum, sum, RistrettoElt, +);
+
+impl std::fmt::Display for RistrettoElt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+const SIZE_BYTES: usize = 32;
+const WIDE_SCALAR_BYTES: usize = 64;
+const SQRT_M1: &str =
+    "19681161376707505956807079304988542015446066515923890162744021073123829784752";
+const SQRT_AD_MINUS_ONE: &str =
+    "25063068953384623474111414158702152701244531502492656460079210482610430750235";
+const INVSQRT_A_MINUS_D: &str =
+    "54469307008909316920995813868745141605393597292927456921205312896311721017578";
+const ERR_UNIFORM_LEN: &str = "input must have 64 bytes";
@@ -0,0 +1,73 @@
+use num_bigint::{BigInt, BigUint};
+use num_traits::identities::One;
+
+use crate::ellipticcurve::{Decode, EllipticCurve, Encode, PointFormat};
+use crate::group::{decode_integer, PrimeGroup, ERR_SCALAR};
+use crate::h2c::{Dst, HashToCurve, Suite, RO};
+use crate::instances::{GetCurve, WeCurveID};
+use crate::ops::Serialize;
+use crate::weierstrass::{Curve, Point, Scalar};
+
+/// WeGroup is the group of points of a Weierstrass curve of prime order,
+/// i.e., with cofactor one. Elements are encoded in compressed form and
+/// hashed to the group using a random-oracle suite.
+#[derive(Clone)]
+pub struct WeGroup {
+    e: Curve,
+    suite: Suite<WeCurveID, RO>,
+}
+
+impl WeGroup {
+    /// Returns the group of points of the curve of `suite`, which is also
+    /// used for hashing to the group. It panics if the curve has a cofactor
+    /// different from one.
+    pub fn new(suite: Suite<WeCurveID, RO>) -> WeGroup {
+        let e = suite.curve.get();
+        if !e.get_cofactor().is_one() {
+            panic!("{}", ERR_COFACTOR)
+        }
+        WeGroup { e, suite }
+    }
+}
+
+impl PrimeGroup for WeGroup {
+    type Elt = Point;
+    type Scalar = Scalar;
+    fn identity(&self) -> Self::Elt {
+        self.e.identity()
+    }
+    fn generator(&self) -> Self::Elt {
+        self.e.get_generator()
+    }
+    fn order(&self) -> BigUint {
+        self.e.get_order()
+    }
+    fn new_scalar(&self, k: BigInt) -> Self::Scalar {
+        self.e.new_scalar(k)
+    }
+    fn encode(&self, p: &Self::Elt) -> Vec<u8> {
+        p.encode(PointFormat::Compressed)
+    }
+    fn decode(&self, buf: &[u8]) -> Result<Self::Elt, std::io::Error> {
+        self.e.decode(buf)
+    }
+    fn encode_scalar(&self, k: &Self::Scalar) -> Vec<u8> {
+        k.to_bytes_be()
+    }
+    fn decode_scalar(&self, buf: &[u8]) -> Result<Self::Scalar, std::io::Error> {
+        let order = self.e.get_order();
+        let len = order.bits().div_ceil(8);
+        match decode_integer(buf, len, &order, false) {
+            Some(k) => Ok(self.e.new_scalar(k)),
+            None => Err(std::io::Error::other(ERR_SCALAR)),
+        }
+    }
+    fn hash_to_group(&self, msg: &[u8], dst: &Dst) -> Self::Elt {
+        self.suite.get(dst).hash(msg)
+    }
+    fn hash_to_scalar(&self, msg: &[u8], dst: &Dst) -> Self::Scalar {
+        self.suite.get(dst).hash_to_scalar(msg)
+    }
+}
+
+const ERR_COFACTOR: &str = "curve must have prime order";
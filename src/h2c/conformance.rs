@@ -0,0 +1,92 @@
+//! This is documentation for the `conformance` module.
+//!
+//! The conformance module checks the suites against the test vectors of
+//! RFC 9380, Appendix J, which are stored in the `vectors` directory in the
+//! JSON format used by the repository of the specification. Besides the
+//! output point `P`, each vector lists the field elements `u` derived from
+//! the message and the points `Q0` and `Q1` (or `Q` for nonuniform
+//! encodings) they are mapped to. Every stage is checked on its own, so a
+//! faulty map to curve is reported as such rather than as a wrong `P`.
+//!
+//! Adding a vector file only takes a line in the test of the matching
+//! curve model.
+
+use serde_json::Value;
+
+use crate::ellipticcurve::EllipticCurve;
+use crate::h2c::{Dst, Encoding, HashToField};
+use crate::instances::GetCurve;
+use crate::ops::FromFactory;
+
+use super::{P256_XMDSHA256_SSWU_RO_, P384_XMDSHA384_SSWU_RO_, SECP256K1_XMDSHA256_SSWU_RO_};
+
+/// Returns the domain separation tag of the vectors and the vectors.
+fn parse(json: &str) -> (Dst, Vec<Value>) {
+    let data: Value = serde_json::from_str(json).unwrap();
+    let dst = Dst::new(data["dst"].as_str().unwrap().as_bytes()).unwrap();
+    (dst, data["vectors"].as_array().unwrap().clone())
+}
+
+fn point<E: EllipticCurve>(e: &E, v: &Value) -> E::Point {
+    let f = e.get_field();
+    let x = f.from(v["x"].as_str().unwrap());
+    let y = f.from(v["y"].as_str().unwrap());
+    e.new_point(x, y)
+}
+
+/// Checks the stages of `h2c` against the vectors: `hash_to_field`, then
+/// `map_to_curve` on every field element, and finally `clear_cofactor` on
+/// the sum of the mapped points.
+fn check<E, V>(name: &str, e: &E, h2c: &Encoding<E, V>, vectors: &[Value])
+where
+    E: EllipticCurve + 'static,
+    E::F: HashToField,
+{
+    let f = e.get_field();
+    for v in vectors {
+        let msg = v["msg"].as_str().unwrap();
+        let want_u = v["u"].as_array().unwrap();
+        let u = h2c.hash_to_field(msg.as_bytes(), want_u.len());
+        for (i, (got, want)) in u.iter().zip(want_u).enumerate() {
+            let want = f.from(want.as_str().unwrap());
+            assert!(*got == want, "{}: u[{}] for msg {:?}", name, i, msg);
+        }
+        let labels: &[&str] = match u.len() {
+            1 => &["Q"],
+            _ => &["Q0", "Q1"],
+        };
+        let mut q = Vec::new();
+        for (u, label) in u.iter().zip(labels) {
+            let got = h2c.map_to_curve(u);
+            let want = point(e, &v[*label]);
+            assert!(got == want, "{}: {} for msg {:?}", name, label, msg);
+            q.push(got);
+        }
+        let sum = q.into_iter().reduce(|a, b| a + b).unwrap();
+        let got = h2c.clear_cofactor(sum);
+        let want = point(e, &v["P"]);
+        assert!(got == want, "{}: P for msg {:?}", name, msg);
+    }
+}
+
+#[test]
+fn weierstrass_vectors() {
+    let files = [
+        (
+            P256_XMDSHA256_SSWU_RO_,
+            include_str!("vectors/P256_XMD-SHA-256_SSWU_RO_.json"),
+        ),
+        (
+            P384_XMDSHA384_SSWU_RO_,
+            include_str!("vectors/P384_XMD-SHA-384_SSWU_RO_.json"),
+        ),
+        (
+            SECP256K1_XMDSHA256_SSWU_RO_,
+            include_str!("vectors/secp256k1_XMD-SHA-256_SSWU_RO_.json"),
+        ),
+    ];
+    for (suite, json) in files.iter() {
+        let (dst, vectors) = parse(json);
+        check(suite.name, &suite.curve.get(), &suite.get(&dst), &vectors);
+    }
+}
@@ -0,0 +1,67 @@
+//! This is documentation for the `dst` module.
+//!
+//! The dst module implements domain separation tags as specified in
+//! RFC 9380, Section 3.1.
+
+use std::io::Error;
+
+/// Dst is a domain separation tag. It binds the outputs of hash functions to
+/// an application and a ciphersuite, so that different uses of the same
+/// hash function behave as independent random oracles.
+///
+/// Tags are never empty. Tags longer than 255 bytes are accepted, since the
+/// expanders reduce them as prescribed by RFC 9380, Section 5.3.3.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Dst(Vec<u8>);
+
+impl Dst {
+    /// Returns a tag from an arbitrary non-empty string.
+    pub fn new(tag: &[u8]) -> Result<Dst, Error> {
+        if tag.is_empty() {
+            return Err(Error::other(ERR_EMPTY_DST));
+        }
+        Ok(Dst(tag.to_vec()))
+    }
+    /// Returns the tag `<app>-V<version>-CS<cs>-with-<suite>`, which is the
+    /// format recommended by RFC 9380, Section 3.1. Here, `app` identifies
+    /// the application, `version` its version, `cs` the ciphersuite within
+    /// the application, and `suite` is the identifier of the hash to curve
+    /// suite, e.g., `P256_XMD:SHA-256_SSWU_RO_`.
+    ///
+    /// Including the suite identifier guarantees that two suites used by the
+    /// same application never share a tag.
+    pub fn compose(app: &str, version: u8, cs: u8, suite: &str) -> Result<Dst, Error> {
+        if app.is_empty() || suite.is_empty() {
+            return Err(Error::other(ERR_EMPTY_DST));
+        }
+        Dst::new(format!("{}-V{:02}-CS{:02}-with-{}", app, version, cs, suite).as_bytes())
+    }
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Dst {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.0))
+    }
+}
+
+const ERR_EMPTY_DST: &str = "domain separation tag must not be empty";
+
+#[cfg(test)]
+mod tests {
+    use super::Dst;
+
+    #[test]
+    fn compose() {
+        let dst = Dst::compose("QUUX", 1, 2, "P256_XMD:SHA-256_SSWU_RO_").unwrap();
+        assert_eq!(
+            dst.as_bytes(),
+            &b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_"[..]
+        );
+        assert!(Dst::new(b"").is_err());
+        assert!(Dst::compose("", 1, 2, "P256_XMD:SHA-256_SSWU_RO_").is_err());
+        assert!(Dst::compose("QUUX", 1, 2, "").is_err());
+    }
+}
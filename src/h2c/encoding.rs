@@ -0,0 +1,174 @@
+//! This is documentation for the `encoding` module.
+//!
+//! The encoding module implements the `hash_to_curve` and `encode_to_curve`
+//! functions of RFC 9380.
+
+use rand_core::{CryptoRng, RngCore};
+
+use std::marker::PhantomData;
+
+use crate::ellipticcurve::{ClearCofactor, EcPoint, EllipticCurve, MapToCurve};
+use crate::field::Field;
+use crate::h2c::{Expander, HashToField, HashToScalar};
+
+/// HashToCurve is a function that outputs a point on an elliptic curve from an
+/// arbitrary string. Its output distribution is indistinguishable from
+/// uniformly random points, so it can be used as a random oracle.
+pub trait HashToCurve {
+    type E: EllipticCurve;
+    /// Returns a point of the prime-order subgroup derived from `msg`.
+    fn hash(&self, msg: &[u8]) -> <Self::E as EllipticCurve>::Point;
+    /// Returns a point sampled uniformly at random among the points of order
+    /// `r` by hashing random bytes, so that, unlike with
+    /// `EllipticCurve::random_point`, nobody learns its discrete logarithm.
+    fn random_point<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> <Self::E as EllipticCurve>::Point {
+        let mut buf = [0u8; RANDOM_POINT_BYTES];
+        loop {
+            rng.fill_bytes(&mut buf);
+            let p = self.hash(&buf);
+            if !p.is_identity() {
+                return p;
+            }
+        }
+    }
+}
+
+/// EncodeToCurve is a function that outputs a point on an elliptic curve from
+/// an arbitrary string. Its output distribution is not uniform, so it must
+/// only be used by protocols that are secure under this assumption.
+pub trait EncodeToCurve {
+    type E: EllipticCurve;
+    /// Returns a point of the prime-order subgroup derived from `msg`.
+    fn encode(&self, msg: &[u8]) -> <Self::E as EllipticCurve>::Point;
+}
+
+/// RO marks random-oracle encodings, which implement `HashToCurve`.
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub struct RO;
+
+/// NU marks nonuniform encodings, which implement `EncodeToCurve`.
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub struct NU;
+
+/// Encoding maps strings to points of `E` as specified in RFC 9380,
+/// Section 3. Random-oracle encodings hash to two field elements, map each
+/// of them to the curve, and add the resulting points; nonuniform encodings
+/// hash to a single field element. Both clear the cofactor at the end.
+pub struct Encoding<E: EllipticCurve, V> {
+    e: E,
+    exp: Box<dyn Expander>,
+    map_to_curve: Box<dyn MapToCurve<E = E>>,
+    clear_cofactor: Box<dyn ClearCofactor<E = E>>,
+    k: usize,
+    _v: PhantomData<V>,
+}
+
+impl<E: EllipticCurve + 'static, V> Encoding<E, V> {
+    /// Returns an encoding to the curve `e`, where `k` is the target security
+    /// level in bits. Cofactors are cleared by multiplying by the cofactor of
+    /// `e`.
+    pub fn new(
+        e: E,
+        exp: Box<dyn Expander>,
+        map_to_curve: Box<dyn MapToCurve<E = E>>,
+        k: usize,
+    ) -> Encoding<E, V> {
+        let h_eff = e.new_scalar(e.get_cofactor());
+        let clear_cofactor = Box::new(MulByCofactor { h_eff });
+        Encoding::with_cofactor(e, exp, map_to_curve, clear_cofactor, k)
+    }
+    /// Returns an encoding to the curve `e` that clears cofactors with
+    /// `clear_cofactor`, e.g., for curves whose cofactor is larger than
+    /// their order, or that have a faster method than multiplying by it.
+    pub fn with_cofactor(
+        e: E,
+        exp: Box<dyn Expander>,
+        map_to_curve: Box<dyn MapToCurve<E = E>>,
+        clear_cofactor: Box<dyn ClearCofactor<E = E>>,
+        k: usize,
+    ) -> Encoding<E, V> {
+        Encoding {
+            e,
+            exp,
+            map_to_curve,
+            clear_cofactor,
+            k,
+            _v: PhantomData,
+        }
+    }
+    /// Returns a scalar derived from `msg` using the same expander and
+    /// security level as the encoding.
+    pub fn hash_to_scalar(&self, msg: &[u8]) -> E::Scalar {
+        self.e.hash_to_scalar(&*self.exp, msg, self.k)
+    }
+    /// Returns the point of the curve that the field element `u` is mapped
+    /// to, which need not be in the prime-order subgroup.
+    pub fn map_to_curve(&self, u: &<E::F as Field>::Elt) -> E::Point {
+        self.map_to_curve.map(u)
+    }
+    /// Returns the point of the prime-order subgroup that `p` is mapped to.
+    pub fn clear_cofactor(&self, p: E::Point) -> E::Point {
+        self.clear_cofactor.clear(p)
+    }
+}
+
+impl<E, V> Encoding<E, V>
+where
+    E: EllipticCurve + 'static,
+    E::F: HashToField,
+{
+    /// Returns the `count` field elements derived from `msg`, which is the
+    /// first step of the encoding. Together with `map_to_curve` and
+    /// `clear_cofactor`, it exposes the intermediate values listed in the
+    /// test vectors of RFC 9380, Appendix J.
+    pub fn hash_to_field(&self, msg: &[u8], count: usize) -> Vec<<E::F as Field>::Elt> {
+        self.e
+            .get_field()
+            .hash_to_field(&*self.exp, msg, count, self.k)
+    }
+}
+
+impl<E> HashToCurve for Encoding<E, RO>
+where
+    E: EllipticCurve + 'static,
+    E::F: HashToField,
+{
+    type E = E;
+    fn hash(&self, msg: &[u8]) -> <Self::E as EllipticCurve>::Point {
+        let u = self.hash_to_field(msg, 2);
+        let q0 = self.map_to_curve(&u[0]);
+        let q1 = self.map_to_curve(&u[1]);
+        self.clear_cofactor(q0 + q1)
+    }
+}
+
+impl<E> EncodeToCurve for Encoding<E, NU>
+where
+    E: EllipticCurve + 'static,
+    E::F: HashToField,
+{
+    type E = E;
+    fn encode(&self, msg: &[u8]) -> <Self::E as EllipticCurve>::Point {
+        let u = self.hash_to_field(msg, 1);
+        self.clear_cofactor(self.map_to_curve(&u[0]))
+    }
+}
+
+/// MulByCofactor clears cofactors by multiplying by the cofactor of the
+/// curve.
+struct MulByCofactor<E: EllipticCurve> {
+    h_eff: E::Scalar,
+}
+
+impl<E: EllipticCurve> ClearCofactor for MulByCofactor<E> {
+    type E = E;
+    fn clear(&self, p: E::Point) -> E::Point {
+        p * &self.h_eff
+    }
+}
+
+/// Length of the random strings hashed by `HashToCurve::random_point`.
+const RANDOM_POINT_BYTES: usize = 32;
@@ -0,0 +1,296 @@
+//! This is documentation for the `expander` module.
+//!
+//! The expander module implements the `expand_message` functions of RFC 9380.
+
+use digest::core_api::BlockSizeUser;
+use digest::{Digest, ExtendableOutput, Update, XofReader};
+
+use std::marker::PhantomData;
+
+use crate::h2c::Dst;
+
+/// Expander produces uniformly random byte strings of arbitrary length from
+/// a message and a domain separation tag.
+pub trait Expander {
+    /// Returns `n` pseudo-random bytes derived from `msg`.
+    fn expand(&self, msg: &[u8], n: usize) -> Vec<u8>;
+}
+
+/// ExpanderXmd implements `expand_message_xmd` using a Merkle-Damgård hash
+/// function such as SHA-256 or SHA-512.
+#[derive(Clone)]
+pub struct ExpanderXmd<D> {
+    dst: Vec<u8>,
+    _h: PhantomData<D>,
+}
+
+impl<D> ExpanderXmd<D>
+where
+    D: Digest + BlockSizeUser,
+{
+    /// Returns an expander bound to `dst`. Tags longer than 255 bytes are
+    /// hashed down as prescribed by the specification.
+    pub fn new(dst: &Dst) -> ExpanderXmd<D> {
+        let dst = dst.as_bytes();
+        let dst = if dst.len() > MAX_DST_LENGTH {
+            D::new()
+                .chain_update(OVERSIZE_DST_SALT)
+                .chain_update(dst)
+                .finalize()
+                .to_vec()
+        } else {
+            dst.to_vec()
+        };
+        ExpanderXmd {
+            dst,
+            _h: PhantomData,
+        }
+    }
+}
+
+impl<D> Expander for ExpanderXmd<D>
+where
+    D: Digest + BlockSizeUser,
+{
+    fn expand(&self, msg: &[u8], n: usize) -> Vec<u8> {
+        let b_len = <D as Digest>::output_size();
+        let s_len = D::block_size();
+        let ell = n.div_ceil(b_len);
+        if ell > 255 || n > 65535 {
+            panic!("{}", ERR_EXPAND_LEN)
+        }
+        let dst_prime = [&self.dst[..], &[self.dst.len() as u8]].concat();
+        let z_pad = vec![0u8; s_len];
+        let b_0 = D::new()
+            .chain_update(z_pad)
+            .chain_update(msg)
+            .chain_update((n as u16).to_be_bytes())
+            .chain_update([0u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        let mut b_i = D::new()
+            .chain_update(&b_0)
+            .chain_update([1u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        let mut out = b_i.to_vec();
+        for i in 2..=ell {
+            let xored: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(x, y)| x ^ y).collect();
+            b_i = D::new()
+                .chain_update(xored)
+                .chain_update([i as u8])
+                .chain_update(&dst_prime)
+                .finalize();
+            out.extend_from_slice(&b_i);
+        }
+        out.truncate(n);
+        out
+    }
+}
+
+/// ExpanderXof implements `expand_message_xof` using an extendable-output
+/// function such as SHAKE128 or SHAKE256.
+#[derive(Clone)]
+pub struct ExpanderXof<X> {
+    dst: Vec<u8>,
+    _h: PhantomData<X>,
+}
+
+impl<X> ExpanderXof<X>
+where
+    X: Default + Update + ExtendableOutput,
+{
+    /// Returns an expander bound to `dst`, where `k` is the target security
+    /// level in bits. Tags longer than 255 bytes are hashed down to
+    /// `ceil(2k/8)` bytes as prescribed by the specification.
+    pub fn new(dst: &Dst, k: usize) -> ExpanderXof<X> {
+        let dst = dst.as_bytes();
+        let dst = if dst.len() > MAX_DST_LENGTH {
+            let mut h = X::default();
+            h.update(OVERSIZE_DST_SALT);
+            h.update(dst);
+            let mut out = vec![0u8; (2 * k).div_ceil(8)];
+            h.finalize_xof().read(&mut out);
+            out
+        } else {
+            dst.to_vec()
+        };
+        ExpanderXof {
+            dst,
+            _h: PhantomData,
+        }
+    }
+}
+
+impl<X> Expander for ExpanderXof<X>
+where
+    X: Default + Update + ExtendableOutput,
+{
+    fn expand(&self, msg: &[u8], n: usize) -> Vec<u8> {
+        if n > 65535 {
+            panic!("{}", ERR_EXPAND_LEN)
+        }
+        let mut h = X::default();
+        h.update(msg);
+        h.update(&(n as u16).to_be_bytes());
+        h.update(&self.dst);
+        h.update(&[self.dst.len() as u8]);
+        let mut out = vec![0u8; n];
+        h.finalize_xof().read(&mut out);
+        out
+    }
+}
+
+const MAX_DST_LENGTH: usize = 255;
+const OVERSIZE_DST_SALT: &[u8] = b"H2C-OVERSIZE-DST-";
+const ERR_EXPAND_LEN: &str = "requested length is too large";
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Sha256, Sha512};
+    use sha3::{Shake128, Shake256};
+
+    use super::{Expander, ExpanderXmd, ExpanderXof};
+    use crate::h2c::Dst;
+
+    // Test vectors from RFC 9380, Appendix K.
+    fn message(prefix: &str) -> Vec<u8> {
+        match prefix {
+            "q128_" => [prefix, &"q".repeat(128)].concat().into_bytes(),
+            "a512_" => [prefix, &"a".repeat(512)].concat().into_bytes(),
+            _ => prefix.as_bytes().to_vec(),
+        }
+    }
+
+    fn dst(tag: &[u8]) -> Dst {
+        Dst::new(tag).unwrap()
+    }
+
+    fn check(exp: &dyn Expander, vectors: &[(&str, usize, &str)]) {
+        for (msg, n, want) in vectors.iter() {
+            let got = exp.expand(&message(msg), *n);
+            assert_eq!(hex::encode(got), *want, "msg: {:?} len: {}", msg, n);
+        }
+    }
+
+    #[test]
+    fn expand_message_xmd() {
+        let long_dst = [DST_SHA256, b"-long-DST-", &[b'1'; 208]].concat();
+        check(&ExpanderXmd::<Sha256>::new(&dst(DST_SHA256)), XMD_SHA256);
+        check(
+            &ExpanderXmd::<Sha256>::new(&dst(&long_dst)),
+            XMD_SHA256_LONG,
+        );
+        check(&ExpanderXmd::<Sha512>::new(&dst(DST_SHA512)), XMD_SHA512);
+    }
+
+    #[test]
+    fn expand_message_xof() {
+        let long_dst = [DST_SHAKE128, b"-long-DST-", &[b'1'; 210]].concat();
+        check(
+            &ExpanderXof::<Shake128>::new(&dst(DST_SHAKE128), 128),
+            XOF_SHAKE128,
+        );
+        check(
+            &ExpanderXof::<Shake128>::new(&dst(&long_dst), 128),
+            XOF_SHAKE128_LONG,
+        );
+        check(
+            &ExpanderXof::<Shake256>::new(&dst(DST_SHAKE256), 256),
+            XOF_SHAKE256,
+        );
+    }
+
+    const DST_SHA256: &[u8] = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    const DST_SHA512: &[u8] = b"QUUX-V01-CS02-with-expander-SHA512-256";
+    const DST_SHAKE128: &[u8] = b"QUUX-V01-CS02-with-expander-SHAKE128";
+    const DST_SHAKE256: &[u8] = b"QUUX-V01-CS02-with-expander-SHAKE256";
+
+    const XMD_SHA256: &[(&str, usize, &str)] = &[
+        (
+            "",
+            32,
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+        ),
+        (
+            "a512_",
+            32,
+            "4623227bcc01293b8c130bf771da8c298dede7383243dc0993d2d94823958c4c",
+        ),
+        (
+            "abc",
+            128,
+            "abba86a6129e366fc877aab32fc4ffc70120d8996c88aee2fe4b32d6c7b6437a647e6c3163d40b76a73cf6a5674ef1d890f95b664ee0afa5359a5c4e07985635bbecbac65d747d3d2da7ec2b8221b17b0ca9dc8a1ac1c07ea6a1e60583e2cb00058e77b7b72a298425cd1b941ad4ec65e8afc50303a22c0f99b0509b4c895f40",
+        ),
+    ];
+
+    const XMD_SHA256_LONG: &[(&str, usize, &str)] = &[
+        (
+            "",
+            32,
+            "e8dc0c8b686b7ef2074086fbdd2f30e3f8bfbd3bdf177f73f04b97ce618a3ed3",
+        ),
+        (
+            "abcdef0123456789",
+            128,
+            "d2ecef3635d2397f34a9f86438d772db19ffe9924e28a1caf6f1c8f15603d4028f40891044e5c7e39ebb9b31339979ff33a4249206f67d4a1e7c765410bcd249ad78d407e303675918f20f26ce6d7027ed3774512ef5b00d816e51bfcc96c3539601fa48ef1c07e494bdc37054ba96ecb9dbd666417e3de289d4f424f502a982",
+        ),
+    ];
+
+    const XMD_SHA512: &[(&str, usize, &str)] = &[
+        (
+            "abc",
+            32,
+            "0da749f12fbe5483eb066a5f595055679b976e93abe9be6f0f6318bce7aca8dc",
+        ),
+        (
+            "q128_",
+            128,
+            "b799b045a58c8d2b4334cf54b78260b45eec544f9f2fb5bd12fb603eaee70db7317bf807c406e26373922b7b8920fa29142703dd52bdf280084fb7ef69da78afdf80b3586395b433dc66cde048a258e476a561e9deba7060af40adf30c64249ca7ddea79806ee5beb9a1422949471d267b21bc88e688e4014087a0b592b695ed",
+        ),
+    ];
+
+    const XOF_SHAKE128: &[(&str, usize, &str)] = &[
+        (
+            "",
+            32,
+            "86518c9cd86581486e9485aa74ab35ba150d1c75c88e26b7043e44e2acd735a2",
+        ),
+        (
+            "a512_",
+            32,
+            "df3447cc5f3e9a77da10f819218ddf31342c310778e0e4ef72bbaecee786a4fe",
+        ),
+        (
+            "abc",
+            128,
+            "c952f0c8e529ca8824acc6a4cab0e782fc3648c563ddb00da7399f2ae35654f4860ec671db2356ba7baa55a34a9d7f79197b60ddae6e64768a37d699a78323496db3878c8d64d909d0f8a7de4927dcab0d3dbbc26cb20a49eceb0530b431cdf47bc8c0fa3e0d88f53b318b6739fbed7d7634974f1b5c386d6230c76260d5337a",
+        ),
+    ];
+
+    const XOF_SHAKE128_LONG: &[(&str, usize, &str)] = &[
+        (
+            "abc",
+            32,
+            "690c8d82c7213b4282c6cb41c00e31ea1d3e2005f93ad19bbf6da40f15790c5c",
+        ),
+        (
+            "q128_",
+            128,
+            "19fdd2639f082e31c77717ac9bb032a22ff0958382b2dbb39020cdc78f0da43305414806abf9a561cb2d0067eb2f7bc544482f75623438ed4b4e39dd9e6e2909dd858bd8f1d57cd0fce2d3150d90aa67b4498bdf2df98c0100dd1a173436ba5d0df6be1defb0b2ce55ccd2f4fc05eb7cb2c019c35d5398b85adc676da4238bc7",
+        ),
+    ];
+
+    const XOF_SHAKE256: &[(&str, usize, &str)] = &[
+        (
+            "",
+            32,
+            "2ffc05c48ed32b95d72e807f6eab9f7530dd1c2f013914c8fed38c5ccc15ad76",
+        ),
+        (
+            "abcdef0123456789",
+            128,
+            "e42e4d9538a189316e3154b821c1bafb390f78b2f010ea404e6ac063deb8c0852fcd412e098e231e43427bd2be1330bb47b4039ad57b30ae1fc94e34993b162ff4d695e42d59d9777ea18d3848d9d336c25d2acb93adcad009bcfb9cde12286df267ada283063de0bb1505565b2eb6c90e31c48798ecdc71a71756a9110ff373",
+        ),
+    ];
+}
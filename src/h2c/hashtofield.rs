@@ -0,0 +1,92 @@
+//! This is documentation for the `hashtofield` module.
+//!
+//! The hashtofield module implements the `hash_to_field` function of RFC 9380.
+
+use crate::field::Field;
+use crate::h2c::Expander;
+
+/// HashToField hashes arbitrary strings into elements of a field as
+/// specified in RFC 9380, Section 5.2.
+///
+/// Besides being the first step of hashing to curves, it can be used on its
+/// own, e.g., for deriving challenges in Fiat-Shamir transformations.
+pub trait HashToField: Field {
+    /// Returns `count` field elements derived from `msg`.
+    ///
+    /// The domain separation tag is the one bound to `exp`, and `k` is the
+    /// target security level in bits, which determines the number of bytes
+    /// reduced per element of the base field.
+    fn hash_to_field(
+        &self,
+        exp: &dyn Expander,
+        msg: &[u8],
+        count: usize,
+        k: usize,
+    ) -> Vec<Self::Elt>;
+}
+
+/// Returns `L = ceil((ceil(log2(p)) + k) / 8)`, the number of bytes used to
+/// obtain one element of GF(p) with bias at most 2^-k.
+pub(crate) fn get_length<F: Field>(f: &F, k: usize) -> usize {
+    (f.get_modulus().bits() + k).div_ceil(8)
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::Sha256;
+
+    use super::HashToField;
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::field::Field;
+    use crate::h2c::{Dst, ExpanderXmd};
+    use crate::instances::{GetCurve, BLS12381G1, P256};
+    use crate::ops::{FromFactory, Serialize};
+    use crate::quadraticfield::Fp2;
+
+    // Test vectors from RFC 9380, Appendix J.
+    #[test]
+    fn prime_field() {
+        let f = P256.get().get_field();
+        let exp = ExpanderXmd::<Sha256>::new(
+            &Dst::new(b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_").unwrap(),
+        );
+        for (msg, u0, u1) in [
+            (
+                "",
+                "ad5342c66a6dd0ff080df1da0ea1c04b96e0330dd89406465eeba11582515009",
+                "8c0f1d43204bd6f6ea70ae8013070a1518b43873bcd850aafa0a9e220e2eea5a",
+            ),
+            (
+                "abc",
+                "afe47f2ea2b10465cc26ac403194dfb68b7f5ee865cda61e9f3e07a537220af1",
+                "379a27833b0bfe6f7bdca08e1e83c760bf9a338ab335542704edcd69ce9e46e0",
+            ),
+        ]
+        .iter()
+        {
+            let u = f.hash_to_field(&exp, msg.as_bytes(), 2, 128);
+            assert_eq!(u.len(), 2);
+            assert_eq!(hex::encode(u[0].to_bytes_be()), *u0);
+            assert_eq!(hex::encode(u[1].to_bytes_be()), *u1);
+        }
+    }
+
+    #[test]
+    fn quadratic_field() {
+        let p = BLS12381G1.get().get_field().get_modulus();
+        let f = Fp2::new(p.to_biguint().unwrap());
+        let exp = ExpanderXmd::<Sha256>::new(
+            &Dst::new(b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_").unwrap(),
+        );
+        let u = f.hash_to_field(&exp, b"", 2, 128);
+        let want = f.from(
+            format!(
+                "0x{},0x{}",
+                "03dbc2cce174e91ba93cbb08f26b917f98194a2ea08d1cce75b2b9cc9f21689d80bd79b594a613d0a68eb807dfdc1cf8",
+                "05a2acec64114845711a54199ea339abd125ba38253b70a92c876df10598bd1986b739cad67961eb94f7076511b3b39a",
+            )
+            .as_str(),
+        );
+        assert!(u[0] == want);
+    }
+}
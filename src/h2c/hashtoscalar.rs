@@ -0,0 +1,64 @@
+//! This is documentation for the `hashtoscalar` module.
+//!
+//! The hashtoscalar module derives scalars from arbitrary strings following
+//! the `hash_to_field` function of RFC 9380 with the group order as modulus.
+
+use crate::ellipticcurve::EllipticCurve;
+use crate::h2c::Expander;
+use crate::ops::Endianness;
+
+/// HashToScalar hashes arbitrary strings into integers modulo the order of
+/// the prime-order subgroup of a curve.
+///
+/// The output is uniformly distributed, as it is obtained by reducing
+/// `L = ceil((ceil(log2(r)) + k) / 8)` pseudo-random bytes modulo `r`. This is
+/// what signature nonce derivation, VRFs, and OPRFs require.
+pub trait HashToScalar: EllipticCurve {
+    /// Returns a scalar derived from `msg`.
+    ///
+    /// The domain separation tag is the one bound to `exp`, and `k` is the
+    /// target security level in bits.
+    fn hash_to_scalar(&self, exp: &dyn Expander, msg: &[u8], k: usize) -> Self::Scalar;
+}
+
+impl<E: EllipticCurve> HashToScalar for E {
+    fn hash_to_scalar(&self, exp: &dyn Expander, msg: &[u8], k: usize) -> Self::Scalar {
+        let l = (self.get_order().bits() + k).div_ceil(8);
+        let bytes = exp.expand(msg, l);
+        self.new_scalar_from_bytes(&bytes, Endianness::Big)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::HashToScalar;
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::h2c::{Dst, P256_XMDSHA256_SSWU_RO_};
+    use crate::instances::GetCurve;
+
+    #[test]
+    fn wide_reduction() {
+        let suite = P256_XMDSHA256_SSWU_RO_;
+        let dst = Dst::compose("QUUX", 1, 2, suite.name).unwrap();
+        let e = suite.curve.get();
+        let exp = suite.exp.get(&dst, suite.k);
+        for (msg, want) in [
+            (
+                "",
+                "600e9f806e6766d4e33183869e7a68cdd9ad77f81aeb564afc810c20108afa27",
+            ),
+            (
+                "abc",
+                "fc85b6dac2e8be7343454b82c1bd5dad62cf42331f3fa060ff7407d79e15be6b",
+            ),
+        ]
+        .iter()
+        {
+            let got = e.hash_to_scalar(&*exp, msg.as_bytes(), suite.k);
+            let want = BigInt::parse_bytes(want.as_bytes(), 16).unwrap();
+            assert!(got == e.new_scalar(want), "msg: {:?}", msg);
+        }
+    }
+}
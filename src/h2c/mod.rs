@@ -0,0 +1,31 @@
+//! This is documentation for the `h2c` module.
+//!
+//! The h2c module implements hashing to elliptic curves as specified in RFC 9380.
+
+mod dst;
+mod encoding;
+mod expander;
+mod hashtofield;
+mod hashtoscalar;
+mod suites;
+
+#[cfg(test)]
+mod conformance;
+
+pub use crate::h2c::dst::Dst;
+pub use crate::h2c::encoding::{EncodeToCurve, Encoding, HashToCurve, NU, RO};
+pub use crate::h2c::expander::{Expander, ExpanderXmd, ExpanderXof};
+pub use crate::h2c::hashtofield::HashToField;
+pub use crate::h2c::hashtoscalar::HashToScalar;
+pub use crate::h2c::suites::{
+    ExpID, HashID, MapID, Suite, BLS12381G1_XMDSHA256_SSWU_NU_, BLS12381G1_XMDSHA256_SSWU_RO_,
+    BLS12381G2_XMDSHA256_SSWU_NU_, BLS12381G2_XMDSHA256_SSWU_RO_, CURVE25519_XMDSHA512_ELL2_NU_,
+    CURVE25519_XMDSHA512_ELL2_RO_, CURVE448_XOFSHAKE256_ELL2_NU_, CURVE448_XOFSHAKE256_ELL2_RO_,
+    EDWARDS25519_XMDSHA512_ELL2_NU_, EDWARDS25519_XMDSHA512_ELL2_RO_,
+    EDWARDS448_XOFSHAKE256_ELL2_NU_, EDWARDS448_XOFSHAKE256_ELL2_RO_, P256_XMDSHA256_SSWU_NU_,
+    P256_XMDSHA256_SSWU_RO_, P384_XMDSHA384_SSWU_NU_, P384_XMDSHA384_SSWU_RO_,
+    P521_XMDSHA512_SSWU_NU_, P521_XMDSHA512_SSWU_RO_, SECP256K1_XMDSHA256_SSWU_NU_,
+    SECP256K1_XMDSHA256_SSWU_RO_,
+};
+
+pub(crate) use crate::h2c::hashtofield::get_length;
@@ -0,0 +1,98 @@
+use std::io::Error;
+
+use crate::edwards::{Curve, Ell2, Point};
+use crate::ellipticcurve::{EllipticCurve, MapToCurve, RationalMap};
+use crate::h2c::suites::{generator_dst, ExpID, HashID, MapID, Suite, ERR_MAP_ID, ERR_NO_SUITE};
+use crate::h2c::{Dst, Encoding, HashToCurve, NU, RO};
+use crate::instances::{
+    edwards25519_to_curve25519, edwards448_to_curve448, EdCurveID, GetCurve, EDWARDS25519,
+    EDWARDS448,
+};
+use crate::ops::FromFactory;
+
+impl<V> Suite<EdCurveID, V> {
+    /// Returns an encoding to the curve of the suite using `dst` as the
+    /// domain separation tag.
+    ///
+    /// The Elligator 2 map of edwards25519 and edwards448 goes through the
+    /// rational maps to curve25519 and curve448 prescribed by RFC 9380;
+    /// other curves use the canonical birational map.
+    pub fn get(&self, dst: &Dst) -> Encoding<Curve, V> {
+        let e = self.curve.get();
+        let f = e.get_field();
+        let map_to_curve: Box<dyn MapToCurve<E = Curve>> = match self.map {
+            MapID::ELL2(z) => {
+                let ratmap: Option<Box<dyn RationalMap<E0 = _, E1 = _>>> =
+                    if self.curve == EDWARDS25519 {
+                        Some(Box::new(edwards25519_to_curve25519()))
+                    } else if self.curve == EDWARDS448 {
+                        Some(Box::new(edwards448_to_curve448()))
+                    } else {
+                        None
+                    };
+                Box::new(Ell2::new(e.clone(), f.from(z), ratmap))
+            }
+            _ => panic!("{}", ERR_MAP_ID),
+        };
+        Encoding::new(e, self.exp.get(dst, self.k), map_to_curve, self.k)
+    }
+}
+
+impl Curve {
+    /// Returns an auxiliary generator of the curve derived from `label`.
+    ///
+    /// The point is obtained by hashing `label` with the random-oracle suite
+    /// of the curve, so nobody knows its discrete logarithm with respect to
+    /// the generator of the curve, nor with respect to the generators derived
+    /// from other labels. Curves without a suite are not supported.
+    pub fn derive_generator(&self, label: &[u8]) -> Result<Point, Error> {
+        let suite = [
+            EDWARDS25519_XMDSHA512_ELL2_RO_,
+            EDWARDS448_XOFSHAKE256_ELL2_RO_,
+        ]
+        .iter()
+        .copied()
+        .find(|s| s.curve.get() == *self)
+        .ok_or_else(|| Error::other(ERR_NO_SUITE))?;
+        Ok(suite.get(&generator_dst(suite.name)).hash(label))
+    }
+}
+/// EDWARDS25519_XMDSHA512_ELL2_RO_ is the `edwards25519_XMD:SHA-512_ELL2_RO_` suite.
+pub static EDWARDS25519_XMDSHA512_ELL2_RO_: Suite<EdCurveID, RO> = Suite {
+    name: "edwards25519_XMD:SHA-512_ELL2_RO_",
+    curve: EDWARDS25519,
+    exp: ExpID::XMD(HashID::SHA512),
+    map: MapID::ELL2(2),
+    k: 128,
+    variant: RO,
+};
+
+/// EDWARDS25519_XMDSHA512_ELL2_NU_ is the `edwards25519_XMD:SHA-512_ELL2_NU_` suite.
+pub static EDWARDS25519_XMDSHA512_ELL2_NU_: Suite<EdCurveID, NU> = Suite {
+    name: "edwards25519_XMD:SHA-512_ELL2_NU_",
+    curve: EDWARDS25519,
+    exp: ExpID::XMD(HashID::SHA512),
+    map: MapID::ELL2(2),
+    k: 128,
+    variant: NU,
+};
+
+/// EDWARDS448_XOFSHAKE256_ELL2_RO_ is the `edwards448_XOF:SHAKE256_ELL2_RO_` suite.
+pub static EDWARDS448_XOFSHAKE256_ELL2_RO_: Suite<EdCurveID, RO> = Suite {
+    name: "edwards448_XOF:SHAKE256_ELL2_RO_",
+    curve: EDWARDS448,
+    exp: ExpID::XOF(HashID::SHAKE256),
+    map: MapID::ELL2(-1),
+    k: 224,
+    variant: RO,
+};
+
+/// EDWARDS448_XOFSHAKE256_ELL2_NU_ is the `edwards448_XOF:SHAKE256_ELL2_NU_` suite.
+pub static EDWARDS448_XOFSHAKE256_ELL2_NU_: Suite<EdCurveID, NU> = Suite {
+    name: "edwards448_XOF:SHAKE256_ELL2_NU_",
+    curve: EDWARDS448,
+    exp: ExpID::XOF(HashID::SHAKE256),
+    map: MapID::ELL2(-1),
+    k: 224,
+    variant: NU,
+};
@@ -0,0 +1,56 @@
+use crate::ellipticcurve::{EllipticCurve, MapToCurve};
+use crate::field::Field;
+use crate::h2c::suites::{ExpID, HashID, MapID, Suite, ERR_MAP_ID};
+use crate::h2c::{Dst, Encoding, NU, RO};
+use crate::instances::{G2CurveID, GetCurve, BLS12381G2};
+use crate::ops::FromFactory;
+use crate::pairing::{G2ClearCofactor, G2Curve, G2SSWU};
+
+impl<V> Suite<G2CurveID, V> {
+    /// Returns an encoding to the curve of the suite using `dst` as the
+    /// domain separation tag.
+    ///
+    /// The constant `z` of `MapID::SSWU(z)` is the real part of the constant
+    /// `Z = z - i` of the map, since RFC 9380 uses `Z = -(2+i)`.
+    pub fn get(&self, dst: &Dst) -> Encoding<G2Curve, V> {
+        let e = self.curve.get();
+        let f = e.get_field();
+        let map_to_curve: Box<dyn MapToCurve<E = G2Curve>> = match self.map {
+            MapID::SSWU(z) => {
+                let z = f.new_elt(f.get_base().from(z), -f.get_base().one());
+                Box::new(G2SSWU::new(e.clone(), z))
+            }
+            _ => panic!("{}", ERR_MAP_ID),
+        };
+        let clear_cofactor = Box::new(G2ClearCofactor::new());
+        Encoding::with_cofactor(
+            e,
+            self.exp.get(dst, self.k),
+            map_to_curve,
+            clear_cofactor,
+            self.k,
+        )
+    }
+}
+
+/// BLS12381G2_XMDSHA256_SSWU_RO_ is the `BLS12381G2_XMD:SHA-256_SSWU_RO_`
+/// suite.
+pub static BLS12381G2_XMDSHA256_SSWU_RO_: Suite<G2CurveID, RO> = Suite {
+    name: "BLS12381G2_XMD:SHA-256_SSWU_RO_",
+    curve: BLS12381G2,
+    exp: ExpID::XMD(HashID::SHA256),
+    map: MapID::SSWU(-2),
+    k: 128,
+    variant: RO,
+};
+
+/// BLS12381G2_XMDSHA256_SSWU_NU_ is the `BLS12381G2_XMD:SHA-256_SSWU_NU_`
+/// suite.
+pub static BLS12381G2_XMDSHA256_SSWU_NU_: Suite<G2CurveID, NU> = Suite {
+    name: "BLS12381G2_XMD:SHA-256_SSWU_NU_",
+    curve: BLS12381G2,
+    exp: ExpID::XMD(HashID::SHA256),
+    map: MapID::SSWU(-2),
+    k: 128,
+    variant: NU,
+};
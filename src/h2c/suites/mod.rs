@@ -0,0 +1,406 @@
+use sha2::{Sha256, Sha384, Sha512};
+use sha3::{Shake128, Shake256};
+
+use crate::h2c::{Dst, Expander, ExpanderXmd, ExpanderXof};
+
+mod edw;
+mod g2;
+mod mont;
+mod weier;
+
+pub use crate::h2c::suites::edw::{
+    EDWARDS25519_XMDSHA512_ELL2_NU_, EDWARDS25519_XMDSHA512_ELL2_RO_,
+    EDWARDS448_XOFSHAKE256_ELL2_NU_, EDWARDS448_XOFSHAKE256_ELL2_RO_,
+};
+pub use crate::h2c::suites::g2::{BLS12381G2_XMDSHA256_SSWU_NU_, BLS12381G2_XMDSHA256_SSWU_RO_};
+pub use crate::h2c::suites::mont::{
+    CURVE25519_XMDSHA512_ELL2_NU_, CURVE25519_XMDSHA512_ELL2_RO_, CURVE448_XOFSHAKE256_ELL2_NU_,
+    CURVE448_XOFSHAKE256_ELL2_RO_,
+};
+pub use crate::h2c::suites::weier::{
+    BLS12381G1_XMDSHA256_SSWU_NU_, BLS12381G1_XMDSHA256_SSWU_RO_, P256_XMDSHA256_SSWU_NU_,
+    P256_XMDSHA256_SSWU_RO_, P384_XMDSHA384_SSWU_NU_, P384_XMDSHA384_SSWU_RO_,
+    P521_XMDSHA512_SSWU_NU_, P521_XMDSHA512_SSWU_RO_, SECP256K1_XMDSHA256_SSWU_NU_,
+    SECP256K1_XMDSHA256_SSWU_RO_,
+};
+
+/// Identifies the hash function used by an expander.
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum HashID {
+    SHA256,
+    SHA384,
+    SHA512,
+    SHAKE128,
+    SHAKE256,
+}
+
+/// Identifies the `expand_message` variant of a suite.
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum ExpID {
+    XMD(HashID),
+    XOF(HashID),
+}
+
+impl ExpID {
+    pub(crate) fn get(&self, dst: &Dst, k: usize) -> Box<dyn Expander> {
+        match *self {
+            ExpID::XMD(HashID::SHA256) => Box::new(ExpanderXmd::<Sha256>::new(dst)),
+            ExpID::XMD(HashID::SHA384) => Box::new(ExpanderXmd::<Sha384>::new(dst)),
+            ExpID::XMD(HashID::SHA512) => Box::new(ExpanderXmd::<Sha512>::new(dst)),
+            ExpID::XOF(HashID::SHAKE128) => Box::new(ExpanderXof::<Shake128>::new(dst, k)),
+            ExpID::XOF(HashID::SHAKE256) => Box::new(ExpanderXof::<Shake256>::new(dst, k)),
+            _ => panic!("{}", ERR_EXP_ID),
+        }
+    }
+}
+
+/// Identifies the map to curve of a suite together with its constant `Z`.
+/// Icart's map has no such constant.
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum MapID {
+    SSWU(i32),
+    SVDW(i32),
+    ELL2(i32),
+    ICART,
+}
+
+/// Suite describes a hash to curve suite as specified in RFC 9380, Section 8.
+/// Instantiating a suite with a domain separation tag returns an encoding,
+/// whose variant is either random oracle (`RO`) or nonuniform (`NU`).
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub struct Suite<T: 'static, V: 'static> {
+    pub name: &'static str,
+    pub curve: T,
+    pub exp: ExpID,
+    pub map: MapID,
+    pub k: usize,
+    pub variant: V,
+}
+
+impl<T, V> std::fmt::Display for Suite<T, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Returns the domain separation tag used for deriving auxiliary generators
+/// with the suite named `name`.
+fn generator_dst(name: &str) -> Dst {
+    Dst::compose(GENERATOR_APP, 1, 1, name).unwrap()
+}
+
+const GENERATOR_APP: &str = "REDOX-ECC-GENERATOR";
+const ERR_NO_SUITE: &str = "no hash to curve suite for this curve";
+const ERR_EXP_ID: &str = "expander not supported for this hash function";
+pub(crate) const ERR_MAP_ID: &str = "map not supported for this curve model";
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use crate::ellipticcurve::{EcPoint, EllipticCurve};
+    use crate::h2c::{Dst, EncodeToCurve, HashToCurve, RO};
+    use crate::instances::{GetCurve, BLS12381G1, CURVE448, EDWARDS25519, P256, SECP256K1};
+    use crate::ops::FromFactory;
+
+    use super::{
+        ExpID, HashID, MapID, Suite, BLS12381G1_XMDSHA256_SSWU_RO_, BLS12381G2_XMDSHA256_SSWU_NU_,
+        BLS12381G2_XMDSHA256_SSWU_RO_, CURVE25519_XMDSHA512_ELL2_NU_,
+        CURVE25519_XMDSHA512_ELL2_RO_, CURVE448_XOFSHAKE256_ELL2_RO_,
+        EDWARDS25519_XMDSHA512_ELL2_NU_, EDWARDS25519_XMDSHA512_ELL2_RO_,
+        EDWARDS448_XOFSHAKE256_ELL2_RO_, P256_XMDSHA256_SSWU_RO_, P384_XMDSHA384_SSWU_RO_,
+        P521_XMDSHA512_SSWU_RO_, SECP256K1_XMDSHA256_SSWU_NU_, SECP256K1_XMDSHA256_SSWU_RO_,
+    };
+
+    // Test vectors taken from RFC 9380, Appendix J.
+    struct Vector {
+        msg: &'static [u8],
+        x: &'static str,
+        y: &'static str,
+    }
+
+    fn dst(name: &str) -> Dst {
+        Dst::compose("QUUX", 1, 2, name).unwrap()
+    }
+
+    #[test]
+    fn montgomery_suites() {
+        let suites = [
+            (
+                CURVE25519_XMDSHA512_ELL2_RO_,
+                Vector {
+                    msg: b"",
+                    x: "0x2de3780abb67e861289f5749d16d3e217ffa722192d16bbd9d1bfb9d112b98c0",
+                    y: "0x3b5dc2a498941a1033d176567d457845637554a2fe7a3507d21abd1c1bd6e878",
+                },
+            ),
+            (
+                CURVE448_XOFSHAKE256_ELL2_RO_,
+                Vector {
+                    msg: b"",
+                    x: "0x5ea5ff623d27c75e73717514134e73e419f831a875ca9e82915fdfc7069d0a9f8b532cfb32b1d8dd04ddeedbe3fa1d0d681c01e825d6a9ea",
+                    y: "0xafadd8de789f8f8e3516efbbe313a7eba364c939ecba00dabf4ced5c563b18e70a284c17d8f46b564c4e6ce11784a3825d941116622128c1",
+                },
+            ),
+        ];
+        for (suite, v) in suites.iter() {
+            let e = suite.curve.get();
+            let f = e.get_field();
+            let got = suite.get(&dst(suite.name)).hash(v.msg);
+            let want = e.new_point(f.from(v.x), f.from(v.y));
+            assert!(got == want, "hash to curve for {}", suite);
+            let r = e.new_scalar(e.get_order().into());
+            assert!(
+                (got * r).is_zero(),
+                "point must be in the subgroup for {}",
+                suite
+            );
+        }
+    }
+
+    #[test]
+    fn edwards_suites() {
+        let suites = [
+            (
+                EDWARDS25519_XMDSHA512_ELL2_RO_,
+                Vector {
+                    msg: b"",
+                    x: "0x3c3da6925a3c3c268448dcabb47ccde5439559d9599646a8260e47b1e4822fc6",
+                    y: "0x09a6c8561a0b22bef63124c588ce4c62ea83a3c899763af26d795302e115dc21",
+                },
+            ),
+            (
+                EDWARDS448_XOFSHAKE256_ELL2_RO_,
+                Vector {
+                    msg: b"",
+                    x: "0x73036d4a88949c032f01507005c133884e2f0d81f9a950826245dda9e844fc78186c39daaa7147ead3e462cff60e9c6340b58134480b4d17",
+                    y: "0x94c1d61b43728e5d784ef4fcb1f38e1075f3aef5e99866911de5a234f1aafdc26b554344742e6ba0420b71b298671bbeb2b7736618634610",
+                },
+            ),
+        ];
+        for (suite, v) in suites.iter() {
+            let e = suite.curve.get();
+            let f = e.get_field();
+            let got = suite.get(&dst(suite.name)).hash(v.msg);
+            let want = e.new_point(f.from(v.x), f.from(v.y));
+            assert!(got == want, "hash to curve for {}", suite);
+            let r = e.new_scalar(e.get_order().into());
+            assert!(
+                (got * r).is_zero(),
+                "point must be in the subgroup for {}",
+                suite
+            );
+        }
+    }
+
+    #[test]
+    fn weierstrass_suites() {
+        let suites = [
+            (
+                P256_XMDSHA256_SSWU_RO_,
+                Vector {
+                    msg: b"",
+                    x: "0x2c15230b26dbc6fc9a37051158c95b79656e17a1a920b11394ca91c44247d3e4",
+                    y: "0x8a7a74985cc5c776cdfe4b1f19884970453912e9d31528c060be9ab5c43e8415",
+                },
+            ),
+            (
+                P256_XMDSHA256_SSWU_RO_,
+                Vector {
+                    msg: b"abc",
+                    x: "0x0bb8b87485551aa43ed54f009230450b492fead5f1cc91658775dac4a3388a0f",
+                    y: "0x5c41b3d0731a27a7b14bc0bf0ccded2d8751f83493404c84a88e71ffd424212e",
+                },
+            ),
+            (
+                P384_XMDSHA384_SSWU_RO_,
+                Vector {
+                    msg: b"",
+                    x: "0xeb9fe1b4f4e14e7140803c1d99d0a93cd823d2b024040f9c067a8eca1f5a2eeac9ad604973527a356f3fa3aeff0e4d83",
+                    y: "0x0c21708cff382b7f4643c07b105c2eaec2cead93a917d825601e63c8f21f6abd9abc22c93c2bed6f235954b25048bb1a",
+                },
+            ),
+            (
+                SECP256K1_XMDSHA256_SSWU_RO_,
+                Vector {
+                    msg: b"",
+                    x: "0xc1cae290e291aee617ebaef1be6d73861479c48b841eaba9b7b5852ddfeb1346",
+                    y: "0x64fa678e07ae116126f08b022a94af6de15985c996c3a91b64c406a960e51067",
+                },
+            ),
+            (
+                SECP256K1_XMDSHA256_SSWU_RO_,
+                Vector {
+                    msg: b"abc",
+                    x: "0x3377e01eab42db296b512293120c6cee72b6ecf9f9205760bd9ff11fb3cb2c4b",
+                    y: "0x7f95890f33efebd1044d382a01b1bee0900fb6116f94688d487c6c7b9c8371f6",
+                },
+            ),
+            (
+                SECP256K1_XMDSHA256_SSWU_RO_,
+                Vector {
+                    msg: b"abcdef0123456789",
+                    x: "0xbac54083f293f1fe08e4a70137260aa90783a5cb84d3f35848b324d0674b0e3a",
+                    y: "0x4436476085d4c3c4508b60fcf4389c40176adce756b398bdee27bca19758d828",
+                },
+            ),
+            (
+                BLS12381G1_XMDSHA256_SSWU_RO_,
+                Vector {
+                    msg: b"",
+                    x: "0x052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1",
+                    y: "0x08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265",
+                },
+            ),
+            (
+                BLS12381G1_XMDSHA256_SSWU_RO_,
+                Vector {
+                    msg: b"abc",
+                    x: "0x03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903",
+                    y: "0x0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d",
+                },
+            ),
+        ];
+        for (suite, v) in suites.iter() {
+            let e = suite.curve.get();
+            let f = e.get_field();
+            let got = suite.get(&dst(suite.name)).hash(v.msg);
+            let want = e.new_point(f.from(v.x), f.from(v.y));
+            assert!(got == want, "hash to curve for {}", suite);
+        }
+        let suite = P521_XMDSHA512_SSWU_RO_;
+        let e = suite.curve.get();
+        let p = suite.get(&dst(suite.name)).hash(b"");
+        assert!(e.is_on_curve(&p), "point must be on curve for {}", suite);
+        assert!(!p.is_zero(), "point must not be the identity for {}", suite);
+    }
+
+    #[test]
+    fn random_points() {
+        let suite = EDWARDS25519_XMDSHA512_ELL2_RO_;
+        let e = suite.curve.get();
+        let h = suite.get(&dst(suite.name));
+        let p = h.random_point(&mut OsRng);
+        let r = e.new_scalar(e.get_order().into());
+        assert!(e.is_on_curve(&p) && !p.is_zero(), "{}", suite);
+        assert!((p.clone() * r).is_zero(), "point must be in the subgroup");
+        assert!(h.random_point(&mut OsRng) != p);
+    }
+
+    #[test]
+    fn nonuniform_suites() {
+        let v = Vector {
+            msg: b"",
+            x: "0x1bb913f0c9daefa0b3375378ffa534bda5526c97391952a7789eb976edfe4d08",
+            y: "0x4548368f4f983243e747b62a600840ae7c1dab5c723991f85d3a9768479f3ec4",
+        };
+        let suite = CURVE25519_XMDSHA512_ELL2_NU_;
+        let e = suite.curve.get();
+        let f = e.get_field();
+        let got = suite.get(&dst(suite.name)).encode(v.msg);
+        let want = e.new_point(f.from(v.x), f.from(v.y));
+        assert!(got == want, "encode to curve for {}", suite);
+
+        let v = Vector {
+            msg: b"",
+            x: "0x1ff2b70ecf862799e11b7ae744e3489aa058ce805dd323a936375a84695e76da",
+            y: "0x222e314d04a4d5725e9f2aff9fb2a6b69ef375a1214eb19021ceab2d687f0f9b",
+        };
+        let suite = EDWARDS25519_XMDSHA512_ELL2_NU_;
+        let e = suite.curve.get();
+        let f = e.get_field();
+        let got = suite.get(&dst(suite.name)).encode(v.msg);
+        let want = e.new_point(f.from(v.x), f.from(v.y));
+        assert!(got == want, "encode to curve for {}", suite);
+
+        let v = Vector {
+            msg: b"",
+            x: "0xa4792346075feae77ac3b30026f99c1441b4ecf666ded19b7522cf65c4c55c5b",
+            y: "0x62c59e2a6aeed1b23be5883e833912b08ba06be7f57c0e9cdc663f31639ff3a7",
+        };
+        let suite = SECP256K1_XMDSHA256_SSWU_NU_;
+        let e = suite.curve.get();
+        let f = e.get_field();
+        let got = suite.get(&dst(suite.name)).encode(v.msg);
+        let want = e.new_point(f.from(v.x), f.from(v.y));
+        assert!(got == want, "encode to curve for {}", suite);
+    }
+
+    #[test]
+    fn g2_suites() {
+        let v = [
+            Vector {
+                msg: b"",
+                x: "0x0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a,0x05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+                y: "0x0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92,0x12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+            },
+            Vector {
+                msg: b"abc",
+                x: "0x02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6,0x139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8",
+                y: "0x1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48,0x00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16",
+            },
+        ];
+        let suite = BLS12381G2_XMDSHA256_SSWU_RO_;
+        let e = suite.curve.get();
+        let f = e.get_field();
+        let h2c = suite.get(&dst(suite.name));
+        for v in v.iter() {
+            let got = h2c.hash(v.msg);
+            let want = e.new_point(f.from(v.x), f.from(v.y));
+            assert!(got == want, "hash to curve for {}", suite);
+            assert!(e.is_in_subgroup(&got), "point must be in G2 for {}", suite);
+        }
+
+        let v = Vector {
+            msg: b"",
+            x: "0x00e7f4568a82b4b7dc1f14c6aaa055edf51502319c723c4dc2688c7fe5944c213f510328082396515734b6612c4e7bb7,0x126b855e9e69b1f691f816e48ac6977664d24d99f8724868a184186469ddfd4617367e94527d4b74fc86413483afb35b",
+            y: "0x0caead0fd7b6176c01436833c79d305c78be307da5f6af6c133c47311def6ff1e0babf57a0fb5539fce7ee12407b0a42,0x1498aadcf7ae2b345243e281ae076df6de84455d766ab6fcdaad71fab60abb2e8b980a440043cd305db09d283c895e3d",
+        };
+        let suite = BLS12381G2_XMDSHA256_SSWU_NU_;
+        let got = suite.get(&dst(suite.name)).encode(v.msg);
+        let want = e.new_point(f.from(v.x), f.from(v.y));
+        assert!(got == want, "encode to curve for {}", suite);
+    }
+
+    #[test]
+    fn custom_svdw_suite() {
+        let suite = Suite {
+            name: "secp256k1_XMD:SHA-256_SVDW_RO_",
+            curve: SECP256K1,
+            exp: ExpID::XMD(HashID::SHA256),
+            map: MapID::SVDW(1),
+            k: 128,
+            variant: RO,
+        };
+        let e = suite.curve.get();
+        let h2c = suite.get(&dst(suite.name));
+        for msg in [&b""[..], b"abc", b"abcdef0123456789"].iter() {
+            let p = h2c.hash(msg);
+            assert!(e.is_on_curve(&p), "point must be on curve for {}", suite);
+            assert!(!p.is_zero(), "point must not be the identity for {}", suite);
+        }
+    }
+
+    #[test]
+    fn derive_generator() {
+        macro_rules! check {
+            ($id:expr) => {
+                let e = $id.get();
+                let g = e.get_generator();
+                let h0 = e.derive_generator(b"H").unwrap();
+                let h1 = e.derive_generator(b"H").unwrap();
+                let h2 = e.derive_generator(b"J").unwrap();
+                assert!(h0 == h1, "derivation must be deterministic for {}", $id);
+                assert!(h0 != h2 && h0 != g, "generators must differ for {}", $id);
+                assert!(!h0.is_zero(), "generator is the identity for {}", $id);
+                let r = e.new_scalar(e.get_order().into());
+                assert!((h0 * r).is_zero(), "generator order for {}", $id);
+            };
+        }
+        check!(P256);
+        check!(SECP256K1);
+        check!(CURVE448);
+        check!(EDWARDS25519);
+        assert!(BLS12381G1.get().derive_generator(b"H").is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::Error;
+
+use crate::ellipticcurve::{EllipticCurve, MapToCurve};
+use crate::h2c::suites::{generator_dst, ExpID, HashID, MapID, Suite, ERR_MAP_ID, ERR_NO_SUITE};
+use crate::h2c::{Dst, Encoding, HashToCurve, NU, RO};
+use crate::instances::{GetCurve, MtCurveID, CURVE25519, CURVE448};
+use crate::montgomery::{Curve, Ell2, Point};
+use crate::ops::FromFactory;
+
+impl<V> Suite<MtCurveID, V> {
+    /// Returns an encoding to the curve of the suite using `dst` as the
+    /// domain separation tag.
+    pub fn get(&self, dst: &Dst) -> Encoding<Curve, V> {
+        let e = self.curve.get();
+        let f = e.get_field();
+        let map_to_curve: Box<dyn MapToCurve<E = Curve>> = match self.map {
+            MapID::ELL2(z) => Box::new(Ell2::new(e.clone(), f.from(z))),
+            _ => panic!("{}", ERR_MAP_ID),
+        };
+        Encoding::new(e, self.exp.get(dst, self.k), map_to_curve, self.k)
+    }
+}
+
+impl Curve {
+    /// Returns an auxiliary generator of the curve derived from `label`.
+    ///
+    /// The point is obtained by hashing `label` with the random-oracle suite
+    /// of the curve, so nobody knows its discrete logarithm with respect to
+    /// the generator of the curve, nor with respect to the generators derived
+    /// from other labels. Curves without a suite are not supported.
+    pub fn derive_generator(&self, label: &[u8]) -> Result<Point, Error> {
+        let suite = [CURVE25519_XMDSHA512_ELL2_RO_, CURVE448_XOFSHAKE256_ELL2_RO_]
+            .iter()
+            .copied()
+            .find(|s| s.curve.get() == *self)
+            .ok_or_else(|| Error::other(ERR_NO_SUITE))?;
+        Ok(suite.get(&generator_dst(suite.name)).hash(label))
+    }
+}
+/// CURVE25519_XMDSHA512_ELL2_RO_ is the `curve25519_XMD:SHA-512_ELL2_RO_` suite.
+pub static CURVE25519_XMDSHA512_ELL2_RO_: Suite<MtCurveID, RO> = Suite {
+    name: "curve25519_XMD:SHA-512_ELL2_RO_",
+    curve: CURVE25519,
+    exp: ExpID::XMD(HashID::SHA512),
+    map: MapID::ELL2(2),
+    k: 128,
+    variant: RO,
+};
+
+/// CURVE25519_XMDSHA512_ELL2_NU_ is the `curve25519_XMD:SHA-512_ELL2_NU_` suite.
+pub static CURVE25519_XMDSHA512_ELL2_NU_: Suite<MtCurveID, NU> = Suite {
+    name: "curve25519_XMD:SHA-512_ELL2_NU_",
+    curve: CURVE25519,
+    exp: ExpID::XMD(HashID::SHA512),
+    map: MapID::ELL2(2),
+    k: 128,
+    variant: NU,
+};
+
+/// CURVE448_XOFSHAKE256_ELL2_RO_ is the `curve448_XOF:SHAKE256_ELL2_RO_` suite.
+pub static CURVE448_XOFSHAKE256_ELL2_RO_: Suite<MtCurveID, RO> = Suite {
+    name: "curve448_XOF:SHAKE256_ELL2_RO_",
+    curve: CURVE448,
+    exp: ExpID::XOF(HashID::SHAKE256),
+    map: MapID::ELL2(-1),
+    k: 224,
+    variant: RO,
+};
+
+/// CURVE448_XOFSHAKE256_ELL2_NU_ is the `curve448_XOF:SHAKE256_ELL2_NU_` suite.
+pub static CURVE448_XOFSHAKE256_ELL2_NU_: Suite<MtCurveID, NU> = Suite {
+    name: "curve448_XOF:SHAKE256_ELL2_NU_",
+    curve: CURVE448,
+    exp: ExpID::XOF(HashID::SHAKE256),
+    map: MapID::ELL2(-1),
+    k: 224,
+    variant: NU,
+};
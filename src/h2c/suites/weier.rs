@@ -0,0 +1,165 @@
+use std::io::Error;
+
+use crate::ellipticcurve::{EllipticCurve, Isogeny, MapToCurve};
+use crate::h2c::suites::{generator_dst, ExpID, HashID, MapID, Suite, ERR_MAP_ID, ERR_NO_SUITE};
+use crate::h2c::{Dst, Encoding, HashToCurve, NU, RO};
+use crate::instances::{
+    get_isogeny_bls12381g1, get_isogeny_secp256k1, GetCurve, WeCurveID, BLS12381G1, P256, P384,
+    P521, SECP256K1,
+};
+use crate::ops::FromFactory;
+use crate::weierstrass::{Curve, Icart, Point, SSWU, SSWUAB0, SVDW};
+
+impl<V> Suite<WeCurveID, V> {
+    /// Returns an encoding to the curve of the suite using `dst` as the
+    /// domain separation tag.
+    ///
+    /// For curves with `A*B=0`, the SSWU map is evaluated on an isogenous
+    /// curve and the resulting points are pushed through the isogeny.
+    pub fn get(&self, dst: &Dst) -> Encoding<Curve, V> {
+        let e = self.curve.get();
+        let f = e.get_field();
+        let map_to_curve: Box<dyn MapToCurve<E = Curve>> = match self.map {
+            MapID::SSWU(z) => match self.get_isogeny() {
+                Some(iso) => Box::new(SSWUAB0::new(e.clone(), f.from(z), iso)),
+                None => Box::new(SSWU::new(e.clone(), f.from(z))),
+            },
+            MapID::SVDW(z) => Box::new(SVDW::new(e.clone(), f.from(z))),
+            MapID::ICART => Box::new(Icart::new(e.clone())),
+            _ => panic!("{}", ERR_MAP_ID),
+        };
+        Encoding::new(e, self.exp.get(dst, self.k), map_to_curve, self.k)
+    }
+    fn get_isogeny(&self) -> Option<Box<dyn Isogeny<E0 = Curve, E1 = Curve>>> {
+        if self.curve == SECP256K1 {
+            Some(Box::new(get_isogeny_secp256k1()))
+        } else if self.curve == BLS12381G1 {
+            Some(Box::new(get_isogeny_bls12381g1()))
+        } else {
+            None
+        }
+    }
+}
+
+impl Curve {
+    /// Returns an auxiliary generator of the curve derived from `label`.
+    ///
+    /// The point is obtained by hashing `label` with the random-oracle suite
+    /// of the curve, so nobody knows its discrete logarithm with respect to
+    /// the generator of the curve, nor with respect to the generators derived
+    /// from other labels. Curves without a suite are not supported.
+    pub fn derive_generator(&self, label: &[u8]) -> Result<Point, Error> {
+        let suite = [
+            P256_XMDSHA256_SSWU_RO_,
+            P384_XMDSHA384_SSWU_RO_,
+            P521_XMDSHA512_SSWU_RO_,
+            SECP256K1_XMDSHA256_SSWU_RO_,
+        ]
+        .iter()
+        .copied()
+        .find(|s| s.curve.get() == *self)
+        .ok_or_else(|| Error::other(ERR_NO_SUITE))?;
+        Ok(suite.get(&generator_dst(suite.name)).hash(label))
+    }
+}
+/// P256_XMDSHA256_SSWU_RO_ is the `P256_XMD:SHA-256_SSWU_RO_` suite.
+pub static P256_XMDSHA256_SSWU_RO_: Suite<WeCurveID, RO> = Suite {
+    name: "P256_XMD:SHA-256_SSWU_RO_",
+    curve: P256,
+    exp: ExpID::XMD(HashID::SHA256),
+    map: MapID::SSWU(-10),
+    k: 128,
+    variant: RO,
+};
+
+/// P256_XMDSHA256_SSWU_NU_ is the `P256_XMD:SHA-256_SSWU_NU_` suite.
+pub static P256_XMDSHA256_SSWU_NU_: Suite<WeCurveID, NU> = Suite {
+    name: "P256_XMD:SHA-256_SSWU_NU_",
+    curve: P256,
+    exp: ExpID::XMD(HashID::SHA256),
+    map: MapID::SSWU(-10),
+    k: 128,
+    variant: NU,
+};
+
+/// P384_XMDSHA384_SSWU_RO_ is the `P384_XMD:SHA-384_SSWU_RO_` suite.
+pub static P384_XMDSHA384_SSWU_RO_: Suite<WeCurveID, RO> = Suite {
+    name: "P384_XMD:SHA-384_SSWU_RO_",
+    curve: P384,
+    exp: ExpID::XMD(HashID::SHA384),
+    map: MapID::SSWU(-12),
+    k: 192,
+    variant: RO,
+};
+
+/// P384_XMDSHA384_SSWU_NU_ is the `P384_XMD:SHA-384_SSWU_NU_` suite.
+pub static P384_XMDSHA384_SSWU_NU_: Suite<WeCurveID, NU> = Suite {
+    name: "P384_XMD:SHA-384_SSWU_NU_",
+    curve: P384,
+    exp: ExpID::XMD(HashID::SHA384),
+    map: MapID::SSWU(-12),
+    k: 192,
+    variant: NU,
+};
+
+/// P521_XMDSHA512_SSWU_RO_ is the `P521_XMD:SHA-512_SSWU_RO_` suite.
+pub static P521_XMDSHA512_SSWU_RO_: Suite<WeCurveID, RO> = Suite {
+    name: "P521_XMD:SHA-512_SSWU_RO_",
+    curve: P521,
+    exp: ExpID::XMD(HashID::SHA512),
+    map: MapID::SSWU(-4),
+    k: 256,
+    variant: RO,
+};
+
+/// P521_XMDSHA512_SSWU_NU_ is the `P521_XMD:SHA-512_SSWU_NU_` suite.
+pub static P521_XMDSHA512_SSWU_NU_: Suite<WeCurveID, NU> = Suite {
+    name: "P521_XMD:SHA-512_SSWU_NU_",
+    curve: P521,
+    exp: ExpID::XMD(HashID::SHA512),
+    map: MapID::SSWU(-4),
+    k: 256,
+    variant: NU,
+};
+
+/// SECP256K1_XMDSHA256_SSWU_RO_ is the `secp256k1_XMD:SHA-256_SSWU_RO_` suite.
+pub static SECP256K1_XMDSHA256_SSWU_RO_: Suite<WeCurveID, RO> = Suite {
+    name: "secp256k1_XMD:SHA-256_SSWU_RO_",
+    curve: SECP256K1,
+    exp: ExpID::XMD(HashID::SHA256),
+    map: MapID::SSWU(-11),
+    k: 128,
+    variant: RO,
+};
+
+/// SECP256K1_XMDSHA256_SSWU_NU_ is the `secp256k1_XMD:SHA-256_SSWU_NU_` suite.
+pub static SECP256K1_XMDSHA256_SSWU_NU_: Suite<WeCurveID, NU> = Suite {
+    name: "secp256k1_XMD:SHA-256_SSWU_NU_",
+    curve: SECP256K1,
+    exp: ExpID::XMD(HashID::SHA256),
+    map: MapID::SSWU(-11),
+    k: 128,
+    variant: NU,
+};
+
+/// BLS12381G1_XMDSHA256_SSWU_RO_ is the `BLS12381G1_XMD:SHA-256_SSWU_RO_`
+/// suite.
+pub static BLS12381G1_XMDSHA256_SSWU_RO_: Suite<WeCurveID, RO> = Suite {
+    name: "BLS12381G1_XMD:SHA-256_SSWU_RO_",
+    curve: BLS12381G1,
+    exp: ExpID::XMD(HashID::SHA256),
+    map: MapID::SSWU(11),
+    k: 128,
+    variant: RO,
+};
+
+/// BLS12381G1_XMDSHA256_SSWU_NU_ is the `BLS12381G1_XMD:SHA-256_SSWU_NU_`
+/// suite.
+pub static BLS12381G1_XMDSHA256_SSWU_NU_: Suite<WeCurveID, NU> = Suite {
+    name: "BLS12381G1_XMD:SHA-256_SSWU_NU_",
+    curve: BLS12381G1,
+    exp: ExpID::XMD(HashID::SHA256),
+    map: MapID::SSWU(11),
+    k: 128,
+    variant: NU,
+};
@@ -1,8 +1,12 @@
+use std::io::Error;
+use std::str::FromStr;
+
 use crate::edwards::{Curve, Params};
-use crate::instances::GetCurve;
+use crate::instances::{self_test, GetCurve, ERR_UNKNOWN_CURVE};
+use crate::int;
 
 #[derive(PartialEq, Eq, Copy, Clone)]
-pub struct EdCurveID(&'static Params);
+pub struct EdCurveID(&'static Params, &'static str);
 
 impl GetCurve for EdCurveID {
     type E = Curve;
@@ -12,35 +16,75 @@ impl GetCurve for EdCurveID {
     }
 }
 
+impl EdCurveID {
+    /// Runs the self-test of the curve, followed by a known-answer test of
+    /// scalar multiplication. It fails if the parameters or the arithmetic
+    /// of the curve are broken.
+    pub fn self_test(&self) -> Result<(), Error> {
+        self_test(&self.get(), self.1)
+    }
+}
+
 impl std::fmt::Display for EdCurveID {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}", self.0.name)
     }
 }
 
+/// Finds a curve by its name, ignoring case.
+impl FromStr for EdCurveID {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        [EDWARDS25519, EDWARDS448, JUBJUB]
+            .iter()
+            .find(|id| id.0.name.eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| Error::other(ERR_UNKNOWN_CURVE))
+    }
+}
+
 /// EDWARDS25519 is the edwards25519 elliptic curve as specified in RFC-7748.
-pub static EDWARDS25519: EdCurveID = EdCurveID(&EDWARDS25519_PARAMS);
+pub static EDWARDS25519: EdCurveID = EdCurveID(EDWARDS25519_PARAMS, EDWARDS25519_KAT);
 /// EDWARDS448 is the edwards448 elliptic curve as specified in RFC-7748.
-pub static EDWARDS448: EdCurveID = EdCurveID(&EDWARDS448_PARAMS);
+pub static EDWARDS448: EdCurveID = EdCurveID(EDWARDS448_PARAMS, EDWARDS448_KAT);
+/// JUBJUB is the Jubjub elliptic curve of Zcash, which is defined over the
+/// scalar field of BLS12-381.
+pub static JUBJUB: EdCurveID = EdCurveID(JUBJUB_PARAMS, JUBJUB_KAT);
 
 static EDWARDS25519_PARAMS: &Params = &Params {
     name: "edwards25519",
-    p: "57896044618658097711785492504343953926634992332820282019728792003956564819949",
-    a: "-1",
-    d: "37095705934669439343138083508754565189542113879843219016388785533085940283555",
-    r: "7237005577332262213973186563042994240857116359379907606001950938285454250989",
-    h: "8",
-    gx: "15112221349535400772501151409588531511454012693041857206046113283949847762202",
-    gy: "46316835694926478169428394003475163141307993866256225615783033603165251855960",
+    p: int!("57896044618658097711785492504343953926634992332820282019728792003956564819949"),
+    a: int!("-1"),
+    d: int!("37095705934669439343138083508754565189542113879843219016388785533085940283555"),
+    r: int!("7237005577332262213973186563042994240857116359379907606001950938285454250989"),
+    h: int!("8"),
+    gx: int!("15112221349535400772501151409588531511454012693041857206046113283949847762202"),
+    gy: int!("46316835694926478169428394003475163141307993866256225615783033603165251855960"),
 };
 
 static EDWARDS448_PARAMS: &Params = &Params {
     name: "edwards448",
-    p: "726838724295606890549323807888004534353641360687318060281490199180612328166730772686396383698676545930088884461843637361053498018365439",
-    a: "1",
-    d: "-39081",
-    r: "181709681073901722637330951972001133588410340171829515070372549795146003961539585716195755291692375963310293709091662304773755859649779",
-    h: "4",
-    gx: "224580040295924300187604334099896036246789641632564134246125461686950415467406032909029192869357953282578032075146446173674602635247710",
-    gy: "298819210078481492676017930443930673437544040154080242095928241372331506189835876003536878655418784733982303233503462500531545062832660",
+    p: int!("726838724295606890549323807888004534353641360687318060281490199180612328166730772686396383698676545930088884461843637361053498018365439"),
+    a: int!("1"),
+    d: int!("-39081"),
+    r: int!("181709681073901722637330951972001133588410340171829515070372549795146003961539585716195755291692375963310293709091662304773755859649779"),
+    h: int!("4"),
+    gx: int!("224580040295924300187604334099896036246789641632564134246125461686950415467406032909029192869357953282578032075146446173674602635247710"),
+    gy: int!("298819210078481492676017930443930673437544040154080242095928241372331506189835876003536878655418784733982303233503462500531545062832660"),
 };
+
+static JUBJUB_PARAMS: &Params = &Params {
+    name: "jubjub",
+    p: int!("52435875175126190479447740508185965837690552500527637822603658699938581184513"),
+    a: int!("-1"),
+    d: int!("19257038036680949359750312669786877991949435402254120286184196891950884077233"),
+    r: int!("6554484396890773809930967563523245729705921265872317281365359162392183254199"),
+    h: int!("8"),
+    gx: int!("8076246640662884909881801758704306714034609987455869804520522091855516602923"),
+    gy: int!("13262374693698910701929044844600465831413122818447359594527400194675274060458"),
+};
+
+// compressed encodings of [k]G for the scalar k of the self-test
+static EDWARDS25519_KAT: &str = "1ff6905f8d464e372f1176f60bfaf64d7f03bbe1972e7ebe9de837fd0b0a3862";
+static EDWARDS448_KAT: &str = "9bc51e757eaf5be3f2470b80f63e4111ce96587004db5b8df427a6c6a33ce39da7f462ca9dbc4e76713fb0563378cf3204abf1fb2f0e035400";
+static JUBJUB_KAT: &str = "4840b8b28cb62853bae239add4c5eb76f9d2f47462801939811baf3185eef184";
@@ -0,0 +1,87 @@
+use std::io::Error;
+use std::str::FromStr;
+
+use crate::instances::{self_test, GetCurve, ERR_UNKNOWN_CURVE};
+use crate::int;
+use crate::pairing::{G2Curve, G2Params};
+
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub struct G2CurveID(&'static G2Params, &'static str);
+
+impl GetCurve for G2CurveID {
+    type E = G2Curve;
+    #[inline]
+    fn get(&self) -> Self::E {
+        G2Curve::from(self.0)
+    }
+}
+
+impl G2CurveID {
+    /// Runs the self-test of the curve, followed by a known-answer test of
+    /// scalar multiplication. It fails if the parameters or the arithmetic
+    /// of the curve are broken.
+    pub fn self_test(&self) -> Result<(), Error> {
+        self_test(&self.get(), self.1)
+    }
+}
+
+impl std::fmt::Display for G2CurveID {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0.name)
+    }
+}
+
+/// Finds a curve by its name, ignoring case.
+impl FromStr for G2CurveID {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        [BLS12381G2, BN254G2]
+            .iter()
+            .find(|id| id.0.name.eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| Error::other(ERR_UNKNOWN_CURVE))
+    }
+}
+
+/// BLS12381G2 is the group G2 of BLS12-381, i.e., the points of order r of
+/// its sextic twist defined over GF(p^2).
+pub static BLS12381G2: G2CurveID = G2CurveID(BLS12381G2_PARAMS, BLS12381G2_KAT);
+/// BN254G2 is the group G2 of BN254, i.e., the points of order r of its
+/// sextic twist `y^2 = x^3 + 3/(9+i)` defined over GF(p^2).
+pub static BN254G2: G2CurveID = G2CurveID(BN254G2_PARAMS, BN254G2_KAT);
+
+static BLS12381G2_PARAMS: &G2Params = &G2Params {
+    name: "bls12381_g2",
+    p: int!("4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787"),
+    b: int!("4", "4"),
+    r: int!("52435875175126190479447740508185965837690552500527637822603658699938581184513"),
+    h: int!("305502333931268344200999753193121504214466019254188142667664032982267604182971884026507427359259977847832272839041616661285803823378372096355777062779109"),
+    gx: int!("0x24aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8", "0x13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e"),
+    gy: int!("0xce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801", "0x606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be"),
+    xi: Some(int!("1", "1")),
+    x: Some(int!("-15132376222941642752")),
+};
+static BN254G2_PARAMS: &G2Params = &G2Params {
+    name: "bn254_g2",
+    p: int!("21888242871839275222246405745257275088696311157297823662689037894645226208583"),
+    b: int!(
+        "19485874751759354771024239261021720505790618469301721065564631296452457478373",
+        "266929791119991161246907387137283842545076965332900288569378510910307636690"
+    ),
+    r: int!("21888242871839275222246405745257275088548364400416034343698204186575808495617"),
+    h: int!("21888242871839275222246405745257275088844257914179612981679871602714643921549"),
+    gx: int!(
+        "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+        "11559732032986387107991004021392285783925812861821192530917403151452391805634"
+    ),
+    gy: int!(
+        "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+        "4082367875863433681332203403145435568316851327593401208105741076214120093531"
+    ),
+    xi: None,
+    x: None,
+};
+
+// compressed encodings of [k]G for the scalar k of the self-test
+static BLS12381G2_KAT: &str = "93ce73574666b2713b9e24e94d78bf3fe0255fea0ff87c2614ea7436bfc36108b2ab45f65841c08e9cf6a4d4a81b0b4405889bc07cc1aeacda2d3af4b5e90747fa1c7a7d120f983d11dfb64ea9da27982fe27ebc0bd66ecbc7ec8c65f5d6f9d1";
+static BN254G2_KAT: &str = "a45115ebc7f7b978c02cd23e609077b1d8c6e73e51159e7bf98ec69fb0bebba71fadb75252c5c536009f3bd72bfb398dae0bdfc82493826ffc38a264a7d47376";
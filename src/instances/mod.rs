@@ -1,22 +1,345 @@
 mod edw;
+mod g2;
 mod mont;
 mod rational_maps;
 mod weier;
 
-pub use crate::instances::edw::{EdCurveID, EDWARDS25519, EDWARDS448};
+pub use crate::instances::edw::{EdCurveID, EDWARDS25519, EDWARDS448, JUBJUB};
+pub use crate::instances::g2::{G2CurveID, BLS12381G2, BN254G2};
 pub use crate::instances::mont::{MtCurveID, CURVE25519, CURVE448};
 pub use crate::instances::rational_maps::{
     edwards25519_to_curve25519, edwards448_to_curve448, get_isogeny_bls12381g1,
     get_isogeny_secp256k1,
 };
 pub use crate::instances::weier::{
-    WeCurveID, BLS12381G1, BLS12381G1_11ISO, P256, P384, P521, SECP256K1, SECP256K1_3ISO,
+    WeCurveID, BLS12381G1, BLS12381G1_11ISO, BN254, P256, P384, P521, SECP256K1, SECP256K1_3ISO,
+    SM2, W25519,
 };
 
-use crate::ellipticcurve::EllipticCurve;
+use num_bigint::BigInt;
+
+use std::fmt::Display;
+use std::io::Error;
+use std::str::FromStr;
+
+use crate::ellipticcurve::{Decode, EllipticCurve, Encode, PointFormat};
 
 /// Obtains a curve from a curve identifier.
 pub trait GetCurve {
     type E: EllipticCurve;
     fn get(&self) -> Self::E;
 }
+
+/// NamedPoint is a point bound to a named curve. It is written as
+/// `name:hex`, where `hex` is the encoding of the point, so that test
+/// vectors can be parsed directly:
+///
+/// ```
+///  use redox_ecc::instances::{NamedPoint, WeCurveID, P256};
+///  let s = "P256:036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+///  let p: NamedPoint<WeCurveID> = s.parse().unwrap();
+///  assert!(p.id == P256);
+///  assert_eq!(p.to_string(), s);
+/// ```
+pub struct NamedPoint<ID: GetCurve> {
+    pub id: ID,
+    pub point: <ID::E as EllipticCurve>::Point,
+}
+
+impl<ID> FromStr for NamedPoint<ID>
+where
+    ID: GetCurve + FromStr<Err = Error>,
+    ID::E: Decode<Deser = <ID::E as EllipticCurve>::Point>,
+{
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let (name, hex) = s
+            .split_once(':')
+            .ok_or_else(|| Error::other(ERR_NAMED_POINT))?;
+        let id: ID = name.parse()?;
+        let point = id.get().point_from_hex(hex)?;
+        Ok(NamedPoint { id, point })
+    }
+}
+
+impl<ID: GetCurve + Display> Display for NamedPoint<ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.id, self.point)
+    }
+}
+
+/// Runs the self-test of `e`, and checks that the compressed encoding of
+/// `[k]G` is `kat`, where `k` is the secret key of the P-256 test vectors of
+/// RFC 6979, Appendix A.2.5. An empty `kat` skips the known-answer test.
+fn self_test<E>(e: &E, kat: &str) -> Result<(), Error>
+where
+    E: EllipticCurve + Decode<Deser = <E as EllipticCurve>::Point>,
+{
+    e.self_test()?;
+    if kat.is_empty() {
+        return Ok(());
+    }
+    let k = BigInt::parse_bytes(KAT_SCALAR.as_bytes(), 16).unwrap();
+    let p = e.get_generator() * &e.new_scalar(k);
+    let enc: String = p
+        .encode(PointFormat::Compressed)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if enc != kat {
+        return Err(Error::other(ERR_KAT));
+    }
+    Ok(())
+}
+
+const KAT_SCALAR: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+const ERR_KAT: &str = "self-test: scalar multiplication does not match the known answer";
+const ERR_UNKNOWN_CURVE: &str = "unknown curve name";
+const ERR_NAMED_POINT: &str = "named point must be written as name:hex";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand_core::OsRng;
+    use sha2::Sha512;
+    use subtle::ConstantTimeEq;
+
+    use crate::ellipticcurve::{EcPoint, EllipticCurve};
+    use crate::field::Field;
+    use crate::instances::{
+        EdCurveID, G2CurveID, GetCurve, MtCurveID, NamedPoint, WeCurveID, BLS12381G1,
+        BLS12381G1_11ISO, BLS12381G2, BN254, BN254G2, CURVE25519, CURVE448, EDWARDS25519,
+        EDWARDS448, JUBJUB, P256, P384, P521, SECP256K1, SECP256K1_3ISO, SM2, W25519,
+    };
+    use crate::int;
+    use crate::ops::{Endianness, FromFactory, Serialize};
+    use crate::weierstrass::{self, Curve, Params};
+    use crate::{edwards, montgomery};
+
+    #[test]
+    fn self_tests() {
+        for id in [
+            P256,
+            P384,
+            P521,
+            SECP256K1,
+            SECP256K1_3ISO,
+            SM2,
+            BLS12381G1,
+            BLS12381G1_11ISO,
+            BN254,
+            W25519,
+        ]
+        .iter()
+        {
+            assert!(id.self_test().is_ok(), "{}", id);
+        }
+        for id in [EDWARDS25519, EDWARDS448, JUBJUB].iter() {
+            assert!(id.self_test().is_ok(), "{}", id);
+        }
+        for id in [CURVE25519, CURVE448].iter() {
+            assert!(id.self_test().is_ok(), "{}", id);
+        }
+        for id in [BLS12381G2, BN254G2].iter() {
+            assert!(id.self_test().is_ok(), "{}", id);
+        }
+    }
+
+    #[test]
+    fn corrupted_parameters() {
+        // P-256 with the order of secp256k1
+        let e = Curve::from(&Params {
+            name: "corrupted",
+            p: int!(
+                "115792089210356248762697446949407573530086143415290314195533631308867097853951"
+            ),
+            a: int!("-3"),
+            b: int!(
+                "41058363725152142129326129780047268409114441015993725554835256314039467401291"
+            ),
+            r: int!(
+                "115792089237316195423570985008687907852837564279074904382605163141518161494337"
+            ),
+            h: int!("1"),
+            gx: int!(
+                "48439561293906451759052585252797914202762949526041747995844080717082404635286"
+            ),
+            gy: int!(
+                "36134250956749795798585127919587881956611106672985015071877198253568414405109"
+            ),
+        });
+        assert!(e.self_test().is_err());
+    }
+
+    #[test]
+    fn names() {
+        for id in [P256, P384, SECP256K1, BN254].iter() {
+            let e = id.get();
+            assert_eq!(e.name(), id.to_string());
+            assert!(e.to_string().starts_with(&format!("{}: Weierstrass", id)));
+        }
+        let e = EDWARDS448.get();
+        assert_eq!(e.name(), "edwards448");
+        assert!(e.to_string().starts_with("edwards448: Twisted Edwards"));
+        let e = CURVE25519.get();
+        assert_eq!(e.name(), "curve25519");
+        assert!(e.to_string().starts_with("curve25519: Montgomery"));
+        assert_eq!(BLS12381G2.get().name(), "bls12381_g2");
+    }
+
+    #[test]
+    fn parameters() {
+        let e = SECP256K1.get();
+        let f = e.get_field();
+        assert!(*e.a() == f.zero() && *e.b() == f.from(7));
+        let e = P256.get();
+        assert!(*e.a() == e.get_field().from(-3));
+        let e = EDWARDS25519.get();
+        assert!(*e.a() == e.get_field().from(-1));
+        assert!(e.d() * e.get_field().from(121666) == e.get_field().from(-121665));
+        let e = CURVE25519.get();
+        let f = e.get_field();
+        assert!(*e.a() == f.from(486662) && *e.b() == f.one());
+        assert!(*e.s() != f.zero());
+        let e = BLS12381G2.get();
+        assert!(*e.b() == e.get_field().from("4,4"));
+    }
+
+    #[test]
+    fn cached_constants() {
+        for id in [P256, SECP256K1, BN254].iter() {
+            let e = id.get();
+            let f = e.get_field();
+            assert!(e.one == f.one() && e.zero == f.zero());
+            assert!(e.b3 == f.from(3) * e.b());
+        }
+        for id in [CURVE25519, CURVE448].iter() {
+            let e = id.get();
+            let f = e.get_field();
+            assert!(e.one == f.one() && e.zero == f.zero());
+            assert!(&e.inv_b * e.b() == f.one());
+            assert!(f.from(4) * &e.a24 + f.from(2) == *e.a());
+        }
+        let e = EDWARDS25519.get();
+        assert!(e.one == e.get_field().one() && e.zero == e.get_field().zero());
+    }
+
+    #[test]
+    fn scalars_from_bytes() {
+        let e = P256.get();
+        let k = e.new_scalar(BigInt::from(e.get_order()) - 1u32);
+        let be = k.to_bytes_be();
+        assert!(e.new_scalar_from_bytes(&be, Endianness::Big) == k);
+        assert!(e.new_scalar_from_bytes(&k.to_bytes_le(), Endianness::Little) == k);
+        // wide inputs are reduced modulo the order
+        let r = e.get_order().to_bytes_be();
+        let wide = [r.as_slice(), be.as_slice()].concat();
+        assert!(e.new_scalar_from_bytes(&wide, Endianness::Big) == k);
+        assert!(e.new_scalar_from_bytes(&[], Endianness::Little) == e.new_scalar(BigInt::from(0)));
+        let e = EDWARDS25519.get();
+        let buf = [1u8, 2];
+        let want = e.new_scalar(BigInt::from(0x0201));
+        assert!(e.new_scalar_from_bytes(&buf, Endianness::Little) == want);
+    }
+
+    #[test]
+    fn scalars_from_hash() {
+        let want = "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+                    2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f";
+        let want = BigInt::parse_bytes(want.as_bytes(), 16).unwrap();
+        let e = EDWARDS25519.get();
+        let k = edwards::Scalar::from_hash::<Sha512>(b"abc", &e.get_order());
+        assert!(k == e.new_scalar(want.clone()));
+        let e = CURVE25519.get();
+        let k = montgomery::Scalar::from_hash::<Sha512>(b"abc", &e.get_order());
+        assert!(bool::from(k.ct_eq(&e.new_scalar(want.clone()))));
+        let e = P256.get();
+        let k = weierstrass::Scalar::from_hash::<Sha512>(b"abc", &e.get_order());
+        assert!(k == e.new_scalar(want));
+    }
+
+    #[test]
+    fn random_points() {
+        fn check<E: EllipticCurve>(e: &E, id: &dyn std::fmt::Display)
+        where
+            E::Point: Clone,
+        {
+            let p = e.random_point(&mut OsRng);
+            let r1 = e.new_scalar(BigInt::from(e.get_order()) - 1u32);
+            assert!(e.is_on_curve(&p) && !p.is_identity(), "{}", id);
+            assert!((p.clone() * &r1 + &p).is_identity(), "{}", id);
+            assert!(e.random_point(&mut OsRng) != p, "{}", id);
+        }
+        for id in [P256, SECP256K1, BLS12381G1].iter() {
+            check(&id.get(), id);
+        }
+        for id in [EDWARDS25519, EDWARDS448].iter() {
+            check(&id.get(), id);
+        }
+        check(&CURVE25519.get(), &CURVE25519);
+    }
+
+    #[test]
+    fn named_points() {
+        let gx = "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+        let gy = "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5";
+        let e = P256.get();
+        let g = e.get_generator();
+        assert!(e.point_from_hex(&format!("03{}", gx)).unwrap() == g);
+        assert!(e.point_from_hex(&format!("04{}{}", gx, gy)).unwrap() == g);
+        assert!(
+            e.point_from_hex(&format!("03{}", gx.to_uppercase()))
+                .unwrap()
+                == g
+        );
+        for bad in ["03", "0", "+3", "zz", "03\u{e9}"].iter() {
+            assert!(e.point_from_hex(bad).is_err(), "{}", bad);
+        }
+
+        let p: NamedPoint<WeCurveID> = format!("p256:03{}", gx).parse().unwrap();
+        assert!(p.id == P256 && p.point == g);
+        assert_eq!(p.to_string(), format!("P256:03{}", gx));
+        for id in [EDWARDS25519, EDWARDS448, JUBJUB].iter() {
+            let s = format!("{}:{}", id, id.get().get_generator());
+            let p: NamedPoint<EdCurveID> = s.parse().unwrap();
+            assert!(p.id == *id && p.to_string() == s);
+        }
+        let s = format!("{}:{}", CURVE448, CURVE448.get().get_generator());
+        assert_eq!(s.parse::<NamedPoint<MtCurveID>>().unwrap().to_string(), s);
+        let s = format!("{}:{}", BN254G2, BN254G2.get().get_generator());
+        assert_eq!(s.parse::<NamedPoint<G2CurveID>>().unwrap().to_string(), s);
+
+        assert!("P256".parse::<NamedPoint<WeCurveID>>().is_err());
+        assert!(format!("P257:03{}", gx)
+            .parse::<NamedPoint<WeCurveID>>()
+            .is_err());
+        assert!(format!("P384:03{}", gx)
+            .parse::<NamedPoint<WeCurveID>>()
+            .is_err());
+    }
+
+    crate::define_curve! {
+        pub(super) static P256_HEX {
+            name: "P256_hex",
+            p: "0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+            a: "-3",
+            b: "0x5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+            gx: "0x6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+            gy: "0x4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+            r: "0xffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+            h: "0x1",
+            kat: "0360fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6",
+            test: defined_curve_self_test,
+        }
+    }
+
+    #[test]
+    fn defined_curve() {
+        let e = P256_HEX.get();
+        assert!(e == P256.get());
+        assert_eq!(e.name(), "P256_hex");
+        assert_eq!(P256_HEX.to_string(), "P256_hex");
+        // not a built-in curve
+        assert!("P256_hex".parse::<WeCurveID>().is_err());
+    }
+}
@@ -1,8 +1,12 @@
-use crate::instances::GetCurve;
+use std::io::Error;
+use std::str::FromStr;
+
+use crate::instances::{self_test, GetCurve, ERR_UNKNOWN_CURVE};
+use crate::int;
 use crate::montgomery::{Curve, Params};
 
 #[derive(PartialEq, Eq, Copy, Clone)]
-pub struct MtCurveID(&'static Params);
+pub struct MtCurveID(&'static Params, &'static str);
 
 impl GetCurve for MtCurveID {
     type E = Curve;
@@ -12,37 +16,62 @@ impl GetCurve for MtCurveID {
     }
 }
 
+impl MtCurveID {
+    /// Runs the self-test of the curve, followed by a known-answer test of
+    /// scalar multiplication. It fails if the parameters or the arithmetic
+    /// of the curve are broken.
+    pub fn self_test(&self) -> Result<(), Error> {
+        self_test(&self.get(), self.1)
+    }
+}
+
 impl std::fmt::Display for MtCurveID {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}", self.0.name)
     }
 }
 
+/// Finds a curve by its name, ignoring case.
+impl FromStr for MtCurveID {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        [CURVE25519, CURVE448]
+            .iter()
+            .find(|id| id.0.name.eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| Error::other(ERR_UNKNOWN_CURVE))
+    }
+}
+
 /// CURVE25519 is the curve25519 elliptic curve as specified in RFC-7748.
-pub static CURVE25519: MtCurveID = MtCurveID(CURVE25519_PARAMS);
+pub static CURVE25519: MtCurveID = MtCurveID(CURVE25519_PARAMS, CURVE25519_KAT);
 /// CURVE448 is the curve448 elliptic curve as specified in RFC-7748.
-pub static CURVE448: MtCurveID = MtCurveID(CURVE448_PARAMS);
+pub static CURVE448: MtCurveID = MtCurveID(CURVE448_PARAMS, CURVE448_KAT);
 
 static CURVE25519_PARAMS: &Params = &Params {
     name: "curve25519",
-    p: "57896044618658097711785492504343953926634992332820282019728792003956564819949",
-    a: "486662",
-    b: "1",
-    s: "1",
-    r: "7237005577332262213973186563042994240857116359379907606001950938285454250989",
-    h: "8",
-    gx: "9",
-    gy: "43114425171068552920764898935933967039370386198203806730763910166200978582548",
+    p: int!("57896044618658097711785492504343953926634992332820282019728792003956564819949"),
+    a: int!("486662"),
+    b: int!("1"),
+    s: int!("1"),
+    r: int!("7237005577332262213973186563042994240857116359379907606001950938285454250989"),
+    h: int!("8"),
+    gx: int!("9"),
+    gy: int!("43114425171068552920764898935933967039370386198203806730763910166200978582548"),
 };
 
 static CURVE448_PARAMS: &Params =  &Params {
     name: "curve448",
-    p: "726838724295606890549323807888004534353641360687318060281490199180612328166730772686396383698676545930088884461843637361053498018365439",
-    a: "156326",
-    b: "1",
-    s: "3",
-    r: "181709681073901722637330951972001133588410340171829515070372549795146003961539585716195755291692375963310293709091662304773755859649779",
-    h: "4",
-    gx: "5",
-    gy: "355293926785568175264127502063783334808976399387714271831880898435169088786967410002932673765864550910142774147268105838985595290606362",
+    p: int!("726838724295606890549323807888004534353641360687318060281490199180612328166730772686396383698676545930088884461843637361053498018365439"),
+    a: int!("156326"),
+    b: int!("1"),
+    s: int!("3"),
+    r: int!("181709681073901722637330951972001133588410340171829515070372549795146003961539585716195755291692375963310293709091662304773755859649779"),
+    h: int!("4"),
+    gx: int!("5"),
+    gy: int!("355293926785568175264127502063783334808976399387714271831880898435169088786967410002932673765864550910142774147268105838985595290606362"),
 };
+
+// compressed encodings of [k]G for the scalar k of the self-test
+static CURVE25519_KAT: &str = "0376a2d372093515b700342b50f83ba304f98c7486d7f7353f545e118babfa6787";
+static CURVE448_KAT: &str = "02fb70f6fb0822065263b2ebc928408cdf5bd48a39961450b835df4e84ef4e7e938f9778df39958bf73a0568ebd09391139234dac764b2ecf4";
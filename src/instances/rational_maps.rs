@@ -142,7 +142,8 @@ impl RationalMap for Ed4isoMt448 {
             let z4 = &z2 ^ 2u32;
             let xx = f4 * y * z * (&x2 - &z2);
             let z0 = x4 - f2 * &x2 * &z2 + &z4 + f4 * &y2 * &z2;
-            let yy = -(&x5 - f2 * &x3 * &z2 + x * &z4 - f4 * x * &y2 * &z2);
+            let yy: FpElt = &x5 - f2 * &x3 * &z2 + x * &z4 - f4 * x * &y2 * &z2;
+            let yy = -yy;
             let z1 = x5 - f2 * &x3 * &z2 + x * z4 - f2 * x2 * &y2 * z - f2 * y2 * z3;
             let tt = &xx * &yy;
             let xx = &xx * &z1;
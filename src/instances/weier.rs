@@ -1,8 +1,12 @@
-use crate::instances::GetCurve;
+use std::io::Error;
+use std::str::FromStr;
+
+use crate::instances::{self_test, GetCurve, ERR_UNKNOWN_CURVE};
+use crate::int;
 use crate::weierstrass::{Curve, Params};
 
 #[derive(PartialEq, Eq, Copy, Clone)]
-pub struct WeCurveID(&'static Params);
+pub struct WeCurveID(&'static Params, &'static str);
 
 impl GetCurve for WeCurveID {
     type E = Curve;
@@ -12,95 +16,184 @@ impl GetCurve for WeCurveID {
     }
 }
 
+impl WeCurveID {
+    /// Creates an identifier for a curve defined outside the crate, see
+    /// `define_curve!`. The string `kat` is the compressed encoding of `[k]G`
+    /// checked by `self_test`, or empty to skip this check.
+    pub const fn new(params: &'static Params, kat: &'static str) -> WeCurveID {
+        WeCurveID(params, kat)
+    }
+    /// Runs the self-test of the curve, followed by a known-answer test of
+    /// scalar multiplication. It fails if the parameters or the arithmetic
+    /// of the curve are broken.
+    pub fn self_test(&self) -> Result<(), Error> {
+        self_test(&self.get(), self.1)
+    }
+}
+
 impl std::fmt::Display for WeCurveID {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}", self.0.name)
     }
 }
 
+/// Finds a curve by its name, ignoring case.
+impl FromStr for WeCurveID {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        [
+            P256,
+            P384,
+            P521,
+            SECP256K1,
+            SECP256K1_3ISO,
+            SM2,
+            BLS12381G1,
+            BLS12381G1_11ISO,
+            BN254,
+            W25519,
+        ]
+        .iter()
+        .find(|id| id.0.name.eq_ignore_ascii_case(s))
+        .copied()
+        .ok_or_else(|| Error::other(ERR_UNKNOWN_CURVE))
+    }
+}
+
 /// P256 is the NIST P-256 elliptic curve.
-pub static P256: WeCurveID = WeCurveID(P256_PARAMS);
+pub static P256: WeCurveID = WeCurveID(P256_PARAMS, P256_KAT);
 /// P384 is the NIST P-384 elliptic curve.
-pub static P384: WeCurveID = WeCurveID(P384_PARAMS);
+pub static P384: WeCurveID = WeCurveID(P384_PARAMS, P384_KAT);
 /// P521 is the NIST P-521 elliptic curve.
-pub static P521: WeCurveID = WeCurveID(P521_PARAMS);
+pub static P521: WeCurveID = WeCurveID(P521_PARAMS, P521_KAT);
 /// SECP256K1 is a 256-bit elliptic curve known as secp256k1.
-pub static SECP256K1: WeCurveID = WeCurveID(SECP256K1_PARAMS);
+pub static SECP256K1: WeCurveID = WeCurveID(SECP256K1_PARAMS, SECP256K1_KAT);
 /// SECP256K1_3ISO is a degree-3 curve isogenous to secp256k1.
-pub static SECP256K1_3ISO: WeCurveID = WeCurveID(SECP256K1_3ISO_PARAMS);
+pub static SECP256K1_3ISO: WeCurveID = WeCurveID(SECP256K1_3ISO_PARAMS, SECP256K1_3ISO_KAT);
+/// SM2 is the 256-bit elliptic curve of the Chinese standard GB/T 32918.
+pub static SM2: WeCurveID = WeCurveID(SM2_PARAMS, SM2_KAT);
 /// BLS12381_G1 is a pairing-friendly curve known as BLS12381 defined over GF(p).
-pub static BLS12381G1: WeCurveID = WeCurveID(BLS12381G1_PARAMS);
+pub static BLS12381G1: WeCurveID = WeCurveID(BLS12381G1_PARAMS, BLS12381G1_KAT);
 /// BLS12381_G1_11ISO is a degree-11 curve isogenous to BLS12381 defined over GF(p).
-pub static BLS12381G1_11ISO: WeCurveID = WeCurveID(BLS12381G1_11ISO_PARAMS);
+pub static BLS12381G1_11ISO: WeCurveID = WeCurveID(BLS12381G1_11ISO_PARAMS, BLS12381G1_11ISO_KAT);
+/// BN254 is the pairing-friendly curve also known as alt_bn128, which is
+/// supported by the precompiled contracts of Ethereum.
+pub static BN254: WeCurveID = WeCurveID(BN254_PARAMS, BN254_KAT);
+/// W25519 is the Weierstrass form of Curve25519 known as Wei25519, whose
+/// cofactor is 8.
+pub static W25519: WeCurveID = WeCurveID(W25519_PARAMS, W25519_KAT);
 
 static P256_PARAMS: &Params = &Params {
     name: "P256",
-    p: "115792089210356248762697446949407573530086143415290314195533631308867097853951",
-    a: "-3",
-    b: "41058363725152142129326129780047268409114441015993725554835256314039467401291",
-    r: "115792089210356248762697446949407573529996955224135760342422259061068512044369",
-    h: "1",
-    gx: "48439561293906451759052585252797914202762949526041747995844080717082404635286",
-    gy: "36134250956749795798585127919587881956611106672985015071877198253568414405109",
+    p: int!("115792089210356248762697446949407573530086143415290314195533631308867097853951"),
+    a: int!("-3"),
+    b: int!("41058363725152142129326129780047268409114441015993725554835256314039467401291"),
+    r: int!("115792089210356248762697446949407573529996955224135760342422259061068512044369"),
+    h: int!("1"),
+    gx: int!("48439561293906451759052585252797914202762949526041747995844080717082404635286"),
+    gy: int!("36134250956749795798585127919587881956611106672985015071877198253568414405109"),
 };
 static P384_PARAMS: &Params = &Params {
     name: "P384",
-    p: "39402006196394479212279040100143613805079739270465446667948293404245721771496870329047266088258938001861606973112319",
-    a: "-3",
-    b: "27580193559959705877849011840389048093056905856361568521428707301988689241309860865136260764883745107765439761230575",
-    r: "39402006196394479212279040100143613805079739270465446667946905279627659399113263569398956308152294913554433653942643",
-    h: "1",
-    gx: "26247035095799689268623156744566981891852923491109213387815615900925518854738050089022388053975719786650872476732087",
-    gy: "8325710961489029985546751289520108179287853048861315594709205902480503199884419224438643760392947333078086511627871",
+    p: int!("39402006196394479212279040100143613805079739270465446667948293404245721771496870329047266088258938001861606973112319"),
+    a: int!("-3"),
+    b: int!("27580193559959705877849011840389048093056905856361568521428707301988689241309860865136260764883745107765439761230575"),
+    r: int!("39402006196394479212279040100143613805079739270465446667946905279627659399113263569398956308152294913554433653942643"),
+    h: int!("1"),
+    gx: int!("26247035095799689268623156744566981891852923491109213387815615900925518854738050089022388053975719786650872476732087"),
+    gy: int!("8325710961489029985546751289520108179287853048861315594709205902480503199884419224438643760392947333078086511627871"),
 };
 static P521_PARAMS: &Params = &Params {
     name: "P521",
-    p: "6864797660130609714981900799081393217269435300143305409394463459185543183397656052122559640661454554977296311391480858037121987999716643812574028291115057151",
-    a: "-3",
-    b: "1093849038073734274511112390766805569936207598951683748994586394495953116150735016013708737573759623248592132296706313309438452531591012912142327488478985984",
-    r: "6864797660130609714981900799081393217269435300143305409394463459185543183397655394245057746333217197532963996371363321113864768612440380340372808892707005449",
-    h: "1",
-    gx:"2661740802050217063228768716723360960729859168756973147706671368418802944996427808491545080627771902352094241225065558662157113545570916814161637315895999846",
-    gy:"3757180025770020463545507224491183603594455134769762486694567779615544477440556316691234405012945539562144444537289428522585666729196580810124344277578376784",
+    p: int!("6864797660130609714981900799081393217269435300143305409394463459185543183397656052122559640661454554977296311391480858037121987999716643812574028291115057151"),
+    a: int!("-3"),
+    b: int!("1093849038073734274511112390766805569936207598951683748994586394495953116150735016013708737573759623248592132296706313309438452531591012912142327488478985984"),
+    r: int!("6864797660130609714981900799081393217269435300143305409394463459185543183397655394245057746333217197532963996371363321113864768612440380340372808892707005449"),
+    h: int!("1"),
+    gx:int!("2661740802050217063228768716723360960729859168756973147706671368418802944996427808491545080627771902352094241225065558662157113545570916814161637315895999846"),
+    gy:int!("3757180025770020463545507224491183603594455134769762486694567779615544477440556316691234405012945539562144444537289428522585666729196580810124344277578376784"),
 };
 static SECP256K1_PARAMS: &Params = &Params {
     name: "secp256k1",
-    p: "115792089237316195423570985008687907853269984665640564039457584007908834671663",
-    a: "0",
-    b: "7",
-    r: "115792089237316195423570985008687907852837564279074904382605163141518161494337",
-    h: "1",
-    gx: "55066263022277343669578718895168534326250603453777594175500187360389116729240",
-    gy: "32670510020758816978083085130507043184471273380659243275938904335757337482424",
+    p: int!("115792089237316195423570985008687907853269984665640564039457584007908834671663"),
+    a: int!("0"),
+    b: int!("7"),
+    r: int!("115792089237316195423570985008687907852837564279074904382605163141518161494337"),
+    h: int!("1"),
+    gx: int!("55066263022277343669578718895168534326250603453777594175500187360389116729240"),
+    gy: int!("32670510020758816978083085130507043184471273380659243275938904335757337482424"),
+};
+static SM2_PARAMS: &Params = &Params {
+    name: "SM2",
+    p: int!("115792089210356248756420345214020892766250353991924191454421193933289684991999"),
+    a: int!("-3"),
+    b: int!("18505919022281880113072981827955639221458448578012075254857346196103069175443"),
+    r: int!("115792089210356248756420345214020892766061623724957744567843809356293439045923"),
+    h: int!("1"),
+    gx: int!("22963146547237050559479531362550074578802567295341616970375194840604139615431"),
+    gy: int!("85132369209828568825618990617112496413088388631904505083283536607588877201568"),
 };
 static SECP256K1_3ISO_PARAMS: &Params = &Params {
     name: "secp256k1_3iso",
-    p: "115792089237316195423570985008687907853269984665640564039457584007908834671663",
-    a: "28734576633528757162648956269730739219262246272443394170905244663053633733939",
-    b: "1771",
-    r: "115792089237316195423570985008687907852837564279074904382605163141518161494337",
-    h: "1",
-    gx: "75295888890003590383366995344834012177557063699577440394299653383124903397514",
-    gy: "82553647407850972504999846303729620951309077682374043495922869307182479212755",
+    p: int!("115792089237316195423570985008687907853269984665640564039457584007908834671663"),
+    a: int!("28734576633528757162648956269730739219262246272443394170905244663053633733939"),
+    b: int!("1771"),
+    r: int!("115792089237316195423570985008687907852837564279074904382605163141518161494337"),
+    h: int!("1"),
+    gx: int!("75295888890003590383366995344834012177557063699577440394299653383124903397514"),
+    gy: int!("82553647407850972504999846303729620951309077682374043495922869307182479212755"),
 };
 
 static BLS12381G1_PARAMS: &Params = &Params {
     name: "bls12381_g1",
-    p: "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787",
-    a: "0",
-    b: "4",
-    r: "52435875175126190479447740508185965837690552500527637822603658699938581184513",
-    h: "15132376222941642753",
-    gx: "0x17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
-    gy: "0x8b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1",
+    p: int!("4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787"),
+    a: int!("0"),
+    b: int!("4"),
+    r: int!("52435875175126190479447740508185965837690552500527637822603658699938581184513"),
+    h: int!("15132376222941642753"),
+    gx: int!("0x17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"),
+    gy: int!("0x8b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1"),
 };
 static BLS12381G1_11ISO_PARAMS: &Params = &Params {
     name: "bls12381_g1_11iso",
-    p: "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787",
-    a: "0x144698a3b8e9433d693a02c96d4982b0ea985383ee66a8d8e8981aefd881ac98936f8da0e0f97f5cf428082d584c1d",
-    b: "0x12e2908d11688030018b12e8753eee3b2016c1f0f24f4070a0b9c14fcef35ef55a23215a316ceaa5d1cc48e98e172be0",
-    r: "52435875175126190479447740508185965837690552500527637822603658699938581184513",
-    h: "15132376222941642753",
-    gx: "0x6a0ead062ba73a09984eb7351a2d851bc817625345ce033a6eb7d78242b6466c877e022dda626a79ddb85bce57997e2",
-    gy: "0x3b89d8bb9326270e46b6b74e19f7b3f10082fbf1a46df72da50c6571b969afc570d6529350b1b9b05ab4fe5c29920b4",
+    p: int!("4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787"),
+    a: int!("0x144698a3b8e9433d693a02c96d4982b0ea985383ee66a8d8e8981aefd881ac98936f8da0e0f97f5cf428082d584c1d"),
+    b: int!("0x12e2908d11688030018b12e8753eee3b2016c1f0f24f4070a0b9c14fcef35ef55a23215a316ceaa5d1cc48e98e172be0"),
+    r: int!("52435875175126190479447740508185965837690552500527637822603658699938581184513"),
+    h: int!("15132376222941642753"),
+    gx: int!("0x6a0ead062ba73a09984eb7351a2d851bc817625345ce033a6eb7d78242b6466c877e022dda626a79ddb85bce57997e2"),
+    gy: int!("0x3b89d8bb9326270e46b6b74e19f7b3f10082fbf1a46df72da50c6571b969afc570d6529350b1b9b05ab4fe5c29920b4"),
 };
+static BN254_PARAMS: &Params = &Params {
+    name: "bn254",
+    p: int!("21888242871839275222246405745257275088696311157297823662689037894645226208583"),
+    a: int!("0"),
+    b: int!("3"),
+    r: int!("21888242871839275222246405745257275088548364400416034343698204186575808495617"),
+    h: int!("1"),
+    gx: int!("1"),
+    gy: int!("2"),
+};
+static W25519_PARAMS: &Params = &Params {
+    name: "W25519",
+    p: int!("0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed"),
+    a: int!("0x2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa984914a144"),
+    b: int!("0x7b425ed097b425ed097b425ed097b425ed097b425ed097b4260b5e9c7710c864"),
+    r: int!("0x1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed"),
+    h: int!("8"),
+    gx: int!("0x2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaad245a"),
+    gy: int!("0x20ae19a1b8a086b4e01edd2c7748d14c923d4d7e6d7c61b229e9c5a27eced3d9"),
+};
+
+// compressed encodings of [k]G for the scalar k of the self-test
+static P256_KAT: &str = "0360fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6";
+static P384_KAT: &str = "0307c230d20b5acb84e2751245cfea6c662892bcf8486a018127aa5e16049a6fdd8ab5326d0f69b5b708eb595ab4ed6ef6";
+static P521_KAT: &str = "03008d350b66b953da1a1d2d3eaac4bdf57f01504a72fd8f9cb9ec042851e155a343abcba5f738758d0c1564eae62b18becfd0d79f6a22e9e63f54d95abb4ae01a27c4";
+static SECP256K1_KAT: &str = "032c8c31fc9f990c6b55e3865a184a4ce50e09481f2eaeb3e60ec1cea13a6ae645";
+static SECP256K1_3ISO_KAT: &str =
+    "0252a49f6c367beba0dc6358f4a6b22e7558465093f4590c35bc9a5c4dd96900f7";
+static SM2_KAT: &str = "0357744271cea7d618858286b96b2a77ca6162559b00e7f1d5bd88226b45b6d076";
+static BLS12381G1_KAT: &str = "021774ac8a4f630d22bbed308e0dc6adb2afb508a16346c03c99fc81bbe235b36244c8f28ee71d52e3a898853002e7dcd8";
+static BLS12381G1_11ISO_KAT: &str = "020c28383d304bfb444b994a95252d01520bf47829aacae1c3b97c8d7bb901662dca1294b662db28ae9c55f53b3c9a6dc9";
+static BN254_KAT: &str = "0314693a2dbf1391c5caff065d4dace55a28f0c77052b00c7c376b3cb433d6450f";
+static W25519_KAT: &str = "02214d7e1cb3dfc061aaded5fba2e64dafa4371f3182a1dfe9ff08bc3656a78beb";
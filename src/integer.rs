@@ -0,0 +1,154 @@
+//! This is documentation for the `integer` module.
+//!
+//! The integer module stores the integers of curve parameters as bytes
+//! computed at compile time, so that no string is parsed when a curve is
+//! created. Integers are written with the `int!` macro:
+//!
+//! ```
+//!  use num_bigint::BigInt;
+//!  use redox_ecc::int;
+//!  use redox_ecc::integer::Integer;
+//!
+//!  static P: Integer = int!("0xffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+//!  static A: Integer = int!("-3");
+//!  assert_eq!(BigInt::from(A), BigInt::from(-3));
+//!  assert_eq!(P.to_biguint().bits(), 256);
+//! ```
+//!
+//! A digit out of place is reported by the compiler:
+//!
+//! ```compile_fail
+//!  use redox_ecc::int;
+//!  use redox_ecc::integer::Integer;
+//!
+//!  static A: Integer = int!("12a4");
+//! ```
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+/// Integer is a signed integer whose magnitude is stored in big-endian
+/// order.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Integer {
+    neg: bool,
+    mag: &'static [u8],
+}
+
+impl Integer {
+    /// Returns the integer written as `s`, whose magnitude `mag` was
+    /// computed by `parse`. Use the `int!` macro instead.
+    #[doc(hidden)]
+    pub const fn new(s: &str, mag: &'static [u8]) -> Integer {
+        let (neg, _, _) = split(s.as_bytes());
+        Integer { neg, mag }
+    }
+    /// Returns the integer as an unsigned integer. It panics if the
+    /// integer is negative.
+    pub fn to_biguint(&self) -> BigUint {
+        assert!(!self.neg, "{}", ERR_NEGATIVE);
+        BigUint::from_bytes_be(self.mag)
+    }
+}
+
+impl From<Integer> for BigInt {
+    fn from(n: Integer) -> BigInt {
+        let sign = if n.neg { Sign::Minus } else { Sign::Plus };
+        BigInt::from_bytes_be(sign, n.mag)
+    }
+}
+
+/// Splits `s` into its sign, its digits, and the radix of its digits,
+/// which is 16 for digits prefixed by `0x` and 10 otherwise.
+const fn split(s: &[u8]) -> (bool, &[u8], u32) {
+    let (neg, s) = match s {
+        [b'-', rest @ ..] => (true, rest),
+        _ => (false, s),
+    };
+    match s {
+        [b'0', b'x', rest @ ..] => (neg, rest, 16),
+        _ => (neg, s, 10),
+    }
+}
+
+/// Returns a number of bytes large enough to hold the magnitude of the
+/// integer written as `s`.
+#[doc(hidden)]
+pub const fn byte_len(s: &str) -> usize {
+    let (_, digits, radix) = split(s.as_bytes());
+    if radix == 16 {
+        digits.len().div_ceil(2)
+    } else {
+        // log_256(10) < 27/64
+        (27 * digits.len()).div_ceil(64)
+    }
+}
+
+/// Returns the magnitude of the integer written as `s` in `N` bytes. It
+/// panics on invalid digits, which fails the compilation when it is
+/// evaluated in a constant.
+#[doc(hidden)]
+pub const fn parse<const N: usize>(s: &str) -> [u8; N] {
+    let (_, digits, radix) = split(s.as_bytes());
+    assert!(!digits.is_empty(), "integer without digits");
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < digits.len() {
+        let d = match digits[i] {
+            c @ b'0'..=b'9' => c - b'0',
+            c @ b'a'..=b'f' if radix == 16 => c - b'a' + 10,
+            c @ b'A'..=b'F' if radix == 16 => c - b'A' + 10,
+            _ => panic!("invalid digit in integer"),
+        };
+        // out = radix*out + d
+        let mut carry = d as u32;
+        let mut j = N;
+        while j > 0 {
+            j -= 1;
+            let t = radix * out[j] as u32 + carry;
+            out[j] = t as u8;
+            carry = t >> 8;
+        }
+        assert!(carry == 0, "integer does not fit in its bytes");
+        i += 1;
+    }
+    out
+}
+
+const ERR_NEGATIVE: &str = "integer must not be negative";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, BigUint};
+
+    use std::str::FromStr;
+
+    use super::Integer;
+    use crate::int;
+
+    #[test]
+    fn parse() {
+        static DEC: Integer =
+            int!("115792089210356248762697446949407573530086143415290314195533631308867097853951");
+        static HEX: Integer =
+            int!("0xFFFFFFFF00000001000000000000000000000000ffffffffffffffffffffffff");
+        static NEG: Integer = int!("-0x3");
+        static PAIR: [Integer; 2] = int!("0", "99");
+        assert_eq!(DEC.to_biguint(), HEX.to_biguint());
+        assert_eq!(
+            BigInt::from(DEC),
+            BigInt::from_str(
+                "115792089210356248762697446949407573530086143415290314195533631308867097853951"
+            )
+            .unwrap()
+        );
+        assert_eq!(BigInt::from(NEG), BigInt::from(-3));
+        assert_eq!(PAIR[0].to_biguint(), BigUint::from(0u32));
+        assert_eq!(BigInt::from(PAIR[1]), BigInt::from(99));
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_to_biguint() {
+        int!("-1").to_biguint();
+    }
+}
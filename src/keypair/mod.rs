@@ -0,0 +1,130 @@
+//! This is documentation for the `keypair` module.
+//!
+//! The keypair module provides a container for a secret scalar and its
+//! public point `[k]G`, which works with any elliptic curve of the crate.
+//!
+//! The secret scalar is wrapped in a `SecretScalar`, so it cannot be
+//! printed and it is zeroized when the key pair is dropped.
+//!
+//! # Generating a Key Pair
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use redox_ecc::keypair::KeyPair;
+//!
+//!  let ec = P256.get();
+//!  let kp = KeyPair::generate(&ec, &mut OsRng);
+//!  let copy = KeyPair::from_bytes(&ec, kp.to_bytes().expose_secret()).unwrap();
+//!  assert!(copy.public() == kp.public());
+//! ```
+
+use num_bigint::BigUint;
+use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
+
+use std::io::Error;
+
+use crate::ellipticcurve::{EllipticCurve, Encode, PointFormat};
+use crate::ops::Serialize;
+use crate::secret::{SecretKey, SecretScalar};
+
+/// KeyPair is a secret scalar together with its public point.
+pub struct KeyPair<E: EllipticCurve> {
+    secret: SecretScalar<E::Scalar>,
+    public: E::Point,
+}
+
+impl<E: EllipticCurve> KeyPair<E> {
+    /// Returns the key pair for the secret scalar `secret`.
+    pub fn new(e: &E, secret: E::Scalar) -> KeyPair<E> {
+        let public = e.get_generator() * &secret;
+        let secret = SecretScalar::new(secret);
+        KeyPair { secret, public }
+    }
+    /// Samples a secret scalar uniformly at random in `[1, n-1]`, where `n`
+    /// is the order of the curve.
+    pub fn generate<R: RngCore + CryptoRng>(e: &E, rng: &mut R) -> KeyPair<E> {
+        KeyPair::new(e, e.random_scalar(rng))
+    }
+    /// Parses a secret scalar encoded in big-endian order with as many bytes
+    /// as the order of the curve. It fails if the scalar is not in
+    /// `[1, n-1]`.
+    pub fn from_bytes(e: &E, buf: &[u8]) -> Result<KeyPair<E>, Error> {
+        let n = e.get_order();
+        let k = BigUint::from_bytes_be(buf);
+        if buf.len() != n.bits().div_ceil(8) || k.is_zero() || k >= n {
+            return Err(Error::other(ERR_SECRET_RANGE));
+        }
+        Ok(KeyPair::new(e, e.new_scalar(k.into())))
+    }
+    /// Returns the secret scalar encoded in big-endian order.
+    pub fn to_bytes(&self) -> SecretKey {
+        SecretKey::new(self.secret.expose_secret().to_bytes_be())
+    }
+    /// Returns the encoding of the public point in the given `format`.
+    pub fn public_key_bytes(&self, format: PointFormat) -> Vec<u8> {
+        self.public.encode(format)
+    }
+    pub fn secret(&self) -> &SecretScalar<E::Scalar> {
+        &self.secret
+    }
+    pub fn public(&self) -> &E::Point {
+        &self.public
+    }
+}
+
+impl<E: EllipticCurve> Clone for KeyPair<E>
+where
+    E::Scalar: Clone,
+    E::Point: Clone,
+{
+    fn clone(&self) -> Self {
+        KeyPair {
+            secret: self.secret.clone(),
+            public: self.public.clone(),
+        }
+    }
+}
+
+const ERR_SECRET_RANGE: &str = "secret scalar must be in [1, n-1]";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand_core::OsRng;
+    use zeroize::Zeroize;
+
+    use super::KeyPair;
+    use crate::ellipticcurve::{EllipticCurve, Encode, PointFormat};
+    use crate::instances::{GetCurve, CURVE25519, EDWARDS25519, P256};
+    use crate::ops::Serialize;
+
+    fn check_keypair<E: EllipticCurve>(e: E) {
+        let kp = KeyPair::generate(&e, &mut OsRng);
+        assert!(*kp.public() == e.get_generator() * kp.secret().expose_secret());
+        let copy = KeyPair::from_bytes(&e, kp.to_bytes().expose_secret()).unwrap();
+        assert!(copy.public() == kp.public());
+        assert_eq!(
+            copy.public_key_bytes(PointFormat::Compressed),
+            kp.public().encode(PointFormat::Compressed)
+        );
+
+        let n = e.get_order().to_bytes_be();
+        assert!(KeyPair::from_bytes(&e, &n).is_err());
+        assert!(KeyPair::from_bytes(&e, &vec![0u8; n.len()]).is_err());
+        assert!(KeyPair::from_bytes(&e, &kp.to_bytes().expose_secret()[1..]).is_err());
+
+        let mut k = e.new_scalar(BigInt::from(0x1234_5678));
+        k.zeroize();
+        assert!(k.to_bytes_be().iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn keypair() {
+        check_keypair(P256.get());
+        check_keypair(CURVE25519.get());
+        check_keypair(EDWARDS25519.get());
+    }
+}
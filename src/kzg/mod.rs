@@ -0,0 +1,530 @@
+//! This is documentation for the `kzg` module.
+//!
+//! The kzg module implements the polynomial commitments of Kate, Zaverucha
+//! and Goldberg over BLS12-381. The commitment to a polynomial `p` is the
+//! point `[p(tau)]G1`, where `tau` is the trapdoor of a structured reference
+//! string (SRS) made of the powers `[tau^i]G1` and `[tau^j]G2`.
+//!
+//! Whoever knows `tau` can open a commitment to any value, so the SRS of an
+//! application must come from a setup ceremony and be parsed with
+//! `Srs::from_bytes`, which checks that it is well formed. `Srs::from_secret`
+//! and `Srs::random` are only meant for tests.
+//!
+//! An opening proves the value of `p` at a point `z` with a single point of
+//! G1. A batch opening proves the values of `p` at `t` distinct points with
+//! a single point of G1 as well, as long as the SRS has at least `t + 1`
+//! powers in G2.
+//!
+//! # Opening a Commitment
+//!
+//! ```
+//!  use num_bigint::BigInt;
+//!  use rand_core::OsRng;
+//!  use redox_ecc::kzg::{Kzg, Polynomial, Srs};
+//!  use redox_ecc::pairing::{Bls12381, PairingEngine};
+//!
+//!  let e = Bls12381::new();
+//!  let kzg = Kzg::new(Srs::random(3, 1, &mut OsRng));
+//!  let p = Polynomial::new((1..=4).map(|i| e.new_scalar(BigInt::from(i))).collect());
+//!  let c = kzg.commit(&p).unwrap();
+//!  let z = e.new_scalar(BigInt::from(5));
+//!  let (y, proof) = kzg.open(&p, &z).unwrap();
+//!  assert!(kzg.verify(&c, &z, &y, &proof).is_ok());
+//! ```
+
+use num_bigint::BigInt;
+use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha512;
+
+use std::io::Error;
+
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode, PointFormat};
+use crate::group::msm::msm;
+use crate::pairing::{Bls12381, G2Point, PairingEngine};
+use crate::weierstrass::{Point, Scalar};
+
+/// Polynomial is a polynomial over the scalars of BLS12-381, whose
+/// coefficients are ordered from the constant term.
+#[derive(Clone)]
+pub struct Polynomial(Vec<Scalar>);
+
+impl Polynomial {
+    pub fn new(coefs: Vec<Scalar>) -> Polynomial {
+        Polynomial(coefs)
+    }
+    pub fn coefficients(&self) -> &[Scalar] {
+        &self.0
+    }
+    /// Evaluates the polynomial at `z` using Horner's rule.
+    pub fn eval(&self, z: &Scalar) -> Scalar {
+        self.0.iter().rev().fold(zero(z), |acc, a| acc * z + a)
+    }
+    /// Returns the quotient and the remainder of the division by `X - z`,
+    /// where the remainder is the value at `z`.
+    fn div_linear(&self, z: &Scalar) -> (Polynomial, Scalar) {
+        let mut rem = zero(z);
+        let mut q = Vec::with_capacity(self.0.len());
+        for a in self.0.iter().rev() {
+            rem = rem * z + a;
+            q.push(rem.clone());
+        }
+        let rem = q.pop().unwrap_or(rem);
+        q.reverse();
+        (Polynomial(q), rem)
+    }
+    /// Returns the quotient of the division by the monic polynomial `d`.
+    fn div_monic(&self, d: &Polynomial) -> Polynomial {
+        let n = d.0.len() - 1;
+        if self.0.len() <= n {
+            return Polynomial(Vec::new());
+        }
+        let mut rem = self.0.clone();
+        let mut q = vec![zero(&d.0[0]); rem.len() - n];
+        for i in (0..q.len()).rev() {
+            q[i] = rem[i + n].clone();
+            for (j, dj) in d.0[..n].iter().enumerate() {
+                rem[i + j] = &rem[i + j] - &(&q[i] * dj);
+            }
+        }
+        Polynomial(q)
+    }
+    /// Returns the difference of the polynomials.
+    fn sub(&self, other: &Polynomial) -> Polynomial {
+        let len = self.0.len().max(other.0.len());
+        Polynomial(
+            (0..len)
+                .map(|i| match (self.0.get(i), other.0.get(i)) {
+                    (Some(a), Some(b)) => a - b,
+                    (Some(a), None) => a.clone(),
+                    (None, Some(b)) => -b,
+                    (None, None) => unreachable!(),
+                })
+                .collect(),
+        )
+    }
+    /// Returns the monic polynomial whose roots are `zs`.
+    fn vanishing(one: &Scalar, zs: &[Scalar]) -> Polynomial {
+        let mut out = vec![one.clone()];
+        for z in zs.iter() {
+            out.insert(0, zero(z));
+            for i in 0..out.len() - 1 {
+                let t = &out[i + 1] * z;
+                out[i] = &out[i] - &t;
+            }
+        }
+        Polynomial(out)
+    }
+    /// Returns the polynomial of degree less than `zs.len()` whose value at
+    /// `zs[i]` is `ys[i]`. It fails if the points are not distinct.
+    fn interpolate(one: &Scalar, zs: &[Scalar], ys: &[Scalar]) -> Result<Polynomial, Error> {
+        let mut out = Polynomial(vec![zero(one); zs.len()]);
+        for (i, (zi, yi)) in zs.iter().zip(ys).enumerate() {
+            let others: Vec<Scalar> = [&zs[..i], &zs[i + 1..]].concat();
+            let basis = Polynomial::vanishing(one, &others);
+            let den = basis.eval(zi);
+            if is_zero(&den) {
+                return Err(Error::other(ERR_DUPLICATE));
+            }
+            let s = yi / den;
+            for (c, b) in out.0.iter_mut().zip(basis.0.iter()) {
+                *c = &*c + &(&s * b);
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Srs is a structured reference string, made of the powers `[tau^i]G1` for
+/// `i` up to the maximum degree of the polynomials and the powers
+/// `[tau^j]G2` for `j` up to the maximum size of a batch opening.
+#[derive(Clone)]
+pub struct Srs {
+    g1: Vec<Point>,
+    g2: Vec<G2Point>,
+}
+
+impl Srs {
+    /// Returns the SRS with trapdoor `tau` for polynomials of degree up to
+    /// `d` and batches of up to `t` points.
+    pub fn from_secret(tau: &Scalar, d: usize, t: usize) -> Srs {
+        let e = Bls12381::new();
+        Srs {
+            g1: powers(e.g1_generator(), tau, d.max(1) + 1),
+            g2: powers(e.g2_generator(), tau, t.max(1) + 1),
+        }
+    }
+    /// Returns an SRS with a random trapdoor, which is discarded.
+    pub fn random<R: RngCore + CryptoRng>(d: usize, t: usize, rng: &mut R) -> Srs {
+        let tau = Bls12381::new().g1().random_scalar(rng);
+        Srs::from_secret(&tau, d, t)
+    }
+    /// Parses an SRS encoded as the number of powers in G1 in four bytes,
+    /// followed by the compressed powers in G1 and then in G2. It fails
+    /// unless the powers start at the generators and both sequences are
+    /// powers of the same non-zero trapdoor, which is checked with a random
+    /// linear combination of them.
+    pub fn from_bytes(buf: &[u8]) -> Result<Srs, Error> {
+        let e = Bls12381::new();
+        if buf.len() < COUNT_SIZE {
+            return Err(Error::other(ERR_ENCODING));
+        }
+        let (count, rest) = buf.split_at(COUNT_SIZE);
+        let n = u32::from_be_bytes([count[0], count[1], count[2], count[3]]) as usize;
+        if rest.len() < n * G1_SIZE || !(rest.len() - n * G1_SIZE).is_multiple_of(G2_SIZE) {
+            return Err(Error::other(ERR_ENCODING));
+        }
+        let (g1, g2) = rest.split_at(n * G1_SIZE);
+        let srs = Srs {
+            g1: g1
+                .chunks(G1_SIZE)
+                .map(|c| e.decode_g1(c))
+                .collect::<Result<_, _>>()?,
+            g2: g2
+                .chunks(G2_SIZE)
+                .map(|c| e.g2().decode(c))
+                .collect::<Result<_, _>>()?,
+        };
+        if srs.g1.len() < 2 || srs.g2.len() < 2 {
+            return Err(Error::other(ERR_ENCODING));
+        }
+        if srs.g1[0] != e.g1_generator() || srs.g2[0] != e.g2_generator() || srs.g1[1].is_zero() {
+            return Err(Error::other(ERR_SRS));
+        }
+        let rho = Scalar::from_hash::<Sha512>(buf, &e.g1().get_order());
+        let r = powers(
+            e.new_scalar(BigInt::from(1)),
+            &rho,
+            srs.g1.len().max(srs.g2.len()),
+        );
+        // [tau]*sum(r_i [tau^i]G1) = sum(r_i [tau^(i+1)]G1)
+        let n = srs.g1.len() - 1;
+        let a = msm(&e.g1().identity(), &srs.g1[..n], &r[..n]);
+        let b = msm(&e.g1().identity(), &srs.g1[1..], &r[..n]);
+        if !e
+            .multi_pairing(&[(b, srs.g2[0].clone()), (-a, srs.g2[1].clone())])
+            .is_identity()
+        {
+            return Err(Error::other(ERR_SRS));
+        }
+        // [tau]*sum(r_j [tau^j]G2) = sum(r_j [tau^(j+1)]G2)
+        let m = srs.g2.len() - 1;
+        let c = msm(&e.g2().identity(), &srs.g2[..m], &r[..m]);
+        let d = msm(&e.g2().identity(), &srs.g2[1..], &r[..m]);
+        if !e
+            .multi_pairing(&[(srs.g1[0].clone(), d), (-srs.g1[1].clone(), c)])
+            .is_identity()
+        {
+            return Err(Error::other(ERR_SRS));
+        }
+        Ok(srs)
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let e = Bls12381::new();
+        let mut out = (self.g1.len() as u32).to_be_bytes().to_vec();
+        for p in self.g1.iter() {
+            out.append(&mut e.encode_g1(p, PointFormat::Compressed));
+        }
+        for q in self.g2.iter() {
+            out.append(&mut q.encode(PointFormat::Compressed));
+        }
+        out
+    }
+    /// Returns the maximum degree of the polynomials.
+    pub fn max_degree(&self) -> usize {
+        self.g1.len() - 1
+    }
+    /// Returns the maximum number of points of a batch opening.
+    pub fn max_batch(&self) -> usize {
+        (self.g2.len() - 1).min(self.g1.len())
+    }
+}
+
+/// Commitment is the commitment to a polynomial, i.e., a point of G1.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Commitment(Point);
+
+impl Commitment {
+    /// Parses a 48-byte commitment. It fails for points outside of G1.
+    pub fn from_bytes(buf: &[u8]) -> Result<Commitment, Error> {
+        decode_g1(buf).map(Commitment)
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Bls12381::new().encode_g1(&self.0, PointFormat::Compressed)
+    }
+    pub fn as_point(&self) -> &Point {
+        &self.0
+    }
+}
+
+/// Proof is the proof of an opening or of a batch opening, i.e., a point of
+/// G1.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Proof(Point);
+
+impl Proof {
+    /// Parses a 48-byte proof. It fails for points outside of G1.
+    pub fn from_bytes(buf: &[u8]) -> Result<Proof, Error> {
+        decode_g1(buf).map(Proof)
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Bls12381::new().encode_g1(&self.0, PointFormat::Compressed)
+    }
+    pub fn as_point(&self) -> &Point {
+        &self.0
+    }
+}
+
+/// Kzg commits to polynomials and opens the commitments with the powers of
+/// an SRS.
+pub struct Kzg {
+    e: Bls12381,
+    srs: Srs,
+}
+
+impl Kzg {
+    pub fn new(srs: Srs) -> Kzg {
+        Kzg {
+            e: Bls12381::new(),
+            srs,
+        }
+    }
+    pub fn srs(&self) -> &Srs {
+        &self.srs
+    }
+    /// Returns the commitment to `p`. It fails if the degree of `p` exceeds
+    /// that of the SRS.
+    pub fn commit(&self, p: &Polynomial) -> Result<Commitment, Error> {
+        if p.0.len() > self.srs.g1.len() {
+            return Err(Error::other(ERR_DEGREE));
+        }
+        let g1 = &self.srs.g1[..p.0.len()];
+        Ok(Commitment(msm(&self.e.g1().identity(), g1, &p.0)))
+    }
+    /// Returns the value `y = p(z)` and a proof of it, which is the
+    /// commitment to `(p - y) / (X - z)`.
+    pub fn open(&self, p: &Polynomial, z: &Scalar) -> Result<(Scalar, Proof), Error> {
+        if p.0.len() > self.srs.g1.len() {
+            return Err(Error::other(ERR_DEGREE));
+        }
+        let (q, y) = p.div_linear(z);
+        Ok((y, Proof(self.commit(&q)?.0)))
+    }
+    /// Verifies that `y` is the value at `z` of the polynomial committed in
+    /// `c`, i.e., that `e(C - [y]G1 + [z]proof, G2) = e(proof, [tau]G2)`.
+    pub fn verify(
+        &self,
+        c: &Commitment,
+        z: &Scalar,
+        y: &Scalar,
+        proof: &Proof,
+    ) -> Result<(), Error> {
+        let p = &c.0 - &(self.e.g1_generator() * y) + &proof.0 * z;
+        self.check(&[
+            (p, self.srs.g2[0].clone()),
+            (-proof.0.clone(), self.srs.g2[1].clone()),
+        ])
+    }
+    /// Returns the values of `p` at the distinct points `zs` and a single
+    /// proof of all of them, which is the commitment to `(p - I) / Z`, where
+    /// `I` interpolates the values and `Z` vanishes at `zs`.
+    pub fn open_batch(&self, p: &Polynomial, zs: &[Scalar]) -> Result<(Vec<Scalar>, Proof), Error> {
+        self.check_batch(zs)?;
+        if p.0.len() > self.srs.g1.len() {
+            return Err(Error::other(ERR_DEGREE));
+        }
+        let ys: Vec<Scalar> = zs.iter().map(|z| p.eval(z)).collect();
+        let one = self.e.new_scalar(BigInt::from(1));
+        let i = Polynomial::interpolate(&one, zs, &ys)?;
+        let q = p.sub(&i).div_monic(&Polynomial::vanishing(&one, zs));
+        Ok((ys, Proof(self.commit(&q)?.0)))
+    }
+    /// Verifies that `ys[i]` is the value at `zs[i]` of the polynomial
+    /// committed in `c`, i.e., that `e(C - [I(tau)]G1, G2) = e(proof,
+    /// [Z(tau)]G2)`.
+    pub fn verify_batch(
+        &self,
+        c: &Commitment,
+        zs: &[Scalar],
+        ys: &[Scalar],
+        proof: &Proof,
+    ) -> Result<(), Error> {
+        self.check_batch(zs)?;
+        if zs.len() != ys.len() {
+            return Err(Error::other(ERR_VALUE_COUNT));
+        }
+        let one = self.e.new_scalar(BigInt::from(1));
+        let i = self.commit(&Polynomial::interpolate(&one, zs, ys)?)?;
+        let z = Polynomial::vanishing(&one, zs);
+        let g2 = &self.srs.g2[..z.0.len()];
+        let z = msm(&self.e.g2().identity(), g2, &z.0);
+        self.check(&[(&c.0 - &i.0, self.srs.g2[0].clone()), (-proof.0.clone(), z)])
+    }
+    fn check_batch(&self, zs: &[Scalar]) -> Result<(), Error> {
+        if zs.is_empty() {
+            return Err(Error::other(ERR_EMPTY));
+        }
+        if zs.len() > self.srs.max_batch() {
+            return Err(Error::other(ERR_BATCH));
+        }
+        Ok(())
+    }
+    fn check(&self, pairs: &[(Point, G2Point)]) -> Result<(), Error> {
+        if !self.e.multi_pairing(pairs).is_identity() {
+            return Err(Error::other(ERR_PROOF));
+        }
+        Ok(())
+    }
+}
+
+/// Returns `[g, tau*g, ..., tau^(n-1)*g]`.
+fn powers<T>(g: T, tau: &Scalar, n: usize) -> Vec<T>
+where
+    T: Clone + for<'a> std::ops::Mul<&'a Scalar, Output = T>,
+{
+    let mut out = Vec::with_capacity(n);
+    let mut p = g;
+    for _ in 0..n {
+        out.push(p.clone());
+        p = p * tau;
+    }
+    out
+}
+
+fn decode_g1(buf: &[u8]) -> Result<Point, Error> {
+    if buf.len() != G1_SIZE {
+        return Err(Error::other(ERR_ENCODING));
+    }
+    Bls12381::new().decode_g1(buf)
+}
+
+fn zero(like: &Scalar) -> Scalar {
+    Scalar {
+        k: BigInt::zero(),
+        r: like.r.clone(),
+    }
+}
+
+fn is_zero(k: &Scalar) -> bool {
+    k.k.is_zero()
+}
+
+const COUNT_SIZE: usize = 4;
+const G1_SIZE: usize = 48;
+const G2_SIZE: usize = 96;
+const ERR_ENCODING: &str = "invalid encoding";
+const ERR_SRS: &str = "malformed structured reference string";
+const ERR_DEGREE: &str = "degree of the polynomial is too large";
+const ERR_BATCH: &str = "too many points in a batch opening";
+const ERR_EMPTY: &str = "no points to open";
+const ERR_VALUE_COUNT: &str = "number of points and values differ";
+const ERR_DUPLICATE: &str = "points of a batch opening must be distinct";
+const ERR_PROOF: &str = "invalid proof";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand_core::OsRng;
+
+    use crate::pairing::{Bls12381, PairingEngine};
+    use crate::weierstrass::Scalar;
+
+    use super::{Commitment, Kzg, Polynomial, Proof, Srs};
+
+    fn scalars(e: &Bls12381, v: &[i64]) -> Vec<Scalar> {
+        v.iter().map(|&i| e.new_scalar(BigInt::from(i))).collect()
+    }
+
+    #[test]
+    fn polynomials() {
+        let e = Bls12381::new();
+        // p = 3 + 2X - X^3, whose values at 0, 1, 2 are 3, 4, -1
+        let p = Polynomial::new(scalars(&e, &[3, 2, 0, -1]));
+        let zs = scalars(&e, &[0, 1, 2]);
+        let ys: Vec<Scalar> = zs.iter().map(|z| p.eval(z)).collect();
+        assert!(ys == scalars(&e, &[3, 4, -1]));
+
+        let (q, y) = p.div_linear(&zs[2]);
+        assert!(y == ys[2]);
+        assert!(q.coefficients() == scalars(&e, &[-2, -2, -1]).as_slice());
+
+        let one = e.new_scalar(BigInt::from(1));
+        let z = Polynomial::vanishing(&one, &zs);
+        assert!(z.coefficients() == scalars(&e, &[0, 2, -3, 1]).as_slice());
+        let i = Polynomial::interpolate(&one, &zs, &ys).unwrap();
+        assert!(i.coefficients() == scalars(&e, &[3, 4, -3]).as_slice());
+        assert!(p.sub(&i).div_monic(&z).coefficients() == scalars(&e, &[-1]).as_slice());
+        assert!(Polynomial::interpolate(&one, &zs[..2], &zs[..2]).is_ok());
+        assert!(Polynomial::interpolate(&one, &[one.clone(), one.clone()], &ys[..2]).is_err());
+    }
+
+    #[test]
+    fn commit_open() {
+        let e = Bls12381::new();
+        let tau = e.new_scalar(BigInt::from(1234567));
+        let kzg = Kzg::new(Srs::from_secret(&tau, 4, 2));
+        let p = Polynomial::new(scalars(&e, &[7, 0, -5, 1, 2]));
+        let c = kzg.commit(&p).unwrap();
+        assert!(c.as_point() == &(e.g1_generator() * &p.eval(&tau)));
+        let c = Commitment::from_bytes(&c.to_bytes()).unwrap();
+
+        let z = e.new_scalar(BigInt::from(-3));
+        let (y, proof) = kzg.open(&p, &z).unwrap();
+        let proof = Proof::from_bytes(&proof.to_bytes()).unwrap();
+        assert!(kzg.verify(&c, &z, &y, &proof).is_ok());
+        assert!(kzg.verify(&c, &z, &(&y + &z), &proof).is_err());
+        assert!(kzg.verify(&c, &y, &y, &proof).is_err());
+
+        let long = Polynomial::new(scalars(&e, &[1; 6]));
+        assert!(kzg.commit(&long).is_err());
+        assert!(kzg.open(&long, &z).is_err());
+    }
+
+    #[test]
+    fn batch_open() {
+        let e = Bls12381::new();
+        let kzg = Kzg::new(Srs::random(4, 2, &mut OsRng));
+        let p = Polynomial::new(scalars(&e, &[7, 0, -5, 1, 2]));
+        let c = kzg.commit(&p).unwrap();
+        let zs = scalars(&e, &[10, 20]);
+        let (ys, proof) = kzg.open_batch(&p, &zs).unwrap();
+        assert!(ys == vec![p.eval(&zs[0]), p.eval(&zs[1])]);
+        assert!(kzg.verify_batch(&c, &zs, &ys, &proof).is_ok());
+        assert!(kzg
+            .verify_batch(&c, &zs, &[ys[1].clone(), ys[0].clone()], &proof)
+            .is_err());
+        assert!(kzg.verify_batch(&c, &zs, &ys[..1], &proof).is_err());
+
+        assert!(kzg.open_batch(&p, &[]).is_err());
+        assert!(kzg.open_batch(&p, &scalars(&e, &[1, 2, 3])).is_err());
+        assert!(kzg.open_batch(&p, &scalars(&e, &[1, 1])).is_err());
+    }
+
+    #[test]
+    fn srs_encoding() {
+        let e = Bls12381::new();
+        let srs = Srs::random(2, 1, &mut OsRng);
+        assert_eq!(srs.max_degree(), 2);
+        assert_eq!(srs.max_batch(), 1);
+        let buf = srs.to_bytes();
+        let got = Srs::from_bytes(&buf).unwrap();
+        assert_eq!(got.to_bytes(), buf);
+
+        // swapping two powers breaks their sequence
+        let mut bad = buf.clone();
+        let (a, b) = (4 + 48, 4 + 2 * 48);
+        let second = bad[a..b].to_vec();
+        bad.copy_within(b..b + 48, a);
+        bad[b..b + 48].copy_from_slice(&second);
+        assert!(Srs::from_bytes(&bad).is_err());
+
+        // the powers must start at the generators
+        let other = Srs::from_secret(&e.new_scalar(BigInt::from(2)), 2, 1);
+        let mut bad = other.to_bytes();
+        bad[4..4 + 48].copy_from_slice(&buf[4 + 48..4 + 2 * 48]);
+        assert!(Srs::from_bytes(&bad).is_err());
+
+        assert!(Srs::from_bytes(&buf[..buf.len() - 1]).is_err());
+        assert!(Srs::from_bytes(&[]).is_err());
+    }
+}
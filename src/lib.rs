@@ -36,6 +36,7 @@ extern crate impl_ops;
 mod macros;
 
 pub mod field;
+pub mod integer;
 pub mod ops;
 pub mod primefield;
 pub mod quadraticfield;
@@ -43,13 +44,59 @@ pub mod quadraticfield;
 pub mod ellipticcurve;
 
 pub mod edwards;
+pub mod fourq;
 pub mod montgomery;
 pub mod weierstrass;
 
 pub mod instances;
+pub mod keypair;
+pub mod secret;
 
+pub mod h2c;
+
+#[cfg(feature = "count-ops")]
+pub mod opcount;
+
+#[cfg(feature = "arbitrary")]
+pub mod fuzzing;
+#[cfg(feature = "test-utils")]
+pub mod testutils;
+
+pub mod group;
+pub mod pairing;
+
+pub mod adaptor;
+pub mod blindschnorr;
+pub mod bls;
+pub mod ecdsa;
+pub mod eddsa;
+pub mod musig2;
+pub mod schnorr;
+pub mod sec1;
+pub mod sm2;
+pub mod taproot;
+
+pub mod cpace;
+pub mod ecdh;
+pub mod xdh;
+
+pub mod ecies;
+pub mod kzg;
+pub mod oprf;
+pub mod pedersen;
+pub mod pedersenhash;
+pub mod ring;
+pub mod transcript;
+pub mod vss;
+
+#[cfg(all(test, feature = "differential-tests"))]
+mod differential;
 #[cfg(test)]
 mod tests;
+#[cfg(all(test, feature = "timing_tests"))]
+mod timing_tests;
+#[cfg(test)]
+mod wycheproof;
 
 /// Returns the version of the crate.
 pub fn version() -> &'static str {
@@ -9,7 +9,147 @@ macro_rules! do_if_eq {
         if $cond {
             $body
         } else {
-            panic!($error)
+            panic!("{}", $error)
         }
     };
 }
+
+/// Increments a counter of the `opcount` module, e.g., `count_op!(mul)`.
+/// It expands to nothing without the `count-ops` feature.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! count_op {
+    ($op:ident) => {
+        #[cfg(feature = "count-ops")]
+        $crate::opcount::tick(|c| c.$op += 1);
+    };
+}
+
+/// Evaluates an expression without counting its operations in the
+/// `opcount` module.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! uncounted {
+    ($e:expr) => {{
+        #[cfg(feature = "count-ops")]
+        let out = $crate::opcount::uncounted(|| $e);
+        #[cfg(not(feature = "count-ops"))]
+        let out = $e;
+        out
+    }};
+}
+
+/// Implements `Sum` or `Product` for a type, for both owned and borrowed
+/// items, by folding them with the operator `op`. The curve or the modulus
+/// of the result is taken from the first item, so it panics if the
+/// iterator is empty.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_iter_fold {
+    ($trait:ident, $method:ident, $type:ty, $op:tt) => {
+        impl std::iter::$trait for $type {
+            fn $method<I: Iterator<Item = $type>>(mut iter: I) -> $type {
+                let first = iter.next().expect("cannot fold an empty iterator");
+                iter.fold(first, |acc, x| acc $op x)
+            }
+        }
+        impl<'a> std::iter::$trait<&'a $type> for $type {
+            fn $method<I: Iterator<Item = &'a $type>>(mut iter: I) -> $type {
+                let first = iter.next().expect("cannot fold an empty iterator");
+                iter.fold(first.clone(), |acc, x| acc $op x)
+            }
+        }
+    };
+}
+
+/// Writes an `Integer` of the `integer` module, whose bytes are computed at
+/// compile time from a decimal string, or a hex string prefixed by `0x`. Two
+/// strings give the pair of integers of an element of a quadratic field.
+#[macro_export]
+macro_rules! int {
+    ($s:expr) => {{
+        const S: &str = $s;
+        const MAG: [u8; $crate::integer::byte_len(S)] = $crate::integer::parse(S);
+        $crate::integer::Integer::new(S, &MAG)
+    }};
+    ($s0:expr, $s1:expr) => {
+        [$crate::int!($s0), $crate::int!($s1)]
+    };
+}
+
+/// Defines a Weierstrass curve from its parameters, which can be written in
+/// decimal or in hex with the prefix `0x`. It expands to a static
+/// `WeCurveID`, so curves can be added without editing the crate:
+///
+/// ```
+///  use redox_ecc::define_curve;
+///  use redox_ecc::instances::{GetCurve, SECP256K1};
+///
+///  define_curve! {
+///      /// K256 is secp256k1 written in hex.
+///      pub static K256 {
+///          name: "k256",
+///          p: "0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+///          a: "0",
+///          b: "7",
+///          gx: "0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+///          gy: "0x483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+///          r: "0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+///          h: "1",
+///      }
+///  }
+///
+///  K256.self_test().unwrap();
+///  assert!(K256.get() == SECP256K1.get());
+/// ```
+///
+/// Two optional fields follow `h`: `kat`, the compressed encoding of `[k]G`
+/// checked by `WeCurveID::self_test`, and `test`, the name of a `#[test]`
+/// function to generate that runs the self-test of the curve.
+#[macro_export]
+macro_rules! define_curve {
+    (
+        $(#[$meta:meta])*
+        $vis:vis static $id:ident {
+            name: $name:expr,
+            p: $p:expr,
+            a: $a:expr,
+            b: $b:expr,
+            gx: $gx:expr,
+            gy: $gy:expr,
+            r: $r:expr,
+            h: $h:expr
+            $(, kat: $kat:expr)?
+            $(, test: $test:ident)?
+            $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis static $id: $crate::instances::WeCurveID = {
+            static PARAMS: $crate::weierstrass::Params = $crate::weierstrass::Params {
+                name: $name,
+                p: $crate::int!($p),
+                a: $crate::int!($a),
+                b: $crate::int!($b),
+                r: $crate::int!($r),
+                h: $crate::int!($h),
+                gx: $crate::int!($gx),
+                gy: $crate::int!($gy),
+            };
+            $crate::instances::WeCurveID::new(&PARAMS, $crate::define_curve!(@kat $($kat)?))
+        };
+        $(
+            #[cfg(test)]
+            #[test]
+            fn $test() {
+                $id.self_test().unwrap();
+            }
+        )?
+    };
+    (@kat) => {
+        ""
+    };
+    (@kat $kat:expr) => {
+        $kat
+    };
+}
@@ -3,41 +3,288 @@
 //! The curve module is meant to be used for bar.
 
 extern crate num_bigint;
-use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
+use num_bigint::{BigInt, BigUint, ToBigInt};
 
 use num_traits::identities::Zero;
+use subtle::ConstantTimeEq;
 
-use std::io::{Error, ErrorKind};
-use std::str::FromStr;
+use std::io::Error;
+use std::sync::Arc;
 
-use crate::do_if_eq;
-use crate::ellipticcurve::{Decode, EllipticCurve};
+use crate::ellipticcurve::{Decode, EllipticCurve, PointFormat};
 use crate::field::{Field, Sgn0, Sqrt};
-use crate::montgomery::point::{Point, ProyCoordinates};
+use crate::integer::Integer;
+use crate::montgomery::point::{Point, ProyCoordinates, XZCoordinates};
 use crate::montgomery::scalar::Scalar;
 use crate::ops::FromFactory;
 use crate::primefield::{Fp, FpElt};
+use crate::{do_if_eq, uncounted};
 
 /// This is an elliptic curve defined in Montgomery from and defined by the equation:
 /// by^2=x^3+ax^2+x.
 ///
-#[derive(Clone, PartialEq)]
-pub struct Curve {
-    f: Fp,
-    pub(super) a: FpElt,
-    pub(super) b: FpElt,
-    pub(super) s: FpElt,
-    pub(super) r: BigUint,
-    pub(super) gx: FpElt,
-    pub(super) gy: FpElt,
-    pub(super) h: BigUint,
+#[derive(Clone)]
+pub struct Curve(pub(crate) Arc<CurveInner>);
+
+/// CurveInner holds the parameters of a curve, which are shared by its points
+/// instead of being copied into each of them.
+pub struct CurveInner {
+    pub(crate) name: &'static str,
+    pub(crate) f: Fp,
+    pub(crate) a: FpElt,
+    pub(crate) b: FpElt,
+    pub(crate) s: FpElt,
+    pub(crate) r: BigUint,
+    pub(crate) gx: FpElt,
+    pub(crate) gy: FpElt,
+    pub(crate) h: BigUint,
+    // constants computed once for the formulas, the decoders, and the ladder
+    pub(crate) zero: FpElt,
+    pub(crate) one: FpElt,
+    pub(crate) inv_b: FpElt,
+    pub(crate) a24: FpElt,
+}
+
+impl std::ops::Deref for Curve {
+    type Target = CurveInner;
+    fn deref(&self) -> &CurveInner {
+        &self.0
+    }
+}
+
+/// Curves are compared by their parameters, regardless of their names.
+impl PartialEq for Curve {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+            || (self.f == other.f
+                && self.a == other.a
+                && self.b == other.b
+                && self.s == other.s
+                && self.r == other.r
+                && self.gx == other.gx
+                && self.gy == other.gy
+                && self.h == other.h)
+    }
 }
 
 impl Curve {
+    /// Returns the name given in the parameters of the curve, which is empty
+    /// for curves derived from other curves.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// Returns the coefficient `a` of the equation `by^2=x^3+ax^2+x`.
+    pub fn a(&self) -> &FpElt {
+        &self.a
+    }
+    /// Returns the coefficient `b` of the equation `by^2=x^3+ax^2+x`.
+    pub fn b(&self) -> &FpElt {
+        &self.b
+    }
+    /// Returns the element `s` used by the complete addition law of `Point`,
+    /// such that a curve with b=1 has no points with y=s.
+    pub fn s(&self) -> &FpElt {
+        &self.s
+    }
     pub(crate) fn new_proy_point(&self, c: ProyCoordinates) -> Point {
         let e = self.clone();
         let pt = Point { e, c };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        do_if_eq!(uncounted!(self.is_on_curve(&pt)), pt, ERR_ECC_NEW)
+    }
+    /// Returns the smallest positive integer `s` such that a curve with b=1
+    /// has no points with y=s. The addition law of `Point` is complete
+    /// whenever `s` has this property.
+    pub(crate) fn find_s(f: &Fp, a: &FpElt) -> FpElt {
+        let mut s = f.one();
+        while Curve::has_points_with_y(f, a, &s) {
+            s += f.one();
+        }
+        s
+    }
+    /// Determines whether the cubic x^3+ax^2+x-s^2 has roots in the field.
+    /// The cubic has zero or three roots if, and only if, its discriminant
+    /// is a non-zero square, and it has three roots if, and only if,
+    /// x^p = x modulo the cubic.
+    fn has_points_with_y(f: &Fp, a: &FpElt, s: &FpElt) -> bool {
+        let s2 = s ^ 2u32;
+        let a2 = a ^ 2u32;
+        let disc: FpElt = (f.from(4) * &a2 * a - f.from(18) * a) * &s2 + &a2
+            - f.from(4)
+            - f.from(27) * (&s2 ^ 2u32);
+        if disc.is_zero() || !disc.is_square() {
+            return true;
+        }
+        let cubic = [-&s2, f.one(), a.clone()];
+        let mul_mod = |u: &[FpElt], v: &[FpElt]| -> Vec<FpElt> {
+            let mut w = vec![f.zero(); 5];
+            for i in 0..3 {
+                for j in 0..3 {
+                    w[i + j] = &w[i + j] + &u[i] * &v[j];
+                }
+            }
+            for k in (3..5).rev() {
+                let t = w[k].clone();
+                for i in 0..3 {
+                    w[k - 3 + i] = &w[k - 3 + i] - &t * &cubic[i];
+                }
+            }
+            w.truncate(3);
+            w
+        };
+        let x = vec![f.zero(), f.one(), f.zero()];
+        let mut xp = vec![f.one(), f.zero(), f.zero()];
+        for bit in f.get_modulus().to_str_radix(2).chars() {
+            xp = mul_mod(&xp, &xp);
+            if bit == '1' {
+                xp = mul_mod(&xp, &x);
+            }
+        }
+        xp == x
+    }
+    /// Decompresses a point encoded with `encode(PointFormat::Compressed)` without branching on
+    /// the sign of the y-coordinate, which matters when the point is derived
+    /// from secret data. The square root is always computed and checked
+    /// with `ct_eq`, and the root is fixed with a conditional negation. Only
+    /// the validity of the encoding is leaked; unlike `decode`, uncompressed
+    /// points and the point at infinity are rejected.
+    pub fn decode_ct(&self, buf: &[u8]) -> Result<Point, Error> {
+        let size = self.f.size_bytes();
+        if buf.len() != size + 1 {
+            return Err(Error::other("Wrong input buffer size."));
+        }
+        let tag = buf[0];
+        if tag & 0xFE != 0x02 {
+            return Err(Error::other("Invalid tag specified"));
+        }
+        let x = self.f.from_canonical_be(&buf[1..])?;
+        let yy = (&x * &x + &self.a * &x + &self.one) * &x * &self.inv_b;
+        let mut y = yy.sqrt();
+        let valid = (&y * &y).ct_eq(&yy);
+        let sign = ((1 - y.sgn0()) / 2) as u8; // parity of y
+        y.conditional_negate(!(tag & 0x01).ct_eq(&sign));
+        if !bool::from(valid) {
+            return Err(Error::other("Invalid x coordinate"));
+        }
+        Ok(self.new_point(x, y))
+    }
+    /// Recovers the point `Q = [k]P` from the x-coordinates of `q = [k]P`
+    /// and `r = [k+1]P` computed by an x-only Montgomery ladder, and from
+    /// the full point `P`. It returns an error if the result is not on the
+    /// curve, which happens when `r` is not the x-coordinate of `Q + P`.
+    // Reference: "Montgomery curves and their arithmetic" by Costello-Smith.
+    // [Alg.5] (eprint.iacr.org/2017/212), based on Okeya-Sakurai.
+    pub fn recover_y(
+        &self,
+        p: &Point,
+        q: &XZCoordinates,
+        r: &XZCoordinates,
+    ) -> Result<Point, Error> {
+        let (xp, yp) = match p.to_affine() {
+            Some(c) => c,
+            None => return Ok(self.identity()),
+        };
+        if q.z.is_zero() {
+            return Ok(self.identity());
+        }
+        if r.z.is_zero() {
+            return Ok(-p);
+        }
+        let (xq, zq, xr, zr) = (&q.x, &q.z, &r.x, &r.z);
+        let mut v1 = &xp * zq; //             1. v1 = xP * ZQ
+        let mut v2 = xq + &v1; //             2. v2 = XQ + v1
+        let mut v3 = xq - &v1; //             3. v3 = XQ - v1
+        v3.square_assign(); //                4. v3 = v3^2
+        v3 *= xr; //                          5. v3 = v3 * XR
+        v1 = &(&self.a + &self.a) * zq; //    6. v1 = 2A * ZQ
+        v2 += &v1; //                         7. v2 = v2 + v1
+        let v4 = xp.mul_add(xq, zq); //       8. v4 = xP * XQ, 9. v4 = v4 + ZQ
+        v2 *= &v4; //                        10. v2 = v2 * v4
+        v1 *= zq; //                         11. v1 = v1 * ZQ
+        v2 -= &v1; //                        12. v2 = v2 - v1
+        v2 *= zr; //                         13. v2 = v2 * ZR
+        let y = v2 - v3; //                  14. Y' = v2 - v3
+        v1 = &(&self.b + &self.b) * &yp; //  15. v1 = 2B * yP
+        v1 *= zq; //                         16. v1 = v1 * ZQ
+        v1 *= zr; //                         17. v1 = v1 * ZR
+        let x = &v1 * xq; //                 18. X' = v1 * XQ
+        let z = v1 * zq; //                  19. Z' = v1 * ZQ
+        let pt = Point {
+            e: self.clone(),
+            c: ProyCoordinates { x, y, z },
+        };
+        if pt.c.z.is_zero() || !self.is_on_curve(&pt) {
+            return Err(Error::other(ERR_RECOVER_Y));
+        }
+        Ok(pt)
+    }
+    /// Returns `[2]P` given `p = x(P)`. This is the xDBL function, which
+    /// takes 2M + 2S + 1m_a24, where `a24 = (A-2)/4`.
+    // Reference: "Montgomery curves and their arithmetic" by Costello-Smith.
+    // [Sec.2.2] (eprint.iacr.org/2017/212).
+    pub fn xdbl(&self, p: &XZCoordinates) -> XZCoordinates {
+        let mut aa = &p.x + &p.z; //   AA = (X + Z)^2
+        aa.square_assign();
+        let mut bb = &p.x - &p.z; //   BB = (X - Z)^2
+        bb.square_assign();
+        let e = &aa - &bb; //          E = AA - BB
+        let z = self.a24.mul_add(&e, &aa) * &e; // Z2 = E * (AA + a24 * E)
+        let x = aa * bb; //            X2 = AA * BB
+        XZCoordinates { x, z }
+    }
+    /// Returns `P+Q` given `p = x(P)`, `q = x(Q)`, and `diff = x(P-Q)`,
+    /// which must not be the point at infinity. This is the xADD function,
+    /// a differential addition that takes 4M + 2S.
+    // Reference: "Montgomery curves and their arithmetic" by Costello-Smith.
+    // [Sec.2.2] (eprint.iacr.org/2017/212).
+    pub fn xadd(
+        &self,
+        p: &XZCoordinates,
+        q: &XZCoordinates,
+        diff: &XZCoordinates,
+    ) -> XZCoordinates {
+        let u = (&p.x - &p.z) * (&q.x + &q.z); // U = (XP - ZP) * (XQ + ZQ)
+        let v = (&p.x + &p.z) * (&q.x - &q.z); // V = (XP + ZP) * (XQ - ZQ)
+        let mut s = &u + &v; //                   X = Z- * (U + V)^2
+        s.square_assign();
+        let mut t = u - v; //                     Z = X- * (U - V)^2
+        t.square_assign();
+        XZCoordinates {
+            x: s * &diff.z,
+            z: t * &diff.x,
+        }
+    }
+    /// Returns `([2]R0, R0+R1)` given `r0 = x(R0)`, `r1 = x(R1)`, and
+    /// `p = x(R1-R0)`. This is one step of the Montgomery ladder, which
+    /// keeps the invariant `R1 - R0 = P` for any choice of the operands.
+    pub fn ladder_step(
+        &self,
+        p: &XZCoordinates,
+        r0: &XZCoordinates,
+        r1: &XZCoordinates,
+    ) -> (XZCoordinates, XZCoordinates) {
+        (self.xdbl(r0), self.xadd(r0, r1, p))
+    }
+    /// Returns `(x([k]P), x([k+1]P))` given `p = x(P)`, which can be passed
+    /// to `recover_y` to get the point `[k]P`. The ladder branches on the
+    /// bits of `k`, so it runs in variable time.
+    pub fn ladder(&self, p: &XZCoordinates, k: &Scalar) -> (XZCoordinates, XZCoordinates) {
+        let mut r0 = XZCoordinates {
+            x: self.one.clone(),
+            z: self.zero.clone(),
+        };
+        let mut r1 = p.clone();
+        for ki in k.iter_lr() {
+            if ki {
+                let (s1, s0) = self.ladder_step(p, &r1, &r0);
+                r0 = s0;
+                r1 = s1;
+            } else {
+                let (s0, s1) = self.ladder_step(p, &r0, &r1);
+                r0 = s0;
+                r1 = s1;
+            }
+        }
+        (r0, r1)
     }
 }
 
@@ -47,22 +294,24 @@ impl EllipticCurve for Curve {
     type Point = Point;
     fn new_point(&self, x: <Self::F as Field>::Elt, y: <Self::F as Field>::Elt) -> Self::Point {
         let e = self.clone();
-        let f = e.get_field();
         let pt = Point {
-            c: ProyCoordinates { x, y, z: f.one() },
+            c: ProyCoordinates {
+                x,
+                y,
+                z: self.one.clone(),
+            },
             e,
         };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        do_if_eq!(uncounted!(self.is_on_curve(&pt)), pt, ERR_ECC_NEW)
     }
     fn new_scalar(&self, k: BigInt) -> Self::Scalar {
         Scalar::new(k, &self.r)
     }
     fn identity(&self) -> Self::Point {
-        let f = &self.f;
         self.new_proy_point(ProyCoordinates {
-            x: f.zero(),
-            y: f.one(),
-            z: f.zero(),
+            x: self.zero.clone(),
+            y: self.one.clone(),
+            z: self.zero.clone(),
         })
     }
     fn is_on_curve(&self, p: &Self::Point) -> bool {
@@ -85,68 +334,65 @@ impl EllipticCurve for Curve {
         self.new_proy_point(ProyCoordinates {
             x: self.gx.clone(),
             y: self.gy.clone(),
-            z: self.f.one(),
+            z: self.one.clone(),
         })
     }
 }
 
 impl Decode for Curve {
     type Deser = <Curve as EllipticCurve>::Point;
-    fn decode(&self, buf: &[u8]) -> Result<Self::Deser, std::io::Error> {
+    fn decode_with_format(&self, buf: &[u8]) -> Result<(Self::Deser, PointFormat), Error> {
         let size = self.f.size_bytes();
         let blen = buf.len();
         if !(blen == 1 || blen == (size + 1) || blen == (2 * size + 1)) {
-            return Err(Error::new(ErrorKind::Other, "Wrong input buffer size."));
+            return Err(Error::other("Wrong input buffer size."));
         }
         let tag = buf[0];
-        // check x coordinate is in the valid range, Sign::Plus => > 0
-        let x_val = BigInt::from_bytes_be(Sign::Plus, &buf[1..size + 1]);
-        let p = self.f.get_modulus();
-        if x_val >= p {
-            return Err(Error::new(ErrorKind::Other, "Invalid x coordinate"));
+        if tag == 0x00 {
+            // the point at infinity is a single zero byte in both the
+            // compressed and the uncompressed formats
+            if blen != 1 {
+                return Err(Error::other(
+                    "Point at infinity should just be a single zero byte",
+                ));
+            }
+            return Ok((self.identity(), PointFormat::Compressed));
+        }
+        if blen == 1 {
+            return Err(Error::other("Invalid bytes for deserialization"));
         }
+        let x = self.f.from_canonical_be(&buf[1..size + 1])?;
         match tag {
-            0x00 => {
-                // return point of infinity
-                if buf.len() != 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Point at infinity should just be a single zero byte",
-                    ));
-                }
-                Ok(self.identity())
-            }
-            0x04 => {
+            0x04 | 0x06 | 0x07 => {
                 if buf.len() != 2 * size + 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Invalid bytes for deserialization",
-                    ));
+                    return Err(Error::other("Invalid bytes for deserialization"));
+                }
+                let y = self.f.from_canonical_be(&buf[size + 1..])?;
+                if &self.b * &y * &y != (&x * &x + &self.a * &x + &self.one) * &x {
+                    return Err(Error::other("Point is not on the curve"));
                 }
-                let x = self.f.elt(x_val);
-                let y_val = BigInt::from_bytes_be(Sign::Plus, &buf[size + 1..]);
-                if y_val >= p {
-                    return Err(Error::new(ErrorKind::Other, "Invalid y coordinate"));
+                if tag == 0x04 {
+                    return Ok((self.new_point(x, y), PointFormat::Uncompressed));
                 }
-                let y = self.f.elt(y_val);
-                Ok(self.new_point(x, y))
+                // the hybrid tag must carry the sign of y
+                if tag & 0x1 != ((y.sgn0() >> 1) & 0x1) as u8 {
+                    return Err(Error::other("Hybrid tag does not match y coordinate"));
+                }
+                Ok((self.new_point(x, y), PointFormat::Hybrid))
             }
             0x02 | 0x03 => {
                 if buf.len() != size + 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Invalid bytes for deserialization",
-                    ));
+                    return Err(Error::other("Invalid bytes for deserialization"));
                 }
                 // recompute y coordinate
-                let one = self.f.one();
-                let x = self.f.elt(x_val);
                 let x_a = &x + &self.a;
                 let xx_ax = &x_a * &x;
-                let xx_ax_1 = &xx_ax + &one;
+                let xx_ax_1 = &xx_ax + &self.one;
                 let byy = &xx_ax_1 * &x;
-                let b_inv = &one / &self.b;
-                let yy = &byy * b_inv;
+                let yy = &byy * &self.inv_b;
+                if !yy.is_square() {
+                    return Err(Error::other("Invalid x coordinate"));
+                }
                 let y_sqrt = yy.sqrt();
                 let s = y_sqrt.sgn0();
                 let deser_tag = (((s >> 1) & 0x1) + 2) as u8;
@@ -154,19 +400,22 @@ impl Decode for Curve {
                 if tag != deser_tag {
                     y = -y;
                 }
-                Ok(self.new_point(x, y))
+                Ok((self.new_point(x, y), PointFormat::Compressed))
             }
-            _ => Err(Error::new(ErrorKind::Other, "Invalid tag specified")),
+            _ => Err(Error::other("Invalid tag specified")),
         }
     }
 }
 
 impl std::fmt::Display for Curve {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if !self.name.is_empty() {
+            write!(f, "{}: ", self.name)?;
+        }
         write!(
             f,
-            "Montgomery Curve by^2=x^3+ax^2+x\na: {}\nb: {}",
-            self.a, self.b,
+            "Montgomery Curve by^2=x^3+ax^2+x over {}\na: {}\nb: {}",
+            self.f, self.a, self.b,
         )
     }
 }
@@ -174,40 +423,54 @@ impl std::fmt::Display for Curve {
 #[derive(PartialEq, Eq)]
 pub struct Params {
     pub name: &'static str,
-    pub p: &'static str,
-    pub a: &'static str,
-    pub b: &'static str,
-    pub s: &'static str,
-    pub r: &'static str,
-    pub h: &'static str,
-    pub gx: &'static str,
-    pub gy: &'static str,
+    pub p: Integer,
+    pub a: Integer,
+    pub b: Integer,
+    pub s: Integer,
+    pub r: Integer,
+    pub h: Integer,
+    pub gx: Integer,
+    pub gy: Integer,
 }
 
 impl<'a> std::convert::From<&'a Params> for Curve {
     fn from(params: &'a Params) -> Curve {
-        let f = Fp::new(BigUint::from_str(params.p).unwrap());
-        Curve {
-            a: f.from(params.a),
-            b: f.from(params.b),
+        let f = Fp::new(params.p.to_biguint());
+        let (a, b) = (f.from(params.a), f.from(params.b));
+        Curve(Arc::new(CurveInner {
+            name: params.name,
             s: f.from(params.s),
-            r: BigUint::from_str(params.r).unwrap(),
-            h: BigUint::from_str(params.h).unwrap(),
+            r: params.r.to_biguint(),
+            h: params.h.to_biguint(),
             gx: f.from(params.gx),
             gy: f.from(params.gy),
+            zero: f.zero(),
+            one: f.one(),
+            inv_b: 1u32 / &b,
+            a24: (&a - f.from(2)) / f.from(4),
+            a,
+            b,
             f,
-        }
+        }))
     }
 }
 
 const ERR_ECC_NEW: &str = "not valid point";
+const ERR_RECOVER_Y: &str = "x-coordinates do not match a ladder on the point";
 
 // tests for ser/deser
 #[cfg(test)]
 mod tests {
-    use crate::ellipticcurve::{Decode, EllipticCurve, Encode};
+    use num_bigint::BigInt;
+    use rand_core::OsRng;
+
+    use super::Curve;
+    use crate::ellipticcurve::{
+        Decode, DecodePolicy, EcPoint, EllipticCurve, Encode, PointFormat, POINT_FORMATS,
+    };
     use crate::field::Field;
     use crate::instances::{GetCurve, CURVE25519, CURVE448};
+    use crate::montgomery::{Point, Scalar, XZCoordinates};
 
     #[test]
     fn point_serialization() {
@@ -215,7 +478,7 @@ mod tests {
             let ec = id.get();
             let len_p = ec.get_field().size_bytes();
             let gen = ec.get_generator();
-            let ser = gen.encode(false);
+            let ser = gen.encode(PointFormat::Uncompressed);
             assert_eq!(ser.len(), 2 * len_p + 1);
             let deser = ec.decode(&ser).unwrap();
             assert!(
@@ -227,13 +490,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn zero_scalar() {
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let k = ec.new_scalar(BigInt::from(0));
+            let p = &ec.get_generator() * &k;
+            assert!(p.is_zero(), "[0]G must be the identity for {}", id);
+        }
+    }
+
     #[test]
     fn point_serialization_compressed() {
         for &id in [CURVE25519, CURVE448].iter() {
             let ec = id.get();
             let len_p = ec.get_field().size_bytes();
             let gen = ec.get_generator();
-            let ser = gen.encode(true);
+            let ser = gen.encode(PointFormat::Compressed);
             assert_eq!(ser.len(), len_p + 1);
             let deser = ec.decode(&ser).unwrap();
             assert!(
@@ -244,4 +517,256 @@ mod tests {
             assert!(gen == deser, "compressed point equality check for {}", id);
         }
     }
+
+    #[test]
+    fn identity_serialization() {
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let inf = ec.identity();
+            assert!(inf.is_identity(), "identity check for {}", id);
+            assert!(!ec.get_generator().is_identity(), "generator of {}", id);
+            for &format in POINT_FORMATS.iter() {
+                let ser = inf.encode(format);
+                assert_eq!(ser, vec![0], "identity encoding for {}", id);
+                let deser = ec.decode(&ser).unwrap();
+                assert!(deser.is_identity(), "identity round-trip for {}", id);
+            }
+            let gen = ec.get_generator();
+            assert!(
+                (&gen - &gen).encode(PointFormat::Compressed) == vec![0],
+                "P - P for {}",
+                id
+            );
+            assert!(ec.decode(&[0, 0]).is_err(), "padded identity for {}", id);
+            for tag in 1u8..=4 {
+                assert!(ec.decode(&[tag]).is_err(), "bare tag for {}", id);
+            }
+        }
+    }
+
+    #[test]
+    fn non_canonical_encodings() {
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let size = ec.get_field().size_bytes();
+            let p = ec.get_field().get_modulus().to_bytes_be().1;
+            let gen = ec.get_generator();
+            for &format in POINT_FORMATS.iter() {
+                let ser = gen.encode(format);
+                let mut long = ser.clone();
+                long.push(0);
+                assert!(ec.decode(&long).is_err(), "over-long encoding for {}", id);
+                let mut bad_x = ser.clone();
+                bad_x[1..size + 1].copy_from_slice(&p);
+                assert!(ec.decode(&bad_x).is_err(), "x >= p for {}", id);
+            }
+            let mut bad_y = gen.encode(PointFormat::Uncompressed);
+            bad_y[size + 1..].copy_from_slice(&p);
+            assert!(ec.decode(&bad_y).is_err(), "y >= p for {}", id);
+        }
+    }
+
+    #[test]
+    fn constant_time_decompression() {
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let mut p = ec.get_generator();
+            for _ in 0..8 {
+                let ser = p.encode(PointFormat::Compressed);
+                let q = ec.decode_ct(&ser).unwrap();
+                assert!(q == p, "decompression of {}", id);
+                assert!(q == ec.decode(&ser).unwrap(), "{}", id);
+                p = &p + &p;
+            }
+            let size = ec.get_field().size_bytes();
+            assert!(
+                ec.decode_ct(&p.encode(PointFormat::Uncompressed)).is_err(),
+                "{}",
+                id
+            );
+            assert!(ec
+                .decode_ct(&ec.identity().encode(PointFormat::Compressed))
+                .is_err());
+            let mut bad_tag = p.encode(PointFormat::Compressed);
+            bad_tag[0] = 0x04;
+            assert!(ec.decode_ct(&bad_tag).is_err(), "{}", id);
+            // find an x-coordinate that is not on the curve
+            let mut ser = p.encode(PointFormat::Compressed);
+            while ec.decode(&ser).is_ok() {
+                ser[size] = ser[size].wrapping_add(1);
+            }
+            assert!(ec.decode_ct(&ser).is_err(), "{}", id);
+        }
+    }
+
+    #[test]
+    fn small_order() {
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let gen = ec.get_generator();
+            let t2 = ec.new_point(f.zero(), f.zero());
+            assert!(!gen.is_small_order(), "generator of {}", id);
+            assert!(t2.is_small_order(), "point of order two of {}", id);
+            assert!(ec.identity().is_small_order(), "identity of {}", id);
+            assert!(
+                !(gen.clone() + &t2).is_small_order(),
+                "mixed point of {}",
+                id
+            );
+            let policy = DecodePolicy::RejectSmallOrder;
+            assert!(ec
+                .decode_with(&gen.encode(PointFormat::Compressed), policy)
+                .is_ok());
+            assert!(ec
+                .decode_with(&t2.encode(PointFormat::Compressed), policy)
+                .is_err());
+            assert!(ec
+                .decode_with(&t2.encode(PointFormat::Compressed), DecodePolicy::AcceptAll)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn completeness_parameter() {
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let s = Curve::find_s(&ec.get_field(), &ec.a);
+            assert!(s == ec.s, "smallest valid s for {}", id);
+        }
+    }
+
+    #[test]
+    fn scalar_blinding() {
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let g = ec.get_generator();
+            let k = ec.new_scalar(BigInt::from(0x1234_5678u64));
+            let want = &g * &k;
+            assert!(g.mul_blinded(&k, &mut OsRng) == want, "{}", id);
+            assert!(g.mul_blinded(&k, &mut OsRng) == want, "{}", id);
+        }
+    }
+
+    #[test]
+    fn randomized_representation() {
+        for &id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let g = ec.get_generator();
+            let mut p = g.clone();
+            p.randomize_representation(&mut OsRng);
+            assert!(p == g, "{}", id);
+            assert!(
+                p.encode(PointFormat::Compressed) == g.encode(PointFormat::Compressed),
+                "{}",
+                id
+            );
+            let mut o = ec.identity();
+            o.randomize_representation(&mut OsRng);
+            assert!(o.is_identity(), "{}", id);
+        }
+    }
+
+    #[test]
+    fn assign_ops() {
+        let ec = CURVE25519.get();
+        let g = ec.get_generator();
+        let (k, l) = (
+            ec.new_scalar(BigInt::from(5)),
+            ec.new_scalar(BigInt::from(7)),
+        );
+        let mut p = ec.identity();
+        p += &g;
+        p += g.clone();
+        p -= &g;
+        p *= &k;
+        assert!(p == &g * &k);
+        p *= l.clone();
+        p -= g.clone();
+        assert!(p == &g * &(&k * &l) - &g);
+
+        let mut s = k.clone();
+        s += &l;
+        s *= &l;
+        s -= k.clone();
+        s /= &l;
+        assert!(&g * &s == &g * &((&(&k + &l) * &l - &k) / &l));
+    }
+
+    #[test]
+    fn iter_sums() {
+        let ec = CURVE25519.get();
+        let g = ec.get_generator();
+        let k: Vec<_> = (1..5).map(|i| ec.new_scalar(BigInt::from(i))).collect();
+        let points: Vec<_> = k.iter().map(|k| &g * k).collect();
+        let sum: Point = points.iter().sum();
+        assert!(sum == &g * &ec.new_scalar(BigInt::from(10)));
+        assert!(points.clone().into_iter().sum::<Point>() == sum);
+        assert!(ec.sum(points) == sum);
+        assert!(ec.sum(Vec::new()).is_identity());
+
+        let s: Scalar = k.iter().sum();
+        assert!(&g * &s == sum);
+        let p: Scalar = k.into_iter().product();
+        assert!(&g * &p == &g * &ec.new_scalar(BigInt::from(24)));
+    }
+
+    #[test]
+    fn affine_coordinates() {
+        let ec = CURVE25519.get();
+        let g = ec.get_generator();
+        let p = &g + &g - &g;
+        assert_eq!(format!("{:x}", p.x().unwrap()), "9");
+        assert!(p.y() == g.y());
+        let (x, y) = p.to_affine().unwrap();
+        assert!(ec.new_point(x, y) == g);
+        assert!(ec.identity().to_affine().is_none());
+    }
+
+    #[test]
+    fn recover_y() {
+        for id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let f = ec.get_field();
+            let p = &ec.get_generator() * &ec.new_scalar(BigInt::from(5));
+            let scale = |c: XZCoordinates| XZCoordinates {
+                x: &c.x * &f.elt(BigInt::from(7)),
+                z: &c.z * &f.elt(BigInt::from(7)),
+            };
+            let r = BigInt::from(ec.get_order());
+            for k in [BigInt::from(0), BigInt::from(1), BigInt::from(12345), r - 1].iter() {
+                let q = &p * &ec.new_scalar(k.clone());
+                let q1 = &p * &ec.new_scalar(k + 1);
+                let xq = scale(XZCoordinates::from(&q));
+                let xq1 = scale(XZCoordinates::from(&q1));
+                assert!(ec.recover_y(&p, &xq, &xq1).unwrap() == q, "k: {}", k);
+            }
+            // [k+2]P instead of [k+1]P
+            let q = &p * &ec.new_scalar(BigInt::from(9));
+            let bad = &p * &ec.new_scalar(BigInt::from(11));
+            let (xq, xbad) = (XZCoordinates::from(&q), XZCoordinates::from(&bad));
+            assert!(ec.recover_y(&p, &xq, &xbad).is_err());
+        }
+    }
+
+    #[test]
+    fn ladder() {
+        for id in [CURVE25519, CURVE448].iter() {
+            let ec = id.get();
+            let p = &ec.get_generator() * &ec.new_scalar(BigInt::from(3));
+            let xp = XZCoordinates::from(&p);
+            let x = |q: &Point| XZCoordinates::from(q);
+            let same = |a: &XZCoordinates, b: &XZCoordinates| &a.x * &b.z == &a.z * &b.x;
+            let p2 = &p + &p;
+            assert!(same(&ec.xdbl(&xp), &x(&p2)));
+            assert!(same(&ec.xadd(&x(&p2), &xp, &xp), &x(&(&p2 + &p))));
+            let (d, s) = ec.ladder_step(&xp, &x(&p2), &x(&(&p2 + &p)));
+            assert!(same(&d, &x(&(&p2 + &p2))) && same(&s, &x(&(&p2 + &p2 + &p))));
+            for k in [0u64, 1, 2, 0xdead_beef].iter() {
+                let k = ec.new_scalar(BigInt::from(*k));
+                let (q, q1) = ec.ladder(&xp, &k);
+                assert!(ec.recover_y(&p, &q, &q1).unwrap() == &p * &k);
+            }
+        }
+    }
 }
@@ -1,11 +1,18 @@
 use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
 
-use crate::ellipticcurve::{EllipticCurve, MapToCurve};
+use std::io::Error;
+
+use crate::ellipticcurve::{EcPoint, EllipticCurve, MapToCurve};
 use crate::field::{CMov, Field, Sgn0, Sqrt};
-use crate::montgomery::Curve;
-use crate::ops::FromFactory;
+use crate::montgomery::{Curve, Point};
+use crate::ops::{Deserialize, FromFactory, Serialize};
 use crate::primefield::FpElt;
 
+/// Ell2 is the Elligator 2 map to a curve in Montgomery model as specified
+/// in RFC 9380, Section 6.7.1. The constant `z` must be a non-square of the
+/// field.
+#[derive(Clone)]
 pub struct Ell2 {
     e: Curve,
     z: FpElt,
@@ -15,19 +22,124 @@ pub struct Ell2 {
 
 impl Ell2 {
     pub fn new(e: Curve, z: FpElt) -> Ell2 {
-        if !Ell2::verify(&e) {
+        if !Ell2::verify(&e, &z) {
             panic!("wrong input parameters")
         } else {
+            // The map is applied on y^2=x^3+(a/b)x^2+(1/b^2)x, whose points
+            // are scaled by b for landing on the curve by^2=x^3+ax^2+x.
             let inb = 1u32 / &e.b;
-            let ca = &e.a / &inb;
+            let ca = &e.a * &inb;
             let cb = inb ^ 2u32;
             Ell2 { e, z, ca, cb }
         }
     }
-    fn verify(e: &Curve) -> bool {
-        let cond1 = !e.a.is_zero();
-        let cond2 = !e.b.is_zero();
-        cond1 && cond2
+    fn verify(e: &Curve, z: &FpElt) -> bool {
+        let cond1 = !e.a.is_zero(); // A != 0
+        let cond2 = !e.b.is_zero(); // B != 0
+        let cond3 = !z.is_square(); // Z is non-square
+        cond1 && cond2 && cond3
+    }
+    /// Returns a field element `u` such that `map(u) = p`, or `None` if `p`
+    /// is not in the image of the map, which happens for about half of the
+    /// points of the curve.
+    ///
+    /// Since the map only depends on `u^2`, both `u` and `-u` are preimages
+    /// of `p`; the returned one satisfies `sgn0(u) = 1`. Protocols that need
+    /// representatives indistinguishable from random strings must choose
+    /// between `u` and `-u` at random.
+    pub fn inverse(&self, p: &Point) -> Option<FpElt> {
+        if p.is_zero() {
+            return None;
+        }
+        let mut p = p.clone();
+        p.normalize();
+        let inb = 1u32 / &self.e.b;
+        let x = &p.c.x * &inb;
+        let y = &p.c.y * &inb;
+        let x_a = &x + &self.ca;
+        // Points obtained from x1 have sgn0(y) = -1, and points obtained
+        // from x2 have sgn0(y) = 1.
+        let u2 = if y.sgn0() == -1 {
+            -x_a / (&self.z * &x) //  x = x1 = -A/(1+Zu^2)
+        } else {
+            -x / (&self.z * x_a) //   x = x2 = -x1-A
+        };
+        if !u2.is_square() {
+            return None;
+        }
+        let mut u = u2.sqrt();
+        if u.sgn0() == -1 {
+            u = -u;
+        }
+        // Exceptional cases, such as points of order two, are filtered out
+        // by evaluating the map.
+        if self.map(&u) == p {
+            Some(u)
+        } else {
+            None
+        }
+    }
+    /// Returns an Elligator squared encoding of `p`, i.e., a pair of field
+    /// elements `(u1, u2)` such that `map(u1) + map(u2) = p`, serialized in
+    /// little-endian as `2*size_bytes` bytes.
+    ///
+    /// Following Tibouchi's sampling, `u1` is uniform and `u2` is a random
+    /// preimage of `p - map(u1)`, which is accepted with probability
+    /// proportional to the number of preimages. Hence, the pair is uniform
+    /// among all encodings of `p`, and the bytes of a random point are
+    /// indistinguishable from a random string. The unused high bits of each
+    /// element are also filled at random. It succeeds for every point, after
+    /// two attempts on average.
+    pub fn encode_uniform<R: RngCore + CryptoRng>(&self, p: &Point, rng: &mut R) -> Vec<u8> {
+        let f = self.e.get_field();
+        loop {
+            let u1 = f.random_nonzero(rng);
+            let q = p - self.map(&u1);
+            let u2 = match self.inverse(&q) {
+                Some(u2) => u2,
+                None => continue,
+            };
+            let mut coin = [0u8; 1];
+            rng.fill_bytes(&mut coin);
+            // u2 = 0 is the only preimage of its point, so it is accepted
+            // with probability 1/2; otherwise, one of u2 and -u2 is chosen.
+            let u2 = match (u2.is_zero(), coin[0] & 1 == 1) {
+                (true, true) => continue,
+                (false, true) => -u2,
+                _ => u2,
+            };
+            let mut out = self.elt_to_bytes(&u1, rng);
+            out.extend(self.elt_to_bytes(&u2, rng));
+            return out;
+        }
+    }
+    /// Returns the point `map(u1) + map(u2)` encoded by `encode_uniform`.
+    /// The unused high bits of each element are ignored.
+    pub fn decode_uniform(&self, bytes: &[u8]) -> Result<Point, Error> {
+        let f = self.e.get_field();
+        let n = f.size_bytes();
+        if bytes.len() != 2 * n {
+            return Err(Error::other(ERR_UNIFORM_LEN));
+        }
+        let spare = 8 * n - f.get_modulus().bits();
+        let mask = 0xffu8 >> spare;
+        let mut u = Vec::with_capacity(2);
+        for chunk in bytes.chunks(n) {
+            let mut buf = chunk.to_vec();
+            buf[n - 1] &= mask;
+            u.push(f.from_bytes_le(&buf)?);
+        }
+        Ok(self.map(&u[0]) + self.map(&u[1]))
+    }
+    fn elt_to_bytes<R: RngCore + CryptoRng>(&self, u: &FpElt, rng: &mut R) -> Vec<u8> {
+        let f = self.e.get_field();
+        let n = f.size_bytes();
+        let spare = 8 * n - f.get_modulus().bits();
+        let mut out = u.to_bytes_le();
+        let mut pad = [0u8; 1];
+        rng.fill_bytes(&mut pad);
+        out[n - 1] |= pad[0] & !(0xffu8 >> spare);
+        out
     }
 }
 
@@ -47,9 +159,9 @@ impl MapToCurve for Ell2 {
         x1 = 1u32 / &x1; //                6.   x1 = inv0(x1)
         x1 = -&self.ca * &x1; //           7.   x1 = -A * x1             // x1 = -A / (1 + Z * u^2)
         let mut gx1 = &x1 + &self.ca; //   8.  gx1 = x1 + A
-        gx1 = gx1 * &x1; //                9.  gx1 = gx1 * x1
-        gx1 = gx1 + &self.cb; //           10. gx1 = gx1 + B
-        gx1 = gx1 * &x1; //                11. gx1 = gx1 * x1            // gx1 = x1^3 + A * x1^2 + B * x1
+        gx1 *= &x1; //                     9.  gx1 = gx1 * x1
+        gx1 += &self.cb; //                10. gx1 = gx1 + B
+        gx1 *= &x1; //                     11. gx1 = gx1 * x1            // gx1 = x1^3 + A * x1^2 + B * x1
         let x2 = -&x1 - &self.ca; //       12.  x2 = -x1 - A
         let gx2 = t1 * &gx1; //            13. gx2 = t1 * gx1
         let e2 = gx1.is_square(); //       14.  e2 = is_square(gx1)
@@ -58,8 +170,102 @@ impl MapToCurve for Ell2 {
         let mut y = y2.sqrt(); //          17.   y = sqrt(y2)
         let e3 = y.sgn0() == 1; //         18.  e3 = sgn0(y) == 1        // Fix sign of y
         y = cmov(&(-&y), &y, e2 ^ e3); //  19.   y = CMOV(-y, y, e2 xor e3)
-        x = x * &self.e.b;
-        y = y * &self.e.b;
+        x *= &self.e.b;
+        y *= &self.e.b;
         self.e.new_point(x, y)
     }
 }
+
+const ERR_UNIFORM_LEN: &str = "wrong length of uniform encoding";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand_core::SeedableRng;
+
+    use std::sync::Arc;
+
+    use super::Ell2;
+    use crate::ellipticcurve::{EllipticCurve, MapToCurve};
+    use crate::field::{Field, Sgn0};
+    use crate::instances::{GetCurve, CURVE25519, CURVE448};
+    use crate::ops::FromFactory;
+
+    #[test]
+    fn scaled_curve() {
+        let mut e = CURVE25519.get();
+        let f = e.get_field();
+        let inner = Arc::get_mut(&mut e.0).unwrap();
+        inner.b = f.from(5);
+        inner.inv_b = 1u32 / &inner.b;
+        let map = Ell2::new(e.clone(), f.from(2));
+        for u in 0..8 {
+            let p = map.map(&f.from(u));
+            assert!(e.is_on_curve(&p), "point must be on curve for u={}", u);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "wrong input parameters")]
+    fn square_z() {
+        let e = CURVE25519.get();
+        let f = e.get_field();
+        Ell2::new(e, f.from(4));
+    }
+
+    #[test]
+    fn inverse() {
+        for &(id, z) in [(CURVE25519, 2), (CURVE448, -1)].iter() {
+            let e = id.get();
+            let f = e.get_field();
+            let ell2 = Ell2::new(e.clone(), f.from(z));
+            for i in 1..16 {
+                let u = f.from(1000 * i);
+                let p = ell2.map(&u);
+                let v = ell2.inverse(&p).expect("point must have a preimage");
+                assert!(v == u || v == -&u, "preimage of map(u) must be u or -u");
+                assert!(v.sgn0() == 1);
+                assert!(ell2.map(&v) == p);
+            }
+            let g = e.get_generator();
+            let mut count = 0;
+            for i in 1..33 {
+                let p = &g * &e.new_scalar(BigInt::from(i));
+                if let Some(v) = ell2.inverse(&p) {
+                    assert!(ell2.map(&v) == p, "inverse must be correct for {}", id);
+                    count += 1;
+                }
+            }
+            assert!(
+                count > 0 && count < 32,
+                "about half of the points are encodable"
+            );
+        }
+    }
+
+    #[test]
+    fn elligator_squared() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(733);
+        for &(id, z) in [(CURVE25519, 2), (CURVE448, -1)].iter() {
+            let e = id.get();
+            let f = e.get_field();
+            let ell2 = Ell2::new(e.clone(), f.from(z));
+            let g = e.get_generator();
+            let n = 2 * f.size_bytes();
+            let mut high = 0u8;
+            for i in 0..16 {
+                let p = &g * &e.new_scalar(BigInt::from(i));
+                let enc = ell2.encode_uniform(&p, &mut rng);
+                assert_eq!(enc.len(), n);
+                assert!(ell2.decode_uniform(&enc).unwrap() == p, "{}", id);
+                high |= enc[n - 1];
+                let again = ell2.encode_uniform(&p, &mut rng);
+                assert!(again != enc, "encodings must be randomized");
+            }
+            if id == CURVE25519 {
+                assert!(high & 0x80 != 0, "unused bits must be random");
+            }
+            assert!(ell2.decode_uniform(&[0u8; 3]).is_err());
+        }
+    }
+}
@@ -7,7 +7,8 @@ mod elligator2;
 mod point;
 mod scalar;
 
+pub(crate) use crate::montgomery::curve::CurveInner;
 pub use crate::montgomery::curve::{Curve, Params};
 pub use crate::montgomery::elligator2::Ell2;
-pub use crate::montgomery::point::{Point, ProyCoordinates};
+pub use crate::montgomery::point::{Point, ProyCoordinates, XZCoordinates};
 pub use crate::montgomery::scalar::Scalar;
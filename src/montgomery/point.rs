@@ -1,17 +1,19 @@
 use impl_ops::impl_op_ex;
 use num_bigint::ToBigInt;
 use num_traits::identities::{One, Zero};
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
 
 use std::ops;
 
-use crate::do_if_eq;
-use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode};
+use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode, PointFormat};
 use crate::field::Sgn0;
 use crate::montgomery::curve::Curve;
 use crate::montgomery::scalar::Scalar;
-use crate::ops::ScMulRef;
 use crate::ops::Serialize;
+use crate::ops::{blinding_mask, ScMulRef};
 use crate::primefield::FpElt;
+use crate::{count_op, do_if_eq, impl_iter_fold};
 
 #[derive(Clone)]
 pub struct ProyCoordinates {
@@ -19,6 +21,30 @@ pub struct ProyCoordinates {
     pub y: FpElt,
     pub z: FpElt,
 }
+/// XZCoordinates are the projective x-coordinate `(X:Z)` of a point, which
+/// stands for both the point and its negative, and is the only coordinate
+/// handled by Montgomery ladders. The point at infinity is `(1:0)`.
+#[derive(Clone)]
+pub struct XZCoordinates {
+    pub x: FpElt,
+    pub z: FpElt,
+}
+
+impl From<&Point> for XZCoordinates {
+    fn from(p: &Point) -> XZCoordinates {
+        if p.is_zero() {
+            return XZCoordinates {
+                x: p.e.one.clone(),
+                z: p.e.zero.clone(),
+            };
+        }
+        XZCoordinates {
+            x: p.c.x.clone(),
+            z: p.c.z.clone(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Point {
     pub(crate) e: Curve,
@@ -30,9 +56,13 @@ impl EcPoint<Scalar> for Point {
     fn is_zero(&self) -> bool {
         self.c.x.is_zero() && !self.c.y.is_zero() && self.c.z.is_zero()
     }
+    fn is_small_order(&self) -> bool {
+        let h = self.e.new_scalar(self.e.get_cofactor());
+        (self * h).is_zero()
+    }
 }
 impl Encode for Point {
-    fn encode(&self, compress: bool) -> Vec<u8> {
+    fn encode(&self, format: PointFormat) -> Vec<u8> {
         // normalize the point to ensure that z = 1
         // clone so that we don't mutate the original point
         let mut p_normal = self.clone();
@@ -47,29 +77,51 @@ impl Encode for Point {
         let y = &coords.y;
         let mut x_bytes = x.to_bytes_be();
         let mut y_bytes = y.to_bytes_be();
-        if compress {
-            let s = y.sgn0();
-            // if sign == 1: tag = 0x02; elif sign == -1: tag = 0x03
-            let tag = (((s >> 1) & 0x1) + 2) as u8;
-            let mut o = vec![tag];
-            o.append(&mut x_bytes);
-            o
-        } else {
-            let mut o: Vec<u8> = vec![0x04];
-            o.append(&mut x_bytes);
+        // if sign == 1: parity = 0; elif sign == -1: parity = 1
+        let parity = ((y.sgn0() >> 1) & 0x1) as u8;
+        let tag = match format {
+            PointFormat::Uncompressed => 0x04,
+            PointFormat::Compressed => 0x02 | parity,
+            PointFormat::Hybrid => 0x06 | parity,
+        };
+        let mut o = vec![tag];
+        o.append(&mut x_bytes);
+        if format != PointFormat::Compressed {
             o.append(&mut y_bytes);
-            o
         }
+        o
     }
 }
 
 impl Point {
     pub fn normalize(&mut self) {
+        // the point at infinity has no affine representation
+        if self.c.z.is_zero() {
+            return;
+        }
         let inv_z = 1u32 / &self.c.z;
         self.c.x = &self.c.x * &inv_z;
         self.c.y = &self.c.y * &inv_z;
         self.c.z.set_one();
     }
+    /// Returns the affine x-coordinate, or `None` for the point at infinity.
+    pub fn x(&self) -> Option<FpElt> {
+        self.to_affine().map(|(x, _)| x)
+    }
+    /// Returns the affine y-coordinate, or `None` for the point at infinity.
+    pub fn y(&self) -> Option<FpElt> {
+        self.to_affine().map(|(_, y)| y)
+    }
+    /// Returns the affine coordinates (x, y), or `None` for the point at
+    /// infinity.
+    pub fn to_affine(&self) -> Option<(FpElt, FpElt)> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut p = self.clone();
+        p.normalize();
+        Some((p.c.x, p.c.y))
+    }
     fn core_neg(&self) -> Point {
         self.e.new_proy_point(ProyCoordinates {
             x: self.c.x.clone(),
@@ -78,6 +130,11 @@ impl Point {
         })
     }
     fn core_add(&self, p: &Point) -> Point {
+        if std::ptr::eq(self, p) {
+            count_op!(dbl);
+        } else {
+            count_op!(add);
+        }
         let (x1, y1, z1) = (&self.c.x, &self.c.y, &self.c.z);
         let (x2, y2, z2) = (&p.c.x, &p.c.y, &p.c.z);
         let (a_ec, s_ec) = (&self.e.a, &self.e.s);
@@ -86,28 +143,65 @@ impl Point {
         let (t5, t6) = (y1 * z2, y2 * z1);
         let (t7, t8) = (x1 * z2, x2 * z1);
         let t9 = &t7 + &t8;
-        let ta = &t9 + &(&t0 * a_ec);
+        let ta = t0.mul_add(a_ec, &t9);
         let rr = &t5 + &t6;
         let tt = &ta - &t1;
-        let vv = t9 * a_ec + &t0 + &t0 + &t0 + &t2;
-        let ss = (&t3 - &t4) * s_ec + t0 - t2;
-        let uu = (t7 - t8) * s_ec - t3 - t4;
-        let ww = (t5 - t6) * s_ec + ta + t1;
-        let x3 = &rr * &ss - &tt * &uu;
-        let y3 = tt * &ww - &vv * &ss;
-        let z3 = vv * uu - rr * ww;
+        let mut vv = t9.mul_add(a_ec, &t2);
+        vv += &t0;
+        vv += &t0;
+        vv += &t0;
+        let mut ss = (&t3 - &t4).mul_add(s_ec, &t0);
+        ss -= &t2;
+        let mut uu = (t7 - t8) * s_ec;
+        uu -= &t3;
+        uu -= &t4;
+        let mut ww = (t5 - t6).mul_add(s_ec, &ta);
+        ww += &t1;
+        let mut x3 = &rr * &ss;
+        x3 -= &(&tt * &uu);
+        let mut y3 = tt * &ww;
+        y3 -= &(&vv * &ss);
+        let mut z3 = vv * uu;
+        z3 -= &(rr * ww);
         self.e.new_proy_point(ProyCoordinates {
             x: x3,
             y: y3,
             z: z3,
         })
     }
+    /// Multiplies the projective coordinates `X`, `Y`, and `Z` by a random
+    /// non-zero field element, which changes the representation of the
+    /// point but not the point itself. Randomizing the input of a secret
+    /// scalar multiplication blunts template attacks, since the attacker
+    /// can no longer predict the intermediate values.
+    pub fn randomize_representation<R: RngCore + CryptoRng>(&mut self, rng: &mut R) {
+        let l = self.e.f.random_nonzero(rng);
+        self.c.x = &self.c.x * &l;
+        self.c.y = &self.c.y * &l;
+        self.c.z = &self.c.z * &l;
+    }
+    /// Returns `[k]p` computed as `[k + m*n]p`, where `n` is the order of
+    /// the curve and `m` is a random 64-bit mask drawn from `rng` on every
+    /// call. Since the bits processed by the multiplication change on every
+    /// call, this hardens it against differential power analysis. The
+    /// representation of `p` is also randomized beforehand.
+    pub fn mul_blinded<R: RngCore + CryptoRng>(&self, k: &Scalar, rng: &mut R) -> Point {
+        let r = self.e.r.to_bigint().unwrap();
+        let n = (&self.e.r * &self.e.h).to_bigint().unwrap();
+        let blinded = Scalar {
+            k: &k.k + blinding_mask(&n, rng),
+            r: k.r.clone(),
+        };
+        let mut p = self.clone();
+        p.randomize_representation(rng);
+        do_if_eq!(r == k.r, p.core_mul(&blinded), ERR_MUL_OP)
+    }
     fn core_mul(&self, k: &Scalar) -> Point {
         let mut q = self.e.identity();
         for ki in k.iter_lr() {
             q = &q + &q;
             if ki {
-                q = q + self;
+                q += self;
             }
         }
         q
@@ -120,6 +214,7 @@ impl Point {
 impl Eq for Point {}
 
 impl PartialEq for Point {
+    /// Compares the points in variable time.
     fn eq(&self, other: &Self) -> bool {
         let x1z2 = &self.c.x * &other.c.z;
         let z1x2 = &self.c.z * &other.c.x;
@@ -129,6 +224,18 @@ impl PartialEq for Point {
     }
 }
 
+impl ConstantTimeEq for Point {
+    /// Compares the points without short-circuiting on their coordinates.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let x1z2 = &self.c.x * &other.c.z;
+        let z1x2 = &self.c.z * &other.c.x;
+        let y1z2 = &self.c.y * &other.c.z;
+        let z1y2 = &self.c.z * &other.c.y;
+        let same_curve = Choice::from((self.e == other.e) as u8);
+        same_curve & x1z2.ct_eq(&z1x2) & y1z2.ct_eq(&z1y2)
+    }
+}
+
 impl_op_ex!(+|a: &Point , b: &Point | -> Point  {
     do_if_eq!(a.e == b.e, a.core_add(b), ERR_ADD_OP)
 });
@@ -138,10 +245,34 @@ impl_op_ex!(*|a: &Point, b: &Scalar| -> Point {
     let r = a.e.r.to_bigint().unwrap();
     do_if_eq!(r == b.r, a.core_mul(b), ERR_MUL_OP)
 });
+impl_op_ex!(+=|a: &mut Point, b: &Point| { *a = &*a + b });
+impl_op_ex!(-=|a: &mut Point, b: &Point| { *a = &*a - b });
+impl_op_ex!(*=|a: &mut Point, b: &Scalar| { *a = &*a * b });
+impl_iter_fold!(Sum, sum, Point, +);
 
+/// Displays the compressed encoding of the point in hex.
 impl std::fmt::Display for Point {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "\nx: {}\ny: {}\nz: {}", self.c.x, self.c.y, self.c.z)
+        self.encode(PointFormat::Compressed)
+            .iter()
+            .try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+/// Shows the name of the curve and the affine coordinates of the point in
+/// hex.
+impl std::fmt::Debug for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_identity() {
+            return write!(f, "Point {{ curve: {:?}, identity }}", self.e.name);
+        }
+        let mut p = self.clone();
+        p.normalize();
+        f.debug_struct("Point")
+            .field("curve", &self.e.name)
+            .field("x", &format_args!("{:#x}", p.c.x))
+            .field("y", &format_args!("{:#x}", p.c.y))
+            .finish()
     }
 }
 
@@ -0,0 +1,619 @@
+//! This is documentation for the `musig2` module.
+//!
+//! The musig2 module implements the MuSig2 multi-signature scheme over
+//! secp256k1 specified in BIP-327. A group of signers aggregates their
+//! public keys into a single BIP-340 public key, and then produces
+//! signatures that verify under it in two rounds: first every signer
+//! publishes a pair of nonces, and then a partial signature.
+//!
+//! Secret nonces cannot be cloned nor serialized, and signing consumes
+//! them, so a nonce cannot be used for two signatures by accident.
+//!
+//! # Signing with Two Parties
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::musig2::{aggregate_nonces, KeyAggContext, SecretNonce, Session};
+//!  use redox_ecc::schnorr::SigningKey;
+//!
+//!  let (sk0, sk1) = (SigningKey::random(&mut OsRng), SigningKey::random(&mut OsRng));
+//!  let pks = [KeyAggContext::public_key(&sk0), KeyAggContext::public_key(&sk1)];
+//!  let ctx = KeyAggContext::new(&pks).unwrap();
+//!  let msg = Some(&b"message"[..]);
+//!  let (sec0, pub0) = SecretNonce::generate(&sk0, Some(&ctx), msg, b"", &mut OsRng);
+//!  let (sec1, pub1) = SecretNonce::generate(&sk1, Some(&ctx), msg, b"", &mut OsRng);
+//!  let aggnonce = aggregate_nonces(&[pub0, pub1]).unwrap();
+//!  let session = Session::new(&ctx, &aggnonce, b"message").unwrap();
+//!  let psigs = [session.sign(sec0, &sk0).unwrap(), session.sign(sec1, &sk1).unwrap()];
+//!  let sig = session.aggregate(&psigs).unwrap();
+//!  assert!(ctx.aggregated_key().verify(b"message", &sig).is_ok());
+//! ```
+
+use num_bigint::{BigInt, BigUint};
+use num_traits::identities::{One, Zero};
+use rand_core::{CryptoRng, RngCore};
+
+use std::io::Error;
+
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode, PointFormat};
+use crate::instances::{GetCurve, SECP256K1};
+use crate::ops::{Endianness, Serialize};
+use crate::schnorr::{
+    challenge, has_even_y, tagged_hash, x_only, Signature, SigningKey, VerifyingKey,
+};
+use crate::weierstrass::{Curve, Point, Scalar};
+
+/// Sorts public keys in lexicographical order, so that the aggregated key
+/// does not depend on the order in which signers are listed.
+pub fn key_sort(pubkeys: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let mut out = pubkeys.to_vec();
+    out.sort();
+    out
+}
+
+/// KeyAggContext is the result of aggregating the public keys of the
+/// signers, possibly tweaked afterwards.
+#[derive(Clone)]
+pub struct KeyAggContext {
+    e: Curve,
+    pubkeys: Vec<Vec<u8>>,
+    pk2: Vec<u8>,
+    list_hash: Vec<u8>,
+    q: Point,
+    gacc: Scalar,
+    tacc: Scalar,
+}
+
+impl KeyAggContext {
+    /// Returns the plain public key of a signer, i.e., its public point in
+    /// compressed form, which is the key used for aggregation.
+    pub fn public_key(sk: &SigningKey) -> Vec<u8> {
+        let e = SECP256K1.get();
+        let d = e.new_scalar_from_bytes(&sk.to_bytes(), Endianness::Big);
+        (e.get_generator() * d).encode(PointFormat::Compressed)
+    }
+    /// Aggregates the plain public keys of the signers. It fails if a key
+    /// is invalid or if the aggregated key is the identity.
+    // based on https://github.com/bitcoin/bips/blob/master/bip-0327.mediawiki#key-aggregation
+    pub fn new(pubkeys: &[Vec<u8>]) -> Result<KeyAggContext, Error> {
+        let e = SECP256K1.get();
+        let points = pubkeys
+            .iter()
+            .map(|pk| cpoint(&e, pk))
+            .collect::<Result<Vec<Point>, Error>>()?;
+        let pk2 = pubkeys
+            .iter()
+            .find(|pk| *pk != &pubkeys[0])
+            .cloned()
+            .unwrap_or_else(|| vec![0u8; POINT_SIZE]);
+        let parts: Vec<&[u8]> = pubkeys.iter().map(|pk| &pk[..]).collect();
+        let list_hash = tagged_hash(TAG_KEYAGG_LIST, &parts);
+        let mut ctx = KeyAggContext {
+            q: e.identity(),
+            gacc: e.new_scalar(BigInt::one()),
+            tacc: e.new_scalar(BigInt::zero()),
+            pubkeys: pubkeys.to_vec(),
+            pk2,
+            list_hash,
+            e,
+        };
+        let coefs: Vec<Scalar> = pubkeys.iter().map(|pk| ctx.coefficient(pk)).collect();
+        ctx.q = points
+            .iter()
+            .zip(coefs.iter())
+            .fold(ctx.e.identity(), |acc, (p, a)| acc + p * a);
+        if ctx.q.is_identity() {
+            return Err(Error::other(ERR_AGG_KEY));
+        }
+        Ok(ctx)
+    }
+    /// Adds `[t]G` to the aggregated key, where `t` is the 32-byte `tweak`.
+    /// If `xonly` is set, the key is first negated if its y-coordinate is
+    /// odd, as done for Taproot tweaks; otherwise, it is a plain tweak as
+    /// done by BIP-32 derivations.
+    // based on https://github.com/bitcoin/bips/blob/master/bip-0327.mediawiki#applying-tweaks
+    pub fn apply_tweak(&mut self, tweak: &[u8], xonly: bool) -> Result<(), Error> {
+        let e = &self.e;
+        let t = BigUint::from_bytes_be(tweak);
+        if tweak.len() != SCALAR_SIZE || t >= e.get_order() {
+            return Err(Error::other(ERR_TWEAK));
+        }
+        let t = e.new_scalar(t.into());
+        let g = if xonly && !has_even_y(&self.q) {
+            e.new_scalar(BigInt::from(-1))
+        } else {
+            e.new_scalar(BigInt::one())
+        };
+        let q = &self.q * &g + e.get_generator() * &t;
+        if q.is_identity() {
+            return Err(Error::other(ERR_AGG_KEY));
+        }
+        self.q = q;
+        self.gacc = &g * &self.gacc;
+        self.tacc = t + g * &self.tacc;
+        Ok(())
+    }
+    /// Returns the aggregated key as a BIP-340 public key.
+    pub fn aggregated_key(&self) -> VerifyingKey {
+        VerifyingKey::from_bytes(&x_only(&self.q)).unwrap()
+    }
+    /// Returns the aggregated key in compressed form, which keeps the parity
+    /// of its y-coordinate as needed for plain tweaks.
+    pub fn plain_key(&self) -> Vec<u8> {
+        self.q.encode(PointFormat::Compressed)
+    }
+    fn coefficient(&self, pk: &[u8]) -> Scalar {
+        if pk == &self.pk2[..] {
+            return self.e.new_scalar(BigInt::one());
+        }
+        let h = tagged_hash(TAG_KEYAGG_COEF, &[&self.list_hash, pk]);
+        self.e.new_scalar_from_bytes(&h, Endianness::Big)
+    }
+}
+
+/// SecretNonce is the secret pair of nonces of a signer for one signing
+/// session. It is consumed when signing.
+pub struct SecretNonce {
+    k1: Scalar,
+    k2: Scalar,
+    pk: Vec<u8>,
+}
+
+impl SecretNonce {
+    /// Generates a pair of nonces for signing with `sk`. The aggregation
+    /// context, the message and the extra input are optional, and only add
+    /// protection against a weak source of randomness.
+    pub fn generate<R: RngCore + CryptoRng>(
+        sk: &SigningKey,
+        ctx: Option<&KeyAggContext>,
+        msg: Option<&[u8]>,
+        extra: &[u8],
+        rng: &mut R,
+    ) -> (SecretNonce, PublicNonce) {
+        let mut rand = [0u8; SCALAR_SIZE];
+        loop {
+            rng.fill_bytes(&mut rand);
+            if let Ok(nonces) = SecretNonce::generate_with_rand(sk, ctx, msg, extra, &rand) {
+                return nonces;
+            }
+        }
+    }
+    /// Generates a pair of nonces from the 32 random bytes `rand`. It fails
+    /// if a derived nonce is zero.
+    ///
+    /// **Attention** Using the same `rand` in two sessions reveals the secret
+    /// key.
+    // based on https://github.com/bitcoin/bips/blob/master/bip-0327.mediawiki#nonce-generation
+    pub fn generate_with_rand(
+        sk: &SigningKey,
+        ctx: Option<&KeyAggContext>,
+        msg: Option<&[u8]>,
+        extra: &[u8],
+        rand: &[u8; SCALAR_SIZE],
+    ) -> Result<(SecretNonce, PublicNonce), Error> {
+        let e = SECP256K1.get();
+        let pk = KeyAggContext::public_key(sk);
+        let mask = tagged_hash(TAG_AUX, &[rand]);
+        let rand: Vec<u8> = sk.to_bytes().iter().zip(mask).map(|(a, b)| a ^ b).collect();
+        let aggpk = ctx.map(|c| x_only(&c.q)).unwrap_or_default();
+        let msg_prefixed = match msg {
+            Some(m) => [&[1u8][..], &(m.len() as u64).to_be_bytes(), m].concat(),
+            None => vec![0u8],
+        };
+        let nonce = |i: u8| {
+            let h = tagged_hash(
+                TAG_NONCE,
+                &[
+                    &rand,
+                    &[pk.len() as u8],
+                    &pk,
+                    &[aggpk.len() as u8],
+                    &aggpk,
+                    &msg_prefixed,
+                    &(extra.len() as u32).to_be_bytes(),
+                    extra,
+                    &[i],
+                ],
+            );
+            e.new_scalar_from_bytes(&h, Endianness::Big)
+        };
+        let (k1, k2) = (nonce(0), nonce(1));
+        if is_zero(&e, &k1) || is_zero(&e, &k2) {
+            return Err(Error::other(ERR_NONCE));
+        }
+        let g = e.get_generator();
+        let mut pubnonce = (&g * &k1).encode(PointFormat::Compressed);
+        pubnonce.append(&mut (g * &k2).encode(PointFormat::Compressed));
+        Ok((SecretNonce { k1, k2, pk }, PublicNonce(pubnonce)))
+    }
+}
+
+/// PublicNonce is the pair of public nonces of a signer, which is sent to
+/// the other signers in the first round.
+#[derive(Clone, PartialEq)]
+pub struct PublicNonce(Vec<u8>);
+
+impl PublicNonce {
+    /// Parses a public nonce, i.e., two points in compressed form.
+    pub fn from_bytes(buf: &[u8]) -> Result<PublicNonce, Error> {
+        let e = SECP256K1.get();
+        if buf.len() != 2 * POINT_SIZE {
+            return Err(Error::other(ERR_NONCE_LEN));
+        }
+        cpoint(&e, &buf[..POINT_SIZE])?;
+        cpoint(&e, &buf[POINT_SIZE..])?;
+        Ok(PublicNonce(buf.to_vec()))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+}
+
+/// AggregateNonce is the sum of the public nonces of all signers, where
+/// each half may be the identity.
+#[derive(Clone, PartialEq)]
+pub struct AggregateNonce(Vec<u8>);
+
+impl AggregateNonce {
+    /// Parses an aggregate nonce, where an identity is encoded as 33 zero
+    /// bytes.
+    pub fn from_bytes(buf: &[u8]) -> Result<AggregateNonce, Error> {
+        let e = SECP256K1.get();
+        if buf.len() != 2 * POINT_SIZE {
+            return Err(Error::other(ERR_NONCE_LEN));
+        }
+        cpoint_ext(&e, &buf[..POINT_SIZE])?;
+        cpoint_ext(&e, &buf[POINT_SIZE..])?;
+        Ok(AggregateNonce(buf.to_vec()))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+}
+
+/// Sums the public nonces of all signers.
+// based on https://github.com/bitcoin/bips/blob/master/bip-0327.mediawiki#nonce-aggregation
+pub fn aggregate_nonces(nonces: &[PublicNonce]) -> Result<AggregateNonce, Error> {
+    let e = SECP256K1.get();
+    let mut out = Vec::with_capacity(2 * POINT_SIZE);
+    for j in 0..2 {
+        let mut r = e.identity();
+        for n in nonces.iter() {
+            r += cpoint(&e, &n.0[j * POINT_SIZE..(j + 1) * POINT_SIZE])?;
+        }
+        out.append(&mut cbytes_ext(&r));
+    }
+    Ok(AggregateNonce(out))
+}
+
+/// PartialSignature is the share of the signature produced by one signer
+/// in the second round.
+#[derive(Clone, PartialEq)]
+pub struct PartialSignature(Scalar);
+
+impl PartialSignature {
+    /// Parses a 32-byte partial signature. It fails if it is not less than
+    /// the order of secp256k1.
+    pub fn from_bytes(buf: &[u8]) -> Result<PartialSignature, Error> {
+        let e = SECP256K1.get();
+        let s = BigUint::from_bytes_be(buf);
+        if buf.len() != SCALAR_SIZE || s >= e.get_order() {
+            return Err(Error::other(ERR_PSIG_RANGE));
+        }
+        Ok(PartialSignature(e.new_scalar(s.into())))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes_be()
+    }
+}
+
+/// Session holds the values shared by all signers for signing a message
+/// once the aggregate nonce is known.
+#[derive(Clone)]
+pub struct Session {
+    ctx: KeyAggContext,
+    b: Scalar,
+    r: Point,
+    c: Scalar,
+}
+
+impl Session {
+    /// Starts a session for signing `msg` under the aggregated key of
+    /// `ctx`, using the aggregate nonce of all signers.
+    // based on https://github.com/bitcoin/bips/blob/master/bip-0327.mediawiki#session-context
+    pub fn new(
+        ctx: &KeyAggContext,
+        aggnonce: &AggregateNonce,
+        msg: &[u8],
+    ) -> Result<Session, Error> {
+        let e = &ctx.e;
+        let q = x_only(&ctx.q);
+        let h = tagged_hash(TAG_NONCE_COEF, &[&aggnonce.0, &q, msg]);
+        let b = e.new_scalar_from_bytes(&h, Endianness::Big);
+        let r1 = cpoint_ext(e, &aggnonce.0[..POINT_SIZE])?;
+        let r2 = cpoint_ext(e, &aggnonce.0[POINT_SIZE..])?;
+        let r = r1 + r2 * &b;
+        let r = if r.is_identity() {
+            e.get_generator()
+        } else {
+            r
+        };
+        let c = challenge(e, &x_only(&r), &q, msg);
+        Ok(Session {
+            ctx: ctx.clone(),
+            b,
+            r,
+            c,
+        })
+    }
+    /// Produces the partial signature of the signer with key `sk`, which
+    /// must be one of the aggregated keys. The secret nonce is consumed.
+    // based on https://github.com/bitcoin/bips/blob/master/bip-0327.mediawiki#signing
+    pub fn sign(&self, secnonce: SecretNonce, sk: &SigningKey) -> Result<PartialSignature, Error> {
+        let e = &self.ctx.e;
+        let pk = KeyAggContext::public_key(sk);
+        if pk != secnonce.pk {
+            return Err(Error::other(ERR_NONCE_KEY));
+        }
+        let a = self.key_coefficient(&pk)?;
+        let (k1, k2) = if has_even_y(&self.r) {
+            (secnonce.k1, secnonce.k2)
+        } else {
+            (-secnonce.k1, -secnonce.k2)
+        };
+        let d = e.new_scalar_from_bytes(&sk.to_bytes(), Endianness::Big);
+        let d = self.parity() * &self.ctx.gacc * d;
+        let s = k1 + &self.b * k2 + &self.c * a * d;
+        Ok(PartialSignature(s))
+    }
+    /// Verifies the partial signature of the signer with plain public key
+    /// `pk` and public nonce `pubnonce`.
+    // based on https://github.com/bitcoin/bips/blob/master/bip-0327.mediawiki#partial-signature-verification
+    pub fn verify_partial(
+        &self,
+        psig: &PartialSignature,
+        pubnonce: &PublicNonce,
+        pk: &[u8],
+    ) -> Result<(), Error> {
+        let e = &self.ctx.e;
+        let a = self.key_coefficient(pk)?;
+        let p = cpoint(e, pk)?;
+        let r1 = cpoint(e, &pubnonce.0[..POINT_SIZE])?;
+        let r2 = cpoint(e, &pubnonce.0[POINT_SIZE..])?;
+        let re = r1 + r2 * &self.b;
+        let re = if has_even_y(&self.r) { re } else { -re };
+        let g = self.parity() * &self.ctx.gacc;
+        let want = re + p * (&self.c * a * g);
+        if e.get_generator() * &psig.0 != want {
+            return Err(Error::other(ERR_PSIG_INVALID));
+        }
+        Ok(())
+    }
+    /// Sums the partial signatures of all signers into a BIP-340 signature
+    /// valid under the aggregated key.
+    // based on https://github.com/bitcoin/bips/blob/master/bip-0327.mediawiki#partial-signature-aggregation
+    pub fn aggregate(&self, psigs: &[PartialSignature]) -> Result<Signature, Error> {
+        let e = &self.ctx.e;
+        let s = psigs
+            .iter()
+            .fold(e.new_scalar(BigInt::zero()), |acc, p| acc + &p.0);
+        let s = s + &self.c * self.parity() * &self.ctx.tacc;
+        let mut out = x_only(&self.r);
+        out.append(&mut s.to_bytes_be());
+        Signature::from_bytes(&out)
+    }
+    /// Returns `1` if the aggregated key has even y-coordinate, and `-1`
+    /// otherwise.
+    fn parity(&self) -> Scalar {
+        let g = if has_even_y(&self.ctx.q) { 1 } else { -1 };
+        self.ctx.e.new_scalar(BigInt::from(g))
+    }
+    fn key_coefficient(&self, pk: &[u8]) -> Result<Scalar, Error> {
+        if !self.ctx.pubkeys.iter().any(|p| p == pk) {
+            return Err(Error::other(ERR_NOT_SIGNER));
+        }
+        Ok(self.ctx.coefficient(pk))
+    }
+}
+
+/// Parses a point in compressed form.
+fn cpoint(e: &Curve, buf: &[u8]) -> Result<Point, Error> {
+    if buf.len() != POINT_SIZE || (buf[0] != 0x02 && buf[0] != 0x03) {
+        return Err(Error::other(ERR_POINT));
+    }
+    e.decode(buf).map_err(|_| Error::other(ERR_POINT))
+}
+
+/// Parses a point in compressed form, where 33 zero bytes encode the
+/// identity.
+fn cpoint_ext(e: &Curve, buf: &[u8]) -> Result<Point, Error> {
+    if buf.len() == POINT_SIZE && buf.iter().all(|&b| b == 0) {
+        return Ok(e.identity());
+    }
+    cpoint(e, buf)
+}
+
+fn cbytes_ext(p: &Point) -> Vec<u8> {
+    if p.is_identity() {
+        return vec![0u8; POINT_SIZE];
+    }
+    p.encode(PointFormat::Compressed)
+}
+
+fn is_zero(e: &Curve, k: &Scalar) -> bool {
+    *k == e.new_scalar(BigInt::zero())
+}
+
+const SCALAR_SIZE: usize = 32;
+const POINT_SIZE: usize = 33;
+const TAG_KEYAGG_LIST: &[u8] = b"KeyAgg list";
+const TAG_KEYAGG_COEF: &[u8] = b"KeyAgg coefficient";
+const TAG_AUX: &[u8] = b"MuSig/aux";
+const TAG_NONCE: &[u8] = b"MuSig/nonce";
+const TAG_NONCE_COEF: &[u8] = b"MuSig/noncecoef";
+const ERR_POINT: &str = "point must be encoded in compressed form";
+const ERR_AGG_KEY: &str = "aggregated key is the identity";
+const ERR_TWEAK: &str = "tweak must be a 32-byte integer less than the order";
+const ERR_NONCE: &str = "random bytes produce a zero nonce";
+const ERR_NONCE_LEN: &str = "nonce must have 66 bytes";
+const ERR_NONCE_KEY: &str = "secret nonce was generated for another key";
+const ERR_NOT_SIGNER: &str = "public key is not one of the aggregated keys";
+const ERR_PSIG_RANGE: &str = "partial signature must be a 32-byte integer less than the order";
+const ERR_PSIG_INVALID: &str = "invalid partial signature";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, Sign};
+
+    use rand_core::OsRng;
+
+    use super::{
+        aggregate_nonces, key_sort, AggregateNonce, KeyAggContext, PartialSignature, PublicNonce,
+        SecretNonce, Session,
+    };
+    use crate::ellipticcurve::{EllipticCurve, Encode, PointFormat};
+    use crate::instances::{GetCurve, SECP256K1};
+    use crate::schnorr::SigningKey;
+
+    fn decode_all(keys: &[&str]) -> Vec<Vec<u8>> {
+        keys.iter().map(|k| hex::decode(k).unwrap()).collect()
+    }
+
+    // Test vectors taken from BIP-327, key_agg_vectors.json.
+    #[test]
+    fn key_agg() {
+        let pks = decode_all(&[
+            "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "03DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            "023590A94E768F8E1815C2F24B4D80A8E3149316C3518CE7B7AD338368D038CA66",
+        ]);
+        let vectors: [(&[usize], &str); 4] = [
+            (
+                &[0, 1, 2],
+                "90539EEDE565F5D054F32CC0C220126889ED1E5D193BAF15AEF344FE59D4610C",
+            ),
+            (
+                &[2, 1, 0],
+                "6204DE8B083426DC6EAF9502D27024D53FC826BF7D2012148A0575435DF54B2B",
+            ),
+            (
+                &[0, 0, 0],
+                "B436E3BAD62B8CD409969A224731C193D051162D8C5AE8B109306127DA3AA935",
+            ),
+            (
+                &[0, 0, 1, 1],
+                "69BC22BFA5D106306E48A20679DE1D7389386124D07571D0D872686028C26A3E",
+            ),
+        ];
+        for (indices, want) in vectors.iter() {
+            let keys: Vec<Vec<u8>> = indices.iter().map(|&i| pks[i].clone()).collect();
+            let ctx = KeyAggContext::new(&keys).unwrap();
+            assert_eq!(hex::encode_upper(ctx.aggregated_key().to_bytes()), *want);
+        }
+        let sorted = key_sort(&[pks[2].clone(), pks[1].clone(), pks[0].clone()]);
+        assert_eq!(sorted, [pks[2].clone(), pks[0].clone(), pks[1].clone()]);
+        let mut bad = pks[0].clone();
+        bad[0] = 0x04;
+        assert!(KeyAggContext::new(&[bad, pks[1].clone()]).is_err());
+        let mut ctx = KeyAggContext::new(&pks).unwrap();
+        assert!(ctx.apply_tweak(&[0xff; 32], true).is_err());
+        assert!(ctx.apply_tweak(&[0x01; 31], true).is_err());
+    }
+
+    // Test vectors taken from BIP-327, sign_verify_vectors.json.
+    #[test]
+    fn sign() {
+        let e = SECP256K1.get();
+        let sk = "7FB9E0E687ADA1EEBF7ECFE2F21E73EBDB51A7D450948DFE8D76D7F2D1007671";
+        let sk = SigningKey::from_bytes(&hex::decode(sk).unwrap()).unwrap();
+        let pks = decode_all(&[
+            "03935F972DA013F80AE011890FA89B67A27B7BE6CCB24D3274D18B2D4067F261A9",
+            "02F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            "02DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA661",
+        ]);
+        assert_eq!(KeyAggContext::public_key(&sk), pks[0]);
+        let aggnonce = "028465FCF0BBDBCF443AABCCE533D42B4B5A10966AC09A49655E8C42DAAB8FCD61\
+                        037496A3CC86926D452CAFCFD55D25972CA1675D549310DE296BFF42F72EEEA8C9";
+        let aggnonce = AggregateNonce::from_bytes(&hex::decode(aggnonce).unwrap()).unwrap();
+        let msg = "F95466D086770E689964664219266FE5ED215C92AE20BAB5C9D79ADDDDF3C0CF";
+        let msg = hex::decode(msg).unwrap();
+        let k1 = "508B81A611F100A6B2B6B29656590898AF488BCF2E1F55CF22E5CFB84421FE61";
+        let k2 = "FA27FD49B1D50085B481285E1CA205D55C82CC1B31FF5CD54A489829355901F7";
+        let k = |h: &str| e.new_scalar(BigInt::from_bytes_be(Sign::Plus, &hex::decode(h).unwrap()));
+        let g = e.get_generator();
+        let pubnonce = [
+            (&g * k(k1)).encode(PointFormat::Compressed),
+            (&g * k(k2)).encode(PointFormat::Compressed),
+        ]
+        .concat();
+        let pubnonce = PublicNonce::from_bytes(&pubnonce).unwrap();
+        let vectors: [(&[usize], &str); 3] = [
+            (
+                &[0, 1, 2],
+                "012ABBCB52B3016AC03AD82395A1A415C48B93DEF78718E62A7A90052FE224FB",
+            ),
+            (
+                &[1, 0, 2],
+                "9FF2F7AAA856150CC8819254218D3ADEEB0535269051897724F9DB3789513A52",
+            ),
+            (
+                &[1, 2, 0],
+                "FA23C359F6FAC4E7796BB93BC9F0532A95468C539BA20FF86D7C76ED92227900",
+            ),
+        ];
+        for (indices, want) in vectors.iter() {
+            let keys: Vec<Vec<u8>> = indices.iter().map(|&i| pks[i].clone()).collect();
+            let ctx = KeyAggContext::new(&keys).unwrap();
+            let session = Session::new(&ctx, &aggnonce, &msg).unwrap();
+            let secnonce = SecretNonce {
+                k1: k(k1),
+                k2: k(k2),
+                pk: pks[0].clone(),
+            };
+            let psig = session.sign(secnonce, &sk).unwrap();
+            assert_eq!(hex::encode_upper(psig.to_bytes()), *want);
+            assert!(session.verify_partial(&psig, &pubnonce, &pks[0]).is_ok());
+            assert!(session.verify_partial(&psig, &pubnonce, &pks[1]).is_err());
+        }
+    }
+
+    #[test]
+    fn sign_with_tweaks() {
+        let sks = [
+            SigningKey::random(&mut OsRng),
+            SigningKey::random(&mut OsRng),
+            SigningKey::random(&mut OsRng),
+        ];
+        let pks: Vec<Vec<u8>> = sks.iter().map(KeyAggContext::public_key).collect();
+        let mut ctx = KeyAggContext::new(&key_sort(&pks)).unwrap();
+        ctx.apply_tweak(&[0x11; 32], false).unwrap();
+        ctx.apply_tweak(&[0x22; 32], true).unwrap();
+        let msg = b"message";
+        let (secnonces, pubnonces): (Vec<SecretNonce>, Vec<PublicNonce>) = sks
+            .iter()
+            .map(|sk| SecretNonce::generate(sk, Some(&ctx), Some(msg), b"", &mut OsRng))
+            .unzip();
+        let aggnonce = aggregate_nonces(&pubnonces).unwrap();
+        let session = Session::new(&ctx, &aggnonce, msg).unwrap();
+        let psigs: Vec<PartialSignature> = secnonces
+            .into_iter()
+            .zip(sks.iter())
+            .map(|(n, sk)| session.sign(n, sk).unwrap())
+            .collect();
+        for ((psig, pubnonce), pk) in psigs.iter().zip(pubnonces.iter()).zip(pks.iter()) {
+            assert!(session.verify_partial(psig, pubnonce, pk).is_ok());
+        }
+        let sig = session.aggregate(&psigs).unwrap();
+        let vk = ctx.aggregated_key();
+        assert!(vk.verify(msg, &sig).is_ok());
+        assert!(vk.verify(b"massage", &sig).is_err());
+        let sig = session.aggregate(&psigs[..2]).unwrap();
+        assert!(vk.verify(msg, &sig).is_err());
+
+        let outsider = SigningKey::random(&mut OsRng);
+        let (n, _) = SecretNonce::generate(&outsider, None, None, b"", &mut OsRng);
+        assert!(session.sign(n, &outsider).is_err());
+        let (n, _) = SecretNonce::generate(&sks[0], None, None, b"", &mut OsRng);
+        assert!(session.sign(n, &sks[1]).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! This is documentation for the `opcount` module.
+//!
+//! The opcount module counts the arithmetic operations performed by the
+//! current thread, so the cost of formulas and algorithms can be compared
+//! across curves without instrumenting them by hand. It is only available
+//! with the `count-ops` feature, since counting slows down every operation.
+//!
+//! Field operations are counted over the prime field, so an operation over
+//! a quadratic extension is counted as the operations of the prime field
+//! that it consists of. A product of an element by itself is counted as a
+//! squaring. Points are added with complete formulas, so adding a point to
+//! itself is counted as a doubling. The validity checks performed when
+//! points are created are not counted.
+//!
+//! # Counting Operations
+//!
+//! ```
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use redox_ecc::opcount;
+//!
+//!  let ec = P256.get();
+//!  let g = ec.get_generator();
+//!  let (_, ops) = opcount::count(|| &g + &g);
+//!  assert_eq!(ops.dbl, 1);
+//!  println!("{}", ops);
+//! ```
+
+use std::cell::Cell;
+use std::ops::Sub;
+
+thread_local! {
+    static COUNTS: Cell<OpCounts> = Cell::new(OpCounts::default());
+}
+
+/// OpCounts is a tally of arithmetic operations.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OpCounts {
+    /// Multiplications of field elements.
+    pub mul: u64,
+    /// Squarings of field elements.
+    pub sqr: u64,
+    /// Inversions of field elements.
+    pub inv: u64,
+    /// Additions of two different points.
+    pub add: u64,
+    /// Doublings of points.
+    pub dbl: u64,
+}
+
+impl Sub for OpCounts {
+    type Output = OpCounts;
+    fn sub(self, other: OpCounts) -> OpCounts {
+        OpCounts {
+            mul: self.mul - other.mul,
+            sqr: self.sqr - other.sqr,
+            inv: self.inv - other.inv,
+            add: self.add - other.add,
+            dbl: self.dbl - other.dbl,
+        }
+    }
+}
+
+impl std::fmt::Display for OpCounts {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}M {}S {}I, {}A {}D",
+            self.mul, self.sqr, self.inv, self.add, self.dbl
+        )
+    }
+}
+
+/// Returns the operations counted by the current thread since the last
+/// call to `reset`.
+pub fn counts() -> OpCounts {
+    COUNTS.with(|c| c.get())
+}
+
+/// Sets the counters of the current thread to zero.
+pub fn reset() {
+    COUNTS.with(|c| c.set(OpCounts::default()))
+}
+
+/// Runs `f` and returns its output together with the operations it
+/// performed. The counters are not reset, so calls can be nested.
+pub fn count<T, F: FnOnce() -> T>(f: F) -> (T, OpCounts) {
+    let before = counts();
+    let out = f();
+    (out, counts() - before)
+}
+
+pub(crate) fn tick<F: FnOnce(&mut OpCounts)>(f: F) {
+    COUNTS.with(|c| {
+        let mut counts = c.get();
+        f(&mut counts);
+        c.set(counts);
+    })
+}
+
+/// Runs `f` without counting the operations it performs.
+pub(crate) fn uncounted<T, F: FnOnce() -> T>(f: F) -> T {
+    let before = counts();
+    let out = f();
+    COUNTS.with(|c| c.set(before));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::{count, OpCounts};
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::field::Field;
+    use crate::instances::{GetCurve, EDWARDS25519, P256};
+
+    #[test]
+    fn field() {
+        let f = P256.get().get_field();
+        let (x, y) = (f.elt(BigInt::from(3)), f.elt(BigInt::from(5)));
+        let (_, ops) = count(|| {
+            let _ = &x * &y;
+            let _ = &x * &x;
+            let _ = &y ^ 2u32;
+            let _ = 1u32 / &x;
+        });
+        let want = OpCounts {
+            mul: 1,
+            sqr: 2,
+            inv: 1,
+            ..OpCounts::default()
+        };
+        assert_eq!(ops, want);
+    }
+
+    #[test]
+    fn points() {
+        // Costello-Renes-Batina, Alg. 1 takes 12M + 3m_a + 2m_3b, and the
+        // twisted Edwards addition of Hisil et al. takes 9M + m_a + m_d;
+        // the points are not normalized, so the mixed formulas are not used
+        let g = P256.get().get_generator();
+        let g = &g + &g;
+        let (_, ops) = count(|| &(&g + &g) + &g);
+        assert_eq!((ops.add, ops.dbl), (1, 1));
+        assert_eq!(ops.mul + ops.sqr, 2 * 17);
+        let g = EDWARDS25519.get().get_generator();
+        let g = &g + &g;
+        let (_, ops) = count(|| &(&g + &g) + &g);
+        assert_eq!((ops.add, ops.dbl), (1, 1));
+        assert_eq!(ops.mul + ops.sqr, 2 * 11);
+    }
+
+    #[test]
+    fn mixed_points() {
+        // with Z2 = 1, Alg. 2 of Costello-Renes-Batina takes 11M + 3m_a +
+        // 2m_3b, and the Edwards addition saves the product Z1 * Z2
+        let g = P256.get().get_generator();
+        let (_, ops) = count(|| &(&g + &g) + &g);
+        assert_eq!(ops.mul + ops.sqr, 2 * 16);
+        let g = EDWARDS25519.get().get_generator();
+        let (_, ops) = count(|| &(&g + &g) + &g);
+        assert_eq!(ops.mul + ops.sqr, 2 * 10);
+    }
+
+    #[test]
+    fn nested() {
+        let g = P256.get().get_generator();
+        let (inner, outer) = count(|| count(|| &g + &g).1);
+        assert_eq!(inner, outer);
+    }
+}
@@ -0,0 +1,674 @@
+//! This is documentation for the `oprf` module.
+//!
+//! The oprf module implements the oblivious pseudorandom functions of
+//! RFC 9497: the base protocol (OPRF), the verifiable protocol (VOPRF), and
+//! the partially-oblivious protocol (POPRF).
+//!
+//! A client learns the output of a pseudorandom function keyed by the
+//! server, while the server learns nothing about the input of the client.
+//! In the verifiable modes, the server proves that it used the key matching
+//! its public key, and in the partially-oblivious mode both parties also
+//! agree on a public input `info`.
+//!
+//! # Evaluating the Function
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::oprf::{ristretto255_sha512, Client, Mode, Server};
+//!
+//!  let server = Server::derive(ristretto255_sha512(), Mode::Voprf, &[7u8; 32], b"key").unwrap();
+//!  let client = Client::new(ristretto255_sha512(), Mode::Voprf, Some(server.public_key().clone())).unwrap();
+//!  let (blind, blinded) = client.blind(b"input", &mut OsRng).unwrap();
+//!  let eval = server.blind_evaluate(&[blinded.clone()], b"", &mut OsRng).unwrap();
+//!  let out = client.finalize(&[b"input"], &[blind], &[blinded], &eval, b"").unwrap();
+//!  assert_eq!(out[0], server.evaluate(b"input", b"").unwrap());
+//! ```
+
+use digest::Digest;
+use num_bigint::BigInt;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Sha256, Sha384, Sha512};
+
+use std::io::Error;
+use std::marker::PhantomData;
+
+use crate::group::{PrimeGroup, Ristretto255, WeGroup};
+use crate::h2c::{Dst, P256_XMDSHA256_SSWU_RO_, P384_XMDSHA384_SSWU_RO_};
+
+/// Mode is the variant of the protocol, which is bound to every output.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Mode {
+    Oprf = 0,
+    Voprf = 1,
+    Poprf = 2,
+}
+
+impl Mode {
+    fn is_verifiable(self) -> bool {
+        self != Mode::Oprf
+    }
+}
+
+/// Ciphersuite is a prime-order group `G` together with the hash function
+/// `H` used for producing outputs, as specified in RFC 9497, Section 4.
+#[derive(Clone)]
+pub struct Ciphersuite<G, H> {
+    g: G,
+    id: &'static str,
+    _h: PhantomData<H>,
+}
+
+impl<G: PrimeGroup, H: Digest> Ciphersuite<G, H> {
+    /// Returns a ciphersuite, where `id` is its identifier, e.g.,
+    /// `P256-SHA256`.
+    pub fn new(g: G, id: &'static str) -> Ciphersuite<G, H> {
+        Ciphersuite {
+            g,
+            id,
+            _h: PhantomData,
+        }
+    }
+    pub fn group(&self) -> &G {
+        &self.g
+    }
+    pub fn id(&self) -> &str {
+        self.id
+    }
+}
+
+/// Returns the ristretto255-SHA512 ciphersuite.
+pub fn ristretto255_sha512() -> Ciphersuite<Ristretto255, Sha512> {
+    Ciphersuite::new(Ristretto255::new(), "ristretto255-SHA512")
+}
+
+/// Returns the P256-SHA256 ciphersuite.
+pub fn p256_sha256() -> Ciphersuite<WeGroup, Sha256> {
+    Ciphersuite::new(WeGroup::new(P256_XMDSHA256_SSWU_RO_), "P256-SHA256")
+}
+
+/// Returns the P384-SHA384 ciphersuite.
+pub fn p384_sha384() -> Ciphersuite<WeGroup, Sha384> {
+    Ciphersuite::new(WeGroup::new(P384_XMDSHA384_SSWU_RO_), "P384-SHA384")
+}
+
+/// Proof is a proof of equality of discrete logarithms between the public
+/// key of the server and the evaluated elements.
+pub struct Proof<G: PrimeGroup> {
+    c: G::Scalar,
+    s: G::Scalar,
+}
+
+impl<G: PrimeGroup> Clone for Proof<G> {
+    fn clone(&self) -> Self {
+        Proof {
+            c: self.c.clone(),
+            s: self.s.clone(),
+        }
+    }
+}
+
+impl<G: PrimeGroup> Proof<G> {
+    /// Parses a proof encoded as two scalars of `g`.
+    pub fn from_bytes(g: &G, buf: &[u8]) -> Result<Proof<G>, Error> {
+        let n = buf.len() / 2;
+        if !buf.len().is_multiple_of(2) {
+            return Err(Error::other(ERR_PROOF_LEN));
+        }
+        let c = g.decode_scalar(&buf[..n])?;
+        let s = g.decode_scalar(&buf[n..])?;
+        Ok(Proof { c, s })
+    }
+    pub fn to_bytes(&self, g: &G) -> Vec<u8> {
+        let mut out = g.encode_scalar(&self.c);
+        out.append(&mut g.encode_scalar(&self.s));
+        out
+    }
+}
+
+/// Evaluation is the response of the server to a batch of blinded
+/// elements. The proof is present only in the verifiable modes.
+pub struct Evaluation<G: PrimeGroup> {
+    pub elements: Vec<G::Elt>,
+    pub proof: Option<Proof<G>>,
+}
+
+impl<G: PrimeGroup> Clone for Evaluation<G> {
+    fn clone(&self) -> Self {
+        Evaluation {
+            elements: self.elements.clone(),
+            proof: self.proof.clone(),
+        }
+    }
+}
+
+/// Context holds the operations shared by clients and servers, which are
+/// domain-separated by the mode and the ciphersuite.
+#[derive(Clone)]
+struct Context<G, H> {
+    cs: Ciphersuite<G, H>,
+    mode: Mode,
+    ctx: Vec<u8>,
+}
+
+impl<G: PrimeGroup, H: Digest> Context<G, H> {
+    fn new(cs: Ciphersuite<G, H>, mode: Mode) -> Context<G, H> {
+        let mut ctx = b"OPRFV1-".to_vec();
+        ctx.push(mode as u8);
+        ctx.push(b'-');
+        ctx.extend_from_slice(cs.id.as_bytes());
+        Context { cs, mode, ctx }
+    }
+    fn dst(&self, prefix: &[u8]) -> Dst {
+        Dst::new(&[prefix, &self.ctx].concat()).unwrap()
+    }
+    fn hash_to_group(&self, input: &[u8]) -> Result<G::Elt, Error> {
+        let p = self.cs.g.hash_to_group(input, &self.dst(b"HashToGroup-"));
+        if p == self.cs.g.identity() {
+            return Err(Error::other(ERR_INVALID_INPUT));
+        }
+        Ok(p)
+    }
+    fn hash_to_scalar(&self, input: &[u8]) -> G::Scalar {
+        self.cs.g.hash_to_scalar(input, &self.dst(b"HashToScalar-"))
+    }
+    fn is_zero(&self, k: &G::Scalar) -> bool {
+        self.cs.g.encode_scalar(k).iter().all(|&b| b == 0)
+    }
+    /// Returns the scalar `m` that tweaks the key with the public input.
+    fn tweak(&self, info: &[u8]) -> Result<G::Scalar, Error> {
+        let framed = [b"Info", &i2osp2(info)?[..], info].concat();
+        Ok(self.hash_to_scalar(&framed))
+    }
+    fn check_info(&self, info: &[u8]) -> Result<(), Error> {
+        if self.mode != Mode::Poprf && !info.is_empty() {
+            return Err(Error::other(ERR_INFO));
+        }
+        Ok(())
+    }
+    /// Hashes the input, the public input, and the unblinded element into
+    /// the output of the function.
+    fn output(&self, input: &[u8], info: &[u8], n: &G::Elt) -> Result<Vec<u8>, Error> {
+        let unblinded = self.cs.g.encode(n);
+        let mut h = H::new();
+        h.update(i2osp2(input)?);
+        h.update(input);
+        if self.mode == Mode::Poprf {
+            h.update(i2osp2(info)?);
+            h.update(info);
+        }
+        h.update(i2osp2(&unblinded)?);
+        h.update(&unblinded);
+        h.update(b"Finalize");
+        Ok(h.finalize().to_vec())
+    }
+    /// Returns the composite elements `M` and `Z`, which are random linear
+    /// combinations of `c` and `d`, respectively.
+    // based on RFC 9497, Section 2.2.1
+    fn composites(&self, b: &G::Elt, c: &[G::Elt], d: &[G::Elt]) -> (G::Elt, G::Elt) {
+        let g = &self.cs.g;
+        let bm = g.encode(b);
+        let seed_dst = [b"Seed-", &self.ctx[..]].concat();
+        let seed = H::new()
+            .chain_update(len2(&bm))
+            .chain_update(&bm)
+            .chain_update(len2(&seed_dst))
+            .chain_update(&seed_dst)
+            .finalize();
+        let di: Vec<G::Scalar> = c
+            .iter()
+            .zip(d.iter())
+            .enumerate()
+            .map(|(i, (ci, di))| {
+                let ci = g.encode(ci);
+                let di = g.encode(di);
+                let transcript = [
+                    &len2(&seed)[..],
+                    &seed,
+                    &(i as u16).to_be_bytes(),
+                    &len2(&ci),
+                    &ci,
+                    &len2(&di),
+                    &di,
+                    b"Composite",
+                ]
+                .concat();
+                self.hash_to_scalar(&transcript)
+            })
+            .collect();
+        (g.multi_scalar_mul(c, &di), g.multi_scalar_mul(d, &di))
+    }
+    fn challenge(&self, elts: [&G::Elt; 5]) -> G::Scalar {
+        let mut transcript = Vec::new();
+        for e in elts.iter() {
+            let enc = self.cs.g.encode(e);
+            transcript.extend_from_slice(&len2(&enc));
+            transcript.extend_from_slice(&enc);
+        }
+        transcript.extend_from_slice(b"Challenge");
+        self.hash_to_scalar(&transcript)
+    }
+    /// Proves that `B = [k]A` and `D_i = [k]C_i` for all `i`.
+    // based on RFC 9497, Section 2.2.1
+    fn generate_proof(
+        &self,
+        k: &G::Scalar,
+        r: &G::Scalar,
+        (a, b): (&G::Elt, &G::Elt),
+        c: &[G::Elt],
+        d: &[G::Elt],
+    ) -> Proof<G> {
+        let (m, _) = self.composites(b, c, d);
+        let z = m.clone() * k;
+        let t2 = a.clone() * r;
+        let t3 = m.clone() * r;
+        let c = self.challenge([b, &m, &z, &t2, &t3]);
+        let s = r.clone() - c.clone() * k;
+        Proof { c, s }
+    }
+    // based on RFC 9497, Section 2.2.2
+    fn verify_proof(
+        &self,
+        (a, b): (&G::Elt, &G::Elt),
+        c: &[G::Elt],
+        d: &[G::Elt],
+        proof: &Proof<G>,
+    ) -> Result<(), Error> {
+        let g = &self.cs.g;
+        let (m, z) = self.composites(b, c, d);
+        let t2 = a.clone() * &proof.s + b.clone() * &proof.c;
+        let t3 = m.clone() * &proof.s + z.clone() * &proof.c;
+        let want = self.challenge([b, &m, &z, &t2, &t3]);
+        if g.encode_scalar(&want) != g.encode_scalar(&proof.c) {
+            return Err(Error::other(ERR_PROOF));
+        }
+        Ok(())
+    }
+}
+
+/// Client is the party that holds the inputs of the function.
+#[derive(Clone)]
+pub struct Client<G: PrimeGroup, H> {
+    ctx: Context<G, H>,
+    pk: Option<G::Elt>,
+}
+
+impl<G: PrimeGroup, H: Digest> Client<G, H> {
+    /// Returns a client running the protocol in the given mode. The public
+    /// key of the server is required in the verifiable modes, and ignored
+    /// in the base mode.
+    pub fn new(
+        cs: Ciphersuite<G, H>,
+        mode: Mode,
+        pk: Option<G::Elt>,
+    ) -> Result<Client<G, H>, Error> {
+        if mode.is_verifiable() && pk.is_none() {
+            return Err(Error::other(ERR_PUBLIC_KEY));
+        }
+        Ok(Client {
+            ctx: Context::new(cs, mode),
+            pk: pk.filter(|_| mode.is_verifiable()),
+        })
+    }
+    /// Blinds `input` with a scalar sampled uniformly at random from `rng`.
+    /// It returns the blind, which must be kept for finalization, and the
+    /// blinded element, which is sent to the server.
+    pub fn blind<R: RngCore + CryptoRng>(
+        &self,
+        input: &[u8],
+        rng: &mut R,
+    ) -> Result<(G::Scalar, G::Elt), Error> {
+        let blind = self.ctx.cs.g.random_scalar(rng);
+        let p = self.blind_with(input, &blind)?;
+        Ok((blind, p))
+    }
+    /// Blinds `input` with the scalar `blind`, which must be non-zero and
+    /// secret.
+    pub fn blind_with(&self, input: &[u8], blind: &G::Scalar) -> Result<G::Elt, Error> {
+        i2osp2(input)?;
+        Ok(self.ctx.hash_to_group(input)? * blind)
+    }
+    /// Returns the outputs of the function for a batch of inputs, given the
+    /// blinds and blinded elements returned by `blind`, and the evaluation
+    /// of the server. In the verifiable modes, it fails if the proof is
+    /// invalid.
+    // based on RFC 9497, Sections 3.3.1, 3.3.2 and 3.3.3
+    pub fn finalize(
+        &self,
+        inputs: &[&[u8]],
+        blinds: &[G::Scalar],
+        blinded: &[G::Elt],
+        eval: &Evaluation<G>,
+        info: &[u8],
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let ctx = &self.ctx;
+        let g = &ctx.cs.g;
+        ctx.check_info(info)?;
+        let n = inputs.len();
+        if blinds.len() != n || blinded.len() != n || eval.elements.len() != n {
+            return Err(Error::other(ERR_BATCH_LEN));
+        }
+        if let Some(pk) = &self.pk {
+            let proof = eval.proof.as_ref().ok_or_else(|| Error::other(ERR_PROOF))?;
+            if ctx.mode == Mode::Poprf {
+                let tweaked = g.generator() * ctx.tweak(info)? + pk;
+                if tweaked == g.identity() {
+                    return Err(Error::other(ERR_INVALID_INPUT));
+                }
+                let a = g.generator();
+                ctx.verify_proof((&a, &tweaked), &eval.elements, blinded, proof)?;
+            } else {
+                let a = g.generator();
+                ctx.verify_proof((&a, pk), blinded, &eval.elements, proof)?;
+            }
+        }
+        let one = g.new_scalar(BigInt::from(1));
+        inputs
+            .iter()
+            .zip(blinds.iter().zip(eval.elements.iter()))
+            .map(|(input, (blind, elt))| {
+                let inv = one.clone() / blind;
+                ctx.output(input, info, &(elt.clone() * inv))
+            })
+            .collect()
+    }
+}
+
+/// Server is the party that holds the key of the function.
+#[derive(Clone)]
+pub struct Server<G: PrimeGroup, H> {
+    ctx: Context<G, H>,
+    sk: G::Scalar,
+    pk: G::Elt,
+}
+
+impl<G: PrimeGroup, H: Digest> Server<G, H> {
+    /// Returns a server with the secret key `sk`, which must not be zero.
+    pub fn new(cs: Ciphersuite<G, H>, mode: Mode, sk: G::Scalar) -> Result<Server<G, H>, Error> {
+        let ctx = Context::new(cs, mode);
+        if ctx.is_zero(&sk) {
+            return Err(Error::other(ERR_KEY));
+        }
+        let pk = ctx.cs.g.generator() * &sk;
+        Ok(Server { ctx, sk, pk })
+    }
+    /// Returns a server with a key sampled uniformly at random from `rng`.
+    pub fn random<R: RngCore + CryptoRng>(
+        cs: Ciphersuite<G, H>,
+        mode: Mode,
+        rng: &mut R,
+    ) -> Server<G, H> {
+        let ctx = Context::new(cs, mode);
+        let sk = ctx.cs.g.random_scalar(rng);
+        let pk = ctx.cs.g.generator() * &sk;
+        Server { ctx, sk, pk }
+    }
+    /// Returns a server with the key deterministically derived from `seed`
+    /// and the public `info`.
+    // based on RFC 9497, Section 3.2.1
+    pub fn derive(
+        cs: Ciphersuite<G, H>,
+        mode: Mode,
+        seed: &[u8],
+        info: &[u8],
+    ) -> Result<Server<G, H>, Error> {
+        let ctx = Context::new(cs, mode);
+        let dst = ctx.dst(b"DeriveKeyPair");
+        let input = [seed, &i2osp2(info)?[..], info].concat();
+        for counter in 0..=255u8 {
+            let sk = ctx
+                .cs
+                .g
+                .hash_to_scalar(&[&input[..], &[counter]].concat(), &dst);
+            if !ctx.is_zero(&sk) {
+                return Server::new(ctx.cs, mode, sk);
+            }
+        }
+        Err(Error::other(ERR_DERIVE))
+    }
+    pub fn secret_key(&self) -> &G::Scalar {
+        &self.sk
+    }
+    pub fn public_key(&self) -> &G::Elt {
+        &self.pk
+    }
+    /// Evaluates a batch of blinded elements. In the verifiable modes, the
+    /// evaluation includes a proof for the whole batch. The public input
+    /// `info` must be empty, except in the partially-oblivious mode. The
+    /// randomness of the proof is sampled from `rng`.
+    // based on RFC 9497, Sections 3.3.1, 3.3.2 and 3.3.3
+    pub fn blind_evaluate<R: RngCore + CryptoRng>(
+        &self,
+        blinded: &[G::Elt],
+        info: &[u8],
+        rng: &mut R,
+    ) -> Result<Evaluation<G>, Error> {
+        let r = self.ctx.cs.g.random_scalar(rng);
+        self.blind_evaluate_with(blinded, info, &r)
+    }
+    fn blind_evaluate_with(
+        &self,
+        blinded: &[G::Elt],
+        info: &[u8],
+        r: &G::Scalar,
+    ) -> Result<Evaluation<G>, Error> {
+        let ctx = &self.ctx;
+        let g = &ctx.cs.g;
+        ctx.check_info(info)?;
+        let a = g.generator();
+        match ctx.mode {
+            Mode::Oprf => Ok(Evaluation {
+                elements: blinded.iter().map(|p| p.clone() * &self.sk).collect(),
+                proof: None,
+            }),
+            Mode::Voprf => {
+                let elements: Vec<G::Elt> = blinded.iter().map(|p| p.clone() * &self.sk).collect();
+                let proof = ctx.generate_proof(&self.sk, r, (&a, &self.pk), blinded, &elements);
+                Ok(Evaluation {
+                    elements,
+                    proof: Some(proof),
+                })
+            }
+            Mode::Poprf => {
+                let t = self.tweaked_key(info)?;
+                let inv = g.new_scalar(BigInt::from(1)) / &t;
+                let elements: Vec<G::Elt> = blinded.iter().map(|p| p.clone() * &inv).collect();
+                let tweaked = a.clone() * &t;
+                let proof = ctx.generate_proof(&t, r, (&a, &tweaked), &elements, blinded);
+                Ok(Evaluation {
+                    elements,
+                    proof: Some(proof),
+                })
+            }
+        }
+    }
+    /// Returns the output of the function for `input` without interacting
+    /// with a client.
+    // based on RFC 9497, Sections 3.3.1 and 3.3.3
+    pub fn evaluate(&self, input: &[u8], info: &[u8]) -> Result<Vec<u8>, Error> {
+        let ctx = &self.ctx;
+        ctx.check_info(info)?;
+        i2osp2(input)?;
+        let p = ctx.hash_to_group(input)?;
+        let n = if ctx.mode == Mode::Poprf {
+            let t = self.tweaked_key(info)?;
+            p * (ctx.cs.g.new_scalar(BigInt::from(1)) / &t)
+        } else {
+            p * &self.sk
+        };
+        ctx.output(input, info, &n)
+    }
+    /// Returns `t = sk + m`, where `m` is the tweak derived from `info`.
+    fn tweaked_key(&self, info: &[u8]) -> Result<G::Scalar, Error> {
+        let t = self.sk.clone() + self.ctx.tweak(info)?;
+        if self.ctx.is_zero(&t) {
+            return Err(Error::other(ERR_INVERSE));
+        }
+        Ok(t)
+    }
+}
+
+/// Returns the length of `buf` encoded in two bytes, or an error if it
+/// does not fit.
+fn i2osp2(buf: &[u8]) -> Result<[u8; 2], Error> {
+    if buf.len() > u16::MAX as usize {
+        return Err(Error::other(ERR_LEN));
+    }
+    Ok(len2(buf))
+}
+
+fn len2(buf: &[u8]) -> [u8; 2] {
+    (buf.len() as u16).to_be_bytes()
+}
+
+const ERR_LEN: &str = "inputs must be shorter than 65536 bytes";
+const ERR_INFO: &str = "public input is only allowed in the POPRF mode";
+const ERR_INVALID_INPUT: &str = "input is mapped to the identity";
+const ERR_INVERSE: &str = "tweaked key is not invertible";
+const ERR_DERIVE: &str = "key derivation failed";
+const ERR_KEY: &str = "secret key must not be zero";
+const ERR_PUBLIC_KEY: &str = "verifiable modes require the public key of the server";
+const ERR_BATCH_LEN: &str = "batch sizes must match";
+const ERR_PROOF: &str = "proof is invalid";
+const ERR_PROOF_LEN: &str = "proof must be encoded as two scalars";
+
+#[cfg(test)]
+mod tests {
+    use digest::Digest;
+    use rand_core::OsRng;
+
+    use super::{
+        p256_sha256, p384_sha384, ristretto255_sha512, Ciphersuite, Client, Mode, Proof, Server,
+    };
+    use crate::group::PrimeGroup;
+
+    struct Vector {
+        mode: Mode,
+        sk: &'static str,
+        input: &'static str,
+        info: &'static str,
+        output: &'static str,
+    }
+
+    fn check_vectors<G: PrimeGroup, H: Digest>(cs: fn() -> Ciphersuite<G, H>, vectors: &[Vector]) {
+        let seed = [0xa3u8; 32];
+        for v in vectors.iter() {
+            let server = Server::derive(cs(), v.mode, &seed, b"test key").unwrap();
+            let g = cs();
+            assert_eq!(
+                hex::encode(g.group().encode_scalar(server.secret_key())),
+                v.sk
+            );
+            let input = hex::decode(v.input).unwrap();
+            let info = hex::decode(v.info).unwrap();
+            let got = server.evaluate(&input, &info).unwrap();
+            assert_eq!(hex::encode(got), v.output);
+        }
+    }
+
+    // Test vectors taken from RFC 9497, Appendix A, using Seed = a3...a3
+    // and KeyInfo = "test key".
+    #[test]
+    fn rfc9497() {
+        check_vectors(
+            ristretto255_sha512,
+            &[
+                Vector {
+                    mode: Mode::Oprf,
+                    sk: "5ebcea5ee37023ccb9fc2d2019f9d7737be85591ae8652ffa9ef0f4d37063b0e",
+                    input: "00",
+                    info: "",
+                    output: "527759c3d9366f277d8c6020418d96bb393ba2afb20ff90df23fb7708264e2f3\
+                             ab9135e3bd69955851de4b1f9fe8a0973396719b7912ba9ee8aa7d0b5e24bcf6",
+                },
+                Vector {
+                    mode: Mode::Poprf,
+                    sk: "145c79c108538421ac164ecbe131942136d5570b16d8bf41a24d4337da981e07",
+                    input: "00",
+                    info: "7465737420696e666f",
+                    output: "ca688351e88afb1d841fde4401c79efebb2eb75e7998fa9737bd5a82a152406d\
+                             38bd29f680504e54fd4587eddcf2f37a2617ac2fbd2993f7bdf45442ace7d221",
+                },
+            ],
+        );
+        check_vectors(
+            p256_sha256,
+            &[Vector {
+                mode: Mode::Oprf,
+                sk: "159749d750713afe245d2d39ccfaae8381c53ce92d098a9375ee70739c7ac0bf",
+                input: "00",
+                info: "",
+                output: "a0b34de5fa4c5b6da07e72af73cc507cceeb48981b97b7285fc375345fe495dd",
+            }],
+        );
+    }
+
+    fn check_protocol<G: PrimeGroup, H: Digest>(cs: fn() -> Ciphersuite<G, H>, modes: &[Mode]) {
+        let inputs: [&[u8]; 2] = [b"first input", b"second input"];
+        for &mode in modes.iter() {
+            let info: &[u8] = if mode == Mode::Poprf { b"info" } else { b"" };
+            let server = Server::random(cs(), mode, &mut OsRng);
+            let pk = Some(server.public_key().clone());
+            let client = Client::new(cs(), mode, pk).unwrap();
+            let (b0, p0) = client.blind(inputs[0], &mut OsRng).unwrap();
+            let (b1, p1) = client.blind(inputs[1], &mut OsRng).unwrap();
+            let (blinds, blinded) = ([b0, b1], [p0, p1]);
+            let eval = server.blind_evaluate(&blinded, info, &mut OsRng).unwrap();
+            assert_eq!(eval.proof.is_some(), mode != Mode::Oprf);
+            let out = client
+                .finalize(&inputs, &blinds, &blinded, &eval, info)
+                .unwrap();
+            for (input, out) in inputs.iter().zip(out.iter()) {
+                assert_eq!(*out, server.evaluate(input, info).unwrap(), "{:?}", mode);
+            }
+            if mode == Mode::Oprf {
+                assert!(server
+                    .blind_evaluate(&blinded, b"info", &mut OsRng)
+                    .is_err());
+                continue;
+            }
+            let g = cs();
+            let proof = eval.proof.as_ref().unwrap().to_bytes(g.group());
+            assert!(Proof::from_bytes(g.group(), &proof).is_ok());
+            assert!(Proof::from_bytes(g.group(), &proof[1..]).is_err());
+            // swaps the evaluated elements so the proof no longer holds
+            let mut bad = eval.clone();
+            bad.elements.swap(0, 1);
+            assert!(client
+                .finalize(&inputs, &blinds, &blinded, &bad, info)
+                .is_err());
+            let other = Server::random(cs(), mode, &mut OsRng);
+            let eval = other.blind_evaluate(&blinded, info, &mut OsRng).unwrap();
+            assert!(client
+                .finalize(&inputs, &blinds, &blinded, &eval, info)
+                .is_err());
+        }
+        assert!(Client::new(cs(), Mode::Voprf, None).is_err());
+    }
+
+    #[test]
+    fn protocol() {
+        check_protocol(ristretto255_sha512, &[Mode::Oprf, Mode::Voprf, Mode::Poprf]);
+        check_protocol(p256_sha256, &[Mode::Voprf]);
+        let server = Server::derive(p384_sha384(), Mode::Oprf, &[1u8; 32], b"").unwrap();
+        assert_eq!(server.evaluate(b"input", b"").unwrap().len(), 48);
+    }
+
+    #[test]
+    fn public_input() {
+        let server = Server::random(ristretto255_sha512(), Mode::Poprf, &mut OsRng);
+        let pk = Some(server.public_key().clone());
+        let client = Client::new(ristretto255_sha512(), Mode::Poprf, pk).unwrap();
+        let (blind, blinded) = client.blind(b"input", &mut OsRng).unwrap();
+        let (blinds, blinded) = ([blind], [blinded]);
+        let eval = server
+            .blind_evaluate(&blinded, b"info", &mut OsRng)
+            .unwrap();
+        let out = client.finalize(&[b"input"], &blinds, &blinded, &eval, b"info");
+        assert!(out.is_ok());
+        let out = client.finalize(&[b"input"], &blinds, &blinded, &eval, b"other");
+        assert!(out.is_err());
+        let a = server.evaluate(b"input", b"info").unwrap();
+        let b = server.evaluate(b"input", b"other").unwrap();
+        assert_ne!(a, b);
+    }
+}
@@ -1,3 +1,7 @@
+use digest::Digest;
+use num_bigint::{BigInt, Sign};
+use rand_core::{CryptoRng, RngCore};
+
 #[doc(hidden)]
 macro_rules! make_trait {
     (binary, $trait:ident, $name:ident) => {
@@ -72,7 +76,15 @@ pub trait Serialize {
     fn to_bytes_le(&self) -> Vec<u8>;
 }
 
+/// Endianness is the byte order of an integer encoded as an array of bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endianness {
+    Big,
+    Little,
+}
+
 /// The Deserialize trait recovers native types from arrays of bytes
+#[allow(clippy::wrong_self_convention)]
 pub trait Deserialize {
     type Deser;
     fn from_bytes_be(&self, _: &[u8]) -> Result<Self::Deser, std::io::Error>;
@@ -96,3 +108,69 @@ pub trait FromFactory<T: Sized> {
     type Output;
     fn from(&self, _: T) -> Self::Output;
 }
+
+/// Returns `m*n` for a fresh random `m` of `BLINDING_BITS` bits, so that
+/// `k + m*n` is a randomized representative of `k` modulo `n`.
+pub(crate) fn blinding_mask<R: RngCore + CryptoRng>(n: &BigInt, rng: &mut R) -> BigInt {
+    let mut m = [0u8; BLINDING_BITS / 8];
+    rng.fill_bytes(&mut m);
+    BigInt::from_bytes_be(Sign::Plus, &m) * n
+}
+
+const BLINDING_BITS: usize = 64;
+
+/// Returns `f` applied to each of the `items`, in the same order as the
+/// `items`. With the `parallel` feature, the items are processed on the
+/// thread pool of rayon, so the output does not depend on the feature.
+pub(crate) fn map_batch<T, U, F>(items: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// Returns the length of the chunks that a batch of `n` items is split into
+/// for `map_batch`, which is `n` unless the `parallel` feature is enabled.
+pub(crate) fn batch_chunk_len(n: usize) -> usize {
+    #[cfg(feature = "parallel")]
+    let n = n.div_ceil(rayon::current_num_threads());
+    n.max(1)
+}
+
+/// Returns the integer read in big-endian order from the digest of `msg`
+/// under `D`, which is at least `len` bytes long. Digests shorter than `len`
+/// are extended with the digests of `msg` followed by a 32-bit big-endian
+/// counter starting at one, in the manner of the ANSI X9.63 KDF.
+pub(crate) fn hash_wide<D: Digest>(msg: &[u8], len: usize) -> BigInt {
+    let mut out = D::digest(msg).to_vec();
+    let mut counter = 1u32;
+    while out.len() < len {
+        let h = D::new()
+            .chain_update(msg)
+            .chain_update(counter.to_be_bytes())
+            .finalize();
+        out.extend_from_slice(&h);
+        counter += 1;
+    }
+    BigInt::from_bytes_be(Sign::Plus, &out)
+}
+
+/// Extra bytes reduced by `hash_wide` callers, so that the bias of the
+/// result is negligible.
+pub(crate) const WIDE_REDUCTION_BYTES: usize = 16;
+
+/// Overwrites the digits of `k` with zeros in place, and sets it to zero.
+pub(crate) fn wipe(k: &mut BigInt) {
+    let digits = k.bits().div_ceil(32);
+    k.assign_from_slice(Sign::Plus, &vec![0u32; digits]);
+}
@@ -0,0 +1,464 @@
+//! This is documentation for the `bls12381` module.
+//!
+//! The bls12381 module implements the optimal ate pairing on BLS12-381,
+//! whose groups are the curve `y^2 = x^3 + 4` over GF(p), its sextic twist
+//! `y^2 = x^3 + 4(1+i)` over GF(p^2), and the subgroup of order `r` of
+//! GF(p^12)*, with GF(p^12) defined by `xi = 1+i`.
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+use std::io::Error;
+
+use crate::ellipticcurve::{EcPoint, EllipticCurve, PointFormat};
+use crate::field::{Field, Sqrt};
+use crate::instances::{GetCurve, BLS12381G1, BLS12381G2};
+use crate::ops::{FromFactory, Serialize};
+use crate::pairing::{Fp12, Fp12Elt, G2Curve, G2Point, Gt, GtElt, PairingEngine};
+use crate::primefield::FpElt;
+use crate::quadraticfield::Fp2Elt;
+use crate::weierstrass::{Curve, Point, ProyCoordinates, Scalar};
+
+/// Bls12381 is the pairing engine of BLS12-381.
+#[derive(Clone)]
+pub struct Bls12381 {
+    g1: Curve,
+    g2: G2Curve,
+    t: Fp12,
+    gt: Gt,
+}
+
+impl Bls12381 {
+    pub fn new() -> Bls12381 {
+        let g1 = BLS12381G1.get();
+        let g2 = BLS12381G2.get();
+        let f = g2.get_field();
+        let xi = f.new_elt(f.get_base().one(), f.get_base().one());
+        let t = Fp12::new(f, xi);
+        let gt = Gt::new(t.clone(), g1.get_order());
+        Bls12381 { g1, g2, t, gt }
+    }
+    /// Returns the curve of G1.
+    pub fn g1(&self) -> &Curve {
+        &self.g1
+    }
+    /// Returns the curve of G2.
+    pub fn g2(&self) -> &G2Curve {
+        &self.g2
+    }
+    /// Returns the target group.
+    pub fn gt(&self) -> &Gt {
+        &self.gt
+    }
+    /// Determines whether `p` belongs to G1 by checking whether
+    /// `phi(p) = [-x^2]p`, where `phi(x, y) = (beta*x, y)` and `beta` is a
+    /// cube root of unity. This is about twice as fast as multiplying by `r`.
+    // Reference: "A note on group membership tests for G1, G2 and GT on BLS
+    // pairing-friendly curves" by Scott. [Sec.3] (eprint.iacr.org/2021/1130).
+    pub fn is_in_g1(&self, p: &Point) -> bool {
+        let beta = self.g1.get_field().from(BETA);
+        let phi = self.g1.new_proy_point(ProyCoordinates {
+            x: &p.c.x * beta,
+            y: p.c.y.clone(),
+            z: p.c.z.clone(),
+        });
+        let x2 = BigInt::from(BLS_X) * BigInt::from(BLS_X);
+        phi == -(p * self.g1.new_scalar(x2))
+    }
+    /// Determines whether `q` belongs to G2 by checking whether
+    /// `psi(q) = [x]q`.
+    pub fn is_in_g2(&self, q: &G2Point) -> bool {
+        self.g2.is_in_subgroup(q)
+    }
+    /// Encodes a point of G1 in the format of Zcash, which is described in
+    /// `G2Curve::decode_with_format`, with coordinates of 48 bytes. It has
+    /// no hybrid form.
+    pub fn encode_g1(&self, p: &Point, format: PointFormat) -> Vec<u8> {
+        let compress = format == PointFormat::Compressed;
+        let len = if compress { G1_SIZE } else { 2 * G1_SIZE };
+        if p.is_zero() {
+            let mut out = vec![0u8; len];
+            out[0] = FLAG_INFINITY | if compress { FLAG_COMPRESSED } else { 0 };
+            return out;
+        }
+        let mut p = p.clone();
+        p.normalize();
+        let mut out = p.c.x.to_bytes_be();
+        if compress {
+            out[0] |= FLAG_COMPRESSED;
+            if self.is_largest(&p.c.y) {
+                out[0] |= FLAG_LARGEST;
+            }
+        } else {
+            out.append(&mut p.c.y.to_bytes_be());
+        }
+        out
+    }
+    /// Decodes a point of G1 in the format of Zcash. It fails for points
+    /// outside of G1.
+    pub fn decode_g1(&self, buf: &[u8]) -> Result<Point, Error> {
+        if buf.is_empty() {
+            return Err(Error::other(ERR_SIZE));
+        }
+        let compressed = buf[0] & FLAG_COMPRESSED != 0;
+        let infinity = buf[0] & FLAG_INFINITY != 0;
+        let largest = buf[0] & FLAG_LARGEST != 0;
+        if buf.len() != if compressed { G1_SIZE } else { 2 * G1_SIZE } {
+            return Err(Error::other(ERR_SIZE));
+        }
+        let mut bytes = buf.to_vec();
+        bytes[0] &= !FLAGS;
+        if infinity {
+            if largest || bytes.iter().any(|&b| b != 0) {
+                return Err(Error::other(ERR_INFINITY));
+            }
+            return Ok(self.g1.identity());
+        }
+        let x = self.decode_coord(&bytes[..G1_SIZE])?;
+        let y2 = &x * &x * &x + &self.g1.b;
+        let y = if compressed {
+            if !y2.is_square() {
+                return Err(Error::other(ERR_NOT_ON_CURVE));
+            }
+            let y = y2.sqrt();
+            if self.is_largest(&y) == largest {
+                y
+            } else {
+                -y
+            }
+        } else {
+            if largest {
+                return Err(Error::other(ERR_FLAGS));
+            }
+            let y = self.decode_coord(&bytes[G1_SIZE..])?;
+            if &y * &y != y2 {
+                return Err(Error::other(ERR_NOT_ON_CURVE));
+            }
+            y
+        };
+        let p = self.g1.new_point(x, y);
+        if !self.is_in_g1(&p) {
+            return Err(Error::other(ERR_SUBGROUP));
+        }
+        Ok(p)
+    }
+    fn decode_coord(&self, buf: &[u8]) -> Result<FpElt, Error> {
+        self.g1.get_field().from_canonical_be(buf)
+    }
+    /// Determines whether `y` is larger than `-y`.
+    fn is_largest(&self, y: &FpElt) -> bool {
+        let half = (self.g1.get_field().get_modulus() - 1u32) >> 1usize;
+        BigInt::from_bytes_be(Sign::Plus, &y.to_bytes_be()) > half
+    }
+    /// Returns the Miller function `f_{x,q}(p)`, where `x` is the parameter
+    /// of the curve, which is not yet a pairing.
+    pub fn miller_loop(&self, p: &Point, q: &G2Point) -> Fp12Elt {
+        self.multi_miller_loop(&[(p.clone(), q.clone())])
+    }
+    /// Returns the product of the Miller functions `f_{x,q_i}(p_i)`, sharing
+    /// the squarings of the accumulator among all pairs. Pairs where either
+    /// point is the identity are skipped, since their pairing is `1`.
+    pub fn multi_miller_loop(&self, pairs: &[(Point, G2Point)]) -> Fp12Elt {
+        let mut terms = Vec::new();
+        for (p, q) in pairs.iter().filter(|(p, q)| !p.is_zero() && !q.is_zero()) {
+            let (px, py) = affine_g1(p);
+            let mut q = q.clone();
+            q.normalize();
+            terms.push((px, py, q.clone(), q));
+        }
+        let mut f = self.t.one();
+        if terms.is_empty() {
+            return f;
+        }
+        for i in (0..BLS_X_BITS - 1).rev() {
+            f = f.square();
+            for (px, py, q, t) in terms.iter_mut() {
+                f = f * self.line_double(t, px, py);
+                *t = &*t + &*t;
+                if (BLS_X >> i) & 1 == 1 {
+                    f = f * self.line_add(t, q, px, py);
+                    *t = &*t + &*q;
+                }
+            }
+        }
+        // x is negative, so the result must be inverted, which amounts to a
+        // conjugation after the final exponentiation
+        f.conjugate()
+    }
+    /// Raises the output of the Miller loop to `3(p^12 - 1)/r`, mapping it
+    /// to GT.
+    // Reference: "Efficient Final Exponentiation via Cyclotomic Structure for
+    // Pairings over Families of Elliptic Curves" by Hayashida-Hayasaka-Teruya.
+    // [Sec.4] (eprint.iacr.org/2020/875).
+    pub fn final_exponentiation(&self, f: &Fp12Elt) -> GtElt {
+        // easy part: f^((p^6 - 1)(p^2 + 1))
+        let f = f.conjugate() * f.inv();
+        let f = f.frobenius().frobenius() * &f;
+        // hard part: f^((x - 1)^2 (x + p)(x^2 + p^2 - 1) + 3)
+        let t0 = exp_by_x(&f) * f.conjugate();
+        let t0 = exp_by_x(&t0) * t0.conjugate();
+        let t1 = exp_by_x(&t0) * t0.frobenius();
+        let t2 = exp_by_x(&exp_by_x(&t1)) * t1.frobenius().frobenius() * t1.conjugate();
+        Gt::new_unchecked(t2 * f.cyclotomic_square() * f)
+    }
+    /// Returns the line tangent to `t` evaluated at `(px, py)`, scaled by a
+    /// factor in GF(p^2) that is removed by the final exponentiation.
+    fn line_double(&self, t: &G2Point, px: &FpElt, py: &FpElt) -> Fp12Elt {
+        let (x, y, z) = (&t.c.x, &t.c.y, &t.c.z);
+        let b3 = &self.g2.b + &self.g2.b + &self.g2.b;
+        let l0 = y * y - b3 * z * z;
+        let x2 = x * x;
+        let l1 = -(&x2 + &x2 + x2).mul_base(px);
+        let l4 = (y * z + y * z).mul_base(py);
+        self.line(l0, l1, l4)
+    }
+    /// Returns the line through `t` and the affine point `q` evaluated at
+    /// `(px, py)`, scaled by a factor in GF(p^2).
+    fn line_add(&self, t: &G2Point, q: &G2Point, px: &FpElt, py: &FpElt) -> Fp12Elt {
+        let (x, y, z) = (&t.c.x, &t.c.y, &t.c.z);
+        let (x2, y2) = (&q.c.x, &q.c.y);
+        let theta = y2 * z - y;
+        let delta = x2 * z - x;
+        let l0 = &theta * x2 - &delta * y2;
+        let l1 = -theta.mul_base(px);
+        let l4 = delta.mul_base(py);
+        self.line(l0, l1, l4)
+    }
+    /// Returns the sparse element `l0 + l1*w^2 + l4*w^3`. Lines on the twist
+    /// are mapped to GF(p^12) by `(x, y) -> (x/w^2, y/w^3)`, and scaled by
+    /// `w^3`, which lies in GF(p^4) and is also removed by the final
+    /// exponentiation.
+    fn line(&self, l0: Fp2Elt, l1: Fp2Elt, l4: Fp2Elt) -> Fp12Elt {
+        let z = self.t.get_base().zero();
+        self.t.new_elt([l0, z.clone(), l1, l4, z.clone(), z])
+    }
+}
+
+impl Default for Bls12381 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PairingEngine for Bls12381 {
+    type Scalar = Scalar;
+    type G1 = Point;
+    type G2 = G2Point;
+    type Gt = GtElt;
+    fn order(&self) -> BigUint {
+        self.g1.get_order()
+    }
+    fn new_scalar(&self, k: BigInt) -> Self::Scalar {
+        self.g1.new_scalar(k)
+    }
+    fn g1_generator(&self) -> Self::G1 {
+        self.g1.get_generator()
+    }
+    fn g2_generator(&self) -> Self::G2 {
+        self.g2.get_generator()
+    }
+    fn pairing(&self, p: &Self::G1, q: &Self::G2) -> Self::Gt {
+        self.final_exponentiation(&self.miller_loop(p, q))
+    }
+    fn multi_pairing(&self, pairs: &[(Self::G1, Self::G2)]) -> Self::Gt {
+        self.final_exponentiation(&self.multi_miller_loop(pairs))
+    }
+}
+
+fn affine_g1(p: &Point) -> (FpElt, FpElt) {
+    let mut p = p.clone();
+    p.normalize();
+    (p.c.x, p.c.y)
+}
+
+/// Returns `f^x` for `f` in the cyclotomic subgroup, where `x` is the
+/// parameter of the curve.
+fn exp_by_x(f: &Fp12Elt) -> Fp12Elt {
+    let mut out = f.clone();
+    for i in (0..BLS_X_BITS - 1).rev() {
+        out = out.cyclotomic_square();
+        if (BLS_X >> i) & 1 == 1 {
+            out = out * f;
+        }
+    }
+    // x is negative
+    out.conjugate()
+}
+
+/// BETA is a primitive cube root of unity in GF(p).
+const BETA: &str =
+    "0x5f19672fdf76ce51ba69c6076a0f77eaddb3a93be6f89688de17d813620a00022e01fffffffefffe";
+/// The parameter of BLS12-381 is `x = -BLS_X`.
+const BLS_X: u64 = 0xd201_0000_0001_0000;
+const BLS_X_BITS: usize = 64;
+const G1_SIZE: usize = 48;
+const FLAG_COMPRESSED: u8 = 0x80;
+const FLAG_INFINITY: u8 = 0x40;
+const FLAG_LARGEST: u8 = 0x20;
+const FLAGS: u8 = FLAG_COMPRESSED | FLAG_INFINITY | FLAG_LARGEST;
+const ERR_SIZE: &str = "wrong size of encoded point";
+const ERR_FLAGS: &str = "invalid flags of encoded point";
+const ERR_INFINITY: &str = "invalid encoding of the point at infinity";
+const ERR_NOT_ON_CURVE: &str = "point is not on the curve";
+const ERR_SUBGROUP: &str = "point is not in the subgroup of order r";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::Bls12381;
+    use crate::ellipticcurve::{EcPoint, EllipticCurve, PointFormat};
+    use crate::field::{Field, Sqrt};
+    use crate::ops::FromFactory;
+    use crate::pairing::PairingEngine;
+    use crate::primefield::FpElt;
+
+    #[test]
+    fn reference() {
+        // e(G1, G2) as computed by the bls12_381 crate
+        let e = Bls12381::new();
+        let t = e.gt().get_tower();
+        let f = t.get_base();
+        let gt = t.new_elt([
+            f.from("0x1250ebd871fc0a92a7b2d83168d0d727272d441befa15c503dd8e90ce98db3e7b6d194f60839c508a84305aaca1789b6,0x089a1c5b46e5110b86750ec6a532348868a84045483c92b7af5af689452eafabf1a8943e50439f1d59882a98eaa0170f"),
+            f.from("0x19f26337d205fb469cd6bd15c3d5a04dc88784fbb3d0b2dbdea54d43b2b73f2cbb12d58386a8703e0f948226e47ee89d,0x06fba23eb7c5af0d9f80940ca771b6ffd5857baaf222eb95a7d2809d61bfe02e1bfd1b68ff02f0b8102ae1c2d5d5ab1a"),
+            f.from("0x1368bb445c7c2d209703f239689ce34c0378a68e72a6b3b216da0e22a5031b54ddff57309396b38c881c4c849ec23e87,0x193502b86edb8857c273fa075a50512937e0794e1e65a7617c90d8bd66065b1fffe51d7a579973b1315021ec3c19934f"),
+            f.from("0x11b8b424cd48bf38fcef68083b0b0ec5c81a93b330ee1a677d0d15ff7b984e8978ef48881e32fac91b93b47333e2ba57,0x03350f55a7aefcd3c31b4fcb6ce5771cc6a0e9786ab5973320c806ad360829107ba810c5a09ffdd9be2291a0c25a99a2"),
+            f.from("0x01b2f522473d171391125ba84dc4007cfbf2f8da752f7c74185203fcca589ac719c34dffbbaad8431dad1c1fb597aaa5,0x018107154f25a764bd3c79937a45b84546da634b8f6be14a8061e55cceba478b23f7dacaa35c8ca78beae9624045b4b6"),
+            f.from("0x04c581234d086a9902249b64728ffd21a189e87935a954051c7cdba7b3872629a4fafc05066245cb9108f0242d0fe3ef,0x0f41e58663bf08cf068672cbd01a7ec73baca4d72ca93544deff686bfd6df543d48eaa24afe47e1efde449383b676631"),
+        ]);
+        let got = e.pairing(&e.g1_generator(), &e.g2_generator());
+        assert!(got.as_fp12() == &gt, "got: {}\nwant: {}", got, gt);
+    }
+
+    #[test]
+    fn bilinearity() {
+        let e = Bls12381::new();
+        let (p, q) = (e.g1_generator(), e.g2_generator());
+        let a = e.new_scalar(BigInt::from(0x1234_5678u64));
+        let b = e.new_scalar(BigInt::from(-0x9abc_def0i64));
+        let ab = &a * &b;
+        let gt = e.pairing(&p, &q);
+        let gt_ab = e.pairing(&(&p * &ab), &q);
+        assert!(e.pairing(&(&p * &a), &(&q * &b)) == gt_ab);
+        assert!(e.pairing(&p, &(&q * &ab)) == gt_ab);
+        assert!(&gt ^ &ab == gt_ab);
+        // non-degeneracy, and the order of GT is r
+        assert!(!gt.is_identity());
+        assert!(gt.pow(&e.order()).is_identity());
+        assert!(e.pairing(&-&p, &q) == gt.inv());
+    }
+
+    #[test]
+    fn identity() {
+        let e = Bls12381::new();
+        let (p, q) = (e.g1_generator(), e.g2_generator());
+        assert!(e.pairing(&(&p - &p), &q).is_identity());
+        assert!(e.pairing(&p, &(&q - &q)).is_identity());
+        assert!(e.multi_pairing(&[]).is_identity());
+        assert!(e
+            .multi_pairing(&[(p.clone(), q.clone()), (-&p, q.clone())])
+            .is_identity());
+        assert!(e
+            .multi_pairing(&[(&p - &p, q.clone()), (p, &q - &q)])
+            .is_identity());
+    }
+
+    #[test]
+    fn subgroups() {
+        let e = Bls12381::new();
+        let g1 = e.g1();
+        let f = g1.get_field();
+        let k = e.new_scalar(BigInt::from(0x1234_5678u64));
+        assert!(e.is_in_g1(&e.g1_generator()));
+        assert!(e.is_in_g1(&(e.g1_generator() * &k)));
+        assert!(e.is_in_g1(&g1.identity()));
+        // points of the curve outside of G1
+        let mut found = 0;
+        for x in 1..20 {
+            let x: FpElt = f.from(x);
+            let y2: FpElt = &x * &x * &x + f.from(4);
+            if y2.is_square() {
+                let p = g1.new_point(x, y2.sqrt());
+                assert!(!e.is_in_g1(&p));
+                assert!(!e.is_in_g1(&(p * &k)));
+                found += 1;
+            }
+        }
+        assert!(found > 0);
+
+        let g2 = e.g2();
+        assert!(e.is_in_g2(&e.g2_generator()));
+        assert!(e.is_in_g2(&(e.g2_generator() * &k)));
+        assert!(e.is_in_g2(&g2.identity()));
+        // points of the twist outside of G2 agree with the slow check
+        let f2 = g2.get_field();
+        let mut found = 0;
+        for x in 1..10 {
+            let x = f2.new_elt(f.from(x), f.one());
+            let y2 = &x * &x * &x + f2.new_elt(f.from(4), f.from(4));
+            if y2.is_square() {
+                let q = g2.new_point(x, y2.sqrt());
+                assert!(!e.is_in_g2(&q));
+                assert!(!q.mul_uint(&e.order()).is_zero());
+                found += 1;
+            }
+        }
+        assert!(found > 0);
+    }
+
+    #[test]
+    fn g1_encoding() {
+        // [k]G1 as encoded by the bls12_381 crate
+        let e = Bls12381::new();
+        let k = BigInt::parse_bytes(
+            b"d7359d57963ab8fbbde1852dcf553fedbc31f464d80ee7d40ae683122b45070",
+            16,
+        )
+        .unwrap();
+        let p = e.g1_generator() * e.new_scalar(k);
+        let half = hex::decode("a2c975348667926acf12f3eecb005044e08a7a9b7d95f30bd281b55445107367a2e5d0558be7943c8bd13f9a1a7036fb").unwrap();
+        let full = hex::decode("02c975348667926acf12f3eecb005044e08a7a9b7d95f30bd281b55445107367a2e5d0558be7943c8bd13f9a1a7036fb13f396ec1b79d6f461189d20a0d3f27718dd6efff3066c31380d785bce9957abc640d2f1301266d1e9d7b1e6da60da95").unwrap();
+        assert_eq!(e.encode_g1(&p, PointFormat::Compressed), half);
+        assert_eq!(e.encode_g1(&p, PointFormat::Uncompressed), full);
+        assert!(e.decode_g1(&half).unwrap() == p);
+        assert!(e.decode_g1(&full).unwrap() == p);
+        let mut neg = half.clone();
+        neg[0] ^= 0x20;
+        assert!(e.decode_g1(&neg).unwrap() == -p);
+        let inf = e.encode_g1(&e.g1().identity(), PointFormat::Compressed);
+        assert!(e.decode_g1(&inf).unwrap().is_zero());
+
+        // wrong lengths, flags, and points outside of G1
+        assert!(e.decode_g1(&half[1..]).is_err());
+        let mut bad = full.clone();
+        bad[0] |= 0x20;
+        assert!(e.decode_g1(&bad).is_err());
+        let mut bad = inf.clone();
+        bad[47] = 1;
+        assert!(e.decode_g1(&bad).is_err());
+        let mut bad = vec![0u8; 48];
+        bad[0] = 0x80;
+        bad[47] = 4;
+        assert!(e.decode_g1(&bad).is_err());
+    }
+
+    #[test]
+    fn multi_pairing() {
+        let e = Bls12381::new();
+        let (p, q) = (e.g1_generator(), e.g2_generator());
+        let a = e.new_scalar(BigInt::from(3));
+        let b = e.new_scalar(BigInt::from(-5));
+        let pairs = [
+            (&p * &a, q.clone()),
+            (p.clone(), &q * &b),
+            (&p - &p, q.clone()),
+        ];
+        let want = e.pairing(&pairs[0].0, &pairs[0].1) * e.pairing(&pairs[1].0, &pairs[1].1);
+        assert!(e.multi_pairing(&pairs) == want);
+        assert!(e.multi_pairing(&pairs[..1]) == e.pairing(&pairs[0].0, &pairs[0].1));
+        // e([a]P, Q) * e(-P, [a]Q) = 1, as in the verification of signatures
+        assert!(e
+            .multi_pairing(&[(&p * &a, q.clone()), (-&p, &q * &a)])
+            .is_identity());
+    }
+}
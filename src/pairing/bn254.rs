@@ -0,0 +1,443 @@
+//! This is documentation for the `bn254` module.
+//!
+//! The bn254 module implements the optimal ate pairing on BN254, also known
+//! as alt_bn128, whose groups are the curve `y^2 = x^3 + 3` over GF(p), its
+//! sextic twist `y^2 = x^3 + 3/(9+i)` over GF(p^2), and the subgroup of
+//! order `r` of GF(p^12)*, with GF(p^12) defined by `xi = 9+i`.
+//!
+//! Besides the pairing, it exposes the precompiled contracts `ecAdd`,
+//! `ecMul`, and `ecPairing` of Ethereum (EIP-196 and EIP-197), which take
+//! and return the very same bytes as the EVM does. Coordinates are encoded
+//! as 32-byte big-endian integers, elements `n0 + n1*i` of GF(p^2) as
+//! `n1 || n0`, and the point at infinity as all zeros.
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+use std::io::Error;
+
+use crate::ellipticcurve::{EcPoint, EllipticCurve};
+use crate::field::Field;
+use crate::instances::{GetCurve, BN254, BN254G2};
+use crate::ops::{FromFactory, Serialize};
+use crate::pairing::{Fp12, Fp12Elt, G2Curve, G2Point, Gt, GtElt, PairingEngine};
+use crate::primefield::FpElt;
+use crate::quadraticfield::Fp2Elt;
+use crate::weierstrass::{Curve, Point, Scalar};
+
+/// Bn254 is the pairing engine of BN254.
+#[derive(Clone)]
+pub struct Bn254 {
+    g1: Curve,
+    g2: G2Curve,
+    t: Fp12,
+    gt: Gt,
+    pi_x: Fp2Elt,
+    pi_y: Fp2Elt,
+}
+
+impl Bn254 {
+    pub fn new() -> Bn254 {
+        let g1 = BN254.get();
+        let g2 = BN254G2.get();
+        let f = g2.get_field();
+        let xi: Fp2Elt = f.from("9,1");
+        // pi_x = xi^((p-1)/3) and pi_y = xi^((p-1)/2)
+        let p1 = f.get_modulus() - 1u32;
+        let pi_x = &xi ^ &(&p1 / 3u32);
+        let pi_y = &xi ^ &(&p1 / 2u32);
+        let t = Fp12::new(f, xi);
+        let gt = Gt::new(t.clone(), g1.get_order());
+        Bn254 {
+            g1,
+            g2,
+            t,
+            gt,
+            pi_x,
+            pi_y,
+        }
+    }
+    /// Returns the curve of G1.
+    pub fn g1(&self) -> &Curve {
+        &self.g1
+    }
+    /// Returns the curve of G2.
+    pub fn g2(&self) -> &G2Curve {
+        &self.g2
+    }
+    /// Returns the target group.
+    pub fn gt(&self) -> &Gt {
+        &self.gt
+    }
+    /// Returns the Miller function `f_{6x+2,q}(p)`, where `x` is the
+    /// parameter of the curve, including the two final lines of the optimal
+    /// ate pairing. It is not yet a pairing.
+    pub fn miller_loop(&self, p: &Point, q: &G2Point) -> Fp12Elt {
+        self.multi_miller_loop(&[(p.clone(), q.clone())])
+    }
+    /// Returns the product of the Miller functions of every pair, sharing
+    /// the squarings of the accumulator among all pairs. Pairs where either
+    /// point is the identity are skipped, since their pairing is `1`.
+    pub fn multi_miller_loop(&self, pairs: &[(Point, G2Point)]) -> Fp12Elt {
+        let mut terms = Vec::new();
+        for (p, q) in pairs.iter().filter(|(p, q)| !p.is_zero() && !q.is_zero()) {
+            let mut p = p.clone();
+            p.normalize();
+            let mut q = q.clone();
+            q.normalize();
+            terms.push((p.c.x, p.c.y, q.clone(), q));
+        }
+        let mut f = self.t.one();
+        if terms.is_empty() {
+            return f;
+        }
+        for i in (0..ATE_LOOP_BITS - 1).rev() {
+            f = f.square();
+            for (px, py, q, t) in terms.iter_mut() {
+                f = f * self.line_double(t, px, py);
+                *t = &*t + &*t;
+                if (ATE_LOOP >> i) & 1 == 1 {
+                    f = f * self.line_add(t, q, px, py);
+                    *t = &*t + &*q;
+                }
+            }
+        }
+        // the optimal ate pairing adds the lines through pi(q) and -pi^2(q)
+        for (px, py, q, t) in terms.iter_mut() {
+            let q1 = self.frobenius(q);
+            let q2 = -self.frobenius(&q1);
+            f = f * self.line_add(t, &q1, px, py);
+            *t = &*t + &q1;
+            f = f * self.line_add(t, &q2, px, py);
+        }
+        f
+    }
+    /// Raises the output of the Miller loop to `(p^12 - 1)/r`, mapping it to
+    /// GT.
+    // Reference: "On the Final Exponentiation for Calculating Pairings on
+    // Ordinary Elliptic Curves" by Scott-Benger-Charlemagne-Dominguez
+    // Perez-Kachisa. [Sec.4] (eprint.iacr.org/2008/490).
+    pub fn final_exponentiation(&self, f: &Fp12Elt) -> GtElt {
+        // easy part: f^((p^6 - 1)(p^2 + 1))
+        let f = f.conjugate() * f.inv();
+        let f = f.frobenius().frobenius() * &f;
+        // hard part: f^((p^4 - p^2 + 1)/r)
+        let a = exp_by_x(&f);
+        let b = exp_by_x(&a);
+        let c = exp_by_x(&b);
+        let fp = f.frobenius();
+        let fp2 = fp.frobenius();
+        let y0 = &fp * &fp2 * fp2.frobenius();
+        let y1 = f.conjugate();
+        let y2 = b.frobenius().frobenius();
+        let y3 = a.frobenius().conjugate();
+        let y4 = (&a * b.frobenius()).conjugate();
+        let y5 = b.conjugate();
+        let y6 = (&c * c.frobenius()).conjugate();
+        let t0 = y6.cyclotomic_square() * y4 * &y5;
+        let t1 = y3 * y5 * &t0;
+        let t0 = t0 * y2;
+        let t1 = (t1.cyclotomic_square() * t0).cyclotomic_square();
+        let t0 = &t1 * y1;
+        let t1 = t1 * y0;
+        Gt::new_unchecked(t0.cyclotomic_square() * t1)
+    }
+    /// Determines whether `q` belongs to G2.
+    pub fn is_in_g2(&self, q: &G2Point) -> bool {
+        self.g2.is_in_subgroup(q)
+    }
+    /// Decodes a point of G1 given as `x || y`. It fails for points that
+    /// are not on the curve.
+    pub fn decode_g1(&self, buf: &[u8]) -> Result<Point, Error> {
+        if buf.len() != 2 * COORD_SIZE {
+            return Err(Error::other(ERR_SIZE));
+        }
+        if buf.iter().all(|&b| b == 0) {
+            return Ok(self.g1.identity());
+        }
+        let x = self.decode_coord(&buf[..COORD_SIZE])?;
+        let y = self.decode_coord(&buf[COORD_SIZE..])?;
+        if &y * &y != &x * &x * &x + &self.g1.b {
+            return Err(Error::other(ERR_NOT_ON_CURVE));
+        }
+        Ok(self.g1.new_point(x, y))
+    }
+    /// Decodes a point of G2 given as `x1 || x0 || y1 || y0`, where `x =
+    /// x0 + x1*i` and `y = y0 + y1*i`. It fails for points that are not on
+    /// the twist or outside the subgroup of order `r`.
+    pub fn decode_g2(&self, buf: &[u8]) -> Result<G2Point, Error> {
+        if buf.len() != 4 * COORD_SIZE {
+            return Err(Error::other(ERR_SIZE));
+        }
+        if buf.iter().all(|&b| b == 0) {
+            return Ok(self.g2.identity());
+        }
+        let c = buf
+            .chunks(COORD_SIZE)
+            .map(|ci| self.decode_coord(ci))
+            .collect::<Result<Vec<_>, _>>()?;
+        let f = self.g2.get_field();
+        let x = f.new_elt(c[1].clone(), c[0].clone());
+        let y = f.new_elt(c[3].clone(), c[2].clone());
+        if &y * &y != &x * &x * &x + &self.g2.b {
+            return Err(Error::other(ERR_NOT_ON_CURVE));
+        }
+        let q = self.g2.new_point(x, y);
+        if !self.is_in_g2(&q) {
+            return Err(Error::other(ERR_SUBGROUP));
+        }
+        Ok(q)
+    }
+    /// Encodes a point of G1 as `x || y`.
+    pub fn encode_g1(&self, p: &Point) -> Vec<u8> {
+        if p.is_zero() {
+            return vec![0u8; 2 * COORD_SIZE];
+        }
+        let mut p = p.clone();
+        p.normalize();
+        let mut out = p.c.x.to_bytes_be();
+        out.append(&mut p.c.y.to_bytes_be());
+        out
+    }
+    /// Encodes a point of G2 as `x1 || x0 || y1 || y0`.
+    pub fn encode_g2(&self, q: &G2Point) -> Vec<u8> {
+        if q.is_zero() {
+            return vec![0u8; 4 * COORD_SIZE];
+        }
+        let mut q = q.clone();
+        q.normalize();
+        let mut out = Vec::new();
+        for c in [&q.c.x, &q.c.y].iter() {
+            out.append(&mut c.c1().to_bytes_be());
+            out.append(&mut c.c0().to_bytes_be());
+        }
+        out
+    }
+    /// Computes the precompiled contract `ecAdd` (address `0x06`), which
+    /// adds two points of G1 given as `p || q`. As in the EVM, the input is
+    /// padded with zeros or truncated to 128 bytes.
+    pub fn ec_add(&self, input: &[u8]) -> Result<Vec<u8>, Error> {
+        let input = resize(input, 4 * COORD_SIZE);
+        let p = self.decode_g1(&input[..2 * COORD_SIZE])?;
+        let q = self.decode_g1(&input[2 * COORD_SIZE..])?;
+        Ok(self.encode_g1(&(p + q)))
+    }
+    /// Computes the precompiled contract `ecMul` (address `0x07`), which
+    /// multiplies a point of G1 by a 32-byte scalar given as `p || k`. As in
+    /// the EVM, the input is padded with zeros or truncated to 96 bytes.
+    pub fn ec_mul(&self, input: &[u8]) -> Result<Vec<u8>, Error> {
+        let input = resize(input, 3 * COORD_SIZE);
+        let p = self.decode_g1(&input[..2 * COORD_SIZE])?;
+        let k = BigInt::from_bytes_be(Sign::Plus, &input[2 * COORD_SIZE..]);
+        Ok(self.encode_g1(&(p * self.g1.new_scalar(k))))
+    }
+    /// Computes the precompiled contract `ecPairing` (address `0x08`),
+    /// which checks whether `e(p_1, q_1)*...*e(p_n, q_n) = 1` for the pairs
+    /// given as `p_1 || q_1 || ... || p_n || q_n`. It returns a 32-byte
+    /// word holding `1` if the check passes and `0` otherwise, and fails if
+    /// the length of the input is not a multiple of 192 bytes.
+    pub fn ec_pairing(&self, input: &[u8]) -> Result<Vec<u8>, Error> {
+        let pair_size = 6 * COORD_SIZE;
+        if !input.len().is_multiple_of(pair_size) {
+            return Err(Error::other(ERR_SIZE));
+        }
+        let mut pairs = Vec::new();
+        for pair in input.chunks(pair_size) {
+            let p = self.decode_g1(&pair[..2 * COORD_SIZE])?;
+            let q = self.decode_g2(&pair[2 * COORD_SIZE..])?;
+            pairs.push((p, q));
+        }
+        let mut out = vec![0u8; COORD_SIZE];
+        if self.multi_pairing(&pairs).is_identity() {
+            out[COORD_SIZE - 1] = 1;
+        }
+        Ok(out)
+    }
+    /// Parses a coordinate over GF(p), rejecting non-canonical ones.
+    fn decode_coord(&self, buf: &[u8]) -> Result<FpElt, Error> {
+        self.g1.get_field().from_canonical_be(buf)
+    }
+    /// Returns `pi(q) = (x^p*xi^((p-1)/3), y^p*xi^((p-1)/2))`, the
+    /// Frobenius endomorphism of the curve acting on the twist.
+    fn frobenius(&self, q: &G2Point) -> G2Point {
+        self.g2.new_point(
+            q.c.x.conjugate() * &self.pi_x,
+            q.c.y.conjugate() * &self.pi_y,
+        )
+    }
+    /// Returns the line tangent to `t` evaluated at `(px, py)`, scaled by a
+    /// factor in GF(p^2) that is removed by the final exponentiation.
+    fn line_double(&self, t: &G2Point, px: &FpElt, py: &FpElt) -> Fp12Elt {
+        let (x, y, z) = (&t.c.x, &t.c.y, &t.c.z);
+        let b3 = &self.g2.b + &self.g2.b + &self.g2.b;
+        let lc = y * y - b3 * z * z;
+        let x2 = x * x;
+        let lx = -(&x2 + &x2 + x2).mul_base(px);
+        let ly = (y * z + y * z).mul_base(py);
+        self.line(ly, lx, lc)
+    }
+    /// Returns the line through `t` and the affine point `q` evaluated at
+    /// `(px, py)`, scaled by a factor in GF(p^2).
+    fn line_add(&self, t: &G2Point, q: &G2Point, px: &FpElt, py: &FpElt) -> Fp12Elt {
+        let (x, y, z) = (&t.c.x, &t.c.y, &t.c.z);
+        let (x2, y2) = (&q.c.x, &q.c.y);
+        let theta = y2 * z - y;
+        let delta = x2 * z - x;
+        let lc = &theta * x2 - &delta * y2;
+        let lx = -theta.mul_base(px);
+        let ly = delta.mul_base(py);
+        self.line(ly, lx, lc)
+    }
+    /// Returns the sparse element `ly + lx*w + lc*w^3`. As the twist is of
+    /// D-type, lines on it are mapped to GF(p^12) by `(x, y) -> (x*w^2,
+    /// y*w^3)`.
+    fn line(&self, ly: Fp2Elt, lx: Fp2Elt, lc: Fp2Elt) -> Fp12Elt {
+        let z = self.t.get_base().zero();
+        self.t.new_elt([ly, lx, z.clone(), lc, z.clone(), z])
+    }
+}
+
+impl Default for Bn254 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PairingEngine for Bn254 {
+    type Scalar = Scalar;
+    type G1 = Point;
+    type G2 = G2Point;
+    type Gt = GtElt;
+    fn order(&self) -> BigUint {
+        self.g1.get_order()
+    }
+    fn new_scalar(&self, k: BigInt) -> Self::Scalar {
+        self.g1.new_scalar(k)
+    }
+    fn g1_generator(&self) -> Self::G1 {
+        self.g1.get_generator()
+    }
+    fn g2_generator(&self) -> Self::G2 {
+        self.g2.get_generator()
+    }
+    fn pairing(&self, p: &Self::G1, q: &Self::G2) -> Self::Gt {
+        self.final_exponentiation(&self.miller_loop(p, q))
+    }
+    fn multi_pairing(&self, pairs: &[(Self::G1, Self::G2)]) -> Self::Gt {
+        self.final_exponentiation(&self.multi_miller_loop(pairs))
+    }
+}
+
+/// Returns the first `len` bytes of `input`, padded with zeros if needed.
+fn resize(input: &[u8], len: usize) -> Vec<u8> {
+    let mut out = input[..input.len().min(len)].to_vec();
+    out.resize(len, 0);
+    out
+}
+
+/// Returns `f^x` for `f` in the cyclotomic subgroup, where `x` is the
+/// parameter of the curve.
+fn exp_by_x(f: &Fp12Elt) -> Fp12Elt {
+    let mut out = f.clone();
+    for i in (0..BN_X_BITS - 1).rev() {
+        out = out.cyclotomic_square();
+        if (BN_X >> i) & 1 == 1 {
+            out = out * f;
+        }
+    }
+    out
+}
+
+/// The parameter of BN254.
+const BN_X: u64 = 4_965_661_367_192_848_881;
+const BN_X_BITS: usize = 63;
+/// The length of the Miller loop of the optimal ate pairing, `6x+2`.
+const ATE_LOOP: u128 = 29_793_968_203_157_093_288;
+const ATE_LOOP_BITS: usize = 65;
+const COORD_SIZE: usize = 32;
+const ERR_SIZE: &str = "wrong length of input";
+const ERR_NOT_ON_CURVE: &str = "point is not on the curve";
+const ERR_SUBGROUP: &str = "point is not in the subgroup of order r";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::Bn254;
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::pairing::PairingEngine;
+
+    #[test]
+    fn precompiles() {
+        let e = Bn254::new();
+        let g = e.encode_g1(&e.g1_generator());
+        let g2 = hex::decode(
+            "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3\
+             15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4",
+        )
+        .unwrap();
+        assert_eq!(e.ec_add(&[g.clone(), g.clone()].concat()).unwrap(), g2);
+        assert_eq!(e.ec_add(&g).unwrap(), g);
+        assert_eq!(e.ec_add(&[]).unwrap(), vec![0u8; 64]);
+
+        let mut two = vec![0u8; 32];
+        two[31] = 2;
+        assert_eq!(e.ec_mul(&[g.clone(), two].concat()).unwrap(), g2);
+        let r = e.order().to_bytes_be();
+        assert_eq!(e.ec_mul(&[g.clone(), r].concat()).unwrap(), vec![0u8; 64]);
+
+        // points off the curve and non-canonical coordinates
+        let mut bad = g.clone();
+        bad[63] ^= 1;
+        assert!(e.ec_add(&bad).is_err());
+        assert!(e.ec_mul(&[0xff; 64]).is_err());
+    }
+
+    #[test]
+    fn ec_pairing() {
+        let e = Bn254::new();
+        let input = hex::decode(
+            "1c76476f4def4bb94541d57ebba1193381ffa7aa76ada664dd31c16024c43f59\
+             3034dd2920f673e204fee2811c678745fc819b55d3e9d294e45c9b03a76aef41\
+             209dd15ebff5d46c4bd888e51a93cf99a7329636c63514396b4a452003a35bf7\
+             04bf11ca01483bfa8b34b43561848d28905960114c8ac04049af4b6315a41678\
+             2bb8324af6cfc93537a2ad1a445cfd0ca2a71acd7ac41fadbf933c2a51be344d\
+             120a2a4cf30c1bf9845f20c6fe39e07ea2cce61f0c9bb048165fe5e4de877550\
+             111e129f1cf1097710d41c4ac70fcdfa5ba2023c6ff1cbeac322de49d1b6df7c\
+             2032c61a830e3c17286de9462bf242fca2883585b93870a73853face6a6bf411\
+             198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+             1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+             090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+             12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        )
+        .unwrap();
+        let mut one = vec![0u8; 32];
+        one[31] = 1;
+        assert_eq!(e.ec_pairing(&input).unwrap(), one);
+        assert_eq!(e.ec_pairing(&input[..192]).unwrap(), vec![0u8; 32]);
+        assert_eq!(e.ec_pairing(&[]).unwrap(), one);
+        assert!(e.ec_pairing(&input[1..]).is_err());
+
+        let mut bad = input.clone();
+        bad[191] ^= 1;
+        assert!(e.ec_pairing(&bad).is_err());
+
+        let q = e.g2_generator();
+        assert!(e.decode_g2(&e.encode_g2(&q)).unwrap() == q);
+        assert!(e.decode_g2(&e.encode_g2(&e.g2().identity())).is_ok());
+    }
+
+    #[test]
+    fn bilinearity() {
+        let e = Bn254::new();
+        let (p, q) = (e.g1_generator(), e.g2_generator());
+        let a = e.new_scalar(BigInt::from(6));
+        let b = e.new_scalar(BigInt::from(-5));
+        let x = e.pairing(&p, &q);
+        assert!(!x.is_identity());
+        assert!(x.pow(&e.order()).is_identity());
+        assert!(e.pairing(&(p.clone() * &a), &(q.clone() * &b)) == &x ^ &(&a * &b));
+        assert!(e
+            .multi_pairing(&[(p.clone(), q.clone()), (-p, q)])
+            .is_identity());
+    }
+}
@@ -0,0 +1,664 @@
+//! This is documentation for the `g2` module.
+//!
+//! The g2 module implements the group G2 of a pairing-friendly curve, i.e.,
+//! the points of order `r` of a sextic twist `y^2 = x^3 + b` defined over
+//! GF(p^2).
+
+use impl_ops::impl_op_ex;
+use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
+use num_traits::identities::{One, Zero};
+use num_traits::Signed;
+use subtle::{Choice, ConstantTimeEq};
+
+use std::io::Error;
+use std::ops;
+use std::sync::Arc;
+
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode, PointFormat};
+use crate::field::{Field, Sqrt};
+use crate::integer::Integer;
+use crate::ops::{map_batch, FromFactory, ScMulRef, Serialize};
+use crate::primefield::FpElt;
+use crate::quadraticfield::{Fp2, Fp2Elt};
+use crate::weierstrass::Scalar;
+use crate::{count_op, do_if_eq, impl_iter_fold, uncounted};
+
+/// G2Curve is a curve `y^2 = x^3 + b` defined over GF(p^2), which is the
+/// sextic twist carrying the group G2 of a pairing-friendly curve.
+///
+/// Points are encoded following the format of Zcash for BLS12-381, which
+/// uses the three most significant bits of the encoding as flags. So this
+/// encoding only supports fields whose elements leave three bits free.
+#[derive(Clone)]
+pub struct G2Curve(pub(crate) Arc<G2CurveInner>);
+
+/// G2CurveInner holds the parameters of a curve, which are shared by its points
+/// instead of being copied into each of them.
+pub struct G2CurveInner {
+    pub(crate) name: &'static str,
+    pub(super) f: Fp2,
+    pub(crate) b: Fp2Elt,
+    pub(super) r: BigUint,
+    pub(super) h: BigUint,
+    pub(super) gx: Fp2Elt,
+    pub(super) gy: Fp2Elt,
+    pub(super) psi: Option<Psi>,
+}
+
+impl std::ops::Deref for G2Curve {
+    type Target = G2CurveInner;
+    fn deref(&self) -> &G2CurveInner {
+        &self.0
+    }
+}
+
+/// Psi is the endomorphism `untwist o Frobenius o twist` of the twist of a
+/// BLS12 curve, which maps `(x, y)` to `(cx*x^p, cy*y^p)`. On G2, it acts as
+/// the multiplication by the parameter `x` of the curve.
+#[derive(Clone, PartialEq)]
+pub(super) struct Psi {
+    x: BigInt,
+    cx: Fp2Elt,
+    cy: Fp2Elt,
+}
+
+/// Curves are compared by their parameters, regardless of their names.
+impl PartialEq for G2Curve {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+            || (self.f == other.f
+                && self.b == other.b
+                && self.r == other.r
+                && self.h == other.h
+                && self.gx == other.gx
+                && self.gy == other.gy
+                && self.psi == other.psi)
+    }
+}
+
+impl G2Curve {
+    /// Returns the name given in the parameters of the curve.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// Returns the coefficient `b` of the equation `y^2=x^3+b` over Fp2.
+    pub fn b(&self) -> &Fp2Elt {
+        &self.b
+    }
+    pub(crate) fn new_proy_point(&self, c: G2Coordinates) -> G2Point {
+        let e = self.clone();
+        let pt = G2Point { e, c };
+        do_if_eq!(uncounted!(self.is_on_curve(&pt)), pt, ERR_ECC_NEW)
+    }
+    /// Determines whether `p` belongs to the subgroup of order `r`. On BLS12
+    /// curves, it checks whether `psi(p) = [x]p`, which is much faster than
+    /// multiplying by `r`.
+    // Reference: "A note on group membership tests for G1, G2 and GT on BLS
+    // pairing-friendly curves" by Scott. [Sec.4] (eprint.iacr.org/2021/1130).
+    pub fn is_in_subgroup(&self, p: &G2Point) -> bool {
+        match &self.psi {
+            Some(psi) => p.psi() == p.mul_int(&psi.x),
+            None => p.mul_uint(&self.r).is_zero(),
+        }
+    }
+    /// Determines whether each of the points `ps` belongs to the subgroup of
+    /// order `r`, see `is_in_subgroup`. The points are checked in parallel
+    /// with the `parallel` feature.
+    pub fn batch_is_in_subgroup(&self, ps: &[G2Point]) -> Vec<bool> {
+        map_batch(ps, |p| self.is_in_subgroup(p))
+    }
+    /// Returns the number of bytes of an encoded coordinate over GF(p).
+    fn coord_size(&self) -> usize {
+        self.f.size_bytes() / 2
+    }
+    /// Parses an element of GF(p^2) encoded as `n1 || n0`, where each
+    /// coefficient is in big-endian order.
+    fn decode_coord(&self, buf: &[u8]) -> Result<Fp2Elt, Error> {
+        let size = self.coord_size();
+        let base = self.f.get_base();
+        let n1 = base.from_canonical_be(&buf[..size])?;
+        let n0 = base.from_canonical_be(&buf[size..2 * size])?;
+        Ok(self.f.new_elt(n0, n1))
+    }
+}
+
+impl EllipticCurve for G2Curve {
+    type F = Fp2;
+    type Scalar = Scalar;
+    type Point = G2Point;
+    fn new_point(&self, x: Fp2Elt, y: Fp2Elt) -> Self::Point {
+        let z = self.f.one();
+        self.new_proy_point(G2Coordinates { x, y, z })
+    }
+    fn new_scalar(&self, k: BigInt) -> Self::Scalar {
+        Scalar::new(k, &self.r)
+    }
+    fn identity(&self) -> Self::Point {
+        self.new_proy_point(G2Coordinates {
+            x: self.f.zero(),
+            y: self.f.one(),
+            z: self.f.zero(),
+        })
+    }
+    fn is_on_curve(&self, p: &Self::Point) -> bool {
+        let p = &p.c;
+        // y^2*z = x^3 + b*z^3
+        let zz = &p.z * &p.z;
+        let lhs = &p.y * &p.y * &p.z;
+        let rhs = &p.x * &p.x * &p.x + &self.b * &zz * &p.z;
+        lhs == rhs
+    }
+    fn get_order(&self) -> BigUint {
+        self.r.clone()
+    }
+    fn get_cofactor(&self) -> BigInt {
+        self.h.to_bigint().unwrap()
+    }
+    fn get_field(&self) -> Self::F {
+        self.f.clone()
+    }
+    fn get_generator(&self) -> Self::Point {
+        self.new_point(self.gx.clone(), self.gy.clone())
+    }
+}
+
+impl Decode for G2Curve {
+    type Deser = G2Point;
+    /// Decodes a point in the format of Zcash, which is either compressed
+    /// (`x`) or uncompressed (`x || y`), with coordinates encoded as
+    /// `n1 || n0`. The first byte holds three flags: its most significant
+    /// bit is set for compressed points, the next one is set for the point
+    /// at infinity, and the third one is set for compressed points whose
+    /// `y` is the lexicographically largest of `y` and `-y`. It fails for
+    /// points outside the subgroup of order `r`.
+    fn decode_with_format(&self, buf: &[u8]) -> Result<(Self::Deser, PointFormat), Error> {
+        let size = 2 * self.coord_size();
+        if buf.is_empty() {
+            return Err(Error::other(ERR_SIZE));
+        }
+        let compressed = buf[0] & FLAG_COMPRESSED != 0;
+        let infinity = buf[0] & FLAG_INFINITY != 0;
+        let largest = buf[0] & FLAG_LARGEST != 0;
+        if buf.len() != if compressed { size } else { 2 * size } {
+            return Err(Error::other(ERR_SIZE));
+        }
+        let format = if compressed {
+            PointFormat::Compressed
+        } else {
+            PointFormat::Uncompressed
+        };
+        let mut bytes = buf.to_vec();
+        bytes[0] &= !FLAGS;
+        if infinity {
+            if largest || bytes.iter().any(|&b| b != 0) {
+                return Err(Error::other(ERR_INFINITY));
+            }
+            return Ok((self.identity(), format));
+        }
+        let x = self.decode_coord(&bytes[..size])?;
+        let y = if compressed {
+            let y2 = &x * &x * &x + &self.b;
+            if !y2.is_square() {
+                return Err(Error::other(ERR_NOT_ON_CURVE));
+            }
+            let y = y2.sqrt();
+            if is_largest(&y, &self.f.get_modulus()) == largest {
+                y
+            } else {
+                -y
+            }
+        } else {
+            if largest {
+                return Err(Error::other(ERR_FLAGS));
+            }
+            let y = self.decode_coord(&bytes[size..])?;
+            if &y * &y != &x * &x * &x + &self.b {
+                return Err(Error::other(ERR_NOT_ON_CURVE));
+            }
+            y
+        };
+        let p = self.new_point(x, y);
+        if !self.is_in_subgroup(&p) {
+            return Err(Error::other(ERR_SUBGROUP));
+        }
+        Ok((p, format))
+    }
+}
+
+impl std::fmt::Display for G2Curve {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if !self.name.is_empty() {
+            write!(f, "{}: ", self.name)?;
+        }
+        write!(f, "Twisted curve y^2=x^3+b over {}\nb: {}", self.f, self.b)
+    }
+}
+
+/// Determines whether `y` is larger than `-y`, comparing first the
+/// coefficients `n1` and then the coefficients `n0`.
+fn is_largest(y: &Fp2Elt, p: &BigInt) -> bool {
+    let half = (p - 1u32) >> 1usize;
+    let n0 = to_int(y.c0());
+    let n1 = to_int(y.c1());
+    if n1.is_zero() {
+        n0 > half
+    } else {
+        n1 > half
+    }
+}
+
+fn to_int(x: &FpElt) -> BigInt {
+    BigInt::from_bytes_be(Sign::Plus, &x.to_bytes_be())
+}
+
+/// G2Coordinates are the projective coordinates `(x: y: z)` of a point,
+/// whose affine coordinates are `(x/z, y/z)`.
+#[derive(Clone)]
+pub struct G2Coordinates {
+    pub x: Fp2Elt,
+    pub y: Fp2Elt,
+    pub z: Fp2Elt,
+}
+
+/// G2Point is a point of a G2Curve.
+#[derive(Clone)]
+pub struct G2Point {
+    pub(super) e: G2Curve,
+    pub(crate) c: G2Coordinates,
+}
+
+impl ScMulRef<Scalar> for G2Point {}
+impl EcPoint<Scalar> for G2Point {
+    fn is_zero(&self) -> bool {
+        self.c.x.is_zero() && !self.c.y.is_zero() && self.c.z.is_zero()
+    }
+    fn is_small_order(&self) -> bool {
+        self.mul_uint(&self.e.h).is_zero()
+    }
+}
+
+impl Encode for G2Point {
+    /// Encodes a point in the format of Zcash, which is described in
+    /// `G2Curve::decode_with_format`, which has no hybrid form.
+    fn encode(&self, format: PointFormat) -> Vec<u8> {
+        let compress = format == PointFormat::Compressed;
+        let size = 2 * self.e.coord_size();
+        let len = if compress { size } else { 2 * size };
+        if self.is_zero() {
+            let mut out = vec![0u8; len];
+            out[0] = FLAG_INFINITY | if compress { FLAG_COMPRESSED } else { 0 };
+            return out;
+        }
+        let mut p = self.clone();
+        p.normalize();
+        let mut out = encode_coord(&p.c.x);
+        if compress {
+            out[0] |= FLAG_COMPRESSED;
+            if is_largest(&p.c.y, &self.e.f.get_modulus()) {
+                out[0] |= FLAG_LARGEST;
+            }
+        } else {
+            out.append(&mut encode_coord(&p.c.y));
+        }
+        out
+    }
+}
+
+fn encode_coord(x: &Fp2Elt) -> Vec<u8> {
+    let mut out = x.c1().to_bytes_be();
+    out.append(&mut x.c0().to_bytes_be());
+    out
+}
+
+impl G2Point {
+    pub fn normalize(&mut self) {
+        // the point at infinity has no affine representation
+        if self.c.z.is_zero() {
+            return;
+        }
+        let inv_z = 1u32 / &self.c.z;
+        self.c.x = &self.c.x * &inv_z;
+        self.c.y = &self.c.y * &inv_z;
+        self.c.z.set_one();
+    }
+    /// Returns the affine x-coordinate, or `None` for the point at infinity.
+    pub fn x(&self) -> Option<Fp2Elt> {
+        self.to_affine().map(|(x, _)| x)
+    }
+    /// Returns the affine y-coordinate, or `None` for the point at infinity.
+    pub fn y(&self) -> Option<Fp2Elt> {
+        self.to_affine().map(|(_, y)| y)
+    }
+    /// Returns the affine coordinates (x, y), or `None` for the point at
+    /// infinity.
+    pub fn to_affine(&self) -> Option<(Fp2Elt, Fp2Elt)> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut p = self.clone();
+        p.normalize();
+        Some((p.c.x, p.c.y))
+    }
+    fn core_neg(&self) -> G2Point {
+        self.e.new_proy_point(G2Coordinates {
+            x: self.c.x.clone(),
+            y: -&self.c.y,
+            z: self.c.z.clone(),
+        })
+    }
+    /// core_add implements complete addition formulas for curves with a=0.
+    // Reference: "Complete addition formulas for prime order elliptic curves" by
+    // Costello-Renes-Batina. [Alg.7] (eprint.iacr.org/2015/1060).
+    fn core_add(&self, p: &G2Point) -> G2Point {
+        if std::ptr::eq(self, p) {
+            count_op!(dbl);
+        } else {
+            count_op!(add);
+        }
+        let b3 = &self.e.b + &self.e.b + &self.e.b;
+        let (x1, x2) = (&self.c.x, &p.c.x);
+        let (y1, y2) = (&self.c.y, &p.c.y);
+        let (z1, z2) = (&self.c.z, &p.c.z);
+        let (mut x3, mut y3, mut z3);
+        let (mut t0, mut t1, mut t2, mut t3, mut t4);
+        t0 = x1 * x2; //    1. t0 = X1 * X2
+        t1 = y1 * y2; //    2. t1 = Y1 * Y2
+        t2 = z1 * z2; //    3. t2 = Z1 * Z2
+        t3 = x1 + y1; //    4. t3 = X1 + Y1
+        t4 = x2 + y2; //    5. t4 = X2 + Y2
+        t3 = &t3 * &t4; //  6. t3 = t3 * t4
+        t4 = &t0 + &t1; //  7. t4 = t0 + t1
+        t3 = &t3 - &t4; //  8. t3 = t3 - t4
+        t4 = y1 + z1; //    9. t4 = Y1 + Z1
+        x3 = y2 + z2; //   10. X3 = Y2 + Z2
+        t4 = &t4 * &x3; // 11. t4 = t4 * X3
+        x3 = &t1 + &t2; // 12. X3 = t1 + t2
+        t4 = &t4 - &x3; // 13. t4 = t4 - X3
+        x3 = x1 + z1; //   14. X3 = X1 + Z1
+        y3 = x2 + z2; //   15. Y3 = X2 + Z2
+        x3 = &x3 * &y3; // 16. X3 = X3 * Y3
+        y3 = &t0 + &t2; // 17. Y3 = t0 + t2
+        y3 = &x3 - &y3; // 18. Y3 = X3 - Y3
+        x3 = &t0 + &t0; // 19. X3 = t0 + t0
+        t0 = &x3 + &t0; // 20. t0 = X3 + t0
+        t2 = &b3 * &t2; // 21. t2 = b3 * t2
+        z3 = &t1 + &t2; // 22. Z3 = t1 + t2
+        t1 = &t1 - &t2; // 23. t1 = t1 - t2
+        y3 = &b3 * &y3; // 24. Y3 = b3 * Y3
+        x3 = &t4 * &y3; // 25. X3 = t4 * Y3
+        t2 = &t3 * &t1; // 26. t2 = t3 * t1
+        x3 = &t2 - &x3; // 27. X3 = t2 - X3
+        y3 = &y3 * &t0; // 28. Y3 = Y3 * t0
+        t1 = &t1 * &z3; // 29. t1 = t1 * Z3
+        y3 = &t1 + &y3; // 30. Y3 = t1 + Y3
+        t0 = &t0 * &t3; // 31. t0 = t0 * t3
+        z3 = &z3 * &t4; // 32. Z3 = Z3 * t4
+        z3 = &z3 + &t0; // 33. Z3 = Z3 + t0
+        self.e.new_proy_point(G2Coordinates {
+            x: x3,
+            y: y3,
+            z: z3,
+        })
+    }
+    /// core_mul implements the double&add Scalar multiplication method.
+    /// This function run in non-constant time.
+    fn core_mul(&self, k: &Scalar) -> G2Point {
+        let mut q = self.e.identity();
+        for ki in k.iter_lr() {
+            q = &q + &q;
+            if ki {
+                q += self;
+            }
+        }
+        q
+    }
+    /// Returns `psi(p)`. It panics if the curve is not the twist of a BLS12
+    /// curve.
+    pub(crate) fn psi(&self) -> G2Point {
+        let psi = self.e.psi.as_ref().expect(ERR_PSI);
+        self.e.new_proy_point(G2Coordinates {
+            x: self.c.x.conjugate() * &psi.cx,
+            y: self.c.y.conjugate() * &psi.cy,
+            z: self.c.z.conjugate(),
+        })
+    }
+    /// Multiplies the point by a signed integer that is not reduced modulo
+    /// `r`.
+    pub(crate) fn mul_int(&self, k: &BigInt) -> G2Point {
+        let q = self.mul_uint(&k.abs().to_biguint().unwrap());
+        if k.sign() == Sign::Minus {
+            -q
+        } else {
+            q
+        }
+    }
+    /// Multiplies the point by an integer that is not reduced modulo `r`,
+    /// such as the cofactor or the order itself.
+    pub(crate) fn mul_uint(&self, k: &BigUint) -> G2Point {
+        let digits = k.to_u32_digits();
+        let mut q = self.e.identity();
+        for i in (0..k.bits()).rev() {
+            q = &q + &q;
+            if (digits[i / 32] >> (i % 32)) & 1 == 1 {
+                q += self;
+            }
+        }
+        q
+    }
+}
+
+impl Eq for G2Point {}
+
+impl PartialEq for G2Point {
+    /// Compares the points in variable time.
+    fn eq(&self, other: &Self) -> bool {
+        let x1z2 = &self.c.x * &other.c.z;
+        let z1x2 = &self.c.z * &other.c.x;
+        let y1z2 = &self.c.y * &other.c.z;
+        let z1y2 = &self.c.z * &other.c.y;
+        self.e == other.e && x1z2 == z1x2 && y1z2 == z1y2
+    }
+}
+
+impl ConstantTimeEq for G2Point {
+    /// Compares the points without short-circuiting on their coordinates.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let x1z2 = &self.c.x * &other.c.z;
+        let z1x2 = &self.c.z * &other.c.x;
+        let y1z2 = &self.c.y * &other.c.z;
+        let z1y2 = &self.c.z * &other.c.y;
+        let same_curve = Choice::from((self.e == other.e) as u8);
+        same_curve & x1z2.ct_eq(&z1x2) & y1z2.ct_eq(&z1y2)
+    }
+}
+
+impl_op_ex!(+|a: &G2Point, b: &G2Point| -> G2Point {
+    do_if_eq!(a.e == b.e, a.core_add(b), ERR_ADD_OP)
+});
+impl_op_ex!(-|a: &G2Point, b: &G2Point| -> G2Point { a + (-b) });
+impl_op_ex!(-|a: &G2Point| -> G2Point { a.core_neg() });
+impl_op_ex!(*|a: &G2Point, b: &Scalar| -> G2Point {
+    let r = a.e.r.to_bigint().unwrap();
+    do_if_eq!(r == b.r, a.core_mul(b), ERR_MUL_OP)
+});
+impl_op_ex!(+=|a: &mut G2Point, b: &G2Point| { *a = &*a + b });
+impl_op_ex!(-=|a: &mut G2Point, b: &G2Point| { *a = &*a - b });
+impl_op_ex!(*=|a: &mut G2Point, b: &Scalar| { *a = &*a * b });
+impl_iter_fold!(Sum, sum, G2Point, +);
+
+/// Displays the compressed encoding of the point in hex.
+impl std::fmt::Display for G2Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.encode(PointFormat::Compressed)
+            .iter()
+            .try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+/// Shows the name of the curve and the affine coordinates of the point in
+/// hex.
+impl std::fmt::Debug for G2Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_identity() {
+            return write!(f, "G2Point {{ curve: {:?}, identity }}", self.e.name);
+        }
+        let mut p = self.clone();
+        p.normalize();
+        f.debug_struct("G2Point")
+            .field("curve", &self.e.name)
+            .field("x", &format_args!("{:#x}", p.c.x))
+            .field("y", &format_args!("{:#x}", p.c.y))
+            .finish()
+    }
+}
+
+/// G2Params are the parameters of a G2Curve. Elements of GF(p^2) are
+/// written as `n0,n1`.
+///
+/// For the M-type twist of a BLS12 curve defined by `xi`, the parameter `x`
+/// of the curve enables the endomorphism `psi`. Otherwise, `x` and `xi` are
+/// left empty.
+#[derive(PartialEq, Eq)]
+pub struct G2Params {
+    pub name: &'static str,
+    pub p: Integer,
+    pub b: [Integer; 2],
+    pub r: Integer,
+    pub h: Integer,
+    pub gx: [Integer; 2],
+    pub gy: [Integer; 2],
+    pub xi: Option<[Integer; 2]>,
+    pub x: Option<Integer>,
+}
+
+impl<'a> std::convert::From<&'a G2Params> for G2Curve {
+    fn from(params: &'a G2Params) -> G2Curve {
+        let f = Fp2::new(params.p.to_biguint());
+        let psi = params.xi.zip(params.x).map(|(xi, x)| {
+            // cx = 1/xi^((p-1)/3) and cy = 1/xi^((p-1)/2)
+            let xi: Fp2Elt = f.from(xi);
+            let p1 = f.get_modulus() - 1u32;
+            Psi {
+                x: BigInt::from(x),
+                cx: 1u32 / &(&xi ^ &(&p1 / 3u32)),
+                cy: 1u32 / &(&xi ^ &(&p1 / 2u32)),
+            }
+        });
+        G2Curve(Arc::new(G2CurveInner {
+            name: params.name,
+            psi,
+            b: f.from(params.b),
+            r: params.r.to_biguint(),
+            h: params.h.to_biguint(),
+            gx: f.from(params.gx),
+            gy: f.from(params.gy),
+            f,
+        }))
+    }
+}
+
+const FLAG_COMPRESSED: u8 = 0x80;
+const FLAG_INFINITY: u8 = 0x40;
+const FLAG_LARGEST: u8 = 0x20;
+const FLAGS: u8 = FLAG_COMPRESSED | FLAG_INFINITY | FLAG_LARGEST;
+const ERR_ECC_NEW: &str = "not valid point";
+const ERR_ADD_OP: &str = "points of different curves";
+const ERR_MUL_OP: &str = "Scalar don't match with point";
+const ERR_SIZE: &str = "wrong size of encoded point";
+const ERR_FLAGS: &str = "invalid flags of encoded point";
+const ERR_INFINITY: &str = "invalid encoding of the point at infinity";
+const ERR_NOT_ON_CURVE: &str = "point is not on the curve";
+const ERR_SUBGROUP: &str = "point is not in the subgroup of order r";
+const ERR_PSI: &str = "psi is only defined for twists of BLS12 curves";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode, PointFormat};
+    use crate::field::{Field, Sqrt};
+    use crate::instances::{GetCurve, BLS12381G2};
+    use crate::ops::FromFactory;
+
+    #[test]
+    fn arithmetic() {
+        let e = BLS12381G2.get();
+        let g = e.get_generator();
+        assert!(e.is_in_subgroup(&g));
+        assert!(!g.is_small_order());
+        assert!((g.clone() * e.new_scalar(BigInt::from(0))).is_zero());
+        let g2 = g.clone() + &g;
+        assert!(g2 == g.clone() * e.new_scalar(BigInt::from(2)));
+        assert!(g2.clone() - &g == g);
+        assert!(g.clone() + e.identity() == g);
+        assert!((g.clone() - &g).is_zero());
+        let k = e.new_scalar(BigInt::from(-7));
+        assert!(g.clone() * k == -(g.clone() * e.new_scalar(BigInt::from(7))));
+    }
+
+    // Test vectors generated with the bls12_381 crate of Zcash.
+    #[test]
+    fn encoding() {
+        let e = BLS12381G2.get();
+        let g = e.get_generator();
+        let k = BigInt::parse_bytes(b"22220000000000001111fedcba98765432100123456789abcdef", 16)
+            .unwrap();
+        let kg = g.clone() * e.new_scalar(k);
+        let vectors = [
+            (g.clone(), "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"),
+            (g.clone() + &g, "aa4edef9c1ed7f729f520e47730a124fd70662a904ba1074728114d1031e1572c6c886f6b57ec72a6178288c47c335771638533957d540a9d2370f17cc7ed5863bc0b995b8825e0ee1ea1e1e4d00dbae81f14b0bf3611b78c952aacab827a053"),
+            (kg.clone(), "8950594eddc3310ded421999dd89b8697e054c8184573b68763cd42a39f21bd36da42ba103ec297e8564afed41a135940638d0cf9ca2f751c221bfb61288e97950274045de3e097cd1df3e622dafe8df18495912631a8cbd159bd004da441a67"),
+            (-kg.clone(), "a950594eddc3310ded421999dd89b8697e054c8184573b68763cd42a39f21bd36da42ba103ec297e8564afed41a135940638d0cf9ca2f751c221bfb61288e97950274045de3e097cd1df3e622dafe8df18495912631a8cbd159bd004da441a67"),
+            (e.identity(), "c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"),
+        ];
+        for (p, want) in vectors.iter() {
+            assert_eq!(hex::encode(p.encode(PointFormat::Compressed)), *want);
+            assert!(e.decode(&hex::decode(want).unwrap()).unwrap() == *p);
+            assert!(e.decode(&p.encode(PointFormat::Uncompressed)).unwrap() == *p);
+        }
+        let uncompressed = "0950594eddc3310ded421999dd89b8697e054c8184573b68763cd42a39f21bd36da42ba103ec297e8564afed41a135940638d0cf9ca2f751c221bfb61288e97950274045de3e097cd1df3e622dafe8df18495912631a8cbd159bd004da441a67059701eea3ee727607bbab1dcbc58ccb44764e331a0ed10205e3cb00ecec399339935b742019a96d7af9de095397f97903c81c2718e2aab4dd778423a22e540d2f07e9f3781149cc20f0f16b40f857f3f6fb1ff04da7b12921bc44ce63290ff9";
+        assert_eq!(
+            hex::encode(kg.encode(PointFormat::Uncompressed)),
+            uncompressed
+        );
+    }
+
+    #[test]
+    fn batch_subgroup() {
+        let e = BLS12381G2.get();
+        let (f2, f) = (e.get_field(), e.get_field().get_base());
+        let g = e.get_generator();
+        let mut points = vec![g.clone(), g.clone() + &g, e.identity()];
+        for x in 1..5 {
+            let x = f2.new_elt(f.from(x), f.one());
+            let y2 = &x * &x * &x + e.b();
+            if y2.is_square() {
+                points.push(e.new_point(x, y2.sqrt()));
+            }
+        }
+        assert!(points.len() > 3);
+        let got = e.batch_is_in_subgroup(&points);
+        assert_eq!(got.len(), points.len());
+        assert!(got[..3].iter().all(|&b| b) && !got[3..].iter().any(|&b| b));
+    }
+
+    #[test]
+    fn invalid_points() {
+        let e = BLS12381G2.get();
+        let mut buf = e.get_generator().encode(PointFormat::Compressed);
+        assert!(e.decode(&buf[1..]).is_err());
+        buf[0] &= 0x7f;
+        assert!(e.decode(&buf).is_err());
+        // the point with x = 2 is on the curve, but not in the subgroup
+        let mut buf = vec![0u8; 96];
+        buf[0] = 0x80;
+        buf[95] = 2;
+        assert!(e.decode(&buf).is_err());
+        // x = 3 is not the x-coordinate of any point
+        buf[95] = 3;
+        assert!(e.decode(&buf).is_err());
+        let mut inf = e.identity().encode(PointFormat::Compressed);
+        inf[95] = 1;
+        assert!(e.decode(&inf).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+//! This is documentation for the `gt` module.
+//!
+//! The gt module implements the target group GT of a pairing, which is the
+//! subgroup of order `r` of GF(p^12)*. As it lies in the cyclotomic
+//! subgroup, squarings are computed with the method of Granger-Scott, and
+//! the inverse of an element is its conjugate.
+//!
+//! Elements are serialized either uncompressed, as the twelve coordinates of
+//! the element over GF(p), or compressed to half the size using the torus
+//! T2. Since `x^(p^6+1) = 1`, every `x = c0 + c1*w` other than `1` and `-1`
+//! is written as
+//!
+//! ```text
+//!   x = (m + w)/(m - w),  with m = (1 + c0)/c1 in GF(p^6),
+//! ```
+//!
+//! so only `m` is stored. The identity is stored as `m = 0`, which would
+//! otherwise encode `-1`, an element outside of GT.
+
+use impl_ops::impl_op_ex;
+use num_bigint::BigUint;
+use num_traits::identities::Zero;
+
+use std::io::Error;
+use std::ops;
+
+use crate::ellipticcurve::{Decode, Encode, PointFormat};
+use crate::field::Field;
+use crate::ops::Serialize;
+use crate::pairing::{Fp12, Fp12Elt, Fp6Elt};
+use crate::quadraticfield::Fp2Elt;
+use crate::weierstrass::Scalar;
+
+/// Gt is the subgroup of order `r` of GF(p^12)*.
+#[derive(Clone)]
+pub struct Gt {
+    t: Fp12,
+    r: BigUint,
+}
+
+impl Gt {
+    pub fn new(t: Fp12, r: BigUint) -> Gt {
+        Gt { t, r }
+    }
+    pub fn identity(&self) -> GtElt {
+        GtElt(self.t.one())
+    }
+    pub fn get_order(&self) -> BigUint {
+        self.r.clone()
+    }
+    pub fn get_tower(&self) -> Fp12 {
+        self.t.clone()
+    }
+    /// Returns `x` as an element of GT. It fails if `x` is not of order `r`.
+    pub fn new_elt(&self, x: Fp12Elt) -> Result<GtElt, Error> {
+        if x.is_zero() || !x.pow(&self.r).is_one() {
+            return Err(Error::other(ERR_SUBGROUP));
+        }
+        Ok(GtElt(x))
+    }
+    /// Returns `x`, which is known to belong to GT, such as the output of a
+    /// final exponentiation.
+    pub(crate) fn new_unchecked(x: Fp12Elt) -> GtElt {
+        GtElt(x)
+    }
+    /// Returns the number of bytes of an element of GF(p).
+    fn coord_size(&self) -> usize {
+        self.t.get_base().size_bytes() / 2
+    }
+    /// Parses elements of GF(p^2) encoded as `n0 || n1`, where each
+    /// coefficient is in big-endian order, rejecting non-canonical ones.
+    fn decode_coords(&self, buf: &[u8]) -> Result<Vec<Fp2Elt>, Error> {
+        let f = self.t.get_base();
+        let base = f.get_base();
+        let size = self.coord_size();
+        let mut out = Vec::new();
+        for c in buf.chunks(2 * size) {
+            let n0 = base.from_canonical_be(&c[..size])?;
+            let n1 = base.from_canonical_be(&c[size..])?;
+            out.push(f.new_elt(n0, n1));
+        }
+        Ok(out)
+    }
+    /// Returns `(m + w)/(m - w) = (m^2 + v + 2mw)/(m^2 - v)`, which is never
+    /// undefined since `v` is not a square in GF(p^6).
+    fn decompress(&self, m: Fp6Elt) -> Fp12Elt {
+        let mm = &m * &m;
+        let v = self.t.fp6(
+            self.t.get_base().zero(),
+            self.t.get_base().one(),
+            self.t.get_base().zero(),
+        );
+        let den = (&mm - &v).inv();
+        let c1 = &m + &m;
+        Fp12Elt {
+            c0: (mm + v) * &den,
+            c1: c1 * den,
+        }
+    }
+}
+
+impl Decode for Gt {
+    type Deser = GtElt;
+    /// Decodes an element of GT either uncompressed or compressed, which
+    /// are told apart by their length. It fails for elements outside of GT.
+    fn decode_with_format(&self, buf: &[u8]) -> Result<(Self::Deser, PointFormat), Error> {
+        let size = 2 * self.coord_size();
+        if buf.len() == 3 * size {
+            let c = self.decode_coords(buf)?;
+            let (c0, c1, c2) = (c[0].clone(), c[1].clone(), c[2].clone());
+            let m = self.t.fp6(c0, c1, c2);
+            if m.is_zero() {
+                return Ok((self.identity(), PointFormat::Compressed));
+            }
+            let x = self.new_elt(self.decompress(m))?;
+            Ok((x, PointFormat::Compressed))
+        } else if buf.len() == 6 * size {
+            let c = self.decode_coords(buf)?;
+            let mut it = c.into_iter();
+            let mut next = || it.next().unwrap();
+            let x =
+                self.new_elt(
+                    self.t
+                        .new_elt([next(), next(), next(), next(), next(), next()]),
+                )?;
+            Ok((x, PointFormat::Uncompressed))
+        } else {
+            Err(Error::other(ERR_SIZE))
+        }
+    }
+}
+
+/// GtElt is an element of the target group GT, written multiplicatively.
+#[derive(Clone, PartialEq, Eq)]
+pub struct GtElt(Fp12Elt);
+
+impl GtElt {
+    /// Returns the element as an element of GF(p^12).
+    pub fn as_fp12(&self) -> &Fp12Elt {
+        &self.0
+    }
+    pub fn is_identity(&self) -> bool {
+        self.0.is_one()
+    }
+    /// Returns the inverse, which is the conjugate.
+    pub fn inv(&self) -> GtElt {
+        GtElt(self.0.conjugate())
+    }
+    /// Returns `x^2`.
+    pub fn square(&self) -> GtElt {
+        GtElt(self.0.cyclotomic_square())
+    }
+    /// Returns `x^k` using the square&multiply method with cyclotomic
+    /// squarings.
+    pub fn pow(&self, k: &BigUint) -> GtElt {
+        let digits = k.to_u32_digits();
+        let mut out = GtElt(self.0.c0.t.one());
+        for i in (0..k.bits()).rev() {
+            out = out.square();
+            if (digits[i / 32] >> (i % 32)) & 1 == 1 {
+                out = out * self;
+            }
+        }
+        out
+    }
+}
+
+impl Encode for GtElt {
+    /// Encodes the coefficients `c[i]` of `sum c[i]*w^i` in increasing order
+    /// of `i` or, when compressed, the coefficients of `m = m0 + m1*v +
+    /// m2*v^2`, each of them as `n0 || n1`. There is no hybrid form.
+    fn encode(&self, format: PointFormat) -> Vec<u8> {
+        if format != PointFormat::Compressed {
+            return self.0.to_bytes_be();
+        }
+        let x = &self.0;
+        let m = if x.is_one() {
+            // c1 is zero only for 1 and -1
+            let z = x.c0.t.get_base().zero();
+            x.c0.t.fp6(z.clone(), z.clone(), z)
+        } else {
+            (x.c0.t.one().c0 + &x.c0) * x.c1.inv()
+        };
+        let mut out = m.c0.to_bytes_be();
+        out.append(&mut m.c1.to_bytes_be());
+        out.append(&mut m.c2.to_bytes_be());
+        out
+    }
+}
+
+impl std::fmt::Display for GtElt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl_op_ex!(*|a: &GtElt, b: &GtElt| -> GtElt { GtElt(&a.0 * &b.0) });
+impl_op_ex!(/|a: &GtElt, b: &GtElt| -> GtElt { GtElt(&a.0 * b.0.conjugate()) });
+impl_op_ex!(^|a: &GtElt, b: &Scalar| -> GtElt {
+    if b.k.is_zero() {
+        return GtElt(a.0.c0.t.one());
+    }
+    a.pow(&b.k.to_biguint().unwrap())
+});
+
+const ERR_SIZE: &str = "wrong length of encoded element";
+const ERR_SUBGROUP: &str = "element does not belong to GT";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::ellipticcurve::{Decode, Encode, PointFormat};
+    use crate::pairing::{Bls12381, PairingEngine};
+
+    #[test]
+    fn group() {
+        let e = Bls12381::new();
+        let gt = e.gt();
+        let x = e.pairing(&e.g1_generator(), &e.g2_generator());
+        let a = e.new_scalar(BigInt::from(11));
+        let b = e.new_scalar(BigInt::from(-7));
+        assert!((&x ^ &a) * (&x ^ &b) == &x ^ &(&a + &b));
+        assert!(&x ^ &(&a * &b) == (&x ^ &a) ^ &b);
+        assert!(x.square() == &x * &x);
+        assert!(&x / &x == gt.identity());
+        assert!((&x * x.inv()).is_identity());
+        assert!((&x ^ &e.new_scalar(BigInt::from(0))).is_identity());
+        assert!(x.pow(&gt.get_order()).is_identity());
+        assert!(gt.new_elt(x.as_fp12().clone()).is_ok());
+        assert!(gt.new_elt(gt.get_tower().zero()).is_err());
+    }
+
+    #[test]
+    fn encoding() {
+        let e = Bls12381::new();
+        let gt = e.gt();
+        let x = e.pairing(&e.g1_generator(), &e.g2_generator());
+        for y in [x.clone(), x.inv(), x.square(), gt.identity()].iter() {
+            let full = y.encode(PointFormat::Uncompressed);
+            let half = y.encode(PointFormat::Compressed);
+            assert_eq!(full.len(), 576);
+            assert_eq!(half.len(), 288);
+            assert!(gt.decode(&full).unwrap() == *y);
+            assert!(gt.decode(&half).unwrap() == *y);
+        }
+        assert!(gt
+            .identity()
+            .encode(PointFormat::Compressed)
+            .iter()
+            .all(|&b| b == 0));
+
+        // elements outside of GT, wrong lengths, and non-canonical coordinates
+        let mut bad = x.encode(PointFormat::Uncompressed);
+        bad[575] ^= 1;
+        assert!(gt.decode(&bad).is_err());
+        let mut bad = x.encode(PointFormat::Compressed);
+        bad[287] ^= 1;
+        assert!(gt.decode(&bad).is_err());
+        assert!(gt.decode(&x.encode(PointFormat::Compressed)[1..]).is_err());
+        let mut bad = x.encode(PointFormat::Compressed);
+        bad[..48].copy_from_slice(&[0xff; 48]);
+        assert!(gt.decode(&bad).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! This is documentation for the `pairing` module.
+//!
+//! The pairing module provides bilinear pairings on pairing-friendly
+//! curves, so that pairing-based protocols can be written once against the
+//! `PairingEngine` trait and instantiated with any of them.
+//!
+//! A pairing is a map `e: G1 x G2 -> GT` between groups of prime order `r`
+//! that is bilinear, i.e., `e([a]P, [b]Q) = e(P, Q)^(ab)`, and
+//! non-degenerate, i.e., `e(P, Q)` generates `GT` whenever `P` and `Q`
+//! generate `G1` and `G2`. The groups `G1` and `G2` are written additively,
+//! and the target group `GT` multiplicatively.
+//!
+//! # Pairing on BLS12-381
+//!
+//! ```
+//!  use num_bigint::BigInt;
+//!  use redox_ecc::pairing::{Bls12381, PairingEngine};
+//!
+//!  let e = Bls12381::new();
+//!  let (p, q) = (e.g1_generator(), e.g2_generator());
+//!  let a = e.new_scalar(BigInt::from(5));
+//!  assert!(e.pairing(&(p.clone() * &a), &q) == e.pairing(&p, &(q * &a)));
+//! ```
+
+use num_bigint::{BigInt, BigUint};
+
+use crate::ellipticcurve::EcScalar;
+use crate::ops::{AddRef, MulRef, NegRef, ScMulRef, SubRef};
+
+mod bls12381;
+mod bn254;
+mod g2;
+mod gt;
+mod sswu;
+mod tower;
+
+pub use crate::pairing::bls12381::Bls12381;
+pub use crate::pairing::bn254::Bn254;
+pub use crate::pairing::g2::{G2Coordinates, G2Curve, G2Params, G2Point};
+pub use crate::pairing::gt::{Gt, GtElt};
+pub use crate::pairing::sswu::{G2ClearCofactor, G2SSWU};
+pub use crate::pairing::tower::{Fp12, Fp12Elt, Fp6Elt};
+
+/// PairingEngine models a pairing-friendly curve together with its
+/// pairing. The three groups share the scalars modulo their order `r`.
+pub trait PairingEngine {
+    type Scalar: EcScalar + Clone;
+    type G1: Clone + Eq + AddRef + SubRef + NegRef + ScMulRef<Self::Scalar>;
+    type G2: Clone + Eq + AddRef + SubRef + NegRef + ScMulRef<Self::Scalar>;
+    type Gt: Clone + Eq + MulRef;
+    /// Returns the order `r` of the groups.
+    fn order(&self) -> BigUint;
+    fn new_scalar(&self, _: BigInt) -> Self::Scalar;
+    fn g1_generator(&self) -> Self::G1;
+    fn g2_generator(&self) -> Self::G2;
+    /// Returns `e(p, q)`.
+    fn pairing(&self, p: &Self::G1, q: &Self::G2) -> Self::Gt;
+    /// Returns the product of `e(p_i, q_i)` for every pair, which is the
+    /// identity of `GT` if there are no pairs. Checking that a product of
+    /// pairings is the identity is the usual way protocols verify pairing
+    /// equations. Implementations should share the Miller loops and perform
+    /// a single final exponentiation, which is much faster than multiplying
+    /// the pairings.
+    fn multi_pairing(&self, pairs: &[(Self::G1, Self::G2)]) -> Self::Gt;
+}
@@ -0,0 +1,239 @@
+//! This is documentation for the `sswu` module.
+//!
+//! The sswu module implements the map to G2 of BLS12-381 specified in
+//! RFC 9380, Section 8.8.2. Since the twist has `A = 0`, the simplified SWU
+//! map is evaluated on the 3-isogenous curve
+//!
+//! ```text
+//!   y^2 = x^3 + 240i*x + 1012(1+i),
+//! ```
+//!
+//! and the resulting point is pushed through the isogeny. The cofactor of
+//! G2 is cleared by computing the multiple by `h_eff = 3(x^2 - 1)h2`, where
+//! `x` is the parameter of the curve, as prescribed by RFC 9380, though
+//! using the endomorphism `psi` rather than a scalar multiplication.
+
+use num_bigint::BigInt;
+use num_traits::identities::Zero;
+
+use std::str::FromStr;
+
+use crate::ellipticcurve::{ClearCofactor, EllipticCurve, MapToCurve};
+use crate::field::{CMov, Field, Sgn0, Sqrt};
+use crate::ops::FromFactory;
+use crate::pairing::{G2Curve, G2Point};
+use crate::quadraticfield::Fp2Elt;
+
+/// G2SSWU is the simplified SWU map to the 3-isogenous curve of the twist
+/// of BLS12-381, composed with the isogeny.
+#[derive(Clone)]
+pub struct G2SSWU {
+    e: G2Curve,
+    a: Fp2Elt,
+    b: Fp2Elt,
+    z: Fp2Elt,
+    c1: Fp2Elt,
+    c2: Fp2Elt,
+    iso: Iso3,
+}
+
+impl G2SSWU {
+    /// Returns the map to `e`, which must be the group G2 of BLS12-381,
+    /// using the constant `z`.
+    pub fn new(e: G2Curve, z: Fp2Elt) -> G2SSWU {
+        let f = e.get_field();
+        let a = f.from(ISO_A);
+        let b = f.from(ISO_B);
+        if !G2SSWU::verify(&a, &b, &z) {
+            panic!("wrong input parameters")
+        }
+        let c1 = -&b * (1u32 / &a);
+        let c2 = -(1u32 / &z);
+        let iso = Iso3::new(&e);
+        G2SSWU {
+            e,
+            a,
+            b,
+            z,
+            c1,
+            c2,
+            iso,
+        }
+    }
+    fn verify(a: &Fp2Elt, b: &Fp2Elt, z: &Fp2Elt) -> bool {
+        let cond1 = !z.is_square(); //               Z is non-square
+        let x = b * &(1u32 / &(z * a)); //           B/(Z*A)
+        let gx = &x * &((&x ^ 2u32) + a) + b; //     g(B/(Z*A))
+        let cond2 = gx.is_square(); //               g(B/(Z*A)) is square
+        cond1 && cond2
+    }
+}
+
+impl MapToCurve for G2SSWU {
+    type E = G2Curve;
+    fn map(
+        &self,
+        u: &<<Self::E as EllipticCurve>::F as Field>::Elt,
+    ) -> <Self::E as EllipticCurve>::Point {
+        let f = self.e.get_field();
+        let cmov = Fp2Elt::cmov;
+        let mut t1 = u ^ 2u32; //         0.   t1 = u^2
+        t1 = &self.z * &t1; //            1.   t1 = Z * u^2
+        let mut t2 = &t1 ^ 2u32; //       2.   t2 = t1^2
+        let mut x1 = &t1 + &t2; //        3.   x1 = t1 + t2
+        x1 = 1u32 / &x1; //               4.   x1 = inv0(x1)
+        let e1 = x1.is_zero(); //         5.   e1 = x1 == 0
+        x1 = x1 + f.one(); //             6.   x1 = x1 + 1
+        x1 = cmov(&x1, &self.c2, e1); //  7.   x1 = CMOV(x1, c2, e1)
+        x1 = x1 * &self.c1; //            8.   x1 = x1 * c1
+        let mut gx1 = &x1 ^ 2u32; //      9.  gx1 = x1^2
+        gx1 = gx1 + &self.a; //           10. gx1 = gx1 + A
+        gx1 = gx1 * &x1; //               11. gx1 = gx1 * x1
+        gx1 = gx1 + &self.b; //           12. gx1 = gx1 + B
+        let x2 = &t1 * &x1; //            13.  x2 = t1 * x1
+        t2 = t1 * t2; //                  14.  t2 = t1 * t2
+        let gx2 = &gx1 * &t2; //          15. gx2 = gx1 * t2
+        let e2 = gx1.is_square(); //      16.  e2 = is_square(gx1)
+        let x = cmov(&x2, &x1, e2); //    17.   x = CMOV(x2, x1, e2)
+        let y2 = cmov(&gx2, &gx1, e2); // 18.  y2 = CMOV(gx2, gx1, e2)
+        let mut y = y2.sqrt(); //         19.   y = sqrt(y2)
+        let e3 = u.sgn0() == y.sgn0(); // 20.  e3 = sgn0(u) == sgn0(y)
+        y = cmov(&(-&y), &y, e3); //      21.   y = CMOV(-y, y, e3)
+        self.iso.push(&self.e, &x, &y)
+    }
+}
+
+/// Iso3 is the 3-isogeny from the curve where the SSWU map is evaluated to
+/// the twist of BLS12-381.
+#[derive(Clone)]
+struct Iso3 {
+    x_num: Vec<Fp2Elt>,
+    x_den: Vec<Fp2Elt>,
+    y_num: Vec<Fp2Elt>,
+    y_den: Vec<Fp2Elt>,
+}
+
+impl Iso3 {
+    fn new(e: &G2Curve) -> Iso3 {
+        let f = e.get_field();
+        let coeffs = |k: &[&str]| k.iter().map(|ki| f.from(*ki)).collect::<Vec<_>>();
+        Iso3 {
+            x_num: coeffs(ISO3_X_NUM),
+            x_den: coeffs(ISO3_X_DEN),
+            y_num: coeffs(ISO3_Y_NUM),
+            y_den: coeffs(ISO3_Y_DEN),
+        }
+    }
+    fn push(&self, e: &G2Curve, x: &Fp2Elt, y: &Fp2Elt) -> G2Point {
+        let eval = |k: &[Fp2Elt]| {
+            k.iter()
+                .rev()
+                .fold(e.get_field().zero(), |acc, ki| acc * x + ki)
+        };
+        let x_den = eval(&self.x_den);
+        let y_den = eval(&self.y_den);
+        // the isogeny maps the points that cancel its denominators to the
+        // identity
+        if x_den.is_zero() || y_den.is_zero() {
+            return e.identity();
+        }
+        let xx = eval(&self.x_num) * (1u32 / &x_den);
+        let yy = y * eval(&self.y_num) * (1u32 / &y_den);
+        e.new_point(xx, yy)
+    }
+}
+
+/// G2ClearCofactor maps points of the twist of BLS12-381 to G2 by
+/// multiplying them by `h_eff`.
+#[derive(Clone)]
+pub struct G2ClearCofactor {
+    x: BigInt,
+}
+
+impl G2ClearCofactor {
+    pub fn new() -> G2ClearCofactor {
+        G2ClearCofactor {
+            x: BigInt::from_str(BLS_X).unwrap(),
+        }
+    }
+}
+
+impl Default for G2ClearCofactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClearCofactor for G2ClearCofactor {
+    type E = G2Curve;
+    /// Returns `[x^2 - x - 1]p + [x - 1]psi(p) + psi^2(2p)`, which equals
+    /// `[h_eff]p` but only takes two multiplications by `x`.
+    // Reference: "Efficient hash maps to G2 on BLS curves" by
+    // Budroni-Pintore. [Sec.4.1] (eprint.iacr.org/2017/419).
+    fn clear(&self, p: G2Point) -> G2Point {
+        let t1 = p.mul_int(&self.x); //           1. t1 = [x]p
+        let t2 = p.psi(); //                      2. t2 = psi(p)
+        let t3 = (&p + &p).psi().psi(); //        3. t3 = psi^2(2p)
+        let t3 = t3 - &t2; //                     4. t3 = t3 - t2
+        let t2 = (&t1 + t2).mul_int(&self.x); //  5. t2 = [x](t1 + t2)
+        t3 + t2 - t1 - p //                       6.  q = t3 + t2 - t1 - p
+    }
+}
+
+// Constants of RFC 9380, Appendix E.3. Elements of GF(p^2) are written as
+// `n0,n1`, and coefficients are listed in increasing degree.
+const ISO_A: &str = "0,240";
+const ISO_B: &str = "1012,1012";
+const BLS_X: &str = "-15132376222941642752";
+#[cfg(test)]
+const H_EFF: &str = "209869847837335686905080341498658477663839067235703451875306851526599783796572738804459333109033834234622528588876978987822447936461846631641690358257586228683615991308971558879306463436166481";
+const ISO3_X_NUM: &[&str] = &[
+    "0x5c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97d6,0x5c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97d6",
+    "0x0,0x11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71a",
+    "0x11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71e,0x8ab05f8bdd54cde190937e76bc3e447cc27c3d6fbd7063fcd104635a790520c0a395554e5c6aaaa9354ffffffffe38d",
+    "0x171d6541fa38ccfaed6dea691f5fb614cb14b4e7f4e810aa22d6108f142b85757098e38d0f671c7188e2aaaaaaaa5ed1,0x0",
+];
+const ISO3_X_DEN: &[&str] = &[
+    "0x0,0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa63",
+    "0xc,0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa9f",
+    "0x1,0x0",
+];
+const ISO3_Y_NUM: &[&str] = &[
+    "0x1530477c7ab4113b59a4c18b076d11930f7da5d4a07f649bf54439d87d27e500fc8c25ebf8c92f6812cfc71c71c6d706,0x1530477c7ab4113b59a4c18b076d11930f7da5d4a07f649bf54439d87d27e500fc8c25ebf8c92f6812cfc71c71c6d706",
+    "0x0,0x5c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97be",
+    "0x11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71c,0x8ab05f8bdd54cde190937e76bc3e447cc27c3d6fbd7063fcd104635a790520c0a395554e5c6aaaa9354ffffffffe38f",
+    "0x124c9ad43b6cf79bfbf7043de3811ad0761b0f37a1e26286b0e977c69aa274524e79097a56dc4bd9e1b371c71c718b10,0x0",
+];
+const ISO3_Y_DEN: &[&str] = &[
+    "0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa8fb,0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa8fb",
+    "0x0,0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa9d3",
+    "0x12,0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa99",
+    "0x1,0x0",
+];
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use std::str::FromStr;
+
+    use super::{G2ClearCofactor, G2SSWU, H_EFF};
+    use crate::ellipticcurve::{ClearCofactor, EllipticCurve, MapToCurve};
+    use crate::instances::{GetCurve, BLS12381G2};
+    use crate::ops::FromFactory;
+
+    #[test]
+    fn clear_cofactor() {
+        let e = BLS12381G2.get();
+        let f = e.get_field();
+        let map = G2SSWU::new(e.clone(), f.from("-2,-1"));
+        let h_eff = BigUint::from_str(H_EFF).unwrap();
+        let cofactor = G2ClearCofactor::new();
+        for u in ["0,0", "1,2", "0x1234,0x5678"].iter() {
+            let p = map.map(&f.from(*u));
+            let q = cofactor.clear(p.clone());
+            assert!(q == p.mul_uint(&h_eff));
+            assert!(e.is_in_subgroup(&q));
+        }
+    }
+}
@@ -0,0 +1,377 @@
+//! This is documentation for the `tower` module.
+//!
+//! The tower module implements the extension fields GF(p^6) and GF(p^12)
+//! of pairing-friendly curves with embedding degree 12, which are built on
+//! top of GF(p^2) as
+//!
+//! ```text
+//!   GF(p^6)  = GF(p^2)[v]/(v^3 - xi),
+//!   GF(p^12) = GF(p^6)[w]/(w^2 - v),
+//! ```
+//!
+//! where `xi` is neither a square nor a cube in GF(p^2).
+
+use impl_ops::impl_op_ex;
+use num_bigint::BigUint;
+use num_traits::identities::{One, Zero};
+
+use std::ops;
+use std::sync::Arc;
+
+use crate::do_if_eq;
+use crate::field::Field;
+use crate::ops::Serialize;
+use crate::quadraticfield::{Fp2, Fp2Elt};
+
+struct Params {
+    f: Fp2,
+    xi: Fp2Elt,
+    /// `gamma[i] = xi^(i*(p-1)/6)`, so that `(w^i)^p = gamma[i]*w^i`.
+    gamma: Vec<Fp2Elt>,
+}
+
+/// Fp12 is the tower of extensions GF(p^2) < GF(p^6) < GF(p^12).
+#[derive(Clone)]
+pub struct Fp12(Arc<Params>);
+
+impl Eq for Fp12 {}
+
+impl PartialEq for Fp12 {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.xi == other.0.xi
+    }
+}
+
+impl Fp12 {
+    /// Returns the tower over `f` defined by the non-residue `xi`. The
+    /// characteristic of `f` must be congruent to 1 modulo 6.
+    pub fn new(f: Fp2, xi: Fp2Elt) -> Fp12 {
+        let exp = (f.get_modulus() - 1u32) / 6u32;
+        let g1 = &xi ^ &exp;
+        let mut gamma = vec![f.one()];
+        for i in 1..6 {
+            gamma.push(&gamma[i - 1] * &g1);
+        }
+        Fp12(Arc::new(Params { f, xi, gamma }))
+    }
+    pub fn get_base(&self) -> Fp2 {
+        self.0.f.clone()
+    }
+    pub fn zero(&self) -> Fp12Elt {
+        let z = self.fp6(self.0.f.zero(), self.0.f.zero(), self.0.f.zero());
+        Fp12Elt {
+            c0: z.clone(),
+            c1: z,
+        }
+    }
+    pub fn one(&self) -> Fp12Elt {
+        let mut one = self.zero();
+        one.c0.c0 = self.0.f.one();
+        one
+    }
+    /// Returns the element `sum c[i]*w^i`.
+    pub fn new_elt(&self, c: [Fp2Elt; 6]) -> Fp12Elt {
+        let [c0, c1, c2, c3, c4, c5] = c;
+        Fp12Elt {
+            c0: self.fp6(c0, c2, c4),
+            c1: self.fp6(c1, c3, c5),
+        }
+    }
+    pub(crate) fn fp6(&self, c0: Fp2Elt, c1: Fp2Elt, c2: Fp2Elt) -> Fp6Elt {
+        Fp6Elt {
+            c0,
+            c1,
+            c2,
+            t: self.clone(),
+        }
+    }
+}
+
+/// Fp6Elt is the element `c0 + c1*v + c2*v^2` of GF(p^6).
+#[derive(Clone, PartialEq, Eq)]
+pub struct Fp6Elt {
+    pub(crate) c0: Fp2Elt,
+    pub(crate) c1: Fp2Elt,
+    pub(crate) c2: Fp2Elt,
+    pub(crate) t: Fp12,
+}
+
+impl Fp6Elt {
+    fn elt(&self, c0: Fp2Elt, c1: Fp2Elt, c2: Fp2Elt) -> Fp6Elt {
+        self.t.fp6(c0, c1, c2)
+    }
+    fn mul_xi(&self, a: &Fp2Elt) -> Fp2Elt {
+        a * &self.t.0.xi
+    }
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+    /// Returns the product by `v`.
+    pub fn mul_by_v(&self) -> Fp6Elt {
+        self.elt(self.mul_xi(&self.c2), self.c0.clone(), self.c1.clone())
+    }
+    // Reference: "Multiplication and Squaring on Pairing-Friendly Fields" by
+    // Devegili-OhEigeartaigh-Scott-Dahab. [Sec.4] (eprint.iacr.org/2006/471).
+    fn core_mul(&self, b: &Fp6Elt) -> Fp6Elt {
+        let (a0, a1, a2) = (&self.c0, &self.c1, &self.c2);
+        let (b0, b1, b2) = (&b.c0, &b.c1, &b.c2);
+        let v0 = a0 * b0;
+        let v1 = a1 * b1;
+        let v2 = a2 * b2;
+        let c0 = &v0 + self.mul_xi(&((a1 + a2) * (b1 + b2) - &v1 - &v2));
+        let c1 = (a0 + a1) * (b0 + b1) - &v0 - &v1 + self.mul_xi(&v2);
+        let c2 = (a0 + a2) * (b0 + b2) - &v0 - &v2 + &v1;
+        self.elt(c0, c1, c2)
+    }
+    pub fn inv(&self) -> Fp6Elt {
+        let (a0, a1, a2) = (&self.c0, &self.c1, &self.c2);
+        let t0 = a0 * a0 - self.mul_xi(&(a1 * a2));
+        let t1 = self.mul_xi(&(a2 * a2)) - a0 * a1;
+        let t2 = a1 * a1 - a0 * a2;
+        let den = a0 * &t0 + self.mul_xi(&(a2 * &t1 + a1 * &t2));
+        let inv = 1u32 / &den;
+        self.elt(t0 * &inv, t1 * &inv, t2 * &inv)
+    }
+    /// Returns `x^p`.
+    pub fn frobenius(&self) -> Fp6Elt {
+        let gamma = &self.t.0.gamma;
+        self.elt(
+            self.c0.conjugate(),
+            self.c1.conjugate() * &gamma[2],
+            self.c2.conjugate() * &gamma[4],
+        )
+    }
+}
+
+impl_op_ex!(+|a: &Fp6Elt, b: &Fp6Elt| -> Fp6Elt {
+    do_if_eq!(a.t == b.t, a.elt(&a.c0 + &b.c0, &a.c1 + &b.c1, &a.c2 + &b.c2), ERR_BIN_OP)
+});
+impl_op_ex!(-|a: &Fp6Elt, b: &Fp6Elt| -> Fp6Elt {
+    do_if_eq!(
+        a.t == b.t,
+        a.elt(&a.c0 - &b.c0, &a.c1 - &b.c1, &a.c2 - &b.c2),
+        ERR_BIN_OP
+    )
+});
+impl_op_ex!(*|a: &Fp6Elt, b: &Fp6Elt| -> Fp6Elt {
+    do_if_eq!(a.t == b.t, a.core_mul(b), ERR_BIN_OP)
+});
+impl_op_ex!(-|a: &Fp6Elt| -> Fp6Elt { a.elt(-&a.c0, -&a.c1, -&a.c2) });
+
+/// Fp12Elt is the element `c0 + c1*w` of GF(p^12).
+#[derive(Clone, PartialEq, Eq)]
+pub struct Fp12Elt {
+    pub(crate) c0: Fp6Elt,
+    pub(crate) c1: Fp6Elt,
+}
+
+impl Fp12Elt {
+    fn elt(&self, c0: Fp6Elt, c1: Fp6Elt) -> Fp12Elt {
+        Fp12Elt { c0, c1 }
+    }
+    /// Returns the coefficients `c[i]` of `sum c[i]*w^i`.
+    pub fn coefficients(&self) -> [Fp2Elt; 6] {
+        [
+            self.c0.c0.clone(),
+            self.c1.c0.clone(),
+            self.c0.c1.clone(),
+            self.c1.c1.clone(),
+            self.c0.c2.clone(),
+            self.c1.c2.clone(),
+        ]
+    }
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+    pub fn is_one(&self) -> bool {
+        self.c0.c0.is_one() && self.c0.c1.is_zero() && self.c0.c2.is_zero() && self.c1.is_zero()
+    }
+    pub fn square(&self) -> Fp12Elt {
+        // (a + bw)^2 = (a + b)(a + bv) - ab - abv + 2abw
+        let (a, b) = (&self.c0, &self.c1);
+        let ab = a * b;
+        let c0 = (a + b) * (a + b.mul_by_v()) - &ab - ab.mul_by_v();
+        let c1 = &ab + &ab;
+        self.elt(c0, c1)
+    }
+    /// Returns `x^(p^6)`, which is the inverse of `x` whenever `x` belongs to
+    /// the cyclotomic subgroup.
+    pub fn conjugate(&self) -> Fp12Elt {
+        self.elt(self.c0.clone(), -&self.c1)
+    }
+    pub fn inv(&self) -> Fp12Elt {
+        // (a + bw)^-1 = (a - bw)/(a^2 - b^2v)
+        let (a, b) = (&self.c0, &self.c1);
+        let den = (a * a - (b * b).mul_by_v()).inv();
+        self.elt(a * &den, -(b * den))
+    }
+    /// Returns `x^p`.
+    pub fn frobenius(&self) -> Fp12Elt {
+        let gamma = &self.c0.t.0.gamma;
+        let c1 = &self.c1;
+        let c1 = c1.elt(
+            c1.c0.conjugate() * &gamma[1],
+            c1.c1.conjugate() * &gamma[3],
+            c1.c2.conjugate() * &gamma[5],
+        );
+        self.elt(self.c0.frobenius(), c1)
+    }
+    /// Returns `x^k` using the square&multiply method.
+    pub fn pow(&self, k: &BigUint) -> Fp12Elt {
+        let digits = k.to_u32_digits();
+        let mut out = self.c0.t.one();
+        for i in (0..k.bits()).rev() {
+            out = out.square();
+            if (digits[i / 32] >> (i % 32)) & 1 == 1 {
+                out = out * self;
+            }
+        }
+        out
+    }
+    /// Returns the square of an element of the cyclotomic subgroup, i.e.,
+    /// such that `x^(p^4 - p^2 + 1) = 1`, which is much faster than `square`.
+    // Reference: "Faster Squaring in the Cyclotomic Subgroup of Sixth Degree
+    // Extensions" by Granger-Scott. [Sec.3.2] (eprint.iacr.org/2009/565).
+    pub fn cyclotomic_square(&self) -> Fp12Elt {
+        let t = &self.c0.t;
+        // GF(p^12) seen as GF(p^4)^3, where GF(p^4) = GF(p^2)[w^3]
+        let sqr = |a: &Fp2Elt, b: &Fp2Elt| {
+            let aa = a * a;
+            let bb = b * b;
+            let c0 = &aa + self.c0.mul_xi(&bb);
+            let c1 = (a + b) * (a + b) - aa - bb;
+            (c0, c1)
+        };
+        let (z0, z1) = (&self.c0.c0, &self.c1.c1);
+        let (z2, z3) = (&self.c1.c0, &self.c0.c2);
+        let (z4, z5) = (&self.c0.c1, &self.c1.c2);
+        let (a0, a1) = sqr(z0, z1);
+        let (b0, b1) = sqr(z2, z3);
+        let (c0, c1) = sqr(z4, z5);
+        let three = |t: &Fp2Elt, z: &Fp2Elt, plus: bool| {
+            // 3t - 2z or 3t + 2z
+            let d = if plus { t + z } else { t - z };
+            &d + &d + t
+        };
+        let z0 = three(&a0, z0, false);
+        let z1 = three(&a1, z1, true);
+        let c1xi = self.c0.mul_xi(&c1);
+        let z2 = three(&c1xi, z2, true);
+        let z3 = three(&c0, z3, false);
+        let z4 = three(&b0, z4, false);
+        let z5 = three(&b1, z5, true);
+        Fp12Elt {
+            c0: t.fp6(z0, z4, z3),
+            c1: t.fp6(z2, z1, z5),
+        }
+    }
+}
+
+impl Serialize for Fp12Elt {
+    /// serializes the coefficients `c[i]` of `sum c[i]*w^i` in increasing
+    /// order of `i`
+    fn to_bytes_be(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for c in self.coefficients().iter() {
+            out.append(&mut c.to_bytes_be());
+        }
+        out
+    }
+    fn to_bytes_le(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for c in self.coefficients().iter() {
+            out.append(&mut c.to_bytes_le());
+        }
+        out
+    }
+}
+
+impl_op_ex!(+|a: &Fp12Elt, b: &Fp12Elt| -> Fp12Elt { a.elt(&a.c0 + &b.c0, &a.c1 + &b.c1) });
+impl_op_ex!(-|a: &Fp12Elt, b: &Fp12Elt| -> Fp12Elt { a.elt(&a.c0 - &b.c0, &a.c1 - &b.c1) });
+impl_op_ex!(*|a: &Fp12Elt, b: &Fp12Elt| -> Fp12Elt {
+    // (a0 + a1w)(b0 + b1w) = a0b0 + a1b1v + ((a0 + a1)(b0 + b1) - a0b0 - a1b1)w
+    let v0 = &a.c0 * &b.c0;
+    let v1 = &a.c1 * &b.c1;
+    let c1 = (&a.c0 + &a.c1) * (&b.c0 + &b.c1) - &v0 - &v1;
+    a.elt(v0 + v1.mul_by_v(), c1)
+});
+impl_op_ex!(-|a: &Fp12Elt| -> Fp12Elt { a.elt(-&a.c0, -&a.c1) });
+
+impl std::fmt::Display for Fp12Elt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let c = self.coefficients();
+        write!(f, "({})", c[0])?;
+        for (i, ci) in c.iter().enumerate().skip(1) {
+            write!(f, " + ({})*w^{}", ci, i)?;
+        }
+        Ok(())
+    }
+}
+
+const ERR_BIN_OP: &str = "elements of different fields";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, BigUint};
+
+    use super::Fp12;
+    use crate::field::Field;
+    use crate::quadraticfield::Fp2;
+
+    fn tower() -> Fp12 {
+        // a toy tower over p = 67, where xi = 1+i is neither a square nor a cube
+        let f = Fp2::new(BigUint::from(67u32));
+        let xi = f.new_elt(
+            f.get_base().elt(BigInt::from(1)),
+            f.get_base().elt(BigInt::from(1)),
+        );
+        Fp12::new(f, xi)
+    }
+
+    #[test]
+    fn arithmetic() {
+        let t = tower();
+        let f = t.get_base();
+        let elt = |k: i64| {
+            let c = |j: i64| {
+                f.new_elt(
+                    f.get_base().elt(BigInt::from(k * j + 3)),
+                    f.get_base().elt(BigInt::from(k + j)),
+                )
+            };
+            t.new_elt([c(1), c(2), c(3), c(4), c(5), c(6)])
+        };
+        let (a, b) = (elt(5), elt(11));
+        assert!((&a * &b) * a.inv() == b);
+        assert!(a.square() == &a * &a);
+        assert!((&a + &b) - &b == a);
+        // the order of GF(p^12)* is p^12 - 1
+        let p = BigUint::from(67u32);
+        assert!(a.pow(&(num_traits::pow(p.clone(), 12) - 1u32)) == t.one());
+        let mut x = a.clone();
+        for _ in 0..12 {
+            x = x.frobenius();
+        }
+        assert!(x == a);
+        assert!(a.frobenius() == a.pow(&p));
+        assert!(a.pow(&num_traits::pow(p.clone(), 6)) == a.conjugate());
+    }
+
+    #[test]
+    fn cyclotomic() {
+        let t = tower();
+        let f = t.get_base();
+        let c = |j: i64| {
+            f.new_elt(
+                f.get_base().elt(BigInt::from(j)),
+                f.get_base().elt(BigInt::from(2 * j + 1)),
+            )
+        };
+        let a = t.new_elt([c(1), c(2), c(3), c(4), c(5), c(6)]);
+        // a^((p^6 - 1)(p^2 + 1)) belongs to the cyclotomic subgroup
+        let a = a.conjugate() * a.inv();
+        let a = a.frobenius().frobenius() * &a;
+        assert!(a.cyclotomic_square() == a.square());
+        assert!(a.conjugate() == a.inv());
+    }
+}
@@ -0,0 +1,147 @@
+//! This is documentation for the `pedersen` module.
+//!
+//! The pedersen module implements vector Pedersen commitments over any
+//! prime-order group, i.e., commitments to `v_1, ..., v_n` of the form
+//! `C = [v_1]G_1 + ... + [v_n]G_n + [r]H`, where `r` is the blinding factor.
+//!
+//! The generators are derived by hashing to the group, so nobody knows a
+//! discrete logarithm relation among them, and they depend only on a label
+//! and their index. Hence, a set of generators for `n` values is a prefix of
+//! any larger set with the same label.
+//!
+//! # Committing to a Vector
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::group::{PrimeGroup, Ristretto255};
+//!  use redox_ecc::pedersen::Pedersen;
+//!
+//!  let g = Ristretto255::new();
+//!  let values = [g.new_scalar(3.into()), g.new_scalar(5.into())];
+//!  let pc = Pedersen::new(g, b"example", 2);
+//!  let (c, r) = pc.commit(&values, &mut OsRng).unwrap();
+//!  assert!(pc.open(&c, &values, &r).is_ok());
+//! ```
+
+use rand_core::{CryptoRng, RngCore};
+
+use std::io::Error;
+
+use crate::group::PrimeGroup;
+use crate::h2c::Dst;
+
+/// Pedersen is a commitment scheme to vectors of up to `n` scalars.
+#[derive(Clone)]
+pub struct Pedersen<G: PrimeGroup> {
+    g: G,
+    gens: Vec<G::Elt>,
+    h: G::Elt,
+}
+
+impl<G: PrimeGroup> Pedersen<G> {
+    /// Returns a commitment scheme for vectors of up to `n` scalars, whose
+    /// generators are derived from `label`.
+    pub fn new(g: G, label: &[u8], n: usize) -> Pedersen<G> {
+        let h = derive_generator(&g, label, 0);
+        let gens = (1..=n).map(|i| derive_generator(&g, label, i)).collect();
+        Pedersen { g, gens, h }
+    }
+    /// Returns the generators `G_1, ..., G_n` used for the values.
+    pub fn generators(&self) -> &[G::Elt] {
+        &self.gens
+    }
+    /// Returns the generator `H` used for the blinding factor.
+    pub fn blinding_generator(&self) -> &G::Elt {
+        &self.h
+    }
+    /// Commits to `values` with a blinding factor sampled uniformly at
+    /// random from `rng`. It returns the commitment and the blinding factor, which is
+    /// needed for opening it. It fails if there are more than `n` values.
+    pub fn commit<R: RngCore + CryptoRng>(
+        &self,
+        values: &[G::Scalar],
+        rng: &mut R,
+    ) -> Result<(G::Elt, G::Scalar), Error> {
+        let r = self.g.random_scalar(rng);
+        let c = self.commit_with(values, &r)?;
+        Ok((c, r))
+    }
+    /// Commits to `values` with the blinding factor `r`. Missing values are
+    /// taken as zero.
+    pub fn commit_with(&self, values: &[G::Scalar], r: &G::Scalar) -> Result<G::Elt, Error> {
+        if values.len() > self.gens.len() {
+            return Err(Error::other(ERR_TOO_MANY));
+        }
+        let mut points = self.gens[..values.len()].to_vec();
+        points.push(self.h.clone());
+        let mut scalars = values.to_vec();
+        scalars.push(r.clone());
+        Ok(self.g.multi_scalar_mul(&points, &scalars))
+    }
+    /// Verifies that `c` is a commitment to `values` with the blinding
+    /// factor `r`.
+    pub fn open(&self, c: &G::Elt, values: &[G::Scalar], r: &G::Scalar) -> Result<(), Error> {
+        if self.commit_with(values, r)? != *c {
+            return Err(Error::other(ERR_OPENING));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the generator with index `i`, where the index zero is reserved
+/// for the blinding generator.
+fn derive_generator<G: PrimeGroup>(g: &G, label: &[u8], i: usize) -> G::Elt {
+    let dst = Dst::new(GENERATOR_DST).unwrap();
+    let mut msg = (label.len() as u64).to_be_bytes().to_vec();
+    msg.extend_from_slice(label);
+    msg.extend_from_slice(&(i as u64).to_be_bytes());
+    g.hash_to_group(&msg, &dst)
+}
+
+const GENERATOR_DST: &[u8] = b"REDOX-ECC-PEDERSEN-V01";
+const ERR_TOO_MANY: &str = "too many values for the number of generators";
+const ERR_OPENING: &str = "commitment does not open to the values";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand_core::OsRng;
+
+    use super::Pedersen;
+    use crate::group::{PrimeGroup, Ristretto255, WeGroup};
+    use crate::h2c::P256_XMDSHA256_SSWU_RO_;
+
+    fn check_commitments<G: PrimeGroup + Clone>(g: G) {
+        let k = |v: i64| g.new_scalar(BigInt::from(v));
+        let pc = Pedersen::new(g.clone(), b"test", 3);
+        let gens = pc.generators();
+        let h = pc.blinding_generator();
+        assert!(gens[0] != gens[1] && gens[1] != gens[2] && gens[0] != *h);
+
+        let values = [k(2), k(3), k(5)];
+        let c = pc.commit_with(&values, &k(7)).unwrap();
+        let want = gens[0].clone() * k(2) + gens[1].clone() * k(3) + gens[2].clone() * k(5);
+        assert!(c == want + h.clone() * k(7));
+        assert!(pc.open(&c, &values, &k(7)).is_ok());
+        assert!(pc.open(&c, &values, &k(8)).is_err());
+        assert!(pc.open(&c, &[k(2), k(3), k(6)], &k(7)).is_err());
+        assert!(pc.commit_with(&vec![k(1); 4], &k(1)).is_err());
+
+        // commitments are additively homomorphic
+        let (c0, r0) = pc.commit(&[k(1), k(2)], &mut OsRng).unwrap();
+        let (c1, r1) = pc.commit(&[k(10), k(20)], &mut OsRng).unwrap();
+        assert!(pc.open(&(c0 + c1), &[k(11), k(22)], &(r0 + r1)).is_ok());
+
+        // generators depend only on the label and their index
+        let more = Pedersen::new(g.clone(), b"test", 5);
+        assert!(more.generators()[..3] == *gens && more.blinding_generator() == h);
+        let other = Pedersen::new(g, b"other", 3);
+        assert!(other.generators()[0] != gens[0]);
+    }
+
+    #[test]
+    fn commitments() {
+        check_commitments(Ristretto255::new());
+        check_commitments(WeGroup::new(P256_XMDSHA256_SSWU_RO_));
+    }
+}
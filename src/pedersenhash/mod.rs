@@ -0,0 +1,216 @@
+//! This is documentation for the `pedersenhash` module.
+//!
+//! The pedersenhash module implements the windowed Pedersen hash used by
+//! Zcash over Jubjub, and generically over any Edwards curve.
+//!
+//! The message is a sequence of bits, padded with zeros to a multiple of
+//! three and split into segments of `c` chunks of three bits. Each chunk
+//! `(s0, s1, s2)` encodes the signed digit `(1 - 2*s2) * (1 + s0 + 2*s1)`,
+//! and each segment `M_i` encodes the integer `<M_i>` whose digits in base
+//! 16 are those of its chunks. The hash is the point
+//!
+//! ```text
+//!   [<M_1>]I_1 + [<M_2>]I_2 + ...
+//! ```
+//!
+//! where the generators `I_i` depend on a personalization string. The
+//! number of chunks per segment is bounded so that `<M_i>` never wraps
+//! around the order of the curve, which makes the hash collision resistant.
+//!
+//! Unlike Zcash, the generators are derived with SHA-512 instead of
+//! BLAKE2s, so the outputs differ from those of Zcash.
+//!
+//! # Hashing to a Point
+//!
+//! ```
+//!  use redox_ecc::pedersenhash::PedersenHash;
+//!
+//!  let ph = PedersenHash::jubjub(b"example");
+//!  let h = ph.hash_bytes_to_field(b"message");
+//!  assert!(h == ph.hash_bytes_to_field(b"message"));
+//! ```
+
+use atomic_refcell::AtomicRefCell;
+use num_bigint::{BigInt, BigUint};
+use num_traits::identities::{One, Zero};
+use sha2::Sha512;
+
+use std::io::Error;
+use std::sync::Arc;
+
+use crate::edwards::{Curve, Point};
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve};
+use crate::field::Field;
+use crate::h2c::{Dst, Expander, ExpanderXmd};
+use crate::instances::{GetCurve, JUBJUB};
+use crate::primefield::FpElt;
+
+/// PedersenHash is the windowed Pedersen hash over an Edwards curve for a
+/// given personalization.
+#[derive(Clone)]
+pub struct PedersenHash {
+    e: Curve,
+    personalization: Vec<u8>,
+    chunks: usize,
+    gens: Arc<AtomicRefCell<Vec<Point>>>,
+}
+
+impl PedersenHash {
+    /// Returns the hash over Jubjub with 63 chunks per segment, as in
+    /// Zcash.
+    pub fn jubjub(personalization: &[u8]) -> PedersenHash {
+        PedersenHash::new(JUBJUB.get(), personalization, JUBJUB_CHUNKS).unwrap()
+    }
+    /// Returns the hash over the curve `e` with `chunks` chunks of three
+    /// bits per segment. It fails if `chunks` is zero, or if it is so large
+    /// that the encoding of a segment may exceed `(r-1)/2`, where `r` is the
+    /// order of the curve.
+    pub fn new(e: Curve, personalization: &[u8], chunks: usize) -> Result<PedersenHash, Error> {
+        // the largest encoding of a segment is 4 * (1 + 16 + ... + 16^(c-1))
+        let max = ((BigUint::one() << (4 * chunks)) - 1u32) * 4u32 / 15u32;
+        if chunks == 0 || max > (e.get_order() - 1u32) / 2u32 {
+            return Err(Error::other(ERR_CHUNKS));
+        }
+        Ok(PedersenHash {
+            e,
+            personalization: personalization.to_vec(),
+            chunks,
+            gens: Arc::new(AtomicRefCell::new(Vec::new())),
+        })
+    }
+    /// Returns the number of bits absorbed by each generator.
+    pub fn segment_bits(&self) -> usize {
+        3 * self.chunks
+    }
+    /// Hashes a sequence of bits to a point of the prime-order subgroup.
+    pub fn hash_to_point(&self, bits: &[bool]) -> Point {
+        let mut h = self.e.identity();
+        for (i, segment) in bits.chunks(self.segment_bits()).enumerate() {
+            let k = self.e.new_scalar(encode_segment(segment));
+            h += self.generator(i) * k;
+        }
+        h
+    }
+    /// Hashes a sequence of bits to the u-coordinate of the point, which is
+    /// the output used by Zcash for note commitments and Merkle trees.
+    pub fn hash_to_field(&self, bits: &[bool]) -> FpElt {
+        self.hash_to_point(bits).x()
+    }
+    /// Hashes a string of bytes to a point. Bytes are read from first to
+    /// last, and the bits of each byte from the least significant.
+    pub fn hash_bytes_to_point(&self, msg: &[u8]) -> Point {
+        self.hash_to_point(&to_bits(msg))
+    }
+    /// Hashes a string of bytes to the u-coordinate of the point.
+    pub fn hash_bytes_to_field(&self, msg: &[u8]) -> FpElt {
+        self.hash_to_field(&to_bits(msg))
+    }
+    /// Returns the generator `I_{i+1}`, deriving it on the first use.
+    pub fn generator(&self, i: usize) -> Point {
+        let mut gens = self.gens.borrow_mut();
+        while gens.len() <= i {
+            let p = self.derive_generator(gens.len() as u32);
+            gens.push(p);
+        }
+        gens[i].clone()
+    }
+    /// Derives a generator by trying to decode the output of a hash as a
+    /// point until it succeeds, and then clearing the cofactor.
+    fn derive_generator(&self, index: u32) -> Point {
+        let e = &self.e;
+        let size = (e.get_field().get_modulus().bits() + 1).div_ceil(8);
+        let dst = Dst::new(GENERATOR_DST).unwrap();
+        let mut msg = (self.personalization.len() as u64).to_be_bytes().to_vec();
+        msg.extend_from_slice(&self.personalization);
+        msg.extend_from_slice(&index.to_be_bytes());
+        let cofactor = e.new_scalar(e.get_cofactor());
+        for ctr in 0u32.. {
+            let buf = ExpanderXmd::<Sha512>::new(&dst)
+                .expand(&[&msg[..], &ctr.to_be_bytes()].concat(), size);
+            if let Ok(p) = e.decode(&buf) {
+                let p = p * &cofactor;
+                if !p.is_identity() {
+                    return p;
+                }
+            }
+        }
+        unreachable!()
+    }
+}
+
+/// Returns the integer `<M_i>` encoded by a segment.
+fn encode_segment(segment: &[bool]) -> BigInt {
+    let mut k = BigInt::zero();
+    let mut pow = BigInt::from(1);
+    for chunk in segment.chunks(3) {
+        let bit = |j: usize| chunk.get(j).copied().unwrap_or(false) as i32;
+        let digit = (1 - 2 * bit(2)) * (1 + bit(0) + 2 * bit(1));
+        k += &pow * digit;
+        pow <<= 4usize;
+    }
+    k
+}
+
+fn to_bits(msg: &[u8]) -> Vec<bool> {
+    msg.iter()
+        .flat_map(|b| (0..8).map(move |j| (b >> j) & 1 == 1))
+        .collect()
+}
+
+const JUBJUB_CHUNKS: usize = 63;
+const GENERATOR_DST: &[u8] = b"REDOX-ECC-PEDERSEN-HASH-V01";
+const ERR_CHUNKS: &str = "number of chunks per segment is out of range";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::{encode_segment, PedersenHash};
+    use crate::ellipticcurve::{EcPoint, EllipticCurve};
+    use crate::instances::{GetCurve, EDWARDS25519, JUBJUB};
+
+    #[test]
+    fn segments() {
+        let bits = |s: &str| s.chars().map(|c| c == '1').collect::<Vec<bool>>();
+        assert_eq!(encode_segment(&bits("000")), BigInt::from(1));
+        assert_eq!(encode_segment(&bits("110")), BigInt::from(4));
+        assert_eq!(encode_segment(&bits("111")), BigInt::from(-4));
+        assert_eq!(encode_segment(&bits("001")), BigInt::from(-1));
+        // the last chunk is padded with zeros
+        assert_eq!(encode_segment(&bits("0001")), BigInt::from(1 + 2 * 16));
+    }
+
+    #[test]
+    fn hash() {
+        let ph = PedersenHash::new(JUBJUB.get(), b"test", 4).unwrap();
+        let e = JUBJUB.get();
+        let r = e.new_scalar(BigInt::from(e.get_order()));
+        let g0 = ph.generator(0);
+        assert!(!g0.is_identity() && (g0.clone() * &r).is_identity());
+        assert!(g0 != ph.generator(1));
+
+        // one segment of 12 bits, and two segments
+        let msg: Vec<bool> = (0..12).map(|i| i % 5 == 0).collect();
+        let k = e.new_scalar(encode_segment(&msg));
+        assert!(ph.hash_to_point(&msg) == g0.clone() * k);
+        let mut longer = msg.clone();
+        longer.extend_from_slice(&[false, false, true]);
+        let k1 = e.new_scalar(BigInt::from(-1));
+        assert!(ph.hash_to_point(&longer) == ph.hash_to_point(&msg) + ph.generator(1) * k1);
+        // the last chunk is padded with zeros
+        let k3 = e.new_scalar(BigInt::from(2 * 16 * 16 * 16));
+        let padded = [&msg[..9], &[true]].concat();
+        assert!(ph.hash_to_point(&padded) == ph.hash_to_point(&msg[..9]) + g0 * k3);
+
+        let other = PedersenHash::new(JUBJUB.get(), b"other", 4).unwrap();
+        assert!(other.hash_to_point(&msg) != ph.hash_to_point(&msg));
+        let jubjub = PedersenHash::jubjub(b"test");
+        assert!(jubjub.hash_bytes_to_field(b"abc") != jubjub.hash_bytes_to_field(b"abd"));
+
+        assert!(PedersenHash::new(JUBJUB.get(), b"test", 0).is_err());
+        assert!(PedersenHash::new(JUBJUB.get(), b"test", 63).is_ok());
+        assert!(PedersenHash::new(JUBJUB.get(), b"test", 64).is_err());
+        let ed = PedersenHash::new(EDWARDS25519.get(), b"test", 62).unwrap();
+        assert!(!ed.hash_bytes_to_point(b"abc").is_small_order());
+    }
+}
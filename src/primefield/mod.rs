@@ -2,24 +2,33 @@
 //!
 //! The primefield module is meant to be used for bar.
 
-use atomic_refcell::AtomicRefCell;
+use digest::Digest;
 use impl_ops::impl_op_ex;
-use num_bigint::{BigInt, BigUint, ToBigInt};
+use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
 use num_integer::Integer;
 use num_traits::cast::ToPrimitive;
 use num_traits::identities::{One, Zero};
+use num_traits::Signed;
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
+use std::io::Error;
 use std::ops;
 use std::ops::{BitXor, Div};
 use std::sync::Arc;
 
-use crate::do_if_eq;
 use crate::field::{CMov, Field, FieldElement, Sgn0, Sqrt};
-use crate::ops::{Deserialize, FromFactory, Serialize};
+use crate::h2c::{get_length, Expander, HashToField};
+use crate::integer;
+use crate::ops::{hash_wide, map_batch, Deserialize, FromFactory, Serialize, WIDE_REDUCTION_BYTES};
+use crate::{count_op, do_if_eq};
 
 struct Params {
     p: BigInt,
-    sqrt_precmp: AtomicRefCell<Option<SqrtPrecmp>>,
+    p_minus_1: BigInt,
+    p_minus_1_div_2: BigInt,
+    p_minus_2: BigInt,
+    sqrt_precmp: SqrtPrecmp,
 }
 
 impl Eq for Params {}
@@ -41,14 +50,70 @@ impl Fp {
     ///  use redox_ecc::primefield::Fp;
     ///  let f = Fp::new(BigUint::from(101u32));
     /// ```
-    /// The `modulus` should be a prime number.
+    /// The `modulus` should be a prime number. The exponents used by
+    /// square roots, quadratic residuosity tests, and inversions are
+    /// computed here once for all the elements of the field.
     pub fn new(modulus: BigUint) -> Self {
         // TODO: verify whether p is prime.
+        let p = modulus.to_bigint().unwrap();
+        let p_minus_1: BigInt = &p - 1u32;
         Fp(Arc::new(Params {
-            p: modulus.to_bigint().unwrap(),
-            sqrt_precmp: AtomicRefCell::new(None),
+            p_minus_1_div_2: &p_minus_1 >> 1usize,
+            p_minus_2: &p - 2u32,
+            sqrt_precmp: SqrtPrecmp::new(&p),
+            p_minus_1,
+            p,
         }))
     }
+    /// Parses the canonical encoding of an element, i.e., exactly
+    /// `size_bytes` bytes in big-endian order holding an integer less than
+    /// the modulus. All point decoders parse coordinates with this function
+    /// or `from_canonical_le`, so values that are not reduced, as well as
+    /// encodings that are too short or too long, are rejected consistently.
+    pub fn from_canonical_be(&self, buf: &[u8]) -> Result<FpElt, Error> {
+        self.canonical(BigInt::from_bytes_be(Sign::Plus, buf), buf.len())
+    }
+    /// Parses the canonical encoding of an element in little-endian order.
+    pub fn from_canonical_le(&self, buf: &[u8]) -> Result<FpElt, Error> {
+        self.canonical(BigInt::from_bytes_le(Sign::Plus, buf), buf.len())
+    }
+    fn canonical(&self, n: BigInt, len: usize) -> Result<FpElt, Error> {
+        if len != self.size_bytes() {
+            return Err(Error::other(ERR_SIZE));
+        }
+        if n >= self.0.p {
+            return Err(Error::other(ERR_NON_CANONICAL));
+        }
+        Ok(FpElt { n, f: self.clone() })
+    }
+    /// Returns the square roots of the elements of `a`, or `None` for the
+    /// elements that are not squares. The constants of the square-root
+    /// method are fetched once for all the elements, and each root is
+    /// checked by squaring it, which saves the exponentiation done by
+    /// `is_square`. The roots are computed in parallel with the `parallel`
+    /// feature.
+    pub fn batch_sqrt(&self, a: &[FpElt]) -> Vec<Option<FpElt>> {
+        map_batch(a, |x| {
+            let r = x.sqrt_with(&self.0.sqrt_precmp);
+            if &r * &r == *x {
+                Some(r)
+            } else {
+                None
+            }
+        })
+    }
+    /// Returns a non-zero element sampled at random. It reduces 128 more bits
+    /// than the size of the modulus, so the bias of the output is negligible.
+    pub fn random_nonzero<R: RngCore + CryptoRng>(&self, rng: &mut R) -> FpElt {
+        let mut buf = vec![0u8; self.size_bytes() + 16];
+        loop {
+            rng.fill_bytes(&mut buf);
+            let x = self.elt(BigInt::from_bytes_be(Sign::Plus, &buf));
+            if !x.is_zero() {
+                return x;
+            }
+        }
+    }
 }
 
 impl Field for Fp {
@@ -68,7 +133,7 @@ impl Field for Fp {
         self.0.p.clone()
     }
     fn size_bytes(&self) -> usize {
-        (self.0.p.bits() + 7) / 8
+        self.0.p.bits().div_ceil(8)
     }
 }
 
@@ -84,6 +149,23 @@ impl Deserialize for Fp {
     }
 }
 
+impl HashToField for Fp {
+    fn hash_to_field(
+        &self,
+        exp: &dyn Expander,
+        msg: &[u8],
+        count: usize,
+        k: usize,
+    ) -> Vec<Self::Elt> {
+        let l = get_length(self, k);
+        let bytes = exp.expand(msg, count * l);
+        bytes
+            .chunks(l)
+            .map(|tv| self.from_bytes_be(tv).unwrap())
+            .collect()
+    }
+}
+
 macro_rules! impl_from_factory {
     ($target:ident, <$($other:ty)+> ) => {
      $(
@@ -99,6 +181,13 @@ macro_rules! impl_from_factory {
 
 impl_from_factory!(Fp, <u8 u16 u32 u64 i8 i16 i32 i64>);
 
+impl FromFactory<integer::Integer> for Fp {
+    type Output = <Fp as Field>::Elt;
+    fn from(&self, n: integer::Integer) -> Self::Output {
+        self.elt(BigInt::from(n))
+    }
+}
+
 impl FromFactory<&str> for Fp {
     type Output = <Fp as Field>::Elt;
     fn from(&self, s: &str) -> Self::Output {
@@ -135,7 +224,8 @@ impl FromFactory<&str> for Fp {
     }
 }
 
-/// FpElt is an element of a prime field.
+/// FpElt is an element of a prime field. Comparisons with `==` take
+/// variable time; use `ct_eq` for secret elements.
 #[derive(Clone, PartialEq, Eq)]
 pub struct FpElt {
     n: BigInt,
@@ -144,6 +234,15 @@ pub struct FpElt {
 
 impl FieldElement for FpElt {}
 
+impl ConstantTimeEq for FpElt {
+    /// Compares the canonical encodings of the elements in constant time.
+    /// Elements of different fields are never equal.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let same_field = Choice::from((self.f == other.f) as u8);
+        same_field & self.to_bytes_be().ct_eq(&other.to_bytes_be())
+    }
+}
+
 impl Serialize for FpElt {
     /// serializes the field element into big-endian bytes
     fn to_bytes_be(&self) -> Vec<u8> {
@@ -167,13 +266,13 @@ impl Serialize for FpElt {
 
 // impl<'b> EltOps<&'b FpElt, FpElt> for FpElt {}
 // impl<'a> EltOps<FpElt, FpElt> for &'a FpElt {}
-impl<'a, 'b> std::ops::Add<&'b FpElt> for &'a FpElt {
+impl<'b> std::ops::Add<&'b FpElt> for &FpElt {
     type Output = FpElt;
     fn add(self, other: &'b FpElt) -> FpElt {
         do_if_eq!(self.f == other.f, self.red(&self.n + &other.n), ERR_BIN_OP)
     }
 }
-impl<'a> std::ops::Add<FpElt> for &'a FpElt {
+impl std::ops::Add<FpElt> for &FpElt {
     type Output = FpElt;
     fn add(self, other: FpElt) -> FpElt {
         do_if_eq!(self.f == other.f, self.red(&self.n + &other.n), ERR_BIN_OP)
@@ -189,8 +288,15 @@ impl FpElt {
     }
     #[inline]
     fn inv_mod(&self) -> FpElt {
-        let p_minus_2 = &self.f.0.p - 2u32;
-        self ^ &p_minus_2
+        count_op!(inv);
+        self.pow(&self.f.0.p_minus_2)
+    }
+    /// Returns the element raised to `exp`, which must be non-negative.
+    #[inline]
+    fn pow(&self, exp: &BigInt) -> FpElt {
+        let n = self.n.modpow(exp, &self.f.0.p);
+        let f = self.f.clone();
+        FpElt { n, f }
     }
 }
 
@@ -201,9 +307,40 @@ impl_op_ex!(-|a: &FpElt, b: &FpElt| -> FpElt {
     do_if_eq!(a.f == b.f, a.red(&a.n - &b.n), ERR_BIN_OP)
 });
 impl_op_ex!(*|a: &FpElt, b: &FpElt| -> FpElt {
+    if std::ptr::eq(a, b) {
+        count_op!(sqr);
+    } else {
+        count_op!(mul);
+    }
     do_if_eq!(a.f == b.f, a.red(&a.n * &b.n), ERR_BIN_OP)
 });
 
+// The assigning operators update the element in place, saving the
+// temporaries of the binary operators in long formulas.
+impl_op_ex!(+=|a: &mut FpElt, b: &FpElt| {
+    do_if_eq!(a.f == b.f, {
+        a.n += &b.n;
+        if a.n >= a.f.0.p {
+            a.n -= &a.f.0.p;
+        }
+    }, ERR_BIN_OP)
+});
+impl_op_ex!(-=|a: &mut FpElt, b: &FpElt| {
+    do_if_eq!(a.f == b.f, {
+        a.n -= &b.n;
+        if a.n.is_negative() {
+            a.n += &a.f.0.p;
+        }
+    }, ERR_BIN_OP)
+});
+impl_op_ex!(*=|a: &mut FpElt, b: &FpElt| {
+    count_op!(mul);
+    do_if_eq!(a.f == b.f, {
+        a.n *= &b.n;
+        a.n %= &a.f.0.p;
+    }, ERR_BIN_OP)
+});
+
 impl_op_ex!(/|a: &FpElt, b: &FpElt| -> FpElt {
     #[allow(clippy::suspicious_arithmetic_impl)] {
         a * b.inv_mod()
@@ -217,7 +354,7 @@ impl_op_ex!(^|a: &FpElt, b: i32| -> FpElt {
     do_if_eq!(b == -1i32, a.inv_mod(), ERR_EXP_INV_OP)
 });
 
-impl<'a> Div<&'a FpElt> for u32 {
+impl Div<&FpElt> for u32 {
     type Output = FpElt;
     #[inline]
     fn div(self, other: &FpElt) -> Self::Output {
@@ -225,7 +362,7 @@ impl<'a> Div<&'a FpElt> for u32 {
     }
 }
 
-impl<'a, 'b> BitXor<&'b BigUint> for &'a FpElt {
+impl<'b> BitXor<&'b BigUint> for &FpElt {
     type Output = FpElt;
     #[inline]
     fn bitxor(self, exp: &'b BigUint) -> Self::Output {
@@ -234,55 +371,104 @@ impl<'a, 'b> BitXor<&'b BigUint> for &'a FpElt {
     }
 }
 
-impl<'a, 'b> BitXor<&'b BigInt> for &'a FpElt {
+impl<'b> BitXor<&'b BigInt> for &FpElt {
     type Output = FpElt;
     #[inline]
     fn bitxor(self, exp: &'b BigInt) -> Self::Output {
-        let expo = &exp.mod_floor(&(&self.f.0.p - 1)).to_biguint().unwrap();
-        self ^ expo
+        self.pow(&exp.mod_floor(&self.f.0.p_minus_1))
     }
 }
 
 impl CMov for FpElt {}
 
+impl FpElt {
+    /// Returns the element obtained by hashing `msg` with `D`. The digest
+    /// is extended to 128 bits more than the size of the modulus before it
+    /// is reduced, so the bias of the output is negligible.
+    pub fn from_hash<D: Digest>(msg: &[u8], f: &Fp) -> FpElt {
+        f.elt(hash_wide::<D>(msg, f.size_bytes() + WIDE_REDUCTION_BYTES))
+    }
+    /// Squares the element in place.
+    pub fn square_assign(&mut self) {
+        count_op!(sqr);
+        self.n = &self.n * &self.n;
+        self.n %= &self.f.0.p;
+    }
+    /// Returns `self*a + b` with a single reduction.
+    pub fn mul_add(&self, a: &FpElt, b: &FpElt) -> FpElt {
+        count_op!(mul);
+        do_if_eq!(
+            self.f == a.f && a.f == b.f,
+            self.red(&self.n * &a.n + &b.n),
+            ERR_BIN_OP
+        )
+    }
+    /// Returns `b` if `choice` is set and `a` otherwise. Unlike `cmov`, the
+    /// result is assembled from the canonical encodings of both elements
+    /// byte by byte, so the selection does not branch on `choice`.
+    pub fn ct_select(a: &FpElt, b: &FpElt, choice: Choice) -> FpElt {
+        do_if_eq!(
+            a.f == b.f,
+            {
+                let bytes: Vec<u8> = a
+                    .to_bytes_be()
+                    .iter()
+                    .zip(b.to_bytes_be().iter())
+                    .map(|(x, y)| u8::conditional_select(x, y, choice))
+                    .collect();
+                a.f.elt(BigInt::from_bytes_be(Sign::Plus, &bytes))
+            },
+            ERR_BIN_OP
+        )
+    }
+    /// Negates the element if `choice` is set. Both the element and its
+    /// negation are computed, and the result is picked with `ct_select`.
+    pub fn conditional_negate(&mut self, choice: Choice) {
+        let neg = -&*self;
+        *self = FpElt::ct_select(self, &neg, choice);
+    }
+}
+
 #[derive(Clone, std::cmp::PartialEq)]
 enum SqrtPrecmp {
     P3MOD4 { exp: BigInt },
-    P5MOD8 { exp: BigInt, sqrt_minus_one: FpElt },
-    P9MOD16,
-    P1MOD16,
+    P5MOD8 { exp: BigInt, sqrt_minus_one: BigInt },
+    P1MOD8 { c1: usize, c3: BigInt, c6: BigInt },
 }
-impl Fp {
-    fn get_sqrt_precmp(&self) -> SqrtPrecmp {
-        self.0
-            .sqrt_precmp
-            .borrow_mut()
-            .get_or_insert(self.calc_sqrt_precmp())
-            .clone()
-    }
-    fn calc_sqrt_precmp(&self) -> SqrtPrecmp {
-        let p = &self.0.p;
+impl SqrtPrecmp {
+    fn new(p: &BigInt) -> SqrtPrecmp {
         let res = (p % 16u32).to_u32().unwrap();
         if 3u32 == (res % 4u32) {
             let exp = (p + 1u32) >> 2usize;
             SqrtPrecmp::P3MOD4 { exp }
         } else if 5u32 == (res % 8u32) {
             let k = (p - 5u32) >> 3usize;
-            let t = &(self.one() + self.one()) ^ &k; // t = 2^k
-            let mut t0 = &t ^ 2u32; //  t^2
-            t0 = &t0 + &t0; //          2t^2
-            t0 = t0 + self.one(); //       2t^2+1
-            t0 = t0 * t; //             t(2t^2+1)
+            let t = BigInt::from(2).modpow(&k, p); // t = 2^k
+            let mut t0 = &t * &t; //                   t^2
+            t0 = &t0 + &t0 + 1u32; //                  2t^2+1
+            t0 = (t0 * t) % p; //                      t(2t^2+1)
             let exp = k + 1;
             let sqrt_minus_one = t0;
             SqrtPrecmp::P5MOD8 {
                 exp,
                 sqrt_minus_one,
             }
-        } else if 9u32 == (res % 16u32) {
-            SqrtPrecmp::P9MOD16
         } else {
-            SqrtPrecmp::P1MOD16
+            // based on RFC 9380, Appendix I.4
+            let p_minus_1: BigInt = p - 1u32;
+            let mut c1 = 0usize;
+            while (&p_minus_1 >> c1).is_even() {
+                c1 += 1;
+            }
+            let c2 = &p_minus_1 >> c1;
+            let c3 = (&c2 - 1u32) >> 1usize;
+            let p_minus_1_div_2 = p_minus_1 >> 1usize;
+            let mut z = BigInt::from(2);
+            while z.modpow(&p_minus_1_div_2, p).is_one() {
+                z += 1u32;
+            }
+            let c6 = z.modpow(&c2, p);
+            SqrtPrecmp::P1MOD8 { c1, c3, c6 }
         }
     }
 }
@@ -290,26 +476,46 @@ impl Fp {
 impl Sqrt for FpElt {
     #[inline]
     fn is_square(&self) -> bool {
-        let p_minus_1_div_2 = (&self.f.0.p - 1) >> 1usize;
-        let res: FpElt = self ^ &p_minus_1_div_2;
+        let res = self.pow(&self.f.0.p_minus_1_div_2);
         res.is_one() || res.is_zero()
     }
     fn sqrt(&self) -> FpElt {
-        let pre = self.f.get_sqrt_precmp();
+        self.sqrt_with(&self.f.0.sqrt_precmp)
+    }
+}
+
+impl FpElt {
+    fn sqrt_with(&self, pre: &SqrtPrecmp) -> FpElt {
         match pre {
-            SqrtPrecmp::P3MOD4 { exp } => self ^ &exp,
+            SqrtPrecmp::P3MOD4 { exp } => self.pow(exp),
             SqrtPrecmp::P5MOD8 {
                 exp,
                 sqrt_minus_one,
             } => {
-                let t0 = self ^ &exp;
+                let t0 = self.pow(exp);
                 let t1 = &t0 ^ 2u32;
                 let e = *self == t1;
-                let t1 = &t0 * sqrt_minus_one;
+                let t1 = t0.red(&t0.n * sqrt_minus_one);
                 FpElt::cmov(&t1, &t0, e)
             }
-            SqrtPrecmp::P9MOD16 => unimplemented!(),
-            SqrtPrecmp::P1MOD16 => unimplemented!(),
+            SqrtPrecmp::P1MOD8 { c1, c3, c6 } => {
+                let mut z = self.pow(c3);
+                let mut t = &z * &z * self;
+                z *= self;
+                let mut b = t.clone();
+                let mut c = self.red(c6.clone());
+                for i in (2..=*c1).rev() {
+                    for _ in 1..=(i - 2) {
+                        b = &b * &b;
+                    }
+                    let e = b.is_one();
+                    z = FpElt::cmov(&(&z * &c), &z, e);
+                    c = &c * &c;
+                    t = FpElt::cmov(&(&t * &c), &t, e);
+                    b = t.clone();
+                }
+                z
+            }
         }
     }
 }
@@ -352,6 +558,13 @@ impl std::fmt::Display for FpElt {
     }
 }
 
+impl std::fmt::LowerHex for FpElt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let x = self.n.mod_floor(&self.f.0.p);
+        std::fmt::LowerHex::fmt(&x, f)
+    }
+}
+
 impl std::fmt::Display for Fp {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "GF({})", &self.0.p)
@@ -359,6 +572,162 @@ impl std::fmt::Display for Fp {
 }
 
 const ERR_BIN_OP: &str = "elements of different fields";
+const ERR_SIZE: &str = "wrong length of encoded field element";
+const ERR_NON_CANONICAL: &str = "field element is not reduced modulo p";
 const ERR_EXP_SQR_OP: &str = "exponent must be 2u32";
 const ERR_EXP_INV_OP: &str = "exponent must be -1i32";
 const ERR_INV_OP: &str = "numerator must be 1u32";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, BigUint, Sign};
+    use sha2::{Digest, Sha256};
+    use subtle::{Choice, ConstantTimeEq};
+
+    use super::{Fp, FpElt};
+    use crate::field::{Field, Sqrt};
+
+    #[test]
+    fn sqrt() {
+        // one prime for each method: 3 mod 4, 5 mod 8, 9 mod 16, and 1 mod 16
+        for p in [103u32, 101, 41, 97, 7681].iter() {
+            let f = Fp::new(BigUint::from(*p));
+            for x in 0..*p {
+                let x = f.elt(BigInt::from(x));
+                let xx = &x * &x;
+                let r = xx.sqrt();
+                assert!(&r * &r == xx, "p: {} x: {}", p, x);
+            }
+        }
+        // the modulus of the scalar field of BLS12-381 has 2-adicity 32
+        let r: BigUint =
+            "52435875175126190479447740508185965837690552500527637822603658699938581184513"
+                .parse()
+                .unwrap();
+        let f = Fp::new(r);
+        let x = &f.elt(BigInt::from(123_456_789)) ^ &BigInt::from(7);
+        let xx = &x * &x;
+        let r = xx.sqrt();
+        assert!(&r * &r == xx);
+        assert!(!(&xx * &f.elt(BigInt::from(7))).is_square());
+    }
+
+    #[test]
+    fn exponents() {
+        for p in [103u32, 101, 97].iter() {
+            let f = Fp::new(BigUint::from(*p));
+            let squares: Vec<_> = (1..*p).map(|x| (x * x) % p).collect();
+            for x in 1..*p {
+                let x = f.elt(BigInt::from(x));
+                assert!(&x * (1u32 / &x) == f.one(), "p: {} x: {}", p, x);
+                assert!(&x ^ &BigInt::from(-1) == &x ^ -1i32);
+                assert!(&x ^ &BigInt::from(p - 1) == f.one());
+                let sq = squares.iter().any(|s| f.elt(BigInt::from(*s)) == x);
+                assert_eq!(x.is_square(), sq, "p: {} x: {}", p, x);
+            }
+        }
+    }
+
+    #[test]
+    fn batch_sqrt() {
+        for p in [103u32, 101, 97].iter() {
+            let f = Fp::new(BigUint::from(*p));
+            let a: Vec<_> = (0..*p).map(|x| f.elt(BigInt::from(x))).collect();
+            for (x, r) in a.iter().zip(f.batch_sqrt(&a)) {
+                match r {
+                    Some(r) => assert!(&r * &r == *x, "p: {} x: {}", p, x),
+                    None => assert!(!x.is_square(), "p: {} x: {}", p, x),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_encoding() {
+        let f = Fp::new(BigUint::from(0x1_0003u32));
+        assert_eq!(f.size_bytes(), 3);
+        let x = f.from_canonical_be(&[0x01, 0x00, 0x02]).unwrap();
+        assert!(x == f.elt(BigInt::from(0x1_0002)));
+        let y = f.from_canonical_le(&[0x02, 0x00, 0x01]).unwrap();
+        assert!(x == y);
+        // p and p+1 are not reduced
+        assert!(f.from_canonical_be(&[0x01, 0x00, 0x03]).is_err());
+        assert!(f.from_canonical_le(&[0x04, 0x00, 0x01]).is_err());
+        // too short or too long, even if the value is in range
+        assert!(f.from_canonical_be(&[0x00, 0x02]).is_err());
+        assert!(f.from_canonical_be(&[0x00, 0x00, 0x00, 0x02]).is_err());
+        assert!(f.from_canonical_le(&[0x02, 0x00, 0x00, 0x00]).is_err());
+    }
+
+    #[test]
+    fn from_hash() {
+        // a single SHA-256 digest covers a 2-byte modulus plus 16 bytes
+        let f = Fp::new(BigUint::from(0x1_0003u32));
+        let h = Sha256::digest(b"abc");
+        let want = f.elt(BigInt::from_bytes_be(Sign::Plus, &h));
+        assert!(FpElt::from_hash::<Sha256>(b"abc", &f) == want);
+        // the 66-byte modulus of P-521 needs 82 bytes, i.e., three digests
+        let f = Fp::new((BigUint::from(1u32) << 521usize) - 1u32);
+        let wide: Vec<u8> = [
+            Sha256::digest(b"abc").to_vec(),
+            Sha256::new()
+                .chain_update(b"abc")
+                .chain_update(1u32.to_be_bytes())
+                .finalize()
+                .to_vec(),
+            Sha256::new()
+                .chain_update(b"abc")
+                .chain_update(2u32.to_be_bytes())
+                .finalize()
+                .to_vec(),
+        ]
+        .concat();
+        let want = f.elt(BigInt::from_bytes_be(Sign::Plus, &wide));
+        assert!(FpElt::from_hash::<Sha256>(b"abc", &f) == want);
+        assert!(FpElt::from_hash::<Sha256>(b"abd", &f) != want);
+    }
+
+    #[test]
+    fn conditional_negate() {
+        let f = Fp::new(BigUint::from(0x1_0003u32));
+        let x = f.elt(BigInt::from(0x1234));
+        let mut y = x.clone();
+        y.conditional_negate(Choice::from(0));
+        assert!(y == x);
+        y.conditional_negate(Choice::from(1));
+        assert!(y == -&x);
+        let z = FpElt::ct_select(&x, &y, Choice::from(1));
+        assert!(z == y);
+        assert!(FpElt::ct_select(&x, &y, Choice::from(0)) == x);
+    }
+
+    #[test]
+    fn constant_time_eq() {
+        let f = Fp::new(BigUint::from(103u32));
+        let g = Fp::new(BigUint::from(101u32));
+        let x = f.elt(BigInt::from(5));
+        assert!(bool::from(x.ct_eq(&f.elt(BigInt::from(108)))));
+        assert!(!bool::from(x.ct_eq(&f.elt(BigInt::from(6)))));
+        assert!(!bool::from(x.ct_eq(&g.elt(BigInt::from(5)))));
+    }
+
+    #[test]
+    fn assign_ops() {
+        let f = Fp::new(BigUint::from(103u32));
+        for (x, y) in [(0, 0), (5, 99), (99, 5), (102, 102), (50, 53)].iter() {
+            let (x, y) = (f.elt(BigInt::from(*x)), f.elt(BigInt::from(*y)));
+            let mut z = x.clone();
+            z += &y;
+            assert!(z == &x + &y);
+            z -= y.clone();
+            assert!(z == x);
+            z -= &y;
+            assert!(z == &x - &y);
+            z *= &y;
+            assert!(z == (&x - &y) * &y);
+            z.square_assign();
+            assert!(z == &((&x - &y) * &y) ^ 2u32);
+            assert!(x.mul_add(&y, &z) == &x * &y + &z);
+        }
+    }
+}
@@ -6,6 +6,7 @@ use atomic_refcell::AtomicRefCell;
 use num_bigint::{BigInt, BigUint};
 use num_traits::cast::ToPrimitive;
 use num_traits::identities::{One, Zero};
+use subtle::{Choice, ConstantTimeEq};
 
 use std::ops;
 use std::ops::{BitXor, Div};
@@ -13,6 +14,8 @@ use std::sync::Arc;
 
 use crate::do_if_eq;
 use crate::field::{CMov, Field, FieldElement, Sgn0, Sqrt};
+use crate::h2c::{get_length, Expander, HashToField};
+use crate::integer::Integer;
 use crate::ops::{Deserialize, FromFactory, Serialize};
 use crate::primefield::{Fp, FpElt};
 
@@ -46,6 +49,17 @@ impl Fp2 {
         let sqrt_precmp = AtomicRefCell::new(None);
         Fp2(Arc::new(Params { base, sqrt_precmp }))
     }
+    /// Returns the element `n0 + n1*i`.
+    pub fn new_elt(&self, n0: FpElt, n1: FpElt) -> Fp2Elt {
+        Fp2Elt {
+            n: vec![n0, n1],
+            f: self.clone(),
+        }
+    }
+    /// Returns the base field GF(p).
+    pub fn get_base(&self) -> Fp {
+        self.0.base.clone()
+    }
 }
 
 impl Field for Fp2 {
@@ -75,7 +89,7 @@ impl Deserialize for Fp2 {
     fn from_bytes_be(&self, bytes: &[u8]) -> Result<Self::Deser, std::io::Error> {
         let len = self.size_bytes();
         if len != bytes.len() {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "wrong size"));
+            return Err(std::io::Error::other("wrong size"));
         }
         let size = len / 2;
         let n0 = self.0.base.from_bytes_be(&bytes[0..size]).unwrap();
@@ -88,7 +102,7 @@ impl Deserialize for Fp2 {
     fn from_bytes_le(&self, bytes: &[u8]) -> Result<Self::Deser, std::io::Error> {
         let len = self.size_bytes();
         if len != bytes.len() {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "wrong size"));
+            return Err(std::io::Error::other("wrong size"));
         }
         let size = len / 2;
         let n0 = self.0.base.from_bytes_le(&bytes[0..size]).unwrap();
@@ -100,6 +114,29 @@ impl Deserialize for Fp2 {
     }
 }
 
+impl HashToField for Fp2 {
+    fn hash_to_field(
+        &self,
+        exp: &dyn Expander,
+        msg: &[u8],
+        count: usize,
+        k: usize,
+    ) -> Vec<Self::Elt> {
+        let l = get_length(&self.0.base, k);
+        let bytes = exp.expand(msg, count * 2 * l);
+        bytes
+            .chunks(2 * l)
+            .map(|tv| Fp2Elt {
+                n: vec![
+                    self.0.base.from_bytes_be(&tv[..l]).unwrap(),
+                    self.0.base.from_bytes_be(&tv[l..]).unwrap(),
+                ],
+                f: self.clone(),
+            })
+            .collect()
+    }
+}
+
 macro_rules! impl_from_factory {
     ($target:ident, <$($other:ty)+> ) => {
      $(
@@ -115,6 +152,16 @@ macro_rules! impl_from_factory {
 
 impl_from_factory!(Fp2, <u8 u16 u32 u64 i8 i16 i32 i64>);
 
+impl FromFactory<[Integer; 2]> for Fp2 {
+    type Output = <Fp2 as Field>::Elt;
+    fn from(&self, [n0, n1]: [Integer; 2]) -> Self::Output {
+        Fp2Elt {
+            n: vec![self.0.base.from(n0), self.0.base.from(n1)],
+            f: self.clone(),
+        }
+    }
+}
+
 impl FromFactory<&str> for Fp2 {
     type Output = <Fp2 as Field>::Elt;
     fn from(&self, s: &str) -> Self::Output {
@@ -128,7 +175,8 @@ impl FromFactory<&str> for Fp2 {
     }
 }
 
-/// Fp2Elt is an element of a prime field.
+/// Fp2Elt is an element of a prime field. Comparisons with `==` take
+/// variable time; use `ct_eq` for secret elements.
 #[derive(Clone, PartialEq, Eq)]
 pub struct Fp2Elt {
     n: Vec<FpElt>,
@@ -137,6 +185,13 @@ pub struct Fp2Elt {
 
 impl FieldElement for Fp2Elt {}
 
+impl ConstantTimeEq for Fp2Elt {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let same_field = Choice::from((self.f == other.f) as u8);
+        same_field & self.n[0].ct_eq(&other.n[0]) & self.n[1].ct_eq(&other.n[1])
+    }
+}
+
 impl Serialize for Fp2Elt {
     /// serializes the field element into big-endian bytes
     fn to_bytes_be(&self) -> Vec<u8> {
@@ -156,7 +211,7 @@ impl Serialize for Fp2Elt {
     }
 }
 
-impl<'a, 'b> std::ops::Add<&'b Fp2Elt> for &'a Fp2Elt {
+impl<'b> std::ops::Add<&'b Fp2Elt> for &Fp2Elt {
     type Output = Fp2Elt;
     fn add(self, other: &'b Fp2Elt) -> Fp2Elt {
         do_if_eq!(
@@ -166,7 +221,7 @@ impl<'a, 'b> std::ops::Add<&'b Fp2Elt> for &'a Fp2Elt {
         )
     }
 }
-impl<'a> std::ops::Add<Fp2Elt> for &'a Fp2Elt {
+impl std::ops::Add<Fp2Elt> for &Fp2Elt {
     type Output = Fp2Elt;
     fn add(self, other: Fp2Elt) -> Fp2Elt {
         do_if_eq!(
@@ -178,6 +233,23 @@ impl<'a> std::ops::Add<Fp2Elt> for &'a Fp2Elt {
 }
 
 impl Fp2Elt {
+    /// Returns the coefficient `n0` of `n0 + n1*i`.
+    pub fn c0(&self) -> &FpElt {
+        &self.n[0]
+    }
+    /// Returns the coefficient `n1` of `n0 + n1*i`.
+    pub fn c1(&self) -> &FpElt {
+        &self.n[1]
+    }
+    /// Returns the conjugate `n0 - n1*i`, which is also the image of the
+    /// Frobenius map `x -> x^p`.
+    pub fn conjugate(&self) -> Fp2Elt {
+        self.elt(self.n[0].clone(), -&self.n[1])
+    }
+    /// Returns the product by an element of the base field.
+    pub fn mul_base(&self, k: &FpElt) -> Fp2Elt {
+        self.elt(&self.n[0] * k, &self.n[1] * k)
+    }
     #[inline]
     fn elt(&self, n0: FpElt, n1: FpElt) -> Fp2Elt {
         let n = vec![n0, n1];
@@ -189,7 +261,7 @@ impl Fp2Elt {
         let n0 = &self.n[0];
         let n1 = &self.n[1];
         let den = 1u32 / &(n0 * n0 + n1 * n1);
-        self.elt(&den * n0, den * n1)
+        self.elt(&den * n0, -(den * n1))
     }
 }
 
@@ -227,7 +299,7 @@ impl_op_ex!(^|a: &Fp2Elt, b: i32| -> Fp2Elt {
     do_if_eq!(b == -1i32, a.inv_mod(), ERR_EXP_INV_OP)
 });
 
-impl<'a> Div<&'a Fp2Elt> for u32 {
+impl Div<&Fp2Elt> for u32 {
     type Output = Fp2Elt;
     #[inline]
     fn div(self, other: &Fp2Elt) -> Self::Output {
@@ -235,14 +307,14 @@ impl<'a> Div<&'a Fp2Elt> for u32 {
     }
 }
 
-impl<'a, 'b> BitXor<&'b BigUint> for &'a Fp2Elt {
+impl<'b> BitXor<&'b BigUint> for &Fp2Elt {
     type Output = Fp2Elt;
     #[inline]
     fn bitxor(self, exp: &'b BigUint) -> Self::Output {
         let v = exp.to_u32_digits();
-        let mut out = Fp2Elt::one();
+        let mut out = self.f.one();
         for vi in v.iter().rev() {
-            for j in (0..31).rev() {
+            for j in (0..32).rev() {
                 out = &out * &out;
                 let bit = (*vi >> j) & 1;
                 if bit == 1 {
@@ -254,7 +326,7 @@ impl<'a, 'b> BitXor<&'b BigUint> for &'a Fp2Elt {
     }
 }
 
-impl<'a, 'b> BitXor<&'b BigInt> for &'a Fp2Elt {
+impl<'b> BitXor<&'b BigInt> for &Fp2Elt {
     type Output = Fp2Elt;
     #[inline]
     fn bitxor(self, exp: &'b BigInt) -> Self::Output {
@@ -324,10 +396,15 @@ impl Sqrt for Fp2Elt {
 
 impl Sgn0 for Fp2Elt {
     fn sgn0(&self) -> i32 {
-        let s0 = self.n[0].sgn0();
-        let z0 = self.n[0].is_zero() as i32;
-        let s1 = self.n[1].sgn0();
-        s0 | (z0 ^ s1)
+        // sgn0 of GF(p) elements is -1 for odd elements
+        let s0 = self.n[0].sgn0() < 0;
+        let z0 = self.n[0].is_zero();
+        let s1 = self.n[1].sgn0() < 0;
+        if s0 || (z0 && s1) {
+            -1
+        } else {
+            1
+        }
     }
 }
 
@@ -363,6 +440,14 @@ impl std::fmt::Display for Fp2Elt {
     }
 }
 
+impl std::fmt::LowerHex for Fp2Elt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::LowerHex::fmt(&self.n[0], f)?;
+        f.write_str("+i*")?;
+        std::fmt::LowerHex::fmt(&self.n[1], f)
+    }
+}
+
 impl std::fmt::Display for Fp2 {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}^2", &self.0.base)
@@ -0,0 +1,363 @@
+//! This is documentation for the `ring` module.
+//!
+//! The ring module implements ring signatures over any prime-order group:
+//! a signature proves that the message was signed by the owner of one of
+//! the public keys of a ring, without revealing which one.
+//!
+//! Plain signatures follow the spontaneous anonymous group (SAG) scheme.
+//! Linkable signatures follow the LSAG scheme of Liu, Wei and Wong, and
+//! carry the key image `I = [x]H(P)` of the signer, which is the same for
+//! all signatures produced with the secret key `x`. Hence, two linkable
+//! signatures by the same signer are detected, as needed for voting,
+//! although the signer remains anonymous.
+//!
+//! # Signing as a Member of a Ring
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::group::{PrimeGroup, Ristretto255};
+//!  use redox_ecc::ring::{linked, Ring};
+//!
+//!  let g = Ristretto255::new();
+//!  let x: Vec<_> = (1..=3).map(|i| g.new_scalar((10 * i).into())).collect();
+//!  let keys: Vec<_> = x.iter().map(|x| g.generator() * x).collect();
+//!  let ring = Ring::new(g, &keys);
+//!  let sig0 = ring.sign_linkable(b"vote 0", 1, &x[1], &mut OsRng).unwrap();
+//!  let sig1 = ring.sign_linkable(b"vote 1", 1, &x[1], &mut OsRng).unwrap();
+//!  assert!(ring.verify(b"vote 0", &sig0).is_ok());
+//!  assert!(ring.verify(b"vote 1", &sig1).is_ok());
+//!  assert!(linked(ring.group(), &sig0, &sig1));
+//! ```
+
+use num_bigint::BigInt;
+use rand_core::{CryptoRng, RngCore};
+
+use std::io::Error;
+
+use crate::group::PrimeGroup;
+use crate::h2c::Dst;
+
+/// RingSignature is the initial challenge `c_0` and one response per member
+/// of the ring. The key image is present only in linkable signatures.
+pub struct RingSignature<G: PrimeGroup> {
+    pub c: G::Scalar,
+    pub r: Vec<G::Scalar>,
+    pub key_image: Option<G::Elt>,
+}
+
+impl<G: PrimeGroup> RingSignature<G> {
+    /// Parses a signature for a ring of `n` members, which is linkable if
+    /// an element follows the `n + 1` scalars.
+    pub fn from_bytes(g: &G, n: usize, buf: &[u8]) -> Result<RingSignature<G>, Error> {
+        let size = g.encode_scalar(&g.new_scalar(BigInt::from(0))).len();
+        if buf.len() < (n + 1) * size {
+            return Err(Error::other(ERR_SIG_LEN));
+        }
+        let (scalars, rest) = buf.split_at((n + 1) * size);
+        let mut scalars = scalars
+            .chunks(size)
+            .map(|k| g.decode_scalar(k))
+            .collect::<Result<Vec<G::Scalar>, Error>>()?;
+        let c = scalars.remove(0);
+        let key_image = if rest.is_empty() {
+            None
+        } else {
+            Some(g.decode(rest)?)
+        };
+        Ok(RingSignature {
+            c,
+            r: scalars,
+            key_image,
+        })
+    }
+    pub fn to_bytes(&self, g: &G) -> Vec<u8> {
+        let mut out = g.encode_scalar(&self.c);
+        for r in self.r.iter() {
+            out.append(&mut g.encode_scalar(r));
+        }
+        if let Some(i) = &self.key_image {
+            out.append(&mut g.encode(i));
+        }
+        out
+    }
+}
+
+impl<G: PrimeGroup> Clone for RingSignature<G> {
+    fn clone(&self) -> Self {
+        RingSignature {
+            c: self.c.clone(),
+            r: self.r.clone(),
+            key_image: self.key_image.clone(),
+        }
+    }
+}
+
+/// Ring is a list of public keys on whose behalf signatures are produced,
+/// together with the hashes of the keys used by linkable signatures.
+#[derive(Clone)]
+pub struct Ring<G: PrimeGroup> {
+    g: G,
+    keys: Vec<G::Elt>,
+    hashes: Vec<G::Elt>,
+    prefix: Vec<u8>,
+}
+
+impl<G: PrimeGroup> Ring<G> {
+    pub fn new(g: G, keys: &[G::Elt]) -> Ring<G> {
+        let mut prefix = (keys.len() as u64).to_be_bytes().to_vec();
+        for p in keys.iter() {
+            prefix.append(&mut g.encode(p));
+        }
+        let hashes = keys.iter().map(|p| hash_point(&g, p)).collect();
+        Ring {
+            g,
+            keys: keys.to_vec(),
+            hashes,
+            prefix,
+        }
+    }
+    pub fn group(&self) -> &G {
+        &self.g
+    }
+    pub fn keys(&self) -> &[G::Elt] {
+        &self.keys
+    }
+    /// Signs `msg` as the member at position `index`, whose secret key is
+    /// `x`. It fails if `x` is not the secret key of that member.
+    // based on Abe, Ohkubo, Suzuki, "1-out-of-n Signatures from a Variety of Keys", ASIACRYPT 2002
+    pub fn sign<R: RngCore + CryptoRng>(
+        &self,
+        msg: &[u8],
+        index: usize,
+        x: &G::Scalar,
+        rng: &mut R,
+    ) -> Result<RingSignature<G>, Error> {
+        self.sign_with(msg, index, x, false, rng)
+    }
+    /// Signs `msg` as the member at position `index`, whose secret key is
+    /// `x`, attaching its key image. It fails if `x` is not the secret key
+    /// of that member.
+    // based on Liu, Wei, Wong, "Linkable Spontaneous Anonymous Group Signature for Ad Hoc Groups", ACISP 2004
+    pub fn sign_linkable<R: RngCore + CryptoRng>(
+        &self,
+        msg: &[u8],
+        index: usize,
+        x: &G::Scalar,
+        rng: &mut R,
+    ) -> Result<RingSignature<G>, Error> {
+        self.sign_with(msg, index, x, true, rng)
+    }
+    /// Verifies a plain or linkable signature of `msg` by a member of the
+    /// ring.
+    pub fn verify(&self, msg: &[u8], sig: &RingSignature<G>) -> Result<(), Error> {
+        let g = &self.g;
+        let n = self.keys.len();
+        if sig.r.len() != n {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        if let Some(i) = &sig.key_image {
+            if *i == g.identity() {
+                return Err(Error::other(ERR_SIG_INVALID));
+            }
+        }
+        let image = sig.key_image.as_ref();
+        let mut c = sig.c.clone();
+        for (j, r) in sig.r.iter().enumerate() {
+            let (l, rr) = self.commitments(r, &c, j, image);
+            c = self.challenge(msg, image, &l, rr.as_ref());
+        }
+        if g.encode_scalar(&c) != g.encode_scalar(&sig.c) {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        Ok(())
+    }
+    fn sign_with<R: RngCore + CryptoRng>(
+        &self,
+        msg: &[u8],
+        index: usize,
+        x: &G::Scalar,
+        linkable: bool,
+        rng: &mut R,
+    ) -> Result<RingSignature<G>, Error> {
+        let g = &self.g;
+        let n = self.keys.len();
+        if index >= n || g.generator() * x != self.keys[index] {
+            return Err(Error::other(ERR_NOT_MEMBER));
+        }
+        let image = linkable.then(|| self.hashes[index].clone() * x);
+        let mut r: Vec<G::Scalar> = (0..n).map(|_| self.g.random_scalar(rng)).collect();
+        let mut cs = vec![None; n];
+        let alpha = self.g.random_scalar(rng);
+        let l = g.generator() * &alpha;
+        let rr = linkable.then(|| self.hashes[index].clone() * &alpha);
+        let mut j = (index + 1) % n;
+        let mut c = self.challenge(msg, image.as_ref(), &l, rr.as_ref());
+        while j != index {
+            cs[j] = Some(c.clone());
+            let (l, rr) = self.commitments(&r[j], &c, j, image.as_ref());
+            c = self.challenge(msg, image.as_ref(), &l, rr.as_ref());
+            j = (j + 1) % n;
+        }
+        r[index] = alpha - c.clone() * x;
+        cs[index] = Some(c);
+        Ok(RingSignature {
+            c: cs[0].take().unwrap(),
+            r,
+            key_image: image,
+        })
+    }
+    /// Returns `[r]G + [c]P_j`, and `[r]H(P_j) + [c]I` for linkable
+    /// signatures.
+    fn commitments(
+        &self,
+        r: &G::Scalar,
+        c: &G::Scalar,
+        j: usize,
+        image: Option<&G::Elt>,
+    ) -> (G::Elt, Option<G::Elt>) {
+        let g = &self.g;
+        let l = g.multi_scalar_mul(
+            &[g.generator(), self.keys[j].clone()],
+            &[r.clone(), c.clone()],
+        );
+        let rr = image.map(|i| {
+            g.multi_scalar_mul(
+                &[self.hashes[j].clone(), i.clone()],
+                &[r.clone(), c.clone()],
+            )
+        });
+        (l, rr)
+    }
+    fn challenge(
+        &self,
+        msg: &[u8],
+        image: Option<&G::Elt>,
+        l: &G::Elt,
+        r: Option<&G::Elt>,
+    ) -> G::Scalar {
+        let g = &self.g;
+        let mut input = self.prefix.clone();
+        if let Some(i) = image {
+            input.append(&mut g.encode(i));
+        }
+        input.extend_from_slice(&(msg.len() as u64).to_be_bytes());
+        input.extend_from_slice(msg);
+        input.append(&mut g.encode(l));
+        if let Some(r) = r {
+            input.append(&mut g.encode(r));
+        }
+        g.hash_to_scalar(&input, &Dst::new(CHALLENGE_DST).unwrap())
+    }
+}
+
+/// Returns the key image `[x]H(P)` of the secret key `x`, where `P = [x]G`.
+pub fn key_image<G: PrimeGroup>(g: &G, x: &G::Scalar) -> G::Elt {
+    hash_point(g, &(g.generator() * x)) * x
+}
+
+/// Determines whether two linkable signatures were produced with the same
+/// secret key. Plain signatures are never linked.
+pub fn linked<G: PrimeGroup>(g: &G, a: &RingSignature<G>, b: &RingSignature<G>) -> bool {
+    match (&a.key_image, &b.key_image) {
+        (Some(i), Some(j)) => g.encode(i) == g.encode(j),
+        _ => false,
+    }
+}
+
+fn hash_point<G: PrimeGroup>(g: &G, p: &G::Elt) -> G::Elt {
+    g.hash_to_group(&g.encode(p), &Dst::new(KEY_IMAGE_DST).unwrap())
+}
+
+const CHALLENGE_DST: &[u8] = b"REDOX-ECC-RING-V01";
+const KEY_IMAGE_DST: &[u8] = b"REDOX-ECC-RING-KEY-IMAGE-V01";
+const ERR_SIG_LEN: &str = "signature is too short";
+const ERR_SIG_INVALID: &str = "invalid ring signature";
+const ERR_NOT_MEMBER: &str = "secret key does not match the member of the ring";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand_core::OsRng;
+
+    use super::{key_image, linked, Ring, RingSignature};
+    use crate::group::{PrimeGroup, Ristretto255, WeGroup};
+    use crate::h2c::P256_XMDSHA256_SSWU_RO_;
+
+    fn keys<G: PrimeGroup>(g: &G, n: i64) -> (Vec<G::Scalar>, Vec<G::Elt>) {
+        let x: Vec<G::Scalar> = (1..=n)
+            .map(|i| g.new_scalar(BigInt::from(1000 + i)))
+            .collect();
+        let keys = x.iter().map(|x| g.generator() * x).collect();
+        (x, keys)
+    }
+
+    fn check_sag<G: PrimeGroup + Clone>(g: G, n: usize) {
+        let (x, keys) = keys(&g, n as i64);
+        let ring = Ring::new(g.clone(), &keys);
+        for (j, xj) in x.iter().enumerate() {
+            let sig = ring.sign(b"message", j, xj, &mut OsRng).unwrap();
+            assert!(sig.key_image.is_none());
+            assert!(ring.verify(b"message", &sig).is_ok());
+        }
+        let sig = ring.sign(b"message", 1, &x[1], &mut OsRng).unwrap();
+        let sig = RingSignature::from_bytes(&g, n, &sig.to_bytes(&g)).unwrap();
+        assert!(ring.verify(b"other", &sig).is_err());
+        assert!(ring.sign(b"message", 0, &x[1], &mut OsRng).is_err());
+        assert!(ring.sign(b"message", n, &x[1], &mut OsRng).is_err());
+    }
+
+    fn check_lsag<G: PrimeGroup + Clone>(g: G) {
+        let (x, keys) = keys(&g, 4);
+        let ring = Ring::new(g.clone(), &keys[..3]);
+        let sig0 = ring
+            .sign_linkable(b"message", 2, &x[2], &mut OsRng)
+            .unwrap();
+        let sig0 = RingSignature::from_bytes(&g, 3, &sig0.to_bytes(&g)).unwrap();
+        assert!(ring.verify(b"message", &sig0).is_ok());
+        assert!(g.encode(sig0.key_image.as_ref().unwrap()) == g.encode(&key_image(&g, &x[2])));
+
+        // the key image can be neither removed nor replaced
+        let mut forged = sig0.clone();
+        forged.key_image = None;
+        assert!(ring.verify(b"message", &forged).is_err());
+        forged.key_image = Some(key_image(&g, &x[1]));
+        assert!(ring.verify(b"message", &forged).is_err());
+        forged.key_image = Some(g.identity());
+        assert!(ring.verify(b"message", &forged).is_err());
+
+        // the same signer is linked across messages and rings
+        let other = Ring::new(g.clone(), &[keys[3].clone(), keys[2].clone()]);
+        let sig1 = other
+            .sign_linkable(b"another", 1, &x[2], &mut OsRng)
+            .unwrap();
+        assert!(other.verify(b"another", &sig1).is_ok());
+        assert!(linked(&g, &sig0, &sig1));
+        let sig2 = other
+            .sign_linkable(b"another", 0, &x[3], &mut OsRng)
+            .unwrap();
+        assert!(!linked(&g, &sig1, &sig2));
+        let mut plain = sig1.clone();
+        plain.key_image = None;
+        assert!(!linked(&g, &sig1, &plain));
+    }
+
+    #[test]
+    fn sag() {
+        check_sag(Ristretto255::new(), 3);
+        check_sag(WeGroup::new(P256_XMDSHA256_SSWU_RO_), 2);
+    }
+
+    #[test]
+    fn lsag() {
+        check_lsag(Ristretto255::new());
+        // a ring of one member over P-256
+        let g = WeGroup::new(P256_XMDSHA256_SSWU_RO_);
+        let (x, keys) = keys(&g, 1);
+        let ring = Ring::new(g, &keys);
+        let sig = ring
+            .sign_linkable(b"message", 0, &x[0], &mut OsRng)
+            .unwrap();
+        assert!(ring.verify(b"message", &sig).is_ok());
+        assert!(ring.verify(b"other", &sig).is_err());
+    }
+}
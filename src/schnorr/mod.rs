@@ -0,0 +1,443 @@
+//! This is documentation for the `schnorr` module.
+//!
+//! The schnorr module implements the Schnorr signatures over secp256k1
+//! specified in BIP-340, which are used by Bitcoin's Taproot outputs.
+//!
+//! # Signing and Verifying
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::schnorr::SigningKey;
+//!
+//!  let sk = SigningKey::random(&mut OsRng);
+//!  let sig = sk.sign(b"message", &mut OsRng);
+//!  assert!(sk.verifying_key().verify(b"message", &sig).is_ok());
+//! ```
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use std::io::Error;
+
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode, PointFormat};
+use crate::field::Field;
+use crate::instances::{GetCurve, SECP256K1};
+use crate::ops::{Endianness, Serialize};
+use crate::weierstrass::{Curve, Point, Scalar};
+
+/// Returns the tagged hash `SHA256(SHA256(tag) || SHA256(tag) || x)` of
+/// BIP-340, where `x` is the concatenation of `parts`.
+pub fn tagged_hash(tag: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    let t = Sha256::digest(tag);
+    let mut h = Sha256::new();
+    h.update(t);
+    h.update(t);
+    for p in parts {
+        h.update(p);
+    }
+    h.finalize().to_vec()
+}
+
+/// Signature is a BIP-340 signature, i.e., the x-coordinate of the point `R`
+/// followed by the integer `s`, both encoded in 32 bytes.
+#[derive(Clone, PartialEq)]
+pub struct Signature(Vec<u8>);
+
+impl Signature {
+    /// Parses a signature. Only the length is checked here, the range of
+    /// both halves is checked during verification.
+    pub fn from_bytes(buf: &[u8]) -> Result<Signature, Error> {
+        if buf.len() != 2 * SIZE {
+            return Err(Error::other(ERR_SIG_LEN));
+        }
+        Ok(Signature(buf.to_vec()))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+}
+
+/// SigningKey is a BIP-340 secret key together with its x-only public key.
+#[derive(Clone)]
+pub struct SigningKey {
+    d: Scalar,
+    vk: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Parses a 32-byte secret key. It fails if the secret is zero or is not
+    /// less than the order of secp256k1.
+    pub fn from_bytes(buf: &[u8]) -> Result<SigningKey, Error> {
+        let e = SECP256K1.get();
+        let d = BigUint::from_bytes_be(buf);
+        if buf.len() != SIZE || d.is_zero() || d >= e.get_order() {
+            return Err(Error::other(ERR_KEY_RANGE));
+        }
+        let d = e.new_scalar(d.into());
+        let p = e.get_generator() * &d;
+        let vk = VerifyingKey::new(&e, p);
+        Ok(SigningKey { d, vk })
+    }
+    /// Returns a signing key sampled uniformly at random.
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> SigningKey {
+        let mut buf = [0u8; SIZE];
+        loop {
+            rng.fill_bytes(&mut buf);
+            if let Ok(sk) = SigningKey::from_bytes(&buf) {
+                return sk;
+            }
+        }
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.d.to_bytes_be()
+    }
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.vk
+    }
+    /// Signs `msg` using 32 bytes sampled at random as auxiliary data, as
+    /// recommended by BIP-340.
+    pub fn sign<R: RngCore + CryptoRng>(&self, msg: &[u8], rng: &mut R) -> Signature {
+        let mut aux = [0u8; SIZE];
+        loop {
+            rng.fill_bytes(&mut aux);
+            if let Ok(sig) = self.sign_with_aux(msg, &aux) {
+                return sig;
+            }
+        }
+    }
+    /// Signs `msg` deriving the nonce from the secret key, the message and
+    /// the auxiliary data `aux`. It fails if the derived nonce is zero, in
+    /// which case other auxiliary data must be used.
+    // based on https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki#default-signing
+    pub fn sign_with_aux(&self, msg: &[u8], aux: &[u8; SIZE]) -> Result<Signature, Error> {
+        let e = &self.vk.e;
+        let d = if self.vk.negated {
+            -&self.d
+        } else {
+            self.d.clone()
+        };
+        let mask = tagged_hash(TAG_AUX, &[aux]);
+        let t: Vec<u8> = d
+            .to_bytes_be()
+            .iter()
+            .zip(mask)
+            .map(|(a, b)| a ^ b)
+            .collect();
+        let rand = tagged_hash(TAG_NONCE, &[&t, &self.vk.enc, msg]);
+        let k = e.new_scalar_from_bytes(&rand, Endianness::Big);
+        if k == e.new_scalar(BigInt::zero()) {
+            return Err(Error::other(ERR_NONCE));
+        }
+        let r = e.get_generator() * &k;
+        let k = if has_even_y(&r) { k } else { -k };
+        let enc_r = x_only(&r);
+        let c = challenge(e, &enc_r, &self.vk.enc, msg);
+        let s = k + c * d;
+        let mut out = enc_r;
+        out.append(&mut s.to_bytes_be());
+        Ok(Signature(out))
+    }
+}
+
+/// VerifyingKey is a BIP-340 public key, i.e., the point with even
+/// y-coordinate among the two points having a given x-coordinate.
+#[derive(Clone)]
+pub struct VerifyingKey {
+    e: Curve,
+    p: Point,
+    enc: Vec<u8>,
+    negated: bool,
+}
+
+impl VerifyingKey {
+    /// Returns the verifying key for the public point `p`, which is negated
+    /// if its y-coordinate is odd.
+    pub(crate) fn new(e: &Curve, p: Point) -> VerifyingKey {
+        let negated = !has_even_y(&p);
+        let p = if negated { -p } else { p };
+        let enc = x_only(&p);
+        VerifyingKey {
+            e: e.clone(),
+            p,
+            enc,
+            negated,
+        }
+    }
+    /// Parses an x-only public key. It fails if the input is not the
+    /// x-coordinate of a point of secp256k1.
+    pub fn from_bytes(buf: &[u8]) -> Result<VerifyingKey, Error> {
+        let e = SECP256K1.get();
+        let p = lift_x(&e, buf).map_err(|_| Error::other(ERR_KEY_POINT))?;
+        Ok(VerifyingKey {
+            e,
+            p,
+            enc: buf.to_vec(),
+            negated: false,
+        })
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.enc.clone()
+    }
+    pub fn as_point(&self) -> &Point {
+        &self.p
+    }
+    /// Verifies a signature of `msg`.
+    // based on https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki#verification
+    pub fn verify(&self, msg: &[u8], sig: &Signature) -> Result<(), Error> {
+        let e = &self.e;
+        let (enc_r, enc_s) = sig.0.split_at(SIZE);
+        if BigInt::from_bytes_be(Sign::Plus, enc_r) >= e.get_field().get_modulus() {
+            return Err(Error::other(ERR_SIG_RANGE));
+        }
+        let s = BigUint::from_bytes_be(enc_s);
+        if s >= e.get_order() {
+            return Err(Error::other(ERR_SIG_RANGE));
+        }
+        let c = challenge(e, enc_r, &self.enc, msg);
+        let r = e.get_generator() * e.new_scalar(s.into()) - &self.p * c;
+        if r.is_identity() || !has_even_y(&r) || x_only(&r) != enc_r {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the point with even y-coordinate whose x-coordinate is the
+/// integer encoded in `buf`.
+pub(crate) fn lift_x(e: &Curve, buf: &[u8]) -> Result<Point, Error> {
+    if buf.len() != SIZE {
+        return Err(Error::other(ERR_KEY_POINT));
+    }
+    let mut enc = vec![0x02];
+    enc.extend_from_slice(buf);
+    e.decode(&enc)
+}
+
+pub(crate) fn challenge(e: &Curve, enc_r: &[u8], enc_p: &[u8], msg: &[u8]) -> Scalar {
+    let h = tagged_hash(TAG_CHALLENGE, &[enc_r, enc_p, msg]);
+    e.new_scalar_from_bytes(&h, Endianness::Big)
+}
+
+pub(crate) fn has_even_y(p: &Point) -> bool {
+    p.encode(PointFormat::Compressed)[0] == 0x02
+}
+
+pub(crate) fn x_only(p: &Point) -> Vec<u8> {
+    p.encode(PointFormat::Compressed)[1..].to_vec()
+}
+
+const SIZE: usize = 32;
+const TAG_AUX: &[u8] = b"BIP0340/aux";
+const TAG_NONCE: &[u8] = b"BIP0340/nonce";
+const TAG_CHALLENGE: &[u8] = b"BIP0340/challenge";
+const ERR_SIG_LEN: &str = "wrong length of signature";
+const ERR_SIG_RANGE: &str = "signature is out of range";
+const ERR_SIG_INVALID: &str = "invalid signature";
+const ERR_KEY_RANGE: &str = "secret key must be in [1, n-1]";
+const ERR_KEY_POINT: &str = "public key must be the x-coordinate of a point of the curve";
+const ERR_NONCE: &str = "auxiliary data produces a zero nonce";
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use super::{Signature, SigningKey, VerifyingKey};
+
+    // Test vectors taken from BIP-340, test-vectors.csv.
+    struct SignVector {
+        sk: &'static str,
+        pk: &'static str,
+        aux: &'static str,
+        msg: &'static str,
+        sig: &'static str,
+    }
+
+    struct VerifyVector {
+        pk: &'static str,
+        msg: &'static str,
+        sig: &'static str,
+        valid: bool,
+    }
+
+    #[test]
+    fn bip340_sign_vectors() {
+        let vectors = [
+            SignVector {
+                sk: "0000000000000000000000000000000000000000000000000000000000000003",
+                pk: "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+                aux: "0000000000000000000000000000000000000000000000000000000000000000",
+                msg: "0000000000000000000000000000000000000000000000000000000000000000",
+                sig: "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0",
+            },
+            SignVector {
+                sk: "b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef",
+                pk: "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+                aux: "0000000000000000000000000000000000000000000000000000000000000001",
+                msg: "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+                sig: "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a",
+            },
+            SignVector {
+                sk: "c90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b14e5c9",
+                pk: "dd308afec5777e13121fa72b9cc1b7cc0139715309b086c960e18fd969774eb8",
+                aux: "c87aa53824b4d7ae2eb035a2b5bbbccc080e76cdc6d1692c4b0b62d798e6d906",
+                msg: "7e2d58d8b3bcdf1abadec7829054f90dda9805aab56c77333024b9d0a508b75c",
+                sig: "5831aaeed7b44bb74e5eab94ba9d4294c49bcf2a60728d8b4c200f50dd313c1bab745879a5ad954a72c45a91c3a51d3c7adea98d82f8481e0e1e03674a6f3fb7",
+            },
+            SignVector {
+                sk: "0b432b2677937381aef05bb02a66ecd012773062cf3fa2549e44f58ed2401710",
+                pk: "25d1dff95105f5253c4022f628a996ad3a0d95fbf21d468a1b33f8c160d8f517",
+                aux: "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                msg: "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                sig: "7eb0509757e246f19449885651611cb965ecc1a187dd51b64fda1edc9637d5ec97582b9cb13db3933705b32ba982af5af25fd78881ebb32771fc5922efc66ea3",
+            },
+            SignVector {
+                sk: "0340034003400340034003400340034003400340034003400340034003400340",
+                pk: "778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117",
+                aux: "0000000000000000000000000000000000000000000000000000000000000000",
+                msg: "",
+                sig: "71535db165ecd9fbbc046e5ffaea61186bb6ad436732fccc25291a55895464cf6069ce26bf03466228f19a3a62db8a649f2d560fac652827d1af0574e427ab63",
+            },
+            SignVector {
+                sk: "0340034003400340034003400340034003400340034003400340034003400340",
+                pk: "778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117",
+                aux: "0000000000000000000000000000000000000000000000000000000000000000",
+                msg: "11",
+                sig: "08a20a0afef64124649232e0693c583ab1b9934ae63b4c3511f3ae1134c6a303ea3173bfea6683bd101fa5aa5dbc1996fe7cacfc5a577d33ec14564cec2bacbf",
+            },
+            SignVector {
+                sk: "0340034003400340034003400340034003400340034003400340034003400340",
+                pk: "778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117",
+                aux: "0000000000000000000000000000000000000000000000000000000000000000",
+                msg: "0102030405060708090a0b0c0d0e0f1011",
+                sig: "5130f39a4059b43bc7cac09a19ece52b5d8699d1a71e3c52da9afdb6b50ac370c4a482b77bf960f8681540e25b6771ece1e5a37fd80e5a51897c5566a97ea5a5",
+            },
+        ];
+        for v in vectors.iter() {
+            let sk = SigningKey::from_bytes(&hex::decode(v.sk).unwrap()).unwrap();
+            let vk = sk.verifying_key();
+            assert_eq!(hex::encode(vk.to_bytes()), v.pk);
+            let mut aux = [0u8; 32];
+            aux.copy_from_slice(&hex::decode(v.aux).unwrap());
+            let msg = hex::decode(v.msg).unwrap();
+            let sig = sk.sign_with_aux(&msg, &aux).unwrap();
+            assert_eq!(hex::encode(sig.to_bytes()), v.sig, "sk: {}", v.sk);
+            assert!(vk.verify(&msg, &sig).is_ok());
+            let vk = VerifyingKey::from_bytes(&vk.to_bytes()).unwrap();
+            assert!(vk.verify(&msg, &sig).is_ok());
+        }
+        let sk = SigningKey::from_bytes(&[0x03, 0x40].repeat(16)).unwrap();
+        let msg = [0x99u8; 100];
+        let sig = sk.sign_with_aux(&msg, &[0u8; 32]).unwrap();
+        assert_eq!(
+            hex::encode(sig.to_bytes()),
+            "403b12b0d8555a344175ea7ec746566303321e5dbfa8be6f091635163eca79a8585ed3e3170807e7c03b720fc54c7b23897fcba0e9d0b4a06894cfd249f22367"
+        );
+    }
+
+    #[test]
+    fn bip340_verify_vectors() {
+        let pk = "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659";
+        let msg = "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89";
+        let vectors = [
+            VerifyVector {
+                pk: "d69c3509bb99e412e68b0fe8544e72837dfa30746d8be2aa65975f29d22dc7b9",
+                msg: "4df3c3f68fcc83b27e9d42c90431a72499f17875c81a599b566c9889b9696703",
+                sig: "00000000000000000000003b78ce563f89a0ed9414f5aa28ad0d96d6795f9c6376afb1548af603b3eb45c9f8207dee1060cb71c04e80f593060b07d28308d7f4",
+                valid: true,
+            },
+            // public key not on the curve
+            VerifyVector {
+                pk: "eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34",
+                msg,
+                sig: "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e17776969e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+                valid: false,
+            },
+            // R has odd y-coordinate
+            VerifyVector {
+                pk,
+                msg,
+                sig: "fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a14602975563cc27944640ac607cd107ae10923d9ef7a73c643e166be5ebeafa34b1ac553e2",
+                valid: false,
+            },
+            // negated message
+            VerifyVector {
+                pk,
+                msg,
+                sig: "1fa62e331edbc21c394792d2ab1100a7b432b013df3f6ff4f99fcb33e0e1515f28890b3edb6e7189b630448b515ce4f8622a954cfe545735aaea5134fccdb2bd",
+                valid: false,
+            },
+            // negated s
+            VerifyVector {
+                pk,
+                msg,
+                sig: "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e177769961764b3aa9b2ffcb6ef947b6887a226e8d7c93e00c5ed0c1834ff0d0c2e6da6",
+                valid: false,
+            },
+            // sG - eP is the identity, with x(R) = 0
+            VerifyVector {
+                pk,
+                msg,
+                sig: "0000000000000000000000000000000000000000000000000000000000000000123dda8328af9c23a94c1feecfd123ba4fb73476f0d594dcb65c6425bd186051",
+                valid: false,
+            },
+            // sG - eP is the identity, with x(R) = 1
+            VerifyVector {
+                pk,
+                msg,
+                sig: "00000000000000000000000000000000000000000000000000000000000000017615fbaf5ae28864013c099742deadb4dba87f11ac6754f93780d5a1837cf197",
+                valid: false,
+            },
+            // x(R) is not the x-coordinate of a point of the curve
+            VerifyVector {
+                pk,
+                msg,
+                sig: "4a298dacae57395a15d0795ddbfd1dcb564da82b0f269bc70a74f8220429ba1d69e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+                valid: false,
+            },
+            // x(R) is equal to the field size
+            VerifyVector {
+                pk,
+                msg,
+                sig: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f69e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+                valid: false,
+            },
+            // s is equal to the curve order
+            VerifyVector {
+                pk,
+                msg,
+                sig: "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e177769fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+                valid: false,
+            },
+            // public key exceeds the field size
+            VerifyVector {
+                pk: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc30",
+                msg,
+                sig: "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e17776969e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+                valid: false,
+            },
+        ];
+        for v in vectors.iter() {
+            let valid = match (
+                VerifyingKey::from_bytes(&hex::decode(v.pk).unwrap()),
+                Signature::from_bytes(&hex::decode(v.sig).unwrap()),
+            ) {
+                (Ok(vk), Ok(sig)) => vk.verify(&hex::decode(v.msg).unwrap(), &sig).is_ok(),
+                _ => false,
+            };
+            assert_eq!(valid, v.valid, "sig: {}", v.sig);
+        }
+    }
+
+    #[test]
+    fn sign_verify() {
+        let sk = SigningKey::random(&mut OsRng);
+        let vk = sk.verifying_key();
+        let sig = sk.sign(b"message", &mut OsRng);
+        assert!(vk.verify(b"message", &sig).is_ok());
+        assert!(vk.verify(b"massage", &sig).is_err());
+        assert!(SigningKey::from_bytes(&[0u8; 32]).is_err());
+        assert!(Signature::from_bytes(&sig.to_bytes()[1..]).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+//! This is documentation for the `sec1` module.
+//!
+//! The sec1 module implements the DER encoding of the ASN.1 structure
+//! `ECPrivateKey` specified in SEC 1, Appendix C.4, which is the format of
+//! the "EC PRIVATE KEY" files emitted by OpenSSL, many HSMs, and legacy
+//! tools:
+//!
+//! ```text
+//! ECPrivateKey ::= SEQUENCE {
+//!   version        INTEGER { ecPrivkeyVer1(1) },
+//!   privateKey     OCTET STRING,
+//!   parameters [0] ECParameters {{ NamedCurve }} OPTIONAL,
+//!   publicKey  [1] BIT STRING OPTIONAL
+//! }
+//! ```
+//!
+//! Only named curves are supported as parameters.
+//!
+//! # Exporting a Key
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use redox_ecc::keypair::KeyPair;
+//!  use redox_ecc::sec1::EcPrivateKey;
+//!
+//!  let kp = KeyPair::generate(&P256.get(), &mut OsRng);
+//!  let der = EcPrivateKey::from_keypair(P256, &kp).to_der().unwrap();
+//!  let key = EcPrivateKey::from_der(&der).unwrap();
+//!  assert!(key.to_keypair(None).unwrap().public() == kp.public());
+//! ```
+
+use zeroize::Zeroize;
+
+use std::io::Error;
+
+use crate::ellipticcurve::{Decode, EllipticCurve, PointFormat};
+use crate::instances::{GetCurve, WeCurveID, P256, P384, P521, SECP256K1, SM2};
+use crate::keypair::KeyPair;
+use crate::secret::SecretKey;
+use crate::weierstrass::Curve;
+
+/// EcPrivateKey is the content of a SEC 1 private key.
+pub struct EcPrivateKey {
+    /// The named curve given as parameters, if any.
+    pub curve: Option<WeCurveID>,
+    /// The secret scalar encoded in big-endian order, which is zeroized on
+    /// drop.
+    pub secret: SecretKey,
+    /// The encoding of the public point, if any.
+    pub public_key: Option<Vec<u8>>,
+}
+
+impl EcPrivateKey {
+    /// Returns the private key of `kp` with its named curve and its public
+    /// point in uncompressed form.
+    pub fn from_keypair(id: WeCurveID, kp: &KeyPair<Curve>) -> EcPrivateKey {
+        EcPrivateKey {
+            curve: Some(id),
+            secret: kp.to_bytes(),
+            public_key: Some(kp.public_key_bytes(PointFormat::Uncompressed)),
+        }
+    }
+    /// Parses the DER encoding of a private key. It fails if the encoding
+    /// is not canonical, if the version is not 1, or if the parameters are
+    /// not a named curve known by this crate.
+    pub fn from_der(buf: &[u8]) -> Result<EcPrivateKey, Error> {
+        let (body, rest) = read(buf, TAG_SEQUENCE)?;
+        if !rest.is_empty() {
+            return Err(Error::other(ERR_DER));
+        }
+        let (version, body) = read(body, TAG_INTEGER)?;
+        if version != [1] {
+            return Err(Error::other(ERR_VERSION));
+        }
+        let (secret, mut body) = read(body, TAG_OCTET_STRING)?;
+        let mut key = EcPrivateKey {
+            curve: None,
+            secret: SecretKey::new(secret.to_vec()),
+            public_key: None,
+        };
+        if body.first() == Some(&TAG_PARAMETERS) {
+            let (params, rest) = read(body, TAG_PARAMETERS)?;
+            let (oid, tail) = read(params, TAG_OID)?;
+            if !tail.is_empty() {
+                return Err(Error::other(ERR_DER));
+            }
+            let id = NAMED_CURVES.iter().find(|(_, o)| *o == oid);
+            key.curve = Some(id.ok_or_else(|| Error::other(ERR_CURVE))?.0);
+            body = rest;
+        }
+        if body.first() == Some(&TAG_PUBLIC_KEY) {
+            let (bits, rest) = read(body, TAG_PUBLIC_KEY)?;
+            let (bits, tail) = read(bits, TAG_BIT_STRING)?;
+            match bits.split_first() {
+                Some((0, point)) if tail.is_empty() => key.public_key = Some(point.to_vec()),
+                _ => return Err(Error::other(ERR_DER)),
+            }
+            body = rest;
+        }
+        if !body.is_empty() {
+            return Err(Error::other(ERR_DER));
+        }
+        Ok(key)
+    }
+    /// Returns the DER encoding of the private key. It fails if the curve
+    /// has no object identifier.
+    pub fn to_der(&self) -> Result<Vec<u8>, Error> {
+        let mut body = tlv(TAG_INTEGER, &[1]);
+        body.append(&mut tlv(TAG_OCTET_STRING, self.secret.expose_secret()));
+        if let Some(id) = self.curve {
+            let oid = NAMED_CURVES.iter().find(|(c, _)| *c == id);
+            let oid = tlv(TAG_OID, oid.ok_or_else(|| Error::other(ERR_CURVE))?.1);
+            body.append(&mut tlv(TAG_PARAMETERS, &oid));
+        }
+        if let Some(pk) = &self.public_key {
+            let bits = tlv(TAG_BIT_STRING, &[&[0], &pk[..]].concat());
+            body.append(&mut tlv(TAG_PUBLIC_KEY, &bits));
+        }
+        let out = tlv(TAG_SEQUENCE, &body);
+        body.zeroize();
+        Ok(out)
+    }
+    /// Returns the key pair of this private key. The curve is taken from
+    /// the parameters, or from `id` if they are absent; if both are given,
+    /// they must match. Secrets shorter than the order, as written by some
+    /// tools, are padded with zeros. It fails if the public key is present
+    /// and does not match the secret scalar.
+    pub fn to_keypair(&self, id: Option<WeCurveID>) -> Result<KeyPair<Curve>, Error> {
+        let id = match (self.curve, id) {
+            (Some(c), Some(i)) if c != i => return Err(Error::other(ERR_CURVE_MISMATCH)),
+            (Some(c), _) | (None, Some(c)) => c,
+            (None, None) => return Err(Error::other(ERR_CURVE)),
+        };
+        let e = id.get();
+        let len = e.get_order().bits().div_ceil(8);
+        if self.secret.len() > len {
+            return Err(Error::other(ERR_SECRET_LEN));
+        }
+        let mut secret = vec![0u8; len - self.secret.len()];
+        secret.extend_from_slice(self.secret.expose_secret());
+        let kp = KeyPair::from_bytes(&e, &secret);
+        secret.zeroize();
+        let kp = kp?;
+        if let Some(pk) = &self.public_key {
+            if e.decode(pk)? != *kp.public() {
+                return Err(Error::other(ERR_PUBLIC_KEY));
+            }
+        }
+        Ok(kp)
+    }
+}
+
+/// Returns the DER encoding of a tag, a length and the value `v`.
+fn tlv(tag: u8, v: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    match v.len() {
+        l if l < 0x80 => out.push(l as u8),
+        l if l < 0x100 => out.extend_from_slice(&[0x81, l as u8]),
+        l => out.extend_from_slice(&[0x82, (l >> 8) as u8, l as u8]),
+    }
+    out.extend_from_slice(v);
+    out
+}
+
+/// Splits `buf` into the value of its first element, which must have the
+/// given tag, and the rest. Only minimal lengths are accepted.
+fn read(buf: &[u8], tag: u8) -> Result<(&[u8], &[u8]), Error> {
+    if buf.len() < 2 || buf[0] != tag {
+        return Err(Error::other(ERR_DER));
+    }
+    let (len, hdr) = match buf[1..] {
+        [l, ..] if l < 0x80 => (l as usize, 2),
+        [0x81, l, ..] if l >= 0x80 => (l as usize, 3),
+        [0x82, h, l, ..] if h != 0 => (((h as usize) << 8) | l as usize, 4),
+        _ => return Err(Error::other(ERR_DER)),
+    };
+    if buf.len() - hdr < len {
+        return Err(Error::other(ERR_DER));
+    }
+    Ok((&buf[hdr..hdr + len], &buf[hdr + len..]))
+}
+
+/// Object identifiers of the named curves, without tag and length.
+static NAMED_CURVES: [(WeCurveID, &[u8]); 5] = [
+    // 1.2.840.10045.3.1.7
+    (P256, &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07]),
+    // 1.3.132.0.34
+    (P384, &[0x2b, 0x81, 0x04, 0x00, 0x22]),
+    // 1.3.132.0.35
+    (P521, &[0x2b, 0x81, 0x04, 0x00, 0x23]),
+    // 1.3.132.0.10
+    (SECP256K1, &[0x2b, 0x81, 0x04, 0x00, 0x0a]),
+    // 1.2.156.10197.1.301
+    (SM2, &[0x2a, 0x81, 0x1c, 0xcf, 0x55, 0x01, 0x82, 0x2d]),
+];
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_PARAMETERS: u8 = 0xa0;
+const TAG_PUBLIC_KEY: u8 = 0xa1;
+const ERR_DER: &str = "invalid DER encoding";
+const ERR_VERSION: &str = "version of ECPrivateKey must be 1";
+const ERR_CURVE: &str = "curve is not a supported named curve";
+const ERR_CURVE_MISMATCH: &str = "parameters do not match the expected curve";
+const ERR_SECRET_LEN: &str = "secret scalar is longer than the order";
+const ERR_PUBLIC_KEY: &str = "public key does not match the secret scalar";
+
+#[cfg(test)]
+mod tests {
+    use super::EcPrivateKey;
+    use crate::ellipticcurve::PointFormat;
+    use crate::instances::{P256, P521, SECP256K1};
+
+    // Test vectors generated with OpenSSL 3.5.
+    struct Vector {
+        der: &'static str,
+        secret: &'static str,
+        has_public_key: bool,
+    }
+
+    #[test]
+    fn openssl_vectors() {
+        let vectors = [
+            Vector {
+                der: "307702010104202e6f93b664ea7b9c2a9c01cbfe03d59ac81bbb1cbea9acf6e0cb6adbc93102c5a00a06082a8648ce3d030107a144034200040235c1c3c05e4a705eaaabe0ae332086ec245434813db9ce0d40724be242aca41630ab13ad0901805827469b1c14eaea31bfe9202ef6d2a55341f57a08bb0d1e",
+                secret: "2e6f93b664ea7b9c2a9c01cbfe03d59ac81bbb1cbea9acf6e0cb6adbc93102c5",
+                has_public_key: true,
+            },
+            Vector {
+                der: "302e0201010420c3c9fc3bef7d17b104a65c4a82089e4228cd79888fc3add03f8492e2b82b0e4aa00706052b8104000a",
+                secret: "c3c9fc3bef7d17b104a65c4a82089e4228cd79888fc3add03f8492e2b82b0e4a",
+                has_public_key: false,
+            },
+            Vector {
+                der: "3081dc020101044200bc72788956ef5c4aaa4bc3d2c9984b2e7b11dbc0f5d723dfeed8c66e86f02f98b30b9dbeea52387e119e154878d88d0ce888b29f15f47db128cd26cf52fb26e7a0a00706052b81040023a181890381860004007c8ade5d97451e7bb24deec4e93740714d7a0a2cc31df2c50f83d59962337524f23676df5a8c4a22390b994aa35efb343353b623940e72bf91ab80b666c79ece72009666af8592887dc548ea78cf7f05d286a7a565a0eadc61fd77ec5ae340eda1d2af6c4e17b0c701fd02e88eb18908078cef2b5dcb8cd7714c6cd4e33cde942b818d",
+                secret: "00bc72788956ef5c4aaa4bc3d2c9984b2e7b11dbc0f5d723dfeed8c66e86f02f98b30b9dbeea52387e119e154878d88d0ce888b29f15f47db128cd26cf52fb26e7a0",
+                has_public_key: true,
+            },
+        ];
+        let curves = [P256, SECP256K1, P521];
+        for (v, id) in vectors.iter().zip(curves.iter()) {
+            let der = hex::decode(v.der).unwrap();
+            let key = EcPrivateKey::from_der(&der).unwrap();
+            assert!(key.curve == Some(*id));
+            assert_eq!(key.public_key.is_some(), v.has_public_key);
+            let kp = key.to_keypair(None).unwrap();
+            assert_eq!(hex::encode(kp.to_bytes().expose_secret()), v.secret);
+            assert_eq!(key.to_der().unwrap(), der);
+            if v.has_public_key {
+                assert_eq!(EcPrivateKey::from_keypair(*id, &kp).to_der().unwrap(), der);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_keys() {
+        let der = hex::decode("302e0201010420c3c9fc3bef7d17b104a65c4a82089e4228cd79888fc3add03f8492e2b82b0e4aa00706052b8104000a").unwrap();
+        let key = EcPrivateKey::from_der(&der).unwrap();
+        assert!(key.to_keypair(Some(P256)).is_err());
+        assert!(key.to_keypair(Some(SECP256K1)).is_ok());
+
+        // version 2, trailing data, unknown curve
+        let mut bad = der.clone();
+        bad[4] = 2;
+        assert!(EcPrivateKey::from_der(&bad).is_err());
+        let mut bad = der.clone();
+        bad.push(0);
+        assert!(EcPrivateKey::from_der(&bad).is_err());
+        let mut bad = der.clone();
+        bad[47] = 0x0b;
+        assert!(EcPrivateKey::from_der(&bad).is_err());
+
+        // no parameters and no curve given
+        let key = EcPrivateKey {
+            curve: None,
+            secret: vec![1].into(),
+            public_key: None,
+        };
+        assert!(key.to_keypair(None).is_err());
+        let der = key.to_der().unwrap();
+        assert_eq!(der, [0x30, 0x06, 0x02, 0x01, 0x01, 0x04, 0x01, 0x01]);
+        let kp = EcPrivateKey::from_der(&der)
+            .unwrap()
+            .to_keypair(Some(P256))
+            .unwrap();
+        assert_eq!(kp.to_bytes().expose_secret()[31], 1);
+
+        // public key of another secret
+        let other = EcPrivateKey {
+            curve: Some(P256),
+            secret: vec![2].into(),
+            public_key: Some(kp.public_key_bytes(PointFormat::Compressed)),
+        };
+        assert!(other.to_keypair(None).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! This is documentation for the `secret` module.
+//!
+//! The secret module provides wrappers for secret values that make leaking
+//! them by accident harder. The wrappers do not implement `Display`, and
+//! their `Debug` output is redacted, so a secret cannot end up in a log
+//! line unless it is unwrapped with `expose_secret` first. The wrapped
+//! values are zeroized on drop and are compared in constant time.
+//!
+//! # Wrapping a Secret
+//!
+//! ```
+//!  use num_bigint::BigInt;
+//!  use redox_ecc::ellipticcurve::EllipticCurve;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use redox_ecc::secret::SecretScalar;
+//!
+//!  let ec = P256.get();
+//!  let k = SecretScalar::new(ec.new_scalar(BigInt::from(7)));
+//!  assert_eq!(format!("{:?}", k), "SecretScalar([REDACTED])");
+//!  let p = ec.get_generator() * k.expose_secret();
+//! ```
+
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+use crate::ellipticcurve::EcScalar;
+
+/// SecretScalar is a scalar that must be kept secret, such as a private key
+/// or a nonce.
+pub struct SecretScalar<S: EcScalar>(S);
+
+impl<S: EcScalar> SecretScalar<S> {
+    pub fn new(k: S) -> SecretScalar<S> {
+        SecretScalar(k)
+    }
+    /// Returns the wrapped scalar. Every use of the secret goes through
+    /// this function, which makes them easy to audit.
+    pub fn expose_secret(&self) -> &S {
+        &self.0
+    }
+}
+
+impl<S: EcScalar> From<S> for SecretScalar<S> {
+    fn from(k: S) -> SecretScalar<S> {
+        SecretScalar(k)
+    }
+}
+
+impl<S: EcScalar + Clone> Clone for SecretScalar<S> {
+    fn clone(&self) -> Self {
+        SecretScalar(self.0.clone())
+    }
+}
+
+impl<S: EcScalar> ConstantTimeEq for SecretScalar<S> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl<S: EcScalar> std::fmt::Debug for SecretScalar<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "SecretScalar([REDACTED])")
+    }
+}
+
+impl<S: EcScalar> Drop for SecretScalar<S> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// SecretKey is a byte string that must be kept secret, such as the
+/// encoding of a private key or a seed.
+#[derive(Clone)]
+pub struct SecretKey(Vec<u8>);
+
+impl SecretKey {
+    pub fn new(bytes: Vec<u8>) -> SecretKey {
+        SecretKey(bytes)
+    }
+    /// Returns the wrapped bytes. Every use of the secret goes through this
+    /// function, which makes them easy to audit.
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.0
+    }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<Vec<u8>> for SecretKey {
+    fn from(bytes: Vec<u8>) -> SecretKey {
+        SecretKey(bytes)
+    }
+}
+
+impl ConstantTimeEq for SecretKey {
+    /// Compares the bytes in constant time; only the lengths may leak.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl std::fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "SecretKey([REDACTED])")
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use subtle::ConstantTimeEq;
+
+    use super::{SecretKey, SecretScalar};
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::instances::{GetCurve, P256};
+
+    #[test]
+    fn redacted() {
+        let ec = P256.get();
+        let k = SecretScalar::new(ec.new_scalar(BigInt::from(0x1234_5678)));
+        let debug = format!("{:?}", k);
+        assert!(!debug.contains(&k.expose_secret().to_string()));
+        assert!(bool::from(k.ct_eq(&k.clone())));
+        assert!(!bool::from(k.ct_eq(&ec.new_scalar(BigInt::from(1)).into())));
+
+        let sk = SecretKey::new(vec![0xab; 32]);
+        assert_eq!(format!("{:?}", sk), "SecretKey([REDACTED])");
+        assert_eq!(sk.expose_secret(), &[0xab; 32][..]);
+        assert!(bool::from(sk.ct_eq(&sk.clone())));
+        assert!(!bool::from(sk.ct_eq(&SecretKey::new(vec![0xab; 31]))));
+    }
+}
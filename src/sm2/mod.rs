@@ -0,0 +1,256 @@
+//! This is documentation for the `sm2` module.
+//!
+//! The sm2 module implements the SM2 digital signature algorithm specified
+//! in GB/T 32918.2 over the SM2 curve, where messages are hashed with SM3
+//! together with the identity of the signer.
+//!
+//! # Signing and Verifying
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::sm2::{SigningKey, DEFAULT_ID};
+//!
+//!  let sk = SigningKey::random(DEFAULT_ID, &mut OsRng).unwrap();
+//!  let sig = sk.sign(b"message", &mut OsRng);
+//!  assert!(sk.verifying_key().verify(b"message", &sig).is_ok());
+//! ```
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::identities::Zero;
+use rand_core::{CryptoRng, RngCore};
+use sm3::{Digest, Sm3};
+
+use std::io::Error;
+
+use crate::ecdsa::Signature;
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, Encode, PointFormat};
+use crate::instances::{GetCurve, SM2};
+use crate::ops::Serialize;
+use crate::primefield::FpElt;
+use crate::weierstrass::{Curve, Point, Scalar};
+
+/// DEFAULT_ID is the identity used when the signer has no other identity,
+/// as recommended by GM/T 0009.
+pub const DEFAULT_ID: &[u8] = b"1234567812345678";
+
+/// SigningKey is an SM2 private key together with its public key and the
+/// identity of its owner.
+#[derive(Clone)]
+pub struct SigningKey {
+    d: Scalar,
+    vk: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Returns the signing key whose secret is `d`, which must be in the
+    /// range [1, n-2]. It fails if `id` is longer than 8191 bytes.
+    pub fn new(id: &[u8], d: Scalar) -> Result<SigningKey, Error> {
+        let e = SM2.get();
+        if is_zero(&e, &d) || is_zero(&e, &(&d + e.new_scalar(1.into()))) {
+            return Err(Error::other(ERR_KEY_RANGE));
+        }
+        let q = e.get_generator() * &d;
+        let vk = VerifyingKey::new(id, q)?;
+        Ok(SigningKey { d, vk })
+    }
+    /// Returns a signing key sampled uniformly at random.
+    pub fn random<R: RngCore + CryptoRng>(id: &[u8], rng: &mut R) -> Result<SigningKey, Error> {
+        let e = SM2.get();
+        loop {
+            let d = e.random_scalar(rng);
+            if !is_zero(&e, &(&d + e.new_scalar(1.into()))) {
+                return SigningKey::new(id, d);
+            }
+        }
+    }
+    /// Parses a secret encoded in big-endian order with 32 bytes.
+    pub fn from_bytes(id: &[u8], buf: &[u8]) -> Result<SigningKey, Error> {
+        let e = SM2.get();
+        let d = BigUint::from_bytes_be(buf);
+        if buf.len() != SIZE || d >= e.get_order() {
+            return Err(Error::other(ERR_KEY_RANGE));
+        }
+        SigningKey::new(id, e.new_scalar(d.into()))
+    }
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.d.to_bytes_be()
+    }
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.vk
+    }
+    /// Signs `msg` using a nonce sampled uniformly at random.
+    pub fn sign<R: RngCore + CryptoRng>(&self, msg: &[u8], rng: &mut R) -> Signature {
+        loop {
+            let k = self.vk.e.random_scalar(rng);
+            if let Ok(sig) = self.sign_with_nonce(msg, &k) {
+                return sig;
+            }
+        }
+    }
+    /// Signs `msg` using the nonce `k`. It fails if the nonce produces an
+    /// invalid signature, in which case another nonce must be used.
+    ///
+    /// **Attention** Revealing `k`, or using it for signing two different
+    /// messages, reveals the secret key.
+    // based on GB/T 32918.2-2016, Section 6.1
+    pub fn sign_with_nonce(&self, msg: &[u8], k: &Scalar) -> Result<Signature, Error> {
+        let e = &self.vk.e;
+        let z = self.vk.digest(msg);
+        let p = e.get_generator() * k;
+        let x = x_scalar(e, &p);
+        let r = z + x;
+        if is_zero(e, &r) || is_zero(e, &(&r + k)) {
+            return Err(Error::other(ERR_NONCE));
+        }
+        let s = (k - &r * &self.d) / (&self.d + e.new_scalar(1.into()));
+        if is_zero(e, &s) {
+            return Err(Error::other(ERR_NONCE));
+        }
+        Ok(Signature { r, s })
+    }
+}
+
+/// VerifyingKey is an SM2 public key together with the identity of its
+/// owner.
+#[derive(Clone)]
+pub struct VerifyingKey {
+    e: Curve,
+    q: Point,
+    za: Vec<u8>,
+}
+
+impl VerifyingKey {
+    /// Returns the verifying key for the point `q` of the SM2 curve. It
+    /// fails if `q` is the identity or if `id` is longer than 8191 bytes.
+    pub fn new(id: &[u8], q: Point) -> Result<VerifyingKey, Error> {
+        let e = SM2.get();
+        if q.is_identity() || !e.is_on_curve(&q) {
+            return Err(Error::other(ERR_KEY_POINT));
+        }
+        let za = identity_hash(&e, id, &q)?;
+        Ok(VerifyingKey { e, q, za })
+    }
+    /// Parses a verifying key encoded as a point of the SM2 curve.
+    pub fn from_bytes(id: &[u8], buf: &[u8]) -> Result<VerifyingKey, Error> {
+        VerifyingKey::new(id, SM2.get().decode(buf)?)
+    }
+    pub fn to_bytes(&self, format: PointFormat) -> Vec<u8> {
+        self.q.encode(format)
+    }
+    pub fn as_point(&self) -> &Point {
+        &self.q
+    }
+    /// Returns the hash `Z` binding the identity of the signer to its public
+    /// key and to the curve parameters.
+    pub fn identity_hash(&self) -> &[u8] {
+        &self.za
+    }
+    /// Verifies a signature of `msg`.
+    // based on GB/T 32918.2-2016, Section 7.1
+    pub fn verify(&self, msg: &[u8], sig: &Signature) -> Result<(), Error> {
+        let e = &self.e;
+        if is_zero(e, &sig.r) || is_zero(e, &sig.s) {
+            return Err(Error::other(ERR_SIG_RANGE));
+        }
+        let t = &sig.r + &sig.s;
+        if is_zero(e, &t) {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        let p = e.get_generator() * &sig.s + &self.q * t;
+        if p.is_identity() {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        let x = x_scalar(e, &p);
+        if self.digest(msg) + x != sig.r {
+            return Err(Error::other(ERR_SIG_INVALID));
+        }
+        Ok(())
+    }
+    /// Returns the integer `e = SM3(Z || msg)` reduced modulo the order.
+    fn digest(&self, msg: &[u8]) -> Scalar {
+        let h = Sm3::new()
+            .chain_update(&self.za)
+            .chain_update(msg)
+            .finalize();
+        self.e.new_scalar(BigInt::from_bytes_be(Sign::Plus, &h))
+    }
+}
+
+/// Computes `Z = SM3(ENTL || ID || a || b || xG || yG || xA || yA)`, where
+/// ENTL is the length of the identity in bits encoded in two bytes.
+// based on GB/T 32918.2-2016, Section 5.5
+fn identity_hash(e: &Curve, id: &[u8], q: &Point) -> Result<Vec<u8>, Error> {
+    if id.len() > MAX_ID_LENGTH {
+        return Err(Error::other(ERR_ID_LEN));
+    }
+    let entl = (8 * id.len() as u16).to_be_bytes();
+    let (gx, gy) = e.get_generator().to_affine().unwrap();
+    let (qx, qy) = q.to_affine().ok_or_else(|| Error::other(ERR_KEY_POINT))?;
+    let mut h = Sm3::new();
+    h.update(entl);
+    h.update(id);
+    for x in [&e.a, &e.b, &gx, &gy, &qx, &qy].iter() {
+        h.update(FpElt::to_bytes_be(x));
+    }
+    Ok(h.finalize().to_vec())
+}
+
+fn is_zero(e: &Curve, k: &Scalar) -> bool {
+    *k == e.new_scalar(BigInt::zero())
+}
+
+fn x_scalar(e: &Curve, p: &Point) -> Scalar {
+    e.new_scalar(p.x().map_or_else(BigInt::zero, |x| {
+        BigInt::from_bytes_be(Sign::Plus, &x.to_bytes_be())
+    }))
+}
+
+const SIZE: usize = 32;
+const MAX_ID_LENGTH: usize = 8191;
+const ERR_ID_LEN: &str = "identity must be at most 8191 bytes long";
+const ERR_SIG_RANGE: &str = "signature integers must be in [1, n-1]";
+const ERR_SIG_INVALID: &str = "invalid signature";
+const ERR_KEY_RANGE: &str = "secret key must be in [1, n-2]";
+const ERR_KEY_POINT: &str = "public key must be a point of the curve other than the identity";
+const ERR_NONCE: &str = "nonce produces an invalid signature";
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use super::{SigningKey, VerifyingKey, DEFAULT_ID};
+    use crate::ecdsa::Signature;
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::instances::{GetCurve, SM2};
+
+    #[test]
+    fn openssl_vector() {
+        // Signature produced by OpenSSL with the SM3 digest and the
+        // distinguishing identifier "example@rustcrypto.org".
+        let pk = "0408d77ae04c01cc4c1104360dd8af6b6f7df334283d7c1a6afd5652407b87bee5014e2a57c36c150d16324dc664e31e6432359609c4e79847a5b161c8c7364c8a";
+        let sig = "d1dcccedd9fb785e0f67c16b7c52901625c0b69de9bca2144acc7be713cad2fcf7d1eae6e3a157b36c65f672f738ca8b46298bf149a6510072c431b49cd88b1c";
+        let e = SM2.get();
+        let id = b"example@rustcrypto.org";
+        let vk = VerifyingKey::from_bytes(id, &hex::decode(pk).unwrap()).unwrap();
+        let sig = Signature::from_bytes(&e, &hex::decode(sig).unwrap()).unwrap();
+        assert!(vk.verify(b"testing", &sig).is_ok());
+        assert!(vk.verify(b"tested", &sig).is_err());
+        let vk = VerifyingKey::from_bytes(DEFAULT_ID, &hex::decode(pk).unwrap()).unwrap();
+        assert!(vk.verify(b"testing", &sig).is_err());
+    }
+
+    #[test]
+    fn sign_verify() {
+        let e = SM2.get();
+        let sk = SigningKey::random(b"alice@example.com", &mut OsRng).unwrap();
+        let vk = sk.verifying_key();
+        let sig = sk.sign(b"message", &mut OsRng);
+        assert!(vk.verify(b"message", &sig).is_ok());
+        assert!(vk.verify(b"massage", &sig).is_err());
+        let sk = SigningKey::from_bytes(DEFAULT_ID, &sk.to_bytes()).unwrap();
+        assert!(sk.verifying_key().verify(b"message", &sig).is_err());
+        let n_minus_1 = e.new_scalar((-1).into());
+        assert!(SigningKey::new(DEFAULT_ID, n_minus_1).is_err());
+        assert!(SigningKey::random(&[0u8; 8192], &mut OsRng).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! This is documentation for the `taproot` module.
+//!
+//! The taproot module implements the key tweaking of Taproot outputs
+//! specified in BIP-341, on top of the BIP-340 keys of the schnorr module.
+//!
+//! The output key `Q = P + [t]G` commits to the internal key `P` and to the
+//! root of a tree of scripts, where `t` is the tagged hash of both. Spending
+//! through the key path requires signing with the tweaked secret key, while
+//! spending through a script reveals the internal key and the parity of the
+//! y-coordinate of `Q`.
+//!
+//! # Spending through the Key Path
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::schnorr::SigningKey;
+//!  use redox_ecc::taproot::{tweak_public_key, tweak_secret_key};
+//!
+//!  let sk = SigningKey::random(&mut OsRng);
+//!  let (output_key, _) = tweak_public_key(sk.verifying_key(), None).unwrap();
+//!  let tweaked = tweak_secret_key(&sk, None).unwrap();
+//!  let sig = tweaked.sign(b"transaction", &mut OsRng);
+//!  assert!(output_key.verify(b"transaction", &sig).is_ok());
+//! ```
+
+use num_bigint::BigUint;
+
+use std::io::Error;
+
+use crate::ellipticcurve::EllipticCurve;
+use crate::instances::{GetCurve, SECP256K1};
+use crate::ops::{Endianness, Serialize};
+use crate::schnorr::{has_even_y, tagged_hash, SigningKey, VerifyingKey};
+use crate::weierstrass::{Curve, Scalar};
+
+/// Returns the tweak committing to the internal key and to the Merkle root
+/// of the script tree, if any.
+pub fn tap_tweak(internal_key: &VerifyingKey, merkle_root: Option<&[u8]>) -> Vec<u8> {
+    let root = merkle_root.unwrap_or_default();
+    tagged_hash(TAG_TWEAK, &[&internal_key.to_bytes(), root])
+}
+
+/// Tweaks an internal key with the Merkle root of the script tree, or with
+/// no script at all if `merkle_root` is `None`. It returns the output key
+/// and whether its y-coordinate is odd, which is the parity bit of the
+/// control blocks. It fails if the tweak is not less than the order, which
+/// happens with negligible probability.
+// based on https://github.com/bitcoin/bips/blob/master/bip-0341.mediawiki#constructing-and-spending-taproot-outputs
+pub fn tweak_public_key(
+    internal_key: &VerifyingKey,
+    merkle_root: Option<&[u8]>,
+) -> Result<(VerifyingKey, bool), Error> {
+    let e = SECP256K1.get();
+    let t = tweak_scalar(&e, &tap_tweak(internal_key, merkle_root))?;
+    let q = internal_key.as_point() + e.get_generator() * t;
+    let odd = !has_even_y(&q);
+    Ok((VerifyingKey::new(&e, q), odd))
+}
+
+/// Tweaks a secret key in the same way as its public key, so that the
+/// result signs for the output key. The secret key is first negated if its
+/// public point has odd y-coordinate. It fails if the tweak is not less than
+/// the order or if the tweaked key is zero.
+// based on https://github.com/bitcoin/bips/blob/master/bip-0341.mediawiki#constructing-and-spending-taproot-outputs
+pub fn tweak_secret_key(sk: &SigningKey, merkle_root: Option<&[u8]>) -> Result<SigningKey, Error> {
+    let e = SECP256K1.get();
+    let d = e.new_scalar_from_bytes(&sk.to_bytes(), Endianness::Big);
+    let d = if has_even_y(&(e.get_generator() * &d)) {
+        d
+    } else {
+        -d
+    };
+    let t = tweak_scalar(&e, &tap_tweak(sk.verifying_key(), merkle_root))?;
+    SigningKey::from_bytes(&(d + t).to_bytes_be())
+}
+
+fn tweak_scalar(e: &Curve, tweak: &[u8]) -> Result<Scalar, Error> {
+    let t = BigUint::from_bytes_be(tweak);
+    if t >= e.get_order() {
+        return Err(Error::other(ERR_TWEAK));
+    }
+    Ok(e.new_scalar(t.into()))
+}
+
+const TAG_TWEAK: &[u8] = b"TapTweak";
+const ERR_TWEAK: &str = "tweak must be less than the order";
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use super::{tap_tweak, tweak_public_key, tweak_secret_key};
+    use crate::schnorr::{SigningKey, VerifyingKey};
+
+    // Test vectors taken from BIP-341, wallet-test-vectors.json.
+    struct Vector {
+        internal_key: &'static str,
+        merkle_root: Option<&'static str>,
+        tweak: &'static str,
+        output_key: &'static str,
+    }
+
+    #[test]
+    fn bip341_vectors() {
+        let vectors = [
+            Vector {
+                internal_key: "d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d",
+                merkle_root: None,
+                tweak: "b86e7be8f39bab32a6f2c0443abbc210f0edac0e2c53d501b36b64437d9c6c70",
+                output_key: "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343",
+            },
+            Vector {
+                internal_key: "187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+                merkle_root: Some(
+                    "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21",
+                ),
+                tweak: "cbd8679ba636c1110ea247542cfbd964131a6be84f873f7f3b62a777528ed001",
+                output_key: "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3",
+            },
+        ];
+        for v in vectors.iter() {
+            let internal_key =
+                VerifyingKey::from_bytes(&hex::decode(v.internal_key).unwrap()).unwrap();
+            let root = v.merkle_root.map(|r| hex::decode(r).unwrap());
+            let tweak = tap_tweak(&internal_key, root.as_deref());
+            assert_eq!(hex::encode(tweak), v.tweak);
+            let (output_key, _) = tweak_public_key(&internal_key, root.as_deref()).unwrap();
+            assert_eq!(hex::encode(output_key.to_bytes()), v.output_key);
+        }
+    }
+
+    #[test]
+    fn key_path() {
+        let root = [7u8; 32];
+        for _ in 0..4 {
+            let sk = SigningKey::random(&mut OsRng);
+            for root in [None, Some(&root[..])].iter() {
+                let (output_key, _) = tweak_public_key(sk.verifying_key(), *root).unwrap();
+                let tweaked = tweak_secret_key(&sk, *root).unwrap();
+                assert_eq!(tweaked.verifying_key().to_bytes(), output_key.to_bytes());
+                let sig = tweaked.sign(b"transaction", &mut OsRng);
+                assert!(output_key.verify(b"transaction", &sig).is_ok());
+                assert!(sk.verifying_key().verify(b"transaction", &sig).is_err());
+            }
+        }
+    }
+}
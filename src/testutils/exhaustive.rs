@@ -0,0 +1,279 @@
+//! This is documentation for the `exhaustive` module.
+//!
+//! The exhaustive module checks curves over small fields by brute force:
+//! it enumerates every point, verifies the order and cofactor of the curve,
+//! computes the structure of the group, and compares the addition of every
+//! pair of points against the textbook affine formulas. This catches
+//! exceptional cases of projective formulas, such as doubling or adding
+//! points of small order, which random multiples of the generator rarely
+//! hit. A toy curve is provided for each model.
+//!
+//! # Checking a Toy Curve
+//!
+//! ```
+//!  use redox_ecc::edwards::Curve;
+//!  use redox_ecc::testutils::{check_small_curve, TOY_EDWARDS};
+//!
+//!  let group = check_small_curve(&Curve::from(&TOY_EDWARDS));
+//!  assert_eq!((group.n1, group.n2), (1, 92));
+//! ```
+
+use num_bigint::BigInt;
+use num_traits::identities::Zero;
+use num_traits::ToPrimitive;
+
+use crate::edwards;
+use crate::ellipticcurve::EllipticCurve;
+use crate::field::Field;
+use crate::int;
+use crate::montgomery;
+use crate::primefield::{Fp, FpElt};
+use crate::weierstrass;
+
+/// Affine is a point given by its affine coordinates, or None for the point
+/// at infinity.
+pub type Affine = Option<(FpElt, FpElt)>;
+
+/// SmallCurve is a curve whose points can be enumerated, together with a
+/// naive implementation of its group law in affine coordinates.
+pub trait SmallCurve: EllipticCurve<F = Fp> {
+    /// Returns all points of the curve, including the identity.
+    fn affine_points(&self) -> Vec<Affine>;
+    /// Adds two points with the affine formulas of the model.
+    fn naive_add(&self, p: &Affine, q: &Affine) -> Affine;
+    /// Converts an affine point into a point of the curve.
+    fn to_point(&self, p: &Affine) -> Self::Point;
+}
+
+/// GroupStructure is the decomposition of the group of points of a curve as
+/// Z/n1 x Z/n2, where n1 divides n2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupStructure {
+    pub order: usize,
+    pub n1: usize,
+    pub n2: usize,
+}
+
+/// Checks a small curve exhaustively and returns the structure of its
+/// group. It panics if the number of points is not the product of the order
+/// and the cofactor of the curve, if the generator does not have the order
+/// of the curve, or if the curve adds a pair of points differently from the
+/// affine formulas.
+pub fn check_small_curve<E: SmallCurve>(e: &E) -> GroupStructure {
+    let points = e.affine_points();
+    let r = e.get_order().to_usize().unwrap();
+    let h = e.get_cofactor().to_usize().unwrap();
+    assert_eq!(points.len(), r * h, "#E != r*h");
+
+    let mut orders = Vec::with_capacity(points.len());
+    for p in &points {
+        let mut q = p.clone();
+        let mut n = 1;
+        while e.to_point(&q) != e.identity() {
+            q = e.naive_add(&q, p);
+            n += 1;
+        }
+        orders.push(n);
+    }
+    let g = e.get_generator();
+    let i = points.iter().position(|p| e.to_point(p) == g).unwrap();
+    assert_eq!(orders[i], r, "the order of the generator is not r");
+
+    let point = |p: &Affine| e.to_point(p);
+    for p in &points {
+        for q in &points {
+            let want = point(&e.naive_add(p, q));
+            let got = point(p) + point(q);
+            assert!(got == want, "P+Q for P={:?} Q={:?}", show(p), show(q));
+        }
+    }
+
+    // the group is Z/n1 x Z/n2, where n2 is the largest order of a point
+    let n2 = *orders.iter().max().unwrap();
+    GroupStructure {
+        order: points.len(),
+        n1: points.len() / n2,
+        n2,
+    }
+}
+
+/// Returns the points (x,y) such that `eq(x,y)` is zero, by trying every
+/// pair of elements of the field.
+fn solutions(f: &Fp, eq: impl Fn(&FpElt, &FpElt) -> FpElt) -> Vec<(FpElt, FpElt)> {
+    let p = f.get_modulus();
+    assert!(p.bits() <= 16, "the field is too large to be enumerated");
+    let elts: Vec<FpElt> = (0..p.to_u32().unwrap())
+        .map(|i| f.elt(BigInt::from(i)))
+        .collect();
+    let mut points = Vec::new();
+    for x in &elts {
+        for y in &elts {
+            if eq(x, y).is_zero() {
+                points.push((x.clone(), y.clone()));
+            }
+        }
+    }
+    points
+}
+
+fn show(p: &Affine) -> String {
+    match p {
+        Some((x, y)) => format!("({},{})", x, y),
+        None => "O".to_string(),
+    }
+}
+
+impl SmallCurve for weierstrass::Curve {
+    fn affine_points(&self) -> Vec<Affine> {
+        let f = self.get_field();
+        let eq = |x: &FpElt, y: &FpElt| y * y - x * x * x - &self.a * x - &self.b;
+        let mut points = vec![None];
+        points.extend(solutions(&f, eq).into_iter().map(Some));
+        points
+    }
+    fn naive_add(&self, p: &Affine, q: &Affine) -> Affine {
+        let f = self.get_field();
+        let ((x1, y1), (x2, y2)) = match (p, q) {
+            (None, _) => return q.clone(),
+            (_, None) => return p.clone(),
+            (Some(p), Some(q)) => (p, q),
+        };
+        if x1 == x2 && (y1 + y2).is_zero() {
+            return None;
+        }
+        let l = if x1 == x2 {
+            (f.elt(3.into()) * x1 * x1 + &self.a) / (y1 + y1)
+        } else {
+            (y2 - y1) / (x2 - x1)
+        };
+        let x3 = &l * &l - x1 - x2;
+        let y3 = l * (x1 - &x3) - y1;
+        Some((x3, y3))
+    }
+    fn to_point(&self, p: &Affine) -> Self::Point {
+        match p {
+            Some((x, y)) => self.new_point(x.clone(), y.clone()),
+            None => self.identity(),
+        }
+    }
+}
+
+impl SmallCurve for montgomery::Curve {
+    fn affine_points(&self) -> Vec<Affine> {
+        let f = self.get_field();
+        let eq = |x: &FpElt, y: &FpElt| &self.b * y * y - x * x * x - &self.a * x * x - x;
+        let mut points = vec![None];
+        points.extend(solutions(&f, eq).into_iter().map(Some));
+        points
+    }
+    fn naive_add(&self, p: &Affine, q: &Affine) -> Affine {
+        let f = self.get_field();
+        let ((x1, y1), (x2, y2)) = match (p, q) {
+            (None, _) => return q.clone(),
+            (_, None) => return p.clone(),
+            (Some(p), Some(q)) => (p, q),
+        };
+        if x1 == x2 && (y1 + y2).is_zero() {
+            return None;
+        }
+        let l = if x1 == x2 {
+            let num = f.elt(3.into()) * x1 * x1 + f.elt(2.into()) * &self.a * x1 + f.one();
+            num / (f.elt(2.into()) * &self.b * y1)
+        } else {
+            (y2 - y1) / (x2 - x1)
+        };
+        let x3 = &self.b * &l * &l - &self.a - x1 - x2;
+        let y3 = l * (x1 - &x3) - y1;
+        Some((x3, y3))
+    }
+    fn to_point(&self, p: &Affine) -> Self::Point {
+        match p {
+            Some((x, y)) => self.new_point(x.clone(), y.clone()),
+            None => self.identity(),
+        }
+    }
+}
+
+impl SmallCurve for edwards::Curve {
+    fn affine_points(&self) -> Vec<Affine> {
+        let f = self.get_field();
+        let eq = |x: &FpElt, y: &FpElt| {
+            let (xx, yy) = (x * x, y * y);
+            &self.a * &xx + &yy - f.one() - &self.d * xx * yy
+        };
+        solutions(&f, eq).into_iter().map(Some).collect()
+    }
+    /// Adds two points with the affine formulas, which are complete if `a`
+    /// is a square and `d` is not.
+    fn naive_add(&self, p: &Affine, q: &Affine) -> Affine {
+        let f = self.get_field();
+        let ((x1, y1), (x2, y2)) = match (p, q) {
+            (Some(p), Some(q)) => (p, q),
+            _ => panic!("Edwards curves have no point at infinity"),
+        };
+        let t = &self.d * x1 * x2 * y1 * y2;
+        let x3 = (x1 * y2 + y1 * x2) / (f.one() + &t);
+        let y3 = (y1 * y2 - &self.a * x1 * x2) / (f.one() - t);
+        Some((x3, y3))
+    }
+    fn to_point(&self, p: &Affine) -> Self::Point {
+        let (x, y) = p.clone().expect("Edwards curves have no point at infinity");
+        self.new_point(x, y)
+    }
+}
+
+/// TOY_WEIERSTRASS is the curve y^2=x^3+3x+11 over GF(109), whose group is
+/// Z/3 x Z/39.
+pub static TOY_WEIERSTRASS: weierstrass::Params = weierstrass::Params {
+    name: "toy-weierstrass",
+    p: int!("109"),
+    a: int!("3"),
+    b: int!("11"),
+    r: int!("13"),
+    h: int!("9"),
+    gx: int!("29"),
+    gy: int!("92"),
+};
+
+/// TOY_MONTGOMERY is the curve y^2=x^3+6x^2+x over GF(101), whose group is
+/// cyclic of order 104.
+pub static TOY_MONTGOMERY: montgomery::Params = montgomery::Params {
+    name: "toy-montgomery",
+    p: int!("101"),
+    a: int!("6"),
+    b: int!("1"),
+    s: int!("1"),
+    r: int!("13"),
+    h: int!("8"),
+    gx: int!("14"),
+    gy: int!("46"),
+};
+
+/// TOY_EDWARDS is the curve x^2+y^2=1+27x^2y^2 over GF(101), whose group is
+/// cyclic of order 92.
+pub static TOY_EDWARDS: edwards::Params = edwards::Params {
+    name: "toy-edwards",
+    p: int!("101"),
+    a: int!("1"),
+    d: int!("27"),
+    r: int!("23"),
+    h: int!("4"),
+    gx: int!("44"),
+    gy: int!("76"),
+};
+
+#[cfg(test)]
+mod tests {
+    use super::{check_small_curve, TOY_EDWARDS, TOY_MONTGOMERY, TOY_WEIERSTRASS};
+    use crate::{edwards, montgomery, weierstrass};
+
+    #[test]
+    fn toy_curves() {
+        let group = check_small_curve(&weierstrass::Curve::from(&TOY_WEIERSTRASS));
+        assert_eq!((group.order, group.n1, group.n2), (117, 3, 39));
+        let group = check_small_curve(&montgomery::Curve::from(&TOY_MONTGOMERY));
+        assert_eq!((group.order, group.n1, group.n2), (104, 1, 104));
+        let group = check_small_curve(&edwards::Curve::from(&TOY_EDWARDS));
+        assert_eq!((group.order, group.n1, group.n2), (92, 1, 92));
+    }
+}
@@ -0,0 +1,157 @@
+//! This is documentation for the `testutils` module.
+//!
+//! The testutils module provides property-based tests for implementations
+//! of `EllipticCurve`, so a new instance, or a curve defined outside of the
+//! crate, can be validated with a single call. It is only available with
+//! the `test-utils` feature.
+//!
+//! Points are random multiples of the generator, and the properties are
+//! checked with `proptest`, which shrinks a failing case to a small scalar
+//! before reporting it. The following properties are checked:
+//!
+//! - associativity and commutativity of the addition,
+//! - the identity and the inverses of points,
+//! - the order of the curve annihilates every point, and multiplying by a
+//!   sum of scalars distributes over the addition,
+//! - decoding the encoding of a point, compressed or not, returns the point.
+//!
+//! Curves over small fields can also be checked exhaustively with
+//! `check_small_curve`, which compares every addition against the affine
+//! formulas of the model.
+//!
+//! # Validating a Curve
+//!
+//! ```
+//!  use proptest::test_runner::Config;
+//!  use redox_ecc::instances::{GetCurve, P256};
+//!  use redox_ecc::testutils;
+//!
+//!  testutils::check_curve_with(&P256.get(), Config::with_cases(4));
+//! ```
+
+use num_bigint::{BigInt, Sign};
+use proptest::collection::vec;
+use proptest::prelude::any;
+use proptest::strategy::Strategy;
+use proptest::test_runner::{Config, TestCaseError, TestCaseResult, TestRunner};
+
+use crate::ellipticcurve::{Decode, EllipticCurve, Encode, POINT_FORMATS};
+
+mod exhaustive;
+pub use crate::testutils::exhaustive::{
+    check_small_curve, Affine, GroupStructure, SmallCurve, TOY_EDWARDS, TOY_MONTGOMERY,
+    TOY_WEIERSTRASS,
+};
+
+/// Checks the properties of the group law of `e` on 256 random cases, or
+/// on as many as the `PROPTEST_CASES` environment variable says. It panics
+/// with the smallest failing case if a property does not hold.
+pub fn check_curve<E>(e: &E)
+where
+    E: EllipticCurve + Decode<Deser = <E as EllipticCurve>::Point>,
+    E::Point: Clone,
+    E::Scalar: Clone,
+{
+    check_curve_with(e, Config::default())
+}
+
+/// Checks the properties of the group law of `e` with the given
+/// configuration of `proptest`, which sets the number of cases among
+/// others.
+pub fn check_curve_with<E>(e: &E, config: Config)
+where
+    E: EllipticCurve + Decode<Deser = <E as EllipticCurve>::Point>,
+    E::Point: Clone,
+    E::Scalar: Clone,
+{
+    let run = |name: &str, test: &dyn Fn(&[Vec<u8>]) -> TestCaseResult| {
+        let mut runner = TestRunner::new(config.clone());
+        if let Err(err) = runner.run(&scalars(e, 3), |k| test(&k)) {
+            panic!("{}: {}", name, err);
+        }
+    };
+    run("associativity", &|k| {
+        let (p, q, r) = (point(e, &k[0]), point(e, &k[1]), point(e, &k[2]));
+        let lhs = (p.clone() + &q) + &r;
+        let rhs = p + &(q + &r);
+        check(lhs == rhs, "(P+Q)+R != P+(Q+R)")
+    });
+    run("commutativity", &|k| {
+        let (p, q) = (point(e, &k[0]), point(e, &k[1]));
+        check(p.clone() + &q == q + &p, "P+Q != Q+P")
+    });
+    run("identity", &|k| {
+        let p = point(e, &k[0]);
+        check(p.clone() + &e.identity() == p, "P+O != P")?;
+        check(e.identity() + &p == p, "O+P != P")
+    });
+    run("inverse", &|k| {
+        let p = point(e, &k[0]);
+        check(p.clone() + &(-p.clone()) == e.identity(), "P+(-P) != O")?;
+        check(p.clone() - &p == e.identity(), "P-P != O")
+    });
+    run("order", &|k| {
+        // scalars are reduced modulo the order r, so [r]P is [r-1]P + P
+        let p = point(e, &k[0]);
+        let r_minus_1 = e.new_scalar(BigInt::from(-1));
+        check(p.clone() * &r_minus_1 + &p == e.identity(), "[r]P != O")?;
+        let (a, b) = (scalar(e, &k[1]), scalar(e, &k[2]));
+        let lhs = p.clone() * &a + &(p.clone() * &b);
+        check(lhs == p * &(a + &b), "[a]P+[b]P != [a+b]P")
+    });
+    run("codec", &|k| {
+        let p = point(e, &k[0]);
+        for &format in POINT_FORMATS.iter() {
+            let buf = p.encode(format);
+            let q = e.decode(&buf).map_err(|err| fail(&err.to_string()))?;
+            check(q == p, "decode(encode(P)) != P")?;
+        }
+        Ok(())
+    });
+}
+
+/// Returns a strategy for `n` integers given as big-endian bytes, which are
+/// longer than the order of `e`, so that their residues are uniform. Short
+/// strings are also drawn, since small scalars often hit corner cases.
+fn scalars<E: EllipticCurve>(e: &E, n: usize) -> impl Strategy<Value = Vec<Vec<u8>>> {
+    let size = e.get_order().bits().div_ceil(8) + 8;
+    vec(vec(any::<u8>(), 0..=size), n)
+}
+
+fn scalar<E: EllipticCurve>(e: &E, k: &[u8]) -> E::Scalar {
+    e.new_scalar(BigInt::from_bytes_be(Sign::Plus, k))
+}
+
+fn point<E: EllipticCurve>(e: &E, k: &[u8]) -> E::Point {
+    e.get_generator() * &scalar(e, k)
+}
+
+fn check(cond: bool, msg: &str) -> TestCaseResult {
+    if cond {
+        Ok(())
+    } else {
+        Err(fail(msg))
+    }
+}
+
+fn fail(msg: &str) -> TestCaseError {
+    TestCaseError::fail(msg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::test_runner::Config;
+
+    use super::check_curve_with;
+    use crate::instances::{GetCurve, BLS12381G2, CURVE25519, EDWARDS448, P256, SECP256K1};
+
+    #[test]
+    fn instances() {
+        let config = Config::with_cases(4);
+        check_curve_with(&P256.get(), config.clone());
+        check_curve_with(&SECP256K1.get(), config.clone());
+        check_curve_with(&EDWARDS448.get(), config.clone());
+        check_curve_with(&CURVE25519.get(), config.clone());
+        check_curve_with(&BLS12381G2.get(), config);
+    }
+}
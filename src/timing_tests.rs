@@ -0,0 +1,149 @@
+//! This is documentation for the `timing_tests` module.
+//!
+//! The timing_tests module is meant to be used for catching timing leaks
+//! with the method of dudect (Reparaz, Balasch, and Verbauwhede, "Dude, is
+//! my code constant time?", DATE 2017). An operation is timed many times
+//! over two classes of inputs: a fixed secret and fresh random secrets,
+//! interleaved at random. Then, Welch's t-test decides whether both timing
+//! distributions have the same mean.
+//!
+//! These tests take a while and depend on the load of the machine, so they
+//! only run with the `timing_tests` feature:
+//!
+//! ```text
+//!  cargo test --release --features timing_tests timing_tests
+//! ```
+
+use num_bigint::{BigInt, Sign};
+use rand_core::{OsRng, RngCore};
+
+use std::time::Instant;
+
+use crate::ellipticcurve::{EllipticCurve, Encode, PointFormat};
+use crate::field::Field;
+use crate::instances::{GetCurve, CURVE25519, P256};
+use crate::ops::FromFactory;
+
+/// Number of measurements taken for each operation.
+const SAMPLES: usize = 20_000;
+/// Fraction of the slowest measurements that are discarded, since they are
+/// mostly caused by interrupts and context switches.
+const CROP: f64 = 0.05;
+/// Values of t above this threshold are evidence of a timing leak; dudect
+/// reports leaks beyond 4.5 and definite leaks beyond 10.
+const THRESHOLD: f64 = 10.0;
+
+/// Welch accumulates the mean and the variance of the timings of both
+/// classes online, with the method of Welford.
+#[derive(Default)]
+struct Welch {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl Welch {
+    fn push(&mut self, class: usize, x: f64) {
+        self.n[class] += 1.0;
+        let delta = x - self.mean[class];
+        self.mean[class] += delta / self.n[class];
+        self.m2[class] += delta * (x - self.mean[class]);
+    }
+    /// Returns the t statistic of Welch's test.
+    fn t(&self) -> f64 {
+        let var0 = self.m2[0] / (self.n[0] - 1.0);
+        let var1 = self.m2[1] / (self.n[1] - 1.0);
+        let den = (var0 / self.n[0] + var1 / self.n[1]).sqrt();
+        (self.mean[0] - self.mean[1]) / den
+    }
+}
+
+/// Returns the t statistic of the timings of `op` over the inputs produced
+/// by `gen`. The input of class 0 is fixed and the input of class 1 is
+/// random; all of them are generated before timing starts.
+fn leakage<I, G, F>(gen: G, op: F) -> f64
+where
+    G: Fn(usize) -> I,
+    F: Fn(&I),
+{
+    let inputs: Vec<(usize, I)> = (0..SAMPLES)
+        .map(|_| {
+            let class = (OsRng.next_u32() & 1) as usize;
+            (class, gen(class))
+        })
+        .collect();
+    let timings: Vec<(usize, f64)> = inputs
+        .iter()
+        .map(|(class, input)| {
+            let start = Instant::now();
+            op(input);
+            (*class, start.elapsed().as_nanos() as f64)
+        })
+        .collect();
+    let mut sorted: Vec<f64> = timings.iter().map(|&(_, x)| x).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let cutoff = sorted[((1.0 - CROP) * SAMPLES as f64) as usize];
+    let mut w = Welch::default();
+    for (class, x) in timings.into_iter().filter(|&(_, x)| x < cutoff) {
+        w.push(class, x);
+    }
+    w.t()
+}
+
+fn random_bigint(bytes: usize) -> BigInt {
+    let mut buf = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut buf);
+    BigInt::from_bytes_be(Sign::Plus, &buf)
+}
+
+#[test]
+fn field_inversion() {
+    let f = CURVE25519.get().get_field();
+    let fixed = f.from("0x5a9eb2c4d1f8370e6b4a2d9c8e1f07b3a6c5d4e3f2a1b0c9d8e7f6a5b4c3d2e1");
+    let t = leakage(
+        |class| match class {
+            0 => fixed.clone(),
+            _ => f.elt(random_bigint(32)),
+        },
+        |x| {
+            let _ = 1u32 / x;
+        },
+    );
+    assert!(t.abs() < THRESHOLD, "field inversion: t = {:.2}", t);
+}
+
+#[test]
+fn scalar_multiplication() {
+    let ec = P256.get();
+    let g = ec.get_generator();
+    let fixed = ec.new_scalar(BigInt::from(1));
+    let t = leakage(
+        |class| match class {
+            0 => fixed.clone(),
+            _ => ec.new_scalar(random_bigint(32)),
+        },
+        |k| {
+            let _ = g.mul_blinded(k, &mut OsRng);
+        },
+    );
+    assert!(t.abs() < THRESHOLD, "scalar multiplication: t = {:.2}", t);
+}
+
+#[test]
+fn decompression() {
+    let ec = CURVE25519.get();
+    let g = ec.get_generator();
+    // the integers behind field elements are not padded, so the fixed point
+    // must have a full-width x-coordinate, unlike the generator
+    let fixed = (&g * &ec.new_scalar(random_bigint(32))).encode(PointFormat::Compressed);
+    let t = leakage(
+        |class| match class {
+            0 => fixed.clone(),
+            _ => (&g * &ec.new_scalar(random_bigint(32))).encode(PointFormat::Compressed),
+        },
+        |buf| {
+            let _ = ec.decode_ct(buf);
+        },
+    );
+    assert!(t.abs() < THRESHOLD, "decompression: t = {:.2}", t);
+}
@@ -0,0 +1,141 @@
+//! This is documentation for the `transcript` module.
+//!
+//! The transcript module implements transcripts for the Fiat-Shamir
+//! transform in the style of Merlin: the prover and the verifier of an
+//! interactive protocol append the same labeled messages to a transcript,
+//! and derive the challenges from it instead of receiving them from each
+//! other.
+//!
+//! Every message is framed with its label and its length, so distinct
+//! sequences of messages never produce the same transcript. Every challenge
+//! is also appended back, so later challenges depend on earlier ones.
+//!
+//! # Proving Knowledge of a Discrete Logarithm
+//!
+//! ```
+//!  use redox_ecc::group::{PrimeGroup, Ristretto255};
+//!  use redox_ecc::transcript::Transcript;
+//!
+//!  let g = Ristretto255::new();
+//!  let (x, r) = (g.new_scalar(5.into()), g.new_scalar(7.into()));
+//!  let (p, t) = (g.generator() * &x, g.generator() * &r);
+//!  let mut prover = Transcript::new(b"schnorr proof");
+//!  prover.append_point(&g, b"P", &p);
+//!  prover.append_point(&g, b"T", &t);
+//!  let c = prover.challenge_scalar(&g, b"c");
+//!  let s = r + c * x;
+//!
+//!  let mut verifier = Transcript::new(b"schnorr proof");
+//!  verifier.append_point(&g, b"P", &p);
+//!  verifier.append_point(&g, b"T", &t);
+//!  let c = verifier.challenge_scalar(&g, b"c");
+//!  assert!(g.generator() * s == t + p * c);
+//! ```
+
+use sha2::{Digest, Sha512};
+
+use crate::group::PrimeGroup;
+use crate::h2c::{Dst, Expander, ExpanderXmd};
+
+/// Transcript is the record of the messages of a protocol, from which the
+/// challenges are derived.
+#[derive(Clone)]
+pub struct Transcript {
+    h: Sha512,
+}
+
+impl Transcript {
+    /// Returns a transcript for the protocol identified by `label`, which
+    /// separates its challenges from those of any other protocol.
+    pub fn new(label: &[u8]) -> Transcript {
+        let mut t = Transcript { h: Sha512::new() };
+        t.append_message(b"dom-sep", label);
+        t
+    }
+    /// Appends a labeled message.
+    pub fn append_message(&mut self, label: &[u8], msg: &[u8]) {
+        self.h.update((label.len() as u64).to_be_bytes());
+        self.h.update(label);
+        self.h.update((msg.len() as u64).to_be_bytes());
+        self.h.update(msg);
+    }
+    /// Appends the canonical encoding of a group element.
+    pub fn append_point<G: PrimeGroup>(&mut self, g: &G, label: &[u8], p: &G::Elt) {
+        self.append_message(label, &g.encode(p));
+    }
+    /// Appends the canonical encoding of a scalar.
+    pub fn append_scalar<G: PrimeGroup>(&mut self, g: &G, label: &[u8], k: &G::Scalar) {
+        self.append_message(label, &g.encode_scalar(k));
+    }
+    /// Returns `len` bytes derived from the transcript. It panics if `len`
+    /// is larger than 16320, the limit of the underlying expander.
+    pub fn challenge_bytes(&mut self, label: &[u8], len: usize) -> Vec<u8> {
+        self.append_message(label, &(len as u64).to_be_bytes());
+        let seed = self.h.clone().finalize();
+        let out = ExpanderXmd::<Sha512>::new(&dst()).expand(&seed, len);
+        self.append_message(label, &out);
+        out
+    }
+    /// Returns a scalar derived from the transcript with the hash to scalar
+    /// function of the group.
+    pub fn challenge_scalar<G: PrimeGroup>(&mut self, g: &G, label: &[u8]) -> G::Scalar {
+        self.append_message(label, b"scalar");
+        let seed = self.h.clone().finalize();
+        let k = g.hash_to_scalar(&seed, &dst());
+        self.append_scalar(g, label, &k);
+        k
+    }
+}
+
+fn dst() -> Dst {
+    Dst::new(TRANSCRIPT_DST).unwrap()
+}
+
+const TRANSCRIPT_DST: &[u8] = b"REDOX-ECC-TRANSCRIPT-V01";
+
+#[cfg(test)]
+mod tests {
+    use super::Transcript;
+    use crate::group::{PrimeGroup, WeGroup};
+    use crate::h2c::P256_XMDSHA256_SSWU_RO_;
+
+    #[test]
+    fn challenges() {
+        let g = WeGroup::new(P256_XMDSHA256_SSWU_RO_);
+        let new = |label: &[u8], msgs: &[(&[u8], &[u8])]| {
+            let mut t = Transcript::new(label);
+            for (l, m) in msgs.iter() {
+                t.append_message(l, m);
+            }
+            t
+        };
+        let c = |mut t: Transcript| t.challenge_bytes(b"c", 32);
+        let base = new(b"proto", &[(b"a", b"bc")]);
+        assert_eq!(c(base.clone()), c(new(b"proto", &[(b"a", b"bc")])));
+        assert_ne!(c(base.clone()), c(new(b"other", &[(b"a", b"bc")])));
+        assert_ne!(c(base.clone()), c(new(b"proto", &[(b"ab", b"c")])));
+        assert_ne!(
+            c(base.clone()),
+            c(new(b"proto", &[(b"a", b"b"), (b"", b"c")]))
+        );
+
+        // every challenge updates the transcript
+        let mut t = base.clone();
+        let c0 = t.challenge_bytes(b"c", 32);
+        let c1 = t.challenge_bytes(b"c", 32);
+        assert_ne!(c0, c1);
+        assert_eq!(t.challenge_bytes(b"c", 100).len(), 100);
+
+        let mut t0 = base.clone();
+        let mut t1 = base;
+        t0.append_point(&g, b"G", &g.generator());
+        t1.append_point(&g, b"G", &g.generator());
+        let k0 = t0.challenge_scalar(&g, b"k");
+        let k1 = t1.challenge_scalar(&g, b"k");
+        assert_eq!(g.encode_scalar(&k0), g.encode_scalar(&k1));
+        t0.append_scalar(&g, b"k", &k0);
+        t1.append_scalar(&g, b"k", &k0);
+        let k0 = t0.challenge_scalar(&g, b"k");
+        assert_ne!(g.encode_scalar(&k0), g.encode_scalar(&k1));
+    }
+}
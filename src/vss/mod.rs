@@ -0,0 +1,632 @@
+//! This is documentation for the `vss` module.
+//!
+//! The vss module implements Shamir's secret sharing over the scalars of a
+//! prime-order group, its verifiable variants by Feldman and by Pedersen,
+//! and the distributed key generation protocol of Pedersen, where every
+//! participant deals a random secret with Feldman's scheme and the group
+//! secret is the sum of all of them.
+//!
+//! A sharing with threshold `t` splits a secret among `n` participants, so
+//! that any `t` shares reconstruct it whereas fewer shares reveal nothing.
+//! Participants are identified by their indices, which go from 1 to `n`.
+//!
+//! # Generating a Key
+//!
+//! ```
+//!  use rand_core::OsRng;
+//!  use redox_ecc::group::{PrimeGroup, Ristretto255};
+//!  use redox_ecc::vss::Participant;
+//!
+//!  let g = Ristretto255::new();
+//!  let (mut alice, msg_a) = Participant::new(g.clone(), 1, 2, 2, &mut OsRng).unwrap();
+//!  let (mut bob, msg_b) = Participant::new(g.clone(), 2, 2, 2, &mut OsRng).unwrap();
+//!  alice.receive(&msg_b, &bob.share_for(1).unwrap()).unwrap();
+//!  bob.receive(&msg_a, &alice.share_for(2).unwrap()).unwrap();
+//!  let (ka, kb) = (alice.finish().unwrap(), bob.finish().unwrap());
+//!  assert!(ka.group_key == kb.group_key);
+//! ```
+
+use num_bigint::BigInt;
+use rand_core::{CryptoRng, RngCore};
+
+use std::io::Error;
+
+use crate::group::PrimeGroup;
+use crate::h2c::Dst;
+
+/// Share is the evaluation of a sharing polynomial at the index of a
+/// participant.
+pub struct Share<G: PrimeGroup> {
+    pub index: u32,
+    pub value: G::Scalar,
+}
+
+impl<G: PrimeGroup> Clone for Share<G> {
+    fn clone(&self) -> Self {
+        Share {
+            index: self.index,
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<G: PrimeGroup> Share<G> {
+    /// Parses a share encoded as its index in four bytes followed by its
+    /// value.
+    pub fn from_bytes(g: &G, buf: &[u8]) -> Result<Share<G>, Error> {
+        if buf.len() < INDEX_SIZE {
+            return Err(Error::other(ERR_ENCODING));
+        }
+        let (index, value) = buf.split_at(INDEX_SIZE);
+        let index = u32::from_be_bytes([index[0], index[1], index[2], index[3]]);
+        if index == 0 {
+            return Err(Error::other(ERR_INDEX));
+        }
+        let value = g.decode_scalar(value)?;
+        Ok(Share { index, value })
+    }
+    pub fn to_bytes(&self, g: &G) -> Vec<u8> {
+        let mut out = self.index.to_be_bytes().to_vec();
+        out.append(&mut g.encode_scalar(&self.value));
+        out
+    }
+}
+
+/// Polynomial is a polynomial over the scalars, whose coefficients are
+/// ordered from the constant term.
+struct Polynomial<G: PrimeGroup>(Vec<G::Scalar>);
+
+impl<G: PrimeGroup> Polynomial<G> {
+    /// Returns a random polynomial of degree `t - 1` with the given constant
+    /// term.
+    fn random<R: RngCore + CryptoRng>(
+        g: &G,
+        constant: &G::Scalar,
+        t: usize,
+        rng: &mut R,
+    ) -> Polynomial<G> {
+        let mut coefs = vec![constant.clone()];
+        coefs.extend((1..t).map(|_| g.random_scalar(rng)));
+        Polynomial(coefs)
+    }
+    /// Evaluates the polynomial at `index` using Horner's rule.
+    fn eval(&self, g: &G, index: u32) -> G::Scalar {
+        let x = g.new_scalar(BigInt::from(index));
+        self.0
+            .iter()
+            .rev()
+            .fold(g.new_scalar(BigInt::from(0)), |acc, a| acc * &x + a)
+    }
+    /// Returns the Feldman commitment `[a_j]G` to every coefficient `a_j`.
+    fn commit(&self, g: &G) -> FeldmanCommitment<G> {
+        FeldmanCommitment {
+            coefficients: self.0.iter().map(|a| g.generator() * a).collect(),
+        }
+    }
+}
+
+/// Splits `secret` into `n` shares, so that any `t` of them reconstruct it.
+/// The coefficients of the sharing polynomial are sampled from `rng`.
+pub fn split<G: PrimeGroup, R: RngCore + CryptoRng>(
+    g: &G,
+    secret: &G::Scalar,
+    t: usize,
+    n: usize,
+    rng: &mut R,
+) -> Result<Vec<Share<G>>, Error> {
+    check_threshold(t, n)?;
+    let poly = Polynomial::random(g, secret, t, rng);
+    Ok(shares(g, &poly, n))
+}
+
+/// Reconstructs the secret from at least `t` shares with distinct indices
+/// by interpolating the sharing polynomial at zero.
+pub fn reconstruct<G: PrimeGroup>(g: &G, shares: &[Share<G>]) -> Result<G::Scalar, Error> {
+    let indices: Vec<u32> = shares.iter().map(|s| s.index).collect();
+    let mut secret = g.new_scalar(BigInt::from(0));
+    for s in shares.iter() {
+        secret = secret + lagrange_at_zero(g, s.index, &indices)? * &s.value;
+    }
+    Ok(secret)
+}
+
+/// Returns the Lagrange coefficient at zero of the participant `i` among
+/// the participants in `indices`, which must be distinct and non-zero.
+fn lagrange_at_zero<G: PrimeGroup>(g: &G, i: u32, indices: &[u32]) -> Result<G::Scalar, Error> {
+    let mut num = g.new_scalar(BigInt::from(1));
+    let mut den = g.new_scalar(BigInt::from(1));
+    let mut seen = false;
+    for &j in indices.iter() {
+        if j == 0 {
+            return Err(Error::other(ERR_INDEX));
+        }
+        if j == i {
+            if seen {
+                return Err(Error::other(ERR_DUPLICATE));
+            }
+            seen = true;
+            continue;
+        }
+        num = num * g.new_scalar(BigInt::from(j));
+        den = den * g.new_scalar(BigInt::from(j) - BigInt::from(i));
+    }
+    Ok(num / den)
+}
+
+/// PedersenVss is the verifiable secret sharing of Pedersen, where the
+/// commitments to the polynomial are perfectly hiding, so they reveal
+/// nothing about the secret.
+pub struct PedersenVss<G: PrimeGroup> {
+    g: G,
+    h: G::Elt,
+}
+
+impl<G: PrimeGroup> PedersenVss<G> {
+    /// Returns an instance of the scheme, whose second generator is derived
+    /// from `label`, so nobody knows its discrete logarithm.
+    pub fn new(g: G, label: &[u8]) -> PedersenVss<G> {
+        let h = g.hash_to_group(label, &Dst::new(PEDERSEN_DST).unwrap());
+        PedersenVss { g, h }
+    }
+    /// Splits `secret` into `n` shares with threshold `t`, blinding the
+    /// commitments with a random polynomial.
+    pub fn deal<R: RngCore + CryptoRng>(
+        &self,
+        secret: &G::Scalar,
+        t: usize,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<PedersenDealing<G>, Error> {
+        check_threshold(t, n)?;
+        let g = &self.g;
+        let a = Polynomial::random(g, secret, t, rng);
+        let b = Polynomial::random(g, &g.random_scalar(rng), t, rng);
+        let gens = [g.generator(), self.h.clone()];
+        let commitments = (0..t)
+            .map(|j| g.multi_scalar_mul(&gens, &[a.0[j].clone(), b.0[j].clone()]))
+            .collect();
+        let shares = shares(g, &a, n).into_iter().zip(shares(g, &b, n)).collect();
+        Ok(PedersenDealing {
+            commitments,
+            shares,
+        })
+    }
+    /// Verifies a share and its blinding share against the commitments.
+    pub fn verify(
+        &self,
+        commitments: &[G::Elt],
+        share: &Share<G>,
+        blinding: &Share<G>,
+    ) -> Result<(), Error> {
+        let g = &self.g;
+        if share.index != blinding.index {
+            return Err(Error::other(ERR_INDEX));
+        }
+        let lhs = g.multi_scalar_mul(
+            &[g.generator(), self.h.clone()],
+            &[share.value.clone(), blinding.value.clone()],
+        );
+        if lhs != eval_commitments(g, commitments, share.index) {
+            return Err(Error::other(ERR_SHARE));
+        }
+        Ok(())
+    }
+}
+
+/// PedersenDealing is the output of the dealer of Pedersen's scheme.
+pub struct PedersenDealing<G: PrimeGroup> {
+    /// Commitments `[a_j]G + [b_j]H` to the coefficients of the sharing
+    /// polynomial `a` and the blinding polynomial `b`, which are broadcast.
+    pub commitments: Vec<G::Elt>,
+    /// Shares of both polynomials for every participant, which are sent
+    /// over private channels.
+    pub shares: Vec<(Share<G>, Share<G>)>,
+}
+
+/// FeldmanCommitment is the commitment of Feldman's scheme to a sharing
+/// polynomial, i.e., the elements `[a_j]G` for its coefficients `a_j`. It
+/// verifies shares dealt by anyone, and reveals the public key `[a_0]G` of
+/// the shared secret.
+pub struct FeldmanCommitment<G: PrimeGroup> {
+    pub coefficients: Vec<G::Elt>,
+}
+
+impl<G: PrimeGroup> Clone for FeldmanCommitment<G> {
+    fn clone(&self) -> Self {
+        FeldmanCommitment {
+            coefficients: self.coefficients.clone(),
+        }
+    }
+}
+
+impl<G: PrimeGroup> FeldmanCommitment<G> {
+    /// Parses a commitment encoded as the concatenation of the encodings of
+    /// its elements.
+    pub fn from_bytes(g: &G, buf: &[u8]) -> Result<FeldmanCommitment<G>, Error> {
+        let size = g.encode(&g.generator()).len();
+        if buf.is_empty() || !buf.len().is_multiple_of(size) {
+            return Err(Error::other(ERR_ENCODING));
+        }
+        let coefficients = buf
+            .chunks(size)
+            .map(|c| g.decode(c))
+            .collect::<Result<Vec<G::Elt>, Error>>()?;
+        Ok(FeldmanCommitment { coefficients })
+    }
+    pub fn to_bytes(&self, g: &G) -> Vec<u8> {
+        let mut out = Vec::new();
+        for c in self.coefficients.iter() {
+            out.append(&mut g.encode(c));
+        }
+        out
+    }
+    /// Returns the threshold of the sharing, i.e., the number of
+    /// coefficients.
+    pub fn threshold(&self) -> usize {
+        self.coefficients.len()
+    }
+    /// Returns the public key `[a_0]G` of the shared secret.
+    pub fn public_key(&self) -> &G::Elt {
+        &self.coefficients[0]
+    }
+    /// Returns the public key `[s]G` matching the share `s` of the
+    /// participant `index`.
+    pub fn verification_key(&self, g: &G, index: u32) -> G::Elt {
+        eval_commitments(g, &self.coefficients, index)
+    }
+    /// Verifies that `share` is the evaluation of the committed polynomial
+    /// at `index`.
+    pub fn verify_share(&self, g: &G, index: u32, share: &G::Scalar) -> Result<(), Error> {
+        if index == 0 {
+            return Err(Error::other(ERR_INDEX));
+        }
+        if g.generator() * share != self.verification_key(g, index) {
+            return Err(Error::other(ERR_SHARE));
+        }
+        Ok(())
+    }
+}
+
+/// Splits `secret` into `n` shares with threshold `t` using Feldman's
+/// scheme, which also returns the commitment to the sharing polynomial.
+pub fn split_feldman<G: PrimeGroup, R: RngCore + CryptoRng>(
+    g: &G,
+    secret: &G::Scalar,
+    t: usize,
+    n: usize,
+    rng: &mut R,
+) -> Result<(Vec<Share<G>>, FeldmanCommitment<G>), Error> {
+    check_threshold(t, n)?;
+    let poly = Polynomial::random(g, secret, t, rng);
+    Ok((shares(g, &poly, n), poly.commit(g)))
+}
+
+/// Round1 is the message broadcast by a participant of the distributed key
+/// generation, i.e., the Feldman commitment to its sharing polynomial.
+pub struct Round1<G: PrimeGroup> {
+    pub sender: u32,
+    pub commitment: FeldmanCommitment<G>,
+}
+
+impl<G: PrimeGroup> Clone for Round1<G> {
+    fn clone(&self) -> Self {
+        Round1 {
+            sender: self.sender,
+            commitment: self.commitment.clone(),
+        }
+    }
+}
+
+impl<G: PrimeGroup> Round1<G> {
+    /// Parses a message encoded as the index of the sender in four bytes
+    /// followed by the encoding of the commitment.
+    pub fn from_bytes(g: &G, buf: &[u8]) -> Result<Round1<G>, Error> {
+        if buf.len() < INDEX_SIZE {
+            return Err(Error::other(ERR_ENCODING));
+        }
+        let (sender, commitment) = buf.split_at(INDEX_SIZE);
+        let sender = u32::from_be_bytes([sender[0], sender[1], sender[2], sender[3]]);
+        let commitment = FeldmanCommitment::from_bytes(g, commitment)?;
+        Ok(Round1 { sender, commitment })
+    }
+    pub fn to_bytes(&self, g: &G) -> Vec<u8> {
+        let mut out = self.sender.to_be_bytes().to_vec();
+        out.append(&mut self.commitment.to_bytes(g));
+        out
+    }
+    /// Verifies that `share` is the evaluation of the committed polynomial
+    /// at the index of the share.
+    pub fn verify_share(&self, g: &G, share: &Share<G>) -> Result<(), Error> {
+        self.commitment.verify_share(g, share.index, &share.value)
+    }
+}
+
+/// KeyShare is the output of the distributed key generation for one
+/// participant.
+pub struct KeyShare<G: PrimeGroup> {
+    /// Share of the group secret held by this participant.
+    pub share: Share<G>,
+    /// Public key of the group, whose secret is never known to anyone.
+    pub group_key: G::Elt,
+    /// Public key `[s_i]G` matching the share of each participant `i`,
+    /// ordered by index.
+    pub verification_keys: Vec<G::Elt>,
+}
+
+/// Participant is the state of a participant of the distributed key
+/// generation of Pedersen, which works in a single round: every
+/// participant broadcasts a `Round1` message, and sends privately to every
+/// other participant its share. A participant finishes once it has
+/// received and verified the messages of all the others.
+pub struct Participant<G: PrimeGroup> {
+    g: G,
+    index: u32,
+    t: usize,
+    n: usize,
+    poly: Polynomial<G>,
+    received: Vec<(Round1<G>, Share<G>)>,
+}
+
+impl<G: PrimeGroup> Participant<G> {
+    /// Starts the protocol for the participant `index` among `n`, with
+    /// threshold `t`. It returns the state and the message to broadcast.
+    /// The secret of the participant is sampled from `rng`.
+    pub fn new<R: RngCore + CryptoRng>(
+        g: G,
+        index: u32,
+        t: usize,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<(Participant<G>, Round1<G>), Error> {
+        check_threshold(t, n)?;
+        if index == 0 || index as usize > n {
+            return Err(Error::other(ERR_INDEX));
+        }
+        let poly = Polynomial::random(&g, &g.random_scalar(rng), t, rng);
+        let msg = Round1 {
+            sender: index,
+            commitment: poly.commit(&g),
+        };
+        let own = (
+            msg.clone(),
+            Share {
+                index,
+                value: poly.eval(&g, index),
+            },
+        );
+        let p = Participant {
+            g,
+            index,
+            t,
+            n,
+            poly,
+            received: vec![own],
+        };
+        Ok((p, msg))
+    }
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// Returns the share for the participant `index`, which must be sent
+    /// over a private channel.
+    pub fn share_for(&self, index: u32) -> Result<Share<G>, Error> {
+        if index == 0 || index as usize > self.n {
+            return Err(Error::other(ERR_INDEX));
+        }
+        Ok(Share {
+            index,
+            value: self.poly.eval(&self.g, index),
+        })
+    }
+    /// Processes the broadcast message of another participant together
+    /// with the share it sent to this participant. It fails if the share
+    /// does not match the commitments, in which case the sender must be
+    /// disqualified.
+    pub fn receive(&mut self, msg: &Round1<G>, share: &Share<G>) -> Result<(), Error> {
+        if msg.sender == 0 || msg.sender as usize > self.n || share.index != self.index {
+            return Err(Error::other(ERR_INDEX));
+        }
+        if self.received.iter().any(|(m, _)| m.sender == msg.sender) {
+            return Err(Error::other(ERR_DUPLICATE));
+        }
+        if msg.commitment.threshold() != self.t {
+            return Err(Error::other(ERR_DEGREE));
+        }
+        msg.verify_share(&self.g, share)?;
+        self.received.push((msg.clone(), share.clone()));
+        Ok(())
+    }
+    /// Finishes the protocol once the messages of all participants have
+    /// been received.
+    pub fn finish(self) -> Result<KeyShare<G>, Error> {
+        let g = &self.g;
+        if self.received.len() != self.n {
+            return Err(Error::other(ERR_MISSING));
+        }
+        let value = self
+            .received
+            .iter()
+            .fold(g.new_scalar(BigInt::from(0)), |acc, (_, s)| acc + &s.value);
+        let sum: Vec<G::Elt> = (0..self.t)
+            .map(|j| {
+                self.received.iter().fold(g.identity(), |acc, (m, _)| {
+                    acc + &m.commitment.coefficients[j]
+                })
+            })
+            .collect();
+        let verification_keys = (1..=self.n as u32)
+            .map(|i| eval_commitments(g, &sum, i))
+            .collect();
+        Ok(KeyShare {
+            share: Share {
+                index: self.index,
+                value,
+            },
+            group_key: sum[0].clone(),
+            verification_keys,
+        })
+    }
+}
+
+fn shares<G: PrimeGroup>(g: &G, poly: &Polynomial<G>, n: usize) -> Vec<Share<G>> {
+    (1..=n as u32)
+        .map(|index| Share {
+            index,
+            value: poly.eval(g, index),
+        })
+        .collect()
+}
+
+/// Returns `sum [index^j]C_j`, which is the commitment to the evaluation of
+/// the committed polynomial at `index`.
+fn eval_commitments<G: PrimeGroup>(g: &G, commitments: &[G::Elt], index: u32) -> G::Elt {
+    let x = g.new_scalar(BigInt::from(index));
+    let mut pow = g.new_scalar(BigInt::from(1));
+    let mut powers = Vec::with_capacity(commitments.len());
+    for _ in commitments.iter() {
+        powers.push(pow.clone());
+        pow = pow * &x;
+    }
+    g.multi_scalar_mul(commitments, &powers)
+}
+
+fn check_threshold(t: usize, n: usize) -> Result<(), Error> {
+    if t == 0 || t > n || n > u32::MAX as usize {
+        return Err(Error::other(ERR_THRESHOLD));
+    }
+    Ok(())
+}
+
+const INDEX_SIZE: usize = 4;
+const PEDERSEN_DST: &[u8] = b"REDOX-ECC-PEDERSEN-VSS-V01";
+const ERR_THRESHOLD: &str = "threshold must be in [1, n]";
+const ERR_INDEX: &str = "index of participant is out of range";
+const ERR_DUPLICATE: &str = "index of participant is repeated";
+const ERR_DEGREE: &str = "number of commitments does not match the threshold";
+const ERR_SHARE: &str = "share does not match the commitments";
+const ERR_MISSING: &str = "messages from some participants are missing";
+const ERR_ENCODING: &str = "invalid encoding of message";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand_core::OsRng;
+
+    use super::{
+        reconstruct, split, split_feldman, FeldmanCommitment, Participant, PedersenVss, Round1,
+        Share,
+    };
+    use crate::group::{PrimeGroup, Ristretto255, WeGroup};
+    use crate::h2c::P256_XMDSHA256_SSWU_RO_;
+
+    #[test]
+    fn shamir() {
+        let g = Ristretto255::new();
+        let secret = g.new_scalar(BigInt::from(0x5ec7e7));
+        let shares = split(&g, &secret, 3, 5, &mut OsRng).unwrap();
+        let want = g.encode_scalar(&secret);
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]].iter() {
+            let s: Vec<Share<Ristretto255>> = subset.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(g.encode_scalar(&reconstruct(&g, &s).unwrap()), want);
+        }
+        let got = reconstruct(&g, &shares[..2]).unwrap();
+        assert_ne!(g.encode_scalar(&got), want);
+        let dup = [shares[0].clone(), shares[0].clone(), shares[1].clone()];
+        assert!(reconstruct(&g, &dup).is_err());
+        assert!(split(&g, &secret, 0, 5, &mut OsRng).is_err());
+        assert!(split(&g, &secret, 6, 5, &mut OsRng).is_err());
+        let enc = shares[3].to_bytes(&g);
+        let share = Share::from_bytes(&g, &enc).unwrap();
+        assert_eq!(share.index, 4);
+        assert!(Share::<Ristretto255>::from_bytes(&g, &enc[..3]).is_err());
+    }
+
+    #[test]
+    fn feldman_vss() {
+        let g = Ristretto255::new();
+        let secret = g.new_scalar(BigInt::from(7));
+        let (shares, commitment) = split_feldman(&g, &secret, 2, 3, &mut OsRng).unwrap();
+        // the commitment goes through its encoding, as it would on the wire
+        let commitment = FeldmanCommitment::from_bytes(&g, &commitment.to_bytes(&g)).unwrap();
+        assert_eq!(commitment.threshold(), 2);
+        assert!(*commitment.public_key() == g.generator() * &secret);
+        for s in shares.iter() {
+            assert!(commitment.verify_share(&g, s.index, &s.value).is_ok());
+            assert!(commitment.verification_key(&g, s.index) == g.generator() * &s.value);
+        }
+        assert!(commitment.verify_share(&g, 2, &shares[0].value).is_err());
+        assert!(commitment.verify_share(&g, 0, &shares[0].value).is_err());
+        let enc = commitment.to_bytes(&g);
+        assert!(FeldmanCommitment::<Ristretto255>::from_bytes(&g, &enc[1..]).is_err());
+        assert!(FeldmanCommitment::<Ristretto255>::from_bytes(&g, &[]).is_err());
+    }
+
+    #[test]
+    fn pedersen_vss() {
+        let g = WeGroup::new(P256_XMDSHA256_SSWU_RO_);
+        let vss = PedersenVss::new(g.clone(), b"test");
+        let secret = g.new_scalar(BigInt::from(42));
+        let dealing = vss.deal(&secret, 2, 3, &mut OsRng).unwrap();
+        let (commitments, shares) = (dealing.commitments, dealing.shares);
+        for (s, b) in shares.iter() {
+            assert!(vss.verify(&commitments, s, b).is_ok());
+        }
+        let (s, b) = &shares[0];
+        let bad = Share::<WeGroup> {
+            index: s.index,
+            value: s.value.clone() + g.new_scalar(BigInt::from(1)),
+        };
+        assert!(vss.verify(&commitments, &bad, b).is_err());
+        assert!(vss.verify(&commitments, s, &shares[1].1).is_err());
+        let a: Vec<Share<WeGroup>> = shares.iter().map(|(s, _)| s.clone()).collect();
+        let got = reconstruct(&g, &a[1..]).unwrap();
+        assert_eq!(g.encode_scalar(&got), g.encode_scalar(&secret));
+    }
+
+    #[test]
+    fn dkg() {
+        let g = Ristretto255::new();
+        let (t, n) = (2, 3);
+        let (mut parties, msgs): (Vec<_>, Vec<_>) = (1..=n as u32)
+            .map(|i| Participant::new(g.clone(), i, t, n, &mut OsRng).unwrap())
+            .unzip();
+        // messages go through their encodings, as they would on the wire
+        let msgs: Vec<Round1<Ristretto255>> = msgs
+            .iter()
+            .map(|m| Round1::from_bytes(&g, &m.to_bytes(&g)).unwrap())
+            .collect();
+        for i in 0..n {
+            for j in 0..n {
+                if i != j {
+                    let share = parties[j].share_for(parties[i].index()).unwrap();
+                    let share = Share::from_bytes(&g, &share.to_bytes(&g)).unwrap();
+                    parties[i].receive(&msgs[j], &share).unwrap();
+                }
+            }
+        }
+        let keys: Vec<_> = parties.into_iter().map(|p| p.finish().unwrap()).collect();
+        for k in keys.iter() {
+            assert!(k.group_key == keys[0].group_key);
+            let i = k.share.index as usize - 1;
+            assert!(k.verification_keys[i] == g.generator() * &k.share.value);
+        }
+        let shares = [keys[2].share.clone(), keys[0].share.clone()];
+        let secret = reconstruct(&g, &shares).unwrap();
+        assert!(g.generator() * secret == keys[0].group_key);
+    }
+
+    #[test]
+    fn dkg_invalid_share() {
+        let g = Ristretto255::new();
+        let (mut alice, _) = Participant::new(g.clone(), 1, 2, 3, &mut OsRng).unwrap();
+        let (bob, msg_b) = Participant::new(g.clone(), 2, 2, 3, &mut OsRng).unwrap();
+        let mut share = bob.share_for(1).unwrap();
+        share.value += g.new_scalar(BigInt::from(1));
+        assert!(alice.receive(&msg_b, &share).is_err());
+        assert!(alice.receive(&msg_b, &bob.share_for(3).unwrap()).is_err());
+        assert!(alice.receive(&msg_b, &bob.share_for(1).unwrap()).is_ok());
+        assert!(alice.receive(&msg_b, &bob.share_for(1).unwrap()).is_err());
+        assert!(alice.finish().is_err());
+        assert!(Participant::new(g, 4, 2, 3, &mut OsRng).is_err());
+    }
+}
@@ -2,38 +2,139 @@
 //!
 //! The curve module is meant to be used for bar.
 
-use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
-use num_traits::identities::Zero;
+use num_bigint::{BigInt, BigUint, ToBigInt};
+use num_traits::identities::{One, Zero};
 
-use std::io::{Error, ErrorKind};
-use std::str::FromStr;
+use std::io::Error;
+use std::sync::Arc;
 
-use crate::do_if_eq;
-use crate::ellipticcurve::{Decode, EllipticCurve};
+use crate::ellipticcurve::{Decode, EcPoint, EllipticCurve, PointFormat};
 use crate::field::{Field, Sgn0, Sqrt};
+use crate::integer::Integer;
 use crate::ops::FromFactory;
 use crate::primefield::{Fp, FpElt};
 use crate::weierstrass::point::{Point, ProyCoordinates};
 use crate::weierstrass::scalar::Scalar;
+use crate::{do_if_eq, uncounted};
 
 /// This is an elliptic curve defined by the Weierstrass equation `y^2=x^3+ax+b`.
 ///
-/// **Atention** This implementation only supports curves of prime order.
-#[derive(Clone, std::cmp::PartialEq)]
-pub struct Curve {
-    f: Fp,
-    pub(super) a: FpElt,
-    pub(super) b: FpElt,
+/// The points of prime order `r` form a subgroup, and multiplying any point
+/// by the cofactor `h` maps it into this subgroup, see `Point::clear_cofactor`.
+/// Scalars are integers modulo `r`, so they are meant to act on the
+/// subgroup, and `decode` rejects the points outside of it.
+#[derive(Clone)]
+pub struct Curve(pub(crate) Arc<CurveInner>);
+
+/// CurveInner holds the parameters of a curve, which are shared by its points
+/// instead of being copied into each of them.
+pub struct CurveInner {
+    pub(crate) name: &'static str,
+    pub(super) f: Fp,
+    pub(crate) a: FpElt,
+    pub(crate) b: FpElt,
     pub(super) r: BigUint,
     pub(super) gx: FpElt,
     pub(super) gy: FpElt,
     pub(super) h: BigUint,
+    // constants computed once for the formulas and the decoder
+    pub(crate) zero: FpElt,
+    pub(crate) one: FpElt,
+    pub(crate) b3: FpElt,
+}
+
+impl std::ops::Deref for Curve {
+    type Target = CurveInner;
+    fn deref(&self) -> &CurveInner {
+        &self.0
+    }
 }
+
+/// Curves are compared by their parameters, regardless of their names.
+impl PartialEq for Curve {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+            || (self.f == other.f
+                && self.a == other.a
+                && self.b == other.b
+                && self.r == other.r
+                && self.gx == other.gx
+                && self.gy == other.gy
+                && self.h == other.h)
+    }
+}
+
 impl Curve {
+    /// Returns the name given in the parameters of the curve, which is empty
+    /// for curves derived from other curves.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+    /// Returns the coefficient `a` of the equation `y^2=x^3+ax+b`.
+    pub fn a(&self) -> &FpElt {
+        &self.a
+    }
+    /// Returns the coefficient `b` of the equation `y^2=x^3+ax+b`.
+    pub fn b(&self) -> &FpElt {
+        &self.b
+    }
+    /// Decodes a batch of points in compressed form, e.g., the public keys
+    /// received by a server. The y-coordinates are recovered with
+    /// `Fp::batch_sqrt`, which shares the square-root constants across the
+    /// batch and needs one exponentiation per point instead of the two
+    /// spent by `decode`. Each encoding is decoded independently, so an
+    /// invalid one only fails its own entry. Encodings that are not
+    /// compressed are passed to `decode`.
+    pub fn batch_decompress<B: AsRef<[u8]>>(&self, bufs: &[B]) -> Vec<Result<Point, Error>> {
+        let size = self.f.size_bytes();
+        let mut out: Vec<Result<Point, Error>> = Vec::with_capacity(bufs.len());
+        let (mut idx, mut xs, mut rhs) = (Vec::new(), Vec::new(), Vec::new());
+        for (i, buf) in bufs.iter().map(AsRef::as_ref).enumerate() {
+            if buf.len() != size + 1 || !(buf[0] == 0x02 || buf[0] == 0x03) {
+                out.push(self.decode(buf));
+                continue;
+            }
+            match self.f.from_canonical_be(&buf[1..]) {
+                Ok(x) => {
+                    let xx_a = &x * &x + &self.a;
+                    rhs.push(xx_a.mul_add(&x, &self.b));
+                    xs.push(x);
+                    idx.push(i);
+                    out.push(Err(Error::other(ERR_X_COORD)));
+                }
+                Err(e) => out.push(Err(e)),
+            }
+        }
+        let roots = self.f.batch_sqrt(&rhs);
+        for ((i, x), y) in idx.into_iter().zip(xs).zip(roots) {
+            if let Some(mut y) = y {
+                let s = y.sgn0();
+                let tag = (((s >> 1) & 0x1) + 2) as u8;
+                if bufs[i].as_ref()[0] != tag {
+                    y = -y;
+                }
+                let z = self.one.clone();
+                out[i] = self.check_subgroup(self.new_proy_point(ProyCoordinates { x, y, z }));
+            }
+        }
+        out
+    }
+    /// Returns whether `p` belongs to the subgroup of order `r`, which holds
+    /// for all the points of curves whose cofactor is one.
+    pub fn is_in_subgroup(&self, p: &Point) -> bool {
+        self.h.is_one() || p.mul_unreduced(&self.r).is_zero()
+    }
+    /// Returns `p` if it belongs to the subgroup of order `r`.
+    fn check_subgroup(&self, p: Point) -> Result<Point, Error> {
+        if !self.is_in_subgroup(&p) {
+            return Err(Error::other(ERR_SUBGROUP));
+        }
+        Ok(p)
+    }
     pub(crate) fn new_proy_point(&self, c: ProyCoordinates) -> Point {
         let e = self.clone();
         let pt = Point { e, c };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        do_if_eq!(uncounted!(self.is_on_curve(&pt)), pt, ERR_ECC_NEW)
     }
 }
 
@@ -43,21 +144,24 @@ impl EllipticCurve for Curve {
     type Point = Point;
     fn new_point(&self, x: <Self::F as Field>::Elt, y: <Self::F as Field>::Elt) -> Self::Point {
         let e = self.clone();
-        let f = e.get_field();
         let pt = Point {
-            c: ProyCoordinates { x, y, z: f.one() },
+            c: ProyCoordinates {
+                x,
+                y,
+                z: self.one.clone(),
+            },
             e,
         };
-        do_if_eq!(self.is_on_curve(&pt), pt, ERR_ECC_NEW)
+        do_if_eq!(uncounted!(self.is_on_curve(&pt)), pt, ERR_ECC_NEW)
     }
     fn new_scalar(&self, k: BigInt) -> Self::Scalar {
         Scalar::new(k, &self.r)
     }
     fn identity(&self) -> Self::Point {
         self.new_proy_point(ProyCoordinates {
-            x: self.f.zero(),
-            y: self.f.one(),
-            z: self.f.zero(),
+            x: self.zero.clone(),
+            y: self.one.clone(),
+            z: self.zero.clone(),
         })
     }
     fn is_on_curve(&self, p: &Self::Point) -> bool {
@@ -83,65 +187,66 @@ impl EllipticCurve for Curve {
         self.new_proy_point(ProyCoordinates {
             x: self.gx.clone(),
             y: self.gy.clone(),
-            z: self.f.one(),
+            z: self.one.clone(),
         })
     }
 }
 
 impl Decode for Curve {
     type Deser = Point;
-    fn decode(&self, buf: &[u8]) -> Result<Self::Deser, Error> {
+    fn decode_with_format(&self, buf: &[u8]) -> Result<(Self::Deser, PointFormat), Error> {
         let size = self.f.size_bytes();
         let blen = buf.len();
         if !(blen == 1 || blen == (size + 1) || blen == (2 * size + 1)) {
-            return Err(Error::new(ErrorKind::Other, "Wrong input buffer size."));
+            return Err(Error::other("Wrong input buffer size."));
         }
         let tag = buf[0];
-        // check x coordinate is in the valid range, Sign::Plus => > 0
-        let x_val = BigInt::from_bytes_be(Sign::Plus, &buf[1..size + 1]);
-        let p = self.f.get_modulus();
-        if x_val >= p {
-            return Err(Error::new(ErrorKind::Other, "Invalid x coordinate"));
+        if tag == 0x00 {
+            // the point at infinity is a single zero byte in both the
+            // compressed and the uncompressed formats
+            if blen != 1 {
+                return Err(Error::other(
+                    "Point at infinity should just be a single zero byte",
+                ));
+            }
+            return Ok((self.identity(), PointFormat::Compressed));
+        }
+        if blen == 1 {
+            return Err(Error::other("Invalid bytes for deserialization"));
         }
+        let x = self.f.from_canonical_be(&buf[1..size + 1])?;
         match tag {
-            0x00 => {
-                // return point of infinity
-                if buf.len() != 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Point at infinity should just be a single zero byte",
-                    ));
-                }
-                Ok(self.identity())
-            }
-            0x04 => {
+            0x04 | 0x06 | 0x07 => {
                 if buf.len() != 2 * size + 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Invalid bytes for deserialization",
-                    ));
+                    return Err(Error::other("Invalid bytes for deserialization"));
                 }
-                let x = self.f.elt(x_val);
-                let y_val = BigInt::from_bytes_be(Sign::Plus, &buf[size + 1..]);
-                if y_val >= p {
-                    return Err(Error::new(ErrorKind::Other, "Invalid y coordinate"));
+                let y = self.f.from_canonical_be(&buf[size + 1..])?;
+                if &y * &y != &x * &x * &x + &self.a * &x + &self.b {
+                    return Err(Error::other("Point is not on the curve"));
                 }
-                let y = self.f.elt(y_val);
-                Ok(self.new_point(x, y))
+                if tag == 0x04 {
+                    let p = self.check_subgroup(self.new_point(x, y))?;
+                    return Ok((p, PointFormat::Uncompressed));
+                }
+                // the hybrid tag must carry the sign of y
+                if tag & 0x1 != ((y.sgn0() >> 1) & 0x1) as u8 {
+                    return Err(Error::other("Hybrid tag does not match y coordinate"));
+                }
+                let p = self.check_subgroup(self.new_point(x, y))?;
+                Ok((p, PointFormat::Hybrid))
             }
             0x02 | 0x03 => {
                 if buf.len() != size + 1 {
-                    return Err(Error::new(
-                        ErrorKind::Other,
-                        "Invalid bytes for deserialization",
-                    ));
+                    return Err(Error::other("Invalid bytes for deserialization"));
                 }
                 // recompute y coordinate
-                let x = self.f.elt(x_val);
                 let xx = &x * &x;
                 let xx_a = &xx + &self.a;
                 let xxx_ax = &xx_a * &x;
                 let xxx_ax_b = &xxx_ax + &self.b;
+                if !xxx_ax_b.is_square() {
+                    return Err(Error::other("Invalid x coordinate"));
+                }
                 let y_sqrt = xxx_ax_b.sqrt();
                 let s = y_sqrt.sgn0();
                 let deser_tag = (((s >> 1) & 0x1) + 2) as u8;
@@ -149,58 +254,83 @@ impl Decode for Curve {
                 if tag != deser_tag {
                     y = -y;
                 }
-                Ok(self.new_point(x, y))
+                let p = self.check_subgroup(self.new_point(x, y))?;
+                Ok((p, PointFormat::Compressed))
             }
-            _ => Err(Error::new(ErrorKind::Other, "Invalid tag specified")),
+            _ => Err(Error::other("Invalid tag specified")),
         }
     }
 }
 
 impl std::fmt::Display for Curve {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if !self.name.is_empty() {
+            write!(f, "{}: ", self.name)?;
+        }
         write!(
             f,
-            "Weierstrass Curve y^2=x^3+ax+b\na: {}\nb: {}",
-            self.a, self.b,
+            "Weierstrass Curve y^2=x^3+ax+b over {}\na: {}\nb: {}",
+            self.f, self.a, self.b,
         )
     }
 }
 
+/// Params are the parameters of a curve, whose integers are written with
+/// the `int!` macro.
 #[derive(PartialEq, Eq)]
 pub struct Params {
     pub name: &'static str,
-    pub p: &'static str,
-    pub a: &'static str,
-    pub b: &'static str,
-    pub r: &'static str,
-    pub h: &'static str,
-    pub gx: &'static str,
-    pub gy: &'static str,
+    pub p: Integer,
+    pub a: Integer,
+    pub b: Integer,
+    pub r: Integer,
+    pub h: Integer,
+    pub gx: Integer,
+    pub gy: Integer,
 }
 
 impl<'a> std::convert::From<&'a Params> for Curve {
     fn from(params: &'a Params) -> Curve {
-        let f = Fp::new(BigUint::from_str(params.p).unwrap());
-        Curve {
+        let f = Fp::new(params.p.to_biguint());
+        let b: FpElt = f.from(params.b);
+        Curve(Arc::new(CurveInner {
+            name: params.name,
             a: f.from(params.a),
-            b: f.from(params.b),
-            r: BigUint::from_str(params.r).unwrap(),
-            h: BigUint::from_str(params.h).unwrap(),
+            r: params.r.to_biguint(),
+            h: params.h.to_biguint(),
             gx: f.from(params.gx),
             gy: f.from(params.gy),
+            zero: f.zero(),
+            one: f.one(),
+            b3: &b + &b + &b,
+            b,
             f,
-        }
+        }))
     }
 }
 
 const ERR_ECC_NEW: &str = "not valid point";
+const ERR_X_COORD: &str = "Invalid x coordinate";
+const ERR_SUBGROUP: &str = "Point is not in the subgroup of order r";
 
 // tests for ser/deser
 #[cfg(test)]
 mod tests {
-    use crate::ellipticcurve::{Decode, EllipticCurve, Encode};
+    use num_bigint::BigInt;
+    use num_traits::identities::One;
+    use rand_core::OsRng;
+    use subtle::ConstantTimeEq;
+
+    use std::convert::TryFrom;
+    use std::sync::Arc;
+
+    use crate::ellipticcurve::{
+        Decode, EcPoint, EllipticCurve, Encode, PointFormat, POINT_FORMATS,
+    };
     use crate::field::Field;
-    use crate::instances::{GetCurve, P256, P384, P521};
+    use crate::instances::{GetCurve, P256, P384, P521, SECP256K1, W25519};
+    use crate::ops::FromFactory;
+    use crate::weierstrass::{Affine, Point, Scalar};
 
     #[test]
     fn point_serialization() {
@@ -208,7 +338,7 @@ mod tests {
             let ec = id.get();
             let len_p = ec.get_field().size_bytes();
             let gen = ec.get_generator();
-            let ser = gen.encode(false);
+            let ser = gen.encode(PointFormat::Uncompressed);
             assert_eq!(ser.len(), 2 * len_p + 1);
             let deser = ec.decode(&ser).unwrap();
             assert!(
@@ -220,13 +350,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn zero_scalar() {
+        for &id in [P256, P384, P521].iter() {
+            let ec = id.get();
+            let k = ec.new_scalar(BigInt::from(0));
+            let p = &ec.get_generator() * &k;
+            assert!(p.is_zero(), "[0]G must be the identity for {}", id);
+        }
+    }
+
     #[test]
     fn point_serialization_compressed() {
         for &id in [P256, P384, P521].iter() {
             let ec = id.get();
             let len_p = ec.get_field().size_bytes();
             let gen = ec.get_generator();
-            let ser = gen.encode(true);
+            let ser = gen.encode(PointFormat::Compressed);
             assert_eq!(ser.len(), len_p + 1);
             let deser = ec.decode(&ser).unwrap();
             assert!(
@@ -237,4 +377,336 @@ mod tests {
             assert!(gen == deser, "compressed point equality check for {}", id);
         }
     }
+
+    #[test]
+    fn point_serialization_hybrid() {
+        for &id in [P256, P384, P521].iter() {
+            let ec = id.get();
+            let len_p = ec.get_field().size_bytes();
+            for p in [ec.get_generator(), -ec.get_generator()].iter() {
+                let mut ser = p.encode(PointFormat::Hybrid);
+                assert_eq!(ser.len(), 2 * len_p + 1);
+                assert_eq!(ser[0] & 0xfe, 0x06, "hybrid tag for {}", id);
+                assert_eq!(ser[0] & 0x01, p.encode(PointFormat::Compressed)[0] & 0x01);
+                let (deser, format) = ec.decode_with_format(&ser).unwrap();
+                assert!(*p == deser, "hybrid point equality check for {}", id);
+                assert_eq!(format, PointFormat::Hybrid);
+                // the sign of y must agree with the tag
+                ser[0] ^= 0x01;
+                assert!(ec.decode(&ser).is_err(), "wrong hybrid tag for {}", id);
+            }
+            let ser = ec.get_generator().encode(PointFormat::Uncompressed);
+            let (_, format) = ec.decode_with_format(&ser).unwrap();
+            assert_eq!(format, PointFormat::Uncompressed);
+        }
+    }
+
+    #[test]
+    fn identity_serialization() {
+        for &id in [P256, P384, P521].iter() {
+            let ec = id.get();
+            let inf = ec.identity();
+            assert!(inf.is_identity(), "identity check for {}", id);
+            assert!(!ec.get_generator().is_identity(), "generator of {}", id);
+            for &format in POINT_FORMATS.iter() {
+                let ser = inf.encode(format);
+                assert_eq!(ser, vec![0], "identity encoding for {}", id);
+                let deser = ec.decode(&ser).unwrap();
+                assert!(deser.is_identity(), "identity round-trip for {}", id);
+            }
+            let gen = ec.get_generator();
+            assert!(
+                (&gen - &gen).encode(PointFormat::Compressed) == vec![0],
+                "P - P for {}",
+                id
+            );
+            assert!(ec.decode(&[0, 0]).is_err(), "padded identity for {}", id);
+            for tag in 1u8..=4 {
+                assert!(ec.decode(&[tag]).is_err(), "bare tag for {}", id);
+            }
+        }
+    }
+
+    #[test]
+    fn non_canonical_encodings() {
+        for &id in [P256, P384, P521].iter() {
+            let ec = id.get();
+            let size = ec.get_field().size_bytes();
+            let p = ec.get_field().get_modulus().to_bytes_be().1;
+            let gen = ec.get_generator();
+            for &format in POINT_FORMATS.iter() {
+                let ser = gen.encode(format);
+                let mut long = ser.clone();
+                long.push(0);
+                assert!(ec.decode(&long).is_err(), "over-long encoding for {}", id);
+                let mut bad_x = ser.clone();
+                bad_x[1..size + 1].copy_from_slice(&p);
+                assert!(ec.decode(&bad_x).is_err(), "x >= p for {}", id);
+            }
+            let mut bad_y = gen.encode(PointFormat::Uncompressed);
+            bad_y[size + 1..].copy_from_slice(&p);
+            assert!(ec.decode(&bad_y).is_err(), "y >= p for {}", id);
+        }
+    }
+
+    #[test]
+    fn constant_time_eq() {
+        let ec = P256.get();
+        let g = ec.get_generator();
+        let k = ec.new_scalar(BigInt::from(3));
+        let p = &g * &k;
+        // the same point in other projective coordinates
+        let q = &(&g + &g) + &g;
+        assert!(bool::from(p.ct_eq(&q)));
+        assert!(!bool::from(p.ct_eq(&g)));
+        assert!(bool::from(k.ct_eq(&ec.new_scalar(BigInt::from(3)))));
+        assert!(!bool::from(k.ct_eq(&ec.new_scalar(BigInt::from(4)))));
+    }
+
+    #[test]
+    fn scalar_blinding() {
+        for &id in [P256, P384].iter() {
+            let ec = id.get();
+            let g = ec.get_generator();
+            let k = ec.new_scalar(BigInt::from(0x1234_5678u64));
+            let want = &g * &k;
+            assert!(g.mul_blinded(&k, &mut OsRng) == want, "{}", id);
+            assert!(g.mul_blinded(&k, &mut OsRng) == want, "{}", id);
+        }
+    }
+
+    #[test]
+    fn randomized_representation() {
+        for &id in [P256, P384].iter() {
+            let ec = id.get();
+            let g = ec.get_generator();
+            let mut p = g.clone();
+            p.randomize_representation(&mut OsRng);
+            assert!(p == g, "{}", id);
+            assert!(
+                p.encode(PointFormat::Compressed) == g.encode(PointFormat::Compressed),
+                "{}",
+                id
+            );
+            let mut o = ec.identity();
+            o.randomize_representation(&mut OsRng);
+            assert!(o.is_identity(), "{}", id);
+        }
+    }
+
+    #[test]
+    fn assign_ops() {
+        let ec = P256.get();
+        let g = ec.get_generator();
+        let (k, l) = (
+            ec.new_scalar(BigInt::from(5)),
+            ec.new_scalar(BigInt::from(7)),
+        );
+        let mut p = ec.identity();
+        p += &g;
+        p += g.clone();
+        p -= &g;
+        p *= &k;
+        assert!(p == &g * &k);
+        p *= l.clone();
+        p -= g.clone();
+        assert!(p == &g * &(&k * &l) - &g);
+
+        let mut s = k.clone();
+        s += &l;
+        s *= &l;
+        s -= k.clone();
+        s /= &l;
+        assert!(&g * &s == &g * &((&(&k + &l) * &l - &k) / &l));
+    }
+
+    #[test]
+    fn iter_sums() {
+        let ec = P256.get();
+        let g = ec.get_generator();
+        let k: Vec<_> = (1..5).map(|i| ec.new_scalar(BigInt::from(i))).collect();
+        let points: Vec<_> = k.iter().map(|k| &g * k).collect();
+        let sum: Point = points.iter().sum();
+        assert!(sum == &g * &ec.new_scalar(BigInt::from(10)));
+        assert!(points.clone().into_iter().sum::<Point>() == sum);
+        assert!(ec.sum(points) == sum);
+        assert!(ec.sum(Vec::new()).is_identity());
+
+        let s: Scalar = k.iter().sum();
+        assert!(&g * &s == sum);
+        let p: Scalar = k.into_iter().product();
+        assert!(&g * &p == &g * &ec.new_scalar(BigInt::from(24)));
+    }
+
+    #[test]
+    fn formatting() {
+        let ec = P256.get();
+        let g = ec.get_generator();
+        let gx = "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+        let gy = "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5";
+        assert_eq!(g.to_string(), format!("03{}", gx));
+        // the projective coordinates of 2G+G are not normalized
+        let p = (&g + &g) + &g;
+        let want = format!("Point {{ curve: \"P256\", x: 0x{}, y: 0x{} }}", gx, gy);
+        assert_eq!(format!("{:?}", p - &g - &g), want);
+        let o = ec.identity();
+        assert_eq!(o.to_string(), "00");
+        assert_eq!(format!("{:?}", o), "Point { curve: \"P256\", identity }");
+    }
+
+    #[test]
+    fn affine_coordinates() {
+        let ec = P256.get();
+        let g = ec.get_generator();
+        let gx = "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+        let gy = "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5";
+        let p = (&g + &g) + &g - &g - &g;
+        assert_eq!(format!("{:x}", p.x().unwrap()), gx);
+        assert_eq!(format!("{:x}", p.y().unwrap()), gy);
+        let (x, y) = p.to_affine().unwrap();
+        assert!(ec.new_point(x, y) == g);
+        let o = ec.identity();
+        assert!(o.x().is_none() && o.y().is_none() && o.to_affine().is_none());
+    }
+
+    #[test]
+    fn shared_parameters() {
+        let ec = P256.get();
+        let g = ec.get_generator();
+        let p = &g * &ec.new_scalar(BigInt::from(5)) + &g;
+        assert!(Arc::ptr_eq(&ec.0, &p.e.0));
+        // curves built separately are still equal
+        let other = P256.get();
+        assert!(!Arc::ptr_eq(&ec.0, &other.0) && ec == other);
+        assert!(p == other.get_generator() * &other.new_scalar(BigInt::from(6)));
+        assert!(ec != P384.get());
+    }
+
+    #[test]
+    fn in_place_ops() {
+        for id in [P256, SECP256K1].iter() {
+            let ec = id.get();
+            let g = ec.get_generator();
+            let p = &g * &ec.new_scalar(BigInt::from(7));
+            let mut d = p.clone();
+            d.double_in_place();
+            assert!(d == &p + &p);
+            let q = Affine::try_from(&(&g * &ec.new_scalar(BigInt::from(3)))).unwrap();
+            let mut s = p.clone();
+            s.add_assign_mixed(&q);
+            assert!(s == &g * &ec.new_scalar(BigInt::from(10)));
+            // adding a point to itself, its negative, and to the identity
+            let mut s = Point::from(&q);
+            s.add_assign_mixed(&q);
+            assert!(s == &g * &ec.new_scalar(BigInt::from(6)));
+            let mut s = -Point::from(&q);
+            s.add_assign_mixed(&q);
+            assert!(s.is_identity());
+            s.add_assign_mixed(&q);
+            assert!(s == Point::from(&q));
+            let mut o = ec.identity();
+            o.double_in_place();
+            assert!(o.is_identity());
+            assert!(Affine::try_from(&o).is_err());
+        }
+    }
+
+    #[test]
+    fn mixed_addition() {
+        for id in [P256, SECP256K1].iter() {
+            let ec = id.get();
+            let g = ec.get_generator();
+            let p = &g * &ec.new_scalar(BigInt::from(3));
+            let q = &g * &ec.new_scalar(BigInt::from(5));
+            let mut pn = p.clone();
+            pn.normalize();
+            assert!(pn.c.z.is_one() && !p.c.z.is_one() && !q.c.z.is_one());
+            let want = &g * &ec.new_scalar(BigInt::from(8));
+            assert!(&p + &q == want && &pn + &q == want && &q + &pn == want);
+            assert!(&pn + &pn == &p + &p);
+            assert!((&pn - &pn).is_identity());
+            assert!(ec.identity() + &pn == p && &pn + ec.identity() == p);
+        }
+    }
+
+    #[test]
+    fn batch_decompress() {
+        for id in [P256, P384, SECP256K1].iter() {
+            let ec = id.get();
+            let g = ec.get_generator();
+            let points: Vec<_> = (1..8)
+                .map(|i| &g * &ec.new_scalar(BigInt::from(i)))
+                .chain(std::iter::once(-&g))
+                .collect();
+            let mut bufs: Vec<_> = points
+                .iter()
+                .map(|p| p.encode(PointFormat::Compressed))
+                .collect();
+            bufs.push(vec![0]);
+            bufs.push(g.encode(PointFormat::Uncompressed));
+            bufs.push(bufs[0][..4].to_vec());
+            // about half of the x-coordinates are not on the curve
+            let others: Vec<_> = (1..9u8)
+                .map(|t| {
+                    let mut buf = g.encode(PointFormat::Compressed);
+                    *buf.last_mut().unwrap() ^= t;
+                    buf
+                })
+                .collect();
+            let got = ec.batch_decompress(&bufs);
+            assert_eq!(got.len(), bufs.len());
+            for (p, q) in points.iter().zip(got.iter()) {
+                assert!(q.as_ref().unwrap() == p);
+            }
+            let n = points.len();
+            assert!(got[n].as_ref().unwrap().is_identity());
+            assert!(*got[n + 1].as_ref().unwrap() == g);
+            assert!(got[n + 2].is_err());
+            for (buf, q) in others.iter().zip(ec.batch_decompress(&others)) {
+                match (q, ec.decode(buf)) {
+                    (Ok(q), Ok(p)) => assert!(q == p),
+                    (Err(_), Err(_)) => {}
+                    _ => panic!("batch_decompress and decode disagree"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cofactor() {
+        let ec = W25519.get();
+        let f = ec.get_field();
+        let g = ec.get_generator();
+        // the point of order two, which maps to (0, 0) on Curve25519
+        let x = f.from("0x2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaad2451");
+        let t = ec.new_point(x, f.zero());
+        assert!(t.is_small_order() && !ec.is_in_subgroup(&t));
+        assert!((&t + &t).is_identity());
+        // P - Q is of order two, which the complete formulas miss
+        assert!(ec.identity() + &t == t && &t + ec.identity() == t);
+        let gt = &g + &t;
+        assert!(!gt.is_small_order() && !ec.is_in_subgroup(&gt));
+        let want = &(&g + &g) + &t;
+        assert!(&gt + &g == want && &g + &gt == want);
+        let mut p = gt.clone();
+        p.add_assign_mixed(&Affine::try_from(&g).unwrap());
+        assert!(p == want);
+        let mut p = gt.clone();
+        p.randomize_representation(&mut OsRng);
+        assert!(&p + &g == want);
+        assert!((&gt - &g) == t && (&gt - &gt).is_identity());
+        // [h](G + T) = [h]G
+        let h = ec.new_scalar(ec.get_cofactor());
+        assert!(gt.clear_cofactor() == &g * &h);
+        assert!(t.clear_cofactor().is_identity());
+        assert!(ec.is_in_subgroup(&g) && ec.is_in_subgroup(&ec.identity()));
+        for &format in POINT_FORMATS.iter() {
+            assert!(ec.decode(&g.encode(format)).unwrap() == g);
+            assert!(ec.decode(&gt.encode(format)).is_err());
+            assert!(ec.decode(&t.encode(format)).is_err());
+        }
+        let got = ec.batch_decompress(&[gt.encode(PointFormat::Compressed)]);
+        assert!(got[0].is_err());
+        assert_eq!((&t + &t).encode(PointFormat::Compressed), vec![0]);
+    }
 }
@@ -0,0 +1,93 @@
+use num_bigint::BigInt;
+use num_traits::identities::Zero;
+
+use crate::ellipticcurve::{EllipticCurve, MapToCurve};
+use crate::field::Field;
+use crate::ops::FromFactory;
+use crate::primefield::FpElt;
+use crate::weierstrass::Curve;
+
+/// Icart is the deterministic encoding proposed by Icart at CRYPTO 2009 for
+/// curves y^2=x^3+ax+b defined over GF(p) with p = 2 mod 3. Under this
+/// condition, cubing is a bijection, so every field element has a unique cube
+/// root.
+///
+/// The map sends u=0 to the identity point. It is not one of the maps
+/// recommended by RFC 9380, and it is meant for comparisons and for protocols
+/// that explicitly specify it.
+#[derive(Clone)]
+pub struct Icart {
+    e: Curve,
+    c1: FpElt,  // 1/3
+    c2: FpElt,  // 1/27
+    c3: BigInt, // (2p-1)/3
+}
+
+impl Icart {
+    pub fn new(e: Curve) -> Icart {
+        if !Icart::verify(&e) {
+            panic!("wrong input parameters")
+        } else {
+            let f = e.get_field();
+            let c1 = 1u32 / &f.from(3u32);
+            let c2 = 1u32 / &f.from(27u32);
+            let c3 = (2u32 * f.get_modulus() - 1u32) / 3u32;
+            Icart { e, c1, c2, c3 }
+        }
+    }
+    fn verify(e: &Curve) -> bool {
+        let p = e.get_field().get_modulus();
+        p % 3u32 == BigInt::from(2u32) // p = 2 mod 3
+    }
+}
+
+impl MapToCurve for Icart {
+    type E = Curve;
+    fn map(
+        &self,
+        u: &<<Self::E as EllipticCurve>::F as Field>::Elt,
+    ) -> <Self::E as EllipticCurve>::Point {
+        if u.is_zero() {
+            return self.e.identity();
+        }
+        let f = self.e.get_field();
+        let u2 = u ^ 2u32; //                             u^2
+        let u4 = &u2 ^ 2u32; //                           u^4
+        let u6 = &u4 * &u2; //                            u^6
+        let t0 = f.from(3u32) * &self.e.a - u4; //        3a-u^4
+        let v = t0 / (f.from(6u32) * u); //               v = (3a-u^4)/(6u)
+        let t1 = (&v ^ 2u32) - &self.e.b - u6 * &self.c2; // v^2-b-u^6/27
+        let x = (&t1 ^ &self.c3) + u2 * &self.c1; //      x = (v^2-b-u^6/27)^(1/3)+u^2/3
+        let y = u * &x + v; //                            y = ux+v
+        self.e.new_point(x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::Icart;
+    use crate::ellipticcurve::{EcPoint, EllipticCurve, MapToCurve};
+    use crate::field::Field;
+    use crate::instances::{GetCurve, P384};
+    use crate::ops::FromFactory;
+
+    #[test]
+    fn icart_map() {
+        let e = P384.get();
+        let f = e.get_field();
+        let icart = Icart::new(e.clone());
+        assert!(icart.map(&f.zero()).is_zero());
+        for u in 1..16 {
+            let p = icart.map(&f.from(u));
+            assert!(e.is_on_curve(&p), "point must be on curve for u={}", u);
+            let r = e.new_scalar(BigInt::from(e.get_order()));
+            assert!(
+                (p * r).is_zero(),
+                "point must be in the subgroup for u={}",
+                u
+            );
+        }
+    }
+}
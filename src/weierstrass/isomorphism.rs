@@ -0,0 +1,217 @@
+use num_traits::identities::Zero;
+
+use std::sync::Arc;
+
+use crate::do_if_eq;
+use crate::ellipticcurve::{EllipticCurve, Isogeny, RationalMap};
+use crate::primefield::FpElt;
+use crate::weierstrass::curve::CurveInner;
+use crate::weierstrass::{Curve, Point, ProyCoordinates};
+
+/// Isomorphism is the map `(x,y) -> (u^2*x, u^3*y)` between the curves
+/// `y^2=x^3+ax+b` and `y^2=x^3+(u^4)ax+(u^6)b`.
+///
+/// Isomorphisms can be composed and inverted, so points can be transported
+/// along a chain of isomorphic curves, e.g., towards the target curve of an
+/// isogeny used for hashing to curves.
+#[derive(Clone)]
+pub struct Isomorphism {
+    e0: Curve,
+    e1: Curve,
+    u: FpElt,
+    u2: FpElt,
+    u3: FpElt,
+}
+
+impl Isomorphism {
+    /// Returns the isomorphism from `e0` to the curve obtained by scaling
+    /// `e0` by `u`. The generator of the codomain is the image of the
+    /// generator of `e0`.
+    pub fn new(e0: Curve, u: FpElt) -> Isomorphism {
+        if u.is_zero() {
+            panic!("wrong input parameters")
+        }
+        let (u2, u3) = Isomorphism::powers(&u);
+        let u4 = &u2 ^ 2u32;
+        let u6 = &u3 ^ 2u32;
+        let e1 = Curve(Arc::new(CurveInner {
+            name: "",
+            f: e0.f.clone(),
+            a: &e0.a * &u4,
+            b: &e0.b * &u6,
+            r: e0.r.clone(),
+            gx: &e0.gx * &u2,
+            gy: &e0.gy * &u3,
+            h: e0.h.clone(),
+            zero: e0.zero.clone(),
+            one: e0.one.clone(),
+            b3: &e0.b3 * &u6,
+        }));
+        Isomorphism { e0, e1, u, u2, u3 }
+    }
+    /// Returns the isomorphism between `e0` and `e1` given by `u`.
+    /// It panics if `e1` is not the curve obtained by scaling `e0` by `u`.
+    pub fn from_curves(e0: Curve, e1: Curve, u: FpElt) -> Isomorphism {
+        if !Isomorphism::verify(&e0, &e1, &u) {
+            panic!("wrong input parameters")
+        }
+        let (u2, u3) = Isomorphism::powers(&u);
+        Isomorphism { e0, e1, u, u2, u3 }
+    }
+    fn powers(u: &FpElt) -> (FpElt, FpElt) {
+        let u2 = u ^ 2u32;
+        let u3 = &u2 * u;
+        (u2, u3)
+    }
+    fn verify(e0: &Curve, e1: &Curve, u: &FpElt) -> bool {
+        let (u2, u3) = Isomorphism::powers(u);
+        let cond0 = !u.is_zero();
+        let cond1 = e0.f == e1.f && e0.r == e1.r && e0.h == e1.h;
+        let cond2 = e1.a == &e0.a * &(&u2 ^ 2u32); // a1 = u^4 * a0
+        let cond3 = e1.b == &e0.b * &(&u3 ^ 2u32); // b1 = u^6 * b0
+        cond0 && cond1 && cond2 && cond3
+    }
+    /// Returns the scaling factor `u` of the isomorphism.
+    pub fn get_scale(&self) -> FpElt {
+        self.u.clone()
+    }
+    /// Returns the isomorphism going from the codomain to the domain.
+    pub fn inverse(&self) -> Isomorphism {
+        let u = 1u32 / &self.u;
+        let (u2, u3) = Isomorphism::powers(&u);
+        Isomorphism {
+            e0: self.e1.clone(),
+            e1: self.e0.clone(),
+            u,
+            u2,
+            u3,
+        }
+    }
+    /// Returns the isomorphism that first applies `self` and then `other`.
+    /// It panics if the codomain of `self` is not the domain of `other`.
+    pub fn compose(&self, other: &Isomorphism) -> Isomorphism {
+        if self.e1 != other.e0 {
+            panic!("Codomain of isomorphism is incompatible with domain")
+        }
+        let u = &self.u * &other.u;
+        let (u2, u3) = Isomorphism::powers(&u);
+        Isomorphism {
+            e0: self.e0.clone(),
+            e1: other.e1.clone(),
+            u,
+            u2,
+            u3,
+        }
+    }
+    fn scale(e: &Curve, p: &Point, u2: &FpElt, u3: &FpElt) -> Point {
+        e.new_proy_point(ProyCoordinates {
+            x: &p.c.x * u2,
+            y: &p.c.y * u3,
+            z: p.c.z.clone(),
+        })
+    }
+}
+
+impl RationalMap for Isomorphism {
+    type E0 = Curve;
+    type E1 = Curve;
+    fn domain(&self) -> Self::E0 {
+        self.e0.clone()
+    }
+    fn codomain(&self) -> Self::E1 {
+        self.e1.clone()
+    }
+    fn push(&self, p: <Self::E0 as EllipticCurve>::Point) -> <Self::E1 as EllipticCurve>::Point {
+        do_if_eq!(
+            p.e == self.e0,
+            Isomorphism::scale(&self.e1, &p, &self.u2, &self.u3),
+            ERR_MAP_DOMAIN
+        )
+    }
+    fn pull(&self, p: <Self::E1 as EllipticCurve>::Point) -> <Self::E0 as EllipticCurve>::Point {
+        let inv_u = 1u32 / &self.u;
+        let (inv_u2, inv_u3) = Isomorphism::powers(&inv_u);
+        do_if_eq!(
+            p.e == self.e1,
+            Isomorphism::scale(&self.e0, &p, &inv_u2, &inv_u3),
+            ERR_MAP_CODOMAIN
+        )
+    }
+}
+
+impl Isogeny for Isomorphism {
+    type E0 = Curve;
+    type E1 = Curve;
+    fn domain(&self) -> Self::E0 {
+        self.e0.clone()
+    }
+    fn codomain(&self) -> Self::E1 {
+        self.e1.clone()
+    }
+    fn push(&self, p: <Self::E0 as EllipticCurve>::Point) -> <Self::E1 as EllipticCurve>::Point {
+        RationalMap::push(self, p)
+    }
+}
+
+impl std::fmt::Display for Isomorphism {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Isomorphism (x,y) -> (u^2x,u^3y)\nu: {}\nfield: {}",
+            self.u,
+            self.e0.get_field()
+        )
+    }
+}
+
+const ERR_MAP_DOMAIN: &str = "point does not belong to the domain";
+const ERR_MAP_CODOMAIN: &str = "point does not belong to the codomain";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::Isomorphism;
+    use crate::ellipticcurve::{EcPoint, EllipticCurve, RationalMap};
+    use crate::instances::{GetCurve, P256, SECP256K1_3ISO};
+    use crate::ops::FromFactory;
+
+    #[test]
+    fn push_pull() {
+        for &id in [P256, SECP256K1_3ISO].iter() {
+            let e0 = id.get();
+            let f = e0.get_field();
+            let iso = Isomorphism::new(e0.clone(), f.from(7));
+            let e1 = iso.codomain();
+            let g0 = e0.get_generator();
+            let g1 = iso.push(g0.clone());
+            assert!(e1.is_on_curve(&g1), "image must be on curve for {}", id);
+            assert!(g1 == e1.get_generator(), "generator mapping for {}", id);
+            assert!(iso.pull(g1) == g0, "pull must invert push for {}", id);
+
+            let k = e0.new_scalar(BigInt::from(1234567));
+            let lhs = iso.push(&g0 * &k);
+            let rhs = &iso.push(g0.clone()) * &e1.new_scalar(BigInt::from(1234567));
+            assert!(lhs == rhs, "map must be a group homomorphism for {}", id);
+            assert!(iso.push(e0.identity()).is_zero());
+        }
+    }
+
+    #[test]
+    fn compose_and_inverse() {
+        let e0 = P256.get();
+        let f = e0.get_field();
+        let iso0 = Isomorphism::new(e0.clone(), f.from(3));
+        let iso1 = Isomorphism::new(iso0.codomain(), f.from(5));
+        let iso01 = iso0.compose(&iso1);
+        let direct = Isomorphism::new(e0.clone(), f.from(15));
+        assert!(iso01.codomain() == direct.codomain());
+
+        let g = e0.get_generator();
+        assert!(iso01.push(g.clone()) == iso1.push(iso0.push(g.clone())));
+        assert!(iso01.inverse().push(iso01.push(g.clone())) == g);
+
+        let given = Isomorphism::from_curves(e0, direct.codomain(), f.from(15));
+        assert!(given.push(g.clone()) == direct.push(g));
+    }
+}
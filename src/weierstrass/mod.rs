@@ -3,6 +3,8 @@
 //! The Weierstrass module is meant to be used for bar.
 
 mod curve;
+mod icart;
+mod isomorphism;
 mod point;
 mod scalar;
 mod sswu;
@@ -10,7 +12,9 @@ mod sswuab0;
 mod svdw;
 
 pub use crate::weierstrass::curve::{Curve, Params};
-pub use crate::weierstrass::point::{Point, ProyCoordinates};
+pub use crate::weierstrass::icart::Icart;
+pub use crate::weierstrass::isomorphism::Isomorphism;
+pub use crate::weierstrass::point::{Affine, Point, ProyCoordinates};
 pub use crate::weierstrass::scalar::Scalar;
 pub use crate::weierstrass::sswu::SSWU;
 pub use crate::weierstrass::sswuab0::SSWUAB0;
@@ -3,19 +3,23 @@
 //! The curve module is meant to be used for bar.
 
 use impl_ops::impl_op_ex;
-use num_bigint::ToBigInt;
+use num_bigint::{BigUint, ToBigInt};
 use num_traits::identities::{One, Zero};
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
 
+use std::convert::TryFrom;
+use std::io::Error;
 use std::ops;
 
-use crate::do_if_eq;
-use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode};
+use crate::ellipticcurve::{EcPoint, EllipticCurve, Encode, PointFormat};
 use crate::field::Sgn0;
-use crate::ops::ScMulRef;
 use crate::ops::Serialize;
+use crate::ops::{blinding_mask, ScMulRef};
 use crate::primefield::FpElt;
 use crate::weierstrass::curve::Curve;
 use crate::weierstrass::scalar::Scalar;
+use crate::{count_op, do_if_eq, impl_iter_fold};
 
 #[derive(Clone)]
 pub struct ProyCoordinates {
@@ -30,14 +34,65 @@ pub struct Point {
     pub(crate) c: ProyCoordinates,
 }
 
+/// Affine is a point other than the point at infinity given by its affine
+/// coordinates. It is the operand of `Point::add_assign_mixed`, and is
+/// obtained from a point with `Affine::try_from`.
+#[derive(Clone)]
+pub struct Affine {
+    e: Curve,
+    x: FpElt,
+    y: FpElt,
+}
+
+impl Affine {
+    /// Returns the x-coordinate of the point.
+    pub fn x(&self) -> &FpElt {
+        &self.x
+    }
+    /// Returns the y-coordinate of the point.
+    pub fn y(&self) -> &FpElt {
+        &self.y
+    }
+}
+
+impl TryFrom<&Point> for Affine {
+    type Error = Error;
+    /// Returns the affine form of `p`, or an error for the point at
+    /// infinity.
+    fn try_from(p: &Point) -> Result<Affine, Error> {
+        let (x, y) = p.to_affine().ok_or_else(|| Error::other(ERR_AFFINE_INF))?;
+        Ok(Affine {
+            e: p.e.clone(),
+            x,
+            y,
+        })
+    }
+}
+
+impl From<&Affine> for Point {
+    fn from(q: &Affine) -> Point {
+        Point {
+            e: q.e.clone(),
+            c: ProyCoordinates {
+                x: q.x.clone(),
+                y: q.y.clone(),
+                z: q.e.one.clone(),
+            },
+        }
+    }
+}
+
 impl ScMulRef<Scalar> for Point {}
 impl EcPoint<Scalar> for Point {
     fn is_zero(&self) -> bool {
         self.c.x.is_zero() && !self.c.y.is_zero() && self.c.z.is_zero()
     }
+    fn is_small_order(&self) -> bool {
+        self.clear_cofactor().is_zero()
+    }
 }
 impl Encode for Point {
-    fn encode(&self, compress: bool) -> Vec<u8> {
+    fn encode(&self, format: PointFormat) -> Vec<u8> {
         // normalize the point to ensure that z = 1
         // clone so that we don't mutate the original point
         let mut p_normal = self.clone();
@@ -52,29 +107,51 @@ impl Encode for Point {
         let y = &coords.y;
         let mut x_bytes = x.to_bytes_be();
         let mut y_bytes = y.to_bytes_be();
-        if compress {
-            let s = y.sgn0();
-            // if sign == 1: tag = 0x02; elif sign == -1: tag = 0x03
-            let tag = (((s >> 1) & 0x1) + 2) as u8;
-            let mut o = vec![tag];
-            o.append(&mut x_bytes);
-            o
-        } else {
-            let mut o: Vec<u8> = vec![0x04];
-            o.append(&mut x_bytes);
+        // if sign == 1: parity = 0; elif sign == -1: parity = 1
+        let parity = ((y.sgn0() >> 1) & 0x1) as u8;
+        let tag = match format {
+            PointFormat::Uncompressed => 0x04,
+            PointFormat::Compressed => 0x02 | parity,
+            PointFormat::Hybrid => 0x06 | parity,
+        };
+        let mut o = vec![tag];
+        o.append(&mut x_bytes);
+        if format != PointFormat::Compressed {
             o.append(&mut y_bytes);
-            o
         }
+        o
     }
 }
 
 impl Point {
     pub fn normalize(&mut self) {
+        // the point at infinity has no affine representation
+        if self.c.z.is_zero() {
+            return;
+        }
         let inv_z = 1u32 / &self.c.z;
         self.c.x = &self.c.x * &inv_z;
         self.c.y = &self.c.y * &inv_z;
         self.c.z.set_one();
     }
+    /// Returns the affine x-coordinate, or `None` for the point at infinity.
+    pub fn x(&self) -> Option<FpElt> {
+        self.to_affine().map(|(x, _)| x)
+    }
+    /// Returns the affine y-coordinate, or `None` for the point at infinity.
+    pub fn y(&self) -> Option<FpElt> {
+        self.to_affine().map(|(_, y)| y)
+    }
+    /// Returns the affine coordinates (x, y), or `None` for the point at
+    /// infinity.
+    pub fn to_affine(&self) -> Option<(FpElt, FpElt)> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut p = self.clone();
+        p.normalize();
+        Some((p.c.x, p.c.y))
+    }
     fn core_neg(&self) -> <Curve as EllipticCurve>::Point {
         self.e.new_proy_point(ProyCoordinates {
             x: self.c.x.clone(),
@@ -83,79 +160,257 @@ impl Point {
         })
     }
     /// core_add implements complete addition formulas for prime order groups.
+    /// If either operand has `Z = 1`, as the points returned by `new_point`
+    /// or `normalize` do, the cheaper mixed formulas are used instead. On
+    /// curves of even order, the few sums that the formulas miss are
+    /// computed by `add_exceptional_coords`.
     // Reference: "Complete addition formulas for prime order elliptic curves" by
     // Costello-Renes-Batina. [Alg.1] (eprint.iacr.org/2015/1060).
     fn core_add(&self, p: &<Curve as EllipticCurve>::Point) -> <Curve as EllipticCurve>::Point {
-        let a = &self.e.a;
-        let b3 = &self.e.b + &self.e.b + &self.e.b;
-        let (x1, x2) = (&self.c.x, &p.c.x);
-        let (y1, y2) = (&self.c.y, &p.c.y);
-        let (z1, z2) = (&self.c.z, &p.c.z);
+        if std::ptr::eq(self, p) {
+            count_op!(dbl);
+        } else {
+            count_op!(add);
+        }
+        self.e.new_proy_point(self.add_coords(&p.c))
+    }
+    /// Returns the coordinates of the sum of the point and the point with
+    /// coordinates `c`, see `core_add`.
+    fn add_coords(&self, c: &ProyCoordinates) -> ProyCoordinates {
+        let sum = if c.z.is_one() {
+            self.add_mixed_coords(&self.c, &c.x, &c.y)
+        } else if self.c.z.is_one() {
+            self.add_mixed_coords(c, &self.c.x, &self.c.y)
+        } else {
+            self.add_proy_coords(c)
+        };
+        if is_exceptional(&sum) {
+            return self.add_exceptional_coords(&self.c, c);
+        }
+        sum
+    }
+    /// Returns the coordinates of the sum of the points with coordinates
+    /// `c1` and `c2` using the chord rule in affine coordinates.
+    fn add_exceptional_coords(
+        &self,
+        c1: &ProyCoordinates,
+        c2: &ProyCoordinates,
+    ) -> ProyCoordinates {
+        if c1.z.is_zero() {
+            return c2.clone();
+        }
+        if c2.z.is_zero() {
+            return c1.clone();
+        }
+        let (inv_z1, inv_z2) = (1u32 / &c1.z, 1u32 / &c2.z);
+        let (x1, y1) = (&c1.x * &inv_z1, &c1.y * &inv_z1);
+        let (x2, y2) = (&c2.x * &inv_z2, &c2.y * &inv_z2);
+        // doubling is never exceptional, so x1 = x2 implies that c2 = -c1
+        if x1 == x2 {
+            return self.e.identity().c;
+        }
+        let l = (&y2 - &y1) / (&x2 - &x1);
+        let x3 = &l * &l - &x1 - &x2;
+        let y3 = l * (&x1 - &x3) - &y1;
+        let z3 = self.e.one.clone();
+        ProyCoordinates {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+    /// Returns the coordinates of the sum of the point and the point with
+    /// coordinates `c` for operands whose `Z` is not one.
+    fn add_proy_coords(&self, c: &ProyCoordinates) -> ProyCoordinates {
+        let (a, b3) = (&self.e.a, &self.e.b3);
+        let (x1, x2) = (&self.c.x, &c.x);
+        let (y1, y2) = (&self.c.y, &c.y);
+        let (z1, z2) = (&self.c.z, &c.z);
         let (mut x3, mut y3, mut z3);
         let (mut t0, mut t1, mut t2, mut t3, mut t4, mut t5);
-        t0 = x1 * x2; //    1. t0 = X1 * X2
-        t1 = y1 * y2; //    2. t1 = Y1 * Y2
-        t2 = z1 * z2; //    3. t2 = Z1 * Z2
-        t3 = x1 + y1; //    4. t3 = X1 + Y1
-        t4 = x2 + y2; //    5. t4 = X2 + Y2
-        t3 = &t3 * &t4; //  6. t3 = t3 * t4
-        t4 = &t0 + &t1; //  7. t4 = t0 + t1
-        t3 = &t3 - &t4; //  8. t3 = t3 - t4
-        t4 = x1 + z1; //    9. t4 = X1 + Z1
-        t5 = x2 + z2; //   10. t5 = X2 + Z2
-        t4 = &t4 * &t5; // 11. t4 = t4 * t5
-        t5 = &t0 + &t2; // 12. t5 = t0 + t2
-        t4 = &t4 - &t5; // 13. t4 = t4 - t5
-        t5 = y1 + z1; //   14. t5 = Y1 + Z1
-        x3 = y2 + z2; //   15. X3 = Y2 + Z2
-        t5 = &t5 * &x3; // 16. t5 = t5 * X3
-        x3 = &t1 + &t2; // 17. X3 = t1 + t2
-        t5 = &t5 - &x3; // 18. t5 = t5 - X3
-        z3 = a * &t4; //   19. Z3 =  a * t4
-        x3 = &b3 * &t2; // 20. X3 = b3 * t2
-        z3 = x3 + &z3; //  21. Z3 = X3 + Z3
-        x3 = &t1 - &z3; // 22. X3 = t1 - Z3
-        z3 = &t1 + &z3; // 23. Z3 = t1 + Z3
-        y3 = &x3 * &z3; // 24. Y3 = X3 * Z3
-        t1 = &t0 + &t0; // 25. t1 = t0 + t0
-        t1 = &t1 + &t0; // 26. t1 = t1 + t0
-        t2 = a * &t2; //   27. t2 =  a * t2
-        t4 = b3 * &t4; //  28. t4 = b3 * t4
-        t1 = &t1 + &t2; // 29. t1 = t1 + t2
-        t2 = &t0 - &t2; // 30. t2 = t0 - t2
-        t2 = a * &t2; //   31. t2 =  a * t2
-        t4 = &t4 + t2; //  32. t4 = t4 + t2
-        t0 = &t1 * &t4; // 33. t0 = t1 * t4
-        y3 = y3 + &t0; //  34. Y3 = Y3 + t0
-        t0 = &t5 * t4; //  35. t0 = t5 * t4
-        x3 = &t3 * &x3; // 36. X3 = t3 * X3
-        x3 = x3 - &t0; //  37. X3 = X3 - t0
-        t0 = t3 * t1; //   38. t0 = t3 * t1
-        z3 = t5 * z3; //   39. Z3 = t5 * Z3
-        z3 = z3 + t0; //   40. Z3 = Z3 + t0
-        self.e.new_proy_point(ProyCoordinates {
+        t0 = x1 * x2; //            1. t0 = X1 * X2
+        t1 = y1 * y2; //            2. t1 = Y1 * Y2
+        t2 = z1 * z2; //            3. t2 = Z1 * Z2
+        t3 = x1 + y1; //            4. t3 = X1 + Y1
+        t4 = x2 + y2; //            5. t4 = X2 + Y2
+        t3 *= &t4; //               6. t3 = t3 * t4
+        t4 = &t0 + &t1; //          7. t4 = t0 + t1
+        t3 -= &t4; //               8. t3 = t3 - t4
+        t4 = x1 + z1; //            9. t4 = X1 + Z1
+        t5 = x2 + z2; //           10. t5 = X2 + Z2
+        t4 *= &t5; //              11. t4 = t4 * t5
+        t5 = &t0 + &t2; //         12. t5 = t0 + t2
+        t4 -= &t5; //              13. t4 = t4 - t5
+        t5 = y1 + z1; //           14. t5 = Y1 + Z1
+        x3 = y2 + z2; //           15. X3 = Y2 + Z2
+        t5 *= &x3; //              16. t5 = t5 * X3
+        x3 = &t1 + &t2; //         17. X3 = t1 + t2
+        t5 -= &x3; //              18. t5 = t5 - X3
+        x3 = b3 * &t2; //          20. X3 = b3 * t2
+        z3 = a.mul_add(&t4, &x3); // 19. Z3 =  a * t4, 21. Z3 = X3 + Z3
+        x3 = &t1 - &z3; //         22. X3 = t1 - Z3
+        z3 += &t1; //              23. Z3 = t1 + Z3
+        y3 = &x3 * &z3; //         24. Y3 = X3 * Z3
+        t1 = &t0 + &t0; //         25. t1 = t0 + t0
+        t1 += &t0; //              26. t1 = t1 + t0
+        t2 *= a; //                27. t2 =  a * t2
+        t4 *= b3; //               28. t4 = b3 * t4
+        t1 += &t2; //              29. t1 = t1 + t2
+        t2 = &t0 - &t2; //         30. t2 = t0 - t2
+        t2 *= a; //                31. t2 =  a * t2
+        t4 += &t2; //              32. t4 = t4 + t2
+        t0 = &t1 * &t4; //         33. t0 = t1 * t4
+        y3 += &t0; //              34. Y3 = Y3 + t0
+        t0 = &t5 * &t4; //         35. t0 = t5 * t4
+        x3 *= &t3; //              36. X3 = t3 * X3
+        x3 -= &t0; //              37. X3 = X3 - t0
+        t0 = t3 * &t1; //          38. t0 = t3 * t1
+        z3 *= &t5; //              39. Z3 = t5 * Z3
+        z3 += &t0; //              40. Z3 = Z3 + t0
+        ProyCoordinates {
             x: x3,
             y: y3,
             z: z3,
-        })
+        }
+    }
+    /// Returns the coordinates of the sum of the point with coordinates `c`
+    /// and the affine point `(x2, y2)`. These are the formulas of
+    /// `add_coords` with `Z2 = 1`, which save one multiplication.
+    // Reference: "Complete addition formulas for prime order elliptic curves" by
+    // Costello-Renes-Batina. [Alg.2] (eprint.iacr.org/2015/1060).
+    fn add_mixed_coords(&self, c: &ProyCoordinates, x2: &FpElt, y2: &FpElt) -> ProyCoordinates {
+        let (a, b3) = (&self.e.a, &self.e.b3);
+        let (x1, y1, z1) = (&c.x, &c.y, &c.z);
+        let (mut x3, mut y3, mut z3);
+        let (mut t0, mut t1, mut t2, mut t3, mut t4, t5);
+        t0 = x1 * x2; //            1. t0 = X1 * X2
+        t1 = y1 * y2; //            2. t1 = Y1 * Y2
+        t3 = x2 + y2; //            3. t3 = X2 + Y2
+        t4 = x1 + y1; //            4. t4 = X1 + Y1
+        t3 *= &t4; //               5. t3 = t3 * t4
+        t4 = &t0 + &t1; //          6. t4 = t0 + t1
+        t3 -= &t4; //               7. t3 = t3 - t4
+        t4 = x2.mul_add(z1, x1); // 8. t4 = X2 * Z1, 9. t4 = t4 + X1
+        t5 = y2.mul_add(z1, y1); // 10. t5 = Y2 * Z1, 11. t5 = t5 + Y1
+        x3 = b3 * z1; //           13. X3 = b3 * Z1
+        z3 = a.mul_add(&t4, &x3); // 12. Z3 =  a * t4, 14. Z3 = X3 + Z3
+        x3 = &t1 - &z3; //         15. X3 = t1 - Z3
+        z3 += &t1; //              16. Z3 = t1 + Z3
+        y3 = &x3 * &z3; //         17. Y3 = X3 * Z3
+        t1 = &t0 + &t0; //         18. t1 = t0 + t0
+        t1 += &t0; //              19. t1 = t1 + t0
+        t2 = a * z1; //            20. t2 =  a * Z1
+        t4 *= b3; //               21. t4 = b3 * t4
+        t1 += &t2; //              22. t1 = t1 + t2
+        t2 = &t0 - &t2; //         23. t2 = t0 - t2
+        t2 *= a; //                24. t2 =  a * t2
+        t4 += &t2; //              25. t4 = t4 + t2
+        t0 = &t1 * &t4; //         26. t0 = t1 * t4
+        y3 += &t0; //              27. Y3 = Y3 + t0
+        t0 = &t5 * &t4; //         28. t0 = t5 * t4
+        x3 *= &t3; //              29. X3 = t3 * X3
+        x3 -= &t0; //              30. X3 = X3 - t0
+        t0 = t3 * &t1; //          31. t0 = t3 * t1
+        z3 *= &t5; //              32. Z3 = t5 * Z3
+        z3 += &t0; //              33. Z3 = Z3 + t0
+        ProyCoordinates {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+    /// Doubles the point in place, which saves building a new point as
+    /// `&p + &p` does.
+    pub fn double_in_place(&mut self) {
+        count_op!(dbl);
+        self.c = self.add_coords(&self.c);
+    }
+    /// Adds `q` to the point in place. Since the `Z` coordinate of `q` is
+    /// one, this is cheaper than adding a projective point.
+    pub fn add_assign_mixed(&mut self, q: &Affine) {
+        count_op!(add);
+        do_if_eq!(
+            self.e == q.e,
+            {
+                let sum = self.add_mixed_coords(&self.c, &q.x, &q.y);
+                self.c = if is_exceptional(&sum) {
+                    self.add_exceptional_coords(&self.c, &Point::from(q).c)
+                } else {
+                    sum
+                }
+            },
+            ERR_ADD_OP
+        )
+    }
+    /// Multiplies the projective coordinates `X`, `Y`, and `Z` by a random
+    /// non-zero field element, which changes the representation of the
+    /// point but not the point itself. Randomizing the input of a secret
+    /// scalar multiplication blunts template attacks, since the attacker
+    /// can no longer predict the intermediate values.
+    pub fn randomize_representation<R: RngCore + CryptoRng>(&mut self, rng: &mut R) {
+        let l = self.e.f.random_nonzero(rng);
+        self.c.x = &self.c.x * &l;
+        self.c.y = &self.c.y * &l;
+        self.c.z = &self.c.z * &l;
+    }
+    /// Returns `[k]p` computed as `[k + m*n]p`, where `n` is the order of
+    /// the curve and `m` is a random 64-bit mask drawn from `rng` on every
+    /// call. Since the bits processed by the multiplication change on every
+    /// call, this hardens it against differential power analysis. The
+    /// representation of `p` is also randomized beforehand.
+    pub fn mul_blinded<R: RngCore + CryptoRng>(&self, k: &Scalar, rng: &mut R) -> Point {
+        let r = self.e.r.to_bigint().unwrap();
+        let n = (&self.e.r * &self.e.h).to_bigint().unwrap();
+        let blinded = Scalar {
+            k: &k.k + blinding_mask(&n, rng),
+            r: k.r.clone(),
+        };
+        let mut p = self.clone();
+        p.randomize_representation(rng);
+        do_if_eq!(r == k.r, p.core_mul(&blinded), ERR_MUL_OP)
+    }
+    /// Returns `[h]p`, where `h` is the cofactor of the curve, which is a
+    /// point of the subgroup of order `r`. The cofactor is not reduced
+    /// modulo `r`, so it may be larger than the order.
+    pub fn clear_cofactor(&self) -> Point {
+        self.mul_unreduced(&self.e.h)
+    }
+    /// Returns `[k]p` for an integer `k` that is not reduced modulo the order
+    /// of the curve, which matters for points outside of the subgroup.
+    pub(crate) fn mul_unreduced(&self, k: &BigUint) -> Point {
+        let k = Scalar {
+            k: k.to_bigint().unwrap(),
+            r: self.e.r.to_bigint().unwrap(),
+        };
+        self.core_mul(&k)
     }
     /// core_mul implements the double&add Scalar multiplication method.
     /// This function run in non-constant time.
     fn core_mul(&self, k: &Scalar) -> <Curve as EllipticCurve>::Point {
         let mut q = self.e.identity();
         for ki in k.iter_lr() {
-            q = &q + &q;
+            q.double_in_place();
             if ki {
-                q = q + self;
+                q += self;
             }
         }
         q
     }
 }
 
+/// Returns whether `c` is the triple `(0, 0, 0)` output by the formulas of
+/// `add_coords` when the difference of the operands is a point of order two.
+/// Such points only exist on curves of even order, such as W-25519.
+// Reference: "Complete addition formulas for prime order elliptic curves" by
+// Costello-Renes-Batina. (eprint.iacr.org/2015/1060).
+fn is_exceptional(c: &ProyCoordinates) -> bool {
+    c.x.is_zero() && c.y.is_zero() && c.z.is_zero()
+}
+
 impl Eq for Point {}
 
 impl PartialEq for Point {
+    /// Compares the points in variable time.
     fn eq(&self, other: &Self) -> bool {
         let x1z2 = &self.c.x * &other.c.z;
         let z1x2 = &self.c.z * &other.c.x;
@@ -165,6 +420,18 @@ impl PartialEq for Point {
     }
 }
 
+impl ConstantTimeEq for Point {
+    /// Compares the points without short-circuiting on their coordinates.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let x1z2 = &self.c.x * &other.c.z;
+        let z1x2 = &self.c.z * &other.c.x;
+        let y1z2 = &self.c.y * &other.c.z;
+        let z1y2 = &self.c.z * &other.c.y;
+        let same_curve = Choice::from((self.e == other.e) as u8);
+        same_curve & x1z2.ct_eq(&z1x2) & y1z2.ct_eq(&z1y2)
+    }
+}
+
 impl_op_ex!(+|a: &Point , b: &Point | -> Point  {
     do_if_eq!(a.e == b.e, a.core_add(b), ERR_ADD_OP)
 });
@@ -174,12 +441,40 @@ impl_op_ex!(*|a: &Point, b: &Scalar| -> Point {
     let r = a.e.r.to_bigint().unwrap();
     do_if_eq!(r == b.r, a.core_mul(b), ERR_MUL_OP)
 });
+impl_op_ex!(+=|a: &mut Point, b: &Point| {
+    count_op!(add);
+    do_if_eq!(a.e == b.e, a.c = a.add_coords(&b.c), ERR_ADD_OP)
+});
+impl_op_ex!(-=|a: &mut Point, b: &Point| { *a = &*a - b });
+impl_op_ex!(*=|a: &mut Point, b: &Scalar| { *a = &*a * b });
+impl_iter_fold!(Sum, sum, Point, +);
 
+/// Displays the compressed encoding of the point in hex.
 impl std::fmt::Display for Point {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "\nx: {}\ny: {}\nz: {}", self.c.x, self.c.y, self.c.z)
+        self.encode(PointFormat::Compressed)
+            .iter()
+            .try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+/// Shows the name of the curve and the affine coordinates of the point in
+/// hex.
+impl std::fmt::Debug for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_identity() {
+            return write!(f, "Point {{ curve: {:?}, identity }}", self.e.name);
+        }
+        let mut p = self.clone();
+        p.normalize();
+        f.debug_struct("Point")
+            .field("curve", &self.e.name)
+            .field("x", &format_args!("{:#x}", p.c.x))
+            .field("y", &format_args!("{:#x}", p.c.y))
+            .finish()
     }
 }
 
 const ERR_MUL_OP: &str = "Scalar don't match with point";
 const ERR_ADD_OP: &str = "points of different curves";
+const ERR_AFFINE_INF: &str = "the point at infinity has no affine coordinates";
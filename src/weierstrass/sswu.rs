@@ -50,13 +50,13 @@ impl MapToCurve for SSWU {
         let mut x1 = &t1 + &t2; //        3.   x1 = t1 + t2
         x1 = 1u32 / &x1; //               4.   x1 = inv0(x1)
         let e1 = x1.is_zero(); //         5.   e1 = x1 == 0
-        x1 = x1 + f.one(); //             6.   x1 = x1 + 1
+        x1 += f.one(); //                 6.   x1 = x1 + 1
         x1 = cmov(&x1, &self.c2, e1); //  7.   x1 = CMOV(x1, c2, e1)
-        x1 = x1 * &self.c1; //            8.   x1 = x1 * c1
+        x1 *= &self.c1; //                8.   x1 = x1 * c1
         let mut gx1 = &x1 ^ 2u32; //      9.  gx1 = x1^2
-        gx1 = gx1 + &self.e.a; //         10. gx1 = gx1 + A
-        gx1 = gx1 * &x1; //               11. gx1 = gx1 * x1
-        gx1 = gx1 + &self.e.b; //         12. gx1 = gx1 + B
+        gx1 += &self.e.a; //              10. gx1 = gx1 + A
+        gx1 *= &x1; //                    11. gx1 = gx1 * x1
+        gx1 += &self.e.b; //              12. gx1 = gx1 + B
         let x2 = &t1 * &x1; //            13.  x2 = t1 * x1
         t2 = t1 * t2; //                  14.  t2 = t1 * t2
         let gx2 = &gx1 * &t2; //          15. gx2 = gx1 * t2
@@ -41,6 +41,21 @@ impl SVDW {
             }
         }
     }
+    /// Returns the constant `Z` selected by the procedure in RFC 9380,
+    /// Appendix H.1. Any curve y^2=x^3+ax+b admits such a constant, so the
+    /// SVDW map can be used when neither SSWU nor an isogeny is available.
+    pub fn find_z(e: &Curve) -> FpElt {
+        let f = e.get_field();
+        let mut ctr = 1u32;
+        loop {
+            for z in [f.from(ctr), -f.from(ctr)].iter() {
+                if SVDW::verify(e, z) {
+                    return z.clone();
+                }
+            }
+            ctr += 1;
+        }
+    }
     fn gx(e: &Curve, x: &FpElt) -> FpElt {
         x * &((x ^ 2u32) + &e.a) + &e.b
     }
@@ -48,6 +63,9 @@ impl SVDW {
         let f = e.get_field();
         let (f2, f3, f4) = (f.from(2u32), f.from(3u32), f.from(4u32));
         let gz = SVDW::gx(e, z);
+        if gz.is_zero() {
+            return false;
+        }
         let gz2 = SVDW::gx(e, &((-z) * (1u32 / &f2)));
         let t0 = -(f3 * (z ^ 2u32) + &f4 * &e.a) * (1u32 / &(&f4 * &gz));
         let cond1 = !gz.is_zero(); //   g(Z) != 0
@@ -69,40 +87,71 @@ impl MapToCurve for SVDW {
         let f = self.e.get_field();
         let cmov = FpElt::cmov;
         let mut t1 = u ^ 2u32; //           1.   t1 = u^2
-        t1 = t1 * &self.c1; //              2.   t1 = t1 * c1
+        t1 *= &self.c1; //                  2.   t1 = t1 * c1
         let t2 = f.one() + &t1; //          3.   t2 = 1 + t1
         t1 = f.one() - &t1; //              4.   t1 = 1 - t1
         let mut t3 = &t1 * &t2; //          5.   t3 = t1 * t2
         t3 = 1u32 / &t3; //                 6.   t3 = inv0(t3)
         let mut t4 = u * &t1; //            7.   t4 = u * t1
-        t4 = t4 * &t3; //                   8.   t4 = t4 * t3
-        t4 = t4 * &self.c3; //              9.   t4 = t4 * c3
+        t4 *= &t3; //                       8.   t4 = t4 * t3
+        t4 *= &self.c3; //                  9.   t4 = t4 * c3
         let x1 = &self.c2 - &t4; //         10.  x1 = c2 - t4
         let mut gx1 = &x1 ^ 2u32; //        11. gx1 = x1^2
-        gx1 = gx1 + &self.e.a; //           12. gx1 = gx1 + A
-        gx1 = gx1 * &x1; //                 13. gx1 = gx1 * x1
-        gx1 = gx1 + &self.e.b; //           14. gx1 = gx1 + B
+        gx1 += &self.e.a; //                12. gx1 = gx1 + A
+        gx1 *= &x1; //                      13. gx1 = gx1 * x1
+        gx1 += &self.e.b; //                14. gx1 = gx1 + B
         let e1 = gx1.is_square(); //        15.  e1 = is_square(gx1)
         let x2 = &self.c2 + &t4; //         16.  x2 = c2 + t4
         let mut gx2 = &x2 ^ 2u32; //        17. gx2 = x2^2
-        gx2 = gx2 + &self.e.a; //           18. gx2 = gx2 + A
-        gx2 = gx2 * &x2; //                 19. gx2 = gx2 * x2
-        gx2 = gx2 + &self.e.b; //           20. gx2 = gx2 + B
+        gx2 += &self.e.a; //                18. gx2 = gx2 + A
+        gx2 *= &x2; //                      19. gx2 = gx2 * x2
+        gx2 += &self.e.b; //                20. gx2 = gx2 + B
         let e2 = gx2.is_square() && !e1; // 21.  e2 = is_square(gx2) AND NOT e1     // Avoid short-circuit logic ops
         let mut x3 = &t2 ^ 2u32; //         22.  x3 = t2^2
-        x3 = x3 * t3; //                    23.  x3 = x3 * t3
+        x3 *= t3; //                        23.  x3 = x3 * t3
         x3 = &x3 ^ 2u32; //                 24.  x3 = x3^2
-        x3 = x3 * &self.c4; //              25.  x3 = x3 * c4
-        x3 = x3 + &self.z; //               26.  x3 = x3 + Z
+        x3 *= &self.c4; //                  25.  x3 = x3 * c4
+        x3 += &self.z; //                   26.  x3 = x3 + Z
         let mut x = cmov(&x3, &x1, e1); //  27.   x = CMOV(x3, x1, e1)      // x = x1 if gx1 is square, else x = x3
         x = cmov(&x, &x2, e2); //           28.   x = CMOV(x, x2, e2)       // x = x2 if gx2 is square and gx1 is not
         let mut gx = &x ^ 2u32; //          29.  gx = x^2
-        gx = gx + &self.e.a; //             30.  gx = gx + A
-        gx = gx * &x; //                    31.  gx = gx * x
-        gx = gx + &self.e.b; //             32.  gx = gx + B
+        gx += &self.e.a; //                 30.  gx = gx + A
+        gx *= &x; //                        31.  gx = gx * x
+        gx += &self.e.b; //                 32.  gx = gx + B
         let mut y = gx.sqrt(); //           33.   y = sqrt(gx)
         let e3 = u.sgn0() == y.sgn0(); //   34.  e3 = sgn0(u) == sgn0(y)
         y = cmov(&(-&y), &y, e3); //        35.   y = CMOV(-y, y, e3)
         self.e.new_point(x, y)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SVDW;
+    use crate::ellipticcurve::{EcPoint, EllipticCurve, MapToCurve};
+    use crate::instances::{GetCurve, BLS12381G1, P256, P384, P521, SECP256K1};
+    use crate::ops::FromFactory;
+
+    #[test]
+    fn svdw_map() {
+        for &id in [P256, P384, P521, SECP256K1, BLS12381G1].iter() {
+            let e = id.get();
+            let f = e.get_field();
+            let svdw = SVDW::new(e.clone(), SVDW::find_z(&e));
+            for u in -4..4 {
+                let p = svdw.map(&f.from(u));
+                assert!(e.is_on_curve(&p), "point must be on curve for {}", id);
+                assert!(!p.is_zero(), "point must not be the identity for {}", id);
+            }
+        }
+    }
+
+    #[test]
+    fn find_z() {
+        for &(id, z) in [(P256, -3), (SECP256K1, 1), (BLS12381G1, -3)].iter() {
+            let e = id.get();
+            let f = e.get_field();
+            assert!(SVDW::find_z(&e) == f.from(z), "wrong Z for {}", id);
+        }
+    }
+}
@@ -0,0 +1,275 @@
+//! This is documentation for the `wycheproof` module.
+//!
+//! The wycheproof module runs the test vectors of Project Wycheproof
+//! against the ECDH, ECDSA, EdDSA, and XDH implementations of the crate.
+//! Valid test cases must be accepted and invalid test cases must be
+//! rejected. Test cases marked as acceptable are edge cases on which
+//! implementations may legitimately disagree, such as compressed public
+//! keys or shared secrets of small order, so both outcomes pass.
+//!
+//! Only the suites of P-256 and Curve25519 run by default; the others take
+//! several minutes in debug builds and are ignored unless requested:
+//!
+//! ```text
+//!  cargo test --release wycheproof -- --ignored
+//! ```
+
+use digest::Digest;
+use num_bigint::{BigInt, Sign};
+use wycheproof::{HashFunction, TestResult};
+
+use std::convert::TryInto;
+use std::fmt::Debug;
+
+use crate::ecdh::DiffieHellman;
+use crate::ecdsa::{Signature, SignaturePolicy, VerifyingKey};
+use crate::eddsa::{self, Algorithm};
+use crate::ellipticcurve::{Decode, EllipticCurve};
+use crate::instances::{GetCurve, WeCurveID, P256, P384, P521, SECP256K1};
+use crate::xdh::{x25519_shared_secret, x448_shared_secret};
+
+/// Report collects the outcomes of the test cases of a suite.
+struct Report {
+    suite: String,
+    passed: usize,
+    acceptable: usize,
+    failures: Vec<String>,
+}
+
+impl Report {
+    fn new<N: Debug>(suite: N) -> Report {
+        Report {
+            suite: format!("{:?}", suite),
+            passed: 0,
+            acceptable: 0,
+            failures: Vec::new(),
+        }
+    }
+    /// Records whether the test case `tc_id` was accepted, which must match
+    /// its expected result unless the result is acceptable.
+    fn check<F: Debug>(&mut self, tc_id: usize, result: TestResult, flags: &[F], accepted: bool) {
+        let decision = if accepted { "accepted" } else { "rejected" };
+        match result {
+            TestResult::Acceptable => self.acceptable += 1,
+            _ if result.must_fail() == accepted => self.failures.push(format!(
+                "tcId {}: {:?} but {} {:?}",
+                tc_id, result, decision, flags
+            )),
+            _ => self.passed += 1,
+        }
+    }
+    /// Records the outcome of a key agreement, which is accepted if it
+    /// produced a shared secret. Producing a wrong shared secret is a
+    /// failure regardless of the expected result.
+    fn check_secret<F: Debug>(
+        &mut self,
+        tc_id: usize,
+        result: TestResult,
+        flags: &[F],
+        out: Option<&[u8]>,
+        want: &[u8],
+    ) {
+        match out {
+            Some(s) if s != want && !result.must_fail() => self
+                .failures
+                .push(format!("tcId {}: wrong shared secret {:?}", tc_id, flags)),
+            _ => self.check(tc_id, result, flags, out.is_some()),
+        }
+    }
+    fn finish(self) {
+        assert!(
+            self.failures.is_empty(),
+            "{}: {} passed, {} acceptable, {} failed\n{}",
+            self.suite,
+            self.passed,
+            self.acceptable,
+            self.failures.len(),
+            self.failures.join("\n")
+        );
+    }
+}
+
+/// Returns the digest of `msg`, or None if the hash function is not
+/// supported by these tests.
+fn digest(hash: HashFunction, msg: &[u8]) -> Option<Vec<u8>> {
+    Some(match hash {
+        HashFunction::Sha2_256 => sha2::Sha256::digest(msg).to_vec(),
+        HashFunction::Sha2_384 => sha2::Sha384::digest(msg).to_vec(),
+        HashFunction::Sha2_512 => sha2::Sha512::digest(msg).to_vec(),
+        HashFunction::Sha3_256 => sha3::Sha3_256::digest(msg).to_vec(),
+        HashFunction::Sha3_384 => sha3::Sha3_384::digest(msg).to_vec(),
+        HashFunction::Sha3_512 => sha3::Sha3_512::digest(msg).to_vec(),
+        _ => return None,
+    })
+}
+
+fn ecdh(name: wycheproof::ecdh::TestName, id: WeCurveID) {
+    use wycheproof::ecdh::TestSet;
+    let e = id.get();
+    let mut report = Report::new(name);
+    for group in TestSet::load(name).unwrap().test_groups {
+        for t in group.tests {
+            let k = e.new_scalar(BigInt::from_bytes_be(Sign::Plus, &t.private_key));
+            let out = e
+                .decode(&t.public_key)
+                .and_then(|p| e.diffie_hellman(&k, &p))
+                .ok();
+            let out = out.as_ref().map(|s| s.as_bytes());
+            report.check_secret(t.tc_id, t.result, &t.flags, out, &t.shared_secret);
+        }
+    }
+    report.finish();
+}
+
+/// Runs a suite of ECDSA signatures, which are DER-encoded unless `p1363`
+/// is set. The Bitcoin suite is run with the policy that rejects high `s`
+/// values.
+fn ecdsa(name: wycheproof::ecdsa::TestName, id: WeCurveID, p1363: bool) {
+    use wycheproof::ecdsa::TestSet;
+    let e = id.get();
+    let policy = match name {
+        wycheproof::ecdsa::TestName::EcdsaSecp256k1Sha256Bitcoin => SignaturePolicy::RejectHighS,
+        _ => SignaturePolicy::AcceptAll,
+    };
+    let mut report = Report::new(name);
+    for group in TestSet::load(name).unwrap().test_groups {
+        let vk = VerifyingKey::from_bytes(&e, &group.key.key).unwrap();
+        for t in group.tests {
+            let z = digest(group.hash, &t.msg).expect("unsupported hash function");
+            let sig = match p1363 {
+                true => Signature::from_bytes(&e, &t.sig),
+                false => Signature::from_der(&e, &t.sig),
+            };
+            let accepted = sig
+                .and_then(|sig| vk.verify_prehash_with(&z, &sig, policy))
+                .is_ok();
+            report.check(t.tc_id, t.result, &t.flags, accepted);
+        }
+    }
+    report.finish();
+}
+
+fn eddsa(name: wycheproof::eddsa::TestName, alg: Algorithm) {
+    use wycheproof::eddsa::TestSet;
+    let mut report = Report::new(name);
+    for group in TestSet::load(name).unwrap().test_groups {
+        let vk = eddsa::VerifyingKey::from_bytes(alg, &group.key.pk).unwrap();
+        for t in group.tests {
+            let accepted = eddsa::Signature::from_bytes(alg, &t.sig)
+                .and_then(|sig| vk.verify(&t.msg, &sig))
+                .is_ok();
+            report.check(t.tc_id, t.result, &t.flags, accepted);
+        }
+    }
+    report.finish();
+}
+
+/// Runs a suite of XDH key agreements; public keys of the wrong length are
+/// rejected, since the functions take arrays.
+fn xdh<F>(name: wycheproof::xdh::TestName, f: F)
+where
+    F: Fn(&[u8], &[u8]) -> Option<Vec<u8>>,
+{
+    use wycheproof::xdh::TestSet;
+    let mut report = Report::new(name);
+    for group in TestSet::load(name).unwrap().test_groups {
+        for t in group.tests {
+            let out = f(&t.private_key, &t.public_key);
+            report.check_secret(
+                t.tc_id,
+                t.result,
+                &t.flags,
+                out.as_deref(),
+                &t.shared_secret,
+            );
+        }
+    }
+    report.finish();
+}
+
+#[test]
+fn ecdh_secp256r1() {
+    ecdh(wycheproof::ecdh::TestName::EcdhSecp256r1Ecpoint, P256);
+}
+
+#[test]
+#[ignore = "slow in debug builds, run with --release -- --ignored"]
+fn ecdh_secp384r1() {
+    ecdh(wycheproof::ecdh::TestName::EcdhSecp384r1Ecpoint, P384);
+}
+
+#[test]
+#[ignore = "slow in debug builds, run with --release -- --ignored"]
+fn ecdh_secp521r1() {
+    ecdh(wycheproof::ecdh::TestName::EcdhSecp521r1Ecpoint, P521);
+}
+
+#[test]
+fn ecdsa_secp256r1() {
+    ecdsa(
+        wycheproof::ecdsa::TestName::EcdsaSecp256r1Sha256,
+        P256,
+        false,
+    );
+}
+
+#[test]
+#[ignore = "slow in debug builds, run with --release -- --ignored"]
+fn ecdsa_secp256r1_p1363() {
+    use wycheproof::ecdsa::TestName::*;
+    ecdsa(EcdsaSecp256r1Sha256P1363, P256, true);
+    ecdsa(EcdsaSecp256r1Sha3_256, P256, false);
+}
+
+#[test]
+#[ignore = "slow in debug builds, run with --release -- --ignored"]
+fn ecdsa_secp384r1() {
+    use wycheproof::ecdsa::TestName::*;
+    ecdsa(EcdsaSecp384r1Sha384, P384, false);
+    ecdsa(EcdsaSecp384r1Sha384P1363, P384, true);
+}
+
+#[test]
+#[ignore = "slow in debug builds, run with --release -- --ignored"]
+fn ecdsa_secp521r1() {
+    use wycheproof::ecdsa::TestName::*;
+    ecdsa(EcdsaSecp521r1Sha512, P521, false);
+    ecdsa(EcdsaSecp521r1Sha512P1363, P521, true);
+}
+
+#[test]
+#[ignore = "slow in debug builds, run with --release -- --ignored"]
+fn ecdsa_secp256k1() {
+    use wycheproof::ecdsa::TestName::*;
+    ecdsa(EcdsaSecp256k1Sha256, SECP256K1, false);
+    ecdsa(EcdsaSecp256k1Sha256P1363, SECP256K1, true);
+    ecdsa(EcdsaSecp256k1Sha256Bitcoin, SECP256K1, false);
+}
+
+#[test]
+fn eddsa_ed25519() {
+    eddsa(wycheproof::eddsa::TestName::Ed25519, Algorithm::Ed25519);
+}
+
+#[test]
+#[ignore = "slow in debug builds, run with --release -- --ignored"]
+fn eddsa_ed448() {
+    eddsa(wycheproof::eddsa::TestName::Ed448, Algorithm::Ed448);
+}
+
+#[test]
+fn xdh_x25519() {
+    xdh(wycheproof::xdh::TestName::X25519, |k, u| {
+        let (k, u) = (k.try_into().ok()?, u.try_into().ok()?);
+        x25519_shared_secret(k, u).ok().map(|s| s.to_vec())
+    });
+}
+
+#[test]
+#[ignore = "slow in debug builds, run with --release -- --ignored"]
+fn xdh_x448() {
+    xdh(wycheproof::xdh::TestName::X448, |k, u| {
+        let (k, u) = (k.try_into().ok()?, u.try_into().ok()?);
+        x448_shared_secret(k, u).ok().map(|s| s.to_vec())
+    });
+}
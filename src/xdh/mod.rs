@@ -0,0 +1,352 @@
+//! This is documentation for the `xdh` module.
+//!
+//! The xdh module implements the X25519 and X448 functions specified in
+//! RFC 7748, which operate on byte strings encoding scalars and
+//! u-coordinates of curve25519 and curve448.
+//!
+//! As RFC 7748 prescribes, any 32 or 56 bytes are accepted as a public key,
+//! so `XDH_PEER_VALIDATION` enforces fewer checks than the `ecdh` module.
+//!
+//! # Key Agreement
+//!
+//! ```
+//!  use redox_ecc::xdh::{x25519, x25519_shared_secret, X25519_BASEPOINT};
+//!
+//!  let (a, b) = ([0x11u8; 32], [0x22u8; 32]);
+//!  let pk_a = x25519(&a, &X25519_BASEPOINT);
+//!  let pk_b = x25519(&b, &X25519_BASEPOINT);
+//!  let k_a = x25519_shared_secret(&a, &pk_b).unwrap();
+//!  let k_b = x25519_shared_secret(&b, &pk_a).unwrap();
+//!  assert_eq!(k_a, k_b);
+//! ```
+
+use num_bigint::{BigInt, Sign};
+
+use std::io::Error;
+
+use crate::ecdh::PeerValidation;
+use crate::field::{CMov, Field};
+use crate::instances::{GetCurve, CURVE25519, CURVE448};
+use crate::montgomery::{Curve, XZCoordinates};
+use crate::ops::Serialize;
+use crate::primefield::FpElt;
+
+/// X25519_BASEPOINT is the u-coordinate of the generator of curve25519.
+pub const X25519_BASEPOINT: [u8; 32] = [
+    9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+/// X448_BASEPOINT is the u-coordinate of the generator of curve448.
+pub const X448_BASEPOINT: [u8; 56] = [
+    5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+/// XDH_PEER_VALIDATION lists the checks applied to the public key of the
+/// peer by `x25519_shared_secret` and `x448_shared_secret`. Only shared
+/// secrets of small order are rejected, and other u-coordinates are
+/// accepted for these reasons:
+///
+/// - A u-coordinate that is not on the curve is on its quadratic twist, and
+///   the twists of curve25519 and curve448 have orders `4q` with `q` prime,
+///   so the ladder leaks at most two bits of a secret that are fixed by
+///   clamping anyway.
+/// - Clamping makes the scalar a multiple of the cofactor, so points
+///   outside the subgroup of prime order reveal nothing about it.
+pub const XDH_PEER_VALIDATION: PeerValidation = PeerValidation {
+    on_curve: false,
+    in_subgroup: false,
+    not_on_twist: false,
+    not_small_order: true,
+};
+
+/// Returns the u-coordinate of the product of the clamped scalar `k` and
+/// the point whose u-coordinate is `u`. The most significant bit of `u` is
+/// ignored and non-canonical values are reduced.
+// based on https://www.rfc-editor.org/rfc/rfc7748#section-5
+pub fn x25519(k: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let mut k = *k;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+    let mut u = *u;
+    u[31] &= 127;
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&ladder(&CURVE25519.get(), &k, &u, 255));
+    out
+}
+
+/// Returns the u-coordinate of the product of the clamped scalar `k` and
+/// the point whose u-coordinate is `u`. Non-canonical values of `u` are
+/// reduced.
+// based on https://www.rfc-editor.org/rfc/rfc7748#section-5
+pub fn x448(k: &[u8; 56], u: &[u8; 56]) -> [u8; 56] {
+    let mut k = *k;
+    k[0] &= 252;
+    k[55] |= 128;
+    let mut out = [0u8; 56];
+    out.copy_from_slice(&ladder(&CURVE448.get(), &k, u, 448));
+    out
+}
+
+/// Computes the X25519 shared secret between the secret key `k` and the
+/// public key `u` of the peer. It fails if the shared secret is all zeros,
+/// i.e., if `u` is a point of small order.
+// based on https://www.rfc-editor.org/rfc/rfc7748#section-6.1
+pub fn x25519_shared_secret(k: &[u8; 32], u: &[u8; 32]) -> Result<[u8; 32], Error> {
+    let out = x25519(k, u);
+    if out.iter().all(|&b| b == 0) {
+        return Err(Error::other(ERR_ZERO_SECRET));
+    }
+    Ok(out)
+}
+
+/// Computes the X448 shared secret between the secret key `k` and the
+/// public key `u` of the peer. It fails if the shared secret is all zeros,
+/// i.e., if `u` is a point of small order.
+// based on https://www.rfc-editor.org/rfc/rfc7748#section-6.2
+pub fn x448_shared_secret(k: &[u8; 56], u: &[u8; 56]) -> Result<[u8; 56], Error> {
+    let out = x448(k, u);
+    if out.iter().all(|&b| b == 0) {
+        return Err(Error::other(ERR_ZERO_SECRET));
+    }
+    Ok(out)
+}
+
+/// Runs the Montgomery ladder over the `bits` least significant bits of the
+/// little-endian scalar `k`, using only u-coordinates. The point need not
+/// be on the curve, it can be on its quadratic twist.
+fn ladder(e: &Curve, k: &[u8], u: &[u8], bits: usize) -> Vec<u8> {
+    let f = &e.f;
+    let x1 = XZCoordinates {
+        x: f.elt(BigInt::from_bytes_le(Sign::Plus, u)),
+        z: e.one.clone(),
+    };
+    let mut r0 = XZCoordinates {
+        x: e.one.clone(),
+        z: e.zero.clone(),
+    };
+    let mut r1 = x1.clone();
+    let mut swap = false;
+    for t in (0..bits).rev() {
+        let kt = (k[t / 8] >> (t % 8)) & 1 == 1;
+        swap ^= kt;
+        cswap(&mut r0.x, &mut r1.x, swap);
+        cswap(&mut r0.z, &mut r1.z, swap);
+        swap = kt;
+        let (s0, s1) = e.ladder_step(&x1, &r0, &r1);
+        r0 = s0;
+        r1 = s1;
+    }
+    cswap(&mut r0.x, &mut r1.x, swap);
+    cswap(&mut r0.z, &mut r1.z, swap);
+    // z is zero for points of small order, which yields zero as expected
+    let exp: BigInt = f.get_modulus() - 2;
+    let z: FpElt = &r0.z ^ &exp;
+    (r0.x * z).to_bytes_le()
+}
+
+fn cswap(a: &mut FpElt, b: &mut FpElt, swap: bool) {
+    let t = FpElt::cmov(a, b, swap);
+    *b = FpElt::cmov(b, a, swap);
+    *a = t;
+}
+
+const ERR_ZERO_SECRET: &str = "shared secret is all zeros";
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::{BigInt, BigUint, ToBigInt};
+    use num_traits::identities::Zero;
+
+    use super::{x25519, x25519_shared_secret, x448, x448_shared_secret};
+    use super::{X25519_BASEPOINT, X448_BASEPOINT};
+    use crate::ellipticcurve::EllipticCurve;
+    use crate::field::Field;
+    use crate::instances::{GetCurve, CURVE25519, CURVE448};
+
+    // Test vectors taken from RFC 7748, Sections 5.2 and 6.
+    struct Vector {
+        k: &'static str,
+        u: &'static str,
+        out: &'static str,
+    }
+
+    fn array<const N: usize>(s: &str) -> [u8; N] {
+        let mut out = [0u8; N];
+        out.copy_from_slice(&hex::decode(s).unwrap());
+        out
+    }
+
+    #[test]
+    fn rfc7748_x25519() {
+        let vectors = [
+            Vector {
+                k: "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+                u: "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+                out: "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552",
+            },
+            Vector {
+                k: "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+                u: "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+                out: "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957",
+            },
+            Vector {
+                k: "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+                u: "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f",
+                out: "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742",
+            },
+            Vector {
+                k: "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+                u: "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a",
+                out: "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742",
+            },
+        ];
+        for v in vectors.iter() {
+            let out = x25519(&array(v.k), &array(v.u));
+            assert_eq!(hex::encode(out), v.out);
+        }
+        let k = array("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        assert_eq!(
+            hex::encode(x25519(&k, &X25519_BASEPOINT)),
+            "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"
+        );
+    }
+
+    #[test]
+    fn rfc7748_x448() {
+        let vectors = [
+            Vector {
+                k: "3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3",
+                u: "06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086",
+                out: "ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f",
+            },
+            Vector {
+                k: "9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b",
+                u: "3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b43027d8b972fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609",
+                out: "07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282bb60c0b56fd2464c335543936521c24403085d59a449a5037514a879d",
+            },
+            Vector {
+                k: "1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d",
+                u: "9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0",
+                out: "07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282bb60c0b56fd2464c335543936521c24403085d59a449a5037514a879d",
+            },
+        ];
+        for v in vectors.iter() {
+            let out = x448(&array(v.k), &array(v.u));
+            assert_eq!(hex::encode(out), v.out);
+        }
+        let k = array("9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b");
+        assert_eq!(
+            hex::encode(x448(&k, &X448_BASEPOINT)),
+            "9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0"
+        );
+    }
+
+    fn iterate<const N: usize>(
+        f: fn(&[u8; N], &[u8; N]) -> [u8; N],
+        base: [u8; N],
+        n: usize,
+    ) -> String {
+        let (mut k, mut u) = (base, base);
+        for _ in 0..n {
+            let out = f(&k, &u);
+            u = k;
+            k = out;
+        }
+        hex::encode(k)
+    }
+
+    #[test]
+    fn iterated() {
+        assert_eq!(
+            iterate(x25519, X25519_BASEPOINT, 1),
+            "422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079"
+        );
+        assert_eq!(
+            iterate(x448, X448_BASEPOINT, 1),
+            "3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113"
+        );
+    }
+
+    #[test]
+    fn iterated_1000() {
+        assert_eq!(
+            iterate(x25519, X25519_BASEPOINT, 1000),
+            "684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51"
+        );
+        assert_eq!(
+            iterate(x448, X448_BASEPOINT, 1000),
+            "aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "expensive-tests")]
+    fn iterated_1000000() {
+        assert_eq!(
+            iterate(x25519, X25519_BASEPOINT, 1_000_000),
+            "7c3911e0ab2586fd864497297e575e6f3bc601c0883c30df5f4dd2d24f665424"
+        );
+        assert_eq!(
+            iterate(x448, X448_BASEPOINT, 1_000_000),
+            "077f453681caca3693198420bbe515cae0002472519b3e67661a7e89cab94695c8f4bcd66e61b9b9c946da8d524de3d69bd9d9d66b997e37"
+        );
+    }
+
+    #[test]
+    fn small_order() {
+        let k = [0x42u8; 32];
+        let mut u = [0u8; 32];
+        assert!(x25519_shared_secret(&k, &u).is_err());
+        u[0] = 1;
+        assert!(x25519_shared_secret(&k, &u).is_err());
+        // u = p + 1 is a non-canonical encoding of 1
+        let p_plus_1 = "eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f";
+        let mut u = [0u8; 32];
+        u.copy_from_slice(&hex::decode(p_plus_1).unwrap());
+        assert!(x25519_shared_secret(&k, &u).is_err());
+        let k = [0x42u8; 56];
+        assert!(x448_shared_secret(&k, &[0u8; 56]).is_err());
+        assert!(x448_shared_secret(&k, &X448_BASEPOINT).is_ok());
+    }
+
+    /// Runs the Miller-Rabin test with the first primes as bases.
+    fn is_probable_prime(n: &BigUint) -> bool {
+        let one = BigUint::from(1u32);
+        let n_minus_1 = n - &one;
+        let (mut d, mut s) = (n_minus_1.clone(), 0);
+        while (&d & &one).is_zero() {
+            d >>= 1;
+            s += 1;
+        }
+        [2u32, 3, 5, 7, 11, 13, 17, 19, 23, 29].iter().all(|&a| {
+            let mut x = BigUint::from(a).modpow(&d, n);
+            if x == one || x == n_minus_1 {
+                return true;
+            }
+            for _ in 1..s {
+                x = x.modpow(&BigUint::from(2u32), n);
+                if x == n_minus_1 {
+                    return true;
+                }
+            }
+            false
+        })
+    }
+
+    #[test]
+    fn twist_security() {
+        // XDH_PEER_VALIDATION relies on the twists having orders 4q for a
+        // prime q, where the order of the twist is 2(p+1) - hr
+        for e in [CURVE25519.get(), CURVE448.get()].iter() {
+            let p = e.get_field().get_modulus();
+            let r = e.get_order().to_bigint().unwrap();
+            let twist: BigInt = 2 * (p + 1) - e.get_cofactor() * r;
+            assert!((&twist % 4u32).is_zero());
+            let q = (twist / 4u32).to_biguint().unwrap();
+            assert!(is_probable_prime(&q));
+        }
+    }
+}